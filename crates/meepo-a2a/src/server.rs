@@ -239,6 +239,7 @@ impl A2aServer {
                 content: prompt,
                 channel: ChannelType::Internal,
                 timestamp: Utc::now(),
+                thread_id: None,
             };
             let result = agent.handle_message(incoming).await;
 
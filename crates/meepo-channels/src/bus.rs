@@ -282,6 +282,7 @@ mod tests {
             content: "hello".to_string(),
             channel: ChannelType::Discord,
             timestamp: chrono::Utc::now(),
+            thread_id: None,
         };
         tx.send(incoming).await.unwrap();
 
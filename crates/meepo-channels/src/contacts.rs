@@ -218,6 +218,7 @@ end tell
                 content,
                 channel: ChannelType::Contacts,
                 timestamp: Utc::now(),
+                thread_id: None,
             };
 
             info!("New contact from Contacts.app: {}", display_name);
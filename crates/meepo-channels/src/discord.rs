@@ -146,6 +146,8 @@ impl EventHandler for DiscordHandler {
             content: msg.content.clone(),
             channel: ChannelType::Discord,
             timestamp: Utc::now(),
+            // Discord threads and DMs are channels of their own
+            thread_id: Some(msg.channel_id.to_string()),
         };
 
         info!("Forwarding Discord message from {}", incoming.sender);
@@ -20,6 +20,27 @@ const MAX_EMAIL_SENDERS: usize = 500;
 /// Timeout for Mail.app AppleScript polling (seconds)
 const MAIL_POLL_TIMEOUT_SECS: u64 = 60;
 
+/// Thread key for an email: the sender plus the subject without reply and
+/// forward prefixes, so a back-and-forth lands in one conversation thread
+fn email_thread_id(sender: &str, subject: &str) -> String {
+    let mut subject = subject.trim();
+    loop {
+        let lower = subject.to_lowercase();
+        let Some(prefix) = ["re:", "fwd:", "fw:", "aw:"]
+            .iter()
+            .find(|p| lower.starts_with(*p))
+        else {
+            break;
+        };
+        subject = subject[prefix.len()..].trim_start();
+    }
+    format!(
+        "{}/{}",
+        sender.trim().to_lowercase(),
+        subject.to_lowercase()
+    )
+}
+
 /// Check if an application is currently running via System Events
 async fn is_app_running(app_name: &str) -> bool {
     let safe_name = app_name
@@ -260,6 +281,7 @@ end tell
                 content,
                 channel: ChannelType::Email,
                 timestamp: Utc::now(),
+                thread_id: Some(email_thread_id(&sender, &stripped_subject)),
             };
 
             info!("New email from {}: {}", sender, stripped_subject);
@@ -419,6 +441,17 @@ mod tests {
         assert_eq!(channel.channel_type(), ChannelType::Email);
     }
 
+    #[test]
+    fn test_email_thread_id() {
+        let first = email_thread_id("Ana <ana@example.com>", "Lisbon trip");
+        assert_eq!(first, "ana <ana@example.com>/lisbon trip");
+        assert_eq!(
+            email_thread_id("Ana <ana@example.com>", "RE: Fwd: re: Lisbon Trip"),
+            first
+        );
+        assert_ne!(email_thread_id("bo@example.com", "Lisbon trip"), first);
+    }
+
     #[test]
     fn test_escape_applescript() {
         assert_eq!(
@@ -200,6 +200,7 @@ impl IMessageChannel {
                 content: content.clone(),
                 channel: ChannelType::IMessage,
                 timestamp,
+                thread_id: Some(handle.clone()),
             };
 
             info!(
@@ -163,6 +163,7 @@ end tell
                 content,
                 channel: ChannelType::Notes,
                 timestamp: Utc::now(),
+                thread_id: None,
             };
 
             info!("New note from Notes.app: {}", name);
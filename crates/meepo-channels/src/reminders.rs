@@ -149,6 +149,7 @@ end tell
                 content,
                 channel: ChannelType::Reminders,
                 timestamp: Utc::now(),
+                thread_id: None,
             };
 
             info!("New reminder from Reminders.app: {}", name);
@@ -363,6 +363,14 @@ impl MessageChannel for SlackChannel {
                             content: text.to_string(),
                             channel: ChannelType::Slack,
                             timestamp: Utc::now(),
+                            // Thread replies carry the parent's ts; top-level
+                            // messages continue the conversation in the channel
+                            thread_id: Some(
+                                match msg.get("thread_ts").and_then(|v| v.as_str()) {
+                                    Some(thread_ts) => format!("{}/{}", channel_id, thread_ts),
+                                    None => channel_id.clone(),
+                                },
+                            ),
                         };
 
                         info!("Forwarding Slack message from {} ({} chars)", user, text.len());
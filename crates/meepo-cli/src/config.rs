@@ -25,6 +25,8 @@ pub struct MeepoConfig {
     #[serde(default)]
     pub skills: SkillsConfig,
     #[serde(default)]
+    pub workflows: WorkflowsConfig,
+    #[serde(default)]
     pub browser: BrowserConfig,
     #[serde(default)]
     pub notifications: NotificationsConfig,
@@ -459,6 +461,340 @@ impl Default for ContactsConfig {
 pub struct KnowledgeConfig {
     pub db_path: String,
     pub tantivy_path: String,
+    /// Language used for stemming in the search index (e.g. "english", "german")
+    #[serde(default = "default_search_language")]
+    pub search_language: String,
+    #[serde(default)]
+    pub resolution: EntityResolutionConfig,
+    #[serde(default)]
+    pub importance: ImportanceSettings,
+    #[serde(default)]
+    pub retention: RetentionConfig,
+    #[serde(default)]
+    pub encryption: EncryptionConfig,
+    #[serde(default)]
+    pub communities: CommunitiesConfig,
+    #[serde(default)]
+    pub corpus: CorpusConfig,
+    #[serde(default)]
+    pub extraction: MemoryExtractionConfig,
+}
+
+fn default_search_language() -> String {
+    "english".to_string()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntityResolutionConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default = "default_true")]
+    pub resolve_on_insert: bool,
+    #[serde(default = "default_true")]
+    pub llm_confirm: bool,
+    #[serde(default = "default_resolution_interval_hours")]
+    pub interval_hours: u64,
+    #[serde(default = "default_candidate_threshold")]
+    pub candidate_threshold: f32,
+    #[serde(default = "default_resolution_exclude_types")]
+    pub exclude_types: Vec<String>,
+    #[serde(default = "default_max_pairs_per_pass")]
+    pub max_pairs_per_pass: usize,
+}
+
+fn default_resolution_interval_hours() -> u64 {
+    24
+}
+
+fn default_candidate_threshold() -> f32 {
+    0.6
+}
+
+fn default_resolution_exclude_types() -> Vec<String> {
+    vec!["document".to_string(), "document_chunk".to_string()]
+}
+
+fn default_max_pairs_per_pass() -> usize {
+    200
+}
+
+impl Default for EntityResolutionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            resolve_on_insert: true,
+            llm_confirm: true,
+            interval_hours: default_resolution_interval_hours(),
+            candidate_threshold: default_candidate_threshold(),
+            exclude_types: default_resolution_exclude_types(),
+            max_pairs_per_pass: default_max_pairs_per_pass(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportanceSettings {
+    #[serde(default = "default_half_life_days")]
+    pub half_life_days: f32,
+    #[serde(default = "default_recall_weight")]
+    pub recall_weight: f32,
+}
+
+fn default_half_life_days() -> f32 {
+    30.0
+}
+
+fn default_recall_weight() -> f32 {
+    0.3
+}
+
+impl Default for ImportanceSettings {
+    fn default() -> Self {
+        Self {
+            half_life_days: default_half_life_days(),
+            recall_weight: default_recall_weight(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetentionConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_retention_action")]
+    pub action: String,
+    #[serde(default = "default_retention_threshold")]
+    pub threshold: f32,
+    #[serde(default = "default_retention_min_age_days")]
+    pub min_age_days: u32,
+    #[serde(default = "default_retention_protected_types")]
+    pub protected_types: Vec<String>,
+    #[serde(default = "default_retention_interval_hours")]
+    pub interval_hours: u64,
+    #[serde(default = "default_action_log_retain_days")]
+    pub action_log_retain_days: u32,
+    #[serde(default)]
+    pub conversation_retain_days: u32,
+}
+
+fn default_retention_action() -> String {
+    "archive".to_string()
+}
+
+fn default_retention_threshold() -> f32 {
+    0.15
+}
+
+fn default_retention_min_age_days() -> u32 {
+    30
+}
+
+fn default_retention_protected_types() -> Vec<String> {
+    vec!["preference".to_string(), "goal".to_string()]
+}
+
+fn default_retention_interval_hours() -> u64 {
+    24
+}
+
+fn default_action_log_retain_days() -> u32 {
+    90
+}
+
+impl Default for RetentionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            action: default_retention_action(),
+            threshold: default_retention_threshold(),
+            min_age_days: default_retention_min_age_days(),
+            protected_types: default_retention_protected_types(),
+            interval_hours: default_retention_interval_hours(),
+            action_log_retain_days: default_action_log_retain_days(),
+            conversation_retain_days: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EncryptionConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_key_secret")]
+    pub key_secret: String,
+}
+
+fn default_key_secret() -> String {
+    "MEEPO_DB_KEY".to_string()
+}
+
+impl Default for EncryptionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            key_secret: default_key_secret(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommunitiesConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default = "default_true")]
+    pub llm_summaries: bool,
+    #[serde(default = "default_communities_interval_hours")]
+    pub interval_hours: u64,
+    #[serde(default = "default_community_min_size")]
+    pub min_size: usize,
+    #[serde(default = "default_max_communities")]
+    pub max_communities: usize,
+    #[serde(default = "default_community_resolution")]
+    pub resolution: f64,
+    #[serde(default = "default_community_exclude_types")]
+    pub exclude_types: Vec<String>,
+}
+
+fn default_communities_interval_hours() -> u64 {
+    24
+}
+
+fn default_community_min_size() -> usize {
+    3
+}
+
+fn default_max_communities() -> usize {
+    50
+}
+
+fn default_community_resolution() -> f64 {
+    1.0
+}
+
+fn default_community_exclude_types() -> Vec<String> {
+    vec!["document_chunk".to_string()]
+}
+
+impl Default for CommunitiesConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            llm_summaries: true,
+            interval_hours: default_communities_interval_hours(),
+            min_size: default_community_min_size(),
+            max_communities: default_max_communities(),
+            resolution: default_community_resolution(),
+            exclude_types: default_community_exclude_types(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CorpusConfig {
+    /// Re-sync corpora as soon as their files change
+    #[serde(default = "default_true")]
+    pub watch: bool,
+    /// Full re-sync of every corpus (0 disables)
+    #[serde(default = "default_corpus_interval_secs")]
+    pub interval_secs: u64,
+    /// Quiet period before changed files are re-ingested
+    #[serde(default = "default_corpus_debounce_ms")]
+    pub debounce_ms: u64,
+    /// Unit of `chunk_size` and `chunk_overlap`: "tokens" or "chars". When
+    /// unset, sizes that are set are read as characters (what they meant
+    /// before token sizing) and the defaults are in tokens.
+    #[serde(default)]
+    pub chunk_unit: Option<String>,
+    /// Target chunk size (default 256 tokens or 1000 characters)
+    #[serde(default)]
+    pub chunk_size: Option<usize>,
+    /// Overlap when a section is too large for one chunk (default 32 tokens
+    /// or 200 characters)
+    #[serde(default)]
+    pub chunk_overlap: Option<usize>,
+}
+
+fn default_corpus_interval_secs() -> u64 {
+    3600
+}
+
+fn default_corpus_debounce_ms() -> u64 {
+    2000
+}
+
+impl Default for CorpusConfig {
+    fn default() -> Self {
+        Self {
+            watch: true,
+            interval_secs: default_corpus_interval_secs(),
+            debounce_ms: default_corpus_debounce_ms(),
+            chunk_unit: None,
+            chunk_size: None,
+            chunk_overlap: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryExtractionConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// How often to look for idle threads
+    #[serde(default = "default_extraction_interval_minutes")]
+    pub interval_minutes: u64,
+    /// A thread counts as finished after this long without a message
+    #[serde(default = "default_extraction_idle_minutes")]
+    pub idle_minutes: u64,
+    #[serde(default = "default_extraction_min_messages")]
+    pub min_messages: usize,
+    #[serde(default = "default_extraction_max_messages")]
+    pub max_messages: usize,
+    #[serde(default = "default_extraction_min_confidence")]
+    pub min_confidence: f32,
+    /// Queue extracted facts for `meepo memory review` instead of committing
+    #[serde(default)]
+    pub require_review: bool,
+    #[serde(default = "default_extraction_max_threads")]
+    pub max_threads_per_pass: usize,
+}
+
+fn default_extraction_interval_minutes() -> u64 {
+    10
+}
+
+fn default_extraction_idle_minutes() -> u64 {
+    30
+}
+
+fn default_extraction_min_messages() -> usize {
+    2
+}
+
+fn default_extraction_max_messages() -> usize {
+    60
+}
+
+fn default_extraction_min_confidence() -> f32 {
+    0.6
+}
+
+fn default_extraction_max_threads() -> usize {
+    10
+}
+
+impl Default for MemoryExtractionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_minutes: default_extraction_interval_minutes(),
+            idle_minutes: default_extraction_idle_minutes(),
+            min_messages: default_extraction_min_messages(),
+            max_messages: default_extraction_max_messages(),
+            min_confidence: default_extraction_min_confidence(),
+            require_review: false,
+            max_threads_per_pass: default_extraction_max_threads(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -468,12 +804,29 @@ pub struct WatchersConfig {
     #[serde(default = "default_min_poll")]
     pub min_poll_interval_secs: u64,
     pub active_hours: ActiveHours,
+    /// IANA timezone for schedules that do not name one; empty means the system timezone
+    #[serde(default)]
+    pub timezone: String,
+    /// Consecutive failures before a watcher is disabled (0 = never)
+    #[serde(default = "default_max_consecutive_errors")]
+    pub max_consecutive_errors: u32,
+    /// Longest delay between retries of a failing watcher
+    #[serde(default = "default_max_backoff")]
+    pub max_backoff_secs: u64,
 }
 
 fn default_max_concurrent() -> usize {
     50
 }
 
+fn default_max_consecutive_errors() -> u32 {
+    10
+}
+
+fn default_max_backoff() -> u64 {
+    3600
+}
+
 fn default_min_poll() -> u64 {
     30
 }
@@ -509,6 +862,50 @@ fn default_workspace() -> String {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MemoryConfig {
     pub workspace: String,
+    #[serde(default)]
+    pub sync: MemorySyncSettings,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemorySyncSettings {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default = "default_memory_importance_threshold")]
+    pub importance_threshold: f32,
+    #[serde(default = "default_memory_max_entries")]
+    pub max_entries: usize,
+    #[serde(default = "default_memory_sync_interval_secs")]
+    pub interval_secs: u64,
+    #[serde(default = "default_memory_conflict")]
+    pub conflict: String,
+}
+
+fn default_memory_importance_threshold() -> f32 {
+    0.6
+}
+
+fn default_memory_max_entries() -> usize {
+    100
+}
+
+fn default_memory_sync_interval_secs() -> u64 {
+    300
+}
+
+fn default_memory_conflict() -> String {
+    "file".to_string()
+}
+
+impl Default for MemorySyncSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            importance_threshold: default_memory_importance_threshold(),
+            max_entries: default_memory_max_entries(),
+            interval_secs: default_memory_sync_interval_secs(),
+            conflict: default_memory_conflict(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -759,6 +1156,29 @@ impl Default for SkillsConfig {
     }
 }
 
+// ── Workflows Config ────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkflowsConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_workflows_dir")]
+    pub dir: String,
+}
+
+fn default_workflows_dir() -> String {
+    "~/.meepo/workflows".to_string()
+}
+
+impl Default for WorkflowsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            dir: default_workflows_dir(),
+        }
+    }
+}
+
 // ── Browser Config ──────────────────────────────────────────────
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -1509,6 +1929,96 @@ mod tests {
         assert!(a2a.auth_token.is_empty());
     }
 
+    #[test]
+    fn test_defaults_search_language() {
+        let language = default_search_language();
+        assert_eq!(language, "english");
+        assert!(meepo_knowledge::parse_language(&language).is_ok());
+    }
+
+    #[test]
+    fn test_defaults_entity_resolution() {
+        let r = EntityResolutionConfig::default();
+        assert!(r.enabled);
+        assert!(r.resolve_on_insert);
+        assert!(r.llm_confirm);
+        assert_eq!(r.interval_hours, 24);
+        assert_eq!(r.exclude_types, vec!["document", "document_chunk"]);
+    }
+
+    #[test]
+    fn test_defaults_importance_and_retention() {
+        let i = ImportanceSettings::default();
+        assert_eq!(i.half_life_days, 30.0);
+        assert_eq!(i.recall_weight, 0.3);
+
+        let r = RetentionConfig::default();
+        assert!(!r.enabled);
+        assert_eq!(r.action, "archive");
+        assert_eq!(r.threshold, 0.15);
+        assert_eq!(r.min_age_days, 30);
+        assert_eq!(r.protected_types, vec!["preference", "goal"]);
+        assert_eq!(r.action_log_retain_days, 90);
+        assert_eq!(r.conversation_retain_days, 0);
+    }
+
+    #[test]
+    fn test_defaults_memory_sync() {
+        let m = MemorySyncSettings::default();
+        assert!(m.enabled);
+        assert_eq!(m.importance_threshold, 0.6);
+        assert_eq!(m.max_entries, 100);
+        assert_eq!(m.interval_secs, 300);
+        assert!(
+            m.conflict
+                .parse::<meepo_knowledge::ConflictPolicy>()
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_defaults_communities() {
+        let c = CommunitiesConfig::default();
+        assert!(c.enabled);
+        assert!(c.llm_summaries);
+        assert_eq!(c.interval_hours, 24);
+        assert_eq!(c.min_size, 3);
+        assert_eq!(c.max_communities, 50);
+        assert_eq!(c.resolution, 1.0);
+        assert_eq!(c.exclude_types, vec!["document_chunk"]);
+    }
+
+    #[test]
+    fn test_defaults_corpus() {
+        let c = CorpusConfig::default();
+        assert!(c.watch);
+        assert_eq!(c.interval_secs, 3600);
+        assert_eq!(c.debounce_ms, 2000);
+        assert_eq!(c.chunk_unit, None);
+        assert_eq!(c.chunk_size, None);
+        assert_eq!(c.chunk_overlap, None);
+    }
+
+    #[test]
+    fn test_defaults_memory_extraction() {
+        let e = MemoryExtractionConfig::default();
+        assert!(e.enabled);
+        assert_eq!(e.interval_minutes, 10);
+        assert_eq!(e.idle_minutes, 30);
+        assert_eq!(e.min_messages, 2);
+        assert_eq!(e.max_messages, 60);
+        assert_eq!(e.min_confidence, 0.6);
+        assert!(!e.require_review);
+        assert_eq!(e.max_threads_per_pass, 10);
+    }
+
+    #[test]
+    fn test_defaults_encryption() {
+        let e = EncryptionConfig::default();
+        assert!(!e.enabled);
+        assert_eq!(e.key_secret, "MEEPO_DB_KEY");
+    }
+
     #[test]
     fn test_defaults_skills() {
         let s = SkillsConfig::default();
@@ -1516,6 +2026,13 @@ mod tests {
         assert_eq!(s.dir, "~/.meepo/skills");
     }
 
+    #[test]
+    fn test_defaults_workflows() {
+        let w = WorkflowsConfig::default();
+        assert!(!w.enabled);
+        assert_eq!(w.dir, "~/.meepo/workflows");
+    }
+
     #[test]
     fn test_defaults_browser() {
         let b = BrowserConfig::default();
@@ -74,6 +74,79 @@ enum Commands {
 
     /// Run system health checks
     Doctor,
+
+    /// Inspect, back up and migrate the knowledge graph
+    Memory {
+        #[command(subcommand)]
+        action: MemoryAction,
+    },
+
+    /// Show watcher health and failures
+    Watchers {
+        #[command(subcommand)]
+        action: WatchersAction,
+    },
+
+    /// List, test and inspect watcher-triggered workflows
+    Workflow {
+        #[command(subcommand)]
+        action: WorkflowAction,
+    },
+}
+
+#[derive(Subcommand)]
+enum WatchersAction {
+    /// List watchers with their status, last success, last error and next run
+    List,
+
+    /// Show failed watcher runs and the events they could not deliver
+    DeadLetters {
+        /// Only show failures of this watcher
+        #[arg(long)]
+        watcher: Option<String>,
+
+        /// Maximum number of results
+        #[arg(long, default_value = "20")]
+        limit: usize,
+    },
+}
+
+#[derive(Subcommand)]
+enum WorkflowAction {
+    /// List workflows with their trigger and steps
+    List,
+
+    /// Run a workflow once, as if its trigger had fired
+    Run {
+        /// Workflow name
+        name: String,
+
+        /// Event payload as JSON
+        #[arg(long, conflicts_with = "event_file")]
+        event: Option<String>,
+
+        /// Read the event payload from a JSON file
+        #[arg(long)]
+        event_file: Option<PathBuf>,
+
+        /// Event kind seen by templates and conditions as `event.kind`
+        #[arg(long, default_value = "manual")]
+        kind: String,
+
+        /// Render inputs and evaluate conditions without calling tools or the LLM
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Show recent workflow runs
+    History {
+        /// Only show runs of this workflow
+        name: Option<String>,
+
+        /// Maximum number of results
+        #[arg(long, default_value = "20")]
+        limit: usize,
+    },
 }
 
 #[derive(Subcommand)]
@@ -109,6 +182,235 @@ enum TemplateAction {
     },
 }
 
+#[derive(Subcommand)]
+enum MemoryAction {
+    /// Search entities by name or type
+    Search {
+        /// Text to search for
+        query: String,
+
+        /// Only return entities of this type
+        #[arg(long = "type")]
+        entity_type: Option<String>,
+
+        /// Maximum number of results
+        #[arg(long, default_value = "20")]
+        limit: usize,
+    },
+
+    /// Show an entity (with its relationships and sources) or a relationship
+    Show {
+        /// Entity or relationship ID
+        id: String,
+
+        /// Show what was known at this date (YYYY-MM-DD, YYYY-MM or RFC 3339)
+        #[arg(long)]
+        as_of: Option<String>,
+    },
+
+    /// Edit an entity or relationship
+    Edit {
+        /// Entity or relationship ID
+        id: String,
+
+        /// New entity name
+        #[arg(long)]
+        name: Option<String>,
+
+        /// New entity type (entities) or relation type (relationships)
+        #[arg(long = "type")]
+        kind: Option<String>,
+
+        /// Replacement metadata as a JSON object
+        #[arg(long)]
+        metadata: Option<String>,
+    },
+
+    /// Delete an entity (and its relationships) or a relationship
+    Delete {
+        /// Entity or relationship ID
+        id: String,
+    },
+
+    /// Export the whole knowledge graph
+    Export {
+        /// Output format: 'ndjson' or 'jsonld'
+        #[arg(long, default_value = "ndjson")]
+        format: String,
+
+        /// Write to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Import a knowledge export
+    Import {
+        /// Path to an NDJSON or JSON-LD export
+        path: PathBuf,
+
+        /// Conflict strategy for existing records: 'skip' or 'merge'
+        #[arg(long, default_value = "skip")]
+        strategy: String,
+    },
+
+    /// Merge one entity into another, re-pointing its relationships
+    Merge {
+        /// ID of the entity to keep
+        keep: String,
+
+        /// ID of the duplicate entity to fold into it
+        duplicate: String,
+    },
+
+    /// Find duplicate entities and merge exact matches
+    Dedupe {
+        /// Only list candidates, don't merge anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Pin an entity so it is never forgotten
+    Pin {
+        /// Entity ID
+        id: String,
+    },
+
+    /// Unpin an entity
+    Unpin {
+        /// Entity ID
+        id: String,
+    },
+
+    /// Forget low-importance memories using the retention policy
+    Forget {
+        /// Only report what would be forgotten
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Override the configured importance threshold
+        #[arg(long)]
+        threshold: Option<f32>,
+    },
+
+    /// List archived memories
+    Archived {
+        /// Maximum number of results
+        #[arg(long, default_value = "50")]
+        limit: usize,
+    },
+
+    /// Restore an archived memory
+    Restore {
+        /// Entity ID
+        id: String,
+    },
+
+    /// Sync MEMORY.md with the knowledge graph in both directions
+    Sync,
+
+    /// List community summaries of the knowledge graph
+    Communities {
+        /// Re-detect communities first (new summaries list their members;
+        /// the daemon rewrites them with the LLM)
+        #[arg(long)]
+        refresh: bool,
+    },
+
+    /// Manage document corpora (directories kept in sync with the graph)
+    Corpus {
+        #[command(subcommand)]
+        action: CorpusAction,
+    },
+
+    /// Review facts extracted from conversations before they are committed
+    Review {
+        #[command(subcommand)]
+        action: ReviewAction,
+    },
+
+    /// Check the search index against the database and repair any drift
+    Reindex {
+        /// Rebuild the whole index instead of repairing only what differs
+        #[arg(long)]
+        full: bool,
+    },
+
+    /// Encrypt the knowledge database in place (requires a SQLCipher build)
+    Encrypt,
+
+    /// Decrypt the knowledge database back to plaintext
+    Decrypt,
+
+    /// Re-encrypt the knowledge database with a new key
+    Rekey {
+        /// Name of the secret holding the new key
+        new_key_secret: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum CorpusAction {
+    /// Register a directory as a corpus and ingest it
+    Add {
+        /// Name for the corpus
+        name: String,
+        /// Directory to ingest
+        path: PathBuf,
+        /// Glob of files to include (repeatable; default: all files)
+        #[arg(long)]
+        include: Vec<String>,
+        /// Glob of files to skip (repeatable)
+        #[arg(long)]
+        exclude: Vec<String>,
+    },
+
+    /// List registered corpora
+    List,
+
+    /// Re-sync one corpus, or all of them
+    Sync {
+        /// Corpus name (default: all)
+        name: Option<String>,
+    },
+
+    /// Unregister a corpus and delete its documents
+    Remove {
+        /// Corpus name
+        name: String,
+        /// Keep the ingested documents in the graph
+        #[arg(long)]
+        keep_documents: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum ReviewAction {
+    /// List extracted facts awaiting review
+    List {
+        /// Show committed and rejected facts too
+        #[arg(long)]
+        all: bool,
+    },
+
+    /// Commit extracted facts to the knowledge graph
+    Accept {
+        /// Candidate IDs
+        ids: Vec<String>,
+        /// Accept every pending fact
+        #[arg(long, conflicts_with = "ids")]
+        all: bool,
+    },
+
+    /// Discard extracted facts (they are not proposed again)
+    Reject {
+        /// Candidate IDs
+        ids: Vec<String>,
+        /// Reject every pending fact
+        #[arg(long, conflicts_with = "ids")]
+        all: bool,
+    },
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
     let cli = Cli::parse();
@@ -130,6 +432,9 @@ async fn main() -> Result<()> {
         Commands::Usage { period, csv } => cmd_usage(&cli.config, &period, csv).await,
         Commands::Template { action } => cmd_template(action).await,
         Commands::Doctor => cmd_doctor(&cli.config).await,
+        Commands::Memory { action } => cmd_memory(&cli.config, action).await,
+        Commands::Watchers { action } => cmd_watchers(&cli.config, action).await,
+        Commands::Workflow { action } => cmd_workflow(&cli.config, action).await,
     }
 }
 
@@ -994,6 +1299,8 @@ async fn cmd_config(config_path: &Option<PathBuf>) -> Result<()> {
 
 async fn cmd_start(config_path: &Option<PathBuf>) -> Result<()> {
     let cfg = MeepoConfig::load(config_path)?;
+    let user_timezone = meepo_scheduler::schedule::resolve_timezone(&cfg.watchers.timezone)
+        .context("Invalid [watchers] timezone")?;
     info!("Starting Meepo daemon...");
 
     let cancel = CancellationToken::new();
@@ -1007,8 +1314,10 @@ async fn cmd_start(config_path: &Option<PathBuf>) -> Result<()> {
     std::fs::create_dir_all(&tantivy_path)?;
 
     // Create KnowledgeGraph which includes both DB and Tantivy index
+    let db_key = knowledge_db_key(&cfg).await?;
     let knowledge_graph = Arc::new(
-        meepo_knowledge::KnowledgeGraph::new(&db_path, &tantivy_path)
+        open_knowledge_graph(&cfg, &db_path, &tantivy_path, db_key.as_deref())
+            .await
             .context("Failed to initialize knowledge graph")?,
     );
 
@@ -1016,12 +1325,29 @@ async fn cmd_start(config_path: &Option<PathBuf>) -> Result<()> {
     let db = knowledge_graph.db();
     info!("Knowledge database and Tantivy index initialized");
 
+    // Initialize scheduler database (kept alive for runtime persistence)
+    let sched_db = Arc::new(std::sync::Mutex::new(
+        meepo_knowledge::encryption::open_connection(&db_path, db_key.as_deref())?,
+    ));
+    meepo_scheduler::persistence::init_watcher_tables(&sched_db.lock().unwrap())?;
+    let watcher_store = meepo_scheduler::WatcherStore::new(sched_db.clone());
+    let feed_store = meepo_scheduler::FeedStore::new(sched_db.clone());
+    let health_store = meepo_scheduler::HealthStore::new(sched_db.clone());
+
     // Load SOUL and MEMORY
     let workspace = shellexpand(&cfg.memory.workspace);
     let soul = meepo_knowledge::load_soul(workspace.join(&cfg.agent.system_prompt_file))
         .unwrap_or_else(|_| "You are Meepo, a helpful AI assistant.".to_string());
-    let memory =
-        meepo_knowledge::load_memory(workspace.join(&cfg.agent.memory_file)).unwrap_or_default();
+    let memory_path = workspace.join(&cfg.agent.memory_file);
+    let memory_sync_cfg = memory_sync_config(&cfg.memory.sync)?;
+    if cfg.memory.sync.enabled
+        && let Err(e) = knowledge_graph
+            .sync_memory_file(&memory_path, &memory_sync_cfg)
+            .await
+    {
+        warn!("Failed to sync {}: {}", memory_path.display(), e);
+    }
+    let memory = meepo_knowledge::load_memory(&memory_path).unwrap_or_default();
     info!(
         "Loaded SOUL ({} chars) and MEMORY ({} chars)",
         soul.len(),
@@ -1559,15 +1885,22 @@ async fn cmd_start(config_path: &Option<PathBuf>) -> Result<()> {
         ),
     ));
     registry.register(Arc::new(meepo_core::tools::memory::RememberTool::new(
-        db.clone(),
-    )));
-    registry.register(Arc::new(meepo_core::tools::memory::RecallTool::new(
-        db.clone(),
+        knowledge_graph.clone(),
     )));
+    registry.register(Arc::new(
+        meepo_core::tools::memory::RecallTool::new(db.clone())
+            .with_importance(importance_config(&cfg.knowledge.importance)),
+    ));
     // Use KnowledgeGraph for SearchKnowledgeTool to enable Tantivy full-text search
     registry.register(Arc::new(
         meepo_core::tools::memory::SearchKnowledgeTool::with_graph(knowledge_graph.clone()),
     ));
+    registry.register(Arc::new(meepo_core::tools::memory::QueryGraphTool::new(
+        knowledge_graph.clone(),
+    )));
+    registry.register(Arc::new(
+        meepo_core::tools::memory::SearchConversationsTool::new(db.clone()),
+    ));
     registry.register(Arc::new(meepo_core::tools::memory::LinkEntitiesTool::new(
         db.clone(),
     )));
@@ -1576,9 +1909,15 @@ async fn cmd_start(config_path: &Option<PathBuf>) -> Result<()> {
         knowledge_graph.clone(),
         db.clone(),
     )));
-    registry.register(Arc::new(meepo_core::tools::rag::IngestDocumentTool::new(
-        knowledge_graph.clone(),
-    )));
+    let ingest = ingest_pipeline(&cfg.knowledge.corpus)?;
+    registry.register(Arc::new(
+        meepo_core::tools::rag::IngestDocumentTool::new(knowledge_graph.clone())
+            .with_chunking_config(ingest.chunking.clone()),
+    ));
+    registry.register(Arc::new(
+        meepo_core::tools::rag::ManageCorpusTool::new(knowledge_graph.clone())
+            .with_pipeline(ingest),
+    ));
     registry.register(Arc::new(meepo_core::tools::system::RunCommandTool));
     registry.register(Arc::new(meepo_core::tools::system::ReadFileTool));
     registry.register(Arc::new(meepo_core::tools::system::WriteFileTool));
@@ -1618,23 +1957,35 @@ async fn cmd_start(config_path: &Option<PathBuf>) -> Result<()> {
         )));
     }
     registry.register(Arc::new(
-        meepo_core::tools::watchers::CreateWatcherTool::new(db.clone(), watcher_command_tx.clone()),
+        meepo_core::tools::watchers::CreateWatcherTool::new(
+            watcher_store.clone(),
+            watcher_command_tx.clone(),
+        )
+        .with_timezone(user_timezone),
+    ));
+    registry.register(Arc::new(
+        meepo_core::tools::watchers::PreviewScheduleTool::new(user_timezone),
     ));
     registry.register(Arc::new(
-        meepo_core::tools::watchers::ListWatchersTool::new(db.clone()),
+        meepo_core::tools::watchers::ListWatchersTool::new(watcher_store.clone())
+            .with_health_store(health_store.clone()),
     ));
     registry.register(Arc::new(
-        meepo_core::tools::watchers::CancelWatcherTool::new(db.clone(), watcher_command_tx.clone()),
+        meepo_core::tools::watchers::CancelWatcherTool::new(
+            watcher_store.clone(),
+            watcher_command_tx.clone(),
+        ),
     ));
     // Autonomous agent management tools
     registry.register(Arc::new(
         meepo_core::tools::autonomous::SpawnBackgroundTaskTool::new(db.clone(), bg_task_tx.clone()),
     ));
     registry.register(Arc::new(
-        meepo_core::tools::autonomous::AgentStatusTool::new(db.clone()),
+        meepo_core::tools::autonomous::AgentStatusTool::new(db.clone(), watcher_store.clone()),
     ));
     registry.register(Arc::new(meepo_core::tools::autonomous::StopTaskTool::new(
         db.clone(),
+        watcher_store.clone(),
         watcher_command_tx.clone(),
         bg_task_tx.clone(),
     )));
@@ -1737,7 +2088,8 @@ async fn cmd_start(config_path: &Option<PathBuf>) -> Result<()> {
         meepo_core::tools::lifestyle::news::ContentDigestTool::new(
             tavily_client.clone(),
             db.clone(),
-        ),
+        )
+        .with_feed_store(feed_store.clone()),
     ));
     // Phase 3: Finance & Expense Tracker (cross-platform — knowledge graph)
     registry.register(Arc::new(
@@ -1859,7 +2211,11 @@ async fn cmd_start(config_path: &Option<PathBuf>) -> Result<()> {
         background_timeout_secs: cfg.orchestrator.background_timeout_secs,
         max_background_groups: cfg.orchestrator.max_background_groups,
     };
+    let resolution_api = api.clone();
+    let community_api = api.clone();
+    let extraction_api = api.clone();
     let orchestrator_api = api.clone();
+    let workflow_api = api.clone();
     let orchestrator = Arc::new(meepo_core::orchestrator::TaskOrchestrator::new(
         orchestrator_api,
         progress_tx,
@@ -2010,19 +2366,149 @@ async fn cmd_start(config_path: &Option<PathBuf>) -> Result<()> {
     }
     let agent = Arc::new(agent);
 
+    // Two-way MEMORY.md sync: re-sync on file edits and periodically so graph
+    // changes reach the file, then hand the new contents to the agent
+    let memory_sync_task = if cfg.memory.sync.enabled {
+        let cancel_memory = cancel.clone();
+        let graph_memory = knowledge_graph.clone();
+        let agent_memory = agent.clone();
+        let interval = std::time::Duration::from_secs(cfg.memory.sync.interval_secs.max(10));
+        let (file_tx, mut file_rx) = tokio::sync::mpsc::unbounded_channel();
+        let file_name = memory_path.file_name().map(|n| n.to_os_string());
+        let watcher = memory_path.parent().and_then(|dir| {
+            use notify::Watcher as _;
+            let mut watcher = notify::recommended_watcher(
+                move |res: std::result::Result<notify::Event, notify::Error>| {
+                    if let Ok(event) = res
+                        && event
+                            .paths
+                            .iter()
+                            .any(|p| p.file_name().map(|n| n.to_os_string()) == file_name)
+                    {
+                        let _ = file_tx.send(());
+                    }
+                },
+            )
+            .map_err(|e| warn!("Failed to watch {}: {}", dir.display(), e))
+            .ok()?;
+            watcher
+                .watch(dir, notify::RecursiveMode::NonRecursive)
+                .map_err(|e| warn!("Failed to watch {}: {}", dir.display(), e))
+                .ok()?;
+            Some(watcher)
+        });
+
+        Some(tokio::spawn(async move {
+            // Dropping the watcher stops it, so keep it alive with the task
+            let _watcher = watcher;
+            info!("MEMORY.md sync started for {}", memory_path.display());
+            loop {
+                tokio::select! {
+                    _ = cancel_memory.cancelled() => {
+                        info!("MEMORY.md sync shutting down");
+                        break;
+                    }
+                    Some(()) = file_rx.recv() => {
+                        // Let the editor finish writing, then coalesce the burst of events
+                        tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+                        while file_rx.try_recv().is_ok() {}
+                    }
+                    _ = tokio::time::sleep(interval) => {}
+                }
+                match graph_memory
+                    .sync_memory_file(&memory_path, &memory_sync_cfg)
+                    .await
+                {
+                    Ok(report) => {
+                        if report.file_written || report.graph_changed() {
+                            agent_memory.update_memory(
+                                meepo_knowledge::load_memory(&memory_path).unwrap_or_default(),
+                            );
+                        }
+                        // Our own write shows up as a file event; skip it
+                        if report.file_written {
+                            tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+                            while file_rx.try_recv().is_ok() {}
+                        }
+                    }
+                    Err(e) => warn!("MEMORY.md sync failed: {}", e),
+                }
+            }
+        }))
+    } else {
+        None
+    };
+
+    // Keep registered document corpora in sync with their directories
+    let corpus_cfg = cfg.knowledge.corpus.clone();
+    let corpus_task = if corpus_cfg.watch || corpus_cfg.interval_secs > 0 {
+        let pipeline = ingest_pipeline(&corpus_cfg)?;
+        Some(tokio::spawn(corpus_sync_loop(
+            knowledge_graph.clone(),
+            pipeline,
+            corpus_cfg,
+            cancel.clone(),
+        )))
+    } else {
+        None
+    };
+
     // Initialize watcher scheduler
-    let (watcher_event_tx, mut watcher_event_rx) = tokio::sync::mpsc::unbounded_channel();
-    let watcher_runner = Arc::new(tokio::sync::Mutex::new(
-        meepo_scheduler::runner::WatcherRunner::new(watcher_event_tx),
-    ));
+    let (watcher_event_tx, raw_watcher_event_rx) = tokio::sync::mpsc::unbounded_channel();
+    // Each watcher's filter and throttle rules apply between its events and the agent
+    let event_gate = meepo_scheduler::EventGate::new();
+    let (gated_event_tx, mut watcher_event_rx) = tokio::sync::mpsc::unbounded_channel();
+    event_gate.spawn(raw_watcher_event_rx, gated_event_tx);
+    // Webhook watchers are served by the gateway, so only register them when it runs
+    let webhook_registry = cfg.gateway.enabled.then(|| {
+        meepo_gateway::WebhookRegistry::new(watcher_event_tx.clone())
+            .with_deliveries(meepo_scheduler::WebhookDeliveries::new(sched_db.clone()))
+    });
+    let watcher_config = meepo_scheduler::runner::WatcherConfig {
+        timezone: user_timezone,
+        max_consecutive_errors: cfg.watchers.max_consecutive_errors,
+        max_backoff_secs: cfg.watchers.max_backoff_secs,
+        ..Default::default()
+    };
+    let mut runner =
+        meepo_scheduler::runner::WatcherRunner::with_config(watcher_event_tx, watcher_config)
+            .with_database(sched_db.clone())
+            .with_page_fetcher(Arc::new(meepo_core::tools::system::SafePageFetcher))
+            .with_event_gate(event_gate);
+    if let Some(registry) = &webhook_registry {
+        runner = runner.with_webhook_routes(Arc::new(registry.clone()));
+    }
+    let watcher_runner = Arc::new(tokio::sync::Mutex::new(runner));
 
-    // Initialize scheduler database (kept alive for runtime persistence)
-    let sched_db = Arc::new(std::sync::Mutex::new(rusqlite::Connection::open(&db_path)?));
-    let watchers = {
+    // Workflows run on their trigger watcher's events instead of the agent
+    let workflows = if cfg.workflows.enabled {
+        meepo_core::workflows::load_workflows(&shellexpand(&cfg.workflows.dir))?
+    } else {
+        Vec::new()
+    };
+
+    {
         let conn = sched_db.lock().unwrap();
-        meepo_scheduler::persistence::init_watcher_tables(&conn)?;
-        meepo_scheduler::persistence::get_active_watchers(&conn)?
-    }; // conn dropped here before any await
+        // Drop dedup state of removed watchers and items not seen for a month
+        if let Err(e) = meepo_scheduler::persistence::cleanup_poll_state(&conn, 30) {
+            warn!("Failed to clean up watcher poll state: {}", e);
+        }
+    } // conn dropped here before any await
+    // Keep workflow trigger watchers in step with the workflow files
+    let trigger_watchers: Vec<_> = workflows.iter().filter_map(|w| w.watcher()).collect();
+    for stale in watcher_store.active().await? {
+        if stale
+            .id
+            .starts_with(meepo_core::workflows::WORKFLOW_WATCHER_PREFIX)
+            && !trigger_watchers.iter().any(|w| w.id == stale.id)
+        {
+            watcher_store.deactivate(&stale.id).await?;
+        }
+    }
+    for watcher in &trigger_watchers {
+        watcher_store.save(watcher).await?;
+    }
+    let watchers = watcher_store.active().await?;
     {
         let runner = watcher_runner.lock().await;
         for w in watchers {
@@ -2191,28 +2677,6 @@ async fn cmd_start(config_path: &Option<PathBuf>) -> Result<()> {
         }
     });
 
-    // Forward watcher events to the autonomous loop
-    let (loop_watcher_tx, loop_watcher_rx) = tokio::sync::mpsc::unbounded_channel();
-    let cancel_clone2 = cancel.clone();
-    let wake_clone2 = wake.clone();
-    let watcher_to_loop = tokio::spawn(async move {
-        loop {
-            tokio::select! {
-                _ = cancel_clone2.cancelled() => break,
-                event = watcher_event_rx.recv() => {
-                    match event {
-                        Some(ev) => {
-                            info!("Watcher event: {} from {}", ev.kind, ev.watcher_id);
-                            let _ = loop_watcher_tx.send(ev);
-                            wake_clone2.notify_one();
-                        }
-                        None => break,
-                    }
-                }
-            }
-        }
-    });
-
     // Build notification service from config (needed by bg task handler and autonomous loop)
     let notifier = {
         let nc = &cfg.notifications;
@@ -2235,6 +2699,66 @@ async fn cmd_start(config_path: &Option<PathBuf>) -> Result<()> {
         meepo_core::notifications::NotificationService::new(notify_config, loop_resp_tx.clone())
     };
 
+    // Forward watcher events to the autonomous loop, or to the workflows they trigger
+    let workflow_engine = Arc::new(
+        meepo_core::workflows::WorkflowEngine::new(registry.clone())
+            .with_llm(Arc::new(workflow_api))
+            .with_run_store(meepo_scheduler::WorkflowRunStore::new(sched_db.clone())),
+    );
+    let mut workflows_by_watcher: std::collections::HashMap<
+        String,
+        Vec<meepo_core::workflows::Workflow>,
+    > = std::collections::HashMap::new();
+    for workflow in workflows {
+        workflows_by_watcher
+            .entry(workflow.watcher_id())
+            .or_default()
+            .push(workflow);
+    }
+    let workflow_notifier = notifier.clone();
+    let (loop_watcher_tx, loop_watcher_rx) = tokio::sync::mpsc::unbounded_channel();
+    let cancel_clone2 = cancel.clone();
+    let wake_clone2 = wake.clone();
+    let watcher_to_loop = tokio::spawn(async move {
+        loop {
+            tokio::select! {
+                _ = cancel_clone2.cancelled() => break,
+                event = watcher_event_rx.recv() => {
+                    match event {
+                        Some(ev) => {
+                            info!("Watcher event: {} from {}", ev.kind, ev.watcher_id);
+                            if ev.kind != "watcher_disabled"
+                                && let Some(triggered) = workflows_by_watcher.get(&ev.watcher_id)
+                            {
+                                for workflow in triggered {
+                                    let engine = workflow_engine.clone();
+                                    let notifier = workflow_notifier.clone();
+                                    let workflow = workflow.clone();
+                                    let ev = ev.clone();
+                                    tokio::spawn(async move {
+                                        let run = engine.run(&workflow, &ev).await;
+                                        if let Some(error) = run.error {
+                                            notifier
+                                                .notify(meepo_core::notifications::NotifyEvent::Error {
+                                                    context: format!("Workflow '{}'", workflow.name),
+                                                    error,
+                                                })
+                                                .await;
+                                        }
+                                    });
+                                }
+                                continue;
+                            }
+                            let _ = loop_watcher_tx.send(ev);
+                            wake_clone2.notify_one();
+                        }
+                        None => break,
+                    }
+                }
+            }
+        }
+    });
+
     // Clone bus_sender for background task handler before it moves into resp_to_bus
     let bus_sender_for_bg = bus_sender.clone();
 
@@ -2272,66 +2796,16 @@ async fn cmd_start(config_path: &Option<PathBuf>) -> Result<()> {
                 cmd = watcher_command_rx.recv() => {
                     if let Some(command) = cmd {
                         let runner = watcher_runner_clone.clone();
-                        let sched_db = sched_db.clone();
                         tokio::spawn(async move {
                             use meepo_core::tools::watchers::WatcherCommand;
                             match command {
-                                WatcherCommand::Create { id, kind, config, action, reply_channel } => {
-                                    // Map the tool's kind string to WatcherKind's serde tag variant name
-                                    let type_tag = match kind.as_str() {
-                                        "email" => "EmailWatch",
-                                        "calendar" => "CalendarWatch",
-                                        "github" => "GitHubWatch",
-                                        "file" => "FileWatch",
-                                        "message" => "MessageWatch",
-                                        "scheduled" | "time" => "Scheduled",
-                                        "oneshot" => "OneShot",
-                                        other => {
-                                            error!("Unknown watcher kind: {}", other);
-                                            return;
-                                        }
-                                    };
-                                    // Inject the "type" tag into config for serde deserialization
-                                    let config_with_type = match config {
-                                        serde_json::Value::Object(mut map) => {
-                                            map.insert("type".to_string(), serde_json::Value::String(type_tag.to_string()));
-                                            serde_json::Value::Object(map)
-                                        }
-                                        _ => {
-                                            error!("Watcher config is not a JSON object");
-                                            return;
-                                        }
-                                    };
-                                    let watcher_kind: meepo_scheduler::watcher::WatcherKind = match serde_json::from_value(config_with_type) {
-                                        Ok(k) => k,
-                                        Err(e) => {
-                                            error!("Failed to deserialize watcher kind: {}", e);
-                                            return;
-                                        }
-                                    };
-                                    let watcher = meepo_scheduler::watcher::Watcher {
-                                        id,
-                                        kind: watcher_kind,
-                                        action,
-                                        reply_channel,
-                                        active: true,
-                                        created_at: chrono::Utc::now(),
-                                    };
-                                    if let Ok(conn) = sched_db.lock()
-                                        && let Err(e) = meepo_scheduler::persistence::save_watcher(&conn, &watcher)
-                                    {
-                                        error!("Failed to persist watcher {}: {}", watcher.id, e);
-                                    }
-                                    if let Err(e) = runner.lock().await.start_watcher(watcher).await {
+                                // The tools have already saved the change to the watcher store
+                                WatcherCommand::Create { watcher } => {
+                                    if let Err(e) = runner.lock().await.start_watcher(*watcher).await {
                                         error!("Failed to start watcher: {}", e);
                                     }
                                 }
                                 WatcherCommand::Cancel { id } => {
-                                    if let Ok(conn) = sched_db.lock()
-                                        && let Err(e) = meepo_scheduler::persistence::deactivate_watcher(&conn, &id)
-                                    {
-                                        error!("Failed to deactivate watcher {} in scheduler DB: {}", id, e);
-                                    }
                                     if let Err(e) = runner.lock().await.stop_watcher(&id).await {
                                         error!("Failed to stop watcher {}: {}", id, e);
                                     }
@@ -2400,6 +2874,7 @@ async fn cmd_start(config_path: &Option<PathBuf>) -> Result<()> {
                                     content: description.clone(),
                                     channel: meepo_core::types::ChannelType::from_string(&reply_channel_clone),
                                     timestamp: chrono::Utc::now(),
+                                    thread_id: None,
                                 };
 
                                 let result = tokio::select! {
@@ -2636,6 +3111,7 @@ async fn cmd_start(config_path: &Option<PathBuf>) -> Result<()> {
     let auto_loop = meepo_core::autonomy::AutonomousLoop::new(
         agent.clone(),
         db.clone(),
+        watcher_store.clone(),
         autonomy_config,
         loop_msg_rx,
         loop_watcher_rx,
@@ -2654,6 +3130,7 @@ async fn cmd_start(config_path: &Option<PathBuf>) -> Result<()> {
         let cancel_digest = cancel.clone();
         let notifier_digest = notifier.clone();
         let db_digest = db.clone();
+        let watcher_store_digest = watcher_store.clone();
         let morning_cron = cfg.notifications.digest.morning_cron.clone();
         let evening_cron = cfg.notifications.digest.evening_cron.clone();
 
@@ -2727,7 +3204,7 @@ async fn cmd_start(config_path: &Option<PathBuf>) -> Result<()> {
                     }
                     _ = tokio::time::sleep_until(wake_time) => {
                         // Build digest summary from DB
-                        let summary = build_digest_summary(&db_digest, is_morning).await;
+                        let summary = build_digest_summary(&db_digest, &watcher_store_digest, is_morning).await;
 
                         if is_morning {
                             notifier_digest.notify(meepo_core::notifications::NotifyEvent::DigestMorning {
@@ -2746,23 +3223,163 @@ async fn cmd_start(config_path: &Option<PathBuf>) -> Result<()> {
         None
     };
 
-    // ── Phase 3: A2A Server ─────────────────────────────────────
-    if cfg.a2a.enabled {
-        let a2a_card = meepo_a2a::AgentCard {
-            name: "meepo".to_string(),
-            description:
-                "Personal AI agent with macOS and Windows integration, code tools, and web search"
-                    .to_string(),
-            url: format!("http://localhost:{}", cfg.a2a.port),
-            capabilities: vec![
-                "file_operations".to_string(),
-                "web_research".to_string(),
-                "email".to_string(),
-                "calendar".to_string(),
-                "code_review".to_string(),
-            ],
-            authentication: meepo_a2a::AuthConfig {
-                schemes: if cfg.a2a.auth_token.is_empty() {
+    // Periodic entity resolution pass
+    let resolution_cfg = cfg.knowledge.resolution.clone();
+    let resolution_task = if resolution_cfg.enabled && resolution_cfg.interval_hours > 0 {
+        let cancel_resolution = cancel.clone();
+        let graph_resolution = knowledge_graph.clone();
+        let confirmer = resolution_cfg
+            .llm_confirm
+            .then(|| meepo_core::LlmMergeConfirmer::new(Arc::new(resolution_api)));
+        let interval = std::time::Duration::from_secs(resolution_cfg.interval_hours * 3600);
+
+        Some(tokio::spawn(async move {
+            info!(
+                "Entity resolution runner started (every {}h)",
+                resolution_cfg.interval_hours
+            );
+            loop {
+                tokio::select! {
+                    _ = cancel_resolution.cancelled() => {
+                        info!("Entity resolution runner shutting down");
+                        break;
+                    }
+                    _ = tokio::time::sleep(interval) => {
+                        let confirmer = confirmer
+                            .as_ref()
+                            .map(|c| c as &dyn meepo_knowledge::MergeConfirmer);
+                        if let Err(e) = graph_resolution.run_entity_resolution(confirmer, false).await {
+                            warn!("Entity resolution pass failed: {}", e);
+                        }
+                    }
+                }
+            }
+        }))
+    } else {
+        None
+    };
+
+    // Periodic retention pass (forget low-importance memories)
+    let retention_cfg = cfg.knowledge.retention.clone();
+    let retention_task = if retention_cfg.enabled && retention_cfg.interval_hours > 0 {
+        let cancel_retention = cancel.clone();
+        let graph_retention = knowledge_graph.clone();
+        let policy = retention_policy(&retention_cfg)?;
+        let interval = std::time::Duration::from_secs(retention_cfg.interval_hours * 3600);
+
+        Some(tokio::spawn(async move {
+            info!(
+                "Memory retention runner started (every {}h, {:?} below {:.2})",
+                retention_cfg.interval_hours, policy.action, policy.threshold
+            );
+            loop {
+                tokio::select! {
+                    _ = cancel_retention.cancelled() => {
+                        info!("Memory retention runner shutting down");
+                        break;
+                    }
+                    _ = tokio::time::sleep(interval) => {
+                        if let Err(e) = graph_retention.apply_retention(&policy, false).await {
+                            warn!("Memory retention pass failed: {}", e);
+                        }
+                    }
+                }
+            }
+        }))
+    } else {
+        None
+    };
+
+    // Periodic community detection and summaries (global smart_recall)
+    let communities_cfg = cfg.knowledge.communities.clone();
+    let communities_task = if communities_cfg.enabled && communities_cfg.interval_hours > 0 {
+        let cancel_communities = cancel.clone();
+        let graph_communities = knowledge_graph.clone();
+        let summarizer = communities_cfg
+            .llm_summaries
+            .then(|| meepo_core::LlmCommunitySummarizer::new(Arc::new(community_api)));
+        let interval = std::time::Duration::from_secs(communities_cfg.interval_hours * 3600);
+
+        Some(tokio::spawn(async move {
+            info!(
+                "Community summary runner started (every {}h)",
+                communities_cfg.interval_hours
+            );
+            loop {
+                tokio::select! {
+                    _ = cancel_communities.cancelled() => {
+                        info!("Community summary runner shutting down");
+                        break;
+                    }
+                    _ = tokio::time::sleep(interval) => {
+                        let summarizer = summarizer
+                            .as_ref()
+                            .map(|s| s as &dyn meepo_knowledge::CommunitySummarizer);
+                        if let Err(e) = graph_communities.refresh_communities(summarizer).await {
+                            warn!("Community refresh failed: {}", e);
+                        }
+                    }
+                }
+            }
+        }))
+    } else {
+        None
+    };
+
+    // Periodic episodic memory extraction from idle conversation threads
+    let extraction_cfg = cfg.knowledge.extraction.clone();
+    let extraction_task = if extraction_cfg.enabled && extraction_cfg.interval_minutes > 0 {
+        let cancel_extraction = cancel.clone();
+        let graph_extraction = knowledge_graph.clone();
+        let extractor = meepo_core::LlmMemoryExtractor::new(Arc::new(extraction_api));
+        let config = extraction_config(&extraction_cfg);
+        let interval = std::time::Duration::from_secs(extraction_cfg.interval_minutes * 60);
+
+        Some(tokio::spawn(async move {
+            info!(
+                "Memory extraction runner started (threads idle for {}m{})",
+                extraction_cfg.idle_minutes,
+                if config.require_review {
+                    ", facts queued for review"
+                } else {
+                    ""
+                }
+            );
+            loop {
+                tokio::select! {
+                    _ = cancel_extraction.cancelled() => {
+                        info!("Memory extraction runner shutting down");
+                        break;
+                    }
+                    _ = tokio::time::sleep(interval) => {
+                        if let Err(e) = graph_extraction.extract_episodic_memories(&extractor, &config).await {
+                            warn!("Memory extraction pass failed: {}", e);
+                        }
+                    }
+                }
+            }
+        }))
+    } else {
+        None
+    };
+
+    // ── Phase 3: A2A Server ─────────────────────────────────────
+    if cfg.a2a.enabled {
+        let a2a_card = meepo_a2a::AgentCard {
+            name: "meepo".to_string(),
+            description:
+                "Personal AI agent with macOS and Windows integration, code tools, and web search"
+                    .to_string(),
+            url: format!("http://localhost:{}", cfg.a2a.port),
+            capabilities: vec![
+                "file_operations".to_string(),
+                "web_research".to_string(),
+                "email".to_string(),
+                "calendar".to_string(),
+                "code_review".to_string(),
+            ],
+            authentication: meepo_a2a::AuthConfig {
+                schemes: if cfg.a2a.auth_token.is_empty() {
                     vec![]
                 } else {
                     vec!["bearer".to_string()]
@@ -2799,11 +3416,15 @@ async fn cmd_start(config_path: &Option<PathBuf>) -> Result<()> {
             .context("Invalid gateway bind address")?;
 
         let gateway_token = shellexpand_str(&cfg.gateway.auth_token);
-        let gateway = meepo_gateway::GatewayServer::with_sessions(
+        let mut gateway = meepo_gateway::GatewayServer::with_sessions(
             bind_addr,
             gateway_token,
             shared_sessions.clone(),
         );
+        if let Some(registry) = webhook_registry {
+            gateway = gateway.with_webhooks(registry);
+        }
+        gateway = gateway.with_watcher_health(health_store.clone());
 
         tokio::spawn(async move {
             if let Err(e) = gateway.run().await {
@@ -2834,6 +3455,24 @@ async fn cmd_start(config_path: &Option<PathBuf>) -> Result<()> {
     if let Some(dt) = digest_task {
         let _ = dt.await;
     }
+    if let Some(rt) = resolution_task {
+        let _ = rt.await;
+    }
+    if let Some(rt) = retention_task {
+        let _ = rt.await;
+    }
+    if let Some(ct) = communities_task {
+        let _ = ct.await;
+    }
+    if let Some(et) = extraction_task {
+        let _ = et.await;
+    }
+    if let Some(mt) = memory_sync_task {
+        let _ = mt.await;
+    }
+    if let Some(ct) = corpus_task {
+        let _ = ct.await;
+    }
 
     // Stop all watchers
     watcher_runner.lock().await.stop_all().await;
@@ -2843,15 +3482,19 @@ async fn cmd_start(config_path: &Option<PathBuf>) -> Result<()> {
 }
 
 /// Build a digest summary from the knowledge database
-async fn build_digest_summary(db: &meepo_knowledge::KnowledgeDb, is_morning: bool) -> String {
+async fn build_digest_summary(
+    db: &meepo_knowledge::KnowledgeDb,
+    watcher_store: &meepo_scheduler::WatcherStore,
+    is_morning: bool,
+) -> String {
     let mut summary = String::new();
 
     // Active watchers
-    match db.get_active_watchers().await {
+    match watcher_store.active().await {
         Ok(watchers) if !watchers.is_empty() => {
             summary.push_str(&format!("📡 {} active watchers\n", watchers.len()));
             for w in watchers.iter().take(5) {
-                summary.push_str(&format!("  • {} → {}\n", w.kind, w.action));
+                summary.push_str(&format!("  • {} → {}\n", w.description(), w.action));
             }
             if watchers.len() > 5 {
                 summary.push_str(&format!("  ... and {} more\n", watchers.len() - 5));
@@ -2949,33 +3592,29 @@ async fn cmd_stop() -> Result<()> {
     Ok(())
 }
 
-async fn cmd_ask(config_path: &Option<PathBuf>, message: &str) -> Result<()> {
-    let cfg = MeepoConfig::load(config_path)?;
+/// API client for one-shot commands: the default model's provider, no failover
+fn build_simple_api_client(cfg: &MeepoConfig) -> Result<meepo_core::api::ApiClient> {
+    use meepo_core::providers::router::ModelRouter;
 
     let use_ollama = cfg.agent.default_model == "ollama";
-    let api = {
-        use meepo_core::providers::router::ModelRouter;
-
-        if use_ollama {
-            let ollama_cfg = cfg.providers.ollama.as_ref().ok_or_else(|| {
-                anyhow::anyhow!(
-                    "default_model is \"ollama\" but [providers.ollama] is not configured"
-                )
-            })?;
-            let url = format!("{}/v1", shellexpand_str(&ollama_cfg.base_url));
-            use meepo_core::providers::openai_compat::OpenAiCompatProvider;
-            let provider = Box::new(OpenAiCompatProvider::new(
-                "ollama".to_string(),
-                String::new(),
-                ollama_cfg.model.clone(),
-                url,
-                ollama_cfg.max_tokens,
-            ));
-            meepo_core::api::ApiClient::from_router(ModelRouter::single(provider))
-        } else {
-            let anthropic_cfg = cfg.providers.anthropic.as_ref().ok_or_else(|| {
-                anyhow::anyhow!(
-                    "ANTHROPIC_API_KEY is not set.\n\n\
+    let api = if use_ollama {
+        let ollama_cfg = cfg.providers.ollama.as_ref().ok_or_else(|| {
+            anyhow::anyhow!("default_model is \"ollama\" but [providers.ollama] is not configured")
+        })?;
+        let url = format!("{}/v1", shellexpand_str(&ollama_cfg.base_url));
+        use meepo_core::providers::openai_compat::OpenAiCompatProvider;
+        let provider = Box::new(OpenAiCompatProvider::new(
+            "ollama".to_string(),
+            String::new(),
+            ollama_cfg.model.clone(),
+            url,
+            ollama_cfg.max_tokens,
+        ));
+        meepo_core::api::ApiClient::from_router(ModelRouter::single(provider))
+    } else {
+        let anthropic_cfg = cfg.providers.anthropic.as_ref().ok_or_else(|| {
+            anyhow::anyhow!(
+                "ANTHROPIC_API_KEY is not set.\n\n\
                      Fix it with:\n  \
                      export ANTHROPIC_API_KEY=\"sk-ant-...\"\n\n\
                      Or use Ollama (no API key needed):\n  \
@@ -2983,12 +3622,12 @@ async fn cmd_ask(config_path: &Option<PathBuf>, message: &str) -> Result<()> {
                      Or run the setup wizard:\n  \
                      meepo setup\n\n\
                      Get a key at: https://console.anthropic.com/settings/keys"
-                )
-            })?;
-            let api_key = shellexpand_str(&anthropic_cfg.api_key);
-            if api_key.is_empty() || api_key.contains("${") {
-                anyhow::bail!(
-                    "ANTHROPIC_API_KEY is not set.\n\n\
+            )
+        })?;
+        let api_key = shellexpand_str(&anthropic_cfg.api_key);
+        if api_key.is_empty() || api_key.contains("${") {
+            anyhow::bail!(
+                "ANTHROPIC_API_KEY is not set.\n\n\
                      Fix it with:\n  \
                      export ANTHROPIC_API_KEY=\"sk-ant-...\"\n\n\
                      Or use Ollama (no API key needed):\n  \
@@ -2996,14 +3635,20 @@ async fn cmd_ask(config_path: &Option<PathBuf>, message: &str) -> Result<()> {
                      Or run the setup wizard:\n  \
                      meepo setup\n\n\
                      Get a key at: https://console.anthropic.com/settings/keys"
-                );
-            }
-            let base_url = shellexpand_str(&anthropic_cfg.base_url);
-            meepo_core::api::ApiClient::new(api_key, Some(cfg.agent.default_model.clone()))
-                .with_max_tokens(cfg.agent.max_tokens)
-                .with_base_url(base_url)
+            );
         }
+        let base_url = shellexpand_str(&anthropic_cfg.base_url);
+        meepo_core::api::ApiClient::new(api_key, Some(cfg.agent.default_model.clone()))
+            .with_max_tokens(cfg.agent.max_tokens)
+            .with_base_url(base_url)
     };
+    Ok(api)
+}
+
+async fn cmd_ask(config_path: &Option<PathBuf>, message: &str) -> Result<()> {
+    let cfg = MeepoConfig::load(config_path)?;
+
+    let api = build_simple_api_client(&cfg)?;
 
     // Load context
     let workspace = shellexpand(&cfg.memory.workspace);
@@ -3046,7 +3691,9 @@ async fn cmd_usage(config_path: &Option<PathBuf>, period: &str, csv: bool) -> Re
     }
 
     let db = Arc::new(
-        meepo_knowledge::KnowledgeDb::new(&db_path).context("Failed to open knowledge database")?,
+        open_knowledge_db(&cfg, &db_path)
+            .await
+            .context("Failed to open knowledge database")?,
     );
 
     let today = chrono::Utc::now().format("%Y-%m-%d").to_string();
@@ -3151,10 +3798,12 @@ async fn cmd_usage(config_path: &Option<PathBuf>, period: &str, csv: bool) -> Re
     Ok(())
 }
 
-async fn cmd_mcp_server(config_path: &Option<PathBuf>) -> Result<()> {
-    let cfg = MeepoConfig::load(config_path)?;
+/// Build the tool registry used outside the daemon: the same tools as
+/// `cmd_start`, minus channels, bus and orchestrator
+async fn build_standalone_registry(cfg: &MeepoConfig) -> Result<meepo_core::tools::ToolRegistry> {
+    let user_timezone = meepo_scheduler::schedule::resolve_timezone(&cfg.watchers.timezone)
+        .context("Invalid [watchers] timezone")?;
 
-    // Build tool registry (same tools as cmd_start, minus channels/bus/orchestrator)
     let db_path = shellexpand(&cfg.knowledge.db_path);
     let tantivy_path = shellexpand(&cfg.knowledge.tantivy_path);
     if let Some(parent) = db_path.parent() {
@@ -3162,12 +3811,24 @@ async fn cmd_mcp_server(config_path: &Option<PathBuf>) -> Result<()> {
     }
     std::fs::create_dir_all(&tantivy_path)?;
 
+    let db_key = knowledge_db_key(cfg).await?;
     let knowledge_graph = Arc::new(
-        meepo_knowledge::KnowledgeGraph::new(&db_path, &tantivy_path)
+        open_knowledge_graph(cfg, &db_path, &tantivy_path, db_key.as_deref())
+            .await
             .context("Failed to initialize knowledge graph")?,
     );
     let db = knowledge_graph.db();
 
+    // Watchers and the feed items collected by the daemon's feed watchers live in
+    // the scheduler tables
+    let sched_db = Arc::new(std::sync::Mutex::new(
+        meepo_knowledge::encryption::open_connection(&db_path, db_key.as_deref())?,
+    ));
+    meepo_scheduler::persistence::init_watcher_tables(&sched_db.lock().unwrap())?;
+    let watcher_store = meepo_scheduler::WatcherStore::new(sched_db.clone());
+    let feed_store = meepo_scheduler::FeedStore::new(sched_db.clone());
+    let health_store = meepo_scheduler::HealthStore::new(sched_db);
+
     // Tavily client (optional)
     let tavily_client = cfg
         .providers
@@ -3178,8 +3839,10 @@ async fn cmd_mcp_server(config_path: &Option<PathBuf>) -> Result<()> {
         .map(|key| Arc::new(meepo_core::tavily::TavilyClient::new(key)));
 
     // Watcher command channel (needed for tool registration even in MCP mode)
-    let (watcher_command_tx, _watcher_command_rx) =
+    let (watcher_command_tx, mut watcher_command_rx) =
         tokio::sync::mpsc::channel::<meepo_core::tools::watchers::WatcherCommand>(100);
+    // No scheduler runs outside the daemon; drain commands so the tools don't fail
+    tokio::spawn(async move { while watcher_command_rx.recv().await.is_some() {} });
 
     let mut registry = meepo_core::tools::ToolRegistry::new();
 
@@ -3419,14 +4082,21 @@ async fn cmd_mcp_server(config_path: &Option<PathBuf>) -> Result<()> {
         code_config,
     )));
     registry.register(Arc::new(meepo_core::tools::memory::RememberTool::new(
-        db.clone(),
-    )));
-    registry.register(Arc::new(meepo_core::tools::memory::RecallTool::new(
-        db.clone(),
+        knowledge_graph.clone(),
     )));
+    registry.register(Arc::new(
+        meepo_core::tools::memory::RecallTool::new(db.clone())
+            .with_importance(importance_config(&cfg.knowledge.importance)),
+    ));
     registry.register(Arc::new(
         meepo_core::tools::memory::SearchKnowledgeTool::with_graph(knowledge_graph.clone()),
     ));
+    registry.register(Arc::new(meepo_core::tools::memory::QueryGraphTool::new(
+        knowledge_graph.clone(),
+    )));
+    registry.register(Arc::new(
+        meepo_core::tools::memory::SearchConversationsTool::new(db.clone()),
+    ));
     registry.register(Arc::new(meepo_core::tools::memory::LinkEntitiesTool::new(
         db.clone(),
     )));
@@ -3454,17 +4124,28 @@ async fn cmd_mcp_server(config_path: &Option<PathBuf>) -> Result<()> {
         registry.register(Arc::new(meepo_core::tools::system::BrowseUrlTool::new()));
     }
     registry.register(Arc::new(
-        meepo_core::tools::watchers::CreateWatcherTool::new(db.clone(), watcher_command_tx.clone()),
+        meepo_core::tools::watchers::CreateWatcherTool::new(
+            watcher_store.clone(),
+            watcher_command_tx.clone(),
+        )
+        .with_timezone(user_timezone),
+    ));
+    registry.register(Arc::new(
+        meepo_core::tools::watchers::PreviewScheduleTool::new(user_timezone),
     ));
     registry.register(Arc::new(
-        meepo_core::tools::watchers::ListWatchersTool::new(db.clone()),
+        meepo_core::tools::watchers::ListWatchersTool::new(watcher_store.clone())
+            .with_health_store(health_store.clone()),
     ));
     registry.register(Arc::new(
-        meepo_core::tools::watchers::CancelWatcherTool::new(db.clone(), watcher_command_tx.clone()),
+        meepo_core::tools::watchers::CancelWatcherTool::new(
+            watcher_store.clone(),
+            watcher_command_tx.clone(),
+        ),
     ));
     // Autonomous tools — agent_status works in MCP mode, spawn/stop won't have handlers
     registry.register(Arc::new(
-        meepo_core::tools::autonomous::AgentStatusTool::new(db.clone()),
+        meepo_core::tools::autonomous::AgentStatusTool::new(db.clone(), watcher_store.clone()),
     ));
 
     // ── Lifestyle Integration Tools (MCP mode) ──────────────────
@@ -3557,7 +4238,8 @@ async fn cmd_mcp_server(config_path: &Option<PathBuf>) -> Result<()> {
         meepo_core::tools::lifestyle::news::ContentDigestTool::new(
             tavily_client.clone(),
             db.clone(),
-        ),
+        )
+        .with_feed_store(feed_store.clone()),
     ));
     registry.register(Arc::new(
         meepo_core::tools::lifestyle::finance::LogExpenseTool::new(db.clone()),
@@ -3736,6 +4418,12 @@ async fn cmd_mcp_server(config_path: &Option<PathBuf>) -> Result<()> {
         }
     }
 
+    Ok(registry)
+}
+
+async fn cmd_mcp_server(config_path: &Option<PathBuf>) -> Result<()> {
+    let cfg = MeepoConfig::load(config_path)?;
+    let registry = build_standalone_registry(&cfg).await?;
     let registry = Arc::new(registry);
     info!("MCP server: {} tools available", registry.len());
 
@@ -3820,7 +4508,7 @@ async fn cmd_template(action: TemplateAction) -> Result<()> {
             if !t.goals.is_empty() {
                 let db_path = config_dir.join("knowledge.db");
                 if db_path.exists() {
-                    let db = meepo_knowledge::KnowledgeDb::new(&db_path)?;
+                    let db = open_knowledge_db(&MeepoConfig::load(&None)?, &db_path).await?;
                     let source = format!("template:{}", t.metadata.name);
                     for goal in &t.goals {
                         db.insert_goal(
@@ -3939,7 +4627,7 @@ async fn cmd_template(action: TemplateAction) -> Result<()> {
             // 4. Delete template goals
             let db_path = config_dir.join("knowledge.db");
             if db_path.exists() {
-                let db = meepo_knowledge::KnowledgeDb::new(&db_path)?;
+                let db = open_knowledge_db(&MeepoConfig::load(&None)?, &db_path).await?;
                 let source = format!("template:{}", active.name);
                 let deleted = db.delete_goals_by_source(&source).await?;
                 println!("  Removed {} template goals", deleted);
@@ -4030,6 +4718,234 @@ tags = []
     }
 }
 
+async fn cmd_watchers(config_path: &Option<PathBuf>, action: WatchersAction) -> Result<()> {
+    let cfg = MeepoConfig::load(config_path)?;
+    let db_path = shellexpand(&cfg.knowledge.db_path);
+    if !db_path.exists() {
+        bail!(
+            "Knowledge database not found at {}. Run `meepo start` first.",
+            db_path.display()
+        );
+    }
+    let db_key = knowledge_db_key(&cfg).await?;
+    let conn = meepo_knowledge::encryption::open_connection(&db_path, db_key.as_deref())?;
+    meepo_scheduler::persistence::init_watcher_tables(&conn)?;
+
+    let time = |at: Option<chrono::DateTime<chrono::Utc>>| {
+        at.map(|at| at.format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_else(|| "-".to_string())
+    };
+    match action {
+        WatchersAction::List => {
+            let health = meepo_scheduler::persistence::list_watcher_health(&conn)?;
+            if health.is_empty() {
+                println!("  No watchers.");
+                return Ok(());
+            }
+            for h in &health {
+                let description =
+                    meepo_scheduler::persistence::get_watcher_by_id(&conn, &h.watcher_id)?
+                        .map(|w| w.description())
+                        .unwrap_or_default();
+                println!("  {}  [{}]  {}", h.watcher_id, h.status, description);
+                println!(
+                    "      last success {}, next run {}",
+                    time(h.last_success),
+                    time(h.next_run)
+                );
+                if let Some(ref error) = h.last_error {
+                    println!(
+                        "      last error {} ({} in a row): {}",
+                        time(h.last_error_at),
+                        h.consecutive_errors,
+                        error
+                    );
+                }
+            }
+            Ok(())
+        }
+        WatchersAction::DeadLetters { watcher, limit } => {
+            let letters =
+                meepo_scheduler::persistence::get_dead_letters(&conn, watcher.as_deref(), limit)?;
+            if letters.is_empty() {
+                println!("  No failed watcher runs.");
+                return Ok(());
+            }
+            for letter in &letters {
+                println!(
+                    "  {}  {}  {}",
+                    letter.failed_at.format("%Y-%m-%d %H:%M"),
+                    letter.watcher_id,
+                    letter.error
+                );
+                if let Some(ref event) = letter.event {
+                    println!("      undelivered {} event: {}", event.kind, event.payload);
+                }
+            }
+            Ok(())
+        }
+    }
+}
+
+async fn cmd_workflow(config_path: &Option<PathBuf>, action: WorkflowAction) -> Result<()> {
+    let cfg = MeepoConfig::load(config_path)?;
+    let dir = shellexpand(&cfg.workflows.dir);
+
+    match action {
+        WorkflowAction::List => {
+            let workflows = meepo_core::workflows::load_workflows(&dir)?;
+            if workflows.is_empty() {
+                println!("  No workflows in {}.", dir.display());
+                return Ok(());
+            }
+            if !cfg.workflows.enabled {
+                println!("  Workflows are disabled; set [workflows] enabled = true to run them.\n");
+            }
+            for workflow in &workflows {
+                let trigger = match workflow.watcher() {
+                    Some(watcher) => watcher.description(),
+                    None => format!("events of watcher {}", workflow.watcher_id()),
+                };
+                println!("  {}  {}", workflow.name, workflow.description);
+                println!("      trigger: {}", trigger);
+                let steps: Vec<_> = workflow
+                    .steps
+                    .iter()
+                    .map(|step| format!("{} ({})", step.id, step.action()))
+                    .collect();
+                println!("      steps: {}", steps.join(" → "));
+            }
+            Ok(())
+        }
+        WorkflowAction::Run {
+            name,
+            event,
+            event_file,
+            kind,
+            dry_run,
+        } => {
+            let workflow = meepo_core::workflows::load_workflows(&dir)?
+                .into_iter()
+                .find(|w| w.name == name)
+                .ok_or_else(|| {
+                    anyhow::anyhow!("No workflow named '{}' in {}", name, dir.display())
+                })?;
+            let payload = match (event, event_file) {
+                (Some(json), _) => {
+                    serde_json::from_str(&json).context("--event is not valid JSON")?
+                }
+                (None, Some(path)) => {
+                    let json = std::fs::read_to_string(&path)
+                        .with_context(|| format!("Failed to read {}", path.display()))?;
+                    serde_json::from_str(&json)
+                        .with_context(|| format!("{} is not valid JSON", path.display()))?
+                }
+                (None, None) => serde_json::json!({}),
+            };
+            let event = meepo_scheduler::WatcherEvent::new(workflow.watcher_id(), kind, payload);
+
+            let run = if dry_run {
+                println!("  Dry run of '{}' — no tools or LLM calls\n", workflow.name);
+                meepo_core::workflows::dry_run(&workflow, &event)
+            } else {
+                let registry = build_standalone_registry(&cfg).await?;
+                let db_path = shellexpand(&cfg.knowledge.db_path);
+                let db_key = knowledge_db_key(&cfg).await?;
+                let conn =
+                    meepo_knowledge::encryption::open_connection(&db_path, db_key.as_deref())?;
+                meepo_scheduler::persistence::init_watcher_tables(&conn)?;
+                let mut engine = meepo_core::workflows::WorkflowEngine::new(Arc::new(registry))
+                    .with_run_store(meepo_scheduler::WorkflowRunStore::new(Arc::new(
+                        std::sync::Mutex::new(conn),
+                    )));
+                if workflow.steps.iter().any(|step| step.llm.is_some()) {
+                    engine = engine.with_llm(Arc::new(build_simple_api_client(&cfg)?));
+                }
+                engine.run(&workflow, &event).await
+            };
+
+            for (step, def) in run.steps.iter().zip(&workflow.steps) {
+                print_workflow_step(step);
+                if let Some(ref when) = def.when {
+                    println!("      when: {}", when);
+                }
+            }
+            match run.error {
+                Some(error) => bail!("Workflow '{}' failed: {}", workflow.name, error),
+                None if dry_run => Ok(()),
+                None => {
+                    println!(
+                        "\n  Workflow '{}' succeeded (run #{}).",
+                        workflow.name, run.id
+                    );
+                    Ok(())
+                }
+            }
+        }
+        WorkflowAction::History { name, limit } => {
+            let db_path = shellexpand(&cfg.knowledge.db_path);
+            if !db_path.exists() {
+                bail!(
+                    "Knowledge database not found at {}. Run `meepo start` first.",
+                    db_path.display()
+                );
+            }
+            let db_key = knowledge_db_key(&cfg).await?;
+            let conn = meepo_knowledge::encryption::open_connection(&db_path, db_key.as_deref())?;
+            meepo_scheduler::persistence::init_watcher_tables(&conn)?;
+
+            let runs =
+                meepo_scheduler::persistence::get_workflow_runs(&conn, name.as_deref(), limit)?;
+            if runs.is_empty() {
+                println!("  No workflow runs.");
+                return Ok(());
+            }
+            for run in &runs {
+                let trigger = run
+                    .event
+                    .as_ref()
+                    .map(|event| format!("{} from {}", event.kind, event.watcher_id))
+                    .unwrap_or_default();
+                println!(
+                    "  #{}  {}  {}  [{}]  {}",
+                    run.id,
+                    run.started_at.format("%Y-%m-%d %H:%M"),
+                    run.workflow,
+                    run.status,
+                    trigger
+                );
+                if let Some(ref error) = run.error {
+                    println!("      {}", error);
+                }
+                for step in &run.steps {
+                    println!("      {}  {}  [{}]", step.id, step.action, step.status);
+                }
+            }
+            Ok(())
+        }
+    }
+}
+
+fn print_workflow_step(step: &meepo_scheduler::StepRun) {
+    let preview = |value: &serde_json::Value| {
+        let text = value.to_string();
+        match text.char_indices().nth(200) {
+            Some((at, _)) => format!("{}…", &text[..at]),
+            None => text,
+        }
+    };
+    println!("  {}  {}  [{}]", step.id, step.action, step.status);
+    if let Some(ref input) = step.input {
+        println!("      input: {}", preview(input));
+    }
+    if let Some(ref output) = step.output {
+        println!("      output: {}", preview(output));
+    }
+    if let Some(ref error) = step.error {
+        println!("      error: {}", error);
+    }
+}
+
 async fn cmd_doctor(config_path: &Option<PathBuf>) -> Result<()> {
     let cfg = config::MeepoConfig::load(config_path)?;
     let db_path = shellexpand(&cfg.knowledge.db_path);
@@ -4038,9 +4954,26 @@ async fn cmd_doctor(config_path: &Option<PathBuf>) -> Result<()> {
         .clone()
         .unwrap_or_else(|| config::config_dir().join("config.toml"));
 
-    let report =
-        meepo_core::doctor::run_doctor(Some(config_file_buf.as_path()), Some(db_path.as_path()))
-            .await?;
+    // The graph is optional: a missing or locked database is reported by the
+    // other checks, and the consistency check is then skipped
+    let graph = if db_path.exists() {
+        let tantivy_path = shellexpand(&cfg.knowledge.tantivy_path);
+        match knowledge_db_key(&cfg).await {
+            Ok(key) => open_knowledge_graph(&cfg, &db_path, &tantivy_path, key.as_deref())
+                .await
+                .ok(),
+            Err(_) => None,
+        }
+    } else {
+        None
+    };
+
+    let report = meepo_core::doctor::run_doctor(
+        Some(config_file_buf.as_path()),
+        Some(db_path.as_path()),
+        graph.as_ref(),
+    )
+    .await?;
 
     println!("\n  Meepo Doctor");
     println!("  ────────────\n");
@@ -4070,6 +5003,960 @@ async fn cmd_doctor(config_path: &Option<PathBuf>) -> Result<()> {
     Ok(())
 }
 
+async fn cmd_memory(config_path: &Option<PathBuf>, action: MemoryAction) -> Result<()> {
+    let cfg = MeepoConfig::load(config_path)?;
+    let db_path = shellexpand(&cfg.knowledge.db_path);
+    let tantivy_path = shellexpand(&cfg.knowledge.tantivy_path);
+    if !db_path.exists() && !matches!(action, MemoryAction::Import { .. }) {
+        bail!(
+            "Knowledge database not found at {}. Run `meepo start` first.",
+            db_path.display()
+        );
+    }
+    if let Some(parent) = db_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    // Encryption changes the database file itself, so run before opening it
+    match action {
+        MemoryAction::Encrypt => {
+            let key = resolve_secret(&cfg, &cfg.knowledge.encryption.key_secret).await?;
+            meepo_knowledge::encryption::encrypt_database(&db_path, &key)?;
+            // The on-disk index holds plaintext copies of entity text
+            if tantivy_path.exists() {
+                std::fs::remove_dir_all(&tantivy_path)?;
+            }
+            println!("  Encrypted {}", db_path.display());
+            println!("  Removed the on-disk search index; it is now rebuilt in memory.");
+            if !cfg.knowledge.encryption.enabled {
+                println!("  Set `enabled = true` under [knowledge.encryption] in your config.");
+            }
+            return Ok(());
+        }
+        MemoryAction::Decrypt => {
+            let key = resolve_secret(&cfg, &cfg.knowledge.encryption.key_secret).await?;
+            meepo_knowledge::encryption::decrypt_database(&db_path, &key)?;
+            let graph = open_knowledge_graph(&cfg, &db_path, &tantivy_path, None).await?;
+            graph.reindex().await?;
+            println!(
+                "  Decrypted {} and rebuilt the search index",
+                db_path.display()
+            );
+            if cfg.knowledge.encryption.enabled {
+                println!("  Set `enabled = false` under [knowledge.encryption] in your config.");
+            }
+            return Ok(());
+        }
+        MemoryAction::Rekey { new_key_secret } => {
+            let old_key = resolve_secret(&cfg, &cfg.knowledge.encryption.key_secret).await?;
+            let new_key = resolve_secret(&cfg, &new_key_secret).await?;
+            meepo_knowledge::encryption::rekey_database(&db_path, &old_key, &new_key)?;
+            println!("  Rotated the encryption key for {}", db_path.display());
+            println!(
+                "  Set `key_secret = \"{}\"` under [knowledge.encryption] in your config.",
+                new_key_secret
+            );
+            return Ok(());
+        }
+        _ => {}
+    }
+
+    let db_key = knowledge_db_key(&cfg).await?;
+    let graph = open_knowledge_graph(&cfg, &db_path, &tantivy_path, db_key.as_deref())
+        .await
+        .context("Failed to open knowledge graph")?;
+
+    match action {
+        MemoryAction::Search {
+            query,
+            entity_type,
+            limit,
+        } => {
+            let entities = graph
+                .search_entities(&query, entity_type.as_deref())
+                .await?;
+            if entities.is_empty() {
+                println!("No matching entities.");
+                return Ok(());
+            }
+            println!();
+            for entity in entities.iter().take(limit) {
+                println!(
+                    "  {}  {:<12} {}",
+                    entity.id, entity.entity_type, entity.name
+                );
+            }
+            println!(
+                "\n  {} of {} shown\n",
+                entities.len().min(limit),
+                entities.len()
+            );
+            Ok(())
+        }
+        MemoryAction::Show { id, as_of } => {
+            let as_of = as_of
+                .as_deref()
+                .map(meepo_knowledge::temporal::parse_as_of)
+                .transpose()?;
+            if let Some(entity) = graph.get_entity(&id).await? {
+                let context = match as_of {
+                    Some(at) => graph.get_context_as_of(&id, at).await?,
+                    None => graph.get_context_for(&id).await?,
+                };
+                println!("\n  {} ({})", entity.name, entity.entity_type);
+                println!("  ID:       {}", entity.id);
+                println!("  Created:  {}", entity.created_at.to_rfc3339());
+                println!("  Updated:  {}", entity.updated_at.to_rfc3339());
+                if let Some(score) = graph.importance_of(&id).await? {
+                    println!("  Importance: {:.2}", score);
+                }
+                if let Some(at) = as_of {
+                    println!("  As of:    {}", at.to_rfc3339());
+                }
+                if let Some(meta) = &entity.metadata {
+                    println!("  Metadata: {}", serde_json::to_string_pretty(meta)?);
+                }
+                // Without --as-of, include ended relationships as history
+                let rels = match as_of {
+                    Some(_) => context.relationships.clone(),
+                    None => graph.get_relationships(&id).await?,
+                };
+                if !rels.is_empty() {
+                    println!("\n  Relationships:");
+                    for rel in &rels {
+                        let (arrow, other_id) = if rel.source_id == id {
+                            ("->", &rel.target_id)
+                        } else {
+                            ("<-", &rel.source_id)
+                        };
+                        let other = graph
+                            .get_entity(other_id)
+                            .await?
+                            .map(|e| e.name)
+                            .unwrap_or_else(|| other_id.clone());
+                        let validity = match (rel.valid_from, rel.valid_to) {
+                            (_, Some(end)) => format!("  (ended {})", end.format("%Y-%m-%d")),
+                            (Some(start), None) => {
+                                format!("  (since {})", start.format("%Y-%m-%d"))
+                            }
+                            (None, None) => String::new(),
+                        };
+                        println!(
+                            "    {} {} {} [{}]{}",
+                            arrow, rel.relation_type, other, rel.id, validity
+                        );
+                    }
+                }
+                if !context.provenance.is_empty() {
+                    println!("\n  Sources:");
+                    for source in &context.provenance {
+                        let target = if source.fact_id == id {
+                            "entity".to_string()
+                        } else {
+                            source.fact_id.clone()
+                        };
+                        println!("    {}  ({})", source.describe(), target);
+                    }
+                }
+                println!();
+            } else if let Some(rel) = graph.db().get_relationship(&id).await? {
+                println!("\n  Relationship {}", rel.id);
+                println!(
+                    "  {} -[{}]-> {}",
+                    rel.source_id, rel.relation_type, rel.target_id
+                );
+                println!("  Created:  {}", rel.created_at.to_rfc3339());
+                if let Some(start) = rel.valid_from {
+                    println!("  Valid:    from {}", start.to_rfc3339());
+                }
+                if let Some(end) = rel.valid_to {
+                    println!("  Ended:    {}", end.to_rfc3339());
+                }
+                if let Some(next) = &rel.superseded_by {
+                    println!("  Superseded by: {}", next);
+                }
+                if let Some(meta) = &rel.metadata {
+                    println!("  Metadata: {}", serde_json::to_string_pretty(meta)?);
+                }
+                for source in graph
+                    .db()
+                    .get_provenance(std::slice::from_ref(&rel.id))
+                    .await?
+                {
+                    println!("  Source:   {}", source.describe());
+                }
+                println!();
+            } else {
+                bail!("No entity or relationship with ID {}", id);
+            }
+            Ok(())
+        }
+        MemoryAction::Edit {
+            id,
+            name,
+            kind,
+            metadata,
+        } => {
+            let metadata = metadata
+                .map(|m| serde_json::from_str::<serde_json::Value>(&m))
+                .transpose()
+                .context("--metadata must be valid JSON")?;
+            if graph
+                .update_entity(&id, name.as_deref(), kind.as_deref(), metadata.clone())
+                .await?
+            {
+                println!("Updated entity {}", id);
+            } else if name.is_none()
+                && graph
+                    .update_relationship(&id, kind.as_deref(), metadata)
+                    .await?
+            {
+                println!("Updated relationship {}", id);
+            } else {
+                bail!("No entity or relationship with ID {}", id);
+            }
+            Ok(())
+        }
+        MemoryAction::Delete { id } => {
+            if graph.delete_entity(&id).await? {
+                println!("Deleted entity {} and its relationships", id);
+            } else if graph.delete_relationship(&id).await? {
+                println!("Deleted relationship {}", id);
+            } else {
+                bail!("No entity or relationship with ID {}", id);
+            }
+            Ok(())
+        }
+        MemoryAction::Export { format, output } => {
+            let format: meepo_knowledge::ExportFormat = format.parse()?;
+            let data = meepo_knowledge::export_knowledge(&graph.db(), format).await?;
+            match output {
+                Some(path) => {
+                    std::fs::write(&path, &data)
+                        .with_context(|| format!("Failed to write {}", path.display()))?;
+                    println!("Exported knowledge graph to {}", path.display());
+                }
+                None => print!("{}", data),
+            }
+            Ok(())
+        }
+        MemoryAction::Import { path, strategy } => {
+            let strategy: meepo_knowledge::ConflictStrategy = strategy.parse()?;
+            let data = std::fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            // Only the search index is rebuilt: the CLI has no embedding
+            // provider yet ([rag.embeddings]), so there is no vector index
+            let report = meepo_knowledge::import_knowledge(&graph, &data, strategy, None).await?;
+            println!("\n  Import complete");
+            println!("  ───────────────\n");
+            println!(
+                "  Entities:      {} created, {} merged, {} skipped",
+                report.entities_created, report.entities_merged, report.entities_skipped
+            );
+            println!(
+                "  Relationships: {} created, {} skipped",
+                report.relationships_created, report.relationships_skipped
+            );
+            println!(
+                "  Conversations: {} created, {} skipped",
+                report.conversations_created, report.conversations_skipped
+            );
+            println!(
+                "  Goals:         {} written, {} skipped",
+                report.goals_written, report.goals_skipped
+            );
+            println!(
+                "  Preferences:   {} written, {} skipped",
+                report.preferences_written, report.preferences_skipped
+            );
+            println!(
+                "  Provenance:    {} created, {} skipped",
+                report.provenance_created, report.provenance_skipped
+            );
+            println!("  Remapped IDs:  {}", report.remapped_ids.len());
+            println!("\n  Search index rebuilt.\n");
+            Ok(())
+        }
+        MemoryAction::Merge { keep, duplicate } => {
+            if keep == duplicate {
+                bail!("Cannot merge an entity into itself");
+            }
+            let kept = graph
+                .merge_entities(&keep, &duplicate, meepo_knowledge::MatchReason::Manual, 1.0)
+                .await?;
+            println!("Merged {} into {} ({})", duplicate, kept.name, kept.id);
+            Ok(())
+        }
+        MemoryAction::Dedupe { dry_run } => {
+            let report = graph.run_entity_resolution(None, dry_run).await?;
+            println!("\n  Entity resolution");
+            println!("  ─────────────────\n");
+            println!("  Pairs examined: {}", report.pairs_examined);
+            for m in &report.merged {
+                println!(
+                    "  merged   {} -> {}  ({:?}, {:.2})",
+                    m.merged_name, m.kept_id, m.reason, m.score
+                );
+            }
+            for pair in &report.pending {
+                println!(
+                    "  {}  {} [{}] ~ {} [{}]  ({:?}, {:.2})",
+                    if dry_run { "candidate" } else { "pending " },
+                    pair.keep.name,
+                    pair.keep.id,
+                    pair.merge.name,
+                    pair.merge.id,
+                    pair.reason,
+                    pair.score
+                );
+            }
+            for f in &report.failed {
+                println!(
+                    "  failed   {} -> {}  ({})",
+                    f.merged_name, f.kept_id, f.error
+                );
+            }
+            if !dry_run && !report.pending.is_empty() {
+                println!(
+                    "\n  Review pending pairs and merge with `meepo memory merge <keep> <duplicate>`."
+                );
+            }
+            println!();
+            if !report.failed.is_empty() {
+                bail!("{} merge(s) failed", report.failed.len());
+            }
+            Ok(())
+        }
+        MemoryAction::Pin { id } => {
+            if !graph.pin(&id, true).await? {
+                bail!("No entity with ID {}", id);
+            }
+            println!("  Pinned {}", id);
+            Ok(())
+        }
+        MemoryAction::Unpin { id } => {
+            if !graph.pin(&id, false).await? {
+                bail!("No entity with ID {}", id);
+            }
+            println!("  Unpinned {}", id);
+            Ok(())
+        }
+        MemoryAction::Forget { dry_run, threshold } => {
+            let mut policy = retention_policy(&cfg.knowledge.retention)?;
+            if let Some(threshold) = threshold {
+                policy.threshold = threshold;
+            }
+            let report = graph.apply_retention(&policy, dry_run).await?;
+            println!(
+                "\n  Memory retention{}",
+                if dry_run { " (dry run)" } else { "" }
+            );
+            println!("  ────────────────\n");
+            println!(
+                "  Examined {} entities, threshold {:.2}, {} pinned skipped",
+                report.examined, policy.threshold, report.pinned_skipped
+            );
+            let verb = match (dry_run, report.action) {
+                (true, _) => "would forget",
+                (false, meepo_knowledge::RetentionAction::Archive) => "archived",
+                (false, meepo_knowledge::RetentionAction::Delete) => "deleted",
+            };
+            for m in &report.forgotten {
+                println!(
+                    "  {}  {} ({}) [{}]  importance {:.2}",
+                    verb, m.name, m.entity_type, m.entity_id, m.score
+                );
+            }
+            if report.forgotten.is_empty() {
+                println!("  Nothing to forget.");
+            }
+            if report.action_logs_deleted > 0 {
+                println!(
+                    "  Deleted {} old action log entries",
+                    report.action_logs_deleted
+                );
+            }
+            if report.conversations_deleted > 0 {
+                println!(
+                    "  Deleted {} old conversations",
+                    report.conversations_deleted
+                );
+            }
+            println!();
+            Ok(())
+        }
+        MemoryAction::Archived { limit } => {
+            let archived = graph.list_archived(limit).await?;
+            if archived.is_empty() {
+                println!("  No archived memories.");
+                return Ok(());
+            }
+            for m in &archived {
+                println!(
+                    "  {}  {} ({})  importance {:.2}, archived {}",
+                    m.entity_id,
+                    m.name,
+                    m.entity_type,
+                    m.importance,
+                    m.archived_at.format("%Y-%m-%d")
+                );
+            }
+            Ok(())
+        }
+        MemoryAction::Restore { id } => match graph.restore(&id).await? {
+            Some(entity) => {
+                println!("  Restored {} ({})", entity.name, entity.entity_type);
+                Ok(())
+            }
+            None => bail!("No archived memory with ID {}", id),
+        },
+        MemoryAction::Sync => {
+            let workspace = shellexpand(&cfg.memory.workspace);
+            let path = workspace.join(&cfg.agent.memory_file);
+            let report = graph
+                .sync_memory_file(&path, &memory_sync_config(&cfg.memory.sync)?)
+                .await?;
+            println!(
+                "  Synced {}: {} created, {} updated, {} archived, {} facts in file{}",
+                path.display(),
+                report.created.len(),
+                report.updated.len(),
+                report.archived.len(),
+                report.entries,
+                if report.file_written {
+                    ""
+                } else {
+                    " (unchanged)"
+                }
+            );
+            for c in &report.conflicts {
+                println!(
+                    "  conflict {}: file '{}' vs graph '{}', kept {:?}",
+                    c.entity_id, c.file_version, c.graph_version, c.resolution
+                );
+            }
+            Ok(())
+        }
+        MemoryAction::Communities { refresh } => {
+            if refresh {
+                let report = graph.refresh_communities(None).await?;
+                println!(
+                    "  Detected {} communities: {} new, {} updated, {} unchanged, {} removed",
+                    report.detected,
+                    report.created,
+                    report.updated,
+                    report.unchanged,
+                    report.removed
+                );
+            }
+            let communities = graph.list_communities().await?;
+            if communities.is_empty() {
+                println!("  No communities yet. Run `meepo memory communities --refresh`.");
+                return Ok(());
+            }
+            println!("\n  Knowledge communities");
+            println!("  ─────────────────────\n");
+            for c in &communities {
+                println!(
+                    "  {}  {} ({} members{})",
+                    c.id,
+                    c.title,
+                    c.members.len(),
+                    if c.summarized {
+                        ""
+                    } else {
+                        ", not yet summarized"
+                    }
+                );
+                if !c.summary.is_empty() {
+                    println!("      {}", c.summary);
+                }
+            }
+            println!();
+            Ok(())
+        }
+        MemoryAction::Corpus { action } => {
+            let pipeline = ingest_pipeline(&cfg.knowledge.corpus)?;
+            let print_report = |name: &str, report: &meepo_knowledge::CorpusSyncReport| {
+                println!("  Synced corpus {}: {}", name, report);
+                for (path, error) in &report.failed {
+                    println!("    failed {}: {}", path, error);
+                }
+            };
+            match action {
+                CorpusAction::Add {
+                    name,
+                    path,
+                    include,
+                    exclude,
+                } => {
+                    let corpus = graph
+                        .add_corpus(
+                            &name,
+                            &shellexpand(&path.to_string_lossy()),
+                            include,
+                            exclude,
+                        )
+                        .await?;
+                    println!(
+                        "  Registered corpus {} at {}",
+                        corpus.name,
+                        corpus.root.display()
+                    );
+                    let report = graph.sync_corpus(&corpus, &pipeline).await?;
+                    print_report(&corpus.name, &report);
+                }
+                CorpusAction::List => {
+                    let corpora = graph.list_corpora().await?;
+                    if corpora.is_empty() {
+                        println!(
+                            "  No corpora. Add one with `meepo memory corpus add <name> <dir>`."
+                        );
+                        return Ok(());
+                    }
+                    println!("\n  Document corpora");
+                    println!("  ────────────────\n");
+                    for corpus in &corpora {
+                        let files = graph.list_corpus_files(corpus).await?;
+                        println!(
+                            "  {}  {} ({} files, last synced {})",
+                            corpus.name,
+                            corpus.root.display(),
+                            files.len(),
+                            corpus
+                                .last_synced_at
+                                .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+                                .unwrap_or_else(|| "never".to_string())
+                        );
+                        println!(
+                            "      include {}{}",
+                            corpus.include.join(", "),
+                            if corpus.exclude.is_empty() {
+                                String::new()
+                            } else {
+                                format!("; exclude {}", corpus.exclude.join(", "))
+                            }
+                        );
+                    }
+                    println!();
+                }
+                CorpusAction::Sync { name } => {
+                    let corpora = match name {
+                        Some(name) => vec![
+                            graph
+                                .get_corpus(&name)
+                                .await?
+                                .with_context(|| format!("No corpus named {}", name))?,
+                        ],
+                        None => graph.list_corpora().await?,
+                    };
+                    for corpus in &corpora {
+                        let report = graph.sync_corpus(corpus, &pipeline).await?;
+                        print_report(&corpus.name, &report);
+                    }
+                }
+                CorpusAction::Remove {
+                    name,
+                    keep_documents,
+                } => {
+                    let corpus = graph
+                        .get_corpus(&name)
+                        .await?
+                        .with_context(|| format!("No corpus named {}", name))?;
+                    let removed = graph.remove_corpus(&corpus, keep_documents).await?;
+                    println!(
+                        "  Removed corpus {} ({} documents deleted)",
+                        corpus.name, removed
+                    );
+                }
+            }
+            Ok(())
+        }
+        MemoryAction::Review { action } => {
+            use meepo_knowledge::CandidateStatus;
+            match action {
+                ReviewAction::List { all } => {
+                    let status = (!all).then_some(CandidateStatus::Pending);
+                    let candidates = graph.list_memory_candidates(status, 500).await?;
+                    if candidates.is_empty() {
+                        println!("  No extracted facts awaiting review.");
+                        return Ok(());
+                    }
+                    println!("\n  Extracted facts");
+                    println!("  ───────────────\n");
+                    for c in &candidates {
+                        println!(
+                            "  {}  {}  ({:.2}{})",
+                            c.id,
+                            c.proposal.describe(),
+                            c.proposal.confidence(),
+                            if all {
+                                format!(", {}", c.status.as_str())
+                            } else {
+                                String::new()
+                            }
+                        );
+                        println!(
+                            "      from {} on {}",
+                            c.thread_id,
+                            c.created_at.format("%Y-%m-%d %H:%M")
+                        );
+                        if let Some(evidence) = c.proposal.evidence() {
+                            println!("      \"{}\"", evidence);
+                        }
+                    }
+                    println!(
+                        "\n  Commit with `meepo memory review accept <id>...` or discard with \
+                         `meepo memory review reject <id>...`.\n"
+                    );
+                }
+                ReviewAction::Accept { ids, all } => {
+                    let ids = if all {
+                        pending_candidate_ids(&graph).await?
+                    } else {
+                        ids
+                    };
+                    for id in ids {
+                        match graph.accept_memory_candidate(&id).await {
+                            Ok(fact_id) => println!("  Committed {} as {}", id, fact_id),
+                            Err(e) => println!("  Could not commit {}: {}", id, e),
+                        }
+                    }
+                }
+                ReviewAction::Reject { ids, all } => {
+                    let ids = if all {
+                        pending_candidate_ids(&graph).await?
+                    } else {
+                        ids
+                    };
+                    for id in ids {
+                        if graph.reject_memory_candidate(&id).await? {
+                            println!("  Rejected {}", id);
+                        } else {
+                            println!("  No pending fact with ID {}", id);
+                        }
+                    }
+                }
+            }
+            Ok(())
+        }
+        MemoryAction::Reindex { full } => {
+            if full {
+                graph.reindex().await?;
+                println!("  Rebuilt the search index");
+                return Ok(());
+            }
+            let report = graph.check_consistency(true).await?;
+            if report.is_consistent() {
+                println!("  Search index matches the knowledge database");
+            } else {
+                println!(
+                    "  Repaired search index: {} pending change(s), {} missing, {} orphaned",
+                    report.pending_changes,
+                    report.missing_from_index.len(),
+                    report.orphaned_in_index.len()
+                );
+            }
+            Ok(())
+        }
+        MemoryAction::Encrypt | MemoryAction::Decrypt | MemoryAction::Rekey { .. } => {
+            unreachable!("handled before opening the knowledge graph")
+        }
+    }
+}
+
+/// Resolve a secret by name through the configured secrets provider
+async fn resolve_secret(cfg: &MeepoConfig, name: &str) -> Result<String> {
+    use meepo_core::secrets::{SecretsConfig, SecretsManager, SecretsProviderType};
+
+    let provider = match cfg.secrets.provider.as_str() {
+        "file" => SecretsProviderType::File,
+        "memory" => SecretsProviderType::Memory,
+        _ => SecretsProviderType::Env,
+    };
+    let manager = SecretsManager::from_config(&SecretsConfig {
+        provider,
+        secrets_dir: cfg.secrets.secrets_dir.clone(),
+    });
+    manager
+        .resolve(name)
+        .await?
+        .filter(|value| !value.is_empty())
+        .with_context(|| format!("Secret '{}' is not set", name))
+}
+
+/// Key for the knowledge database, or None when encryption is disabled
+async fn knowledge_db_key(cfg: &MeepoConfig) -> Result<Option<String>> {
+    if !cfg.knowledge.encryption.enabled {
+        return Ok(None);
+    }
+    let key = resolve_secret(cfg, &cfg.knowledge.encryption.key_secret)
+        .await
+        .context("Knowledge database encryption is enabled but its key is unavailable")?;
+    Ok(Some(key))
+}
+
+/// Open the knowledge graph, encrypted when [knowledge.encryption] is enabled
+async fn open_knowledge_graph(
+    cfg: &MeepoConfig,
+    db_path: &std::path::Path,
+    tantivy_path: &std::path::Path,
+    key: Option<&str>,
+) -> Result<meepo_knowledge::KnowledgeGraph> {
+    let language = meepo_knowledge::parse_language(&cfg.knowledge.search_language)
+        .context("Invalid [knowledge] search_language")?;
+    let graph = match key {
+        Some(key) => {
+            meepo_knowledge::KnowledgeGraph::open_encrypted(db_path, key, language).await?
+        }
+        None => meepo_knowledge::KnowledgeGraph::open(db_path, tantivy_path, language)?,
+    };
+    Ok(graph
+        .with_resolution(resolution_config(&cfg.knowledge.resolution))
+        .with_importance(importance_config(&cfg.knowledge.importance))
+        .with_communities(community_config(&cfg.knowledge.communities)))
+}
+
+/// Open the knowledge database, encrypted when [knowledge.encryption] is enabled
+async fn open_knowledge_db(
+    cfg: &MeepoConfig,
+    db_path: &std::path::Path,
+) -> Result<meepo_knowledge::KnowledgeDb> {
+    match knowledge_db_key(cfg).await? {
+        Some(key) => meepo_knowledge::KnowledgeDb::new_encrypted(db_path, &key),
+        None => meepo_knowledge::KnowledgeDb::new(db_path),
+    }
+}
+
+/// Map the CLI's importance settings onto the knowledge crate's config
+fn importance_config(cfg: &config::ImportanceSettings) -> meepo_knowledge::ImportanceConfig {
+    meepo_knowledge::ImportanceConfig {
+        half_life_days: cfg.half_life_days,
+        recall_weight: cfg.recall_weight,
+        ..Default::default()
+    }
+}
+
+/// Build a retention policy from the CLI's retention settings
+fn retention_policy(cfg: &config::RetentionConfig) -> Result<meepo_knowledge::RetentionPolicy> {
+    Ok(meepo_knowledge::RetentionPolicy {
+        action: cfg.action.parse()?,
+        threshold: cfg.threshold,
+        min_age_days: cfg.min_age_days,
+        protected_types: cfg.protected_types.clone(),
+        action_log_retain_days: cfg.action_log_retain_days,
+        conversation_retain_days: cfg.conversation_retain_days,
+        ..Default::default()
+    })
+}
+
+/// Map the CLI's MEMORY.md sync settings onto the knowledge crate's config
+fn memory_sync_config(
+    cfg: &config::MemorySyncSettings,
+) -> Result<meepo_knowledge::MemorySyncConfig> {
+    Ok(meepo_knowledge::MemorySyncConfig {
+        importance_threshold: cfg.importance_threshold,
+        max_entries: cfg.max_entries,
+        conflict_policy: cfg
+            .conflict
+            .parse()
+            .context("Invalid [memory.sync] conflict")?,
+        ..Default::default()
+    })
+}
+
+/// Document ingestion pipeline with the configured chunk sizes
+fn ingest_pipeline(cfg: &config::CorpusConfig) -> Result<meepo_knowledge::IngestPipeline> {
+    use meepo_knowledge::ChunkUnit;
+
+    let unit = match cfg.chunk_unit.as_deref() {
+        Some(unit) => unit
+            .parse()
+            .context("Invalid [knowledge.corpus] chunk_unit")?,
+        // Sizes written before token sizing existed are in characters
+        None if cfg.chunk_size.is_some() || cfg.chunk_overlap.is_some() => {
+            warn!(
+                "[knowledge.corpus] chunk_size is read as characters; set chunk_unit = \"chars\" \
+                 to keep that, or chunk_unit = \"tokens\" to size chunks in tokens"
+            );
+            ChunkUnit::Chars
+        }
+        None => ChunkUnit::Tokens,
+    };
+    let defaults = match unit {
+        ChunkUnit::Tokens => meepo_knowledge::ChunkingConfig::tokens(256, 32),
+        ChunkUnit::Chars => meepo_knowledge::ChunkingConfig::default(),
+    };
+    Ok(
+        meepo_knowledge::IngestPipeline::default().with_chunking_config(
+            meepo_knowledge::ChunkingConfig {
+                chunk_size: cfg.chunk_size.unwrap_or(defaults.chunk_size),
+                chunk_overlap: cfg.chunk_overlap.unwrap_or(defaults.chunk_overlap),
+                ..defaults
+            },
+        ),
+    )
+}
+
+/// Keep every registered corpus in sync with its directory.
+///
+/// Runs a full sync at startup and every `interval_secs`, re-lists corpora
+/// every minute to pick up new registrations, and re-ingests just the
+/// changed paths after file events settle for `debounce_ms`.
+async fn corpus_sync_loop(
+    graph: Arc<meepo_knowledge::KnowledgeGraph>,
+    pipeline: meepo_knowledge::IngestPipeline,
+    cfg: config::CorpusConfig,
+    cancel: CancellationToken,
+) {
+    use notify::Watcher as _;
+    use std::collections::HashSet;
+    use std::time::{Duration, Instant};
+
+    let (path_tx, mut path_rx) = tokio::sync::mpsc::unbounded_channel::<PathBuf>();
+    let mut watcher = if cfg.watch {
+        notify::recommended_watcher(
+            move |res: std::result::Result<notify::Event, notify::Error>| {
+                // Our own reads show up as access events; only react to changes
+                if let Ok(event) = res
+                    && !matches!(event.kind, notify::EventKind::Access(_))
+                {
+                    for path in event.paths {
+                        let _ = path_tx.send(path);
+                    }
+                }
+            },
+        )
+        .map_err(|e| warn!("Failed to create corpus file watcher: {}", e))
+        .ok()
+    } else {
+        None
+    };
+    let mut watched: HashSet<PathBuf> = HashSet::new();
+    let debounce = Duration::from_millis(cfg.debounce_ms.max(100));
+    let mut next_full = Some(Instant::now());
+
+    loop {
+        let corpora = match graph.list_corpora().await {
+            Ok(corpora) => corpora,
+            Err(e) => {
+                warn!("Failed to list corpora: {}", e);
+                Vec::new()
+            }
+        };
+
+        if let Some(watcher) = watcher.as_mut() {
+            let roots: HashSet<PathBuf> = corpora.iter().map(|c| c.root.clone()).collect();
+            for root in watched.difference(&roots) {
+                let _ = watcher.unwatch(root);
+            }
+            watched.retain(|r| roots.contains(r));
+            for root in roots {
+                if !watched.contains(&root) {
+                    match watcher.watch(&root, notify::RecursiveMode::Recursive) {
+                        Ok(()) => {
+                            info!("Watching corpus directory {}", root.display());
+                            watched.insert(root);
+                        }
+                        Err(e) => warn!("Failed to watch {}: {}", root.display(), e),
+                    }
+                }
+            }
+        }
+
+        if next_full.is_some_and(|at| Instant::now() >= at) {
+            for corpus in &corpora {
+                if let Err(e) = graph.sync_corpus(corpus, &pipeline).await {
+                    warn!("Corpus {} sync failed: {}", corpus.name, e);
+                }
+            }
+            next_full = (cfg.interval_secs > 0)
+                .then(|| Instant::now() + Duration::from_secs(cfg.interval_secs.max(60)));
+        }
+
+        tokio::select! {
+            _ = cancel.cancelled() => {
+                info!("Corpus sync shutting down");
+                break;
+            }
+            Some(path) = path_rx.recv() => {
+                // Wait for the burst of events to settle before re-ingesting
+                let mut paths = HashSet::from([path]);
+                let deadline = Instant::now() + debounce * 10;
+                while Instant::now() < deadline {
+                    match tokio::time::timeout(debounce, path_rx.recv()).await {
+                        Ok(Some(path)) => {
+                            paths.insert(path);
+                        }
+                        _ => break,
+                    }
+                }
+                for corpus in &corpora {
+                    let changed: Vec<PathBuf> = paths
+                        .iter()
+                        .filter(|p| p.starts_with(&corpus.root))
+                        .cloned()
+                        .collect();
+                    if changed.is_empty() {
+                        continue;
+                    }
+                    if let Err(e) = graph.sync_corpus_paths(corpus, &changed, &pipeline).await {
+                        warn!("Corpus {} sync failed: {}", corpus.name, e);
+                    }
+                }
+            }
+            _ = tokio::time::sleep(Duration::from_secs(60)) => {}
+        }
+    }
+}
+
+/// IDs of all extracted facts awaiting review
+async fn pending_candidate_ids(graph: &meepo_knowledge::KnowledgeGraph) -> Result<Vec<String>> {
+    Ok(graph
+        .list_memory_candidates(Some(meepo_knowledge::CandidateStatus::Pending), 10_000)
+        .await?
+        .into_iter()
+        .map(|c| c.id)
+        .collect())
+}
+
+/// Map the CLI's memory extraction settings onto the knowledge crate's config
+fn extraction_config(cfg: &config::MemoryExtractionConfig) -> meepo_knowledge::ExtractionConfig {
+    meepo_knowledge::ExtractionConfig {
+        enabled: cfg.enabled,
+        idle_minutes: cfg.idle_minutes,
+        min_messages: cfg.min_messages,
+        max_messages: cfg.max_messages,
+        min_confidence: cfg.min_confidence,
+        require_review: cfg.require_review,
+        max_threads_per_pass: cfg.max_threads_per_pass,
+    }
+}
+
+/// Map the CLI's community settings onto the knowledge crate's config
+fn community_config(cfg: &config::CommunitiesConfig) -> meepo_knowledge::CommunityConfig {
+    meepo_knowledge::CommunityConfig {
+        enabled: cfg.enabled,
+        min_size: cfg.min_size,
+        max_communities: cfg.max_communities,
+        resolution: cfg.resolution,
+        exclude_types: cfg.exclude_types.clone(),
+        ..Default::default()
+    }
+}
+
+/// Map the CLI's entity resolution settings onto the knowledge crate's config
+fn resolution_config(cfg: &config::EntityResolutionConfig) -> meepo_knowledge::ResolutionConfig {
+    meepo_knowledge::ResolutionConfig {
+        enabled: cfg.enabled,
+        resolve_on_insert: cfg.resolve_on_insert,
+        candidate_threshold: cfg.candidate_threshold,
+        exclude_types: cfg.exclude_types.clone(),
+        max_pairs_per_pass: cfg.max_pairs_per_pass,
+        ..Default::default()
+    }
+}
+
 /// Recursively copy a directory
 fn copy_dir_recursive(src: &std::path::Path, dst: &std::path::Path) -> Result<()> {
     std::fs::create_dir_all(dst)?;
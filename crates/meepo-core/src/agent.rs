@@ -1,7 +1,7 @@
 //! Main agent loop - the brain of meepo
 
 use anyhow::{Context, Result};
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 use tracing::{debug, info};
 
 use crate::api::ApiClient;
@@ -16,7 +16,7 @@ use crate::tools::{GuardedToolExecutor, ToolExecutor, ToolRegistry};
 use crate::types::{IncomingMessage, MessageKind, OutgoingMessage};
 use crate::usage::{UsageSource, UsageTracker};
 
-use meepo_knowledge::KnowledgeDb;
+use meepo_knowledge::{KnowledgeDb, conversation_thread_id};
 
 /// Maximum context size in bytes to prevent multi-MB context strings.
 const MAX_CONTEXT_SIZE: usize = 100_000;
@@ -26,7 +26,8 @@ pub struct Agent {
     api: ApiClient,
     tools: Arc<ToolRegistry>,
     soul: String,
-    memory: String,
+    /// MEMORY.md contents; replaced at runtime when the file is re-synced
+    memory: RwLock<String>,
     db: Arc<KnowledgeDb>,
     /// Middleware chain for pre/post processing
     middleware: MiddlewareChain,
@@ -57,7 +58,7 @@ impl Agent {
             api,
             tools,
             soul,
-            memory,
+            memory: RwLock::new(memory),
             db,
             middleware: MiddlewareChain::new(),
             router_config: QueryRouterConfig::default(),
@@ -148,9 +149,15 @@ impl Agent {
             }
         }
 
-        // Store the incoming message in conversation history
+        // Store the incoming message in its conversation thread
         self.db
-            .insert_conversation(&msg.channel.to_string(), &msg.sender, &msg.content, None)
+            .insert_conversation_in_thread(
+                &msg.channel.to_string(),
+                msg.thread_id.as_deref(),
+                &msg.sender,
+                &msg.content,
+                None,
+            )
             .await
             .context("Failed to store conversation")?;
 
@@ -245,7 +252,7 @@ impl Agent {
         let context = self.load_context(&msg, &strategy, &intent).await?;
 
         // Build system prompt
-        let system_prompt = build_system_prompt(&self.soul, &self.memory, &context);
+        let system_prompt = build_system_prompt(&self.soul, &self.memory(), &context);
 
         // Get tool definitions (with optional LLM selection + usage tracking)
         let all_tools = self.tools.list_tools();
@@ -372,9 +379,15 @@ impl Agent {
             debug!("Failed to record usage: {}", e);
         }
 
-        // Store the response in conversation history
+        // Store the response in the same thread
         self.db
-            .insert_conversation(&msg.channel.to_string(), "meepo", &response_text, None)
+            .insert_conversation_in_thread(
+                &msg.channel.to_string(),
+                msg.thread_id.as_deref(),
+                "meepo",
+                &response_text,
+                None,
+            )
             .await
             .context("Failed to store response")?;
 
@@ -406,11 +419,13 @@ impl Agent {
         let mut context = String::new();
         let mut truncated = false;
 
-        // Add recent conversation history from this channel (with summarization)
+        // Add recent history from the active thread (with summarization)
         if strategy.load_history {
+            let thread_id =
+                conversation_thread_id(&msg.channel.to_string(), msg.thread_id.as_deref());
             let recent = self
                 .db
-                .get_recent_conversations(Some(&msg.channel.to_string()), 30)
+                .get_thread_conversations(&thread_id, 30)
                 .await
                 .context("Failed to load recent conversations")?;
 
@@ -520,10 +535,23 @@ impl Agent {
         Ok(context)
     }
 
-    /// Update the agent's memory
-    pub fn update_memory(&mut self, new_memory: String) {
-        self.memory = new_memory;
-        info!("Updated agent memory ({} chars)", self.memory.len());
+    /// Current memory contents
+    pub fn memory(&self) -> String {
+        self.memory
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+
+    /// Update the agent's memory. Takes `&self` so a shared agent picks up
+    /// MEMORY.md changes between messages.
+    pub fn update_memory(&self, new_memory: String) {
+        let len = new_memory.len();
+        *self
+            .memory
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = new_memory;
+        info!("Updated agent memory ({} chars)", len);
     }
 
     /// Update the agent's soul
@@ -574,14 +602,14 @@ mod tests {
     fn test_agent_creation() {
         let (agent, _temp) = create_test_agent();
         assert_eq!(agent.soul, "I am a test agent");
-        assert_eq!(agent.memory, "Test memory");
+        assert_eq!(agent.memory(), "Test memory");
     }
 
     #[test]
     fn test_update_memory() {
-        let (mut agent, _temp) = create_test_agent();
+        let (agent, _temp) = create_test_agent();
         agent.update_memory("New memory".to_string());
-        assert_eq!(agent.memory, "New memory");
+        assert_eq!(agent.memory(), "New memory");
     }
 
     #[tokio::test]
@@ -594,6 +622,7 @@ mod tests {
             content: "Hello meepo".to_string(),
             channel: ChannelType::Internal,
             timestamp: Utc::now(),
+            thread_id: None,
         };
 
         let strategy = RetrievalStrategy {
@@ -692,6 +721,7 @@ mod tests {
             content: "Hello".to_string(),
             channel: ChannelType::Internal,
             timestamp: Utc::now(),
+            thread_id: None,
         };
 
         let strategy = RetrievalStrategy {
@@ -727,6 +757,7 @@ mod tests {
             content: "Tell me about Rust Language please".to_string(),
             channel: ChannelType::Internal,
             timestamp: Utc::now(),
+            thread_id: None,
         };
 
         let strategy = RetrievalStrategy {
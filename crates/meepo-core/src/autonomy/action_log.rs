@@ -46,6 +46,8 @@ pub fn classify_tool(tool_name: &str) -> ActionRisk {
         | "recall"
         | "search_knowledge"
         | "smart_recall"
+        | "query_graph"
+        | "search_conversations"
         | "browse_url"
         | "web_search"
         | "get_clipboard"
@@ -83,6 +85,7 @@ pub fn classify_tool(tool_name: &str) -> ActionRisk {
         | "remember"
         | "link_entities"
         | "ingest_document"
+        | "manage_corpus"
         | "create_watcher"
         | "cancel_watcher"
         | "create_task"
@@ -244,6 +247,8 @@ mod tests {
             "list_directory",
             "search_files",
             "smart_recall",
+            "query_graph",
+            "search_conversations",
             "browse_url",
             "get_clipboard",
             "read_emails",
@@ -290,6 +295,7 @@ mod tests {
             "remember",
             "link_entities",
             "ingest_document",
+            "manage_corpus",
             "create_watcher",
             "cancel_watcher",
             "create_task",
@@ -20,7 +20,7 @@ use crate::agent::Agent;
 use crate::notifications::{NotificationService, NotifyEvent};
 use crate::types::{ChannelType, IncomingMessage, MessageKind, OutgoingMessage};
 use meepo_knowledge::KnowledgeDb;
-use meepo_scheduler::WatcherEvent;
+use meepo_scheduler::{WatcherEvent, WatcherStore};
 
 use self::action_log::ActionLogger;
 use self::goals::GoalEvaluator;
@@ -102,6 +102,9 @@ pub struct AutonomousLoop {
     db: Arc<KnowledgeDb>,
     config: AutonomyConfig,
 
+    /// Watchers whose events arrive on `watcher_rx`
+    watchers: WatcherStore,
+
     /// Evaluates due goals and decides on actions
     goal_evaluator: GoalEvaluator,
 
@@ -141,6 +144,7 @@ impl AutonomousLoop {
     pub fn new(
         agent: Arc<Agent>,
         db: Arc<KnowledgeDb>,
+        watchers: WatcherStore,
         config: AutonomyConfig,
         message_rx: mpsc::Receiver<IncomingMessage>,
         watcher_rx: mpsc::UnboundedReceiver<WatcherEvent>,
@@ -156,6 +160,7 @@ impl AutonomousLoop {
         Self {
             agent,
             db,
+            watchers,
             config,
             goal_evaluator,
             action_logger,
@@ -347,6 +352,7 @@ impl AutonomousLoop {
             content: prompt,
             channel: ChannelType::Internal,
             timestamp: now,
+            thread_id: None,
         };
 
         match self.agent.handle_message(msg).await {
@@ -426,6 +432,7 @@ impl AutonomousLoop {
             content: prompt,
             channel: ChannelType::Internal,
             timestamp: chrono::Utc::now(),
+            thread_id: None,
         };
 
         match self.agent.handle_message(msg).await {
@@ -494,6 +501,7 @@ impl AutonomousLoop {
                                     content: action_prompt.clone(),
                                     channel: ChannelType::Internal,
                                     timestamp: chrono::Utc::now(),
+                                    thread_id: None,
                                 };
 
                                 match self.agent.handle_message(action_msg).await {
@@ -593,6 +601,23 @@ impl AutonomousLoop {
             event.kind, event.watcher_id
         );
 
+        // The scheduler gave up on a failing watcher (and already deactivated
+        // it): tell the user, not the agent
+        if event.kind == "watcher_disabled" {
+            let field = |name: &str| event.payload[name].as_str().unwrap_or_default().to_string();
+            self.notifier
+                .notify(NotifyEvent::WatcherDisabled {
+                    watcher_id: event.watcher_id.clone(),
+                    description: field("description"),
+                    error: field("error"),
+                    consecutive_errors: event.payload["consecutive_errors"]
+                        .as_u64()
+                        .unwrap_or_default() as u32,
+                })
+                .await;
+            return;
+        }
+
         // Notify user that a watcher triggered
         self.notifier
             .notify(NotifyEvent::WatcherTriggered {
@@ -603,7 +628,7 @@ impl AutonomousLoop {
             .await;
 
         // Look up the watcher to get reply_channel and action
-        let (reply_channel, action) = match self.db.get_watcher(&event.watcher_id).await {
+        let (reply_channel, action) = match self.watchers.get(&event.watcher_id).await {
             Ok(Some(w)) => (ChannelType::from_string(&w.reply_channel), w.action),
             Ok(None) => {
                 error!("Watcher {} not found in database", event.watcher_id);
@@ -631,6 +656,7 @@ impl AutonomousLoop {
             content,
             channel: reply_channel.clone(),
             timestamp: chrono::Utc::now(),
+            thread_id: None,
         };
 
         match self.agent.handle_message(msg).await {
@@ -680,6 +706,12 @@ mod tests {
         (agent, db, temp_dir)
     }
 
+    fn watcher_store() -> WatcherStore {
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        meepo_scheduler::persistence::init_watcher_tables(&conn).unwrap();
+        WatcherStore::new(Arc::new(std::sync::Mutex::new(conn)))
+    }
+
     #[tokio::test]
     async fn test_drain_inputs_empty() {
         let (agent, db, _tmp) = setup();
@@ -692,6 +724,7 @@ mod tests {
         let mut loop_ = AutonomousLoop::new(
             agent,
             db,
+            watcher_store(),
             AutonomyConfig {
                 enabled: true,
                 tick_interval_secs: 30,
@@ -728,6 +761,7 @@ mod tests {
                 content: "hello".into(),
                 channel: ChannelType::Discord,
                 timestamp: chrono::Utc::now(),
+                thread_id: None,
             })
             .await
             .unwrap();
@@ -735,6 +769,7 @@ mod tests {
         let mut loop_ = AutonomousLoop::new(
             agent,
             db,
+            watcher_store(),
             AutonomyConfig {
                 enabled: true,
                 tick_interval_secs: 30,
@@ -781,4 +816,53 @@ mod tests {
         limiter.try_acquire();
         assert_eq!(limiter.remaining(), 2);
     }
+
+    #[tokio::test]
+    async fn test_disabled_watcher_notifies_user() {
+        let (agent, db, _tmp) = setup();
+        let (_, msg_rx) = mpsc::channel(16);
+        let (_, watcher_rx) = mpsc::unbounded_channel();
+        let (resp_tx, mut resp_rx) = mpsc::channel(16);
+        let notifier = NotificationService::new(
+            crate::notifications::NotifyConfig {
+                enabled: true,
+                ..Default::default()
+            },
+            resp_tx.clone(),
+        );
+
+        let loop_ = AutonomousLoop::new(
+            agent,
+            db,
+            watcher_store(),
+            AutonomyConfig {
+                enabled: true,
+                tick_interval_secs: 30,
+                max_goals: 50,
+                send_acknowledgments: true,
+                daily_plan_hour: 7,
+                max_calls_per_minute: 10,
+            },
+            msg_rx,
+            watcher_rx,
+            resp_tx,
+            notifier,
+            AutonomousLoop::create_wake_handle(),
+        );
+
+        loop_
+            .handle_watcher_event(WatcherEvent::disabled(
+                "w-1".to_string(),
+                "GitHub watcher for a/b".to_string(),
+                "HTTP 401 Bad credentials".to_string(),
+                10,
+            ))
+            .await;
+
+        // Only the notification is sent; the agent is not asked to act
+        let msg = resp_rx.try_recv().unwrap();
+        assert!(msg.content.contains("Watcher disabled after 10 failures"));
+        assert!(msg.content.contains("HTTP 401 Bad credentials"));
+        assert!(resp_rx.try_recv().is_err());
+    }
 }
@@ -0,0 +1,230 @@
+//! LLM summaries for knowledge graph communities
+//!
+//! The knowledge crate detects communities of closely related entities;
+//! [`LlmCommunitySummarizer`] asks the model for a short title and a summary
+//! of what each community is about, which global `smart_recall` then maps over.
+
+use anyhow::{Context, Result, bail};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::debug;
+
+use meepo_knowledge::{CommunityContext, CommunitySummarizer, CommunitySummary};
+
+use crate::api::{ApiClient, ApiMessage, ContentBlock, MessageContent};
+
+/// Longest metadata preview per member in the prompt
+const MEMBER_PREVIEW_CHARS: usize = 200;
+
+/// Summarizes communities by asking the LLM
+pub struct LlmCommunitySummarizer {
+    api: Arc<ApiClient>,
+}
+
+impl LlmCommunitySummarizer {
+    pub fn new(api: Arc<ApiClient>) -> Self {
+        Self { api }
+    }
+}
+
+#[async_trait]
+impl CommunitySummarizer for LlmCommunitySummarizer {
+    async fn summarize(&self, community: &CommunityContext) -> Result<CommunitySummary> {
+        let messages = vec![ApiMessage {
+            role: "user".to_string(),
+            content: MessageContent::Text(build_prompt(community)),
+        }];
+
+        let response = self
+            .api
+            .chat(
+                &messages,
+                &[],
+                "You summarize clusters of a personal knowledge graph. Describe the shared \
+                 theme, project or area of life that connects the entities. Be factual and \
+                 only use what the records say.",
+            )
+            .await
+            .context("Failed to summarize community")?;
+
+        let text = response
+            .content
+            .iter()
+            .filter_map(|b| {
+                if let ContentBlock::Text { text } = b {
+                    Some(text.as_str())
+                } else {
+                    None
+                }
+            })
+            .collect::<String>();
+
+        let summary = parse_summary(&text)?;
+        debug!(
+            "Summarized community of {} as '{}'",
+            community.size, summary.title
+        );
+        Ok(summary)
+    }
+}
+
+fn build_prompt(community: &CommunityContext) -> String {
+    let names: HashMap<&str, &str> = community
+        .members
+        .iter()
+        .map(|e| (e.id.as_str(), e.name.as_str()))
+        .collect();
+
+    let mut prompt = format!(
+        "These {} entities form a closely connected group in the knowledge graph{}.\n\
+         Respond in exactly this format:\n\
+         TITLE: <a short name for the group, at most 8 words>\n\
+         SUMMARY: <2-4 sentences on what connects them>\n\n\
+         Entities:\n",
+        community.size,
+        if community.members.len() < community.size {
+            format!(
+                " (the {} most connected are listed)",
+                community.members.len()
+            )
+        } else {
+            String::new()
+        }
+    );
+    for entity in &community.members {
+        let metadata = entity
+            .metadata
+            .as_ref()
+            .map(|m| m.to_string())
+            .unwrap_or_default();
+        let preview: String = metadata.chars().take(MEMBER_PREVIEW_CHARS).collect();
+        prompt.push_str(&format!("- {} ({})", entity.name, entity.entity_type));
+        if !preview.is_empty() && preview != "{}" {
+            prompt.push_str(&format!(": {}", preview));
+        }
+        prompt.push('\n');
+    }
+
+    if !community.relationships.is_empty() {
+        prompt.push_str("\nRelationships:\n");
+        for r in &community.relationships {
+            let name = |id: &str| names.get(id).copied().unwrap_or("?").to_string();
+            prompt.push_str(&format!(
+                "- {} {} {}\n",
+                name(&r.source_id),
+                r.relation_type,
+                name(&r.target_id)
+            ));
+        }
+    }
+    prompt
+}
+
+/// Parse a `TITLE: ... SUMMARY: ...` response
+fn parse_summary(text: &str) -> Result<CommunitySummary> {
+    let mut title = None;
+    let mut summary: Option<String> = None;
+    for line in text.lines() {
+        let line = line.trim();
+        if let Some(rest) = strip_label(line, "title") {
+            title = Some(
+                rest.trim_matches(|c| c == '"' || c == '*')
+                    .trim()
+                    .to_string(),
+            );
+        } else if let Some(rest) = strip_label(line, "summary") {
+            summary = Some(rest.to_string());
+        } else if let Some(s) = summary.as_mut()
+            && !line.is_empty()
+        {
+            s.push(' ');
+            s.push_str(line);
+        }
+    }
+
+    match (title, summary) {
+        (Some(title), Some(summary)) if !title.is_empty() && !summary.trim().is_empty() => {
+            Ok(CommunitySummary {
+                title,
+                summary: summary.trim().to_string(),
+            })
+        }
+        _ => bail!("Community summary response was not in TITLE/SUMMARY format"),
+    }
+}
+
+/// Strip a case-insensitive `label:` prefix (optionally in bold)
+fn strip_label<'a>(line: &'a str, label: &str) -> Option<&'a str> {
+    let line = line.trim_start_matches('*');
+    let head = line.get(..label.len())?;
+    if !head.eq_ignore_ascii_case(label) {
+        return None;
+    }
+    let rest = line[label.len()..].trim_start_matches('*');
+    rest.strip_prefix(':')
+        .map(|r| r.trim_start_matches('*').trim())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use meepo_knowledge::{Entity, Relationship};
+
+    fn entity(id: &str, name: &str) -> Entity {
+        Entity {
+            id: id.to_string(),
+            name: name.to_string(),
+            entity_type: "project".to_string(),
+            metadata: Some(serde_json::json!({"status": "active"})),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_parse_summary() {
+        let parsed = parse_summary(
+            "TITLE: Billing migration\nSUMMARY: Alice and Bob move billing to Stripe.\n\
+             Carol reviews the rollout.",
+        )
+        .unwrap();
+        assert_eq!(parsed.title, "Billing migration");
+        assert_eq!(
+            parsed.summary,
+            "Alice and Bob move billing to Stripe. Carol reviews the rollout."
+        );
+
+        let bold = parse_summary("**Title:** \"Garden\"\n**Summary:** Vegetables.").unwrap();
+        assert_eq!(bold.title, "Garden");
+        assert_eq!(bold.summary, "Vegetables.");
+
+        assert!(parse_summary("Here is a summary of the group.").is_err());
+        assert!(parse_summary("TITLE: Only a title").is_err());
+    }
+
+    #[test]
+    fn test_build_prompt_lists_members_and_relationships() {
+        let community = CommunityContext {
+            members: vec![entity("a", "Billing"), entity("b", "Stripe")],
+            relationships: vec![Relationship {
+                id: "r".to_string(),
+                source_id: "a".to_string(),
+                target_id: "b".to_string(),
+                relation_type: "uses".to_string(),
+                metadata: None,
+                created_at: Utc::now(),
+                valid_from: None,
+                valid_to: None,
+                superseded_by: None,
+            }],
+            size: 5,
+        };
+        let prompt = build_prompt(&community);
+        assert!(prompt.contains("These 5 entities"));
+        assert!(prompt.contains("the 2 most connected are listed"));
+        assert!(prompt.contains("- Billing (project): {\"status\":\"active\"}"));
+        assert!(prompt.contains("- Billing uses Stripe"));
+    }
+}
@@ -60,10 +60,12 @@ impl DoctorReport {
     }
 }
 
-/// Run all doctor checks
+/// Run all doctor checks. When a knowledge graph is given, drift between
+/// its database and search index is also checked and repaired.
 pub async fn run_doctor(
     config_path: Option<&std::path::Path>,
     db_path: Option<&std::path::Path>,
+    graph: Option<&meepo_knowledge::KnowledgeGraph>,
 ) -> Result<DoctorReport> {
     info!("Running doctor checks...");
     let mut checks = Vec::new();
@@ -74,6 +76,12 @@ pub async fn run_doctor(
     // 2. Database directory writable
     checks.push(check_db_path(db_path));
 
+    // 2b. Knowledge database encrypted at rest
+    checks.push(check_db_encryption(db_path));
+
+    // 2c. Search index consistent with the knowledge database
+    checks.push(check_index_consistency(graph).await);
+
     // 3. Docker available (for sandbox)
     checks.push(check_docker().await);
 
@@ -192,6 +200,78 @@ fn check_db_path(path: Option<&std::path::Path>) -> CheckResult {
     }
 }
 
+fn check_db_encryption(path: Option<&std::path::Path>) -> CheckResult {
+    let Some(p) = path.filter(|p| p.exists()) else {
+        return CheckResult {
+            name: "database_encryption".to_string(),
+            status: CheckStatus::Skip,
+            message: "No knowledge database yet".to_string(),
+            fix_hint: None,
+        };
+    };
+    match meepo_knowledge::encryption::is_encrypted(p) {
+        Ok(true) => CheckResult {
+            name: "database_encryption".to_string(),
+            status: CheckStatus::Pass,
+            message: "Knowledge database is encrypted".to_string(),
+            fix_hint: None,
+        },
+        Ok(false) => CheckResult {
+            name: "database_encryption".to_string(),
+            status: CheckStatus::Warn,
+            message: "Knowledge database is stored in plaintext".to_string(),
+            fix_hint: Some(
+                "Set a key secret under [knowledge.encryption] and run `meepo memory encrypt`"
+                    .to_string(),
+            ),
+        },
+        Err(e) => CheckResult {
+            name: "database_encryption".to_string(),
+            status: CheckStatus::Fail,
+            message: format!("Cannot read knowledge database: {}", e),
+            fix_hint: None,
+        },
+    }
+}
+
+async fn check_index_consistency(graph: Option<&meepo_knowledge::KnowledgeGraph>) -> CheckResult {
+    let Some(graph) = graph else {
+        return CheckResult {
+            name: "index_consistency".to_string(),
+            status: CheckStatus::Skip,
+            message: "Knowledge graph not available".to_string(),
+            fix_hint: None,
+        };
+    };
+    match graph.check_consistency(true).await {
+        Ok(report) if report.is_consistent() => CheckResult {
+            name: "index_consistency".to_string(),
+            status: CheckStatus::Pass,
+            message: "Search index matches the knowledge database".to_string(),
+            fix_hint: None,
+        },
+        Ok(report) => CheckResult {
+            name: "index_consistency".to_string(),
+            status: CheckStatus::Warn,
+            message: format!(
+                "Search index drifted ({} pending change(s), {} missing, {} orphaned){}",
+                report.pending_changes,
+                report.missing_from_index.len(),
+                report.orphaned_in_index.len(),
+                if report.repaired { "; repaired" } else { "" }
+            ),
+            fix_hint: (!report.repaired)
+                .then(|| "Run `meepo memory reindex` to rebuild the search index".to_string()),
+        },
+        Err(e) => CheckResult {
+            name: "index_consistency".to_string(),
+            status: CheckStatus::Fail,
+            message: format!("Cannot check search index: {}", e),
+            fix_hint: Some("Run `meepo memory reindex` to rebuild the search index".to_string()),
+        },
+    }
+}
+
 async fn check_docker() -> CheckResult {
     let result = tokio::process::Command::new("docker")
         .arg("info")
@@ -406,6 +486,49 @@ mod tests {
         assert_eq!(result.status, CheckStatus::Skip);
     }
 
+    #[test]
+    fn test_check_db_encryption() {
+        assert_eq!(check_db_encryption(None).status, CheckStatus::Skip);
+
+        let temp = tempfile::TempDir::new().unwrap();
+        let path = temp.path().join("k.db");
+        meepo_knowledge::KnowledgeDb::new(&path).unwrap();
+        let result = check_db_encryption(Some(&path));
+        assert_eq!(result.status, CheckStatus::Warn);
+        assert!(result.fix_hint.unwrap().contains("meepo memory encrypt"));
+    }
+
+    #[tokio::test]
+    async fn test_check_index_consistency() {
+        assert_eq!(
+            check_index_consistency(None).await.status,
+            CheckStatus::Skip
+        );
+
+        let temp = tempfile::TempDir::new().unwrap();
+        let graph =
+            meepo_knowledge::KnowledgeGraph::new(temp.path().join("k.db"), temp.path().join("idx"))
+                .unwrap();
+        assert_eq!(
+            check_index_consistency(Some(&graph)).await.status,
+            CheckStatus::Pass
+        );
+
+        // A write that bypassed the index is reported and repaired
+        graph
+            .db()
+            .insert_entity("Harbour", "place", None)
+            .await
+            .unwrap();
+        let result = check_index_consistency(Some(&graph)).await;
+        assert_eq!(result.status, CheckStatus::Warn);
+        assert!(result.message.contains("repaired"));
+        assert_eq!(
+            check_index_consistency(Some(&graph)).await.status,
+            CheckStatus::Pass
+        );
+    }
+
     #[test]
     fn test_check_home_dir() {
         let result = check_home_dir();
@@ -463,7 +586,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_run_doctor() {
-        let report = run_doctor(None, None).await.unwrap();
+        let report = run_doctor(None, None, None).await.unwrap();
         // Should complete without error
         assert!(!report.checks.is_empty());
     }
@@ -0,0 +1,137 @@
+//! LLM confirmation for ambiguous entity merges
+//!
+//! The knowledge crate only auto-merges exact name/alias matches. Fuzzier
+//! candidates ("Bob" vs "Bob Jones") are handed to [`LlmMergeConfirmer`],
+//! which asks the model whether both records describe the same thing.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use std::sync::Arc;
+use tracing::debug;
+
+use meepo_knowledge::MergeConfirmer;
+use meepo_knowledge::sqlite::Entity;
+
+use crate::api::{ApiClient, ApiMessage, ContentBlock, MessageContent};
+
+/// Confirms entity merges by asking the LLM
+pub struct LlmMergeConfirmer {
+    api: Arc<ApiClient>,
+}
+
+impl LlmMergeConfirmer {
+    pub fn new(api: Arc<ApiClient>) -> Self {
+        Self { api }
+    }
+}
+
+#[async_trait]
+impl MergeConfirmer for LlmMergeConfirmer {
+    async fn confirm(&self, a: &Entity, b: &Entity) -> Result<bool> {
+        let prompt = build_prompt(a, b);
+        let messages = vec![ApiMessage {
+            role: "user".to_string(),
+            content: MessageContent::Text(prompt),
+        }];
+
+        let response = self
+            .api
+            .chat(
+                &messages,
+                &[],
+                "You deduplicate a personal knowledge graph. Answer YES only if both \
+                 records clearly refer to the same real-world entity. When unsure, answer NO.",
+            )
+            .await
+            .context("Failed to confirm entity merge")?;
+
+        let text = response
+            .content
+            .iter()
+            .filter_map(|b| {
+                if let ContentBlock::Text { text } = b {
+                    Some(text.as_str())
+                } else {
+                    None
+                }
+            })
+            .collect::<String>();
+
+        let verdict = parse_verdict(&text);
+        debug!(
+            "Merge confirmation for '{}' / '{}': {}",
+            a.name, b.name, verdict
+        );
+        Ok(verdict)
+    }
+}
+
+fn describe(entity: &Entity) -> String {
+    let metadata = entity
+        .metadata
+        .as_ref()
+        .map(|m| m.to_string())
+        .unwrap_or_else(|| "{}".to_string());
+    let preview: String = metadata.chars().take(500).collect();
+    format!(
+        "name: {}\ntype: {}\nmetadata: {}",
+        entity.name, entity.entity_type, preview
+    )
+}
+
+fn build_prompt(a: &Entity, b: &Entity) -> String {
+    format!(
+        "Do these two records refer to the same entity?\n\
+         Respond with exactly one word: YES or NO.\n\n\
+         Record A:\n{}\n\n\
+         Record B:\n{}",
+        describe(a),
+        describe(b)
+    )
+}
+
+/// Parse a YES/NO answer, defaulting to NO for anything unclear
+fn parse_verdict(text: &str) -> bool {
+    text.split_whitespace()
+        .next()
+        .map(|w| {
+            w.trim_matches(|c: char| !c.is_alphanumeric())
+                .eq_ignore_ascii_case("yes")
+        })
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn entity(name: &str) -> Entity {
+        Entity {
+            id: name.to_lowercase(),
+            name: name.to_string(),
+            entity_type: "person".to_string(),
+            metadata: Some(serde_json::json!({"role": "engineer"})),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_parse_verdict() {
+        assert!(parse_verdict("YES"));
+        assert!(parse_verdict("yes."));
+        assert!(parse_verdict("  Yes, they match"));
+        assert!(!parse_verdict("NO"));
+        assert!(!parse_verdict("Not sure, yes maybe"));
+        assert!(!parse_verdict(""));
+    }
+
+    #[test]
+    fn test_build_prompt_includes_both_records() {
+        let prompt = build_prompt(&entity("Bob"), &entity("Bob Jones"));
+        assert!(prompt.contains("name: Bob\n"));
+        assert!(prompt.contains("name: Bob Jones"));
+        assert!(prompt.contains("engineer"));
+    }
+}
@@ -6,17 +6,21 @@
 //! - Comprehensive tool system with macOS integration, code execution, memory, and more
 //! - Context loading from SOUL and MEMORY files
 //! - Integration with knowledge graph and watcher scheduler
+//! - Watcher-triggered workflows that call tools directly
 
 pub mod agent;
 pub mod agents;
 pub mod api;
 pub mod audio;
 pub mod autonomy;
+pub mod community_summaries;
 pub mod context;
 pub mod corrective_rag;
 pub mod doctor;
+pub mod entity_resolution;
 pub mod guardrails;
 pub mod intent;
+pub mod memory_extraction;
 pub mod middleware;
 pub mod notifications;
 pub mod orchestrator;
@@ -33,14 +37,18 @@ pub mod tool_selector;
 pub mod tools;
 pub mod types;
 pub mod usage;
+pub mod workflows;
 
 // Re-export main types for convenience
 pub use agent::Agent;
 pub use api::{ApiClient, ApiMessage, ApiResponse, ContentBlock, MessageContent, ToolDefinition};
 pub use autonomy::{AutonomousLoop, AutonomyConfig};
+pub use community_summaries::LlmCommunitySummarizer;
 pub use context::build_system_prompt;
 pub use corrective_rag::CorrectiveRagConfig;
+pub use entity_resolution::LlmMergeConfirmer;
 pub use intent::{IntentConfig, UserIntent};
+pub use memory_extraction::LlmMemoryExtractor;
 pub use middleware::{AgentMiddleware, MiddlewareChain, MiddlewareContext};
 pub use notifications::{NotificationService, NotifyConfig, NotifyEvent};
 pub use orchestrator::{
@@ -54,6 +62,7 @@ pub use tool_selector::ToolSelectorConfig;
 pub use tools::{ToolExecutor, ToolHandler, ToolRegistry};
 pub use types::{ChannelType, IncomingMessage, MessageKind, OutgoingMessage};
 pub use usage::{AccumulatedUsage, BudgetStatus, UsageConfig, UsageSource, UsageTracker};
+pub use workflows::{Workflow, WorkflowEngine};
 
 #[cfg(test)]
 mod tests {
@@ -0,0 +1,184 @@
+//! LLM extraction of episodic memories from finished conversations
+//!
+//! The knowledge crate decides which conversation threads have gone idle and
+//! what to do with the results; [`LlmMemoryExtractor`] asks the model which
+//! entities, relationships and user preferences a transcript establishes.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use std::sync::Arc;
+use tracing::debug;
+
+use meepo_knowledge::{Episode, ExtractedMemories, MemoryExtractor};
+
+use crate::api::{ApiClient, ApiMessage, ContentBlock, MessageContent};
+
+/// Instructions for the extraction model
+const SYSTEM_PROMPT: &str = "You maintain the long-term memory of a personal assistant. \
+     From a finished conversation, extract only durable facts worth remembering weeks \
+     later: people, places, projects, organizations and events, how they relate, and \
+     the user's stated preferences. Skip small talk, one-off requests and anything the \
+     conversation does not clearly establish. Respond with JSON only.";
+
+/// Extracts memories by asking the LLM
+pub struct LlmMemoryExtractor {
+    api: Arc<ApiClient>,
+}
+
+impl LlmMemoryExtractor {
+    pub fn new(api: Arc<ApiClient>) -> Self {
+        Self { api }
+    }
+}
+
+#[async_trait]
+impl MemoryExtractor for LlmMemoryExtractor {
+    async fn extract(&self, episode: &Episode) -> Result<ExtractedMemories> {
+        let messages = vec![ApiMessage {
+            role: "user".to_string(),
+            content: MessageContent::Text(build_prompt(episode)),
+        }];
+
+        let response = self
+            .api
+            .chat(&messages, &[], SYSTEM_PROMPT)
+            .await
+            .context("Failed to extract memories")?;
+
+        let text = response
+            .content
+            .iter()
+            .filter_map(|b| {
+                if let ContentBlock::Text { text } = b {
+                    Some(text.as_str())
+                } else {
+                    None
+                }
+            })
+            .collect::<String>();
+
+        let extracted = parse_memories(&text)?;
+        debug!(
+            "Extracted {} entities, {} relationships, {} preferences from thread {}",
+            extracted.entities.len(),
+            extracted.relationships.len(),
+            extracted.preferences.len(),
+            episode.thread_id
+        );
+        Ok(extracted)
+    }
+}
+
+fn build_prompt(episode: &Episode) -> String {
+    let mut prompt = format!(
+        "Conversation on {} (\"{}\"):\n\n{}\n",
+        episode.channel,
+        episode.title,
+        episode.transcript()
+    );
+    if !episode.known_entities.is_empty() {
+        prompt.push_str(&format!(
+            "Already known entities (reuse these exact names when referring to them): {}\n\n",
+            episode.known_entities.join(", ")
+        ));
+    }
+    prompt.push_str(
+        "Respond with a JSON object:\n\
+         ```json\n\
+         {\"entities\": [{\"name\": \"...\", \"entity_type\": \"person|place|project|organization|event|...\", \
+         \"description\": \"...\", \"confidence\": 0.0-1.0, \"evidence\": \"short quote\"}],\n \
+         \"relationships\": [{\"source\": \"entity name\", \"relation_type\": \"works_at|lives_in|...\", \
+         \"target\": \"entity name\", \"confidence\": 0.0-1.0, \"evidence\": \"short quote\"}],\n \
+         \"preferences\": [{\"category\": \"...\", \"key\": \"...\", \"value\": \"...\", \
+         \"confidence\": 0.0-1.0, \"evidence\": \"short quote\"}]}\n\
+         ```\n\
+         Refer to the user as \"User\". Use empty arrays when there is nothing worth remembering.",
+    );
+    prompt
+}
+
+/// Parse the model's JSON response, clamping confidences to 0.0–1.0
+fn parse_memories(text: &str) -> Result<ExtractedMemories> {
+    let json = extract_json_object(text);
+    let mut extracted: ExtractedMemories = serde_json::from_str(json)
+        .context("Memory extraction response was not the expected JSON object")?;
+    for e in &mut extracted.entities {
+        e.confidence = e.confidence.clamp(0.0, 1.0);
+    }
+    for r in &mut extracted.relationships {
+        r.confidence = r.confidence.clamp(0.0, 1.0);
+    }
+    for p in &mut extracted.preferences {
+        p.confidence = p.confidence.clamp(0.0, 1.0);
+    }
+    Ok(extracted)
+}
+
+/// Extract a JSON object from a response that may contain markdown fences
+fn extract_json_object(text: &str) -> &str {
+    let inner = match text.find("```") {
+        Some(start) => {
+            let after_fence = &text[start + 3..];
+            let after_fence = after_fence.strip_prefix("json").unwrap_or(after_fence);
+            after_fence
+                .find("```")
+                .map_or(after_fence, |end| &after_fence[..end])
+        }
+        None => text,
+    };
+    match (inner.find('{'), inner.rfind('}')) {
+        (Some(start), Some(end)) if start < end => &inner[start..=end],
+        _ => inner.trim(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use meepo_knowledge::Conversation;
+
+    #[test]
+    fn test_parse_memories() {
+        let parsed = parse_memories(
+            "Here you go:\n```json\n{\"entities\": [{\"name\": \"Casa Alfama\", \
+             \"entity_type\": \"hotel\", \"confidence\": 1.4}],\n\
+             \"preferences\": [{\"category\": \"travel\", \"key\": \"seat\", \
+             \"value\": \"aisle\", \"confidence\": 0.9}]}\n```",
+        )
+        .unwrap();
+        assert_eq!(parsed.entities[0].name, "Casa Alfama");
+        assert_eq!(parsed.entities[0].confidence, 1.0);
+        assert!(parsed.relationships.is_empty());
+        assert_eq!(parsed.preferences[0].value, serde_json::json!("aisle"));
+
+        let bare = parse_memories("{\"entities\": []}").unwrap();
+        assert_eq!(bare, ExtractedMemories::default());
+
+        assert!(parse_memories("Nothing worth remembering.").is_err());
+    }
+
+    #[test]
+    fn test_build_prompt_includes_transcript_and_known_entities() {
+        let episode = Episode {
+            thread_id: "slack:T1".to_string(),
+            channel: "slack".to_string(),
+            title: "Lisbon trip".to_string(),
+            messages: vec![Conversation {
+                id: "c1".to_string(),
+                channel: "slack".to_string(),
+                sender: "alice".to_string(),
+                content: "Book Casa Alfama".to_string(),
+                metadata: None,
+                created_at: Utc::now(),
+                thread_id: Some("slack:T1".to_string()),
+            }],
+            known_entities: vec!["Alice".to_string(), "Lisbon".to_string()],
+        };
+        let prompt = build_prompt(&episode);
+        assert!(prompt.starts_with("Conversation on slack (\"Lisbon trip\"):"));
+        assert!(prompt.contains("alice: Book Casa Alfama\n"));
+        assert!(prompt.contains("reuse these exact names when referring to them): Alice, Lisbon"));
+        assert!(prompt.contains("\"preferences\""));
+    }
+}
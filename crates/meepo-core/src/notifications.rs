@@ -32,6 +32,12 @@ pub enum NotifyEvent {
         kind: String,
         payload: String,
     },
+    WatcherDisabled {
+        watcher_id: String,
+        description: String,
+        error: String,
+        consecutive_errors: u32,
+    },
     AutonomousAction {
         description: String,
     },
@@ -125,7 +131,10 @@ impl NotificationService {
         }
 
         // Check if this event type is enabled
-        let is_error = matches!(event, NotifyEvent::Error { .. });
+        let is_error = matches!(
+            event,
+            NotifyEvent::Error { .. } | NotifyEvent::WatcherDisabled { .. }
+        );
         if !self.should_notify(&event) {
             debug!("Notification suppressed (event type disabled): {:?}", event);
             return;
@@ -159,7 +168,7 @@ impl NotificationService {
             NotifyEvent::TaskFailed { .. } => self.config.on_task_fail,
             NotifyEvent::WatcherTriggered { .. } => self.config.on_watcher_triggered,
             NotifyEvent::AutonomousAction { .. } => self.config.on_autonomous_action,
-            NotifyEvent::Error { .. } => self.config.on_error,
+            NotifyEvent::Error { .. } | NotifyEvent::WatcherDisabled { .. } => self.config.on_error,
             NotifyEvent::BudgetWarning { .. } | NotifyEvent::BudgetExceeded { .. } => true,
             NotifyEvent::DigestMorning { .. } | NotifyEvent::DigestEvening { .. } => true,
         }
@@ -231,6 +240,20 @@ impl NotificationService {
                     truncate(payload, 300)
                 )
             }
+            NotifyEvent::WatcherDisabled {
+                watcher_id,
+                description,
+                error,
+                consecutive_errors,
+            } => {
+                format!(
+                    "🛑 Watcher disabled after {} failures in a row\n[{}] {}\n\nLast error: {}",
+                    consecutive_errors,
+                    watcher_id,
+                    truncate(description, 150),
+                    truncate(error, 300)
+                )
+            }
             NotifyEvent::AutonomousAction { description } => {
                 format!(
                     "🧠 Taking autonomous action\n{}",
@@ -443,6 +466,12 @@ mod tests {
                 kind: "file".into(),
                 payload: "changed".into(),
             },
+            NotifyEvent::WatcherDisabled {
+                watcher_id: "w2".into(),
+                description: "GitHub watcher".into(),
+                error: "HTTP 401".into(),
+                consecutive_errors: 10,
+            },
             NotifyEvent::AutonomousAction {
                 description: "doing stuff".into(),
             },
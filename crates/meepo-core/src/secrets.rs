@@ -66,6 +66,7 @@ const ALLOWED_SECRET_ENV_VARS: &[&str] = &[
     "GITHUB_TOKEN",
     "MEEPO_GATEWAY_TOKEN",
     "ELEVENLABS_API_KEY",
+    "MEEPO_DB_KEY",
     "HOME",
     "USERPROFILE",
     "USER",
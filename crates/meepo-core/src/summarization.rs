@@ -156,8 +156,10 @@ pub async fn summarize_conversations(
 
 /// Build context string from conversations, applying summarization if needed.
 ///
-/// Returns a context string with an optional summary section followed by
-/// recent verbatim messages.
+/// `conversations` is the history of the active conversation thread, oldest
+/// first (see `KnowledgeDb::get_thread_conversations`), so a summary never
+/// mixes unrelated threads of the same channel. Returns a context string with
+/// an optional summary section followed by recent verbatim messages.
 pub async fn build_summarized_context(
     api: &ApiClient,
     conversations: &[(String, String)],
@@ -201,6 +201,13 @@ fn select_heuristic(
         relevant_prefixes.push("search_knowledge");
         relevant_prefixes.push("link_entities");
     }
+    if lower.contains("discuss")
+        || lower.contains("conversation")
+        || lower.contains("talked")
+        || lower.contains("we said")
+    {
+        relevant_prefixes.push("search_conversations");
+    }
     if lower.contains("ingest") || lower.contains("index") || lower.contains("document") {
         relevant_prefixes.push("ingest_");
     }
@@ -9,6 +9,7 @@ use tracing::debug;
 
 use super::{ToolHandler, json_schema};
 use meepo_knowledge::KnowledgeDb;
+use meepo_scheduler::WatcherStore;
 
 /// Commands for background task management
 #[derive(Debug, Clone)]
@@ -119,11 +120,12 @@ impl ToolHandler for SpawnBackgroundTaskTool {
 /// Unified view of everything the agent is managing autonomously
 pub struct AgentStatusTool {
     db: Arc<KnowledgeDb>,
+    watchers: WatcherStore,
 }
 
 impl AgentStatusTool {
-    pub fn new(db: Arc<KnowledgeDb>) -> Self {
-        Self { db }
+    pub fn new(db: Arc<KnowledgeDb>, watchers: WatcherStore) -> Self {
+        Self { db, watchers }
     }
 }
 
@@ -148,8 +150,8 @@ impl ToolHandler for AgentStatusTool {
 
         // Active watchers
         let watchers = self
-            .db
-            .get_active_watchers()
+            .watchers
+            .active()
             .await
             .context("Failed to get active watchers")?;
 
@@ -161,7 +163,11 @@ impl ToolHandler for AgentStatusTool {
                 let age = format_age(w.created_at);
                 output.push_str(&format!(
                     "- [{}] {} → {} ({})\n  Action: {}\n",
-                    w.id, w.kind, w.reply_channel, age, w.action
+                    w.id,
+                    w.description(),
+                    w.reply_channel,
+                    age,
+                    w.action
                 ));
             }
             output.push('\n');
@@ -238,6 +244,7 @@ impl ToolHandler for AgentStatusTool {
 /// Cancel any watcher or background task by ID
 pub struct StopTaskTool {
     db: Arc<KnowledgeDb>,
+    watchers: WatcherStore,
     watcher_tx: mpsc::Sender<super::watchers::WatcherCommand>,
     task_tx: mpsc::Sender<BackgroundTaskCommand>,
 }
@@ -245,11 +252,13 @@ pub struct StopTaskTool {
 impl StopTaskTool {
     pub fn new(
         db: Arc<KnowledgeDb>,
+        watchers: WatcherStore,
         watcher_tx: mpsc::Sender<super::watchers::WatcherCommand>,
         task_tx: mpsc::Sender<BackgroundTaskCommand>,
     ) -> Self {
         Self {
             db,
+            watchers,
             watcher_tx,
             task_tx,
         }
@@ -290,8 +299,8 @@ impl ToolHandler for StopTaskTool {
 
         if task_id.starts_with("w-") {
             // Cancel watcher
-            self.db
-                .update_watcher_active(task_id, false)
+            self.watchers
+                .deactivate(task_id)
                 .await
                 .context("Failed to deactivate watcher")?;
 
@@ -346,6 +355,12 @@ fn format_age(dt: chrono::DateTime<chrono::Utc>) -> String {
 mod tests {
     use super::*;
 
+    fn watcher_store() -> WatcherStore {
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        meepo_scheduler::persistence::init_watcher_tables(&conn).unwrap();
+        WatcherStore::new(Arc::new(std::sync::Mutex::new(conn)))
+    }
+
     #[test]
     fn test_format_age() {
         let now = chrono::Utc::now();
@@ -362,7 +377,7 @@ mod tests {
     async fn test_agent_status_empty() {
         let temp = tempfile::TempDir::new().unwrap();
         let db = Arc::new(meepo_knowledge::KnowledgeDb::new(temp.path().join("test.db")).unwrap());
-        let tool = AgentStatusTool::new(db);
+        let tool = AgentStatusTool::new(db, watcher_store());
 
         let result = tool.execute(serde_json::json!({})).await.unwrap();
         assert!(result.contains("None"));
@@ -374,7 +389,7 @@ mod tests {
         let db = Arc::new(meepo_knowledge::KnowledgeDb::new(temp.path().join("test.db")).unwrap());
         let (watcher_tx, _) = mpsc::channel(1);
         let (task_tx, _) = mpsc::channel(1);
-        let tool = StopTaskTool::new(db, watcher_tx, task_tx);
+        let tool = StopTaskTool::new(db, watcher_store(), watcher_tx, task_tx);
 
         let result = tool
             .execute(serde_json::json!({"task_id": "invalid-123"}))
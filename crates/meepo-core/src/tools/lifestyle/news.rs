@@ -12,6 +12,7 @@ use tracing::debug;
 use crate::tavily::TavilyClient;
 use crate::tools::{ToolHandler, json_schema};
 use meepo_knowledge::KnowledgeDb;
+use meepo_scheduler::FeedStore;
 
 /// Track a content feed or news source
 pub struct TrackFeedTool {
@@ -136,10 +137,7 @@ impl ToolHandler for TrackFeedTool {
              - Keywords: {}\n\
              - Check interval: every {} hours\n\
              - Max items: {}\n\n\
-             To activate periodic checking, create a watcher:\n\
-             - kind: 'scheduled'\n\
-             - config: {{\"cron_expr\": \"0 */{} * * *\", \"task\": \"Check feed: {}\"}}\n\
-             - action: \"Fetch latest from '{}' and summarize new items\"",
+             {}",
             name,
             entity_id,
             source,
@@ -151,13 +149,43 @@ impl ToolHandler for TrackFeedTool {
             },
             interval,
             max_items,
-            interval,
-            name,
-            name
+            watcher_hint(name, source, source_type, &filter_keywords, interval)
         ))
     }
 }
 
+/// How to set up periodic checking for a tracked feed
+fn watcher_hint(
+    name: &str,
+    source: &str,
+    source_type: &str,
+    keywords: &[String],
+    interval_hours: u64,
+) -> String {
+    if source_type == "rss" {
+        format!(
+            "To collect new items, create a feed watcher:\n\
+             - kind: 'feed'\n\
+             - config: {}\n\
+             - action: \"Summarize the new item from '{}'\"",
+            serde_json::json!({
+                "url": source,
+                "keywords": keywords,
+                "interval_secs": interval_hours * 3600,
+            }),
+            name
+        )
+    } else {
+        format!(
+            "To activate periodic checking, create a watcher:\n\
+             - kind: 'scheduled'\n\
+             - config: {{\"cron_expr\": \"0 */{} * * *\", \"task\": \"Check feed: {}\"}}\n\
+             - action: \"Fetch latest from '{}' and summarize new items\"",
+            interval_hours, name, name
+        )
+    }
+}
+
 /// Stop tracking a feed
 pub struct UntrackFeedTool {
     db: Arc<KnowledgeDb>,
@@ -334,11 +362,61 @@ impl ToolHandler for SummarizeArticleTool {
 pub struct ContentDigestTool {
     tavily: Option<Arc<TavilyClient>>,
     db: Arc<KnowledgeDb>,
+    feeds: Option<FeedStore>,
 }
 
 impl ContentDigestTool {
     pub fn new(tavily: Option<Arc<TavilyClient>>, db: Arc<KnowledgeDb>) -> Self {
-        Self { tavily, db }
+        Self {
+            tavily,
+            db,
+            feeds: None,
+        }
+    }
+
+    /// Read RSS/Atom items collected by feed watchers instead of searching the web
+    pub fn with_feed_store(mut self, feeds: FeedStore) -> Self {
+        self.feeds = Some(feeds);
+        self
+    }
+
+    /// Format the items feed watchers have collected from `source`
+    async fn collected_items(&self, source: &str, keywords: &[String], max_items: u64) -> String {
+        let Some(ref feeds) = self.feeds else {
+            return "Collected feed items not available.\n".to_string();
+        };
+        // Over-fetch so keyword filtering still leaves enough items
+        let items = match feeds.recent_items(source, (max_items as usize) * 5).await {
+            Ok(items) => items,
+            Err(e) => return format!("Error reading collected items: {}\n", e),
+        };
+        let items: Vec<_> = items
+            .into_iter()
+            .filter(|item| item.matches_keywords(keywords))
+            .take(max_items as usize)
+            .collect();
+
+        if items.is_empty() {
+            return "No items collected yet. Create a 'feed' watcher for this URL to start \
+                    collecting them.\n"
+                .to_string();
+        }
+
+        let mut out = String::new();
+        for item in items {
+            out.push_str(&format!("- **{}**", item.title));
+            if let Some(published) = item.published {
+                out.push_str(&format!(" ({})", published.format("%Y-%m-%d")));
+            }
+            out.push('\n');
+            if let Some(link) = &item.link {
+                out.push_str(&format!("  {}\n", link));
+            }
+            if let Some(summary) = &item.summary {
+                out.push_str(&format!("  {}\n", summary));
+            }
+        }
+        out
     }
 }
 
@@ -349,8 +427,9 @@ impl ToolHandler for ContentDigestTool {
     }
 
     fn description(&self) -> &str {
-        "Generate a personalized content digest from all tracked feeds. Fetches latest items \
-         from each active feed, filters by keywords, and compiles a curated digest with summaries."
+        "Generate a personalized content digest from all tracked feeds. Uses the items collected \
+         by feed watchers for RSS/Atom feeds and web search for other sources, filters by \
+         keywords, and compiles a curated digest with summaries."
     }
 
     fn input_schema(&self) -> Value {
@@ -422,10 +501,19 @@ impl ToolHandler for ContentDigestTool {
                 .and_then(|s| s.as_str())
                 .unwrap_or("search_query");
 
+            let keywords: Vec<String> = feed
+                .metadata
+                .as_ref()
+                .and_then(|m| m.get("filter_keywords"))
+                .and_then(|k| serde_json::from_value(k.clone()).ok())
+                .unwrap_or_default();
+
             let mut section = format!("## {}\nSource: {} ({})\n\n", feed.name, source, source_type);
 
             // Fetch content based on source type
-            if let Some(ref tavily) = self.tavily {
+            if source_type == "rss" {
+                section.push_str(&self.collected_items(source, &keywords, max_items).await);
+            } else if let Some(ref tavily) = self.tavily {
                 if source_type == "search_query" || source_type == "website" {
                     match tavily.search(source, max_items as usize).await {
                         Ok(results) => {
@@ -499,6 +587,46 @@ mod tests {
         assert_eq!(tool.name(), "summarize_article");
     }
 
+    #[tokio::test]
+    async fn test_content_digest_uses_collected_feed_items() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = Arc::new(KnowledgeDb::new(dir.path().join("news.db")).unwrap());
+        let url = "https://blog.rust-lang.org/feed.xml";
+        db.insert_entity(
+            "Rust Blog",
+            "tracked_feed",
+            Some(serde_json::json!({
+                "source": url,
+                "source_type": "rss",
+                "filter_keywords": ["release"],
+                "active": true,
+            })),
+        )
+        .await
+        .unwrap();
+
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        meepo_scheduler::init_watcher_tables(&conn).unwrap();
+        for (guid, title) in [("1", "Rust 1.90 release"), ("2", "Community survey")] {
+            let item = meepo_scheduler::FeedItem {
+                guid: guid.to_string(),
+                title: title.to_string(),
+                link: Some(format!("https://blog.rust-lang.org/{}", guid)),
+                summary: None,
+                published: None,
+            };
+            meepo_scheduler::persistence::record_feed_item(&conn, "w1", url, &item).unwrap();
+        }
+
+        let tool = ContentDigestTool::new(None, db)
+            .with_feed_store(FeedStore::new(Arc::new(std::sync::Mutex::new(conn))));
+        let digest = tool.execute(serde_json::json!({})).await.unwrap();
+        assert!(digest.contains("## Rust Blog"));
+        assert!(digest.contains("**Rust 1.90 release**"));
+        assert!(digest.contains("https://blog.rust-lang.org/1"));
+        assert!(!digest.contains("Community survey"));
+    }
+
     #[test]
     fn test_content_digest_schema() {
         let tool = ContentDigestTool::new(None, test_db());
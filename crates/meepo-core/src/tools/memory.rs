@@ -7,16 +7,69 @@ use std::sync::Arc;
 use tracing::debug;
 
 use super::{ToolHandler, json_schema};
-use meepo_knowledge::{KnowledgeDb, KnowledgeGraph};
+use meepo_knowledge::temporal::{self, FactOptions, FactType, Provenance, SourceType};
+use meepo_knowledge::{
+    Conversation, ConversationSearch, Direction, EdgeFilter, ImportanceConfig, KnowledgeDb,
+    KnowledgeGraph, QueryLimits, SearchOptions, importance_score,
+};
+
+/// Add the optional provenance properties to a tool's schema properties
+fn with_source_properties(mut properties: Value) -> Value {
+    if let Some(props) = properties.as_object_mut() {
+        props.insert(
+            "source_type".to_string(),
+            serde_json::json!({
+                "type": "string",
+                "enum": ["conversation", "email", "document", "web", "user", "agent"],
+                "description": "Optional: where this information came from"
+            }),
+        );
+        props.insert(
+            "source_id".to_string(),
+            serde_json::json!({
+                "type": "string",
+                "description": "Optional: identifier of the source (conversation ID, email message ID, file path, URL)"
+            }),
+        );
+        props.insert(
+            "source_excerpt".to_string(),
+            serde_json::json!({
+                "type": "string",
+                "description": "Optional: short quote from the source supporting this fact"
+            }),
+        );
+    }
+    properties
+}
+
+/// Read optional provenance fields from tool input
+fn parse_source(input: &Value) -> Result<Option<Provenance>> {
+    let Some(source_type) = input.get("source_type").and_then(|v| v.as_str()) else {
+        return Ok(None);
+    };
+    let source_type: SourceType = source_type.parse()?;
+    let source_id = input
+        .get("source_id")
+        .and_then(|v| v.as_str())
+        .unwrap_or("unspecified");
+    let mut source = Provenance::new(source_type, source_id);
+    if let Some(excerpt) = input.get("source_excerpt").and_then(|v| v.as_str()) {
+        source = source.with_excerpt(excerpt);
+    }
+    Ok(Some(source))
+}
 
 /// Remember information by adding to knowledge graph
+///
+/// Goes through [`KnowledgeGraph::add_entity`], so a name that resolves to an
+/// existing entity is folded into it instead of creating a duplicate.
 pub struct RememberTool {
-    db: Arc<KnowledgeDb>,
+    graph: Arc<KnowledgeGraph>,
 }
 
 impl RememberTool {
-    pub fn new(db: Arc<KnowledgeDb>) -> Self {
-        Self { db }
+    pub fn new(graph: Arc<KnowledgeGraph>) -> Self {
+        Self { graph }
     }
 }
 
@@ -33,7 +86,7 @@ impl ToolHandler for RememberTool {
 
     fn input_schema(&self) -> Value {
         json_schema(
-            serde_json::json!({
+            with_source_properties(serde_json::json!({
                 "name": {
                     "type": "string",
                     "description": "Name or identifier for this piece of knowledge"
@@ -45,8 +98,12 @@ impl ToolHandler for RememberTool {
                 "metadata": {
                     "type": "object",
                     "description": "Additional structured information about this entity"
+                },
+                "pinned": {
+                    "type": "boolean",
+                    "description": "Optional: pin this memory so it is never forgotten (default: false)"
                 }
-            }),
+            })),
             vec!["name", "entity_type"],
         )
     }
@@ -61,15 +118,34 @@ impl ToolHandler for RememberTool {
             .and_then(|v| v.as_str())
             .ok_or_else(|| anyhow::anyhow!("Missing 'entity_type' parameter"))?;
         let metadata = input.get("metadata").cloned();
+        let pinned = input
+            .get("pinned")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        let source = parse_source(&input)?;
 
         debug!("Remembering: {} (type: {})", name, entity_type);
 
         let entity_id = self
-            .db
-            .insert_entity(name, entity_type, metadata)
+            .graph
+            .add_entity(name, entity_type, metadata)
             .await
             .context("Failed to insert entity")?;
 
+        let db = self.graph.db();
+        if let Some(source) = source {
+            db.add_provenance(FactType::Entity, &entity_id, &source)
+                .await
+                .context("Failed to record source")?;
+        }
+        if pinned {
+            db.set_pinned(&entity_id, true).await?;
+            return Ok(format!(
+                "Remembered '{}' with ID: {} (pinned)",
+                name, entity_id
+            ));
+        }
+
         Ok(format!("Remembered '{}' with ID: {}", name, entity_id))
     }
 }
@@ -77,11 +153,21 @@ impl ToolHandler for RememberTool {
 /// Recall information from knowledge graph
 pub struct RecallTool {
     db: Arc<KnowledgeDb>,
+    importance: ImportanceConfig,
 }
 
 impl RecallTool {
     pub fn new(db: Arc<KnowledgeDb>) -> Self {
-        Self { db }
+        Self {
+            db,
+            importance: ImportanceConfig::default(),
+        }
+    }
+
+    /// Set the importance scoring used to rank results
+    pub fn with_importance(mut self, config: ImportanceConfig) -> Self {
+        self.importance = config;
+        self
     }
 }
 
@@ -93,7 +179,8 @@ impl ToolHandler for RecallTool {
 
     fn description(&self) -> &str {
         "Search the knowledge graph for previously stored information. \
-         Returns matching entities based on name or type."
+         Returns matching entities based on name or type, most important first, with their sources. \
+         Pass as_of to see what was known at a past date."
     }
 
     fn input_schema(&self) -> Value {
@@ -106,6 +193,10 @@ impl ToolHandler for RecallTool {
                 "entity_type": {
                     "type": "string",
                     "description": "Optional: filter by entity type"
+                },
+                "as_of": {
+                    "type": "string",
+                    "description": "Optional: point in time (YYYY-MM-DD, YYYY-MM or RFC 3339) — show only what was known then, including the relationships in effect"
                 }
             }),
             vec!["query"],
@@ -118,25 +209,85 @@ impl ToolHandler for RecallTool {
             .and_then(|v| v.as_str())
             .ok_or_else(|| anyhow::anyhow!("Missing 'query' parameter"))?;
         let entity_type = input.get("entity_type").and_then(|v| v.as_str());
+        let as_of = input
+            .get("as_of")
+            .and_then(|v| v.as_str())
+            .map(temporal::parse_as_of)
+            .transpose()?;
 
         debug!("Searching knowledge graph for: {}", query);
 
-        let results = self
+        let mut results = self
             .db
             .search_entities(query, entity_type)
             .await
             .context("Failed to search entities")?;
+        if let Some(at) = as_of {
+            results.retain(|e| e.created_at <= at);
+        }
 
         if results.is_empty() {
             return Ok("No matching information found.".to_string());
         }
 
-        let mut output = format!("Found {} result(s):\n\n", results.len());
-        for entity in results.iter().take(10) {
+        // Most important first, then record the returned entities as accessed
+        let ids: Vec<String> = results.iter().map(|e| e.id.clone()).collect();
+        let now = chrono::Utc::now();
+        let scores: std::collections::HashMap<String, f32> = self
+            .db
+            .get_entity_stats(&ids)
+            .await?
+            .iter()
+            .map(|s| {
+                (
+                    s.entity_id.clone(),
+                    importance_score(s, now, &self.importance),
+                )
+            })
+            .collect();
+        let score_of = |id: &str| scores.get(id).copied().unwrap_or(0.0);
+        results.sort_by(|a, b| score_of(&b.id).total_cmp(&score_of(&a.id)));
+        results.truncate(10);
+
+        let ids: Vec<String> = results.iter().map(|e| e.id.clone()).collect();
+        self.db.record_access(&ids).await?;
+        let mut sources = self.db.get_provenance(&ids).await?;
+        if let Some(at) = as_of {
+            sources.retain(|p| p.recorded_at <= at);
+        }
+
+        let mut output = match as_of {
+            Some(at) => format!(
+                "Found {} result(s) as of {}:\n\n",
+                results.len(),
+                at.format("%Y-%m-%d")
+            ),
+            None => format!("Found {} result(s):\n\n", results.len()),
+        };
+        for entity in &results {
             output.push_str(&format!("- {} ({})", entity.name, entity.entity_type));
             if let Some(metadata) = &entity.metadata {
                 output.push_str(&format!("\n  Metadata: {}", metadata));
             }
+            for source in sources.iter().filter(|p| p.fact_id == entity.id) {
+                output.push_str(&format!("\n  Source: {}", source.describe()));
+            }
+            if let Some(at) = as_of {
+                for rel in self.db.get_relationships_as_of(&entity.id, at).await? {
+                    let (arrow, other_id) = if rel.source_id == entity.id {
+                        ("->", &rel.target_id)
+                    } else {
+                        ("<-", &rel.source_id)
+                    };
+                    let other = self
+                        .db
+                        .get_entity(other_id)
+                        .await?
+                        .map(|e| e.name)
+                        .unwrap_or_else(|| other_id.clone());
+                    output.push_str(&format!("\n  {} {} {}", rel.relation_type, arrow, other));
+                }
+            }
             output.push('\n');
         }
 
@@ -163,12 +314,13 @@ impl ToolHandler for LinkEntitiesTool {
 
     fn description(&self) -> &str {
         "Create a relationship between two entities in the knowledge graph. \
-         Useful for building connections between concepts, people, facts, etc."
+         Useful for building connections between concepts, people, facts, etc. \
+         Facts like works_at or lives_in replace the previous value, which is kept as history."
     }
 
     fn input_schema(&self) -> Value {
         json_schema(
-            serde_json::json!({
+            with_source_properties(serde_json::json!({
                 "source_id": {
                     "type": "string",
                     "description": "ID of the source entity"
@@ -184,8 +336,16 @@ impl ToolHandler for LinkEntitiesTool {
                 "metadata": {
                     "type": "object",
                     "description": "Optional metadata about the relationship"
+                },
+                "valid_from": {
+                    "type": "string",
+                    "description": "Optional: when this became true (YYYY-MM-DD, YYYY-MM or RFC 3339; default now)"
+                },
+                "supersede": {
+                    "type": "boolean",
+                    "description": "Optional: end the source's current relationships of this type to other targets (default true for exclusive types like works_at, lives_in)"
                 }
-            }),
+            })),
             vec!["source_id", "target_id", "relation_type"],
         )
     }
@@ -205,15 +365,41 @@ impl ToolHandler for LinkEntitiesTool {
             .ok_or_else(|| anyhow::anyhow!("Missing 'relation_type' parameter"))?;
         let metadata = input.get("metadata").cloned();
 
+        let mut opts = FactOptions::for_relation(relation_type);
+        if let Some(supersede) = input.get("supersede").and_then(|v| v.as_bool()) {
+            opts.supersede = supersede;
+        }
+        if let Some(valid_from) = input.get("valid_from").and_then(|v| v.as_str()) {
+            opts.valid_from = Some(temporal::parse_valid_from(valid_from)?);
+        }
+        opts.provenance = parse_source(&input)?;
+
         debug!("Linking {} -> {} ({})", source_id, target_id, relation_type);
 
-        let rel_id = self
+        let fact = self
             .db
-            .insert_relationship(source_id, target_id, relation_type, metadata)
+            .record_relationship(source_id, target_id, relation_type, metadata, opts)
             .await
             .context("Failed to create relationship")?;
 
-        Ok(format!("Created relationship with ID: {}", rel_id))
+        let mut output = if fact.existing {
+            format!("Relationship already recorded with ID: {}", fact.id)
+        } else {
+            format!("Created relationship with ID: {}", fact.id)
+        };
+        if !fact.superseded.is_empty() {
+            output.push_str(&format!(
+                "\nSuperseded previous relationship(s): {}",
+                fact.superseded.join(", ")
+            ));
+        }
+        if let Some(next) = &fact.superseded_by {
+            output.push_str(&format!(
+                "\nEnded at the start of later relationship: {}",
+                next
+            ));
+        }
+        Ok(output)
     }
 }
 
@@ -252,7 +438,7 @@ impl ToolHandler for SearchKnowledgeTool {
 
     fn description(&self) -> &str {
         "Perform a full-text search across all stored knowledge. \
-         Uses Tantivy for fast, relevance-ranked full-text search."
+         Uses Tantivy for fast full-text search, ranked by relevance and memory importance."
     }
 
     fn input_schema(&self) -> Value {
@@ -265,6 +451,28 @@ impl ToolHandler for SearchKnowledgeTool {
                 "limit": {
                     "type": "number",
                     "description": "Maximum number of results (default: 10)"
+                },
+                "entity_types": {
+                    "type": "array",
+                    "items": {"type": "string"},
+                    "description": "Optional: only return entities of these types"
+                },
+                "tags": {
+                    "type": "array",
+                    "items": {"type": "string"},
+                    "description": "Optional: only return entities tagged with all of these"
+                },
+                "after": {
+                    "type": "string",
+                    "description": "Optional: only entities created on or after this date (RFC 3339, YYYY-MM-DD or YYYY-MM)"
+                },
+                "before": {
+                    "type": "string",
+                    "description": "Optional: only entities created up to this date (RFC 3339, YYYY-MM-DD or YYYY-MM)"
+                },
+                "fuzzy": {
+                    "type": "boolean",
+                    "description": "Also match near-misspellings of query terms (default: true)"
                 }
             }),
             vec!["query"],
@@ -282,9 +490,39 @@ impl ToolHandler for SearchKnowledgeTool {
 
         // Use Tantivy if KnowledgeGraph is available, otherwise fall back to basic search
         if let Some(graph) = &self.graph {
+            let strings = |key: &str| -> Vec<String> {
+                input
+                    .get(key)
+                    .and_then(|v| v.as_array())
+                    .map(|items| {
+                        items
+                            .iter()
+                            .filter_map(|v| v.as_str())
+                            .map(str::to_string)
+                            .collect()
+                    })
+                    .unwrap_or_default()
+            };
+            let options = SearchOptions {
+                entity_types: strings("entity_types"),
+                tags: strings("tags"),
+                created_after: input
+                    .get("after")
+                    .and_then(|v| v.as_str())
+                    .map(temporal::parse_valid_from)
+                    .transpose()?,
+                created_before: input
+                    .get("before")
+                    .and_then(|v| v.as_str())
+                    .map(temporal::parse_as_of)
+                    .transpose()?,
+                fuzzy: input.get("fuzzy").and_then(|v| v.as_bool()).unwrap_or(true),
+            };
+
             // Use Tantivy full-text search via KnowledgeGraph
             let search_results = graph
-                .search(query, limit)
+                .search_ranked_with(query, &options, limit)
+                .await
                 .context("Failed to perform full-text search")?;
 
             if search_results.is_empty() {
@@ -292,7 +530,7 @@ impl ToolHandler for SearchKnowledgeTool {
             }
 
             let mut output = format!(
-                "Found {} result(s) (sorted by relevance):\n\n",
+                "Found {} result(s) (sorted by relevance and importance):\n\n",
                 search_results.len()
             );
             for result in search_results.iter().take(limit) {
@@ -339,6 +577,354 @@ impl ToolHandler for SearchKnowledgeTool {
     }
 }
 
+/// Longest `query_graph` output, to keep results prompt-sized
+const MAX_GRAPH_OUTPUT_CHARS: usize = 6000;
+
+/// Query the knowledge graph structure: paths, neighborhoods and patterns
+pub struct QueryGraphTool {
+    graph: Arc<KnowledgeGraph>,
+    limits: QueryLimits,
+}
+
+impl QueryGraphTool {
+    pub fn new(graph: Arc<KnowledgeGraph>) -> Self {
+        Self {
+            graph,
+            limits: QueryLimits::default(),
+        }
+    }
+
+    pub fn with_limits(mut self, limits: QueryLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    async fn resolve(&self, input: &Value, key: &str) -> Result<meepo_knowledge::Entity> {
+        let reference = input
+            .get(key)
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing '{}' parameter", key))?;
+        self.graph
+            .resolve_entity(reference)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("No entity with ID or name '{}'", reference))
+    }
+}
+
+#[async_trait]
+impl ToolHandler for QueryGraphTool {
+    fn name(&self) -> &str {
+        "query_graph"
+    }
+
+    fn description(&self) -> &str {
+        "Query how knowledge is connected. Modes: 'path' finds the shortest chain of \
+         relationships between two entities (\"how do I know Carol?\"); 'neighborhood' lists \
+         entities within a few hops of one entity; 'pattern' matches a Cypher-like pattern \
+         such as (p:person)-[works_at]->(:organization {name: \"Acme\"}) or \
+         (proj:project)<-[involved_in]-(p:person)-[works_at]->(o {name: \"Acme\"}). \
+         Entities can be given by ID or exact name."
+    }
+
+    fn input_schema(&self) -> Value {
+        json_schema(
+            serde_json::json!({
+                "mode": {
+                    "type": "string",
+                    "enum": ["path", "neighborhood", "pattern"],
+                    "description": "Kind of query"
+                },
+                "from": {
+                    "type": "string",
+                    "description": "path: starting entity ID or name"
+                },
+                "to": {
+                    "type": "string",
+                    "description": "path: target entity ID or name"
+                },
+                "entity": {
+                    "type": "string",
+                    "description": "neighborhood: center entity ID or name"
+                },
+                "hops": {
+                    "type": "number",
+                    "description": "neighborhood: how many relationships away to look (default: 1)"
+                },
+                "pattern": {
+                    "type": "string",
+                    "description": "pattern: nodes (var:type {name: \"...\"}) joined by -[relation]->, <-[relation]- or -[relation]-"
+                },
+                "relation_types": {
+                    "type": "array",
+                    "items": {"type": "string"},
+                    "description": "path/neighborhood: only follow these relation types"
+                },
+                "direction": {
+                    "type": "string",
+                    "enum": ["outgoing", "incoming", "both"],
+                    "description": "path/neighborhood: direction to follow relationships (default: both)"
+                },
+                "limit": {
+                    "type": "number",
+                    "description": "Maximum number of results (default: 10)"
+                }
+            }),
+            vec!["mode"],
+        )
+    }
+
+    async fn execute(&self, input: Value) -> Result<String> {
+        let mode = input
+            .get("mode")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'mode' parameter"))?;
+        let limit = input.get("limit").and_then(|v| v.as_u64()).unwrap_or(10) as usize;
+        let limits = QueryLimits {
+            max_results: limit.clamp(1, self.limits.max_results.max(1)),
+            ..self.limits.clone()
+        };
+        let filter = EdgeFilter {
+            relation_types: input
+                .get("relation_types")
+                .and_then(|v| v.as_array())
+                .map(|items| {
+                    items
+                        .iter()
+                        .filter_map(|v| v.as_str())
+                        .map(str::to_string)
+                        .collect()
+                })
+                .unwrap_or_default(),
+            direction: input
+                .get("direction")
+                .and_then(|v| v.as_str())
+                .map(str::parse::<Direction>)
+                .transpose()?
+                .unwrap_or_default(),
+        };
+
+        debug!("Graph query ({}): {}", mode, input);
+
+        let mut output = match mode {
+            "path" => {
+                let from = self.resolve(&input, "from").await?;
+                let to = self.resolve(&input, "to").await?;
+                match self
+                    .graph
+                    .shortest_path(&from.id, &to.id, &filter, &limits)
+                    .await?
+                {
+                    Some(path) => format!("Shortest path ({} hop(s)):\n{}\n", path.len(), path),
+                    None => format!(
+                        "No connection between {} and {} within {} hops.",
+                        from.name, to.name, limits.max_depth
+                    ),
+                }
+            }
+            "neighborhood" => {
+                let center = self.resolve(&input, "entity").await?;
+                let hops = input.get("hops").and_then(|v| v.as_u64()).unwrap_or(1) as usize;
+                let hood = self
+                    .graph
+                    .neighborhood(&center.id, hops, &filter, &limits)
+                    .await?
+                    .context("Entity disappeared during query")?;
+                if hood.neighbors.is_empty() {
+                    format!("{} has no connected entities.", center.name)
+                } else {
+                    let mut out = format!(
+                        "Entities within {} hop(s) of {} ({}):\n",
+                        hops.min(limits.max_depth),
+                        center.name,
+                        center.entity_type
+                    );
+                    let names: std::collections::HashMap<&str, &str> =
+                        std::iter::once(&hood.center)
+                            .chain(hood.neighbors.iter().map(|n| &n.entity))
+                            .map(|e| (e.id.as_str(), e.name.as_str()))
+                            .collect();
+                    for n in &hood.neighbors {
+                        let from = names.get(n.via_entity_id.as_str()).copied().unwrap_or("?");
+                        let link = if n.via.source_id == n.via_entity_id {
+                            format!("{} -[{}]-> {}", from, n.via.relation_type, n.entity.name)
+                        } else {
+                            format!("{} <-[{}]- {}", from, n.via.relation_type, n.entity.name)
+                        };
+                        out.push_str(&format!(
+                            "- {} ({}) [{}], {} hop(s): {}\n",
+                            n.entity.name, n.entity.entity_type, n.entity.id, n.distance, link
+                        ));
+                    }
+                    if hood.truncated {
+                        out.push_str("(more results omitted)\n");
+                    }
+                    out
+                }
+            }
+            "pattern" => {
+                let pattern = input
+                    .get("pattern")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| anyhow::anyhow!("Missing 'pattern' parameter"))?;
+                let results = self.graph.match_pattern(pattern, &limits).await?;
+                if results.matches.is_empty() {
+                    "No matches.".to_string()
+                } else {
+                    let mut out = format!("{} match(es):\n", results.matches.len());
+                    for m in &results.matches {
+                        out.push_str(&format!("- {}\n", m));
+                        if !m.bindings.is_empty() {
+                            let vars: Vec<String> = m
+                                .bindings
+                                .iter()
+                                .map(|(var, id)| format!("{}={}", var, id))
+                                .collect();
+                            out.push_str(&format!("  {}\n", vars.join(", ")));
+                        }
+                    }
+                    if results.truncated {
+                        out.push_str("(more matches omitted)\n");
+                    }
+                    out
+                }
+            }
+            other => {
+                return Err(anyhow::anyhow!(
+                    "Unknown mode '{}' (use path, neighborhood or pattern)",
+                    other
+                ));
+            }
+        };
+
+        if output.len() > MAX_GRAPH_OUTPUT_CHARS {
+            let mut cut = MAX_GRAPH_OUTPUT_CHARS;
+            while !output.is_char_boundary(cut) {
+                cut -= 1;
+            }
+            output.truncate(cut);
+            output.push_str("\n(output truncated)");
+        }
+        Ok(output)
+    }
+}
+
+/// Longest context message shown by `search_conversations`, in characters
+const MAX_CONTEXT_MESSAGE_CHARS: usize = 200;
+
+/// Search past conversations across channels and threads
+pub struct SearchConversationsTool {
+    db: Arc<KnowledgeDb>,
+}
+
+impl SearchConversationsTool {
+    pub fn new(db: Arc<KnowledgeDb>) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl ToolHandler for SearchConversationsTool {
+    fn name(&self) -> &str {
+        "search_conversations"
+    }
+
+    fn description(&self) -> &str {
+        "Full-text search over past conversations on every channel. Returns matching \
+         messages with an excerpt and the surrounding messages of their thread. Use this \
+         for questions like 'what did we discuss about the Lisbon trip'."
+    }
+
+    fn input_schema(&self) -> Value {
+        json_schema(
+            serde_json::json!({
+                "query": {
+                    "type": "string",
+                    "description": "Words to look for; messages matching more of them rank higher"
+                },
+                "channel": {
+                    "type": "string",
+                    "description": "Optional: only search this channel (e.g. slack, discord, email)"
+                },
+                "thread_id": {
+                    "type": "string",
+                    "description": "Optional: only search this conversation thread"
+                },
+                "limit": {
+                    "type": "number",
+                    "description": "Maximum number of matches (default: 5)"
+                },
+                "context": {
+                    "type": "number",
+                    "description": "Messages of context to show before and after each match (default: 2)"
+                }
+            }),
+            vec!["query"],
+        )
+    }
+
+    async fn execute(&self, input: Value) -> Result<String> {
+        let query = input
+            .get("query")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'query' parameter"))?;
+        let text = |key: &str| input.get(key).and_then(|v| v.as_str()).map(str::to_string);
+        let options = ConversationSearch {
+            channel: text("channel"),
+            thread_id: text("thread_id"),
+            limit: input.get("limit").and_then(|v| v.as_u64()).unwrap_or(5) as usize,
+            context: input.get("context").and_then(|v| v.as_u64()).unwrap_or(2) as usize,
+        };
+
+        debug!("Searching conversations for: {}", query);
+        let matches = self
+            .db
+            .search_conversations(query, &options)
+            .await
+            .context("Failed to search conversations")?;
+        if matches.is_empty() {
+            return Ok("No matching conversations found.".to_string());
+        }
+
+        let line = |c: &Conversation| {
+            let content: String = c.content.chars().take(MAX_CONTEXT_MESSAGE_CHARS).collect();
+            let ellipsis = if content.len() < c.content.len() {
+                "…"
+            } else {
+                ""
+            };
+            format!(
+                "     {}: {}{}\n",
+                c.sender,
+                content.replace('\n', " "),
+                ellipsis
+            )
+        };
+        let mut output = format!("Found {} matching message(s):\n\n", matches.len());
+        for (i, m) in matches.iter().enumerate() {
+            output.push_str(&format!(
+                "{}. {} on {} (thread {})\n",
+                i + 1,
+                m.message.created_at.format("%Y-%m-%d %H:%M"),
+                m.message.channel,
+                m.message.thread_id.as_deref().unwrap_or(&m.message.channel)
+            ));
+            for c in &m.before {
+                output.push_str(&line(c));
+            }
+            output.push_str(&format!(
+                "  >> {}: {}\n",
+                m.message.sender,
+                m.excerpt.replace('\n', " ")
+            ));
+            for c in &m.after {
+                output.push_str(&line(c));
+            }
+            output.push('\n');
+        }
+        Ok(output)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -359,10 +945,45 @@ mod tests {
         (graph, temp)
     }
 
+    #[tokio::test]
+    async fn test_search_conversations_tool() {
+        let (db, _temp) = setup();
+        for (sender, content) in [
+            ("alice", "Let's plan the Lisbon trip"),
+            ("meepo", "Flights start at 80 EUR"),
+            ("alice", "Book the Alfama hotel too"),
+        ] {
+            db.insert_conversation_in_thread("slack", Some("C1/17.1"), sender, content, None)
+                .await
+                .unwrap();
+        }
+        db.insert_conversation("discord", "bob", "Lunch at noon?", None)
+            .await
+            .unwrap();
+
+        let tool = SearchConversationsTool::new(db);
+        assert_eq!(tool.name(), "search_conversations");
+        let output = tool
+            .execute(serde_json::json!({"query": "flights to Lisbon", "context": 1}))
+            .await
+            .unwrap();
+        assert!(output.starts_with("Found 2 matching message(s)"));
+        assert!(output.contains("on slack (thread slack:C1/17.1)"));
+        assert!(output.contains(">> meepo: **Flights** start at 80 EUR"));
+        assert!(output.contains("     alice: Book the Alfama hotel too"));
+
+        let none = tool
+            .execute(serde_json::json!({"query": "Lisbon", "channel": "discord"}))
+            .await
+            .unwrap();
+        assert_eq!(none, "No matching conversations found.");
+        assert!(tool.execute(serde_json::json!({})).await.is_err());
+    }
+
     #[test]
     fn test_remember_tool_schema() {
-        let (db, _temp) = setup();
-        let tool = RememberTool::new(db);
+        let (graph, _temp) = setup_graph();
+        let tool = RememberTool::new(graph);
         assert_eq!(tool.name(), "remember");
         assert!(!tool.description().is_empty());
         let schema = tool.input_schema();
@@ -371,8 +992,9 @@ mod tests {
 
     #[tokio::test]
     async fn test_remember_and_recall() {
-        let (db, _temp) = setup();
-        let remember = RememberTool::new(db.clone());
+        let (graph, _temp) = setup_graph();
+        let db = graph.db();
+        let remember = RememberTool::new(graph.clone());
         let recall = RecallTool::new(db);
 
         // Remember something
@@ -396,10 +1018,60 @@ mod tests {
         assert!(result.contains("Rust programming"));
     }
 
+    #[tokio::test]
+    async fn test_recall_ranks_pinned_first_and_records_access() {
+        let (graph, _temp) = setup_graph();
+        let db = graph.db();
+        let remember = RememberTool::new(graph.clone());
+        let recall = RecallTool::new(db.clone());
+
+        remember
+            .execute(serde_json::json!({"name": "Tokyo hotel", "entity_type": "fact"}))
+            .await
+            .unwrap();
+        let result = remember
+            .execute(serde_json::json!({
+                "name": "Tokyo flight",
+                "entity_type": "fact",
+                "pinned": true
+            }))
+            .await
+            .unwrap();
+        assert!(result.contains("(pinned)"));
+
+        let result = recall
+            .execute(serde_json::json!({"query": "Tokyo"}))
+            .await
+            .unwrap();
+        let flight = result.find("Tokyo flight").unwrap();
+        let hotel = result.find("Tokyo hotel").unwrap();
+        assert!(flight < hotel);
+
+        let stats = db.get_all_entity_stats().await.unwrap();
+        assert!(stats.iter().all(|s| s.access_count == 1));
+    }
+
+    #[tokio::test]
+    async fn test_remember_resolves_existing_entity() {
+        let (graph, _temp) = setup_graph();
+        let remember = RememberTool::new(graph.clone());
+
+        let first = remember
+            .execute(serde_json::json!({"name": "Alice", "entity_type": "person"}))
+            .await
+            .unwrap();
+        let second = remember
+            .execute(serde_json::json!({"name": "alice", "entity_type": "person"}))
+            .await
+            .unwrap();
+        assert_eq!(first.split("ID: ").nth(1), second.split("ID: ").nth(1));
+        assert_eq!(graph.get_all_entities().await.unwrap().len(), 1);
+    }
+
     #[tokio::test]
     async fn test_remember_missing_name() {
-        let (db, _temp) = setup();
-        let tool = RememberTool::new(db);
+        let (graph, _temp) = setup_graph();
+        let tool = RememberTool::new(graph);
         let result = tool
             .execute(serde_json::json!({
                 "entity_type": "concept"
@@ -428,8 +1100,9 @@ mod tests {
 
     #[tokio::test]
     async fn test_link_entities() {
-        let (db, _temp) = setup();
-        let remember = RememberTool::new(db.clone());
+        let (graph, _temp) = setup_graph();
+        let db = graph.db();
+        let remember = RememberTool::new(graph.clone());
         let link = LinkEntitiesTool::new(db);
 
         // Create two entities
@@ -467,9 +1140,99 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_search_knowledge_tool() {
+    async fn test_recall_shows_source() {
+        let (graph, _temp) = setup_graph();
+        let db = graph.db();
+        let remember = RememberTool::new(graph.clone());
+        let recall = RecallTool::new(db);
+
+        remember
+            .execute(serde_json::json!({
+                "name": "Dana",
+                "entity_type": "person",
+                "source_type": "email",
+                "source_id": "<msg-42@example.com>",
+                "source_excerpt": "Dana is our new designer"
+            }))
+            .await
+            .unwrap();
+
+        let result = recall
+            .execute(serde_json::json!({"query": "Dana"}))
+            .await
+            .unwrap();
+        assert!(result.contains("Source: email <msg-42@example.com>"));
+        assert!(result.contains("Dana is our new designer"));
+    }
+
+    #[tokio::test]
+    async fn test_remember_invalid_source_type() {
+        let (graph, _temp) = setup_graph();
+        let tool = RememberTool::new(graph);
+        let result = tool
+            .execute(serde_json::json!({
+                "name": "X",
+                "entity_type": "fact",
+                "source_type": "rumor"
+            }))
+            .await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_link_entities_supersedes_and_recall_as_of() {
         let (db, _temp) = setup();
-        let remember = RememberTool::new(db.clone());
+        let bob = db.insert_entity("Bob", "person", None).await.unwrap();
+        let acme = db.insert_entity("Acme", "company", None).await.unwrap();
+        let globex = db.insert_entity("Globex", "company", None).await.unwrap();
+        let link = LinkEntitiesTool::new(db.clone());
+        let recall = RecallTool::new(db.clone());
+
+        link.execute(serde_json::json!({
+            "source_id": bob,
+            "target_id": acme,
+            "relation_type": "works_at",
+            "valid_from": "2020-01"
+        }))
+        .await
+        .unwrap();
+        let result = link
+            .execute(serde_json::json!({
+                "source_id": bob,
+                "target_id": globex,
+                "relation_type": "works_at",
+                "source_type": "conversation"
+            }))
+            .await
+            .unwrap();
+        assert!(result.contains("Superseded"));
+
+        let current = db.get_current_relationships_for(&bob).await.unwrap();
+        assert_eq!(current.len(), 1);
+        assert_eq!(current[0].target_id, globex);
+
+        let tomorrow = (chrono::Utc::now() + chrono::Duration::days(1))
+            .format("%Y-%m-%d")
+            .to_string();
+        let result = recall
+            .execute(serde_json::json!({"query": "Bob", "as_of": tomorrow}))
+            .await
+            .unwrap();
+        assert!(result.contains("works_at -> Globex"));
+        assert!(!result.contains("works_at -> Acme"));
+
+        let result = recall
+            .execute(serde_json::json!({"query": "Bob", "as_of": "2019-06"}))
+            .await
+            .unwrap();
+        assert!(result.contains("No matching"));
+    }
+
+    #[tokio::test]
+    async fn test_search_knowledge_tool() {
+        let (graph, _temp) = setup_graph();
+        let db = graph.db();
+        let remember = RememberTool::new(graph.clone());
         let search = SearchKnowledgeTool::new(db);
 
         // Add some data
@@ -566,6 +1329,51 @@ mod tests {
         assert!(result.contains("article"));
     }
 
+    #[tokio::test]
+    async fn test_search_knowledge_filters_and_typos() {
+        let (graph, _temp) = setup_graph();
+        graph
+            .add_entity(
+                "Kubernetes upgrade",
+                "task",
+                Some(serde_json::json!({"tags": ["infra"]})),
+            )
+            .await
+            .unwrap();
+        graph.add_entity("Kubernetes", "tool", None).await.unwrap();
+
+        let search = SearchKnowledgeTool::with_graph(graph);
+        let result = search
+            .execute(serde_json::json!({
+                "query": "kubrenetes",
+                "entity_types": ["task"],
+                "tags": ["infra"],
+                "after": "2020-01"
+            }))
+            .await
+            .unwrap();
+        assert!(result.contains("Found 1 result"));
+        assert!(result.contains("(task)"));
+
+        let result = search
+            .execute(serde_json::json!({"query": "upgrade"}))
+            .await
+            .unwrap();
+        assert!(result.contains("**upgrade**"));
+
+        let result = search
+            .execute(serde_json::json!({"query": "kubrenetes", "fuzzy": false}))
+            .await
+            .unwrap();
+        assert!(result.contains("No results"));
+
+        let result = search
+            .execute(serde_json::json!({"query": "kubernetes", "before": "2020-01"}))
+            .await
+            .unwrap();
+        assert!(result.contains("No results"));
+    }
+
     #[tokio::test]
     async fn test_search_knowledge_no_results() {
         let (graph, _temp) = setup_graph();
@@ -617,4 +1425,114 @@ mod tests {
         let tool = SearchKnowledgeTool::new(db);
         assert_eq!(tool.name(), "search_knowledge");
     }
+
+    async fn sample_graph() -> (Arc<meepo_knowledge::KnowledgeGraph>, TempDir) {
+        let (graph, temp) = setup_graph();
+        let alice = graph.add_entity("Alice", "person", None).await.unwrap();
+        let bob = graph.add_entity("Bob", "person", None).await.unwrap();
+        let carol = graph.add_entity("Carol", "person", None).await.unwrap();
+        let acme = graph
+            .add_entity("Acme", "organization", None)
+            .await
+            .unwrap();
+        let apollo = graph.add_entity("Apollo", "project", None).await.unwrap();
+        graph
+            .link_entities(&alice, &bob, "knows", None)
+            .await
+            .unwrap();
+        graph
+            .link_entities(&bob, &carol, "knows", None)
+            .await
+            .unwrap();
+        graph
+            .link_entities(&bob, &acme, "works_at", None)
+            .await
+            .unwrap();
+        graph
+            .link_entities(&bob, &apollo, "involved_in", None)
+            .await
+            .unwrap();
+        (graph, temp)
+    }
+
+    #[tokio::test]
+    async fn test_query_graph_path() {
+        let (graph, _temp) = sample_graph().await;
+        let tool = QueryGraphTool::new(graph);
+
+        let result = tool
+            .execute(serde_json::json!({"mode": "path", "from": "alice", "to": "Carol"}))
+            .await
+            .unwrap();
+        assert!(result.contains("2 hop(s)"));
+        assert!(result.contains("Alice -[knows]-> Bob -[knows]-> Carol"));
+
+        let result = tool
+            .execute(serde_json::json!({
+                "mode": "path",
+                "from": "Alice",
+                "to": "Acme",
+                "relation_types": ["knows"]
+            }))
+            .await
+            .unwrap();
+        assert!(result.contains("No connection"));
+
+        let err = tool
+            .execute(serde_json::json!({"mode": "path", "from": "Alice", "to": "Zed"}))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("Zed"));
+    }
+
+    #[tokio::test]
+    async fn test_query_graph_neighborhood_and_pattern() {
+        let (graph, _temp) = sample_graph().await;
+        let tool = QueryGraphTool::new(graph);
+
+        let result = tool
+            .execute(serde_json::json!({
+                "mode": "neighborhood",
+                "entity": "Bob",
+                "direction": "outgoing"
+            }))
+            .await
+            .unwrap();
+        assert!(result.contains("Bob -[knows]-> Carol"));
+        assert!(result.contains("Bob -[works_at]-> Acme"));
+        assert!(!result.contains("Alice"));
+
+        let result = tool
+            .execute(serde_json::json!({
+                "mode": "pattern",
+                "pattern": "(proj:project)<-[involved_in]-(p:person)-[works_at]->(:organization {name: \"Acme\"})"
+            }))
+            .await
+            .unwrap();
+        assert!(result.contains("1 match(es)"));
+        assert!(result.contains("Apollo (project)"));
+        assert!(result.contains("p="));
+
+        let result = tool
+            .execute(serde_json::json!({
+                "mode": "pattern",
+                "pattern": "(a:person)--(b)",
+                "limit": 1
+            }))
+            .await
+            .unwrap();
+        assert!(result.contains("1 match(es)"));
+        assert!(result.contains("more matches omitted"));
+
+        assert!(
+            tool.execute(serde_json::json!({"mode": "pattern", "pattern": "(a"}))
+                .await
+                .is_err()
+        );
+        assert!(
+            tool.execute(serde_json::json!({"mode": "teleport"}))
+                .await
+                .is_err()
+        );
+    }
 }
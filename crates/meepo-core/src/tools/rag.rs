@@ -1,26 +1,33 @@
-//! RAG-enhanced tools: smart_recall and ingest_document
+//! RAG-enhanced tools: smart_recall, ingest_document and manage_corpus
 //!
 //! These tools leverage the new RAG features (hybrid search, GraphRAG,
-//! document chunking) to provide more powerful knowledge retrieval
-//! and document ingestion capabilities.
+//! community summaries, document chunking) to provide more powerful
+//! knowledge retrieval and document ingestion capabilities.
 
 use anyhow::{Context, Result};
 use async_trait::async_trait;
 use serde_json::Value;
+use std::path::PathBuf;
 use std::sync::Arc;
-use tracing::{debug, info};
+use tracing::debug;
 
 use super::{ToolHandler, json_schema};
-use meepo_knowledge::chunking::{
-    ChunkingConfig, DocumentMetadata, chunk_text, detect_content_type,
-};
+use meepo_knowledge::chunking::ChunkingConfig;
+use meepo_knowledge::communities::format_global_context;
+use meepo_knowledge::extract::ExtractorRegistry;
 use meepo_knowledge::graph_rag::{GraphRagConfig, format_graph_context, graph_expand};
-use meepo_knowledge::{KnowledgeDb, KnowledgeGraph};
+use meepo_knowledge::{
+    Corpus, CorpusSyncReport, IngestOptions, IngestPipeline, KnowledgeDb, KnowledgeGraph,
+};
+
+/// Member names shown per community in global recall
+const GLOBAL_MEMBER_NAMES: usize = 8;
 
 /// Smart recall tool that uses GraphRAG for relationship-aware retrieval.
 ///
 /// Unlike the basic `recall` tool, this traverses entity relationships
-/// to pull in contextually connected knowledge.
+/// to pull in contextually connected knowledge. In global mode it maps
+/// over community summaries instead, for questions about the whole corpus.
 pub struct SmartRecallTool {
     graph: Arc<KnowledgeGraph>,
     db: Arc<KnowledgeDb>,
@@ -40,6 +47,32 @@ impl SmartRecallTool {
         self.config = config;
         self
     }
+
+    /// Answer a corpus-wide question from community summaries
+    async fn global_recall(&self, query: &str, limit: usize) -> Result<String> {
+        debug!("Global recall for: {} (limit={})", query, limit);
+        let ranked = self
+            .graph
+            .global_search(query, limit)
+            .await
+            .context("Failed to search community summaries")?;
+
+        if ranked.is_empty() {
+            return Ok(
+                "No community summaries yet. They are built periodically from the \
+                       knowledge graph; try mode 'local' instead."
+                    .to_string(),
+            );
+        }
+
+        let mut output = format!(
+            "Global view: {} communit{} of related knowledge, most relevant first:\n\n",
+            ranked.len(),
+            if ranked.len() == 1 { "y" } else { "ies" }
+        );
+        output.push_str(&format_global_context(&ranked, GLOBAL_MEMBER_NAMES));
+        Ok(output)
+    }
 }
 
 #[async_trait]
@@ -51,7 +84,10 @@ impl ToolHandler for SmartRecallTool {
     fn description(&self) -> &str {
         "Search the knowledge graph with relationship-aware retrieval (GraphRAG). \
          Finds directly matching entities AND related knowledge by traversing \
-         entity relationships. Returns richer context than basic recall."
+         entity relationships. Returns richer context than basic recall. Use \
+         mode 'global' for broad questions about the whole knowledge base \
+         (e.g. main themes or areas of work); it returns summaries of the \
+         graph's communities of related entities."
     }
 
     fn input_schema(&self) -> Value {
@@ -68,6 +104,11 @@ impl ToolHandler for SmartRecallTool {
                 "max_hops": {
                     "type": "number",
                     "description": "Maximum relationship hops to traverse (default: 2)"
+                },
+                "mode": {
+                    "type": "string",
+                    "enum": ["local", "global"],
+                    "description": "'local' expands from matching entities (default); 'global' maps over community summaries for corpus-wide questions"
                 }
             }),
             vec!["query"],
@@ -81,16 +122,26 @@ impl ToolHandler for SmartRecallTool {
             .ok_or_else(|| anyhow::anyhow!("Missing 'query' parameter"))?;
         let limit = input.get("limit").and_then(|v| v.as_u64()).unwrap_or(5) as usize;
         let max_hops = input.get("max_hops").and_then(|v| v.as_u64()).unwrap_or(2) as usize;
+        match input
+            .get("mode")
+            .and_then(|v| v.as_str())
+            .unwrap_or("local")
+        {
+            "local" => {}
+            "global" => return self.global_recall(query, limit).await,
+            other => anyhow::bail!("Unknown mode '{}' (expected 'local' or 'global')", other),
+        }
 
         debug!(
             "Smart recall for: {} (limit={}, hops={})",
             query, limit, max_hops
         );
 
-        // Step 1: Search using Tantivy full-text search
+        // Step 1: Search using Tantivy full-text search, boosted by importance
         let search_results = self
             .graph
-            .search(query, limit)
+            .search_ranked(query, limit)
+            .await
             .context("Failed to search knowledge graph")?;
 
         if search_results.is_empty() {
@@ -133,25 +184,57 @@ impl ToolHandler for SmartRecallTool {
 }
 
 /// Ingest a document into the knowledge graph by chunking and indexing it.
+///
+/// Files are turned into text by the [`ExtractorRegistry`] first, so PDFs,
+/// Word documents, EPUBs, web pages and email keep their page numbers and
+/// headings, which end up in each chunk's metadata as a citation.
+/// Ingesting the same path again replaces the earlier document.
 pub struct IngestDocumentTool {
     graph: Arc<KnowledgeGraph>,
-    chunking_config: ChunkingConfig,
+    pipeline: IngestPipeline,
 }
 
 impl IngestDocumentTool {
     pub fn new(graph: Arc<KnowledgeGraph>) -> Self {
         Self {
             graph,
-            chunking_config: ChunkingConfig::default(),
+            pipeline: IngestPipeline::default(),
         }
     }
 
     pub fn with_chunking_config(mut self, config: ChunkingConfig) -> Self {
-        self.chunking_config = config;
+        self.pipeline.chunking = config;
+        self
+    }
+
+    pub fn with_extractors(mut self, extractors: Arc<ExtractorRegistry>) -> Self {
+        self.pipeline.extractors = extractors;
         self
     }
 }
 
+/// Expand a leading `~/` to the home directory
+fn expand_home(path: &str) -> PathBuf {
+    if let Some(rest) = path.strip_prefix("~/")
+        && let Some(home) = dirs::home_dir()
+    {
+        return home.join(rest);
+    }
+    PathBuf::from(path)
+}
+
+fn string_list(input: &Value, key: &str) -> Vec<String> {
+    input
+        .get(key)
+        .and_then(|v| v.as_array())
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
 #[async_trait]
 impl ToolHandler for IngestDocumentTool {
     fn name(&self) -> &str {
@@ -160,9 +243,12 @@ impl ToolHandler for IngestDocumentTool {
 
     fn description(&self) -> &str {
         "Ingest a document into the knowledge graph. The document is split into \
-         chunks and each chunk is indexed for later retrieval. Supports text files, \
-         markdown, code, and other text formats. Use this to build up the knowledge \
-         base from files."
+         chunks along its headings (or function and class boundaries for source \
+         code) and each chunk is indexed for later retrieval. Supports PDF, DOCX, \
+         EPUB, HTML, email (.eml/.mbox), markdown, code, and other text formats; \
+         page numbers and headings are kept so recalled chunks can be cited. \
+         Re-ingesting a file replaces its previous version. Use this to build up \
+         the knowledge base from files; use manage_corpus for whole directories."
     }
 
     fn input_schema(&self) -> Value {
@@ -191,134 +277,221 @@ impl ToolHandler for IngestDocumentTool {
             .get("path")
             .and_then(|v| v.as_str())
             .ok_or_else(|| anyhow::anyhow!("Missing 'path' parameter"))?;
-        let title = input.get("title").and_then(|v| v.as_str());
-        let tags: Vec<String> = input
-            .get("tags")
-            .and_then(|v| v.as_array())
-            .map(|arr| {
-                arr.iter()
-                    .filter_map(|v| v.as_str().map(|s| s.to_string()))
-                    .collect()
-            })
-            .unwrap_or_default();
-
-        // Expand ~ in path
-        let expanded_path = if let Some(rest) = path.strip_prefix("~/") {
-            if let Some(home) = dirs::home_dir() {
-                home.join(rest)
-            } else {
-                std::path::PathBuf::from(path)
-            }
-        } else {
-            std::path::PathBuf::from(path)
+        let options = IngestOptions {
+            title: input
+                .get("title")
+                .and_then(|v| v.as_str())
+                .map(str::to_string),
+            tags: string_list(&input, "tags"),
+            corpus: None,
         };
 
-        // Read the file
-        let content = tokio::fs::read_to_string(&expanded_path)
-            .await
-            .context(format!("Failed to read file: {}", expanded_path.display()))?;
-
-        if content.is_empty() {
+        let expanded_path = expand_home(path);
+        let Some(ingested) = self
+            .graph
+            .ingest_document(&expanded_path, &self.pipeline, &options)
+            .await?
+        else {
             return Ok("File is empty, nothing to ingest.".to_string());
-        }
-
-        let filename = expanded_path
-            .file_name()
-            .map(|n| n.to_string_lossy().to_string())
-            .unwrap_or_else(|| "unknown".to_string());
-
-        let doc_title = title.unwrap_or(&filename);
-        let content_type = detect_content_type(path);
+        };
 
-        info!(
-            "Ingesting document: {} ({} chars, {})",
-            doc_title,
-            content.len(),
-            content_type
+        let metadata = &ingested.metadata;
+        let mut output = format!(
+            "Ingested '{}': {} chunks created from {} chars ({})\nDocument ID: {}",
+            metadata.title.as_deref().unwrap_or("unknown"),
+            metadata.chunk_count,
+            metadata.total_chars,
+            metadata.content_type,
+            ingested.document_id
         );
+        if ingested.replaced > 0 {
+            output.push_str("\nReplaced the previously ingested version.");
+        }
+        Ok(output)
+    }
+}
 
-        // Chunk the document
-        let chunks = chunk_text(&content, &self.chunking_config);
+/// Register, sync, list and remove managed document corpora.
+///
+/// A corpus is a directory whose matching files are ingested and kept in
+/// sync: unchanged files are skipped, changed ones re-ingested and deleted
+/// ones removed. The daemon also re-syncs corpora when their files change.
+pub struct ManageCorpusTool {
+    graph: Arc<KnowledgeGraph>,
+    pipeline: IngestPipeline,
+}
 
-        // Create a parent document entity
-        let doc_metadata = serde_json::json!({
-            "source_path": path,
-            "content_type": content_type,
-            "total_chars": content.len(),
-            "chunk_count": chunks.len(),
-            "tags": tags,
-        });
+impl ManageCorpusTool {
+    pub fn new(graph: Arc<KnowledgeGraph>) -> Self {
+        Self {
+            graph,
+            pipeline: IngestPipeline::default(),
+        }
+    }
 
-        let doc_id = self
-            .graph
-            .add_entity(doc_title, "document", Some(doc_metadata))
-            .await
-            .context("Failed to create document entity")?;
-
-        // Index each chunk as a child entity linked to the document
-        let mut chunk_ids = Vec::new();
-        for chunk in &chunks {
-            let chunk_name = format!(
-                "{} [chunk {}/{}]",
-                doc_title,
-                chunk.chunk_index + 1,
-                chunk.total_chunks
-            );
+    pub fn with_pipeline(mut self, pipeline: IngestPipeline) -> Self {
+        self.pipeline = pipeline;
+        self
+    }
 
-            let chunk_metadata = serde_json::json!({
-                "full_content": chunk.content,
-                "chunk_index": chunk.chunk_index,
-                "start_offset": chunk.start_offset,
-                "end_offset": chunk.end_offset,
-                "total_chunks": chunk.total_chunks,
-                "parent_document": doc_id,
-            });
-
-            let chunk_id = self
-                .graph
-                .add_entity(&chunk_name, "document_chunk", Some(chunk_metadata))
-                .await
-                .context("Failed to create chunk entity")?;
+    async fn corpus(&self, input: &Value) -> Result<Corpus> {
+        let name = input
+            .get("name")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'name' parameter"))?;
+        self.graph
+            .get_corpus(name)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("No corpus named '{}'", name))
+    }
+}
 
-            // Link chunk to parent document
-            self.graph
-                .link_entities(&doc_id, &chunk_id, "contains_chunk", None)
-                .await
-                .context("Failed to link chunk to document")?;
+fn format_sync_report(name: &str, report: &CorpusSyncReport) -> String {
+    let mut output = format!("Synced corpus '{}': {}", name, report);
+    for (path, error) in report.failed.iter().take(10) {
+        output.push_str(&format!("\n  failed {}: {}", path, error));
+    }
+    output
+}
 
-            chunk_ids.push(chunk_id);
-        }
+#[async_trait]
+impl ToolHandler for ManageCorpusTool {
+    fn name(&self) -> &str {
+        "manage_corpus"
+    }
 
-        // Link consecutive chunks
-        for window in chunk_ids.windows(2) {
-            let _ = self
-                .graph
-                .link_entities(&window[0], &window[1], "next_chunk", None)
-                .await;
-        }
+    fn description(&self) -> &str {
+        "Manage document corpora: directories whose files are ingested into the \
+         knowledge graph and kept in sync as files are added, changed or deleted. \
+         Actions: 'add' registers a directory (with optional glob include/exclude \
+         patterns such as '*.pdf' or 'drafts/**') and ingests it, 'sync' re-syncs one \
+         corpus, 'list' shows corpora, 'remove' unregisters one and deletes its documents."
+    }
 
-        let metadata = DocumentMetadata {
-            source_path: Some(path.to_string()),
-            title: Some(doc_title.to_string()),
-            content_type: content_type.to_string(),
-            total_chars: content.len(),
-            chunk_count: chunks.len(),
-        };
+    fn input_schema(&self) -> Value {
+        json_schema(
+            serde_json::json!({
+                "action": {
+                    "type": "string",
+                    "enum": ["add", "sync", "list", "remove"],
+                    "description": "What to do"
+                },
+                "name": {
+                    "type": "string",
+                    "description": "Corpus name (required except for 'list')"
+                },
+                "path": {
+                    "type": "string",
+                    "description": "Directory to register (for 'add')"
+                },
+                "include": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Glob patterns of files to ingest (default: all files)"
+                },
+                "exclude": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Glob patterns of files to skip"
+                },
+                "keep_documents": {
+                    "type": "boolean",
+                    "description": "For 'remove': keep the ingested documents (default false)"
+                }
+            }),
+            vec!["action"],
+        )
+    }
 
-        Ok(format!(
-            "Ingested '{}': {} chunks created from {} chars ({})\nDocument ID: {}",
-            metadata.title.as_deref().unwrap_or("unknown"),
-            metadata.chunk_count,
-            metadata.total_chars,
-            metadata.content_type,
-            doc_id
-        ))
+    async fn execute(&self, input: Value) -> Result<String> {
+        let action = input
+            .get("action")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'action' parameter"))?;
+
+        match action {
+            "add" => {
+                let name = input
+                    .get("name")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| anyhow::anyhow!("Missing 'name' parameter"))?;
+                let path = input
+                    .get("path")
+                    .and_then(|v| v.as_str())
+                    .ok_or_else(|| anyhow::anyhow!("Missing 'path' parameter"))?;
+                let corpus = self
+                    .graph
+                    .add_corpus(
+                        name,
+                        &expand_home(path),
+                        string_list(&input, "include"),
+                        string_list(&input, "exclude"),
+                    )
+                    .await?;
+                let report = self.graph.sync_corpus(&corpus, &self.pipeline).await?;
+                Ok(format!(
+                    "Registered corpus '{}' at {}\n{}",
+                    corpus.name,
+                    corpus.root.display(),
+                    format_sync_report(&corpus.name, &report)
+                ))
+            }
+            "sync" => {
+                let corpus = self.corpus(&input).await?;
+                let report = self.graph.sync_corpus(&corpus, &self.pipeline).await?;
+                Ok(format_sync_report(&corpus.name, &report))
+            }
+            "list" => {
+                let corpora = self.graph.list_corpora().await?;
+                if corpora.is_empty() {
+                    return Ok("No corpora registered.".to_string());
+                }
+                let mut output = format!("{} corpora:\n", corpora.len());
+                for corpus in &corpora {
+                    let files = self.graph.list_corpus_files(corpus).await?;
+                    output.push_str(&format!(
+                        "- {}: {} ({} files; include {}; exclude {}; last synced {})\n",
+                        corpus.name,
+                        corpus.root.display(),
+                        files.len(),
+                        corpus.include.join(", "),
+                        if corpus.exclude.is_empty() {
+                            "none".to_string()
+                        } else {
+                            corpus.exclude.join(", ")
+                        },
+                        corpus
+                            .last_synced_at
+                            .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+                            .unwrap_or_else(|| "never".to_string())
+                    ));
+                }
+                Ok(output)
+            }
+            "remove" => {
+                let corpus = self.corpus(&input).await?;
+                let keep = input
+                    .get("keep_documents")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false);
+                let removed = self.graph.remove_corpus(&corpus, keep).await?;
+                Ok(format!(
+                    "Removed corpus '{}' ({} documents deleted)",
+                    corpus.name, removed
+                ))
+            }
+            other => Err(anyhow::anyhow!(
+                "Unknown action '{}': use add, sync, list or remove",
+                other
+            )),
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use meepo_knowledge::temporal::{FactType, Provenance, SourceType};
 
     #[test]
     fn test_smart_recall_schema() {
@@ -365,6 +538,47 @@ mod tests {
         assert!(result.contains("No matching"));
     }
 
+    #[tokio::test]
+    async fn test_smart_recall_global_mode() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let graph = Arc::new(
+            KnowledgeGraph::new(temp.path().join("test.db"), temp.path().join("idx")).unwrap(),
+        );
+        let db = graph.db();
+        let tool = SmartRecallTool::new(graph.clone(), db);
+
+        let empty = tool
+            .execute(serde_json::json!({"query": "main themes", "mode": "global"}))
+            .await
+            .unwrap();
+        assert!(empty.contains("No community summaries"));
+
+        let mut ids = Vec::new();
+        for name in ["Billing", "Stripe", "Invoices"] {
+            ids.push(graph.add_entity(name, "project", None).await.unwrap());
+        }
+        for (a, b) in [(0, 1), (1, 2), (0, 2)] {
+            graph
+                .link_entities(&ids[a], &ids[b], "related_to", None)
+                .await
+                .unwrap();
+        }
+        graph.refresh_communities(None).await.unwrap();
+
+        let result = tool
+            .execute(serde_json::json!({"query": "what are the main themes?", "mode": "global"}))
+            .await
+            .unwrap();
+        assert!(result.starts_with("Global view: 1 community"));
+        assert!(result.contains("3 members"));
+        assert!(result.contains("Billing"));
+
+        let bad = tool
+            .execute(serde_json::json!({"query": "x", "mode": "sideways"}))
+            .await;
+        assert!(bad.is_err());
+    }
+
     #[tokio::test]
     async fn test_ingest_nonexistent_file() {
         let temp = tempfile::TempDir::new().unwrap();
@@ -409,6 +623,12 @@ mod tests {
         assert!(result.contains("Ingested"));
         assert!(result.contains("Rust Guide"));
 
+        // The document entity points back at its file
+        let source = Provenance::new(SourceType::Document, test_file.display().to_string());
+        let facts = db.get_facts_from_source(&source).await.unwrap();
+        assert_eq!(facts.len(), 1);
+        assert_eq!(facts[0].fact_type, FactType::Entity);
+
         // Recall it
         let recall = SmartRecallTool::new(graph, db);
         let result = recall
@@ -417,4 +637,91 @@ mod tests {
             .unwrap();
         assert!(result.contains("Found"));
     }
+
+    #[tokio::test]
+    async fn test_ingest_html_cites_sections() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let graph = Arc::new(
+            KnowledgeGraph::new(temp.path().join("test.db"), temp.path().join("idx")).unwrap(),
+        );
+
+        let page = temp.path().join("page.html");
+        tokio::fs::write(
+            &page,
+            "<html><head><title>Field notes</title></head><body>\
+             <nav>Home | About</nav><article><h1>Birds</h1>\
+             <p>Robins nest in early spring along the river.</p></article></body></html>",
+        )
+        .await
+        .unwrap();
+
+        let ingest = IngestDocumentTool::new(graph.clone());
+        let result = ingest
+            .execute(serde_json::json!({"path": page.to_str().unwrap()}))
+            .await
+            .unwrap();
+        assert!(result.contains("Ingested 'Field notes'"));
+        assert!(result.contains("(text/html)"));
+
+        let chunks = graph
+            .db()
+            .get_entities_by_type(&["document_chunk".to_string()], 10)
+            .await
+            .unwrap();
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].name, "Field notes [chunk 1/1, §Birds]");
+        let metadata = chunks[0].metadata.as_ref().unwrap();
+        assert_eq!(metadata["section"], "Birds");
+        assert!(!metadata["full_content"].as_str().unwrap().contains("About"));
+    }
+
+    #[tokio::test]
+    async fn test_manage_corpus_actions() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let graph = Arc::new(
+            KnowledgeGraph::new(temp.path().join("test.db"), temp.path().join("idx")).unwrap(),
+        );
+        let docs = temp.path().join("docs");
+        std::fs::create_dir_all(&docs).unwrap();
+        std::fs::write(docs.join("a.md"), "# Alpha\n\nFirst.").unwrap();
+        std::fs::write(docs.join("skip.log"), "noise").unwrap();
+
+        let tool = ManageCorpusTool::new(graph.clone());
+        assert_eq!(tool.name(), "manage_corpus");
+        let added = tool
+            .execute(serde_json::json!({
+                "action": "add",
+                "name": "notes",
+                "path": docs.to_str().unwrap(),
+                "include": ["*.md"]
+            }))
+            .await
+            .unwrap();
+        assert!(added.contains("Registered corpus 'notes'"));
+        assert!(added.contains("1 added, 0 updated, 0 removed, 0 unchanged"));
+
+        let synced = tool
+            .execute(serde_json::json!({"action": "sync", "name": "notes"}))
+            .await
+            .unwrap();
+        assert!(synced.contains("0 added, 0 updated, 0 removed, 1 unchanged"));
+
+        let listed = tool
+            .execute(serde_json::json!({"action": "list"}))
+            .await
+            .unwrap();
+        assert!(listed.contains("- notes:"));
+        assert!(listed.contains("1 files; include *.md; exclude none"));
+
+        let removed = tool
+            .execute(serde_json::json!({"action": "remove", "name": "notes"}))
+            .await
+            .unwrap();
+        assert!(removed.contains("1 documents deleted"));
+        assert!(
+            tool.execute(serde_json::json!({"action": "sync", "name": "notes"}))
+                .await
+                .is_err()
+        );
+    }
 }
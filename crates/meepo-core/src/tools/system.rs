@@ -535,7 +535,7 @@ fn validate_url(url_str: &str) -> Result<ValidatedUrl> {
 }
 
 /// Convenience wrapper that discards resolved IPs (for redirect checks, etc.)
-fn is_safe_url(url_str: &str) -> Result<()> {
+pub(crate) fn is_safe_url(url_str: &str) -> Result<()> {
     validate_url(url_str).map(|_| ())
 }
 
@@ -765,6 +765,68 @@ impl BrowseUrlTool {
     }
 }
 
+/// Fetches pages for page and feed watchers with the same SSRF protection as
+/// `browse_url`.
+///
+/// Every hop of a redirect chain is validated and its resolved IPs pinned.
+pub struct SafePageFetcher;
+
+#[async_trait]
+impl meepo_scheduler::PageFetcher for SafePageFetcher {
+    async fn get(&self, url: &str, headers: &[(&str, &str)]) -> Result<reqwest::Response> {
+        get_validated(url, headers, validate_url).await
+    }
+}
+
+/// GET `url`, following up to five redirects by hand so that `validate` runs
+/// on every hop and the connection goes to the IPs it resolved
+async fn get_validated(
+    url: &str,
+    headers: &[(&str, &str)],
+    validate: impl Fn(&str) -> Result<ValidatedUrl>,
+) -> Result<reqwest::Response> {
+    const MAX_REDIRECTS: usize = 5;
+
+    let mut current_url = url.to_string();
+    for _ in 0..=MAX_REDIRECTS {
+        let validated = validate(&current_url)?;
+        let mut builder = reqwest::Client::builder()
+            .user_agent("meepo-agent/1.0")
+            .timeout(std::time::Duration::from_secs(30))
+            .redirect(reqwest::redirect::Policy::none());
+        for addr in &validated.resolved_ips {
+            builder = builder.resolve(&validated.host, *addr);
+        }
+        let client = builder.build().context("Failed to create HTTP client")?;
+
+        let mut request = client.get(&current_url);
+        for (name, value) in headers {
+            request = request.header(*name, *value);
+        }
+        let response = request
+            .send()
+            .await
+            .with_context(|| format!("Failed to fetch {}", current_url))?;
+
+        let status = response.status();
+        if status.is_redirection() && status != reqwest::StatusCode::NOT_MODIFIED {
+            let location = response
+                .headers()
+                .get("location")
+                .and_then(|v| v.to_str().ok())
+                .context("Redirect without Location header")?;
+            current_url = url::Url::parse(&current_url)
+                .and_then(|base| base.join(location))
+                .context("Invalid redirect URL")?
+                .to_string();
+            continue;
+        }
+        return Ok(response);
+    }
+
+    anyhow::bail!("Too many redirects fetching {}", url)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1149,4 +1211,58 @@ mod tests {
         let err_msg = result.unwrap_err().to_string().to_lowercase();
         assert!(err_msg.contains("private") || err_msg.contains("not allowed"));
     }
+
+    #[tokio::test]
+    async fn test_safe_page_fetcher_blocks_unsafe_urls() {
+        use meepo_scheduler::PageFetcher;
+
+        for url in [
+            "http://localhost:8080/admin",
+            "http://192.168.1.1/router",
+            "http://169.254.169.254/latest/meta-data/",
+            "file:///etc/passwd",
+        ] {
+            assert!(SafePageFetcher.fetch(url).await.is_err(), "{} allowed", url);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_redirect_to_private_address_is_blocked() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        // A "public" feed that redirects to the cloud metadata endpoint
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let mut buf = [0u8; 1024];
+                let _ = socket.read(&mut buf).await;
+                let _ = socket
+                    .write_all(
+                        b"HTTP/1.1 302 Found\r\n\
+                          Location: http://169.254.169.254/latest/meta-data/\r\n\
+                          Content-Length: 0\r\nConnection: close\r\n\r\n",
+                    )
+                    .await;
+            }
+        });
+
+        // Allow the local server itself; every other hop gets the real policy
+        let start = format!("http://{}/feed.xml", addr);
+        let allowed = start.clone();
+        let result = get_validated(&start, &[("Accept", "application/rss+xml")], |url| {
+            if url == allowed {
+                Ok(ValidatedUrl {
+                    host: addr.ip().to_string(),
+                    resolved_ips: vec![],
+                })
+            } else {
+                validate_url(url)
+            }
+        })
+        .await;
+
+        let err = result.expect_err("redirect to a private address was followed");
+        assert!(err.to_string().contains("not allowed"), "{}", err);
+    }
 }
@@ -3,38 +3,46 @@
 use anyhow::{Context, Result};
 use async_trait::async_trait;
 use serde_json::Value;
-use std::sync::Arc;
 use tokio::sync::mpsc;
 use tracing::{debug, warn};
 
 use super::{ToolHandler, json_schema};
-use meepo_knowledge::KnowledgeDb;
+use meepo_scheduler::schedule::{self, CronSchedule, ParsedSchedule, Tz};
+use meepo_scheduler::{
+    EventRules, HealthStatus, HealthStore, Watcher, WatcherHealth, WatcherKind, WatcherStore,
+};
 
 /// Commands to send to the watcher scheduler
+///
+/// The tools save watchers to the `WatcherStore` before sending these; the
+/// scheduler only starts and stops them.
 #[derive(Debug, Clone)]
 pub enum WatcherCommand {
-    Create {
-        id: String,
-        kind: String,
-        config: Value,
-        action: String,
-        reply_channel: String,
-    },
+    Create { watcher: Box<Watcher> },
     List,
-    Cancel {
-        id: String,
-    },
+    Cancel { id: String },
 }
 
 /// Create a new watcher
 pub struct CreateWatcherTool {
-    db: Arc<KnowledgeDb>,
+    store: WatcherStore,
     command_tx: mpsc::Sender<WatcherCommand>,
+    timezone: Tz,
 }
 
 impl CreateWatcherTool {
-    pub fn new(db: Arc<KnowledgeDb>, command_tx: mpsc::Sender<WatcherCommand>) -> Self {
-        Self { db, command_tx }
+    pub fn new(store: WatcherStore, command_tx: mpsc::Sender<WatcherCommand>) -> Self {
+        Self {
+            store,
+            command_tx,
+            timezone: Tz::UTC,
+        }
+    }
+
+    /// The user's timezone, for schedules that do not name one
+    pub fn with_timezone(mut self, timezone: Tz) -> Self {
+        self.timezone = timezone;
+        self
     }
 }
 
@@ -46,7 +54,10 @@ impl ToolHandler for CreateWatcherTool {
 
     fn description(&self) -> &str {
         "Create a new watcher to monitor for specific events. \
-         Watchers can monitor emails, calendar events, files, GitHub, etc."
+         Watchers can monitor emails, calendar events, files, GitHub, RSS/Atom feeds, \
+         web pages (reporting a diff when the selected content changes), signed inbound \
+         webhooks on the gateway, etc. Optional rules filter, debounce, batch or rate-limit \
+         events before they reach you, so noisy sources don't trigger on every event."
     }
 
     fn input_schema(&self) -> Value {
@@ -54,11 +65,11 @@ impl ToolHandler for CreateWatcherTool {
             serde_json::json!({
                 "kind": {
                     "type": "string",
-                    "description": "Type of watcher: 'email', 'calendar', 'file', 'github', 'time'"
+                    "description": "Type of watcher: 'email', 'calendar', 'file', 'github', 'feed', 'page', 'webhook', 'time' (or 'scheduled'), 'oneshot'"
                 },
                 "config": {
                     "type": "object",
-                    "description": "Configuration specific to the watcher type (e.g., file path, email filters)"
+                    "description": "Configuration specific to the watcher type (e.g., file path, email filters, for 'feed': {\"url\": ..., \"keywords\": [...], \"interval_secs\": 1800}, for 'page': {\"url\": ..., \"selector\": \"CSS selector\", \"region_start\": ..., \"region_end\": ..., \"change_threshold\": 0.0-1.0}, for 'webhook': {\"path\": ..., \"scheme\": \"github\"|\"stripe\"|\"generic\", \"secret\": ... (generated if omitted)}). Scheduled and one-shot watchers accept \"misfire\": {\"action\": \"skip\"|\"run_once\"|\"run_all\", \"max_runs\": 5, \"grace_secs\": 300} for runs missed while the computer was off or asleep (default: run once). Time-based watchers take a \"task\" and either \"cron_expr\" or \"schedule\": a phrase like \"every weekday at 8:30\", \"first monday of the month\" or \"in 2 hours\", plus an optional IANA \"timezone\" (default: the user's timezone)"
                 },
                 "action": {
                     "type": "string",
@@ -67,6 +78,10 @@ impl ToolHandler for CreateWatcherTool {
                 "reply_channel": {
                     "type": "string",
                     "description": "Channel to send notifications to (e.g., 'slack', 'discord', 'internal')"
+                },
+                "rules": {
+                    "type": "object",
+                    "description": "Optional event rules. \"filter\": expression over the event payload, e.g. \"from == 'boss@example.com' and subject ~ '(?i)invoice'\" or \"action in ['opened', 'reopened'] && labels contains 'bug'\" (operators: == != < <= > >= ~ !~ contains in and or not). \"debounce_secs\": wait until events stop for this long and keep the latest. \"batch_window_secs\": collect events for this long after the first and deliver one summary. \"rate_limit\": {\"max_events\": 5, \"per_secs\": 3600}"
                 }
             }),
             vec!["kind", "config", "action", "reply_channel"],
@@ -74,11 +89,11 @@ impl ToolHandler for CreateWatcherTool {
     }
 
     async fn execute(&self, input: Value) -> Result<String> {
-        let kind = input
+        let mut kind = input
             .get("kind")
             .and_then(|v| v.as_str())
             .ok_or_else(|| anyhow::anyhow!("Missing 'kind' parameter"))?;
-        let config = input
+        let mut config = input
             .get("config")
             .ok_or_else(|| anyhow::anyhow!("Missing 'config' parameter"))?
             .clone();
@@ -98,40 +113,317 @@ impl ToolHandler for CreateWatcherTool {
             ));
         }
 
+        let rules: EventRules = match input.get("rules") {
+            Some(rules) if !rules.is_null() => {
+                serde_json::from_value(rules.clone()).context("Invalid 'rules' parameter")?
+            }
+            _ => EventRules::default(),
+        };
+        rules.validate()?;
+
+        if matches!(kind, "page" | "feed" | "rss") {
+            let url = config
+                .get("url")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| anyhow::anyhow!("{} watchers need a 'url' in config", kind))?;
+            crate::tools::system::is_safe_url(url)?;
+        }
+
+        if kind == "webhook" {
+            prepare_webhook_config(&mut config)?;
+        }
+
+        let mut next_runs = None;
+        if matches!(kind, "time" | "scheduled" | "oneshot") {
+            let (schedule_kind, preview) =
+                prepare_schedule_config(kind, &mut config, action, self.timezone)?;
+            kind = schedule_kind;
+            next_runs = Some(preview);
+        }
+
         debug!("Creating watcher: {} -> {}", kind, action);
 
-        // Store in database
-        let watcher_id = self
-            .db
-            .insert_watcher(kind, config.clone(), action, reply_channel)
+        let mut watcher = Watcher::new(
+            WatcherKind::from_config(kind, config.clone())?,
+            action.to_string(),
+            reply_channel.to_string(),
+        )
+        .with_rules(rules.clone());
+        watcher.id = format!("w-{}", uuid::Uuid::new_v4());
+        let watcher_id = watcher.id.clone();
+
+        self.store
+            .save(&watcher)
             .await
-            .context("Failed to create watcher in database")?;
+            .context("Failed to save watcher")?;
 
-        // Send command to scheduler (include ID so the runner uses the same one)
+        // Send command to scheduler to start it
         self.command_tx
             .send(WatcherCommand::Create {
-                id: watcher_id.clone(),
-                kind: kind.to_string(),
-                config,
-                action: action.to_string(),
-                reply_channel: reply_channel.to_string(),
+                watcher: Box::new(watcher.clone()),
             })
             .await
             .context("Failed to send command to scheduler")?;
 
-        Ok(format!("Created watcher with ID: {}", watcher_id))
+        let rules_note = if rules.is_empty() {
+            String::new()
+        } else {
+            format!("\nEvent rules: {}", rules.describe())
+        };
+
+        if let Some(path) = watcher.webhook_path() {
+            return Ok(format!(
+                "Created watcher with ID: {}{}\n\
+                 Send signed POST requests to /hooks/{} on the gateway.\n\
+                 Signing secret: {}",
+                watcher_id,
+                rules_note,
+                path,
+                config["secret"].as_str().unwrap_or_default()
+            ));
+        }
+
+        if let Some(next_runs) = next_runs {
+            return Ok(format!(
+                "Created watcher with ID: {}{}\n{}",
+                watcher_id, rules_note, next_runs
+            ));
+        }
+
+        Ok(format!(
+            "Created watcher with ID: {}{}",
+            watcher_id, rules_note
+        ))
+    }
+}
+
+/// Check a time-based watcher's config, turning a "schedule" phrase into a
+/// cron expression or one-shot time. Returns the watcher kind to create and
+/// a preview of its next runs.
+fn prepare_schedule_config(
+    kind: &str,
+    config: &mut Value,
+    action: &str,
+    default_tz: Tz,
+) -> Result<(&'static str, String)> {
+    let Some(config) = config.as_object_mut() else {
+        anyhow::bail!("Time-based watcher config must be an object");
+    };
+    let tz = match config.get("timezone").and_then(|v| v.as_str()) {
+        Some(name) => schedule::resolve_timezone(name)?,
+        None => default_tz,
+    };
+    if !config.contains_key("task") {
+        config.insert("task".to_string(), Value::String(action.to_string()));
+    }
+
+    let now = chrono::Utc::now();
+    let parsed = if let Some(text) = config.remove("schedule") {
+        let text = text
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("'schedule' must be a string"))?;
+        schedule::parse_schedule(text, now, tz)?
+    } else if kind == "oneshot" {
+        let at = config.get("at").cloned().ok_or_else(|| {
+            anyhow::anyhow!("One-shot watchers need 'at' or 'schedule' in config")
+        })?;
+        let at: chrono::DateTime<chrono::Utc> = serde_json::from_value(at)
+            .context("'at' must be an RFC 3339 time, e.g. 2026-03-04T09:00:00Z")?;
+        ParsedSchedule::Once(at)
+    } else {
+        let expr = config
+            .get("cron_expr")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| {
+                anyhow::anyhow!("Scheduled watchers need 'cron_expr' or 'schedule' in config")
+            })?;
+        CronSchedule::parse(expr, tz)?;
+        ParsedSchedule::Cron(expr.to_string())
+    };
+
+    let preview = describe_schedule(&parsed, now, tz, 3)?;
+    match parsed {
+        ParsedSchedule::Cron(expr) => {
+            config.insert("cron_expr".to_string(), Value::String(expr));
+            Ok(("scheduled", preview))
+        }
+        ParsedSchedule::Once(at) => {
+            config.remove("cron_expr");
+            config.remove("timezone");
+            config.insert("at".to_string(), serde_json::to_value(at)?);
+            Ok(("oneshot", preview))
+        }
+    }
+}
+
+/// A schedule and its next `count` runs in `tz`, for the user to check
+fn describe_schedule(
+    parsed: &ParsedSchedule,
+    now: chrono::DateTime<chrono::Utc>,
+    tz: Tz,
+    count: usize,
+) -> Result<String> {
+    let runs = parsed.preview(now, tz, count)?;
+    let mut output = match parsed {
+        ParsedSchedule::Cron(expr) => format!("Cron: {} ({})\n", expr, tz),
+        ParsedSchedule::Once(_) => format!("Runs once ({})\n", tz),
+    };
+    if runs.is_empty() {
+        output.push_str("No upcoming runs.");
+        return Ok(output);
+    }
+    output.push_str("Next runs:");
+    for at in runs {
+        output.push_str(&format!(
+            "\n- {}",
+            at.with_timezone(&tz).format("%a %Y-%m-%d %H:%M %Z")
+        ));
+    }
+    Ok(output)
+}
+
+/// Show when a schedule would run, before creating a watcher with it
+pub struct PreviewScheduleTool {
+    timezone: Tz,
+}
+
+impl PreviewScheduleTool {
+    pub fn new(timezone: Tz) -> Self {
+        Self { timezone }
+    }
+}
+
+#[async_trait]
+impl ToolHandler for PreviewScheduleTool {
+    fn name(&self) -> &str {
+        "preview_schedule"
+    }
+
+    fn description(&self) -> &str {
+        "Check a schedule before creating a time-based watcher. Accepts a phrase \
+         ('every weekday at 8:30', 'first monday of the month at 9', 'every 15 minutes', \
+         'tomorrow at noon', 'in 2 hours') or a cron expression, and shows the cron \
+         expression it becomes and its next run times."
+    }
+
+    fn input_schema(&self) -> Value {
+        json_schema(
+            serde_json::json!({
+                "schedule": {
+                    "type": "string",
+                    "description": "Schedule phrase or cron expression"
+                },
+                "timezone": {
+                    "type": "string",
+                    "description": "IANA timezone, e.g. 'Europe/Paris' (default: the user's timezone)"
+                },
+                "count": {
+                    "type": "integer",
+                    "description": "How many upcoming runs to show (default: 5, max: 20)"
+                }
+            }),
+            vec!["schedule"],
+        )
+    }
+
+    async fn execute(&self, input: Value) -> Result<String> {
+        let text = input
+            .get("schedule")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'schedule' parameter"))?;
+        let tz = match input.get("timezone").and_then(|v| v.as_str()) {
+            Some(name) => schedule::resolve_timezone(name)?,
+            None => self.timezone,
+        };
+        let count = input
+            .get("count")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(5)
+            .clamp(1, 20) as usize;
+
+        let now = chrono::Utc::now();
+        let parsed = schedule::parse_schedule(text, now, tz)?;
+        describe_schedule(&parsed, now, tz, count)
+    }
+}
+
+/// Check a webhook watcher's config, generating a signing secret if none was given
+fn prepare_webhook_config(config: &mut Value) -> Result<()> {
+    let Some(config) = config.as_object_mut() else {
+        anyhow::bail!("Webhook watcher config must be an object");
+    };
+    if let Some(scheme) = config.get("scheme").and_then(|v| v.as_str())
+        && !matches!(scheme, "github" | "stripe" | "generic")
+    {
+        anyhow::bail!(
+            "Unknown webhook scheme '{}' (use 'github', 'stripe' or 'generic')",
+            scheme
+        );
+    }
+    let has_secret = config
+        .get("secret")
+        .and_then(|v| v.as_str())
+        .is_some_and(|s| !s.is_empty());
+    if !has_secret {
+        let secret = format!(
+            "{}{}",
+            uuid::Uuid::new_v4().simple(),
+            uuid::Uuid::new_v4().simple()
+        );
+        config.insert("secret".to_string(), Value::String(secret));
     }
+    Ok(())
 }
 
 /// List active watchers
 pub struct ListWatchersTool {
-    db: Arc<KnowledgeDb>,
+    store: WatcherStore,
+    health: Option<HealthStore>,
 }
 
 impl ListWatchersTool {
-    pub fn new(db: Arc<KnowledgeDb>) -> Self {
-        Self { db }
+    pub fn new(store: WatcherStore) -> Self {
+        Self {
+            store,
+            health: None,
+        }
+    }
+
+    /// Include run health from the scheduler and list auto-disabled watchers
+    pub fn with_health_store(mut self, health: HealthStore) -> Self {
+        self.health = Some(health);
+        self
+    }
+}
+
+/// Status, last success, last error and next run of a watcher
+fn format_health(health: &WatcherHealth) -> String {
+    let time = |at: Option<chrono::DateTime<chrono::Utc>>| {
+        at.map(|at| at.format("%Y-%m-%d %H:%M UTC").to_string())
+            .unwrap_or_else(|| "never".to_string())
+    };
+    let mut output = format!("  Status: {}", health.status);
+    if health.consecutive_errors > 0 {
+        output.push_str(&format!(
+            " ({} consecutive errors)",
+            health.consecutive_errors
+        ));
+    }
+    output.push_str(&format!(
+        "\n  Last success: {}\n",
+        time(health.last_success)
+    ));
+    if let Some(ref error) = health.last_error {
+        output.push_str(&format!(
+            "  Last error: {} ({})\n",
+            error,
+            time(health.last_error_at)
+        ));
     }
+    if health.status != HealthStatus::Disabled {
+        output.push_str(&format!("  Next run: {}\n", time(health.next_run)));
+    }
+    output
 }
 
 #[async_trait]
@@ -141,7 +433,8 @@ impl ToolHandler for ListWatchersTool {
     }
 
     fn description(&self) -> &str {
-        "List all currently active watchers and their configurations."
+        "List all currently active watchers with their configurations and health \
+         (last success, last error, next run), plus watchers disabled after repeated failures."
     }
 
     fn input_schema(&self) -> Value {
@@ -152,23 +445,57 @@ impl ToolHandler for ListWatchersTool {
         debug!("Listing active watchers");
 
         let watchers = self
-            .db
-            .get_active_watchers()
+            .store
+            .active()
             .await
             .context("Failed to get active watchers")?;
 
-        if watchers.is_empty() {
+        let health = match self.health {
+            Some(ref store) => store.watchers().await.unwrap_or_else(|e| {
+                warn!("Failed to read watcher health: {}", e);
+                Vec::new()
+            }),
+            None => Vec::new(),
+        };
+        let disabled: Vec<&WatcherHealth> = health
+            .iter()
+            .filter(|h| h.status == HealthStatus::Disabled)
+            .collect();
+
+        if watchers.is_empty() && disabled.is_empty() {
             return Ok("No active watchers.".to_string());
         }
 
         let mut output = format!("Active watchers ({}):\n\n", watchers.len());
         for watcher in watchers {
             output.push_str(&format!("- ID: {}\n", watcher.id));
-            output.push_str(&format!("  Kind: {}\n", watcher.kind));
+            output.push_str(&format!("  Kind: {}\n", watcher.description()));
             output.push_str(&format!("  Action: {}\n", watcher.action));
             output.push_str(&format!("  Channel: {}\n", watcher.reply_channel));
-            output.push_str(&format!("  Config: {}\n", watcher.config));
-            output.push_str(&format!("  Created: {}\n\n", watcher.created_at));
+            if !watcher.rules.is_empty() {
+                output.push_str(&format!("  Rules: {}\n", watcher.rules.describe()));
+            }
+            output.push_str(&format!("  Created: {}\n", watcher.created_at));
+            if let Some(h) = health.iter().find(|h| h.watcher_id == watcher.id) {
+                output.push_str(&format_health(h));
+            }
+            output.push('\n');
+        }
+
+        if !disabled.is_empty() {
+            output.push_str(&format!(
+                "Disabled after repeated failures ({}):\n\n",
+                disabled.len()
+            ));
+            for h in disabled {
+                output.push_str(&format!("- ID: {}\n", h.watcher_id));
+                if let Ok(Some(watcher)) = self.store.get(&h.watcher_id).await {
+                    output.push_str(&format!("  Kind: {}\n", watcher.description()));
+                    output.push_str(&format!("  Action: {}\n", watcher.action));
+                }
+                output.push_str(&format_health(h));
+                output.push('\n');
+            }
         }
 
         Ok(output)
@@ -177,13 +504,13 @@ impl ToolHandler for ListWatchersTool {
 
 /// Cancel/deactivate a watcher
 pub struct CancelWatcherTool {
-    db: Arc<KnowledgeDb>,
+    store: WatcherStore,
     command_tx: mpsc::Sender<WatcherCommand>,
 }
 
 impl CancelWatcherTool {
-    pub fn new(db: Arc<KnowledgeDb>, command_tx: mpsc::Sender<WatcherCommand>) -> Self {
-        Self { db, command_tx }
+    pub fn new(store: WatcherStore, command_tx: mpsc::Sender<WatcherCommand>) -> Self {
+        Self { store, command_tx }
     }
 }
 
@@ -218,10 +545,14 @@ impl ToolHandler for CancelWatcherTool {
         debug!("Canceling watcher: {}", watcher_id);
 
         // Deactivate in database
-        self.db
-            .update_watcher_active(watcher_id, false)
+        let found = self
+            .store
+            .deactivate(watcher_id)
             .await
             .context("Failed to deactivate watcher")?;
+        if !found {
+            anyhow::bail!("No watcher with ID: {}", watcher_id);
+        }
 
         // Send cancel command to scheduler
         self.command_tx
@@ -243,24 +574,28 @@ impl ToolHandler for CancelWatcherTool {
 mod tests {
     use super::*;
     use crate::tools::ToolHandler;
-    use tempfile::TempDir;
+    use meepo_scheduler::persistence;
+    use std::sync::{Arc, Mutex};
+
+    fn connection() -> Arc<Mutex<rusqlite::Connection>> {
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        persistence::init_watcher_tables(&conn).unwrap();
+        Arc::new(Mutex::new(conn))
+    }
 
     fn setup() -> (
-        Arc<meepo_knowledge::KnowledgeDb>,
+        WatcherStore,
         mpsc::Sender<WatcherCommand>,
         mpsc::Receiver<WatcherCommand>,
-        TempDir,
     ) {
-        let temp = TempDir::new().unwrap();
-        let db = Arc::new(meepo_knowledge::KnowledgeDb::new(&temp.path().join("test.db")).unwrap());
         let (tx, rx) = mpsc::channel(100);
-        (db, tx, rx, temp)
+        (WatcherStore::new(connection()), tx, rx)
     }
 
     #[test]
     fn test_create_watcher_schema() {
-        let (db, tx, _rx, _temp) = setup();
-        let tool = CreateWatcherTool::new(db, tx);
+        let (store, tx, _rx) = setup();
+        let tool = CreateWatcherTool::new(store, tx);
         assert_eq!(tool.name(), "create_watcher");
         assert!(!tool.description().is_empty());
         let schema = tool.input_schema();
@@ -269,22 +604,22 @@ mod tests {
 
     #[test]
     fn test_list_watchers_schema() {
-        let (db, _tx, _rx, _temp) = setup();
-        let tool = ListWatchersTool::new(db);
+        let (store, _tx, _rx) = setup();
+        let tool = ListWatchersTool::new(store);
         assert_eq!(tool.name(), "list_watchers");
     }
 
     #[test]
     fn test_cancel_watcher_schema() {
-        let (db, tx, _rx, _temp) = setup();
-        let tool = CancelWatcherTool::new(db, tx);
+        let (store, tx, _rx) = setup();
+        let tool = CancelWatcherTool::new(store, tx);
         assert_eq!(tool.name(), "cancel_watcher");
     }
 
     #[tokio::test]
     async fn test_list_watchers_empty() {
-        let (db, _tx, _rx, _temp) = setup();
-        let tool = ListWatchersTool::new(db);
+        let (store, _tx, _rx) = setup();
+        let tool = ListWatchersTool::new(store);
         let result = tool.execute(serde_json::json!({})).await.unwrap();
         assert!(
             result.contains("No")
@@ -296,9 +631,10 @@ mod tests {
 
     #[tokio::test]
     async fn test_create_and_list_watcher() {
-        let (db, tx, _rx, _temp) = setup();
-        let create = CreateWatcherTool::new(db.clone(), tx);
-        let list = ListWatchersTool::new(db);
+        let (store, tx, _rx) = setup();
+        let create = CreateWatcherTool::new(store.clone(), tx.clone());
+        let list = ListWatchersTool::new(store.clone());
+        let cancel = CancelWatcherTool::new(store, tx);
 
         let result = create
             .execute(serde_json::json!({
@@ -314,6 +650,271 @@ mod tests {
         );
 
         let result = list.execute(serde_json::json!({})).await.unwrap();
-        assert!(result.contains("test") || result.contains("Run"));
+        assert!(result.contains("Scheduled task 'test task' (cron: 0 * * * *)"));
+        assert!(result.contains("Action: Run a test"));
+
+        let id = result
+            .lines()
+            .find_map(|line| line.strip_prefix("- ID: "))
+            .unwrap()
+            .to_string();
+        let result = cancel
+            .execute(serde_json::json!({"watcher_id": id}))
+            .await
+            .unwrap();
+        assert!(result.contains(&id));
+        let result = list.execute(serde_json::json!({})).await.unwrap();
+        assert_eq!(result, "No active watchers.");
+        assert!(
+            cancel
+                .execute(serde_json::json!({"watcher_id": "w-missing"}))
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_list_watchers_with_health() {
+        let db = connection();
+        let store = WatcherStore::new(db.clone());
+        let mut ids = Vec::new();
+        for action in ["Check the build", "Watch the issues"] {
+            let watcher = Watcher::new(
+                WatcherKind::Scheduled {
+                    cron_expr: "0 * * * *".to_string(),
+                    task: action.to_string(),
+                    timezone: None,
+                    misfire: Default::default(),
+                },
+                action.to_string(),
+                "internal".to_string(),
+            );
+            store.save(&watcher).await.unwrap();
+            ids.push(watcher.id);
+        }
+        {
+            let conn = db.lock().unwrap();
+            let now = chrono::Utc::now();
+            persistence::record_watcher_failure(&conn, &ids[0], "HTTP 502", now).unwrap();
+            persistence::record_watcher_failure(&conn, &ids[1], "HTTP 401", now).unwrap();
+            persistence::disable_watcher(&conn, &ids[1], now).unwrap();
+        }
+
+        let tool = ListWatchersTool::new(store).with_health_store(HealthStore::new(db));
+        let result = tool.execute(serde_json::json!({})).await.unwrap();
+        let (active, disabled) = result.split_once("Disabled after").unwrap();
+        assert!(active.contains("Check the build"));
+        assert!(active.contains("Status: failing (1 consecutive errors)"));
+        assert!(active.contains("Last error: HTTP 502"));
+        assert!(active.contains("Last success: never"));
+        assert!(disabled.contains("Watch the issues"));
+        assert!(disabled.contains("Status: disabled"));
+        assert!(disabled.contains("Last error: HTTP 401"));
+        assert!(!disabled.contains("Next run"));
+    }
+
+    #[tokio::test]
+    async fn test_create_page_watcher_rejects_private_urls() {
+        let (store, tx, mut rx) = setup();
+        let create = CreateWatcherTool::new(store, tx);
+
+        let result = create
+            .execute(serde_json::json!({
+                "kind": "page",
+                "config": {"url": "http://192.168.1.1/status"},
+                "action": "Tell me when it changes",
+                "reply_channel": "internal"
+            }))
+            .await;
+        assert!(result.is_err());
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_create_feed_watcher_rejects_private_urls() {
+        let (store, tx, mut rx) = setup();
+        let create = CreateWatcherTool::new(store, tx);
+
+        for kind in ["feed", "rss"] {
+            let result = create
+                .execute(serde_json::json!({
+                    "kind": kind,
+                    "config": {"url": "http://169.254.169.254/latest/meta-data/"},
+                    "action": "Summarize new items",
+                    "reply_channel": "internal"
+                }))
+                .await;
+            assert!(result.is_err(), "{} watcher accepted", kind);
+        }
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_create_webhook_watcher_generates_secret() {
+        let (store, tx, mut rx) = setup();
+        let create = CreateWatcherTool::new(store, tx);
+
+        let result = create
+            .execute(serde_json::json!({
+                "kind": "webhook",
+                "config": {"path": "ci/builds", "scheme": "generic"},
+                "action": "Summarize failed builds",
+                "reply_channel": "internal"
+            }))
+            .await
+            .unwrap();
+        assert!(result.contains("/hooks/ci/builds"));
+
+        let Some(WatcherCommand::Create { watcher }) = rx.try_recv().ok() else {
+            panic!("expected a create command");
+        };
+        let WatcherKind::Webhook { ref secret, .. } = watcher.kind else {
+            panic!("expected a webhook watcher");
+        };
+        assert_eq!(secret.len(), 64);
+        assert!(result.contains(secret.as_str()));
+
+        let bad_scheme = create
+            .execute(serde_json::json!({
+                "kind": "webhook",
+                "config": {"scheme": "gitlab"},
+                "action": "x",
+                "reply_channel": "internal"
+            }))
+            .await;
+        assert!(bad_scheme.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_create_watcher_with_rules() {
+        let (store, tx, mut rx) = setup();
+        let create = CreateWatcherTool::new(store.clone(), tx);
+
+        let result = create
+            .execute(serde_json::json!({
+                "kind": "email",
+                "config": {"interval_secs": 300},
+                "action": "Summarize invoices",
+                "reply_channel": "internal",
+                "rules": {
+                    "filter": "subject ~ '(?i)invoice'",
+                    "batch_window_secs": 600
+                }
+            }))
+            .await
+            .unwrap();
+        assert!(result.contains("Event rules: filter `subject ~ '(?i)invoice'`, batched per 600s"));
+        let Some(WatcherCommand::Create { watcher }) = rx.try_recv().ok() else {
+            panic!("expected a create command");
+        };
+        assert_eq!(watcher.rules.batch_window_secs, 600);
+        assert_eq!(
+            store.get(&watcher.id).await.unwrap().unwrap().rules,
+            watcher.rules
+        );
+
+        for rules in [
+            serde_json::json!({"filter": "subject = 'x'"}),
+            serde_json::json!({"debounce": 5}),
+            serde_json::json!({"rate_limit": {"max_events": 0, "per_secs": 60}}),
+        ] {
+            let result = create
+                .execute(serde_json::json!({
+                    "kind": "email",
+                    "config": {"interval_secs": 300},
+                    "action": "x",
+                    "reply_channel": "internal",
+                    "rules": rules
+                }))
+                .await;
+            assert!(result.is_err(), "{}", rules);
+        }
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_create_watcher_from_schedule_phrase() {
+        let (store, tx, mut rx) = setup();
+        let create =
+            CreateWatcherTool::new(store, tx).with_timezone("Europe/Paris".parse().unwrap());
+
+        let result = create
+            .execute(serde_json::json!({
+                "kind": "time",
+                "config": {"schedule": "every weekday at 8:30"},
+                "action": "Morning briefing",
+                "reply_channel": "internal"
+            }))
+            .await
+            .unwrap();
+        assert!(result.contains("0 30 8 * * MON-FRI (Europe/Paris)"));
+        assert_eq!(result.matches("08:30 CE").count(), 3);
+
+        let Some(WatcherCommand::Create { watcher }) = rx.try_recv().ok() else {
+            panic!("expected a create command");
+        };
+        let WatcherKind::Scheduled {
+            cron_expr, task, ..
+        } = watcher.kind
+        else {
+            panic!("expected a scheduled watcher");
+        };
+        assert_eq!(cron_expr, "0 30 8 * * MON-FRI");
+        assert_eq!(task, "Morning briefing");
+
+        // Relative phrases become one-shot watchers
+        create
+            .execute(serde_json::json!({
+                "kind": "time",
+                "config": {"schedule": "in 2 hours", "task": "Stretch"},
+                "action": "Remind me",
+                "reply_channel": "internal"
+            }))
+            .await
+            .unwrap();
+        let Some(WatcherCommand::Create { watcher }) = rx.try_recv().ok() else {
+            panic!("expected a create command");
+        };
+        assert!(matches!(watcher.kind, WatcherKind::OneShot { .. }));
+
+        for config in [
+            serde_json::json!({"schedule": "whenever"}),
+            serde_json::json!({"cron_expr": "every day"}),
+            serde_json::json!({"cron_expr": "0 9 * * *", "timezone": "Mars/Base"}),
+        ] {
+            let result = create
+                .execute(serde_json::json!({
+                    "kind": "scheduled",
+                    "config": config,
+                    "action": "x",
+                    "reply_channel": "internal"
+                }))
+                .await;
+            assert!(result.is_err());
+        }
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_preview_schedule() {
+        let tool = PreviewScheduleTool::new(Tz::UTC);
+        assert_eq!(tool.name(), "preview_schedule");
+
+        let result = tool
+            .execute(serde_json::json!({
+                "schedule": "first monday of the month at 9",
+                "timezone": "America/New_York",
+                "count": 4
+            }))
+            .await
+            .unwrap();
+        assert!(result.contains("0 0 9 1-7 * MON (America/New_York)"));
+        assert_eq!(result.matches("\n- Mon ").count(), 4);
+
+        assert!(
+            tool.execute(serde_json::json!({"schedule": "every 7 minutes"}))
+                .await
+                .is_err()
+        );
     }
 }
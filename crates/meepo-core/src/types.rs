@@ -11,6 +11,10 @@ pub struct IncomingMessage {
     pub content: String,
     pub channel: ChannelType,
     pub timestamp: DateTime<Utc>,
+    /// Channel-native thread or reply-chain ID (Slack `thread_ts`, Discord
+    /// channel, email subject chain), None for the channel's default thread
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thread_id: Option<String>,
 }
 
 /// What kind of outgoing message this is
@@ -230,6 +234,7 @@ mod tests {
             content: "hello".to_string(),
             channel: ChannelType::Discord,
             timestamp: Utc::now(),
+            thread_id: None,
         };
         let json = serde_json::to_string(&msg).unwrap();
         let parsed: IncomingMessage = serde_json::from_str(&json).unwrap();
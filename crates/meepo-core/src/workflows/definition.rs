@@ -0,0 +1,302 @@
+//! Workflow definitions — parsed from TOML or YAML files
+//!
+//! ```toml
+//! name = "receipts"
+//! description = "Log expenses from receipt emails"
+//!
+//! [trigger.kind]
+//! type = "EmailWatch"
+//! subject_contains = "receipt"
+//! interval_secs = 300
+//!
+//! [[steps]]
+//! id = "parse"
+//! tool = "parse_receipt"
+//! input = { text = "{{ event.payload.body }}" }
+//!
+//! [[steps]]
+//! id = "log"
+//! tool = "log_expense"
+//! input = { amount = "{{ steps.parse.output.total }}", vendor = "{{ steps.parse.output.vendor }}" }
+//!
+//! [[steps]]
+//! id = "alert"
+//! tool = "send_notification"
+//! when = "steps.parse.output.total > 100"
+//! input = { message = "Big expense at {{ steps.parse.output.vendor }}" }
+//! ```
+
+use anyhow::{Context, Result, bail};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashSet;
+use std::path::Path;
+
+use meepo_scheduler::{EventRules, Filter, Watcher, WatcherKind};
+
+use super::template;
+
+/// Prefix of the IDs of watchers created for workflow triggers
+pub const WORKFLOW_WATCHER_PREFIX: &str = "workflow:";
+
+/// A parsed workflow
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Workflow {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub trigger: Trigger,
+    pub steps: Vec<Step>,
+}
+
+/// What starts a workflow
+///
+/// Either a watcher of its own (`kind`, any `WatcherKind`, with optional event
+/// `rules`), or the events of an existing watcher (`watcher`, its ID).
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Trigger {
+    #[serde(default)]
+    pub kind: Option<WatcherKind>,
+    #[serde(default)]
+    pub watcher: Option<String>,
+    #[serde(default)]
+    pub rules: EventRules,
+}
+
+/// One step: a tool call or an LLM prompt
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Step {
+    /// Name other steps use to read this one's output (`steps.<id>.output`)
+    pub id: String,
+    /// Registered tool to call with `input`
+    #[serde(default)]
+    pub tool: Option<String>,
+    /// Prompt to send to the LLM instead of calling a tool
+    #[serde(default)]
+    pub llm: Option<String>,
+    /// Tool input; strings may contain `{{ path }}` templates
+    #[serde(default)]
+    pub input: Value,
+    /// Filter expression over the run context; the step is skipped when false
+    #[serde(default)]
+    pub when: Option<String>,
+    /// Keep going when this step fails (its error is in `steps.<id>.error`)
+    #[serde(default)]
+    pub continue_on_error: bool,
+}
+
+impl Step {
+    /// Tool name, or `llm` for a prompt step
+    pub fn action(&self) -> &str {
+        self.tool.as_deref().unwrap_or("llm")
+    }
+}
+
+impl Workflow {
+    /// Parse a workflow from TOML
+    pub fn from_toml(content: &str) -> Result<Self> {
+        let workflow: Self = toml::from_str(content).context("Failed to parse workflow TOML")?;
+        workflow.validate()?;
+        Ok(workflow)
+    }
+
+    /// Parse a workflow from YAML
+    pub fn from_yaml(content: &str) -> Result<Self> {
+        let workflow: Self =
+            serde_yml::from_str(content).context("Failed to parse workflow YAML")?;
+        workflow.validate()?;
+        Ok(workflow)
+    }
+
+    /// Load a `.toml`, `.yaml` or `.yml` workflow file
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => Self::from_toml(&content),
+            Some("yaml" | "yml") => Self::from_yaml(&content),
+            _ => bail!("Unsupported workflow file: {}", path.display()),
+        }
+    }
+
+    /// Check names, the trigger, step IDs, templates and conditions
+    pub fn validate(&self) -> Result<()> {
+        if self.name.is_empty()
+            || !self
+                .name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        {
+            bail!(
+                "Workflow name '{}' must be non-empty and only contain letters, digits, '_' and '-'",
+                self.name
+            );
+        }
+
+        match (&self.trigger.kind, &self.trigger.watcher) {
+            (Some(_), None) => self.trigger.rules.validate()?,
+            (None, Some(id)) if !id.trim().is_empty() => {
+                if !self.trigger.rules.is_empty() {
+                    bail!(
+                        "Workflow '{}': trigger rules only apply to a `kind` trigger; set them on watcher '{}'",
+                        self.name,
+                        id
+                    );
+                }
+            }
+            _ => bail!(
+                "Workflow '{}': trigger needs exactly one of `kind` or `watcher`",
+                self.name
+            ),
+        }
+
+        if self.steps.is_empty() {
+            bail!("Workflow '{}' has no steps", self.name);
+        }
+        let mut seen = HashSet::new();
+        for step in &self.steps {
+            let context = || format!("Workflow '{}', step '{}'", self.name, step.id);
+            let mut chars = step.id.chars();
+            if !chars
+                .next()
+                .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+                || !chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+            {
+                bail!(
+                    "Workflow '{}': step id '{}' must start with a letter or '_' and only contain letters, digits, '_' and '-'",
+                    self.name,
+                    step.id
+                );
+            }
+            if !seen.insert(step.id.as_str()) {
+                bail!("Workflow '{}': duplicate step id '{}'", self.name, step.id);
+            }
+            match (&step.tool, &step.llm) {
+                (Some(tool), None) if !tool.is_empty() => {}
+                (None, Some(prompt)) => {
+                    template::check(&Value::String(prompt.clone())).with_context(context)?;
+                }
+                _ => bail!("{}: needs exactly one of `tool` or `llm`", context()),
+            }
+            template::check(&step.input).with_context(context)?;
+            if let Some(when) = &step.when {
+                Filter::parse(when).with_context(context)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// ID of the watcher whose events start this workflow
+    pub fn watcher_id(&self) -> String {
+        match &self.trigger.watcher {
+            Some(id) => id.clone(),
+            None => format!("{}{}", WORKFLOW_WATCHER_PREFIX, self.name),
+        }
+    }
+
+    /// The watcher to run for a `kind` trigger (none for an existing watcher)
+    pub fn watcher(&self) -> Option<Watcher> {
+        let kind = self.trigger.kind.clone()?;
+        let mut watcher =
+            Watcher::new(kind, format!("Run workflow '{}'", self.name), String::new())
+                .with_rules(self.trigger.rules.clone());
+        watcher.id = self.watcher_id();
+        Some(watcher)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RECEIPTS_TOML: &str = r#"
+name = "receipts"
+description = "Log expenses"
+
+[trigger.kind]
+type = "EmailWatch"
+subject_contains = "receipt"
+interval_secs = 300
+
+[trigger.rules]
+debounce_secs = 30
+
+[[steps]]
+id = "parse"
+tool = "parse_receipt"
+input = { text = "{{ event.payload.body }}" }
+
+[[steps]]
+id = "alert"
+llm = "Summarize: {{ steps.parse.output }}"
+when = "steps.parse.output.total > 100"
+continue_on_error = true
+"#;
+
+    #[test]
+    fn test_parse_toml() {
+        let workflow = Workflow::from_toml(RECEIPTS_TOML).unwrap();
+        assert_eq!(workflow.name, "receipts");
+        assert_eq!(workflow.steps.len(), 2);
+        assert_eq!(workflow.steps[0].action(), "parse_receipt");
+        assert_eq!(workflow.steps[1].action(), "llm");
+        assert!(workflow.steps[1].continue_on_error);
+        assert_eq!(workflow.watcher_id(), "workflow:receipts");
+
+        let watcher = workflow.watcher().unwrap();
+        assert_eq!(watcher.id, "workflow:receipts");
+        assert_eq!(watcher.rules.debounce_secs, 30);
+        assert!(matches!(watcher.kind, WatcherKind::EmailWatch { .. }));
+    }
+
+    #[test]
+    fn test_parse_yaml_existing_watcher() {
+        let workflow = Workflow::from_yaml(
+            r#"
+name: triage
+trigger:
+  watcher: w-123
+steps:
+  - id: label
+    tool: github_label
+    input:
+      issue: "{{ event.payload.number }}"
+      labels: ["triage"]
+"#,
+        )
+        .unwrap();
+        assert_eq!(workflow.watcher_id(), "w-123");
+        assert!(workflow.watcher().is_none());
+        assert_eq!(workflow.steps[0].input["labels"][0], "triage");
+    }
+
+    #[test]
+    fn test_invalid_workflows() {
+        let invalid = [
+            // Both trigger forms
+            "name = \"x\"\n[trigger]\nwatcher = \"w\"\n[trigger.kind]\ntype = \"FileWatch\"\npath = \"/tmp\"\n[[steps]]\nid = \"a\"\ntool = \"t\"",
+            // No trigger form
+            "name = \"x\"\n[trigger]\n[[steps]]\nid = \"a\"\ntool = \"t\"",
+            // Bad name
+            "name = \"a b\"\n[trigger]\nwatcher = \"w\"\n[[steps]]\nid = \"a\"\ntool = \"t\"",
+            // No steps
+            "name = \"x\"\nsteps = []\n[trigger]\nwatcher = \"w\"",
+            // Duplicate step
+            "name = \"x\"\n[trigger]\nwatcher = \"w\"\n[[steps]]\nid = \"a\"\ntool = \"t\"\n[[steps]]\nid = \"a\"\ntool = \"t\"",
+            // Tool and llm
+            "name = \"x\"\n[trigger]\nwatcher = \"w\"\n[[steps]]\nid = \"a\"\ntool = \"t\"\nllm = \"hi\"",
+            // Bad condition
+            "name = \"x\"\n[trigger]\nwatcher = \"w\"\n[[steps]]\nid = \"a\"\ntool = \"t\"\nwhen = \"a ==\"",
+            // Unclosed template
+            "name = \"x\"\n[trigger]\nwatcher = \"w\"\n[[steps]]\nid = \"a\"\ntool = \"t\"\ninput = { q = \"{{ event\" }",
+            // Unknown field
+            "name = \"x\"\n[trigger]\nwatcher = \"w\"\n[[steps]]\nid = \"a\"\ntool = \"t\"\nretries = 3",
+        ];
+        for source in invalid {
+            assert!(Workflow::from_toml(source).is_err(), "accepted: {}", source);
+        }
+    }
+}
@@ -0,0 +1,524 @@
+//! Workflow execution
+//!
+//! Steps run in order against a context of the triggering event and earlier
+//! step results:
+//!
+//! ```json
+//! {"event": {"watcher_id": "...", "kind": "...", "payload": {...}, "timestamp": "..."},
+//!  "steps": {"parse": {"status": "succeeded", "output": {...}}}}
+//! ```
+//!
+//! Tool output that is valid JSON is stored parsed, so later steps can reach
+//! into it; anything else is stored as a string.
+
+use anyhow::{Context, Result, anyhow};
+use async_trait::async_trait;
+use chrono::Utc;
+use serde_json::{Value, json};
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use meepo_scheduler::{
+    Filter, RunStatus, StepRun, StepStatus, WatcherEvent, WorkflowRun, WorkflowRunStore,
+};
+
+use super::definition::{Step, Workflow};
+use super::template;
+use crate::api::{ApiClient, ApiMessage, ContentBlock, MessageContent};
+use crate::tools::ToolExecutor;
+
+/// Answers the prompts of `llm` steps
+#[async_trait]
+pub trait WorkflowLlm: Send + Sync {
+    async fn complete(&self, prompt: &str) -> Result<String>;
+}
+
+#[async_trait]
+impl WorkflowLlm for ApiClient {
+    async fn complete(&self, prompt: &str) -> Result<String> {
+        let messages = vec![ApiMessage {
+            role: "user".to_string(),
+            content: MessageContent::Text(prompt.to_string()),
+        }];
+        let response = self
+            .chat(
+                &messages,
+                &[],
+                "You are one step of an automated workflow. Reply with only the requested \
+                 output, without preamble. When asked for JSON, reply with JSON only.",
+            )
+            .await
+            .context("Workflow LLM step failed")?;
+        Ok(response
+            .content
+            .iter()
+            .filter_map(|b| {
+                if let ContentBlock::Text { text } = b {
+                    Some(text.as_str())
+                } else {
+                    None
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n"))
+    }
+}
+
+/// Runs workflows and records their history
+pub struct WorkflowEngine {
+    tools: Arc<dyn ToolExecutor>,
+    llm: Option<Arc<dyn WorkflowLlm>>,
+    runs: Option<WorkflowRunStore>,
+}
+
+impl WorkflowEngine {
+    pub fn new(tools: Arc<dyn ToolExecutor>) -> Self {
+        Self {
+            tools,
+            llm: None,
+            runs: None,
+        }
+    }
+
+    /// Model for `llm` steps; without one they fail
+    pub fn with_llm(mut self, llm: Arc<dyn WorkflowLlm>) -> Self {
+        self.llm = Some(llm);
+        self
+    }
+
+    /// Record every run in the workflow history
+    pub fn with_run_store(mut self, runs: WorkflowRunStore) -> Self {
+        self.runs = Some(runs);
+        self
+    }
+
+    /// Run a workflow for an event and record the run
+    pub async fn run(&self, workflow: &Workflow, event: &WatcherEvent) -> WorkflowRun {
+        let started_at = Utc::now();
+        let mut context = initial_context(event);
+        let mut steps = Vec::new();
+        let mut error = None;
+
+        for step in &workflow.steps {
+            if let Some(when) = &step.when
+                && !condition(when, &context)
+            {
+                steps.push(record(
+                    &mut context,
+                    step,
+                    StepStatus::Skipped,
+                    None,
+                    None,
+                    None,
+                ));
+                continue;
+            }
+
+            let (input, result) = match &step.llm {
+                Some(prompt) => {
+                    let prompt = template::render_text(prompt, &context);
+                    let result = self.complete(&prompt).await;
+                    (Value::String(prompt), result)
+                }
+                None => {
+                    let input = template::render(&step.input, &context);
+                    let result = self.tools.execute(step.action(), input.clone()).await;
+                    (input, result)
+                }
+            };
+
+            match result {
+                Ok(output) => {
+                    steps.push(record(
+                        &mut context,
+                        step,
+                        StepStatus::Succeeded,
+                        Some(input),
+                        Some(parse_output(output)),
+                        None,
+                    ));
+                }
+                Err(e) => {
+                    let message = format!("{:#}", e);
+                    steps.push(record(
+                        &mut context,
+                        step,
+                        StepStatus::Failed,
+                        Some(input),
+                        None,
+                        Some(message.clone()),
+                    ));
+                    if !step.continue_on_error {
+                        error = Some(format!("Step '{}' failed: {}", step.id, message));
+                        break;
+                    }
+                }
+            }
+        }
+
+        let mut run = WorkflowRun {
+            id: 0,
+            workflow: workflow.name.clone(),
+            status: if error.is_some() {
+                RunStatus::Failed
+            } else {
+                RunStatus::Succeeded
+            },
+            event: Some(event.clone()),
+            steps,
+            error,
+            started_at,
+            finished_at: Utc::now(),
+        };
+        match &run.error {
+            Some(e) => warn!("Workflow '{}' failed: {}", workflow.name, e),
+            None => info!(
+                "Workflow '{}' finished ({} steps)",
+                workflow.name,
+                run.steps.len()
+            ),
+        }
+        if let Some(store) = &self.runs {
+            match store.record(&run).await {
+                Ok(id) => run.id = id,
+                Err(e) => warn!(
+                    "Failed to record run of workflow '{}': {}",
+                    workflow.name, e
+                ),
+            }
+        }
+        run
+    }
+
+    async fn complete(&self, prompt: &str) -> Result<String> {
+        let llm = self
+            .llm
+            .as_ref()
+            .ok_or_else(|| anyhow!("No LLM is configured for workflow steps"))?;
+        llm.complete(prompt).await
+    }
+}
+
+/// Show what a run would do without calling tools or the LLM
+///
+/// Outputs of earlier steps are unknown, so templates that read them render
+/// as `<steps.id.output...>` placeholders, and steps whose condition reads
+/// them are planned rather than skipped. Dry runs are not recorded.
+pub fn dry_run(workflow: &Workflow, event: &WatcherEvent) -> WorkflowRun {
+    let started_at = Utc::now();
+    let mut context = initial_context(event);
+    let mut steps = Vec::new();
+
+    for step in &workflow.steps {
+        if let Some(when) = &step.when
+            && !reads_step_outputs(when)
+            && !condition(when, &context)
+        {
+            steps.push(record(
+                &mut context,
+                step,
+                StepStatus::Skipped,
+                None,
+                None,
+                None,
+            ));
+            continue;
+        }
+        let resolve = |path: &str| {
+            let planned = path
+                .strip_prefix("steps.")
+                .and_then(|rest| rest.split('.').next())
+                .is_some_and(|id| {
+                    template::lookup(&context, &format!("steps.{}.status", id))
+                        == &Value::String(StepStatus::Planned.to_string())
+                });
+            if planned {
+                Value::String(format!("<{}>", path))
+            } else {
+                template::lookup(&context, path).clone()
+            }
+        };
+        let input = match &step.llm {
+            Some(prompt) => Value::String(template::render_text_with(prompt, &resolve)),
+            None => template::render_with(&step.input, &resolve),
+        };
+        steps.push(record(
+            &mut context,
+            step,
+            StepStatus::Planned,
+            Some(input),
+            None,
+            None,
+        ));
+    }
+
+    WorkflowRun {
+        id: 0,
+        workflow: workflow.name.clone(),
+        status: RunStatus::Succeeded,
+        event: Some(event.clone()),
+        steps,
+        error: None,
+        started_at,
+        finished_at: Utc::now(),
+    }
+}
+
+fn initial_context(event: &WatcherEvent) -> Value {
+    json!({
+        "event": {
+            "watcher_id": event.watcher_id,
+            "kind": event.kind,
+            "payload": event.payload,
+            "timestamp": event.timestamp.to_rfc3339(),
+        },
+        "steps": {},
+    })
+}
+
+/// Add a step's result to the context and return its history record
+fn record(
+    context: &mut Value,
+    step: &Step,
+    status: StepStatus,
+    input: Option<Value>,
+    output: Option<Value>,
+    error: Option<String>,
+) -> StepRun {
+    context["steps"][&step.id] = json!({
+        "status": status.to_string(),
+        "output": output,
+        "error": error,
+    });
+    StepRun {
+        id: step.id.clone(),
+        action: step.action().to_string(),
+        status,
+        input,
+        output,
+        error,
+    }
+}
+
+/// Evaluate a `when` condition; it was validated when the workflow was loaded
+fn condition(when: &str, context: &Value) -> bool {
+    Filter::parse(when).is_ok_and(|filter| filter.matches(context))
+}
+
+fn reads_step_outputs(when: &str) -> bool {
+    Filter::parse(when).is_ok_and(|filter| {
+        filter
+            .paths()
+            .iter()
+            .any(|path| path == "steps" || path.starts_with("steps."))
+    })
+}
+
+fn parse_output(output: String) -> Value {
+    serde_json::from_str(&output).unwrap_or(Value::String(output))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::ToolDefinition;
+    use rusqlite::Connection;
+    use std::sync::Mutex;
+
+    /// Records calls; `parse_receipt` returns JSON, `fail` errors
+    #[derive(Default)]
+    struct FakeTools {
+        calls: Mutex<Vec<(String, Value)>>,
+    }
+
+    #[async_trait]
+    impl ToolExecutor for FakeTools {
+        async fn execute(&self, tool_name: &str, input: Value) -> Result<String> {
+            self.calls
+                .lock()
+                .unwrap()
+                .push((tool_name.to_string(), input.clone()));
+            match tool_name {
+                "parse_receipt" => Ok(r#"{"vendor": "Cafe", "total": 142.5}"#.to_string()),
+                "fail" => Err(anyhow!("tool exploded")),
+                _ => Ok(format!("ok: {}", input)),
+            }
+        }
+
+        fn list_tools(&self) -> Vec<ToolDefinition> {
+            Vec::new()
+        }
+    }
+
+    struct FakeLlm;
+
+    #[async_trait]
+    impl WorkflowLlm for FakeLlm {
+        async fn complete(&self, prompt: &str) -> Result<String> {
+            Ok(format!("LLM says: {}", prompt))
+        }
+    }
+
+    fn workflow(steps: &str) -> Workflow {
+        Workflow::from_toml(&format!(
+            "name = \"receipts\"\n[trigger]\nwatcher = \"w-1\"\n{}",
+            steps
+        ))
+        .unwrap()
+    }
+
+    fn event() -> WatcherEvent {
+        WatcherEvent::new(
+            "w-1".to_string(),
+            "email_received".to_string(),
+            json!({"subject": "Your receipt", "body": "Cafe $142.50"}),
+        )
+    }
+
+    const STEPS: &str = r#"
+[[steps]]
+id = "parse"
+tool = "parse_receipt"
+input = { text = "{{ event.payload.body }}" }
+
+[[steps]]
+id = "log"
+tool = "log_expense"
+input = { amount = "{{ steps.parse.output.total }}", vendor = "{{ steps.parse.output.vendor }}" }
+
+[[steps]]
+id = "small"
+tool = "never_called"
+when = "steps.parse.output.total < 100"
+
+[[steps]]
+id = "summary"
+llm = "Summarize the {{ steps.parse.output.vendor }} receipt"
+when = "steps.parse.output.total >= 100 and event.kind == \"email_received\""
+"#;
+
+    #[tokio::test]
+    async fn test_run_workflow() {
+        let tools = Arc::new(FakeTools::default());
+        let conn = Connection::open_in_memory().unwrap();
+        meepo_scheduler::persistence::init_watcher_tables(&conn).unwrap();
+        let store = WorkflowRunStore::new(Arc::new(Mutex::new(conn)));
+        let engine = WorkflowEngine::new(tools.clone())
+            .with_llm(Arc::new(FakeLlm))
+            .with_run_store(store.clone());
+
+        let run = engine.run(&workflow(STEPS), &event()).await;
+        assert_eq!(run.status, RunStatus::Succeeded);
+        assert!(run.id > 0);
+        let statuses: Vec<_> = run.steps.iter().map(|s| s.status).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                StepStatus::Succeeded,
+                StepStatus::Succeeded,
+                StepStatus::Skipped,
+                StepStatus::Succeeded
+            ]
+        );
+
+        let calls = tools.calls.lock().unwrap().clone();
+        assert_eq!(calls.len(), 2);
+        assert_eq!(
+            calls[0],
+            ("parse_receipt".to_string(), json!({"text": "Cafe $142.50"}))
+        );
+        // A whole-string template keeps the number a number
+        assert_eq!(
+            calls[1],
+            (
+                "log_expense".to_string(),
+                json!({"amount": 142.5, "vendor": "Cafe"})
+            )
+        );
+        assert_eq!(
+            run.steps[3].output,
+            Some(json!("LLM says: Summarize the Cafe receipt"))
+        );
+
+        let history = store.recent(Some("receipts"), 10).await.unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].steps.len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_failed_step_stops_run() {
+        let tools = Arc::new(FakeTools::default());
+        let engine = WorkflowEngine::new(tools.clone());
+        let run = engine
+            .run(
+                &workflow(
+                    r#"
+[[steps]]
+id = "tolerated"
+tool = "fail"
+continue_on_error = true
+
+[[steps]]
+id = "report"
+tool = "notify"
+input = { error = "{{ steps.tolerated.error }}" }
+
+[[steps]]
+id = "ask"
+llm = "no model configured"
+
+[[steps]]
+id = "after"
+tool = "notify"
+"#,
+                ),
+                &event(),
+            )
+            .await;
+
+        assert_eq!(run.status, RunStatus::Failed);
+        assert_eq!(run.steps.len(), 3);
+        assert_eq!(run.steps[0].status, StepStatus::Failed);
+        assert_eq!(run.steps[1].input, Some(json!({"error": "tool exploded"})));
+        assert_eq!(run.steps[2].status, StepStatus::Failed);
+        assert!(run.error.unwrap().contains("Step 'ask' failed"));
+        // The step after the failure never ran
+        assert_eq!(tools.calls.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_dry_run() {
+        let run = dry_run(
+            &workflow(&format!(
+                "{}\n[[steps]]\nid = \"other_kind\"\ntool = \"t\"\nwhen = \"event.kind == \\\"file_changed\\\"\"",
+                STEPS
+            )),
+            &event(),
+        );
+        let statuses: Vec<_> = run.steps.iter().map(|s| s.status).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                StepStatus::Planned,
+                StepStatus::Planned,
+                // Depends on an unknown output, so it might run
+                StepStatus::Planned,
+                StepStatus::Planned,
+                StepStatus::Skipped
+            ]
+        );
+        assert_eq!(run.steps[0].input, Some(json!({"text": "Cafe $142.50"})));
+        assert_eq!(
+            run.steps[1].input,
+            Some(json!({
+                "amount": "<steps.parse.output.total>",
+                "vendor": "<steps.parse.output.vendor>"
+            }))
+        );
+        assert_eq!(
+            run.steps[3].input,
+            Some(json!("Summarize the <steps.parse.output.vendor> receipt"))
+        );
+        assert!(run.steps.iter().all(|s| s.output.is_none()));
+    }
+}
@@ -0,0 +1,113 @@
+//! Workflows — deterministic multi-step automations triggered by watchers
+//!
+//! A workflow file (TOML or YAML) names a trigger — a watcher of any kind —
+//! and a list of steps that call registered tools directly, with inputs
+//! templated from the event and earlier step outputs, optional `when`
+//! conditions, and optional LLM prompt steps. Events from a workflow's
+//! watcher run the workflow instead of the agent, so fixed pipelines don't
+//! cost a full tool loop. Every run is kept in the workflow run history.
+
+pub mod definition;
+pub mod engine;
+pub mod template;
+
+use anyhow::Result;
+use std::collections::HashSet;
+use std::path::Path;
+use tracing::{info, warn};
+
+pub use definition::{Step, Trigger, WORKFLOW_WATCHER_PREFIX, Workflow};
+pub use engine::{WorkflowEngine, WorkflowLlm, dry_run};
+
+/// Load all workflows from a directory
+///
+/// Every `*.toml`, `*.yaml` and `*.yml` file directly in `dir` is one
+/// workflow. Invalid files and duplicate names are skipped with a warning.
+pub fn load_workflows(dir: &Path) -> Result<Vec<Workflow>> {
+    let mut workflows = Vec::new();
+
+    if !dir.exists() {
+        info!(
+            "Workflows directory does not exist: {} — skipping",
+            dir.display()
+        );
+        return Ok(workflows);
+    }
+
+    let mut paths: Vec<_> = std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.is_file()
+                && matches!(
+                    path.extension().and_then(|ext| ext.to_str()),
+                    Some("toml" | "yaml" | "yml")
+                )
+        })
+        .collect();
+    paths.sort();
+
+    let mut names = HashSet::new();
+    for path in paths {
+        match Workflow::load(&path) {
+            Ok(workflow) if !names.insert(workflow.name.clone()) => {
+                warn!(
+                    "Skipping {}: workflow '{}' is already defined",
+                    path.display(),
+                    workflow.name
+                );
+            }
+            Ok(workflow) => {
+                info!("Loaded workflow: {} from {}", workflow.name, path.display());
+                workflows.push(workflow);
+            }
+            Err(e) => warn!("Failed to load workflow from {}: {:#}", path.display(), e),
+        }
+    }
+
+    info!(
+        "Loaded {} workflows from {}",
+        workflows.len(),
+        dir.display()
+    );
+    Ok(workflows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_load_workflows() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("a.toml"),
+            "name = \"digest\"\n[trigger]\nwatcher = \"w-1\"\n[[steps]]\nid = \"s\"\ntool = \"t\"",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("b.yaml"),
+            "name: triage\ntrigger:\n  watcher: w-2\nsteps:\n  - id: s\n    llm: hi\n",
+        )
+        .unwrap();
+        // Same name as a.toml
+        fs::write(
+            dir.path().join("c.yml"),
+            "name: digest\ntrigger:\n  watcher: w-3\nsteps:\n  - id: s\n    tool: t\n",
+        )
+        .unwrap();
+        fs::write(dir.path().join("broken.toml"), "name = ").unwrap();
+        fs::write(dir.path().join("README.md"), "# notes").unwrap();
+
+        let workflows = load_workflows(dir.path()).unwrap();
+        let names: Vec<_> = workflows.iter().map(|w| w.name.as_str()).collect();
+        assert_eq!(names, vec!["digest", "triage"]);
+        assert_eq!(workflows[0].watcher_id(), "w-1");
+
+        assert!(
+            load_workflows(&dir.path().join("missing"))
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
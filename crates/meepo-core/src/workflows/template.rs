@@ -0,0 +1,170 @@
+//! `{{ path }}` templates in workflow step inputs
+//!
+//! Paths are dotted keys into the run context, the same as in filter
+//! expressions: `event.payload.subject`, `steps.parse.output.items.0`. A string
+//! that is exactly one template becomes the referenced value itself (a number
+//! stays a number); otherwise each template is replaced by the value as text.
+//! A missing path is `null`, which renders as an empty string.
+
+use anyhow::{Result, bail};
+use serde_json::Value;
+
+/// Render every string in `value` against `context`
+pub fn render(value: &Value, context: &Value) -> Value {
+    render_with(value, &|path| lookup(context, path).clone())
+}
+
+/// Render a string against `context`, always producing text
+pub fn render_text(template: &str, context: &Value) -> String {
+    render_text_with(template, &|path| lookup(context, path).clone())
+}
+
+/// Render every string in `value`, resolving paths with `resolve`
+pub fn render_with(value: &Value, resolve: &dyn Fn(&str) -> Value) -> Value {
+    match value {
+        Value::String(s) => render_string(s, resolve),
+        Value::Array(items) => {
+            Value::Array(items.iter().map(|v| render_with(v, resolve)).collect())
+        }
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(k, v)| (k.clone(), render_with(v, resolve)))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+/// Render a string, resolving paths with `resolve`, always producing text
+pub fn render_text_with(template: &str, resolve: &dyn Fn(&str) -> Value) -> String {
+    match render_string(template, resolve) {
+        Value::String(s) => s,
+        other => text(&other),
+    }
+}
+
+/// Check that every template in `value` is closed and names a path
+pub fn check(value: &Value) -> Result<()> {
+    match value {
+        Value::String(s) => {
+            for part in parse(s)? {
+                if let Part::Path(path) = part
+                    && path.is_empty()
+                {
+                    bail!("Empty template '{{{{ }}}}' in '{}'", s);
+                }
+            }
+            Ok(())
+        }
+        Value::Array(items) => items.iter().try_for_each(check),
+        Value::Object(map) => map.values().try_for_each(check),
+        _ => Ok(()),
+    }
+}
+
+/// Look up a dotted path, `null` when missing
+pub fn lookup<'a>(context: &'a Value, path: &str) -> &'a Value {
+    path.split('.')
+        .try_fold(context, |value, key| match value {
+            Value::Object(map) => map.get(key),
+            Value::Array(items) => key.parse::<usize>().ok().and_then(|i| items.get(i)),
+            _ => None,
+        })
+        .unwrap_or(&Value::Null)
+}
+
+enum Part<'a> {
+    Text(&'a str),
+    Path(&'a str),
+}
+
+fn parse(template: &str) -> Result<Vec<Part<'_>>> {
+    let mut parts = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        if start > 0 {
+            parts.push(Part::Text(&rest[..start]));
+        }
+        let Some(len) = rest[start + 2..].find("}}") else {
+            bail!("Unclosed '{{{{' in template '{}'", template);
+        };
+        parts.push(Part::Path(rest[start + 2..start + 2 + len].trim()));
+        rest = &rest[start + 2 + len + 2..];
+    }
+    if !rest.is_empty() {
+        parts.push(Part::Text(rest));
+    }
+    Ok(parts)
+}
+
+fn render_string(template: &str, resolve: &dyn Fn(&str) -> Value) -> Value {
+    // Templates are checked when the workflow is loaded; render anything else verbatim
+    let Ok(parts) = parse(template) else {
+        return Value::String(template.to_string());
+    };
+    if let [Part::Path(path)] = parts.as_slice() {
+        return resolve(path);
+    }
+    let mut out = String::new();
+    for part in parts {
+        match part {
+            Part::Text(t) => out.push_str(t),
+            Part::Path(path) => out.push_str(&text(&resolve(path))),
+        }
+    }
+    Value::String(out)
+}
+
+fn text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_render() {
+        let context = json!({
+            "event": {"payload": {"subject": "Receipt", "total": 42.5, "tags": ["a", "b"]}},
+            "steps": {"parse": {"output": {"vendor": "Cafe"}}}
+        });
+        let input = json!({
+            "amount": "{{ event.payload.total }}",
+            "note": "{{event.payload.subject}} from {{ steps.parse.output.vendor }} ({{ event.payload.total }})",
+            "first_tag": "{{ event.payload.tags.0 }}",
+            "missing": "[{{ event.payload.nope }}]",
+            "nested": [{"v": "{{ steps.parse.output }}"}],
+            "literal": 7
+        });
+        assert_eq!(
+            render(&input, &context),
+            json!({
+                "amount": 42.5,
+                "note": "Receipt from Cafe (42.5)",
+                "first_tag": "a",
+                "missing": "[]",
+                "nested": [{"v": {"vendor": "Cafe"}}],
+                "literal": 7
+            })
+        );
+        assert_eq!(
+            render_text("Vendor: {{ steps.parse.output }}", &context),
+            r#"Vendor: {"vendor":"Cafe"}"#
+        );
+        assert_eq!(render_text("{{ event.payload.total }}", &context), "42.5");
+    }
+
+    #[test]
+    fn test_check() {
+        assert!(check(&json!({"a": ["{{ x.y }} and {{z}}"]})).is_ok());
+        assert!(check(&json!("no templates")).is_ok());
+        assert!(check(&json!({"a": "{{ x"})).is_err());
+        assert!(check(&json!(["{{ }}"])).is_err());
+    }
+}
@@ -0,0 +1,161 @@
+//! A watcher's life through the watcher tools, the watcher store and the
+//! runner: create, run, cancel, and a daemon restart on the same database.
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use meepo_core::tools::ToolHandler;
+use meepo_core::tools::watchers::{
+    CancelWatcherTool, CreateWatcherTool, ListWatchersTool, WatcherCommand,
+};
+use meepo_scheduler::{WatcherEvent, WatcherRunner, WatcherStore, persistence};
+use rusqlite::Connection;
+use serde_json::json;
+use tokio::sync::mpsc;
+
+fn open(path: &std::path::Path) -> Arc<Mutex<Connection>> {
+    let conn = Connection::open(path).unwrap();
+    persistence::init_watcher_tables(&conn).unwrap();
+    Arc::new(Mutex::new(conn))
+}
+
+/// Start or stop watchers the way the daemon does for the tools' commands
+async fn apply_commands(runner: &WatcherRunner, commands: &mut mpsc::Receiver<WatcherCommand>) {
+    while let Ok(command) = commands.try_recv() {
+        match command {
+            WatcherCommand::Create { watcher } => runner.start_watcher(*watcher).await.unwrap(),
+            WatcherCommand::Cancel { id } => {
+                runner.stop_watcher(&id).await.unwrap();
+            }
+            WatcherCommand::List => {}
+        }
+    }
+}
+
+fn created_id(output: &str) -> String {
+    output
+        .lines()
+        .find_map(|line| line.strip_prefix("Created watcher with ID: "))
+        .unwrap()
+        .to_string()
+}
+
+async fn next_event(events: &mut mpsc::UnboundedReceiver<WatcherEvent>) -> Option<WatcherEvent> {
+    tokio::time::timeout(Duration::from_secs(5), events.recv())
+        .await
+        .ok()
+        .flatten()
+}
+
+#[tokio::test]
+async fn test_create_run_cancel_restart() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("meepo.db");
+
+    let conn = open(&path);
+    let store = WatcherStore::new(conn.clone());
+    let (command_tx, mut commands) = mpsc::channel(16);
+    let (event_tx, mut events) = mpsc::unbounded_channel();
+    let runner = WatcherRunner::new(event_tx).with_database(conn);
+
+    let create = CreateWatcherTool::new(store.clone(), command_tx.clone());
+    let cancel = CancelWatcherTool::new(store.clone(), command_tx);
+
+    // Create
+    let at = chrono::Utc::now() + chrono::Duration::seconds(1);
+    let reminder = created_id(
+        &create
+            .execute(json!({
+                "kind": "oneshot",
+                "config": {"at": at.to_rfc3339(), "task": "Stretch"},
+                "action": "Remind me to stretch",
+                "reply_channel": "internal"
+            }))
+            .await
+            .unwrap(),
+    );
+    let mut keyword_watchers = Vec::new();
+    for keyword in ["urgent", "invoice"] {
+        keyword_watchers.push(created_id(
+            &create
+                .execute(json!({
+                    "kind": "message",
+                    "config": {"keyword": keyword},
+                    "action": format!("Flag {} messages", keyword),
+                    "reply_channel": "internal"
+                }))
+                .await
+                .unwrap(),
+        ));
+    }
+    assert_eq!(store.active().await.unwrap().len(), 3);
+    apply_commands(&runner, &mut commands).await;
+    assert_eq!(runner.active_count().await, 3);
+
+    // Run
+    let event = next_event(&mut events)
+        .await
+        .expect("reminder did not fire");
+    assert_eq!(event.watcher_id, reminder);
+    assert_eq!(event.kind, "task_triggered");
+    assert_eq!(event.payload["task"], "Stretch");
+
+    // Cancel
+    cancel
+        .execute(json!({"watcher_id": keyword_watchers[1]}))
+        .await
+        .unwrap();
+    apply_commands(&runner, &mut commands).await;
+    assert!(!runner.is_running(&keyword_watchers[1]).await);
+    assert!(runner.is_running(&keyword_watchers[0]).await);
+    assert!(
+        !store
+            .get(&keyword_watchers[1])
+            .await
+            .unwrap()
+            .unwrap()
+            .active
+    );
+    runner.stop_all().await;
+    drop(runner);
+    drop(store);
+
+    // Restart: a new connection, store and runner on the same file
+    let conn = open(&path);
+    let store = WatcherStore::new(conn.clone());
+    let (event_tx, mut events) = mpsc::unbounded_channel();
+    let runner = WatcherRunner::new(event_tx).with_database(conn);
+
+    let active: Vec<_> = store
+        .active()
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|w| w.id)
+        .collect();
+    // The reminder fired before the restart, so it is no longer active
+    assert_eq!(active.len(), 1);
+    assert!(!active.contains(&reminder));
+    assert!(active.contains(&keyword_watchers[0]));
+
+    let listed = ListWatchersTool::new(store.clone())
+        .execute(json!({}))
+        .await
+        .unwrap();
+    assert!(listed.contains("Flag urgent messages"));
+    assert!(!listed.contains("Flag invoice messages"));
+
+    for watcher in store.active().await.unwrap() {
+        runner.start_watcher(watcher).await.unwrap();
+    }
+    assert!(runner.is_running(&keyword_watchers[0]).await);
+
+    // The reminder already ran before the restart and does not fire again
+    assert!(
+        tokio::time::timeout(Duration::from_secs(2), events.recv())
+            .await
+            .is_err()
+    );
+    assert!(!runner.is_running(&reminder).await);
+    runner.stop_all().await;
+}
@@ -1,7 +1,8 @@
 //! meepo-gateway — WebSocket control plane for Meepo
 //!
 //! Provides a WebSocket server that clients (WebChat, macOS app, mobile nodes)
-//! connect to for real-time chat, session management, and event streaming.
+//! connect to for real-time chat, session management, and event streaming. Also
+//! receives signed inbound webhooks for `Webhook` watchers.
 
 pub mod auth;
 pub mod events;
@@ -10,9 +11,11 @@ pub mod server;
 pub mod session;
 pub mod session_tools;
 pub mod webchat;
+pub mod webhooks;
 
 pub use server::GatewayServer;
 pub use session_tools::{
     AgentToAgentConfig, AgentsListTool, SessionsHistoryTool, SessionsListTool, SessionsSendTool,
     SessionsSpawnTool,
 };
+pub use webhooks::WebhookRegistry;
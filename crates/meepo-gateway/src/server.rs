@@ -20,6 +20,11 @@ use crate::protocol::{
     self, ERR_INVALID_METHOD, ERR_INVALID_PARAMS, GatewayEvent, GatewayRequest, GatewayResponse,
 };
 use crate::session::SessionManager;
+use crate::webhooks::WebhookRegistry;
+use meepo_scheduler::HealthStore;
+
+/// Dead letters returned alongside watcher health by `/api/watchers`
+const RECENT_DEAD_LETTERS: usize = 20;
 
 /// Shared state for all WebSocket connections
 #[derive(Clone)]
@@ -34,6 +39,8 @@ pub struct GatewayState {
 pub struct GatewayServer {
     state: GatewayState,
     bind: SocketAddr,
+    webhooks: Option<WebhookRegistry>,
+    watcher_health: Option<HealthStore>,
 }
 
 impl GatewayServer {
@@ -54,7 +61,24 @@ impl GatewayServer {
             auth_token,
             start_time: std::time::Instant::now(),
         };
-        Self { state, bind }
+        Self {
+            state,
+            bind,
+            webhooks: None,
+            watcher_health: None,
+        }
+    }
+
+    /// Serve inbound webhooks for `Webhook` watchers on `/hooks/`
+    pub fn with_webhooks(mut self, webhooks: WebhookRegistry) -> Self {
+        self.webhooks = Some(webhooks);
+        self
+    }
+
+    /// Serve watcher health and recent dead letters on `/api/watchers`
+    pub fn with_watcher_health(mut self, health: HealthStore) -> Self {
+        self.watcher_health = Some(health);
+        self
     }
 
     /// Get a reference to the event bus (for broadcasting from outside)
@@ -87,14 +111,27 @@ impl GatewayServer {
             .allow_methods([axum::http::Method::GET, axum::http::Method::POST])
             .allow_headers(tower_http::cors::Any);
 
-        Router::new()
+        let mut router = Router::new()
             .route("/ws", get(ws_handler))
             .route("/api/status", get(status_handler))
             .route("/api/sessions", get(sessions_handler))
             .route("/", get(crate::webchat::index_handler))
             .route("/assets/{*path}", get(crate::webchat::static_handler))
-            .layer(cors)
-            .with_state(self.state.clone())
+            .with_state(self.state.clone());
+        if let Some(health) = &self.watcher_health {
+            router = router.merge(
+                Router::new()
+                    .route("/api/watchers", get(watchers_handler))
+                    .with_state((self.state.auth_token.clone(), health.clone())),
+            );
+        }
+        let router = router.layer(cors);
+
+        // Webhooks authenticate by signature, not the bearer token
+        match &self.webhooks {
+            Some(webhooks) => router.merge(webhooks.router()),
+            None => router,
+        }
     }
 
     /// Start the server (blocks until shutdown)
@@ -152,6 +189,30 @@ async fn sessions_handler(
     Ok(axum::Json(serde_json::json!({ "sessions": sessions })))
 }
 
+async fn watchers_handler(
+    State((auth_token, health)): State<(String, HealthStore)>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, StatusCode> {
+    if !check_auth(&auth_token, &headers) {
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+    let watchers = health.watchers().await.map_err(|e| {
+        warn!("Failed to read watcher health: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    let dead_letters = health
+        .dead_letters(None, RECENT_DEAD_LETTERS)
+        .await
+        .map_err(|e| {
+            warn!("Failed to read dead letters: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    Ok(axum::Json(serde_json::json!({
+        "watchers": watchers,
+        "dead_letters": dead_letters,
+    })))
+}
+
 // ── WebSocket Handler ──
 
 async fn ws_handler(
@@ -535,4 +596,53 @@ mod tests {
         .await;
         assert!(resp.error.is_some());
     }
+
+    #[tokio::test]
+    async fn test_watchers_endpoint() {
+        use axum::body::Body;
+        use axum::http::Request;
+        use meepo_scheduler::{Watcher, WatcherKind, persistence};
+        use tower::ServiceExt;
+
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        persistence::init_watcher_tables(&conn).unwrap();
+        let watcher = Watcher::new(
+            WatcherKind::Scheduled {
+                cron_expr: "0 * * * *".to_string(),
+                task: "Check the build".to_string(),
+                timezone: None,
+                misfire: Default::default(),
+            },
+            "Check the build".to_string(),
+            "internal".to_string(),
+        );
+        persistence::save_watcher(&conn, &watcher).unwrap();
+        let now = chrono::Utc::now();
+        persistence::record_watcher_failure(&conn, &watcher.id, "HTTP 502", now).unwrap();
+        persistence::record_dead_letter(&conn, &watcher.id, None, "HTTP 502", now).unwrap();
+
+        let router = GatewayServer::new("127.0.0.1:0".parse().unwrap(), "secret".to_string())
+            .with_watcher_health(HealthStore::new(Arc::new(std::sync::Mutex::new(conn))))
+            .router();
+        let request = |token: Option<&str>| {
+            let mut builder = Request::get("/api/watchers");
+            if let Some(token) = token {
+                builder = builder.header("authorization", format!("Bearer {}", token));
+            }
+            builder.body(Body::empty()).unwrap()
+        };
+
+        let denied = router.clone().oneshot(request(None)).await.unwrap();
+        assert_eq!(denied.status(), StatusCode::UNAUTHORIZED);
+
+        let response = router.oneshot(request(Some("secret"))).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["watchers"][0]["watcher_id"], watcher.id.as_str());
+        assert_eq!(json["watchers"][0]["status"], "failing");
+        assert_eq!(json["dead_letters"][0]["error"], "HTTP 502");
+    }
 }
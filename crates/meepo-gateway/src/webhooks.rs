@@ -0,0 +1,725 @@
+//! Inbound webhooks — signed HTTP triggers for `Webhook` watchers
+//!
+//! Each webhook watcher owns a path under `/hooks/`. Requests must carry an
+//! HMAC-SHA256 signature made with the watcher's secret, in GitHub, Stripe or
+//! generic format. Signed timestamps older than five minutes and signed
+//! content seen in the last day are rejected, so a captured request can't be
+//! replayed, not even with a new delivery ID header; with a database attached,
+//! the replay window outlives restarts. Accepted
+//! requests become `WatcherEvent`s, which the agent handles with the watcher's
+//! action prompt like any other watcher event.
+//!
+//! Hook routes don't use the gateway bearer token: the signature is the
+//! credential, so third-party services can call them.
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+use anyhow::{Result, bail};
+use axum::Router;
+use axum::body::Bytes;
+use axum::extract::{DefaultBodyLimit, Path, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::routing::post;
+use hmac::{Hmac, KeyInit, Mac};
+use meepo_scheduler::{
+    Watcher, WatcherEvent, WatcherKind, WebhookDeliveries, WebhookRoutes, WebhookScheme,
+};
+use sha2::{Digest, Sha256};
+use tokio::sync::mpsc;
+use tracing::{info, warn};
+
+/// Largest body any webhook watcher may accept
+pub const MAX_WEBHOOK_BODY_BYTES: usize = 1024 * 1024;
+
+/// How far a signed timestamp may be from the current time
+const TIMESTAMP_TOLERANCE_SECS: i64 = 300;
+
+/// How long delivery IDs are remembered for replay protection
+const REPLAY_WINDOW: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Most delivery IDs remembered at once when no database is attached
+const MAX_REPLAY_ENTRIES: usize = 10_000;
+
+/// A registered webhook watcher
+#[derive(Debug, Clone)]
+struct Endpoint {
+    watcher_id: String,
+    scheme: WebhookScheme,
+    secret: String,
+    max_body_bytes: usize,
+}
+
+#[derive(Default)]
+struct RegistryInner {
+    /// Path (without `/hooks/`) -> endpoint
+    endpoints: HashMap<String, Endpoint>,
+    /// Delivery key -> when it was accepted, when no database is attached
+    seen: HashMap<String, Instant>,
+}
+
+/// Why a webhook request was rejected
+#[derive(Debug, Clone, PartialEq)]
+enum Rejection {
+    MissingSignature,
+    BadSignature,
+    StaleTimestamp,
+    Replayed,
+}
+
+impl Rejection {
+    fn status(&self) -> StatusCode {
+        match self {
+            Self::Replayed => StatusCode::CONFLICT,
+            _ => StatusCode::UNAUTHORIZED,
+        }
+    }
+}
+
+/// Verified request, ready to become an event
+struct Verified {
+    /// Key for replay protection, derived only from signed material so it
+    /// can't be changed without invalidating the signature
+    replay_key: String,
+    /// Delivery ID sent by the caller, for logging
+    delivery: Option<String>,
+    event_type: Option<String>,
+    body: serde_json::Value,
+}
+
+/// Webhook endpoints registered by the watcher runner
+#[derive(Clone)]
+pub struct WebhookRegistry {
+    inner: Arc<RwLock<RegistryInner>>,
+    events: mpsc::UnboundedSender<WatcherEvent>,
+    deliveries: Option<WebhookDeliveries>,
+}
+
+impl WebhookRegistry {
+    /// Create a registry that sends accepted requests to `events`
+    pub fn new(events: mpsc::UnboundedSender<WatcherEvent>) -> Self {
+        Self {
+            inner: Arc::new(RwLock::new(RegistryInner::default())),
+            events,
+            deliveries: None,
+        }
+    }
+
+    /// Remember accepted delivery IDs in the scheduler database instead of in
+    /// memory, so a restart doesn't reopen the replay window
+    pub fn with_deliveries(mut self, deliveries: WebhookDeliveries) -> Self {
+        self.deliveries = Some(deliveries);
+        self
+    }
+
+    /// Routes serving `POST /hooks/{path}`
+    pub fn router(&self) -> Router {
+        Router::new()
+            .route("/hooks/{*path}", post(hook_handler))
+            .layer(DefaultBodyLimit::max(MAX_WEBHOOK_BODY_BYTES))
+            .with_state(self.clone())
+    }
+
+    /// Paths currently accepting requests
+    pub fn paths(&self) -> Vec<String> {
+        let mut paths: Vec<String> = self.read().endpoints.keys().cloned().collect();
+        paths.sort();
+        paths
+    }
+
+    fn read(&self) -> std::sync::RwLockReadGuard<'_, RegistryInner> {
+        self.inner.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn write(&self) -> std::sync::RwLockWriteGuard<'_, RegistryInner> {
+        self.inner.write().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Verify a request and, if it's genuine and new, emit its event
+    async fn accept(
+        &self,
+        path: &str,
+        headers: &HeaderMap,
+        body: &[u8],
+        now: i64,
+    ) -> Result<(), StatusCode> {
+        let endpoint = self
+            .read()
+            .endpoints
+            .get(path)
+            .cloned()
+            .ok_or(StatusCode::NOT_FOUND)?;
+        if body.len() > endpoint.max_body_bytes {
+            return Err(StatusCode::PAYLOAD_TOO_LARGE);
+        }
+
+        let verified = verify(&endpoint, headers, body, now).map_err(|rejection| {
+            warn!("Rejected webhook on /hooks/{}: {:?}", path, rejection);
+            rejection.status()
+        })?;
+
+        let delivery = verified.delivery.as_deref().unwrap_or("no delivery ID");
+        if !self
+            .remember(&endpoint.watcher_id, &verified.replay_key)
+            .await?
+        {
+            warn!(
+                "Rejected webhook on /hooks/{} ({}): {:?}",
+                path,
+                delivery,
+                Rejection::Replayed
+            );
+            return Err(Rejection::Replayed.status());
+        }
+
+        info!(
+            "Webhook received on /hooks/{} ({}, {})",
+            path,
+            verified.event_type.as_deref().unwrap_or("no event type"),
+            delivery
+        );
+        let event = WatcherEvent::webhook(
+            endpoint.watcher_id,
+            path.to_string(),
+            verified.event_type,
+            verified.body,
+        );
+        self.events
+            .send(event)
+            .map_err(|_| StatusCode::SERVICE_UNAVAILABLE)
+    }
+
+    /// Record a request's replay key; `false` if it was already seen
+    async fn remember(&self, watcher_id: &str, replay_key: &str) -> Result<bool, StatusCode> {
+        if let Some(deliveries) = &self.deliveries {
+            return deliveries
+                .record(watcher_id, replay_key, REPLAY_WINDOW)
+                .await
+                .map_err(|e| {
+                    warn!("Failed to record webhook delivery: {:#}", e);
+                    StatusCode::INTERNAL_SERVER_ERROR
+                });
+        }
+
+        let key = format!("{}:{}", watcher_id, replay_key);
+        let mut inner = self.write();
+        let now = Instant::now();
+        inner
+            .seen
+            .retain(|_, at| now.duration_since(*at) < REPLAY_WINDOW);
+        if inner.seen.contains_key(&key) {
+            return Ok(false);
+        }
+        if inner.seen.len() >= MAX_REPLAY_ENTRIES
+            && let Some(oldest) = inner
+                .seen
+                .iter()
+                .min_by_key(|(_, at)| **at)
+                .map(|(k, _)| k.clone())
+        {
+            inner.seen.remove(&oldest);
+        }
+        inner.seen.insert(key, now);
+        Ok(true)
+    }
+}
+
+impl WebhookRoutes for WebhookRegistry {
+    fn register(&self, watcher: &Watcher) -> Result<()> {
+        let WatcherKind::Webhook {
+            secret,
+            scheme,
+            max_body_bytes,
+            ..
+        } = &watcher.kind
+        else {
+            bail!("Watcher {} is not a webhook watcher", watcher.id);
+        };
+        let path = watcher.webhook_path().unwrap_or_default().to_string();
+        if path.is_empty()
+            || !path
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '/'))
+            || path.contains("//")
+        {
+            bail!(
+                "Invalid webhook path '{}': use letters, digits, '-', '_' and '/'",
+                path
+            );
+        }
+        if secret.is_empty() {
+            bail!("Webhook watcher {} has no secret", watcher.id);
+        }
+
+        let mut inner = self.write();
+        if let Some(existing) = inner.endpoints.get(&path)
+            && existing.watcher_id != watcher.id
+        {
+            bail!(
+                "Webhook path /hooks/{} is already used by watcher {}",
+                path,
+                existing.watcher_id
+            );
+        }
+        inner.endpoints.retain(|_, e| e.watcher_id != watcher.id);
+        inner.endpoints.insert(
+            path,
+            Endpoint {
+                watcher_id: watcher.id.clone(),
+                scheme: *scheme,
+                secret: secret.clone(),
+                max_body_bytes: (*max_body_bytes).min(MAX_WEBHOOK_BODY_BYTES),
+            },
+        );
+        Ok(())
+    }
+
+    fn unregister(&self, watcher_id: &str) {
+        self.write()
+            .endpoints
+            .retain(|_, e| e.watcher_id != watcher_id);
+    }
+}
+
+async fn hook_handler(
+    State(registry): State<WebhookRegistry>,
+    Path(path): Path<String>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> StatusCode {
+    let path = path.trim_matches('/');
+    match registry
+        .accept(path, &headers, &body, chrono::Utc::now().timestamp())
+        .await
+    {
+        Ok(()) => StatusCode::ACCEPTED,
+        Err(status) => status,
+    }
+}
+
+// ── Signature verification ──
+
+fn verify(
+    endpoint: &Endpoint,
+    headers: &HeaderMap,
+    body: &[u8],
+    now: i64,
+) -> Result<Verified, Rejection> {
+    let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+    let json = parse_body(body);
+
+    match endpoint.scheme {
+        WebhookScheme::GitHub => {
+            let signature = header("x-hub-signature-256")
+                .and_then(|s| s.strip_prefix("sha256="))
+                .ok_or(Rejection::MissingSignature)?;
+            check_hmac(&endpoint.secret, &[body], signature)?;
+            // GitHub signs only the body, so a replayed body is a replay
+            // whatever its X-GitHub-Delivery header says
+            Ok(Verified {
+                replay_key: digest(&[body]),
+                delivery: header("x-github-delivery").map(str::to_string),
+                event_type: header("x-github-event").map(str::to_string),
+                body: json,
+            })
+        }
+        WebhookScheme::Stripe => {
+            let header_value = header("stripe-signature").ok_or(Rejection::MissingSignature)?;
+            let mut timestamp = None;
+            let mut signatures = Vec::new();
+            for part in header_value.split(',') {
+                match part.trim().split_once('=') {
+                    Some(("t", t)) => timestamp = Some(t),
+                    Some(("v1", v)) => signatures.push(v),
+                    _ => {}
+                }
+            }
+            let timestamp = timestamp.ok_or(Rejection::MissingSignature)?;
+            check_timestamp(timestamp, now)?;
+            let signed = [timestamp.as_bytes(), b".", body];
+            if !signatures
+                .into_iter()
+                .any(|sig| check_hmac(&endpoint.secret, &signed, sig).is_ok())
+            {
+                return Err(Rejection::BadSignature);
+            }
+            // The event ID is part of the signed body, so Stripe's retries of
+            // one event (re-signed with a new timestamp) count as replays
+            let event_id = json.get("id").and_then(|v| v.as_str());
+            Ok(Verified {
+                replay_key: match event_id {
+                    Some(id) => digest(&[b"id:", id.as_bytes()]),
+                    None => digest(&signed),
+                },
+                delivery: event_id.map(str::to_string),
+                event_type: json
+                    .get("type")
+                    .and_then(|v| v.as_str())
+                    .map(str::to_string),
+                body: json,
+            })
+        }
+        WebhookScheme::Generic => {
+            let timestamp = header("x-webhook-timestamp").ok_or(Rejection::MissingSignature)?;
+            let signature = header("x-webhook-signature")
+                .map(|s| s.strip_prefix("sha256=").unwrap_or(s))
+                .ok_or(Rejection::MissingSignature)?;
+            check_timestamp(timestamp, now)?;
+            let signed = [timestamp.as_bytes(), b".", body];
+            check_hmac(&endpoint.secret, &signed, signature)?;
+            Ok(Verified {
+                replay_key: digest(&signed),
+                delivery: header("x-webhook-id").map(str::to_string),
+                event_type: header("x-webhook-event").map(str::to_string).or_else(|| {
+                    json.get("type")
+                        .and_then(|v| v.as_str())
+                        .map(str::to_string)
+                }),
+                body: json,
+            })
+        }
+    }
+}
+
+/// Hex SHA-256 of the concatenated parts
+fn digest(parts: &[&[u8]]) -> String {
+    let mut hasher = Sha256::new();
+    for part in parts {
+        hasher.update(part);
+    }
+    hex::encode(hasher.finalize())
+}
+
+/// Constant-time check of a hex HMAC-SHA256 over the concatenated parts
+fn check_hmac(secret: &str, parts: &[&[u8]], signature_hex: &str) -> Result<(), Rejection> {
+    let signature = hex::decode(signature_hex.trim()).map_err(|_| Rejection::BadSignature)?;
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).map_err(|_| Rejection::BadSignature)?;
+    for part in parts {
+        mac.update(part);
+    }
+    mac.verify_slice(&signature)
+        .map_err(|_| Rejection::BadSignature)
+}
+
+fn check_timestamp(timestamp: &str, now: i64) -> Result<(), Rejection> {
+    let timestamp: i64 = timestamp
+        .trim()
+        .parse()
+        .map_err(|_| Rejection::StaleTimestamp)?;
+    if (now - timestamp).abs() > TIMESTAMP_TOLERANCE_SECS {
+        return Err(Rejection::StaleTimestamp);
+    }
+    Ok(())
+}
+
+/// JSON bodies are passed through as-is; anything else as a string
+fn parse_body(body: &[u8]) -> serde_json::Value {
+    serde_json::from_slice(body)
+        .unwrap_or_else(|_| serde_json::Value::String(String::from_utf8_lossy(body).into_owned()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::Request;
+    use tower::ServiceExt;
+
+    const SECRET: &str = "whsec_test";
+
+    fn sign(parts: &[&[u8]]) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(SECRET.as_bytes()).unwrap();
+        for part in parts {
+            mac.update(part);
+        }
+        hex::encode(mac.finalize().into_bytes())
+    }
+
+    fn watcher(path: &str, scheme: WebhookScheme) -> Watcher {
+        Watcher::new(
+            WatcherKind::Webhook {
+                path: path.to_string(),
+                secret: SECRET.to_string(),
+                scheme,
+                max_body_bytes: 1024,
+            },
+            "Handle the webhook".to_string(),
+            "ch".to_string(),
+        )
+    }
+
+    fn registry() -> (WebhookRegistry, mpsc::UnboundedReceiver<WatcherEvent>) {
+        let (tx, rx) = mpsc::unbounded_channel();
+        (WebhookRegistry::new(tx), rx)
+    }
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.insert(*name, value.parse().unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn test_register_validates_paths() {
+        let (registry, _rx) = registry();
+        let first = watcher("github/meepo", WebhookScheme::GitHub);
+        registry.register(&first).unwrap();
+        // Re-registering the same watcher is fine
+        registry.register(&first).unwrap();
+
+        let conflict = watcher("github/meepo", WebhookScheme::GitHub);
+        assert!(registry.register(&conflict).is_err());
+        assert!(
+            registry
+                .register(&watcher("bad path!", WebhookScheme::Generic))
+                .is_err()
+        );
+        assert!(
+            registry
+                .register(&watcher("a//b", WebhookScheme::Generic))
+                .is_err()
+        );
+        assert_eq!(registry.paths(), vec!["github/meepo"]);
+
+        registry.unregister(&first.id);
+        assert!(registry.paths().is_empty());
+        registry.register(&conflict).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_github_signature() {
+        let (registry, mut rx) = registry();
+        let w = watcher("gh", WebhookScheme::GitHub);
+        registry.register(&w).unwrap();
+        let body = br#"{"action":"opened","number":7}"#;
+        let signature = format!("sha256={}", sign(&[body]));
+
+        let good = headers(&[
+            ("x-hub-signature-256", &signature),
+            ("x-github-event", "pull_request"),
+            ("x-github-delivery", "d-1"),
+        ]);
+        registry.accept("gh", &good, body, 0).await.unwrap();
+        let event = rx.try_recv().unwrap();
+        assert_eq!(event.watcher_id, w.id);
+        assert_eq!(event.kind, "webhook_received");
+        assert_eq!(event.payload["event"], "pull_request");
+        assert_eq!(event.payload["body"]["number"], 7);
+
+        // Same delivery again is a replay
+        assert_eq!(
+            registry.accept("gh", &good, body, 0).await,
+            Err(StatusCode::CONFLICT)
+        );
+
+        let tampered = br#"{"action":"closed","number":7}"#;
+        let retry = headers(&[
+            ("x-hub-signature-256", &signature),
+            ("x-github-delivery", "d-2"),
+        ]);
+        assert_eq!(
+            registry.accept("gh", &retry, tampered, 0).await,
+            Err(StatusCode::UNAUTHORIZED)
+        );
+        assert_eq!(
+            registry.accept("gh", &HeaderMap::new(), body, 0).await,
+            Err(StatusCode::UNAUTHORIZED)
+        );
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_replay_rejected_after_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("scheduler.db");
+        let open = || {
+            let conn = rusqlite::Connection::open(&path).unwrap();
+            meepo_scheduler::persistence::init_watcher_tables(&conn).unwrap();
+            WebhookDeliveries::new(Arc::new(std::sync::Mutex::new(conn)))
+        };
+
+        let w = watcher("gh", WebhookScheme::GitHub);
+        let body = br#"{"action":"opened"}"#;
+        let signature = format!("sha256={}", sign(&[body]));
+        let request = headers(&[
+            ("x-hub-signature-256", &signature),
+            ("x-github-delivery", "d-1"),
+        ]);
+
+        let (first, mut rx) = registry();
+        let first = first.with_deliveries(open());
+        first.register(&w).unwrap();
+        first.accept("gh", &request, body, 0).await.unwrap();
+        assert!(rx.try_recv().is_ok());
+        drop(first);
+
+        // A new registry on the same database still knows the delivery
+        let (restarted, mut rx) = registry();
+        let restarted = restarted.with_deliveries(open());
+        restarted.register(&w).unwrap();
+        assert_eq!(
+            restarted.accept("gh", &request, body, 0).await,
+            Err(StatusCode::CONFLICT)
+        );
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_stripe_signature_and_tolerance() {
+        let (registry, mut rx) = registry();
+        registry
+            .register(&watcher("stripe", WebhookScheme::Stripe))
+            .unwrap();
+        let body = br#"{"id":"evt_1","type":"invoice.paid"}"#;
+        let now = 1_700_000_000;
+        let t = now.to_string();
+        let header_value = format!("t={},v1=00ff,v1={}", t, sign(&[t.as_bytes(), b".", body]));
+        let good = headers(&[("stripe-signature", &header_value)]);
+
+        // Too old
+        assert_eq!(
+            registry.accept("stripe", &good, body, now + 301).await,
+            Err(StatusCode::UNAUTHORIZED)
+        );
+        registry
+            .accept("stripe", &good, body, now + 10)
+            .await
+            .unwrap();
+        assert_eq!(rx.try_recv().unwrap().payload["event"], "invoice.paid");
+
+        // Replayed event id
+        assert_eq!(
+            registry.accept("stripe", &good, body, now + 20).await,
+            Err(StatusCode::CONFLICT)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_generic_signature() {
+        let (registry, mut rx) = registry();
+        registry
+            .register(&watcher("ci", WebhookScheme::Generic))
+            .unwrap();
+        let body = b"build 42 failed";
+        let ts = "1700000000";
+        let signature = format!("sha256={}", sign(&[ts.as_bytes(), b".", body]));
+        let good = headers(&[
+            ("x-webhook-timestamp", ts),
+            ("x-webhook-signature", &signature),
+            ("x-webhook-event", "build.failed"),
+        ]);
+        registry
+            .accept("ci", &good, body, 1_700_000_100)
+            .await
+            .unwrap();
+        let event = rx.try_recv().unwrap();
+        assert_eq!(event.payload["event"], "build.failed");
+        assert_eq!(event.payload["body"], "build 42 failed");
+
+        // Signature must cover the timestamp
+        let shifted = headers(&[
+            ("x-webhook-timestamp", "1700000001"),
+            ("x-webhook-signature", &signature),
+        ]);
+        assert_eq!(
+            registry.accept("ci", &shifted, body, 1_700_000_100).await,
+            Err(StatusCode::UNAUTHORIZED)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_replay_with_new_delivery_id_is_rejected() {
+        let (registry, mut rx) = registry();
+        registry
+            .register(&watcher("gh", WebhookScheme::GitHub))
+            .unwrap();
+        registry
+            .register(&watcher("ci", WebhookScheme::Generic))
+            .unwrap();
+
+        let body = br#"{"action":"opened","number":7}"#;
+        let signature = format!("sha256={}", sign(&[body]));
+        for (delivery, expected) in [("d-1", Ok(())), ("d-forged", Err(StatusCode::CONFLICT))] {
+            let request = headers(&[
+                ("x-hub-signature-256", &signature),
+                ("x-github-delivery", delivery),
+            ]);
+            assert_eq!(registry.accept("gh", &request, body, 0).await, expected);
+        }
+
+        let body = b"build 42 failed";
+        let ts = "1700000000";
+        let signature = format!("sha256={}", sign(&[ts.as_bytes(), b".", body]));
+        for (delivery, expected) in [("w-1", Ok(())), ("w-forged", Err(StatusCode::CONFLICT))] {
+            let request = headers(&[
+                ("x-webhook-timestamp", ts),
+                ("x-webhook-signature", &signature),
+                ("x-webhook-id", delivery),
+            ]);
+            assert_eq!(
+                registry.accept("ci", &request, body, 1_700_000_100).await,
+                expected
+            );
+        }
+
+        assert_eq!(drain_count(&mut rx), 2);
+    }
+
+    fn drain_count(rx: &mut mpsc::UnboundedReceiver<WatcherEvent>) -> usize {
+        std::iter::from_fn(|| rx.try_recv().ok()).count()
+    }
+
+    #[tokio::test]
+    async fn test_router_status_codes() {
+        let (registry, mut rx) = registry();
+        registry
+            .register(&watcher("ci", WebhookScheme::Generic))
+            .unwrap();
+        let router = registry.router();
+
+        let post = |path: &str, body: Vec<u8>, signed: bool| {
+            let ts = chrono::Utc::now().timestamp().to_string();
+            let mut request = Request::post(path);
+            if signed {
+                let signature = sign(&[ts.as_bytes(), b".", &body]);
+                request = request
+                    .header("x-webhook-timestamp", ts)
+                    .header("x-webhook-signature", format!("sha256={}", signature));
+            }
+            request.body(Body::from(body)).unwrap()
+        };
+
+        let status = |response: axum::response::Response| response.status();
+        let ok = router
+            .clone()
+            .oneshot(post("/hooks/ci", b"{\"ok\":true}".to_vec(), true))
+            .await
+            .unwrap();
+        assert_eq!(status(ok), StatusCode::ACCEPTED);
+        assert_eq!(rx.try_recv().unwrap().payload["body"]["ok"], true);
+
+        let unsigned = router
+            .clone()
+            .oneshot(post("/hooks/ci", b"{}".to_vec(), false))
+            .await
+            .unwrap();
+        assert_eq!(status(unsigned), StatusCode::UNAUTHORIZED);
+
+        let unknown = router
+            .clone()
+            .oneshot(post("/hooks/nope", b"{}".to_vec(), true))
+            .await
+            .unwrap();
+        assert_eq!(status(unknown), StatusCode::NOT_FOUND);
+
+        let too_large = router
+            .oneshot(post("/hooks/ci", vec![b'x'; 2048], true))
+            .await
+            .unwrap();
+        assert_eq!(status(too_large), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+}
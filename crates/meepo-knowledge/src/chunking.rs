@@ -2,20 +2,54 @@
 //!
 //! Splits documents into overlapping chunks for indexing in the knowledge
 //! graph. Supports recursive character splitting with configurable chunk
-//! size and overlap.
+//! size and overlap, measured in bytes or estimated tokens.
+//!
+//! [`chunk_document`] picks a [`ChunkStrategy`] from the content type:
+//! Markdown (and formats extracted to Markdown) is split along its heading
+//! hierarchy, with the heading path recorded on every chunk, and source code
+//! is split at function and type definitions. Sections or definitions that
+//! are still too large fall back to recursive splitting.
 
 use serde::{Deserialize, Serialize};
 use tracing::debug;
 
+use crate::extract::{Location, markdown_heading};
+
+/// Unit that chunk sizes are measured in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ChunkUnit {
+    /// Bytes of UTF-8 text
+    #[default]
+    Chars,
+    /// Estimated model tokens, see [`count_tokens`]
+    Tokens,
+}
+
+impl std::str::FromStr for ChunkUnit {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.to_lowercase().as_str() {
+            "chars" => Ok(Self::Chars),
+            "tokens" => Ok(Self::Tokens),
+            other => anyhow::bail!(
+                "Unknown chunk unit '{}' (expected 'tokens' or 'chars')",
+                other
+            ),
+        }
+    }
+}
+
 /// Configuration for document chunking
 #[derive(Debug, Clone)]
 pub struct ChunkingConfig {
-    /// Target chunk size in characters
+    /// Target chunk size, in `unit`s
     pub chunk_size: usize,
-    /// Overlap between consecutive chunks in characters
+    /// Overlap between consecutive chunks, in `unit`s
     pub chunk_overlap: usize,
     /// Separators to split on, in priority order
     pub separators: Vec<String>,
+    pub unit: ChunkUnit,
 }
 
 impl Default for ChunkingConfig {
@@ -33,10 +67,89 @@ impl Default for ChunkingConfig {
                 ", ".to_string(),
                 " ".to_string(),
             ],
+            unit: ChunkUnit::Chars,
         }
     }
 }
 
+impl ChunkingConfig {
+    /// Chunks sized in estimated tokens, with the default separators
+    pub fn tokens(chunk_size: usize, chunk_overlap: usize) -> Self {
+        Self {
+            chunk_size,
+            chunk_overlap,
+            unit: ChunkUnit::Tokens,
+            ..Default::default()
+        }
+    }
+
+    /// Size of `text` in this config's unit
+    pub fn measure(&self, text: &str) -> usize {
+        match self.unit {
+            ChunkUnit::Chars => text.len(),
+            ChunkUnit::Tokens => count_tokens(text),
+        }
+    }
+
+    /// Longest suffix of `text` no larger than `size`, cut at a word
+    /// boundary when measuring tokens
+    fn tail<'a>(&self, text: &'a str, size: usize) -> &'a str {
+        if size == 0 {
+            return "";
+        }
+        match self.unit {
+            ChunkUnit::Chars => {
+                let mut start = text.len().saturating_sub(size);
+                while !text.is_char_boundary(start) {
+                    start += 1;
+                }
+                &text[start..]
+            }
+            ChunkUnit::Tokens => {
+                let mut start = text.len();
+                let mut tokens = 0;
+                while start > 0 {
+                    let head = text[..start].trim_end();
+                    let word_start = head
+                        .char_indices()
+                        .rev()
+                        .find(|(_, c)| c.is_whitespace())
+                        .map(|(i, c)| i + c.len_utf8())
+                        .unwrap_or(0);
+                    tokens += count_tokens(&head[word_start..]);
+                    if tokens > size {
+                        break;
+                    }
+                    start = word_start;
+                }
+                &text[start..]
+            }
+        }
+    }
+}
+
+/// Estimate how many tokens a model tokenizer would produce for `text`.
+///
+/// Approximates common BPE vocabularies on English prose and code: a run of
+/// letters, digits and underscores counts one token per six characters,
+/// every other non-whitespace character counts one, and whitespace is free.
+pub fn count_tokens(text: &str) -> usize {
+    let mut tokens = 0;
+    let mut word: usize = 0;
+    for c in text.chars() {
+        if c.is_ascii_alphanumeric() || c == '_' {
+            word += 1;
+            continue;
+        }
+        tokens += word.div_ceil(6);
+        word = 0;
+        if !c.is_whitespace() {
+            tokens += 1;
+        }
+    }
+    tokens + word.div_ceil(6)
+}
+
 /// A chunk of a document with position metadata
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DocumentChunk {
@@ -50,6 +163,19 @@ pub struct DocumentChunk {
     pub end_offset: usize,
     /// Total number of chunks in the document
     pub total_chunks: usize,
+    /// Page the chunk starts on, for paginated formats such as PDF
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub page: Option<u32>,
+    /// Heading path the chunk starts under, e.g. `3 Results > 3.1 Setup`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub section: Option<String>,
+}
+
+impl DocumentChunk {
+    /// Human-readable location of the chunk, e.g. `p. 12, §3 Results`
+    pub fn citation(&self) -> Option<String> {
+        format_citation(self.page, self.section.as_deref())
+    }
 }
 
 /// Metadata about an ingested document
@@ -78,57 +204,524 @@ pub fn chunk_text(text: &str, config: &ChunkingConfig) -> Vec<DocumentChunk> {
     }
 
     // If text fits in one chunk, return it directly
-    if text.len() <= config.chunk_size {
+    if config.measure(text) <= config.chunk_size {
         return vec![DocumentChunk {
             content: text.to_string(),
             chunk_index: 0,
             start_offset: 0,
             end_offset: text.len(),
             total_chunks: 1,
+            page: None,
+            section: None,
         }];
     }
 
-    let raw_chunks = recursive_split(text, &config.separators, config.chunk_size);
+    let raw_chunks = recursive_split(text, &config.separators, config);
 
     // Merge small chunks and apply overlap
-    let merged = merge_with_overlap(&raw_chunks, config.chunk_size, config.chunk_overlap);
+    let merged = merge_with_overlap(&raw_chunks, config);
 
-    // Build DocumentChunk structs with offsets
+    // Raw chunks tile the text, and each overlap is a suffix of the previous
+    // raw chunk, so offsets follow from the lengths
     let total = merged.len();
     let mut chunks = Vec::with_capacity(total);
     let mut offset = 0;
 
-    for (i, chunk_text) in merged.iter().enumerate() {
-        // Find the actual position in the original text
-        let start = if i == 0 {
-            0
-        } else {
-            text[offset..]
-                .find(chunk_text.split_at(chunk_text.len().min(50)).0)
-                .map(|pos| offset + pos)
-                .unwrap_or(offset)
-        };
-
+    for (i, ((overlap, chunk_text), raw)) in merged.into_iter().zip(&raw_chunks).enumerate() {
+        let start = offset - overlap;
         let end = start + chunk_text.len();
 
         chunks.push(DocumentChunk {
-            content: chunk_text.clone(),
+            content: chunk_text,
             chunk_index: i,
             start_offset: start,
             end_offset: end.min(text.len()),
             total_chunks: total,
+            page: None,
+            section: None,
         });
 
-        offset = start + chunk_text.len().saturating_sub(config.chunk_overlap);
+        offset += raw.len();
     }
 
     debug!("Split {} chars into {} chunks", text.len(), chunks.len());
     chunks
 }
 
-/// Recursively split text on separators
-fn recursive_split(text: &str, separators: &[String], chunk_size: usize) -> Vec<String> {
-    if text.len() <= chunk_size || separators.is_empty() {
+/// Fill in `page` and `section` of chunks from an extracted document's locations.
+///
+/// Locations must be sorted by offset; each chunk takes the location in
+/// effect at its start offset. A section already set by a structure-aware
+/// strategy is kept.
+pub fn annotate_chunks(chunks: &mut [DocumentChunk], locations: &[Location]) {
+    for chunk in chunks.iter_mut() {
+        let idx = locations.partition_point(|l| l.offset <= chunk.start_offset);
+        if idx == 0 {
+            continue;
+        }
+        let location = &locations[idx - 1];
+        chunk.page = location.page;
+        if chunk.section.is_none() {
+            chunk.section = location.section.clone();
+        }
+    }
+}
+
+/// Format a page and heading path as a short citation
+pub fn format_citation(page: Option<u32>, section: Option<&str>) -> Option<String> {
+    let section = section.map(|s| s.rsplit(" > ").next().unwrap_or(s));
+    match (page, section) {
+        (Some(p), Some(s)) => Some(format!("p. {}, §{}", p, s)),
+        (Some(p), None) => Some(format!("p. {}", p)),
+        (None, Some(s)) => Some(format!("§{}", s)),
+        (None, None) => None,
+    }
+}
+
+/// How a document is split into chunks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChunkStrategy {
+    /// Recursive separator splitting, for prose and unknown formats
+    Text,
+    /// Split along the heading hierarchy, merging small sibling sections
+    Markdown,
+    /// Split at function, class and type definitions
+    Code(CodeLanguage),
+}
+
+/// Languages with syntax-aware chunking
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CodeLanguage {
+    Rust,
+    Python,
+    /// TypeScript and JavaScript
+    TypeScript,
+    Go,
+}
+
+impl ChunkStrategy {
+    /// Strategy for a content type as returned by [`detect_content_type`].
+    ///
+    /// HTML, DOCX and EPUB are extracted to Markdown, so they are split by
+    /// heading as well.
+    pub fn for_content_type(content_type: &str) -> Self {
+        match content_type {
+            "text/markdown"
+            | "text/html"
+            | "application/epub+zip"
+            | "application/vnd.openxmlformats-officedocument.wordprocessingml.document" => {
+                Self::Markdown
+            }
+            "text/x-rust" => Self::Code(CodeLanguage::Rust),
+            "text/x-python" => Self::Code(CodeLanguage::Python),
+            "text/javascript" | "text/typescript" => Self::Code(CodeLanguage::TypeScript),
+            "text/x-go" => Self::Code(CodeLanguage::Go),
+            _ => Self::Text,
+        }
+    }
+}
+
+/// Split a document with the strategy for its content type
+pub fn chunk_document(
+    text: &str,
+    content_type: &str,
+    config: &ChunkingConfig,
+) -> Vec<DocumentChunk> {
+    chunk_with_strategy(text, ChunkStrategy::for_content_type(content_type), config)
+}
+
+/// Split a document with an explicit strategy.
+///
+/// Structure-aware strategies set `section` on every chunk to the heading
+/// path (Markdown) or definition path (code) the chunk starts under.
+/// Adjacent small sections are merged up to `chunk_size`; only the fallback
+/// splitting of oversized sections applies `chunk_overlap`.
+pub fn chunk_with_strategy(
+    text: &str,
+    strategy: ChunkStrategy,
+    config: &ChunkingConfig,
+) -> Vec<DocumentChunk> {
+    let spans = match strategy {
+        ChunkStrategy::Text => return chunk_text(text, config),
+        ChunkStrategy::Markdown => markdown_spans(text, config),
+        ChunkStrategy::Code(language) => {
+            let mut spans = Vec::new();
+            let whole = Span {
+                start: 0,
+                end: text.len(),
+                path: Vec::new(),
+            };
+            split_code(text, whole, 0, language, config, &mut spans);
+            pack_spans(text, spans, config, |_, _| true)
+        }
+    };
+
+    let spans: Vec<Span> = spans
+        .into_iter()
+        .filter(|s| !text[s.start..s.end].trim().is_empty())
+        .collect();
+    let total = spans.len();
+    let chunks: Vec<DocumentChunk> = spans
+        .into_iter()
+        .enumerate()
+        .map(|(i, span)| DocumentChunk {
+            content: text[span.start..span.end].to_string(),
+            chunk_index: i,
+            start_offset: span.start,
+            end_offset: span.end,
+            total_chunks: total,
+            page: None,
+            section: (!span.path.is_empty()).then(|| span.path.join(" > ")),
+        })
+        .collect();
+    debug!(
+        "Split {} chars into {} chunks ({:?})",
+        text.len(),
+        chunks.len(),
+        strategy
+    );
+    chunks
+}
+
+/// A byte range of a document and the heading or definition path it starts under
+#[derive(Debug, Clone)]
+struct Span {
+    start: usize,
+    end: usize,
+    path: Vec<String>,
+}
+
+/// Push `span` whole if it fits, otherwise split it recursively with overlap
+fn split_span(text: &str, span: Span, config: &ChunkingConfig, out: &mut Vec<Span>) {
+    let slice = &text[span.start..span.end];
+    if config.measure(slice) <= config.chunk_size {
+        out.push(span);
+        return;
+    }
+    for chunk in chunk_text(slice, config) {
+        out.push(Span {
+            start: span.start + chunk.start_offset,
+            end: span.start + chunk.end_offset,
+            path: span.path.clone(),
+        });
+    }
+}
+
+/// Merge adjacent spans while they fit in one chunk and `mergeable` allows it
+fn pack_spans(
+    text: &str,
+    spans: Vec<Span>,
+    config: &ChunkingConfig,
+    mergeable: impl Fn(&Span, &Span) -> bool,
+) -> Vec<Span> {
+    let mut packed: Vec<Span> = Vec::with_capacity(spans.len());
+    for span in spans {
+        if let Some(last) = packed.last_mut()
+            && last.end == span.start
+            && mergeable(last, &span)
+            && config.measure(&text[last.start..span.end]) <= config.chunk_size
+        {
+            last.end = span.end;
+            // A preamble without a heading or definition takes the path of
+            // what follows it
+            if last.path.is_empty() {
+                last.path = span.path;
+            }
+            continue;
+        }
+        packed.push(span);
+    }
+    packed
+}
+
+/// Split Markdown into one span per heading section, then merge small
+/// sections that share a top-level heading
+fn markdown_spans(text: &str, config: &ChunkingConfig) -> Vec<Span> {
+    let mut sections = Vec::new();
+    let mut headings: Vec<(usize, String)> = Vec::new();
+    let mut current = Span {
+        start: 0,
+        end: 0,
+        path: Vec::new(),
+    };
+    let mut in_fence = false;
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        let trimmed = line.trim();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+        } else if !in_fence && let Some((level, heading)) = markdown_heading(trimmed) {
+            headings.retain(|(l, _)| *l < level);
+            headings.push((level, heading.to_string()));
+            let next = Span {
+                start: offset,
+                end: offset,
+                path: headings.iter().map(|(_, h)| h.clone()).collect(),
+            };
+            let mut done = std::mem::replace(&mut current, next);
+            done.end = offset;
+            if done.end > done.start {
+                sections.push(done);
+            }
+        }
+        offset += line.len();
+    }
+    current.end = text.len();
+    sections.push(current);
+
+    let mut spans = Vec::new();
+    for section in sections {
+        split_span(text, section, config, &mut spans);
+    }
+    pack_spans(text, spans, config, |a, b| a.path.first() == b.path.first())
+}
+
+/// Split `region` of source code at the definitions indented at least
+/// `min_indent`, recursing into definitions that are still too large
+fn split_code(
+    text: &str,
+    region: Span,
+    min_indent: usize,
+    language: CodeLanguage,
+    config: &ChunkingConfig,
+    out: &mut Vec<Span>,
+) {
+    if config.measure(&text[region.start..region.end]) <= config.chunk_size {
+        out.push(region);
+        return;
+    }
+
+    let mut lines = Vec::new();
+    let mut offset = region.start;
+    for line in text[region.start..region.end].split_inclusive('\n') {
+        lines.push((offset, line));
+        offset += line.len();
+    }
+
+    let definitions: Vec<usize> = (0..lines.len())
+        .filter(|&i| {
+            let line = lines[i].1;
+            indentation(line) >= min_indent && language.is_definition(line.trim_start())
+        })
+        .collect();
+    let Some(level) = definitions.iter().map(|&i| indentation(lines[i].1)).min() else {
+        split_span(text, region, config, out);
+        return;
+    };
+
+    // Each definition starts at its doc comments, attributes or decorators
+    let mut boundaries: Vec<(usize, String)> = Vec::new();
+    for &i in definitions
+        .iter()
+        .filter(|&&i| indentation(lines[i].1) == level)
+    {
+        let mut first = i;
+        while first > 0 {
+            let prev = lines[first - 1].1;
+            if prev.trim().is_empty()
+                || indentation(prev) != level
+                || !language.is_preamble(prev.trim_start())
+            {
+                break;
+            }
+            first -= 1;
+        }
+        if boundaries.last().is_some_and(|(b, _)| *b >= first) {
+            continue;
+        }
+        boundaries.push((first, language.label(lines[i].1)));
+    }
+
+    // Imports and module docs before the first definition of a file are a
+    // chunk of their own; the signature of a split class or impl leads its
+    // first member instead
+    let head_end = lines[boundaries[0].0].0;
+    if min_indent == 0 && head_end > region.start {
+        let head = Span {
+            start: region.start,
+            end: head_end,
+            path: region.path.clone(),
+        };
+        split_span(text, head, config, out);
+    }
+    for (k, (line, label)) in boundaries.iter().enumerate() {
+        let mut path = region.path.clone();
+        path.push(label.clone());
+        let unit = Span {
+            start: if k == 0 && min_indent > 0 {
+                region.start
+            } else {
+                lines[*line].0
+            },
+            end: boundaries
+                .get(k + 1)
+                .map(|(next, _)| lines[*next].0)
+                .unwrap_or(region.end),
+            path,
+        };
+        split_code(text, unit, level + 1, language, config, out);
+    }
+}
+
+/// Width of a line's leading whitespace, counting tabs as four columns
+fn indentation(line: &str) -> usize {
+    line.chars()
+        .take_while(|c| *c == ' ' || *c == '\t')
+        .map(|c| if c == '\t' { 4 } else { 1 })
+        .sum()
+}
+
+/// Strip any of `prefixes` from the start of `line`, repeatedly
+fn strip_modifiers<'a>(mut line: &'a str, prefixes: &[&str]) -> &'a str {
+    while let Some(rest) = prefixes.iter().find_map(|p| line.strip_prefix(p)) {
+        line = rest.trim_start();
+    }
+    line
+}
+
+impl CodeLanguage {
+    /// Whether an unindented line starts a function, class or type definition
+    fn is_definition(self, line: &str) -> bool {
+        match self {
+            Self::Rust => {
+                let mut line = line;
+                // pub(crate), pub(super), pub(in path)
+                while let Some(rest) = line.strip_prefix("pub(")
+                    && let Some(end) = rest.find(')')
+                {
+                    line = rest[end + 1..].trim_start();
+                }
+                let line = strip_modifiers(
+                    line,
+                    &[
+                        "pub ",
+                        "async ",
+                        "const ",
+                        "unsafe ",
+                        "default ",
+                        "extern \"C\" ",
+                    ],
+                );
+                [
+                    "fn ",
+                    "struct ",
+                    "enum ",
+                    "trait ",
+                    "impl ",
+                    "impl<",
+                    "mod ",
+                    "union ",
+                    "type ",
+                    "macro_rules!",
+                ]
+                .iter()
+                .any(|k| line.starts_with(k))
+            }
+            Self::Python => {
+                let line = strip_modifiers(line, &["async "]);
+                line.starts_with("def ") || line.starts_with("class ")
+            }
+            Self::TypeScript => {
+                let line = strip_modifiers(line, &["export ", "default ", "declare ", "abstract "]);
+                if [
+                    "function ",
+                    "function*",
+                    "async function",
+                    "class ",
+                    "interface ",
+                    "enum ",
+                    "namespace ",
+                    "type ",
+                ]
+                .iter()
+                .any(|k| line.starts_with(k))
+                {
+                    return true;
+                }
+                if (line.starts_with("const ") || line.starts_with("let "))
+                    && (line.contains("=>") || line.contains("function"))
+                {
+                    return true;
+                }
+                is_method_definition(line)
+            }
+            Self::Go => line.starts_with("func ") || line.starts_with("type "),
+        }
+    }
+
+    /// Whether an unindented line belongs to the definition that follows it
+    /// (doc comments, attributes, decorators)
+    fn is_preamble(self, line: &str) -> bool {
+        let prefixes: &[&str] = match self {
+            Self::Rust => &["//", "#[", "/*", "*"],
+            Self::Python => &["@", "#"],
+            Self::TypeScript => &["@", "//", "/*", "*"],
+            Self::Go => &["//"],
+        };
+        prefixes.iter().any(|p| line.starts_with(p))
+    }
+
+    /// Short name of the definition starting on `line`, e.g. `pub fn parse`
+    fn label(self, line: &str) -> String {
+        let line = line.trim();
+        let (stops, from): (&[char], usize) = match self {
+            Self::Rust => (&['(', '{', ';', '='], 0),
+            Self::Python => (&['(', ':'], 0),
+            Self::TypeScript => (&['(', '{', '=', ';'], 0),
+            // Skip a method receiver: func (s *Server) Start(
+            Self::Go => (
+                &['(', '{'],
+                line.strip_prefix("func (")
+                    .and_then(|rest| rest.find(')'))
+                    .map(|i| "func (".len() + i + 1)
+                    .unwrap_or(0),
+            ),
+        };
+        let end = line[from..]
+            .find(stops)
+            .map(|i| from + i)
+            .unwrap_or(line.len());
+        let label = line[..end].trim();
+        let label = label.strip_suffix(" where").unwrap_or(label);
+        label.chars().take(80).collect()
+    }
+}
+
+/// A TypeScript class method such as `async load(id: string) {`
+fn is_method_definition(line: &str) -> bool {
+    let line = strip_modifiers(
+        line,
+        &[
+            "public ",
+            "private ",
+            "protected ",
+            "static ",
+            "readonly ",
+            "async ",
+            "get ",
+            "set ",
+            "override ",
+        ],
+    );
+    let name_len = line
+        .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$' || c == '#'))
+        .unwrap_or(line.len());
+    let name = &line[..name_len];
+    !name.is_empty()
+        && !matches!(
+            name,
+            "if" | "for" | "while" | "switch" | "catch" | "return" | "function" | "super"
+        )
+        && line[name_len..].starts_with(['(', '<'])
+        && (line.trim_end().ends_with('{') || line.trim_end().ends_with("{}"))
+}
+
+/// Recursively split text on separators.
+///
+/// The returned pieces concatenate back to `text`.
+fn recursive_split(text: &str, separators: &[String], config: &ChunkingConfig) -> Vec<String> {
+    let chunk_size = config.chunk_size;
+    if separators.is_empty() || config.measure(text) <= chunk_size {
         return vec![text.to_string()];
     }
 
@@ -139,6 +732,7 @@ fn recursive_split(text: &str, separators: &[String], chunk_size: usize) -> Vec<
 
     let mut result = Vec::new();
     let mut current = String::new();
+    let mut current_size = 0;
 
     for (i, split) in splits.iter().enumerate() {
         let with_sep = if i < splits.len() - 1 {
@@ -146,23 +740,26 @@ fn recursive_split(text: &str, separators: &[String], chunk_size: usize) -> Vec<
         } else {
             split.to_string()
         };
+        let size = config.measure(&with_sep);
 
-        if current.len() + with_sep.len() > chunk_size && !current.is_empty() {
+        if current_size + size > chunk_size && !current.is_empty() {
             // Current chunk is full, try to recursively split if still too large
-            if current.len() > chunk_size {
-                result.extend(recursive_split(&current, remaining_separators, chunk_size));
+            if current_size > chunk_size {
+                result.extend(recursive_split(&current, remaining_separators, config));
             } else {
                 result.push(current.clone());
             }
             current.clear();
+            current_size = 0;
         }
 
         current.push_str(&with_sep);
+        current_size += size;
     }
 
     if !current.is_empty() {
-        if current.len() > chunk_size {
-            result.extend(recursive_split(&current, remaining_separators, chunk_size));
+        if current_size > chunk_size {
+            result.extend(recursive_split(&current, remaining_separators, config));
         } else {
             result.push(current);
         }
@@ -171,40 +768,26 @@ fn recursive_split(text: &str, separators: &[String], chunk_size: usize) -> Vec<
     result
 }
 
-/// Merge chunks and add overlap between consecutive chunks
-fn merge_with_overlap(chunks: &[String], max_size: usize, overlap: usize) -> Vec<String> {
-    if chunks.is_empty() {
-        return Vec::new();
-    }
-
-    if chunks.len() == 1 {
-        return chunks.to_vec();
-    }
-
-    let mut result = Vec::new();
+/// Prepend overlap from the previous chunk to each chunk.
+///
+/// Returns each chunk with the byte length of the overlap it starts with.
+fn merge_with_overlap(chunks: &[String], config: &ChunkingConfig) -> Vec<(usize, String)> {
+    let overlap = config.chunk_overlap;
+    let mut result = Vec::with_capacity(chunks.len());
 
     for (i, chunk) in chunks.iter().enumerate() {
         if i == 0 {
-            result.push(chunk.clone());
-        } else {
-            // Prepend overlap from previous chunk
-            let prev = &chunks[i - 1];
-            let overlap_text = if prev.len() > overlap {
-                &prev[prev.len() - overlap..]
-            } else {
-                prev.as_str()
-            };
+            result.push((0, chunk.clone()));
+            continue;
+        }
 
-            let merged = format!("{}{}", overlap_text, chunk);
-            if merged.len() <= max_size + overlap {
-                result.push(merged);
-            } else {
-                // If merged is too large, just use the chunk with truncated overlap
-                let truncated_overlap =
-                    &overlap_text[overlap_text.len().saturating_sub(overlap / 2)..];
-                result.push(format!("{}{}", truncated_overlap, chunk));
-            }
+        let prev = &chunks[i - 1];
+        let mut overlap_text = config.tail(prev, overlap);
+        if config.measure(overlap_text) + config.measure(chunk) > config.chunk_size + overlap {
+            // If merged is too large, just use the chunk with truncated overlap
+            overlap_text = config.tail(prev, overlap / 2);
         }
+        result.push((overlap_text.len(), format!("{}{}", overlap_text, chunk)));
     }
 
     result
@@ -221,16 +804,31 @@ pub fn detect_content_type(path: &str) -> &'static str {
         "text/x-rust"
     } else if lower.ends_with(".py") {
         "text/x-python"
-    } else if lower.ends_with(".js") || lower.ends_with(".ts") {
+    } else if [".js", ".jsx", ".mjs", ".ts", ".tsx"]
+        .iter()
+        .any(|ext| lower.ends_with(ext))
+    {
         "text/javascript"
+    } else if lower.ends_with(".go") {
+        "text/x-go"
     } else if lower.ends_with(".json") {
         "application/json"
     } else if lower.ends_with(".toml") {
         "application/toml"
     } else if lower.ends_with(".yaml") || lower.ends_with(".yml") {
         "application/yaml"
-    } else if lower.ends_with(".html") || lower.ends_with(".htm") {
+    } else if lower.ends_with(".html") || lower.ends_with(".htm") || lower.ends_with(".xhtml") {
         "text/html"
+    } else if lower.ends_with(".pdf") {
+        "application/pdf"
+    } else if lower.ends_with(".docx") {
+        "application/vnd.openxmlformats-officedocument.wordprocessingml.document"
+    } else if lower.ends_with(".epub") {
+        "application/epub+zip"
+    } else if lower.ends_with(".eml") {
+        "message/rfc822"
+    } else if lower.ends_with(".mbox") {
+        "application/mbox"
     } else if lower.ends_with(".csv") {
         "text/csv"
     } else {
@@ -312,6 +910,13 @@ mod tests {
         assert!(chunks.iter().any(|c| c.content.contains("Fourth")));
     }
 
+    #[test]
+    fn test_chunk_unit_parse() {
+        assert_eq!("tokens".parse::<ChunkUnit>().unwrap(), ChunkUnit::Tokens);
+        assert_eq!("Chars".parse::<ChunkUnit>().unwrap(), ChunkUnit::Chars);
+        assert!("words".parse::<ChunkUnit>().is_err());
+    }
+
     #[test]
     fn test_detect_content_type() {
         assert_eq!(detect_content_type("readme.md"), "text/markdown");
@@ -327,12 +932,53 @@ mod tests {
         assert_eq!(detect_content_type("script.py"), "text/x-python");
         assert_eq!(detect_content_type("app.js"), "text/javascript");
         assert_eq!(detect_content_type("app.ts"), "text/javascript");
+        assert_eq!(detect_content_type("view.tsx"), "text/javascript");
+        assert_eq!(detect_content_type("main.go"), "text/x-go");
         assert_eq!(detect_content_type("config.toml"), "application/toml");
         assert_eq!(detect_content_type("config.yaml"), "application/yaml");
         assert_eq!(detect_content_type("config.yml"), "application/yaml");
         assert_eq!(detect_content_type("page.html"), "text/html");
         assert_eq!(detect_content_type("page.htm"), "text/html");
         assert_eq!(detect_content_type("data.csv"), "text/csv");
+        assert_eq!(detect_content_type("paper.pdf"), "application/pdf");
+        assert_eq!(detect_content_type("book.epub"), "application/epub+zip");
+        assert_eq!(detect_content_type("note.eml"), "message/rfc822");
+        assert_eq!(detect_content_type("archive.mbox"), "application/mbox");
+    }
+
+    #[test]
+    fn test_annotate_chunks_and_citation() {
+        let config = ChunkingConfig {
+            chunk_size: 40,
+            chunk_overlap: 0,
+            ..Default::default()
+        };
+        let text = "Intro text that is long enough.\n\nResults text that is long enough.";
+        let mut chunks = chunk_text(text, &config);
+        assert_eq!(chunks.len(), 2);
+        let results_at = text.find("Results").unwrap();
+        annotate_chunks(
+            &mut chunks,
+            &[
+                Location {
+                    offset: 0,
+                    page: Some(1),
+                    section: None,
+                },
+                Location {
+                    offset: results_at,
+                    page: Some(2),
+                    section: Some("3 Results".to_string()),
+                },
+            ],
+        );
+        assert_eq!(chunks[0].citation().as_deref(), Some("p. 1"));
+        assert_eq!(chunks[1].citation().as_deref(), Some("p. 2, §3 Results"));
+        assert_eq!(
+            format_citation(None, Some("Guide > Install")).as_deref(),
+            Some("§Install")
+        );
+        assert_eq!(format_citation(None, None), None);
     }
 
     #[test]
@@ -373,6 +1019,8 @@ mod tests {
             start_offset: 100,
             end_offset: 200,
             total_chunks: 5,
+            page: None,
+            section: None,
         };
         let json = serde_json::to_string(&chunk).unwrap();
         let parsed: DocumentChunk = serde_json::from_str(&json).unwrap();
@@ -407,4 +1055,223 @@ mod tests {
         let chunks = chunk_text(text, &config);
         assert!(chunks.len() > 1);
     }
+
+    #[test]
+    fn test_count_tokens() {
+        assert_eq!(count_tokens(""), 0);
+        assert_eq!(count_tokens("  \n\t"), 0);
+        assert_eq!(count_tokens("hello world"), 2);
+        assert_eq!(count_tokens("internationalization"), 4);
+        assert_eq!(count_tokens("fn main() {}"), 6);
+    }
+
+    #[test]
+    fn test_chunk_text_token_sizing() {
+        let config = ChunkingConfig::tokens(100, 10);
+        let text = "word ".repeat(500);
+        let chunks = chunk_text(&text, &config);
+        assert!(chunks.len() >= 5);
+        for chunk in &chunks {
+            assert_eq!(chunk.content, text[chunk.start_offset..chunk.end_offset]);
+            assert!(count_tokens(&chunk.content) <= 110);
+        }
+        assert!(chunks[1].start_offset < chunks[0].end_offset);
+        assert!(chunks[1].content.starts_with("word"));
+    }
+
+    #[test]
+    fn test_chunk_strategy_for_content_type() {
+        let strategy = |path: &str| ChunkStrategy::for_content_type(detect_content_type(path));
+        assert_eq!(strategy("notes.md"), ChunkStrategy::Markdown);
+        assert_eq!(strategy("page.html"), ChunkStrategy::Markdown);
+        assert_eq!(strategy("lib.rs"), ChunkStrategy::Code(CodeLanguage::Rust));
+        assert_eq!(
+            strategy("app.py"),
+            ChunkStrategy::Code(CodeLanguage::Python)
+        );
+        assert_eq!(
+            strategy("app.tsx"),
+            ChunkStrategy::Code(CodeLanguage::TypeScript)
+        );
+        assert_eq!(strategy("main.go"), ChunkStrategy::Code(CodeLanguage::Go));
+        assert_eq!(strategy("paper.pdf"), ChunkStrategy::Text);
+
+        let config = ChunkingConfig::tokens(20, 0);
+        let text = "Plain prose without any headings. ".repeat(10);
+        let plain = chunk_document(&text, "text/plain", &config);
+        let recursive = chunk_text(&text, &config);
+        assert_eq!(plain.len(), recursive.len());
+        assert!(plain.iter().all(|c| c.section.is_none()));
+    }
+
+    /// Chunks of a fixture at a size that forces both structural splits and
+    /// the fallback for oversized sections
+    fn fixture_chunks(text: &str, content_type: &str) -> Vec<DocumentChunk> {
+        let config = ChunkingConfig::tokens(80, 10);
+        let chunks = chunk_document(text, content_type, &config);
+        for chunk in &chunks {
+            assert_eq!(chunk.content, text[chunk.start_offset..chunk.end_offset]);
+            assert!(count_tokens(&chunk.content) <= 90, "{:?}", chunk);
+            assert_eq!(chunk.total_chunks, chunks.len());
+        }
+        chunks
+    }
+
+    /// Section of the chunk that `needle` starts in
+    fn section_of<'a>(chunks: &'a [DocumentChunk], text: &str, needle: &str) -> Option<&'a str> {
+        let at = text.find(needle).unwrap();
+        chunks
+            .iter()
+            .find(|c| c.start_offset <= at && at < c.end_offset)
+            .and_then(|c| c.section.as_deref())
+    }
+
+    /// Assert that the text from `from` up to the end of `to` is kept in one chunk
+    fn assert_whole(chunks: &[DocumentChunk], text: &str, from: &str, to: &str) {
+        let start = text.find(from).unwrap();
+        let end = start + text[start..].find(to).unwrap() + to.len();
+        assert!(
+            chunks
+                .iter()
+                .any(|c| c.start_offset <= start && end <= c.end_offset),
+            "{:?} was split",
+            from
+        );
+    }
+
+    #[test]
+    fn test_markdown_chunks_follow_headings() {
+        let text = include_str!("../testdata/chunking/guide.md");
+        let chunks = fixture_chunks(text, "text/markdown");
+
+        assert!(chunks.iter().all(|c| c.section.is_some()));
+        for chunk in chunks.iter().filter(|c| c.content.starts_with('#')) {
+            let heading = chunk
+                .content
+                .lines()
+                .next()
+                .unwrap()
+                .trim_start_matches('#');
+            assert!(chunk.section.as_deref().unwrap().ends_with(heading.trim()));
+        }
+        assert_whole(&chunks, text, "### From source", "```\n");
+        assert_eq!(
+            section_of(&chunks, text, "cargo install"),
+            Some("Meepo Guide > Installation > From source")
+        );
+        assert!(
+            chunks
+                .iter()
+                .all(|c| !c.section.as_deref().unwrap().contains("not a heading"))
+        );
+
+        // The long section is split, and every piece keeps its heading path
+        let configuration: Vec<_> = chunks
+            .iter()
+            .filter(|c| c.section.as_deref() == Some("Meepo Guide > Configuration"))
+            .collect();
+        assert!(configuration.len() >= 2);
+        assert!(configuration[1].content.contains("[providers]"));
+
+        // Sections under different top-level headings are never merged
+        let last = chunks.last().unwrap();
+        assert_eq!(last.section.as_deref(), Some("Appendix"));
+        assert!(last.content.starts_with("# Appendix"));
+    }
+
+    #[test]
+    fn test_rust_chunks_follow_definitions() {
+        let text = include_str!("../testdata/chunking/sample.rs");
+        let chunks = fixture_chunks(text, "text/x-rust");
+
+        assert_whole(
+            &chunks,
+            text,
+            "/// Settings read",
+            "HashMap<String, u64>,\n}",
+        );
+        assert_whole(
+            &chunks,
+            text,
+            "impl Config {",
+            "HashMap::new(),\n        }\n    }",
+        );
+        assert_eq!(
+            section_of(&chunks, text, "pub fn new("),
+            Some("impl Config > pub fn new")
+        );
+        // Too large for one chunk: split inside, but still cited by name
+        assert_eq!(
+            section_of(&chunks, text, "must be absolute"),
+            Some("impl Config > pub fn validate")
+        );
+        assert_whole(
+            &chunks,
+            text,
+            "#[cfg(test)]",
+            "assert_eq!(limits[\"b\"], 2);",
+        );
+        assert_eq!(
+            section_of(&chunks, text, "fn test_parse_limits"),
+            Some("mod tests")
+        );
+    }
+
+    #[test]
+    fn test_python_chunks_follow_definitions() {
+        let text = include_str!("../testdata/chunking/sample.py");
+        let chunks = fixture_chunks(text, "text/x-python");
+
+        assert_whole(&chunks, text, "@dataclass", "quantity: int = 0");
+        assert_whole(&chunks, text, "class Store:", "self.items = {}");
+        assert_whole(&chunks, text, "    def add(", "self.items[item.sku] = item");
+        assert_eq!(
+            section_of(&chunks, text, "# Merge quantities"),
+            Some("class Store > def add")
+        );
+        assert_eq!(
+            section_of(&chunks, text, "async def load"),
+            Some("class Store > async def load")
+        );
+        assert_eq!(section_of(&chunks, text, "def main"), Some("def main"));
+    }
+
+    #[test]
+    fn test_typescript_chunks_follow_definitions() {
+        let text = include_str!("../testdata/chunking/sample.ts");
+        let chunks = fixture_chunks(text, "text/javascript");
+
+        assert_whole(
+            &chunks,
+            text,
+            "  /** Merge quantities",
+            "this.items.set(item.sku, item);\n    }\n  }",
+        );
+        assert_eq!(
+            section_of(&chunks, text, "add(item: Item)"),
+            Some("export class Store > add")
+        );
+        assert_whole(&chunks, text, "export const formatItem", "};");
+        assert_eq!(
+            section_of(&chunks, text, "export function main"),
+            Some("export function main")
+        );
+    }
+
+    #[test]
+    fn test_go_chunks_follow_definitions() {
+        let text = include_str!("../testdata/chunking/sample.go");
+        let chunks = fixture_chunks(text, "text/x-go");
+
+        assert_whole(&chunks, text, "// NewStore", "make(map[string]*Item)}\n}");
+        assert_whole(&chunks, text, "// Get returns", "return item, nil\n}");
+        assert_eq!(
+            section_of(&chunks, text, "func (s *Store) Get"),
+            Some("func (s *Store) Get")
+        );
+        assert_eq!(
+            section_of(&chunks, text, "s.items[item.SKU] = &item"),
+            Some("func (s *Store) Add")
+        );
+    }
 }
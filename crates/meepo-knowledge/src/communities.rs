@@ -0,0 +1,883 @@
+//! GraphRAG communities — corpus-wide summaries of the knowledge graph
+//!
+//! Local GraphRAG expansion starts from search hits, which works for specific
+//! questions but not for broad ones ("what are the main themes of my work?").
+//! This module partitions the graph into communities of densely connected
+//! entities with the Louvain method and keeps an LLM-written summary of each
+//! community as an entity of type [`COMMUNITY_TYPE`].
+//!
+//! Refreshing is incremental: every community carries a fingerprint of its
+//! members and internal relationships, so a pass only re-summarizes the
+//! communities whose part of the graph actually changed. Summaries are written
+//! by a [`CommunitySummarizer`] (typically an LLM); without one a plain
+//! listing of the members is stored and replaced once a summarizer is available.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crate::sqlite::{Entity, Relationship};
+
+/// Entity type of stored community summaries
+pub const COMMUNITY_TYPE: &str = "community";
+
+/// Louvain stops after this many aggregation levels
+const MAX_LEVELS: usize = 10;
+
+/// Louvain stops moving nodes within a level after this many sweeps
+const MAX_SWEEPS: usize = 50;
+
+/// Configuration for community detection and summaries
+#[derive(Debug, Clone)]
+pub struct CommunityConfig {
+    /// Whether communities are maintained at all
+    pub enabled: bool,
+    /// Communities smaller than this are not summarized
+    pub min_size: usize,
+    /// Only the largest communities are kept
+    pub max_communities: usize,
+    /// Louvain resolution; higher values give smaller communities
+    pub resolution: f64,
+    /// Entity types left out of detection (e.g. document chunks)
+    pub exclude_types: Vec<String>,
+    /// Maximum members described to the summarizer
+    pub max_members_in_prompt: usize,
+    /// Minimum member overlap (Jaccard) for a detected community to take over
+    /// an existing summary instead of creating a new one
+    pub match_overlap: f32,
+}
+
+impl Default for CommunityConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            min_size: 3,
+            max_communities: 50,
+            resolution: 1.0,
+            exclude_types: vec!["document_chunk".to_string()],
+            max_members_in_prompt: 40,
+            match_overlap: 0.5,
+        }
+    }
+}
+
+impl CommunityConfig {
+    /// Whether entities of this type take part in community detection
+    pub fn applies_to(&self, entity_type: &str) -> bool {
+        entity_type != COMMUNITY_TYPE && !self.exclude_types.iter().any(|t| t == entity_type)
+    }
+}
+
+/// A detected community of entities
+#[derive(Debug, Clone, PartialEq)]
+pub struct Community {
+    /// Member entity IDs, sorted
+    pub members: Vec<String>,
+    /// Number of current relationships between members
+    pub internal_relationships: usize,
+    /// Fingerprint of the members and their internal relationships
+    pub fingerprint: String,
+}
+
+/// Everything a summarizer gets to see about a community
+#[derive(Debug, Clone)]
+pub struct CommunityContext {
+    /// Members, most connected first (capped by `max_members_in_prompt`)
+    pub members: Vec<Entity>,
+    /// Relationships between the listed members
+    pub relationships: Vec<Relationship>,
+    /// Total number of members, including those not listed
+    pub size: usize,
+}
+
+/// A community summary as written by a summarizer
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CommunitySummary {
+    pub title: String,
+    pub summary: String,
+}
+
+/// Writes a title and summary for a community (typically via an LLM)
+#[async_trait]
+pub trait CommunitySummarizer: Send + Sync {
+    async fn summarize(&self, community: &CommunityContext) -> Result<CommunitySummary>;
+}
+
+/// A community summary stored in the knowledge graph
+#[derive(Debug, Clone)]
+pub struct StoredCommunity {
+    pub id: String,
+    pub title: String,
+    pub summary: String,
+    pub members: Vec<String>,
+    pub member_names: Vec<String>,
+    pub fingerprint: String,
+    /// Whether the summary was written by a summarizer rather than the fallback
+    pub summarized: bool,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl StoredCommunity {
+    /// Read a community summary back from its entity
+    pub fn from_entity(entity: &Entity) -> Option<Self> {
+        if entity.entity_type != COMMUNITY_TYPE {
+            return None;
+        }
+        let metadata = entity.metadata.as_ref()?;
+        let strings = |key: &str| -> Vec<String> {
+            metadata
+                .get(key)
+                .and_then(|v| v.as_array())
+                .map(|arr| {
+                    arr.iter()
+                        .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+        Some(Self {
+            id: entity.id.clone(),
+            title: entity.name.clone(),
+            summary: metadata
+                .get("summary")
+                .and_then(|v| v.as_str())
+                .unwrap_or_default()
+                .to_string(),
+            members: strings("members"),
+            member_names: strings("member_names"),
+            fingerprint: metadata
+                .get("fingerprint")
+                .and_then(|v| v.as_str())
+                .unwrap_or_default()
+                .to_string(),
+            summarized: metadata
+                .get("summarized")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false),
+            updated_at: entity.updated_at,
+        })
+    }
+}
+
+/// Build the metadata stored on a community entity
+pub fn community_metadata(
+    community: &Community,
+    context: &CommunityContext,
+    summary: &CommunitySummary,
+    summarized: bool,
+) -> JsonValue {
+    let member_names: Vec<&str> = context.members.iter().map(|e| e.name.as_str()).collect();
+    serde_json::json!({
+        "summary": summary.summary,
+        "members": community.members,
+        "member_names": member_names,
+        "size": community.members.len(),
+        "fingerprint": community.fingerprint,
+        "summarized": summarized,
+    })
+}
+
+/// What a refresh pass did
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CommunityReport {
+    /// Communities found by detection (at or above the minimum size)
+    pub detected: usize,
+    /// New community summaries
+    pub created: usize,
+    /// Existing summaries rewritten because their community changed
+    pub updated: usize,
+    /// Summaries kept as they were
+    pub unchanged: usize,
+    /// Summaries of communities that no longer exist
+    pub removed: usize,
+    /// Communities whose summarizer call failed (stored with the fallback)
+    pub failed: usize,
+}
+
+/// What to do with one community during a refresh
+#[derive(Debug, Clone, PartialEq)]
+pub enum RefreshAction {
+    /// Store a summary for a community without one
+    Create(Community),
+    /// Rewrite an existing summary
+    Update { id: String, community: Community },
+    /// The stored summary is still accurate
+    Keep { id: String },
+    /// Delete the summary of a community that no longer exists
+    Remove { id: String },
+}
+
+/// Partition the graph into communities with the Louvain method.
+///
+/// Only current relationships between entities that take part in detection
+/// are used; entities without any such relationship belong to no community.
+/// Communities are returned largest first, filtered by `min_size` and capped
+/// by `max_communities`.
+pub fn detect_communities(
+    entities: &[Entity],
+    relationships: &[Relationship],
+    config: &CommunityConfig,
+) -> Vec<Community> {
+    let included: HashMap<&str, &Entity> = entities
+        .iter()
+        .filter(|e| config.applies_to(&e.entity_type))
+        .map(|e| (e.id.as_str(), e))
+        .collect();
+    let current: Vec<&Relationship> = relationships
+        .iter()
+        .filter(|r| r.valid_to.is_none() && r.source_id != r.target_id)
+        .filter(|r| {
+            included.contains_key(r.source_id.as_str())
+                && included.contains_key(r.target_id.as_str())
+        })
+        .collect();
+
+    // Sorted node IDs keep the partition deterministic
+    let mut ids: Vec<&str> = current
+        .iter()
+        .flat_map(|r| [r.source_id.as_str(), r.target_id.as_str()])
+        .collect();
+    ids.sort_unstable();
+    ids.dedup();
+    let index: HashMap<&str, usize> = ids.iter().enumerate().map(|(i, id)| (*id, i)).collect();
+
+    let mut weights: BTreeMap<(usize, usize), f64> = BTreeMap::new();
+    for r in &current {
+        let (a, b) = (index[r.source_id.as_str()], index[r.target_id.as_str()]);
+        *weights.entry((a.min(b), a.max(b))).or_default() += 1.0;
+    }
+    let edges: Vec<(usize, usize, f64)> =
+        weights.into_iter().map(|((a, b), w)| (a, b, w)).collect();
+    let labels = louvain(ids.len(), &edges, config.resolution);
+
+    let mut groups: BTreeMap<usize, Vec<String>> = BTreeMap::new();
+    for (node, label) in labels.iter().enumerate() {
+        groups
+            .entry(*label)
+            .or_default()
+            .push(ids[node].to_string());
+    }
+
+    let mut communities: Vec<Community> = groups
+        .into_values()
+        .filter(|members| members.len() >= config.min_size.max(2))
+        .map(|mut members| {
+            members.sort();
+            let set: HashSet<&str> = members.iter().map(|s| s.as_str()).collect();
+            let internal: Vec<&Relationship> = current
+                .iter()
+                .copied()
+                .filter(|r| {
+                    set.contains(r.source_id.as_str()) && set.contains(r.target_id.as_str())
+                })
+                .collect();
+            let member_entities: Vec<&Entity> =
+                members.iter().map(|id| included[id.as_str()]).collect();
+            Community {
+                fingerprint: fingerprint(&member_entities, &internal),
+                internal_relationships: internal.len(),
+                members,
+            }
+        })
+        .collect();
+
+    communities.sort_by(|a, b| {
+        b.members
+            .len()
+            .cmp(&a.members.len())
+            .then_with(|| a.members.cmp(&b.members))
+    });
+    communities.truncate(config.max_communities);
+    communities
+}
+
+/// Louvain community detection on an undirected weighted graph.
+///
+/// `edges` are `(a, b, weight)` with `a != b`, each pair listed once.
+/// Returns a community label for every node.
+pub fn louvain(node_count: usize, edges: &[(usize, usize, f64)], resolution: f64) -> Vec<usize> {
+    let mut membership: Vec<usize> = (0..node_count).collect();
+    let mut level = LevelGraph::new(node_count, edges.to_vec());
+
+    for _ in 0..MAX_LEVELS {
+        let (labels, moved) = level.local_moves(resolution);
+        if !moved {
+            break;
+        }
+        let (renumbered, count) = renumber(&labels);
+        for label in membership.iter_mut() {
+            *label = renumbered[*label];
+        }
+        level = level.aggregate(&renumbered, count);
+    }
+    membership
+}
+
+/// One level of the Louvain hierarchy. Self-loops (`a == b` edges) carry the
+/// weight of edges that were merged inside a node by aggregation; they count
+/// towards the node's degree but never pull it into another community.
+struct LevelGraph {
+    adjacency: Vec<Vec<(usize, f64)>>,
+    degree: Vec<f64>,
+    total_degree: f64,
+    edges: Vec<(usize, usize, f64)>,
+}
+
+impl LevelGraph {
+    fn new(node_count: usize, edges: Vec<(usize, usize, f64)>) -> Self {
+        let mut adjacency = vec![Vec::new(); node_count];
+        let mut degree = vec![0.0; node_count];
+        for &(a, b, w) in &edges {
+            if a == b {
+                degree[a] += 2.0 * w;
+            } else {
+                adjacency[a].push((b, w));
+                adjacency[b].push((a, w));
+                degree[a] += w;
+                degree[b] += w;
+            }
+        }
+        let total_degree = degree.iter().sum();
+        Self {
+            adjacency,
+            degree,
+            total_degree,
+            edges,
+        }
+    }
+
+    /// Move nodes between communities while modularity improves.
+    /// Returns each node's community and whether any node moved.
+    fn local_moves(&self, resolution: f64) -> (Vec<usize>, bool) {
+        let n = self.adjacency.len();
+        let mut community: Vec<usize> = (0..n).collect();
+        let mut totals = self.degree.clone();
+        let mut moved_any = false;
+        if self.total_degree == 0.0 {
+            return (community, false);
+        }
+
+        for _ in 0..MAX_SWEEPS {
+            let mut moved = false;
+            for node in 0..n {
+                let k = self.degree[node];
+                let current = community[node];
+
+                let mut links: BTreeMap<usize, f64> = BTreeMap::new();
+                for &(neighbor, w) in &self.adjacency[node] {
+                    *links.entry(community[neighbor]).or_default() += w;
+                }
+
+                totals[current] -= k;
+                let gain = |c: usize, w: f64| w - resolution * totals[c] * k / self.total_degree;
+                let mut best = current;
+                let mut best_gain = gain(current, links.get(&current).copied().unwrap_or(0.0));
+                for (&c, &w) in &links {
+                    let g = gain(c, w);
+                    if g > best_gain + 1e-12 {
+                        best = c;
+                        best_gain = g;
+                    }
+                }
+                totals[best] += k;
+
+                if best != current {
+                    community[node] = best;
+                    moved = true;
+                    moved_any = true;
+                }
+            }
+            if !moved {
+                break;
+            }
+        }
+        (community, moved_any)
+    }
+
+    /// Collapse each community into a single node; edges inside a community
+    /// (and existing self-loops) become the new node's self-loop
+    fn aggregate(&self, labels: &[usize], count: usize) -> Self {
+        let mut weights: BTreeMap<(usize, usize), f64> = BTreeMap::new();
+        for &(a, b, w) in &self.edges {
+            let (ca, cb) = (labels[a], labels[b]);
+            *weights.entry((ca.min(cb), ca.max(cb))).or_default() += w;
+        }
+        LevelGraph::new(
+            count,
+            weights.into_iter().map(|((a, b), w)| (a, b, w)).collect(),
+        )
+    }
+}
+
+/// Map arbitrary labels onto 0..count in order of first appearance
+fn renumber(labels: &[usize]) -> (Vec<usize>, usize) {
+    let mut mapping: HashMap<usize, usize> = HashMap::new();
+    let renumbered = labels
+        .iter()
+        .map(|label| {
+            let next = mapping.len();
+            *mapping.entry(*label).or_insert(next)
+        })
+        .collect();
+    (renumbered, mapping.len())
+}
+
+/// Stable fingerprint of a community's members and internal relationships
+fn fingerprint(members: &[&Entity], relationships: &[&Relationship]) -> String {
+    let mut parts: Vec<String> = members
+        .iter()
+        .map(|e| format!("e:{}:{}", e.id, e.updated_at.to_rfc3339()))
+        .collect();
+    parts.extend(
+        relationships
+            .iter()
+            .map(|r| format!("r:{}:{}", r.id, r.relation_type)),
+    );
+    parts.sort();
+    format!("{:016x}", fnv1a(parts.join("\n").as_bytes()))
+}
+
+/// 64-bit FNV-1a; stable across builds, unlike `DefaultHasher`
+fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in bytes {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    hash
+}
+
+/// Decide which stored summaries to keep, rewrite, create or remove.
+///
+/// Each detected community takes over the stored summary with the largest
+/// member overlap (at least `match_overlap`), largest communities first. A
+/// matched summary is kept when the fingerprint is unchanged, unless it was
+/// written by the fallback and a summarizer is now available.
+pub fn plan_refresh(
+    detected: &[Community],
+    existing: &[StoredCommunity],
+    config: &CommunityConfig,
+    have_summarizer: bool,
+) -> Vec<RefreshAction> {
+    let mut claimed: HashSet<&str> = HashSet::new();
+    let mut actions = Vec::new();
+
+    for community in detected {
+        let members: HashSet<&str> = community.members.iter().map(|s| s.as_str()).collect();
+        let best = existing
+            .iter()
+            .filter(|s| !claimed.contains(s.id.as_str()))
+            .map(|s| (s, jaccard(&members, &s.members)))
+            .filter(|(_, overlap)| *overlap >= config.match_overlap)
+            .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
+
+        match best {
+            Some((stored, _)) => {
+                claimed.insert(stored.id.as_str());
+                if stored.fingerprint == community.fingerprint
+                    && (stored.summarized || !have_summarizer)
+                {
+                    actions.push(RefreshAction::Keep {
+                        id: stored.id.clone(),
+                    });
+                } else {
+                    actions.push(RefreshAction::Update {
+                        id: stored.id.clone(),
+                        community: community.clone(),
+                    });
+                }
+            }
+            None => actions.push(RefreshAction::Create(community.clone())),
+        }
+    }
+
+    for stored in existing {
+        if !claimed.contains(stored.id.as_str()) {
+            actions.push(RefreshAction::Remove {
+                id: stored.id.clone(),
+            });
+        }
+    }
+    actions
+}
+
+fn jaccard(a: &HashSet<&str>, b: &[String]) -> f32 {
+    let b: HashSet<&str> = b.iter().map(|s| s.as_str()).collect();
+    let union = a.union(&b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    a.intersection(&b).count() as f32 / union as f32
+}
+
+/// Gather what the summarizer sees: members ordered by how connected they are
+/// inside the community, and the relationships among the listed members
+pub fn community_context(
+    community: &Community,
+    entities: &HashMap<String, Entity>,
+    relationships: &[Relationship],
+    config: &CommunityConfig,
+) -> CommunityContext {
+    let set: HashSet<&str> = community.members.iter().map(|s| s.as_str()).collect();
+    let internal: Vec<&Relationship> = relationships
+        .iter()
+        .filter(|r| r.valid_to.is_none())
+        .filter(|r| set.contains(r.source_id.as_str()) && set.contains(r.target_id.as_str()))
+        .collect();
+
+    let mut degree: HashMap<&str, usize> = HashMap::new();
+    for r in &internal {
+        *degree.entry(r.source_id.as_str()).or_default() += 1;
+        *degree.entry(r.target_id.as_str()).or_default() += 1;
+    }
+    let mut members: Vec<Entity> = community
+        .members
+        .iter()
+        .filter_map(|id| entities.get(id).cloned())
+        .collect();
+    members.sort_by(|a, b| {
+        let (da, db) = (
+            degree.get(a.id.as_str()).copied().unwrap_or(0),
+            degree.get(b.id.as_str()).copied().unwrap_or(0),
+        );
+        db.cmp(&da).then_with(|| a.name.cmp(&b.name))
+    });
+    members.truncate(config.max_members_in_prompt);
+
+    let listed: HashSet<&str> = members.iter().map(|e| e.id.as_str()).collect();
+    let relationships = internal
+        .into_iter()
+        .filter(|r| listed.contains(r.source_id.as_str()) && listed.contains(r.target_id.as_str()))
+        .cloned()
+        .collect();
+
+    CommunityContext {
+        members,
+        relationships,
+        size: community.members.len(),
+    }
+}
+
+/// Summary used when no summarizer is configured or it fails
+pub fn fallback_summary(context: &CommunityContext) -> CommunitySummary {
+    let names: Vec<&str> = context.members.iter().map(|e| e.name.as_str()).collect();
+    let title = match names.as_slice() {
+        [] => "Community".to_string(),
+        [one] => format!("{} and related", one),
+        [first, second, ..] => format!("{}, {} and related", first, second),
+    };
+
+    let mut types: BTreeMap<&str, usize> = BTreeMap::new();
+    for e in &context.members {
+        *types.entry(e.entity_type.as_str()).or_default() += 1;
+    }
+    let mut relations: BTreeMap<&str, usize> = BTreeMap::new();
+    for r in &context.relationships {
+        *relations.entry(r.relation_type.as_str()).or_default() += 1;
+    }
+    let counts = |map: &BTreeMap<&str, usize>| {
+        map.iter()
+            .map(|(k, v)| format!("{} {}", v, k))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    let mut summary = format!(
+        "{} connected entities ({}): {}.",
+        context.size,
+        counts(&types),
+        names.join(", ")
+    );
+    if !relations.is_empty() {
+        summary.push_str(&format!(" Relationships: {}.", counts(&relations)));
+    }
+    CommunitySummary { title, summary }
+}
+
+/// Rank stored communities for a corpus-wide question.
+///
+/// `relevance` holds full-text scores of community entities that matched the
+/// query. Matching communities come first; the rest follow by size, so broad
+/// questions that match nothing still see the biggest themes.
+pub fn rank_communities(
+    communities: Vec<StoredCommunity>,
+    relevance: &HashMap<String, f32>,
+    limit: usize,
+) -> Vec<(StoredCommunity, f32)> {
+    let max_relevance = relevance.values().copied().fold(0.0f32, f32::max);
+    let max_size = communities
+        .iter()
+        .map(|c| c.members.len())
+        .max()
+        .unwrap_or(0)
+        .max(1) as f32;
+
+    let mut ranked: Vec<(StoredCommunity, f32)> = communities
+        .into_iter()
+        .map(|c| {
+            let size = c.members.len() as f32 / max_size;
+            let score = match relevance.get(&c.id) {
+                Some(r) if max_relevance > 0.0 => 1.0 + 0.7 * (r / max_relevance) + 0.3 * size,
+                _ => 0.3 * size,
+            };
+            (c, score)
+        })
+        .collect();
+    ranked.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+    ranked.truncate(limit);
+    ranked
+}
+
+/// Format ranked community summaries as context for the model
+pub fn format_global_context(ranked: &[(StoredCommunity, f32)], max_member_names: usize) -> String {
+    let mut out = String::new();
+    for (i, (community, _)) in ranked.iter().enumerate() {
+        out.push_str(&format!(
+            "{}. {} ({} members) [{}]\n",
+            i + 1,
+            community.title,
+            community.members.len(),
+            community.id
+        ));
+        if !community.summary.is_empty() {
+            out.push_str(&format!("   {}\n", community.summary));
+        }
+        if !community.member_names.is_empty() {
+            let shown: Vec<&str> = community
+                .member_names
+                .iter()
+                .take(max_member_names)
+                .map(|s| s.as_str())
+                .collect();
+            let more = community.members.len().saturating_sub(shown.len());
+            out.push_str(&format!("   Key members: {}", shown.join(", ")));
+            if more > 0 {
+                out.push_str(&format!(" (+{} more)", more));
+            }
+            out.push('\n');
+        }
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entity(id: &str, entity_type: &str) -> Entity {
+        Entity {
+            id: id.to_string(),
+            name: id.to_uppercase(),
+            entity_type: entity_type.to_string(),
+            metadata: None,
+            created_at: Utc::now(),
+            updated_at: DateTime::parse_from_rfc3339("2026-01-01T00:00:00Z")
+                .unwrap()
+                .with_timezone(&Utc),
+        }
+    }
+
+    fn rel(id: &str, source: &str, target: &str) -> Relationship {
+        Relationship {
+            id: id.to_string(),
+            source_id: source.to_string(),
+            target_id: target.to_string(),
+            relation_type: "related_to".to_string(),
+            metadata: None,
+            created_at: Utc::now(),
+            valid_from: None,
+            valid_to: None,
+            superseded_by: None,
+        }
+    }
+
+    /// Two triangles joined by a single bridge edge (c — d)
+    fn two_clusters() -> (Vec<Entity>, Vec<Relationship>) {
+        let entities = ["a", "b", "c", "d", "e", "f"]
+            .iter()
+            .map(|id| entity(id, "concept"))
+            .collect();
+        let relationships = vec![
+            rel("r1", "a", "b"),
+            rel("r2", "b", "c"),
+            rel("r3", "a", "c"),
+            rel("r4", "d", "e"),
+            rel("r5", "e", "f"),
+            rel("r6", "d", "f"),
+            rel("r7", "c", "d"),
+        ];
+        (entities, relationships)
+    }
+
+    #[test]
+    fn test_louvain_separates_clusters() {
+        let edges = vec![
+            (0, 1, 1.0),
+            (1, 2, 1.0),
+            (0, 2, 1.0),
+            (3, 4, 1.0),
+            (4, 5, 1.0),
+            (3, 5, 1.0),
+            (2, 3, 1.0),
+        ];
+        let labels = louvain(6, &edges, 1.0);
+        assert_eq!(labels[0], labels[1]);
+        assert_eq!(labels[1], labels[2]);
+        assert_eq!(labels[3], labels[4]);
+        assert_eq!(labels[4], labels[5]);
+        assert_ne!(labels[0], labels[3]);
+
+        assert_eq!(louvain(3, &[], 1.0), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_detect_communities_filters_and_fingerprints() {
+        let (mut entities, mut relationships) = two_clusters();
+        // A pair is below the minimum size; chunks and ended facts are ignored
+        entities.push(entity("x", "concept"));
+        entities.push(entity("y", "concept"));
+        entities.push(entity("chunk", "document_chunk"));
+        relationships.push(rel("r8", "x", "y"));
+        relationships.push(rel("r9", "a", "chunk"));
+        let mut ended = rel("r10", "a", "f");
+        ended.valid_to = Some(Utc::now());
+        relationships.push(ended);
+
+        let config = CommunityConfig::default();
+        let communities = detect_communities(&entities, &relationships, &config);
+        assert_eq!(communities.len(), 2);
+        assert_eq!(communities[0].members, vec!["a", "b", "c"]);
+        assert_eq!(communities[1].members, vec!["d", "e", "f"]);
+        assert_eq!(communities[0].internal_relationships, 3);
+
+        // Same graph, same fingerprints; a changed member changes only its own
+        let again = detect_communities(&entities, &relationships, &config);
+        assert_eq!(again, communities);
+        entities[0].updated_at = Utc::now();
+        let changed = detect_communities(&entities, &relationships, &config);
+        assert_ne!(changed[0].fingerprint, communities[0].fingerprint);
+        assert_eq!(changed[1].fingerprint, communities[1].fingerprint);
+    }
+
+    fn stored(id: &str, community: &Community, summarized: bool) -> StoredCommunity {
+        StoredCommunity {
+            id: id.to_string(),
+            title: id.to_string(),
+            summary: String::new(),
+            members: community.members.clone(),
+            member_names: Vec::new(),
+            fingerprint: community.fingerprint.clone(),
+            summarized,
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_plan_refresh_is_incremental() {
+        let (entities, relationships) = two_clusters();
+        let config = CommunityConfig::default();
+        let detected = detect_communities(&entities, &relationships, &config);
+
+        let mut changed = detected[1].clone();
+        changed.fingerprint = "stale".to_string();
+        let gone = Community {
+            members: vec!["p".into(), "q".into(), "r".into()],
+            internal_relationships: 2,
+            fingerprint: "x".into(),
+        };
+        let existing = vec![
+            stored("s1", &detected[0], true),
+            stored("s2", &changed, true),
+            stored("s3", &gone, true),
+        ];
+
+        let actions = plan_refresh(&detected, &existing, &config, true);
+        assert_eq!(
+            actions,
+            vec![
+                RefreshAction::Keep { id: "s1".into() },
+                RefreshAction::Update {
+                    id: "s2".into(),
+                    community: detected[1].clone()
+                },
+                RefreshAction::Remove { id: "s3".into() },
+            ]
+        );
+
+        // Nothing stored yet: everything is created
+        let fresh = plan_refresh(&detected, &[], &config, false);
+        assert!(fresh.iter().all(|a| matches!(a, RefreshAction::Create(_))));
+
+        // Fallback summaries are upgraded once a summarizer is available
+        let fallback = vec![stored("s1", &detected[0], false)];
+        let actions = plan_refresh(&detected[..1], &fallback, &config, true);
+        assert!(matches!(actions[0], RefreshAction::Update { .. }));
+        let actions = plan_refresh(&detected[..1], &fallback, &config, false);
+        assert!(matches!(actions[0], RefreshAction::Keep { .. }));
+    }
+
+    #[test]
+    fn test_context_fallback_and_stored_roundtrip() {
+        let (entities, relationships) = two_clusters();
+        let config = CommunityConfig::default();
+        let detected = detect_communities(&entities, &relationships, &config);
+        let by_id: HashMap<String, Entity> =
+            entities.into_iter().map(|e| (e.id.clone(), e)).collect();
+
+        let context = community_context(&detected[0], &by_id, &relationships, &config);
+        assert_eq!(context.size, 3);
+        assert_eq!(context.relationships.len(), 3);
+
+        let summary = fallback_summary(&context);
+        assert!(summary.title.ends_with("and related"));
+        assert!(summary.summary.contains("3 connected entities (3 concept)"));
+        assert!(summary.summary.contains("3 related_to"));
+
+        let mut community_entity = entity("c1", COMMUNITY_TYPE);
+        community_entity.name = summary.title.clone();
+        community_entity.metadata =
+            Some(community_metadata(&detected[0], &context, &summary, false));
+        let stored = StoredCommunity::from_entity(&community_entity).unwrap();
+        assert_eq!(stored.members, detected[0].members);
+        assert_eq!(stored.fingerprint, detected[0].fingerprint);
+        assert_eq!(stored.summary, summary.summary);
+        assert!(!stored.summarized);
+        assert!(StoredCommunity::from_entity(&by_id["a"]).is_none());
+    }
+
+    #[test]
+    fn test_rank_communities_prefers_matches_then_size() {
+        let community = |id: &str, size: usize| StoredCommunity {
+            id: id.to_string(),
+            title: id.to_string(),
+            summary: format!("{} summary", id),
+            members: (0..size).map(|i| format!("{}{}", id, i)).collect(),
+            member_names: vec!["Alice".to_string()],
+            fingerprint: String::new(),
+            summarized: true,
+            updated_at: Utc::now(),
+        };
+        let all = vec![
+            community("small", 3),
+            community("big", 10),
+            community("mid", 5),
+        ];
+
+        let relevance = HashMap::from([("small".to_string(), 2.0)]);
+        let ranked = rank_communities(all.clone(), &relevance, 2);
+        let ids: Vec<&str> = ranked.iter().map(|(c, _)| c.id.as_str()).collect();
+        assert_eq!(ids, vec!["small", "big"]);
+
+        let ranked = rank_communities(all, &HashMap::new(), 3);
+        let ids: Vec<&str> = ranked.iter().map(|(c, _)| c.id.as_str()).collect();
+        assert_eq!(ids, vec!["big", "mid", "small"]);
+
+        let text = format_global_context(&ranked[..1], 5);
+        assert!(text.starts_with("1. big (10 members) [big]"));
+        assert!(text.contains("Key members: Alice (+9 more)"));
+    }
+}
@@ -0,0 +1,279 @@
+//! Managed document corpora
+//!
+//! A corpus is a directory registered with glob include and exclude
+//! patterns. Syncing walks the directory and hashes every matching file:
+//! unchanged files are skipped, changed files have their document and chunks
+//! replaced, and files that disappeared are removed from the graph. The
+//! daemon keeps registered corpora in sync with a file watcher.
+//!
+//! Patterns are matched against paths relative to the corpus root with `/`
+//! separators. A pattern without a `/` matches the file name at any depth,
+//! so `*.pdf` and `**/*.pdf` are equivalent. Hidden files and directories
+//! are always skipped.
+
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, Utc};
+use glob::{MatchOptions, Pattern};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+/// Include pattern used when none is given
+pub const DEFAULT_INCLUDE: &str = "**/*";
+
+/// Files larger than this are skipped during a sync
+pub const MAX_FILE_BYTES: u64 = 50 * 1024 * 1024;
+
+const MATCH_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+/// A registered directory of documents
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Corpus {
+    pub id: String,
+    pub name: String,
+    /// Canonical path of the directory
+    pub root: PathBuf,
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_synced_at: Option<DateTime<Utc>>,
+}
+
+impl Corpus {
+    /// Describe a new corpus, validating the name, root and patterns
+    pub fn new(
+        name: &str,
+        root: impl AsRef<Path>,
+        include: Vec<String>,
+        exclude: Vec<String>,
+    ) -> Result<Self> {
+        let name = name.trim();
+        if name.is_empty() {
+            bail!("Corpus name must not be empty");
+        }
+        let root = root.as_ref();
+        let root = root
+            .canonicalize()
+            .with_context(|| format!("Corpus directory not found: {}", root.display()))?;
+        if !root.is_dir() {
+            bail!("Corpus root is not a directory: {}", root.display());
+        }
+        let include = if include.is_empty() {
+            vec![DEFAULT_INCLUDE.to_string()]
+        } else {
+            include
+        };
+        for pattern in include.iter().chain(&exclude) {
+            compile_pattern(pattern)?;
+        }
+
+        Ok(Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            name: name.to_string(),
+            root,
+            include,
+            exclude,
+            created_at: Utc::now(),
+            last_synced_at: None,
+        })
+    }
+
+    /// Whether `path` (absolute, or relative to the root) belongs to the corpus
+    pub fn matches(&self, path: &Path) -> bool {
+        let relative = path.strip_prefix(&self.root).unwrap_or(path);
+        if relative.is_absolute() {
+            return false;
+        }
+        if relative
+            .components()
+            .any(|c| c.as_os_str().to_string_lossy().starts_with('.'))
+        {
+            return false;
+        }
+        let relative = relative.to_string_lossy().replace('\\', "/");
+        let any = |patterns: &[String]| {
+            patterns.iter().any(|p| {
+                compile_pattern(p)
+                    .map(|p| p.matches_with(&relative, MATCH_OPTIONS))
+                    .unwrap_or(false)
+            })
+        };
+        any(&self.include) && !any(&self.exclude)
+    }
+
+    /// Walk the root and list every file that belongs to the corpus
+    pub fn scan(&self) -> Result<Vec<PathBuf>> {
+        if !self.root.is_dir() {
+            bail!("Corpus directory is missing: {}", self.root.display());
+        }
+        let mut files = Vec::new();
+        let walker = walkdir::WalkDir::new(&self.root)
+            .follow_links(false)
+            .into_iter()
+            .filter_entry(|e| e.depth() == 0 || !e.file_name().to_string_lossy().starts_with('.'));
+        for entry in walker {
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(e) => {
+                    warn!("Skipping unreadable path in corpus {}: {}", self.name, e);
+                    continue;
+                }
+            };
+            if entry.file_type().is_file() && self.matches(entry.path()) {
+                files.push(entry.into_path());
+            }
+        }
+        files.sort();
+        Ok(files)
+    }
+}
+
+/// Compile a pattern, letting patterns without `/` match at any depth
+fn compile_pattern(pattern: &str) -> Result<Pattern> {
+    let pattern = pattern.trim().trim_start_matches("./");
+    let full = if pattern.contains('/') {
+        pattern.to_string()
+    } else {
+        format!("**/{}", pattern)
+    };
+    Pattern::new(&full).with_context(|| format!("Invalid glob pattern '{}'", pattern))
+}
+
+/// A file of a corpus as of its last ingestion
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CorpusFile {
+    pub corpus_id: String,
+    /// Absolute path of the file
+    pub path: String,
+    /// SHA-256 of the file contents, hex encoded
+    pub content_hash: String,
+    /// The `document` entity the file was ingested as
+    pub document_id: String,
+    pub chunk_count: usize,
+    pub ingested_at: DateTime<Utc>,
+}
+
+/// Hex SHA-256 of file contents
+pub fn content_hash(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// What a corpus sync changed
+#[derive(Debug, Clone, Default)]
+pub struct CorpusSyncReport {
+    /// Files ingested for the first time
+    pub added: Vec<String>,
+    /// Files whose content changed and were re-ingested
+    pub updated: Vec<String>,
+    /// Files that disappeared and were removed from the graph
+    pub removed: Vec<String>,
+    /// Files skipped because their content hash was unchanged
+    pub unchanged: usize,
+    /// Files that could not be ingested, with the error
+    pub failed: Vec<(String, String)>,
+}
+
+impl CorpusSyncReport {
+    /// Whether the sync changed the knowledge graph
+    pub fn changed(&self) -> bool {
+        !self.added.is_empty() || !self.updated.is_empty() || !self.removed.is_empty()
+    }
+
+    /// Fold another report into this one
+    pub fn merge(&mut self, other: CorpusSyncReport) {
+        self.added.extend(other.added);
+        self.updated.extend(other.updated);
+        self.removed.extend(other.removed);
+        self.unchanged += other.unchanged;
+        self.failed.extend(other.failed);
+    }
+}
+
+impl std::fmt::Display for CorpusSyncReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} added, {} updated, {} removed, {} unchanged",
+            self.added.len(),
+            self.updated.len(),
+            self.removed.len(),
+            self.unchanged
+        )?;
+        if !self.failed.is_empty() {
+            write!(f, ", {} failed", self.failed.len())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_corpus_patterns_and_scan() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let root = temp.path();
+        for file in [
+            "notes.md",
+            "papers/a.pdf",
+            "papers/drafts/b.pdf",
+            "papers/drafts/b.tmp",
+            ".git/config",
+            "archive/old.md",
+        ] {
+            let path = root.join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, file).unwrap();
+        }
+
+        let corpus = Corpus::new(
+            "research",
+            root,
+            vec!["*.md".to_string(), "papers/**/*.pdf".to_string()],
+            vec!["archive/**".to_string()],
+        )
+        .unwrap();
+        let names: Vec<String> = corpus
+            .scan()
+            .unwrap()
+            .iter()
+            .map(|p| {
+                p.strip_prefix(&corpus.root)
+                    .unwrap()
+                    .to_string_lossy()
+                    .replace('\\', "/")
+            })
+            .collect();
+        assert_eq!(names, ["notes.md", "papers/a.pdf", "papers/drafts/b.pdf"]);
+
+        assert!(corpus.matches(Path::new("papers/x.pdf")));
+        assert!(!corpus.matches(Path::new("papers/x.tmp")));
+        assert!(!corpus.matches(Path::new("/elsewhere/notes.md")));
+
+        let everything = Corpus::new("all", root, vec![], vec![]).unwrap();
+        assert_eq!(everything.include, [DEFAULT_INCLUDE]);
+        assert_eq!(everything.scan().unwrap().len(), 5);
+
+        assert!(Corpus::new("bad", root, vec!["[".to_string()], vec![]).is_err());
+        assert!(Corpus::new("missing", root.join("nope"), vec![], vec![]).is_err());
+        assert!(Corpus::new(" ", root, vec![], vec![]).is_err());
+    }
+
+    #[test]
+    fn test_content_hash() {
+        assert_eq!(
+            content_hash(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_ne!(content_hash(b"abc"), content_hash(b"abd"));
+    }
+}
@@ -10,6 +10,9 @@ use std::path::Path;
 use std::sync::{Arc, Mutex};
 use tracing::{debug, info};
 
+use crate::outbox::PendingChange;
+use crate::sqlite::Entity;
+
 /// Configuration for the embedding system
 #[derive(Debug, Clone)]
 pub struct EmbeddingConfig {
@@ -84,10 +87,20 @@ impl VectorIndex {
 
     /// Load embeddings from SQLite blob storage
     pub fn load_from_db(db_path: &Path, dimensions: usize) -> Result<Self> {
-        let index = Self::new(dimensions);
-
         let conn = rusqlite::Connection::open(db_path)
             .context("Failed to open database for vector index")?;
+        Self::load_from_conn(&conn, dimensions)
+    }
+
+    /// Load embeddings from an encrypted knowledge database
+    pub fn load_from_encrypted_db(db_path: &Path, key: &str, dimensions: usize) -> Result<Self> {
+        let conn = crate::encryption::open_connection(db_path, Some(key))
+            .context("Failed to open database for vector index")?;
+        Self::load_from_conn(&conn, dimensions)
+    }
+
+    fn load_from_conn(conn: &rusqlite::Connection, dimensions: usize) -> Result<Self> {
+        let index = Self::new(dimensions);
 
         // Create table if it doesn't exist
         conn.execute(
@@ -175,11 +188,73 @@ impl VectorIndex {
         results
     }
 
+    /// Remove all stored embeddings
+    pub fn clear(&self) {
+        self.embeddings.lock().unwrap().clear();
+    }
+
+    /// Replace the index contents with fresh embeddings for the given entities.
+    ///
+    /// Used after bulk changes such as a knowledge import, where stored vectors
+    /// may refer to entities that were remapped or no longer exist.
+    pub fn rebuild_from_entities(
+        &self,
+        entities: &[Entity],
+        provider: &dyn EmbeddingProvider,
+    ) -> Result<usize> {
+        let texts: Vec<String> = entities.iter().map(entity_text).collect();
+        let refs: Vec<&str> = texts.iter().map(|t| t.as_str()).collect();
+        let vectors = provider.embed_batch(&refs)?;
+
+        self.clear();
+        for (entity, vector) in entities.iter().zip(vectors) {
+            self.insert(&entity.id, vector)?;
+        }
+
+        info!("Rebuilt vector index with {} embeddings", entities.len());
+        Ok(entities.len())
+    }
+
+    /// Apply outbox changes: changed entities are re-embedded and deleted
+    /// ones removed
+    pub fn apply_changes(
+        &self,
+        changes: &[PendingChange],
+        provider: &dyn EmbeddingProvider,
+    ) -> Result<()> {
+        let upserts: Vec<&Entity> = changes.iter().filter_map(|c| c.entity.as_ref()).collect();
+        let texts: Vec<String> = upserts.iter().map(|e| entity_text(e)).collect();
+        let refs: Vec<&str> = texts.iter().map(|t| t.as_str()).collect();
+        let vectors = if refs.is_empty() {
+            Vec::new()
+        } else {
+            provider.embed_batch(&refs)?
+        };
+
+        for change in changes.iter().filter(|c| c.entity.is_none()) {
+            self.remove(&change.entity_id);
+        }
+        for (entity, vector) in upserts.into_iter().zip(vectors) {
+            self.insert(&entity.id, vector)?;
+        }
+        Ok(())
+    }
+
     /// Persist all embeddings to SQLite
     pub fn persist_to_db(&self, db_path: &Path) -> Result<()> {
         let conn = rusqlite::Connection::open(db_path)
             .context("Failed to open database for persistence")?;
+        self.persist_to_conn(&conn)
+    }
 
+    /// Persist all embeddings to an encrypted knowledge database
+    pub fn persist_to_encrypted_db(&self, db_path: &Path, key: &str) -> Result<()> {
+        let conn = crate::encryption::open_connection(db_path, Some(key))
+            .context("Failed to open database for persistence")?;
+        self.persist_to_conn(&conn)
+    }
+
+    fn persist_to_conn(&self, conn: &rusqlite::Connection) -> Result<()> {
         conn.execute(
             "CREATE TABLE IF NOT EXISTS embeddings (
                 entity_id TEXT PRIMARY KEY,
@@ -192,6 +267,8 @@ impl VectorIndex {
         let embeddings = self.embeddings.lock().unwrap();
 
         let tx = conn.unchecked_transaction()?;
+        // Mirror the index, so removed entities do not come back on load
+        tx.execute("DELETE FROM embeddings", [])?;
         for (entity_id, vector) in embeddings.iter() {
             let blob = f32_vec_to_bytes(vector);
             tx.execute(
@@ -216,6 +293,20 @@ impl VectorIndex {
     }
 }
 
+/// Text embedded for an entity
+fn entity_text(entity: &Entity) -> String {
+    format!(
+        "{} {} {}",
+        entity.name,
+        entity.entity_type,
+        entity
+            .metadata
+            .as_ref()
+            .map(|m| m.to_string())
+            .unwrap_or_default()
+    )
+}
+
 /// Compute cosine similarity between two vectors
 fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
     if a.len() != b.len() || a.is_empty() {
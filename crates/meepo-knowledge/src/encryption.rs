@@ -0,0 +1,240 @@
+//! Encryption at rest for the knowledge database
+//!
+//! When built with the `sqlcipher` feature, SQLite is replaced by SQLCipher
+//! and the knowledge database can be opened with a key. Plaintext databases
+//! are converted in place with [`encrypt_database`] (and back with
+//! [`decrypt_database`]); [`rekey_database`] rotates the key.
+//!
+//! Embeddings live in the same file and are encrypted with it. The Tantivy
+//! index holds copies of entity text, so an encrypted knowledge graph keeps
+//! its search index in memory and rebuilds it from the database on open
+//! instead of writing it to disk.
+
+use anyhow::{Context, Result, bail};
+use rusqlite::Connection;
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+/// First bytes of every plaintext SQLite database file
+const SQLITE_HEADER: &[u8; 16] = b"SQLite format 3\0";
+
+/// Whether this build links SQLCipher (the `sqlcipher` feature)
+pub fn sqlcipher_available() -> bool {
+    Connection::open_in_memory()
+        .and_then(|conn| conn.query_row("PRAGMA cipher_version", [], |row| row.get::<_, String>(0)))
+        .is_ok()
+}
+
+fn require_sqlcipher() -> Result<()> {
+    if !sqlcipher_available() {
+        bail!(
+            "Knowledge database encryption requires SQLCipher; rebuild meepo with `--features sqlcipher`"
+        );
+    }
+    Ok(())
+}
+
+/// Whether the file at `path` is an encrypted database.
+/// Missing or empty files are not encrypted.
+pub fn is_encrypted(path: &Path) -> Result<bool> {
+    use std::io::Read;
+
+    let mut file = match std::fs::File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+        Err(e) => return Err(e).context("Failed to read knowledge database"),
+    };
+    let mut header = [0u8; 16];
+    match file.read_exact(&mut header) {
+        Ok(()) => Ok(&header != SQLITE_HEADER),
+        Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => Ok(false),
+        Err(e) => Err(e).context("Failed to read knowledge database"),
+    }
+}
+
+/// Unlock a connection with `key` and check the key is correct
+pub(crate) fn apply_key(conn: &Connection, key: &str) -> Result<()> {
+    if key.is_empty() {
+        bail!("Encryption key is empty");
+    }
+    conn.pragma_update(None, "key", key)?;
+    conn.query_row("SELECT count(*) FROM sqlite_master", [], |row| {
+        row.get::<_, i64>(0)
+    })
+    .context("Failed to unlock knowledge database (wrong key, or not encrypted)")?;
+    Ok(())
+}
+
+/// Open a connection, unlocking it when a key is given
+pub fn open_connection(path: &Path, key: Option<&str>) -> Result<Connection> {
+    let conn = Connection::open(path).context("Failed to open SQLite database")?;
+    if let Some(key) = key {
+        require_sqlcipher()?;
+        apply_key(&conn, key)?;
+    }
+    Ok(conn)
+}
+
+/// Quote a string as an SQL literal (ATTACH ... KEY does not take parameters)
+fn sql_literal(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(suffix);
+    path.with_file_name(name)
+}
+
+/// Copy every table of `conn` into a new database at `dest` encrypted with
+/// `dest_key` (an empty key produces a plaintext database)
+fn export_to(conn: &Connection, dest: &Path, dest_key: &str) -> Result<()> {
+    let _ = std::fs::remove_file(dest);
+    conn.execute_batch(&format!(
+        "ATTACH DATABASE {} AS exported KEY {};
+         SELECT sqlcipher_export('exported');
+         DETACH DATABASE exported;",
+        sql_literal(&dest.to_string_lossy()),
+        sql_literal(dest_key),
+    ))
+    .context("Failed to export knowledge database")?;
+    Ok(())
+}
+
+/// Atomically replace `path` with `replacement`, dropping stale journal files
+fn swap_in(path: &Path, replacement: &Path) -> Result<()> {
+    std::fs::rename(replacement, path).context("Failed to replace knowledge database")?;
+    for suffix in ["-wal", "-shm", "-journal"] {
+        let _ = std::fs::remove_file(sibling(path, suffix));
+    }
+    Ok(())
+}
+
+/// Convert a plaintext database into an encrypted one, in place
+pub fn encrypt_database(path: &Path, key: &str) -> Result<()> {
+    require_sqlcipher()?;
+    if key.is_empty() {
+        bail!("Encryption key is empty");
+    }
+    if !path.exists() {
+        bail!("Knowledge database not found at {}", path.display());
+    }
+    if is_encrypted(path)? {
+        bail!(
+            "Knowledge database at {} is already encrypted",
+            path.display()
+        );
+    }
+
+    let tmp = sibling(path, ".encrypting");
+    {
+        let conn = Connection::open(path).context("Failed to open SQLite database")?;
+        export_to(&conn, &tmp, key)?;
+    }
+    // Make sure the copy opens with the key before replacing the original
+    open_connection(&tmp, Some(key))?;
+    swap_in(path, &tmp)?;
+
+    info!("Encrypted knowledge database at {}", path.display());
+    Ok(())
+}
+
+/// Convert an encrypted database back to plaintext, in place
+pub fn decrypt_database(path: &Path, key: &str) -> Result<()> {
+    require_sqlcipher()?;
+    if !is_encrypted(path)? {
+        bail!("Knowledge database at {} is not encrypted", path.display());
+    }
+
+    let tmp = sibling(path, ".decrypting");
+    {
+        let conn = open_connection(path, Some(key))?;
+        export_to(&conn, &tmp, "")?;
+    }
+    swap_in(path, &tmp)?;
+
+    warn!(
+        "Decrypted knowledge database at {}; it is now stored in plaintext",
+        path.display()
+    );
+    Ok(())
+}
+
+/// Re-encrypt a database with a new key
+pub fn rekey_database(path: &Path, old_key: &str, new_key: &str) -> Result<()> {
+    if new_key.is_empty() {
+        bail!("New encryption key is empty");
+    }
+    let conn = open_connection(path, Some(old_key))?;
+    conn.pragma_update(None, "rekey", new_key)
+        .context("Failed to rotate knowledge database key")?;
+    info!("Rotated encryption key for {}", path.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plaintext_db(dir: &Path) -> PathBuf {
+        let path = dir.join("k.db");
+        let conn = Connection::open(&path).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE notes (body TEXT);
+             INSERT INTO notes VALUES ('dentist on friday');",
+        )
+        .unwrap();
+        path
+    }
+
+    fn read_note(path: &Path, key: Option<&str>) -> Result<String> {
+        let conn = open_connection(path, key)?;
+        Ok(conn.query_row("SELECT body FROM notes", [], |row| row.get(0))?)
+    }
+
+    #[test]
+    fn test_is_encrypted_plaintext_and_missing() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let path = plaintext_db(temp.path());
+        assert!(!is_encrypted(&path).unwrap());
+        assert!(!is_encrypted(&temp.path().join("missing.db")).unwrap());
+    }
+
+    #[test]
+    fn test_sql_literal_escapes_quotes() {
+        assert_eq!(sql_literal("it's"), "'it''s'");
+    }
+
+    #[test]
+    fn test_encrypt_requires_sqlcipher_or_roundtrips() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let path = plaintext_db(temp.path());
+
+        if !sqlcipher_available() {
+            assert!(encrypt_database(&path, "k1").is_err());
+            assert!(open_connection(&path, Some("k1")).is_err());
+            return;
+        }
+
+        encrypt_database(&path, "k1").unwrap();
+        assert!(is_encrypted(&path).unwrap());
+        assert!(
+            !std::fs::read(&path)
+                .unwrap()
+                .windows(7)
+                .any(|w| w == b"dentist")
+        );
+        assert!(read_note(&path, None).is_err());
+        assert!(read_note(&path, Some("wrong")).is_err());
+        assert_eq!(read_note(&path, Some("k1")).unwrap(), "dentist on friday");
+        assert!(encrypt_database(&path, "k1").is_err());
+
+        rekey_database(&path, "k1", "k2").unwrap();
+        assert!(read_note(&path, Some("k1")).is_err());
+        assert_eq!(read_note(&path, Some("k2")).unwrap(), "dentist on friday");
+
+        decrypt_database(&path, "k2").unwrap();
+        assert!(!is_encrypted(&path).unwrap());
+        assert_eq!(read_note(&path, None).unwrap(), "dentist on friday");
+    }
+}
@@ -0,0 +1,468 @@
+//! Episodic memory extraction from finished conversations
+//!
+//! Facts only enter the graph mid-conversation when the model calls
+//! `remember` or `link_entities`, so most of what a chat establishes is lost
+//! once it scrolls out of the history window. Once a conversation thread has
+//! been idle for a while, its new messages are handed to a
+//! [`MemoryExtractor`] (typically an LLM) that proposes entities,
+//! relationships and user preferences.
+//!
+//! Proposals below the confidence threshold or already known to the graph are
+//! dropped. The rest are committed with provenance pointing at the thread, or
+//! — when review is required — queued as [`MemoryCandidate`]s until they are
+//! accepted or rejected with `meepo memory review`.
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+
+use crate::resolution::normalize_name;
+use crate::sqlite::Conversation;
+
+/// Longest message passed to the extractor, in characters
+const MAX_MESSAGE_CHARS: usize = 2000;
+
+/// Configuration for post-conversation memory extraction
+#[derive(Debug, Clone)]
+pub struct ExtractionConfig {
+    /// Whether extraction runs at all
+    pub enabled: bool,
+    /// A thread counts as finished after this long without a message
+    pub idle_minutes: u64,
+    /// Threads with fewer new messages are marked done without extraction
+    pub min_messages: usize,
+    /// Only the newest messages of a long thread are extracted from
+    pub max_messages: usize,
+    /// Proposals below this confidence are dropped
+    pub min_confidence: f32,
+    /// Queue proposals for review instead of committing them
+    pub require_review: bool,
+    /// Maximum threads extracted per pass
+    pub max_threads_per_pass: usize,
+}
+
+impl Default for ExtractionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            idle_minutes: 30,
+            min_messages: 2,
+            max_messages: 60,
+            min_confidence: 0.6,
+            require_review: false,
+            max_threads_per_pass: 10,
+        }
+    }
+}
+
+/// A finished stretch of a conversation thread
+#[derive(Debug, Clone)]
+pub struct Episode {
+    pub thread_id: String,
+    pub channel: String,
+    pub title: String,
+    /// Messages since the last extraction, oldest first
+    pub messages: Vec<Conversation>,
+    /// Names of existing entities the conversation seems to mention, so the
+    /// extractor can reuse their canonical spelling
+    pub known_entities: Vec<String>,
+}
+
+impl Episode {
+    /// The messages as `sender: content` lines, long messages shortened
+    pub fn transcript(&self) -> String {
+        let mut out = String::new();
+        for message in &self.messages {
+            let content = message.content.trim();
+            out.push_str(&message.sender);
+            out.push_str(": ");
+            if content.chars().count() > MAX_MESSAGE_CHARS {
+                out.extend(content.chars().take(MAX_MESSAGE_CHARS));
+                out.push_str(" [...]");
+            } else {
+                out.push_str(content);
+            }
+            out.push('\n');
+        }
+        out
+    }
+}
+
+fn default_confidence() -> f32 {
+    0.5
+}
+
+/// An entity the extractor believes the conversation established
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProposedEntity {
+    pub name: String,
+    pub entity_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default = "default_confidence")]
+    pub confidence: f32,
+    /// Quote from the conversation supporting the fact
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub evidence: Option<String>,
+}
+
+/// A relationship between two entities, referenced by name
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProposedRelationship {
+    pub source: String,
+    /// Type used to create the source entity if it does not exist yet
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_type: Option<String>,
+    pub relation_type: String,
+    pub target: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_type: Option<String>,
+    #[serde(default = "default_confidence")]
+    pub confidence: f32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub evidence: Option<String>,
+}
+
+/// A user preference, stored with `upsert_preference`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProposedPreference {
+    pub category: String,
+    pub key: String,
+    pub value: JsonValue,
+    #[serde(default = "default_confidence")]
+    pub confidence: f32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub evidence: Option<String>,
+}
+
+/// Everything an extractor proposes for one episode
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ExtractedMemories {
+    #[serde(default)]
+    pub entities: Vec<ProposedEntity>,
+    #[serde(default)]
+    pub relationships: Vec<ProposedRelationship>,
+    #[serde(default)]
+    pub preferences: Vec<ProposedPreference>,
+}
+
+/// Proposes memories from a finished conversation (typically via an LLM)
+#[async_trait]
+pub trait MemoryExtractor: Send + Sync {
+    async fn extract(&self, episode: &Episode) -> Result<ExtractedMemories>;
+}
+
+/// A single proposed fact
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum MemoryProposal {
+    Entity(ProposedEntity),
+    Relationship(ProposedRelationship),
+    Preference(ProposedPreference),
+}
+
+impl MemoryProposal {
+    pub fn confidence(&self) -> f32 {
+        match self {
+            Self::Entity(e) => e.confidence,
+            Self::Relationship(r) => r.confidence,
+            Self::Preference(p) => p.confidence,
+        }
+    }
+
+    pub fn evidence(&self) -> Option<&str> {
+        match self {
+            Self::Entity(e) => e.evidence.as_deref(),
+            Self::Relationship(r) => r.evidence.as_deref(),
+            Self::Preference(p) => p.evidence.as_deref(),
+        }
+    }
+
+    /// Identity of the fact, used to drop duplicates
+    pub fn key(&self) -> String {
+        match self {
+            Self::Entity(e) => format!("entity:{}:{}", e.entity_type, normalize_name(&e.name)),
+            Self::Relationship(r) => format!(
+                "relationship:{}:{}:{}",
+                normalize_name(&r.source),
+                r.relation_type,
+                normalize_name(&r.target)
+            ),
+            Self::Preference(p) => format!("preference:{}", p.key),
+        }
+    }
+
+    /// One-line human-readable description
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Entity(e) => match &e.description {
+                Some(d) => format!("{} ({}): {}", e.name, e.entity_type, d),
+                None => format!("{} ({})", e.name, e.entity_type),
+            },
+            Self::Relationship(r) => format!("{} {} {}", r.source, r.relation_type, r.target),
+            Self::Preference(p) => format!("{}.{} = {}", p.category, p.key, p.value),
+        }
+    }
+}
+
+/// Lowercase snake_case form of a type or key
+fn normalize_label(label: &str) -> String {
+    label
+        .trim()
+        .to_lowercase()
+        .split(|c: char| c.is_whitespace() || c == '-')
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+/// Clean up extracted memories into a list of proposals.
+///
+/// Names are trimmed and types normalized to snake_case; proposals with
+/// empty fields are dropped, as are those below `min_confidence` (the second
+/// value counts these), and duplicates keep their most confident version.
+/// Relationship endpoints that are proposed as entities in the same batch
+/// inherit their type. Entities come first so relationships can refer to
+/// them when committed in order.
+pub fn prepare_proposals(
+    extracted: ExtractedMemories,
+    min_confidence: f32,
+) -> (Vec<MemoryProposal>, usize) {
+    let mut entity_types: HashMap<String, String> = HashMap::new();
+    let mut proposals = Vec::new();
+
+    for mut e in extracted.entities {
+        e.name = e.name.trim().to_string();
+        e.entity_type = normalize_label(&e.entity_type);
+        e.description = e.description.filter(|d| !d.trim().is_empty());
+        if e.name.is_empty() || e.entity_type.is_empty() {
+            continue;
+        }
+        entity_types.insert(normalize_name(&e.name), e.entity_type.clone());
+        proposals.push(MemoryProposal::Entity(e));
+    }
+    for mut r in extracted.relationships {
+        r.source = r.source.trim().to_string();
+        r.target = r.target.trim().to_string();
+        r.relation_type = normalize_label(&r.relation_type);
+        if r.source.is_empty() || r.target.is_empty() || r.relation_type.is_empty() {
+            continue;
+        }
+        let known_type = |name: &str, given: Option<String>| {
+            given
+                .map(|t| normalize_label(&t))
+                .filter(|t| !t.is_empty())
+                .or_else(|| entity_types.get(&normalize_name(name)).cloned())
+        };
+        r.source_type = known_type(&r.source, r.source_type.take());
+        r.target_type = known_type(&r.target, r.target_type.take());
+        proposals.push(MemoryProposal::Relationship(r));
+    }
+    for mut p in extracted.preferences {
+        p.category = normalize_label(&p.category);
+        p.key = normalize_label(&p.key);
+        if p.key.is_empty() || p.value.is_null() {
+            continue;
+        }
+        if p.category.is_empty() {
+            p.category = "general".to_string();
+        }
+        proposals.push(MemoryProposal::Preference(p));
+    }
+
+    let mut kept: Vec<MemoryProposal> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+    let mut low_confidence = 0;
+    for proposal in proposals {
+        if proposal.confidence() < min_confidence {
+            low_confidence += 1;
+            continue;
+        }
+        match index.get(&proposal.key()) {
+            Some(&i) => {
+                if proposal.confidence() > kept[i].confidence() {
+                    kept[i] = proposal;
+                }
+            }
+            None => {
+                index.insert(proposal.key(), kept.len());
+                kept.push(proposal);
+            }
+        }
+    }
+    (kept, low_confidence)
+}
+
+/// Review state of a queued proposal
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CandidateStatus {
+    Pending,
+    Committed,
+    Rejected,
+}
+
+impl CandidateStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Committed => "committed",
+            Self::Rejected => "rejected",
+        }
+    }
+}
+
+impl std::str::FromStr for CandidateStatus {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "pending" => Ok(Self::Pending),
+            "committed" => Ok(Self::Committed),
+            "rejected" => Ok(Self::Rejected),
+            other => Err(anyhow!("Unknown candidate status: {}", other)),
+        }
+    }
+}
+
+/// A proposal queued for review
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryCandidate {
+    pub id: String,
+    /// Conversation thread the proposal was extracted from
+    pub thread_id: String,
+    pub proposal: MemoryProposal,
+    pub status: CandidateStatus,
+    pub created_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub decided_at: Option<DateTime<Utc>>,
+}
+
+/// What an extraction pass did
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExtractionReport {
+    /// Threads whose new messages were handed to the extractor
+    pub threads: usize,
+    /// Proposals that passed the confidence threshold
+    pub proposed: usize,
+    /// Proposals written to the graph
+    pub committed: usize,
+    /// Proposals queued for review
+    pub queued: usize,
+    /// Proposals already known to the graph (or already queued or rejected)
+    pub duplicates: usize,
+    /// Proposals dropped for low confidence
+    pub low_confidence: usize,
+    /// Threads whose extraction failed (retried on the next pass)
+    pub failed: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entity(name: &str, entity_type: &str, confidence: f32) -> ProposedEntity {
+        ProposedEntity {
+            name: name.to_string(),
+            entity_type: entity_type.to_string(),
+            description: None,
+            confidence,
+            evidence: None,
+        }
+    }
+
+    #[test]
+    fn test_prepare_proposals() {
+        let extracted = ExtractedMemories {
+            entities: vec![
+                entity(" Casa Alfama ", "Hotel", 0.9),
+                entity("casa alfama", "hotel", 0.7),
+                entity("Lisbon", "place", 0.3),
+                entity("", "person", 0.9),
+            ],
+            relationships: vec![ProposedRelationship {
+                source: "User".to_string(),
+                source_type: Some("Person".to_string()),
+                relation_type: "stays at".to_string(),
+                target: "Casa Alfama".to_string(),
+                target_type: None,
+                confidence: 0.8,
+                evidence: Some("Booked Casa Alfama".to_string()),
+            }],
+            preferences: vec![ProposedPreference {
+                category: "".to_string(),
+                key: "Seat Preference".to_string(),
+                value: serde_json::json!("aisle"),
+                confidence: 0.95,
+                evidence: None,
+            }],
+        };
+
+        let (kept, low_confidence) = prepare_proposals(extracted, 0.6);
+        assert_eq!(low_confidence, 1);
+        assert_eq!(kept.len(), 3);
+        match &kept[0] {
+            MemoryProposal::Entity(e) => {
+                assert_eq!(e.name, "Casa Alfama");
+                assert_eq!(e.entity_type, "hotel");
+                assert_eq!(e.confidence, 0.9);
+            }
+            other => panic!("expected entity, got {:?}", other),
+        }
+        match &kept[1] {
+            MemoryProposal::Relationship(r) => {
+                assert_eq!(r.relation_type, "stays_at");
+                assert_eq!(r.source_type.as_deref(), Some("person"));
+                assert_eq!(r.target_type.as_deref(), Some("hotel"));
+            }
+            other => panic!("expected relationship, got {:?}", other),
+        }
+        assert_eq!(kept[2].key(), "preference:seat_preference");
+        assert_eq!(kept[2].describe(), "general.seat_preference = \"aisle\"");
+    }
+
+    #[test]
+    fn test_proposal_serde_is_tagged() {
+        let proposal = MemoryProposal::Entity(entity("Lisbon", "place", 0.8));
+        let json = serde_json::to_value(&proposal).unwrap();
+        assert_eq!(json["kind"], "entity");
+        assert_eq!(json["name"], "Lisbon");
+        let back: MemoryProposal = serde_json::from_value(json).unwrap();
+        assert_eq!(back, proposal);
+
+        let parsed: ExtractedMemories =
+            serde_json::from_str(r#"{"entities": [{"name": "Bob", "entity_type": "person"}]}"#)
+                .unwrap();
+        assert_eq!(parsed.entities[0].confidence, 0.5);
+        assert!(parsed.preferences.is_empty());
+    }
+
+    #[test]
+    fn test_transcript_shortens_long_messages() {
+        let message = |sender: &str, content: String| Conversation {
+            id: "c".to_string(),
+            channel: "slack".to_string(),
+            sender: sender.to_string(),
+            content,
+            metadata: None,
+            created_at: Utc::now(),
+            thread_id: None,
+        };
+        let episode = Episode {
+            thread_id: "slack".to_string(),
+            channel: "slack".to_string(),
+            title: "Trip".to_string(),
+            messages: vec![
+                message("alice", "  Plan the trip  ".to_string()),
+                message("meepo", "x".repeat(MAX_MESSAGE_CHARS + 10)),
+            ],
+            known_entities: Vec::new(),
+        };
+        let transcript = episode.transcript();
+        assert!(transcript.starts_with("alice: Plan the trip\nmeepo: xxx"));
+        assert!(transcript.ends_with(" [...]\n"));
+    }
+}
@@ -0,0 +1,882 @@
+//! Knowledge export and import
+//!
+//! Serializes the whole knowledge graph (entities, relationships, conversations,
+//! goals, preferences and fact provenance) to a versioned NDJSON or JSON-LD document, and imports
+//! such documents back with ID remapping and configurable conflict handling.
+
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+use crate::embeddings::{EmbeddingProvider, VectorIndex};
+use crate::graph::KnowledgeGraph;
+use crate::sqlite::{Conversation, Entity, Goal, KnowledgeDb, Relationship, UserPreference};
+use crate::temporal::{FactType, ProvenanceRecord};
+
+/// Identifier written into every export header
+pub const EXPORT_FORMAT: &str = "meepo-knowledge";
+
+/// Current export format version. Bump when the record layout changes.
+/// Version 2 added relationship validity fields and provenance records.
+pub const EXPORT_VERSION: u32 = 2;
+
+const JSONLD_VOCAB: &str = "https://meepo.dev/ns/knowledge#";
+
+/// Serialization format for exports
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    /// One JSON record per line, starting with a header record
+    Ndjson,
+    /// A single JSON-LD document with an `@graph` array
+    JsonLd,
+}
+
+impl std::str::FromStr for ExportFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "ndjson" | "jsonl" => Ok(Self::Ndjson),
+            "jsonld" | "json-ld" => Ok(Self::JsonLd),
+            other => bail!(
+                "Unknown export format '{}'. Use 'ndjson' or 'jsonld'",
+                other
+            ),
+        }
+    }
+}
+
+/// How to handle imported records that already exist in the database
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConflictStrategy {
+    /// Keep the existing record and drop the imported one
+    #[default]
+    Skip,
+    /// Update the existing record, merging metadata (imported keys win)
+    Merge,
+}
+
+impl std::str::FromStr for ConflictStrategy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "skip" => Ok(Self::Skip),
+            "merge" => Ok(Self::Merge),
+            other => bail!(
+                "Unknown conflict strategy '{}'. Use 'skip' or 'merge'",
+                other
+            ),
+        }
+    }
+}
+
+/// Header record at the start of every export
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportHeader {
+    pub format: String,
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+}
+
+/// A single record in an NDJSON export
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ExportRecord {
+    Header(ExportHeader),
+    Entity(Entity),
+    Relationship(Relationship),
+    Conversation(Conversation),
+    Goal(Goal),
+    Preference(UserPreference),
+    Provenance(ProvenanceRecord),
+}
+
+impl ExportRecord {
+    fn jsonld_type(&self) -> &'static str {
+        match self {
+            Self::Header(_) => "Export",
+            Self::Entity(_) => "Entity",
+            Self::Relationship(_) => "Relationship",
+            Self::Conversation(_) => "Conversation",
+            Self::Goal(_) => "Goal",
+            Self::Preference(_) => "Preference",
+            Self::Provenance(_) => "Provenance",
+        }
+    }
+
+    fn jsonld_id(&self) -> Option<String> {
+        let (kind, id) = match self {
+            Self::Header(_) => return None,
+            Self::Entity(e) => ("entity", &e.id),
+            Self::Relationship(r) => ("relationship", &r.id),
+            Self::Conversation(c) => ("conversation", &c.id),
+            Self::Goal(g) => ("goal", &g.id),
+            Self::Preference(p) => ("preference", &p.id),
+            Self::Provenance(p) => ("provenance", &p.id),
+        };
+        Some(format!("urn:meepo:{}:{}", kind, id))
+    }
+}
+
+/// Counts of what an import did
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ImportReport {
+    pub entities_created: usize,
+    pub entities_merged: usize,
+    pub entities_skipped: usize,
+    pub relationships_created: usize,
+    pub relationships_skipped: usize,
+    pub conversations_created: usize,
+    pub conversations_skipped: usize,
+    pub goals_written: usize,
+    pub goals_skipped: usize,
+    pub preferences_written: usize,
+    pub preferences_skipped: usize,
+    pub provenance_created: usize,
+    pub provenance_skipped: usize,
+    /// Imported entity IDs that were assigned a different ID locally
+    pub remapped_ids: HashMap<String, String>,
+}
+
+impl std::fmt::Display for ImportReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "entities: {} created, {} merged, {} skipped; relationships: {} created, {} skipped; \
+             conversations: {} created, {} skipped; goals: {} written, {} skipped; \
+             preferences: {} written, {} skipped; provenance: {} created, {} skipped; \
+             {} IDs remapped",
+            self.entities_created,
+            self.entities_merged,
+            self.entities_skipped,
+            self.relationships_created,
+            self.relationships_skipped,
+            self.conversations_created,
+            self.conversations_skipped,
+            self.goals_written,
+            self.goals_skipped,
+            self.preferences_written,
+            self.preferences_skipped,
+            self.provenance_created,
+            self.provenance_skipped,
+            self.remapped_ids.len()
+        )
+    }
+}
+
+/// Collect every exportable record from the database, header first
+pub async fn collect_records(db: &KnowledgeDb) -> Result<Vec<ExportRecord>> {
+    let mut records = vec![ExportRecord::Header(ExportHeader {
+        format: EXPORT_FORMAT.to_string(),
+        version: EXPORT_VERSION,
+        exported_at: Utc::now(),
+    })];
+
+    let mut entities = db.get_all_entities().await?;
+    entities.sort_by_key(|e| e.created_at);
+    records.extend(entities.into_iter().map(ExportRecord::Entity));
+    records.extend(
+        db.get_all_relationships()
+            .await?
+            .into_iter()
+            .map(ExportRecord::Relationship),
+    );
+    records.extend(
+        db.get_all_conversations()
+            .await?
+            .into_iter()
+            .map(ExportRecord::Conversation),
+    );
+    records.extend(
+        db.get_all_goals()
+            .await?
+            .into_iter()
+            .map(ExportRecord::Goal),
+    );
+    records.extend(
+        db.get_preferences(None)
+            .await?
+            .into_iter()
+            .map(ExportRecord::Preference),
+    );
+    records.extend(
+        db.get_all_provenance()
+            .await?
+            .into_iter()
+            .map(ExportRecord::Provenance),
+    );
+
+    debug!("Collected {} export records", records.len());
+    Ok(records)
+}
+
+/// Export the knowledge database in the given format
+pub async fn export_knowledge(db: &KnowledgeDb, format: ExportFormat) -> Result<String> {
+    let records = collect_records(db).await?;
+    let output = match format {
+        ExportFormat::Ndjson => to_ndjson(&records)?,
+        ExportFormat::JsonLd => to_jsonld(&records)?,
+    };
+    info!(
+        "Exported {} knowledge records as {:?}",
+        records.len() - 1,
+        format
+    );
+    Ok(output)
+}
+
+/// Render records as NDJSON (one record per line)
+pub fn to_ndjson(records: &[ExportRecord]) -> Result<String> {
+    let mut out = String::new();
+    for record in records {
+        out.push_str(&serde_json::to_string(record)?);
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+/// Render records as a JSON-LD document
+pub fn to_jsonld(records: &[ExportRecord]) -> Result<String> {
+    let mut graph = Vec::new();
+    let mut header = None;
+
+    for record in records {
+        let mut node = serde_json::to_value(record)?;
+        let obj = node
+            .as_object_mut()
+            .context("export record did not serialize to an object")?;
+        obj.remove("type");
+        if let ExportRecord::Header(h) = record {
+            header = Some(h.clone());
+            continue;
+        }
+        if let Some(id) = record.jsonld_id() {
+            obj.insert("@id".to_string(), JsonValue::String(id));
+        }
+        obj.insert(
+            "@type".to_string(),
+            JsonValue::String(record.jsonld_type().to_string()),
+        );
+        graph.push(node);
+    }
+
+    let header = header.unwrap_or(ExportHeader {
+        format: EXPORT_FORMAT.to_string(),
+        version: EXPORT_VERSION,
+        exported_at: Utc::now(),
+    });
+
+    let doc = serde_json::json!({
+        "@context": {
+            "@vocab": JSONLD_VOCAB,
+            "created_at": {"@type": "http://www.w3.org/2001/XMLSchema#dateTime"},
+            "updated_at": {"@type": "http://www.w3.org/2001/XMLSchema#dateTime"},
+        },
+        "format": header.format,
+        "version": header.version,
+        "exported_at": header.exported_at,
+        "@graph": graph,
+    });
+
+    Ok(serde_json::to_string_pretty(&doc)?)
+}
+
+/// Parse an export produced by [`export_knowledge`], auto-detecting NDJSON or JSON-LD
+pub fn parse_export(input: &str) -> Result<Vec<ExportRecord>> {
+    if let Ok(doc) = serde_json::from_str::<JsonValue>(input)
+        && doc.get("@graph").is_some()
+    {
+        return parse_jsonld(&doc);
+    }
+    parse_ndjson(input)
+}
+
+fn parse_ndjson(input: &str) -> Result<Vec<ExportRecord>> {
+    let mut records = Vec::new();
+    for (lineno, line) in input.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let record: ExportRecord = serde_json::from_str(line)
+            .with_context(|| format!("Invalid export record on line {}", lineno + 1))?;
+        records.push(record);
+    }
+
+    match records.first() {
+        Some(ExportRecord::Header(h)) => check_header(h)?,
+        _ => bail!("Export is missing its header record"),
+    }
+    Ok(records)
+}
+
+fn parse_jsonld(doc: &JsonValue) -> Result<Vec<ExportRecord>> {
+    let header: ExportHeader = serde_json::from_value(serde_json::json!({
+        "format": doc.get("format").cloned().unwrap_or(JsonValue::Null),
+        "version": doc.get("version").cloned().unwrap_or(JsonValue::Null),
+        "exported_at": doc.get("exported_at").cloned().unwrap_or(JsonValue::Null),
+    }))
+    .context("JSON-LD export is missing format/version/exported_at")?;
+    check_header(&header)?;
+
+    let nodes = doc
+        .get("@graph")
+        .and_then(|g| g.as_array())
+        .context("JSON-LD @graph is not an array")?;
+
+    let mut records = vec![ExportRecord::Header(header)];
+    for node in nodes {
+        let mut node = node.clone();
+        let obj = node
+            .as_object_mut()
+            .context("JSON-LD node is not an object")?;
+        let node_type = obj
+            .remove("@type")
+            .and_then(|t| t.as_str().map(|s| s.to_string()))
+            .context("JSON-LD node is missing @type")?;
+        obj.remove("@id");
+        let tag = match node_type.as_str() {
+            "Entity" => "entity",
+            "Relationship" => "relationship",
+            "Conversation" => "conversation",
+            "Goal" => "goal",
+            "Preference" => "preference",
+            "Provenance" => "provenance",
+            other => {
+                warn!("Skipping unknown JSON-LD node type: {}", other);
+                continue;
+            }
+        };
+        obj.insert("type".to_string(), JsonValue::String(tag.to_string()));
+        let record: ExportRecord = serde_json::from_value(node)
+            .with_context(|| format!("Invalid {} node in JSON-LD export", node_type))?;
+        records.push(record);
+    }
+    Ok(records)
+}
+
+fn check_header(header: &ExportHeader) -> Result<()> {
+    if header.format != EXPORT_FORMAT {
+        bail!(
+            "Unrecognized export format '{}' (expected '{}')",
+            header.format,
+            EXPORT_FORMAT
+        );
+    }
+    if header.version > EXPORT_VERSION {
+        bail!(
+            "Export version {} is newer than supported version {}",
+            header.version,
+            EXPORT_VERSION
+        );
+    }
+    Ok(())
+}
+
+/// Merge two optional metadata objects; keys from `incoming` win
+fn merge_metadata(existing: Option<JsonValue>, incoming: Option<JsonValue>) -> Option<JsonValue> {
+    match (existing, incoming) {
+        (Some(JsonValue::Object(mut base)), Some(JsonValue::Object(extra))) => {
+            base.extend(extra);
+            Some(JsonValue::Object(base))
+        }
+        (existing, None) => existing,
+        (_, incoming) => incoming,
+    }
+}
+
+/// Whether a local entity and an incoming one describe the same thing
+fn same_entity(local: &Entity, incoming: &Entity) -> bool {
+    local.name.eq_ignore_ascii_case(&incoming.name)
+        && local
+            .entity_type
+            .eq_ignore_ascii_case(&incoming.entity_type)
+}
+
+/// Import an export document into the knowledge graph.
+///
+/// Entities are matched against existing ones by ID — as long as name and
+/// type agree — then by case-insensitive name and type. Matches are skipped or
+/// merged according to `strategy`; new entities keep their original ID unless
+/// it is already taken by a different entity, in which case a fresh one is
+/// assigned and relationships are rewritten to follow it.
+///
+/// The Tantivy index is rebuilt afterwards. If `vectors` is given, the vector
+/// index is rebuilt with the supplied provider too (callers persist it).
+pub async fn import_knowledge(
+    graph: &KnowledgeGraph,
+    input: &str,
+    strategy: ConflictStrategy,
+    vectors: Option<(&VectorIndex, &dyn EmbeddingProvider)>,
+) -> Result<ImportReport> {
+    let records = parse_export(input)?;
+    let db = graph.db();
+    let mut report = ImportReport::default();
+    let mut id_map: HashMap<String, String> = HashMap::new();
+
+    // Entities first so relationships can be remapped
+    for record in &records {
+        let ExportRecord::Entity(entity) = record else {
+            continue;
+        };
+
+        // An ID hit only counts as the same entity if name and type agree;
+        // otherwise the ID belongs to something else locally
+        let id_taken = db.get_entity(&entity.id).await?;
+        let existing = match id_taken {
+            Some(ref e) if same_entity(e, entity) => id_taken.clone(),
+            _ => {
+                db.find_entity_by_name(&entity.name, &entity.entity_type)
+                    .await?
+            }
+        };
+
+        if let Some(existing) = existing {
+            if existing.id != entity.id {
+                report
+                    .remapped_ids
+                    .insert(entity.id.clone(), existing.id.clone());
+            }
+            id_map.insert(entity.id.clone(), existing.id.clone());
+            match strategy {
+                ConflictStrategy::Skip => report.entities_skipped += 1,
+                ConflictStrategy::Merge => {
+                    let metadata =
+                        merge_metadata(existing.metadata.clone(), entity.metadata.clone());
+                    db.update_entity(&existing.id, Some(&entity.name), None, metadata)
+                        .await?;
+                    report.entities_merged += 1;
+                }
+            }
+            continue;
+        }
+
+        if id_taken.is_some() {
+            let fresh = Entity {
+                id: Uuid::new_v4().to_string(),
+                ..entity.clone()
+            };
+            db.insert_entity_record(&fresh).await?;
+            report
+                .remapped_ids
+                .insert(entity.id.clone(), fresh.id.clone());
+            id_map.insert(entity.id.clone(), fresh.id);
+        } else {
+            db.insert_entity_record(entity).await?;
+            id_map.insert(entity.id.clone(), entity.id.clone());
+        }
+        report.entities_created += 1;
+    }
+
+    let existing_rels = db.get_all_relationships().await?;
+    let mut rel_keys: HashMap<(String, String, String), String> = existing_rels
+        .into_iter()
+        .map(|r| ((r.source_id, r.target_id, r.relation_type), r.id))
+        .collect();
+    let mut rel_map: HashMap<String, String> = HashMap::new();
+
+    for record in &records {
+        match record {
+            ExportRecord::Relationship(rel) => {
+                let source = id_map.get(&rel.source_id).cloned();
+                let target = id_map.get(&rel.target_id).cloned();
+                let (Some(source_id), Some(target_id)) = (source, target) else {
+                    warn!("Skipping relationship {} with unknown endpoint(s)", rel.id);
+                    report.relationships_skipped += 1;
+                    continue;
+                };
+
+                let key = (
+                    source_id.clone(),
+                    target_id.clone(),
+                    rel.relation_type.clone(),
+                );
+                if let Some(existing_id) = rel_keys.get(&key) {
+                    rel_map.insert(rel.id.clone(), existing_id.clone());
+                    if strategy == ConflictStrategy::Merge
+                        && let Some(existing) = db.get_relationship(existing_id).await?
+                    {
+                        let metadata = merge_metadata(existing.metadata, rel.metadata.clone());
+                        db.update_relationship(existing_id, None, metadata).await?;
+                    }
+                    report.relationships_skipped += 1;
+                    continue;
+                }
+
+                let id = if db.get_relationship(&rel.id).await?.is_some() {
+                    Uuid::new_v4().to_string()
+                } else {
+                    rel.id.clone()
+                };
+                let remapped = Relationship {
+                    id: id.clone(),
+                    source_id,
+                    target_id,
+                    ..rel.clone()
+                };
+                db.insert_relationship_record(&remapped).await?;
+                rel_map.insert(rel.id.clone(), id.clone());
+                rel_keys.insert(key, id);
+                report.relationships_created += 1;
+            }
+            ExportRecord::Conversation(conv) => {
+                let mut conv = conv.clone();
+                if let Some(JsonValue::Object(meta)) = conv.metadata.as_mut()
+                    && let Some(JsonValue::String(entity_id)) = meta.get("entity_id")
+                    && let Some(mapped) = id_map.get(entity_id)
+                {
+                    let mapped = mapped.clone();
+                    meta.insert("entity_id".to_string(), JsonValue::String(mapped));
+                }
+                if db.insert_conversation_record(&conv).await? {
+                    report.conversations_created += 1;
+                } else {
+                    report.conversations_skipped += 1;
+                }
+            }
+            ExportRecord::Goal(goal) => {
+                let replace = strategy == ConflictStrategy::Merge;
+                if db.insert_goal_record(goal, replace).await? {
+                    report.goals_written += 1;
+                } else {
+                    report.goals_skipped += 1;
+                }
+            }
+            ExportRecord::Preference(pref) => {
+                let exists = db
+                    .get_preferences(Some(&pref.category))
+                    .await?
+                    .iter()
+                    .any(|p| p.key == pref.key);
+                if exists && strategy == ConflictStrategy::Skip {
+                    report.preferences_skipped += 1;
+                    continue;
+                }
+                db.upsert_preference(
+                    &pref.category,
+                    &pref.key,
+                    pref.value.clone(),
+                    pref.confidence,
+                    pref.learned_from.as_deref(),
+                )
+                .await?;
+                report.preferences_written += 1;
+            }
+            ExportRecord::Provenance(record) => {
+                let mapped = match record.fact_type {
+                    FactType::Entity => id_map.get(&record.fact_id),
+                    FactType::Relationship => rel_map.get(&record.fact_id),
+                };
+                let Some(fact_id) = mapped else {
+                    report.provenance_skipped += 1;
+                    continue;
+                };
+                let remapped = ProvenanceRecord {
+                    fact_id: fact_id.clone(),
+                    ..record.clone()
+                };
+                if db.insert_provenance_record(&remapped).await? {
+                    report.provenance_created += 1;
+                } else {
+                    report.provenance_skipped += 1;
+                }
+            }
+            ExportRecord::Header(_) | ExportRecord::Entity(_) => {}
+        }
+    }
+
+    graph.reindex().await?;
+    if let Some((index, provider)) = vectors {
+        let entities = graph.get_all_entities().await?;
+        index.rebuild_from_entities(&entities, provider)?;
+    }
+
+    info!("Knowledge import complete: {}", report);
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::embeddings::NoOpEmbeddingProvider;
+
+    fn new_graph(temp: &tempfile::TempDir, name: &str) -> Result<KnowledgeGraph> {
+        KnowledgeGraph::new(
+            temp.path().join(format!("{}.db", name)),
+            temp.path().join(format!("{}_idx", name)),
+        )
+    }
+
+    async fn seed(graph: &KnowledgeGraph) -> Result<(String, String)> {
+        let alice = graph
+            .add_entity("Alice", "person", Some(serde_json::json!({"role": "eng"})))
+            .await?;
+        let acme = graph.add_entity("Acme", "company", None).await?;
+        graph.link_entities(&alice, &acme, "works_at", None).await?;
+        graph
+            .store_conversation("slack", "user", "Alice joined Acme", None)
+            .await?;
+        graph
+            .db()
+            .insert_goal("Ship the release", 4, 3600, None, None, "user")
+            .await?;
+        graph
+            .db()
+            .upsert_preference("schedule", "wake_time", serde_json::json!("7am"), 0.8, None)
+            .await?;
+        Ok((alice, acme))
+    }
+
+    #[tokio::test]
+    async fn test_ndjson_roundtrip() -> Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        let src = new_graph(&temp, "src")?;
+        let (alice, _) = seed(&src).await?;
+
+        let exported = export_knowledge(&src.db(), ExportFormat::Ndjson).await?;
+        let first_line = exported.lines().next().unwrap();
+        assert!(first_line.contains("\"type\":\"header\""));
+        assert!(first_line.contains(&format!("\"version\":{}", EXPORT_VERSION)));
+
+        let dst = new_graph(&temp, "dst")?;
+        let report = import_knowledge(&dst, &exported, ConflictStrategy::Skip, None).await?;
+        assert_eq!(report.entities_created, 2);
+        assert_eq!(report.relationships_created, 1);
+        assert_eq!(report.conversations_created, 1);
+        assert_eq!(report.goals_written, 1);
+        assert_eq!(report.preferences_written, 1);
+
+        // IDs are preserved and the search index was rebuilt
+        assert_eq!(dst.get_relationships(&alice).await?.len(), 1);
+        assert!(!dst.search("Alice", 10)?.is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_jsonld_roundtrip() -> Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        let src = new_graph(&temp, "src")?;
+        seed(&src).await?;
+
+        let exported = export_knowledge(&src.db(), ExportFormat::JsonLd).await?;
+        let doc: JsonValue = serde_json::from_str(&exported)?;
+        assert!(doc.get("@context").is_some());
+        assert_eq!(doc["@graph"].as_array().unwrap().len(), 6);
+        assert!(exported.contains("urn:meepo:entity:"));
+
+        let records = parse_export(&exported)?;
+        assert!(matches!(records[0], ExportRecord::Header(_)));
+        assert_eq!(records.len(), 7);
+
+        let dst = new_graph(&temp, "dst")?;
+        let report = import_knowledge(&dst, &exported, ConflictStrategy::Skip, None).await?;
+        assert_eq!(report.entities_created, 2);
+        assert_eq!(report.relationships_created, 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_import_skip_is_idempotent() -> Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        let graph = new_graph(&temp, "g")?;
+        seed(&graph).await?;
+
+        let exported = export_knowledge(&graph.db(), ExportFormat::Ndjson).await?;
+        let report = import_knowledge(&graph, &exported, ConflictStrategy::Skip, None).await?;
+        assert_eq!(report.entities_created, 0);
+        assert_eq!(report.entities_skipped, 2);
+        assert_eq!(report.relationships_skipped, 1);
+        assert_eq!(report.conversations_skipped, 1);
+        assert_eq!(report.goals_skipped, 1);
+        assert_eq!(report.preferences_skipped, 1);
+        assert_eq!(graph.get_all_entities().await?.len(), 2);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_import_remaps_ids_by_name_and_merges() -> Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        let src = new_graph(&temp, "src")?;
+        let (src_alice, _) = seed(&src).await?;
+        let exported = export_knowledge(&src.db(), ExportFormat::Ndjson).await?;
+
+        // Destination already knows "alice" under a different ID
+        let dst = new_graph(&temp, "dst")?;
+        let local_alice = dst
+            .add_entity(
+                "alice",
+                "person",
+                Some(serde_json::json!({"team": "infra"})),
+            )
+            .await?;
+
+        let report = import_knowledge(&dst, &exported, ConflictStrategy::Merge, None).await?;
+        assert_eq!(report.entities_merged, 1);
+        assert_eq!(report.entities_created, 1);
+        assert_eq!(report.remapped_ids.get(&src_alice), Some(&local_alice));
+
+        // The relationship now hangs off the local entity
+        let rels = dst.get_relationships(&local_alice).await?;
+        assert_eq!(rels.len(), 1);
+        assert_eq!(rels[0].source_id, local_alice);
+
+        // Metadata from both sides is kept
+        let merged = dst.get_entity(&local_alice).await?.unwrap();
+        let meta = merged.metadata.unwrap();
+        assert_eq!(meta["team"], "infra");
+        assert_eq!(meta["role"], "eng");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_import_assigns_fresh_id_when_taken_by_other_entity() -> Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        let src = new_graph(&temp, "src")?;
+        let (src_alice, acme) = seed(&src).await?;
+        let exported = export_knowledge(&src.db(), ExportFormat::Ndjson).await?;
+
+        // Locally, Alice's ID belongs to an unrelated entity
+        let dst = new_graph(&temp, "dst")?;
+        dst.db()
+            .insert_entity_record(&Entity {
+                id: src_alice.clone(),
+                name: "Quarterly report".to_string(),
+                entity_type: "document".to_string(),
+                metadata: None,
+                created_at: Utc::now(),
+                updated_at: Utc::now(),
+            })
+            .await?;
+
+        let report = import_knowledge(&dst, &exported, ConflictStrategy::Merge, None).await?;
+        assert_eq!(report.entities_created, 2);
+        assert_eq!(report.entities_merged, 0);
+        let fresh = report.remapped_ids.get(&src_alice).unwrap().clone();
+        assert_ne!(fresh, src_alice);
+
+        // The local entity is untouched and the relationship follows the new ID
+        let local = dst.get_entity(&src_alice).await?.unwrap();
+        assert_eq!(local.name, "Quarterly report");
+        assert_eq!(dst.get_entity(&fresh).await?.unwrap().name, "Alice");
+        let rels = dst.get_relationships(&fresh).await?;
+        assert_eq!(rels.len(), 1);
+        assert_eq!(rels[0].target_id, acme);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_import_rebuilds_vector_index() -> Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        let src = new_graph(&temp, "src")?;
+        seed(&src).await?;
+        let exported = export_knowledge(&src.db(), ExportFormat::Ndjson).await?;
+
+        let dst = new_graph(&temp, "dst")?;
+        let index = VectorIndex::new(4);
+        index.insert("stale", vec![1.0, 0.0, 0.0, 0.0])?;
+        let provider = NoOpEmbeddingProvider::new(4);
+        import_knowledge(
+            &dst,
+            &exported,
+            ConflictStrategy::Skip,
+            Some((&index, &provider)),
+        )
+        .await?;
+        assert_eq!(index.len(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_rejects_newer_version() {
+        let input = format!(
+            "{{\"type\":\"header\",\"format\":\"{}\",\"version\":{},\"exported_at\":\"2026-01-01T00:00:00Z\"}}\n",
+            EXPORT_FORMAT,
+            EXPORT_VERSION + 1
+        );
+        assert!(parse_export(&input).is_err());
+    }
+
+    #[test]
+    fn test_parse_requires_header() {
+        let input = "{\"type\":\"goal\"}\n";
+        assert!(parse_export(input).is_err());
+    }
+
+    #[test]
+    fn test_format_and_strategy_from_str() {
+        assert_eq!(
+            "ndjson".parse::<ExportFormat>().unwrap(),
+            ExportFormat::Ndjson
+        );
+        assert_eq!(
+            "JSON-LD".parse::<ExportFormat>().unwrap(),
+            ExportFormat::JsonLd
+        );
+        assert!("xml".parse::<ExportFormat>().is_err());
+        assert_eq!(
+            "merge".parse::<ConflictStrategy>().unwrap(),
+            ConflictStrategy::Merge
+        );
+        assert!("overwrite".parse::<ConflictStrategy>().is_err());
+    }
+
+    #[test]
+    fn test_merge_metadata() {
+        let merged = merge_metadata(
+            Some(serde_json::json!({"a": 1, "b": 2})),
+            Some(serde_json::json!({"b": 3, "c": 4})),
+        )
+        .unwrap();
+        assert_eq!(merged, serde_json::json!({"a": 1, "b": 3, "c": 4}));
+        assert_eq!(
+            merge_metadata(Some(serde_json::json!({"a": 1})), None),
+            Some(serde_json::json!({"a": 1}))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_provenance_roundtrip_follows_remapped_ids() -> Result<()> {
+        use crate::temporal::{FactOptions, Provenance, SourceType};
+
+        let temp = tempfile::TempDir::new()?;
+        let src = new_graph(&temp, "src")?;
+        let alice = src.add_entity("Alice", "person", None).await?;
+        let acme = src.add_entity("Acme", "company", None).await?;
+        let email = Provenance::new(SourceType::Email, "<msg-1@example.com>");
+        src.add_provenance(FactType::Entity, &alice, &email).await?;
+        src.record_fact(
+            &alice,
+            &acme,
+            "works_at",
+            None,
+            FactOptions::for_relation("works_at").with_provenance(email.clone()),
+        )
+        .await?;
+
+        let exported = export_knowledge(&src.db(), ExportFormat::Ndjson).await?;
+        assert_eq!(exported.matches("\"type\":\"provenance\"").count(), 2);
+
+        // Destination already knows Alice under a different ID
+        let dst = new_graph(&temp, "dst")?;
+        let local_alice = dst.add_entity("Alice", "person", None).await?;
+        let report = import_knowledge(&dst, &exported, ConflictStrategy::Skip, None).await?;
+        assert_eq!(report.provenance_created, 2);
+
+        let context = dst.get_context_for(&local_alice).await?;
+        assert_eq!(context.relationships.len(), 1);
+        assert_eq!(context.provenance.len(), 2);
+        assert!(context.provenance.iter().all(|p| p.source == email));
+
+        // Re-importing doesn't duplicate provenance
+        let again = import_knowledge(&dst, &exported, ConflictStrategy::Skip, None).await?;
+        assert_eq!(again.provenance_created, 0);
+        assert_eq!(again.provenance_skipped, 2);
+        Ok(())
+    }
+}
@@ -0,0 +1,1389 @@
+//! Text extraction for rich document formats
+//!
+//! Turns PDF, DOCX, EPUB, HTML and email files into clean text before
+//! chunking. Headings are rendered as Markdown `#` lines and tables as
+//! Markdown tables, and every extractor records [`Location`]s (page number
+//! and heading path by byte offset) so chunks can cite where they came from.
+//!
+//! Formats are pluggable: implement [`Extractor`] and add it to an
+//! [`ExtractorRegistry`]. Files no extractor claims are read as UTF-8 text.
+
+use anyhow::{Context, Result, bail};
+use ego_tree::NodeRef;
+use scraper::{ElementRef, Html, Node, Selector};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::io::{Cursor, Read};
+use std::path::Path;
+use std::sync::Arc;
+use tracing::{debug, warn};
+
+use crate::chunking::detect_content_type;
+
+/// Largest archive member (DOCX/EPUB part) that will be decompressed
+const MAX_ENTRY_BYTES: u64 = 64 * 1024 * 1024;
+
+/// HTML elements that never contain main content
+const HTML_SKIP: &[&str] = &[
+    "script", "style", "noscript", "template", "svg", "canvas", "iframe", "nav", "header",
+    "footer", "aside", "form", "button", "select", "input", "textarea", "head",
+];
+
+/// HTML elements that start a new block of text
+const HTML_BLOCKS: &[&str] = &[
+    "p",
+    "div",
+    "section",
+    "article",
+    "main",
+    "ul",
+    "ol",
+    "dl",
+    "dt",
+    "dd",
+    "blockquote",
+    "figure",
+    "figcaption",
+    "address",
+    "details",
+    "summary",
+    "hr",
+    "body",
+    "html",
+];
+
+/// Where a position in the extracted text comes from
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Location {
+    /// Byte offset into [`ExtractedDocument::text`] where this location starts
+    pub offset: usize,
+    /// Page number (1-based), for paginated formats
+    pub page: Option<u32>,
+    /// Heading path, e.g. `3 Results > 3.1 Setup`
+    pub section: Option<String>,
+}
+
+/// Clean text and structure extracted from a document
+#[derive(Debug, Clone, Default)]
+pub struct ExtractedDocument {
+    /// Title from the document's own metadata, if any
+    pub title: Option<String>,
+    /// Extracted text with Markdown headings and tables
+    pub text: String,
+    /// MIME type of the source document
+    pub content_type: String,
+    /// Page and heading locations, sorted by offset
+    pub locations: Vec<Location>,
+    /// Extra format-specific metadata (author, sender, page count, ...)
+    pub metadata: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Extracts text from one document format
+pub trait Extractor: Send + Sync {
+    /// Short name used in logs
+    fn name(&self) -> &'static str;
+
+    /// Lowercase file extensions (without the dot) this extractor handles
+    fn extensions(&self) -> &'static [&'static str];
+
+    /// Extract text and structure from the raw file bytes
+    fn extract(&self, bytes: &[u8]) -> Result<ExtractedDocument>;
+}
+
+/// Chooses an [`Extractor`] for a file by its extension
+pub struct ExtractorRegistry {
+    extractors: Vec<Arc<dyn Extractor>>,
+    fallback: Arc<dyn Extractor>,
+}
+
+impl ExtractorRegistry {
+    /// Registry with no format extractors; everything is read as text
+    pub fn empty() -> Self {
+        Self {
+            extractors: Vec::new(),
+            fallback: Arc::new(TextExtractor),
+        }
+    }
+
+    /// Add an extractor; it takes precedence over earlier ones for the same extensions
+    pub fn register(&mut self, extractor: Arc<dyn Extractor>) {
+        self.extractors.insert(0, extractor);
+    }
+
+    /// The extractor that will handle `path`
+    pub fn find(&self, path: &Path) -> &dyn Extractor {
+        let ext = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_lowercase())
+            .unwrap_or_default();
+        self.extractors
+            .iter()
+            .find(|e| e.extensions().contains(&ext.as_str()))
+            .unwrap_or(&self.fallback)
+            .as_ref()
+    }
+
+    /// Extract a file that has already been read into memory
+    pub fn extract(&self, path: &Path, bytes: &[u8]) -> Result<ExtractedDocument> {
+        let extractor = self.find(path);
+        let mut doc = extractor.extract(bytes).with_context(|| {
+            format!(
+                "{} extraction failed for {}",
+                extractor.name(),
+                path.display()
+            )
+        })?;
+        if doc.content_type.is_empty() {
+            doc.content_type = detect_content_type(&path.to_string_lossy()).to_string();
+        }
+        debug!(
+            "Extracted {} chars from {} with {} extractor",
+            doc.text.len(),
+            path.display(),
+            extractor.name()
+        );
+        Ok(doc)
+    }
+}
+
+impl Default for ExtractorRegistry {
+    /// Registry with all built-in extractors
+    fn default() -> Self {
+        let mut registry = Self::empty();
+        registry.register(Arc::new(PdfExtractor));
+        registry.register(Arc::new(DocxExtractor));
+        registry.register(Arc::new(EpubExtractor));
+        registry.register(Arc::new(HtmlExtractor));
+        registry.register(Arc::new(EmlExtractor));
+        registry.register(Arc::new(MboxExtractor));
+        registry
+    }
+}
+
+// ── Text building ───────────────────────────────────────────────
+
+/// Accumulates blocks of text while tracking page and heading locations
+#[derive(Default)]
+struct TextBuilder {
+    text: String,
+    locations: Vec<Location>,
+    page: Option<u32>,
+    headings: Vec<(usize, String)>,
+}
+
+impl TextBuilder {
+    fn section(&self) -> Option<String> {
+        if self.headings.is_empty() {
+            None
+        } else {
+            Some(
+                self.headings
+                    .iter()
+                    .map(|(_, h)| h.as_str())
+                    .collect::<Vec<_>>()
+                    .join(" > "),
+            )
+        }
+    }
+
+    /// Record the current page and section at the end of the text
+    fn mark(&mut self) {
+        let location = Location {
+            offset: self.text.len(),
+            page: self.page,
+            section: self.section(),
+        };
+        match self.locations.last_mut() {
+            Some(last) if last.offset == location.offset => *last = location,
+            Some(last) if last.page == location.page && last.section == location.section => {}
+            _ => self.locations.push(location),
+        }
+    }
+
+    fn start_block(&mut self) {
+        if !self.text.is_empty() && !self.text.ends_with("\n\n") {
+            self.text.push_str("\n\n");
+        }
+    }
+
+    fn set_page(&mut self, page: u32) {
+        self.page = Some(page);
+        self.start_block();
+        self.mark();
+    }
+
+    /// Enter a section without writing a heading line (e.g. PDF bookmarks)
+    fn set_section(&mut self, level: usize, title: &str) {
+        let title = normalize_ws(title);
+        if title.is_empty() {
+            return;
+        }
+        self.headings.retain(|(l, _)| *l < level);
+        self.headings.push((level, title));
+        self.mark();
+    }
+
+    fn heading(&mut self, level: usize, title: &str) {
+        let title = normalize_ws(title);
+        if title.is_empty() {
+            return;
+        }
+        self.headings.retain(|(l, _)| *l < level);
+        self.headings.push((level, title.clone()));
+        self.start_block();
+        self.mark();
+        self.text.push_str(&"#".repeat(level.clamp(1, 6)));
+        self.text.push(' ');
+        self.text.push_str(&title);
+    }
+
+    fn paragraph(&mut self, text: &str) {
+        let text = text.trim();
+        if text.is_empty() {
+            return;
+        }
+        self.start_block();
+        self.text.push_str(text);
+    }
+
+    fn table(&mut self, rows: &[Vec<String>]) {
+        if let Some(table) = markdown_table(rows) {
+            self.paragraph(&table);
+        }
+    }
+
+    fn finish(mut self, title: Option<String>, content_type: &str) -> ExtractedDocument {
+        self.text.truncate(self.text.trim_end().len());
+        ExtractedDocument {
+            title: title.map(|t| normalize_ws(&t)).filter(|t| !t.is_empty()),
+            text: self.text,
+            content_type: content_type.to_string(),
+            locations: self.locations,
+            metadata: serde_json::Map::new(),
+        }
+    }
+}
+
+/// Collapse runs of whitespace into single spaces
+fn normalize_ws(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Render rows as a Markdown table, treating the first row as the header
+fn markdown_table(rows: &[Vec<String>]) -> Option<String> {
+    let rows: Vec<&Vec<String>> = rows
+        .iter()
+        .filter(|r| r.iter().any(|c| !c.trim().is_empty()))
+        .collect();
+    let columns = rows.iter().map(|r| r.len()).max()?;
+    if columns == 0 {
+        return None;
+    }
+
+    let render = |row: &Vec<String>| {
+        let cells: Vec<String> = (0..columns)
+            .map(|i| {
+                row.get(i)
+                    .map(|c| normalize_ws(c).replace('|', "\\|"))
+                    .unwrap_or_default()
+            })
+            .collect();
+        format!("| {} |", cells.join(" | "))
+    };
+
+    let mut lines = vec![render(rows[0])];
+    lines.push(format!("|{}", " --- |".repeat(columns)));
+    lines.extend(rows[1..].iter().map(|r| render(r)));
+    Some(lines.join("\n"))
+}
+
+/// Read one member of a zip archive as UTF-8
+fn read_zip_entry<R: Read + std::io::Seek>(
+    archive: &mut zip::ZipArchive<R>,
+    name: &str,
+) -> Result<String> {
+    let entry = archive
+        .by_name(name)
+        .with_context(|| format!("Archive has no {}", name))?;
+    let mut bytes = Vec::new();
+    entry
+        .take(MAX_ENTRY_BYTES)
+        .read_to_end(&mut bytes)
+        .with_context(|| format!("Failed to read {}", name))?;
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+// ── Plain text and Markdown ─────────────────────────────────────
+
+/// Reads UTF-8 text as-is, tracking Markdown `#` headings as sections
+pub struct TextExtractor;
+
+impl Extractor for TextExtractor {
+    fn name(&self) -> &'static str {
+        "text"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    fn extract(&self, bytes: &[u8]) -> Result<ExtractedDocument> {
+        let text = String::from_utf8_lossy(bytes);
+        let text = text.strip_prefix('\u{feff}').unwrap_or(&text).to_string();
+
+        // Offsets must match the original text, so only record locations
+        let mut headings: Vec<(usize, String)> = Vec::new();
+        let mut locations = Vec::new();
+        let mut title = None;
+        let mut in_fence = false;
+        let mut offset = 0;
+        for line in text.split_inclusive('\n') {
+            let trimmed = line.trim();
+            if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+                in_fence = !in_fence;
+            } else if !in_fence && let Some((level, heading)) = markdown_heading(trimmed) {
+                if level == 1 && title.is_none() {
+                    title = Some(heading.to_string());
+                }
+                headings.retain(|(l, _)| *l < level);
+                headings.push((level, heading.to_string()));
+                locations.push(Location {
+                    offset,
+                    page: None,
+                    section: Some(
+                        headings
+                            .iter()
+                            .map(|(_, h)| h.as_str())
+                            .collect::<Vec<_>>()
+                            .join(" > "),
+                    ),
+                });
+            }
+            offset += line.len();
+        }
+
+        Ok(ExtractedDocument {
+            title,
+            text,
+            content_type: String::new(),
+            locations,
+            metadata: serde_json::Map::new(),
+        })
+    }
+}
+
+/// Parse an ATX Markdown heading line into (level, text)
+pub(crate) fn markdown_heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.chars().take_while(|c| *c == '#').count();
+    if level == 0 || level > 6 {
+        return None;
+    }
+    let rest = &line[level..];
+    if !rest.starts_with(' ') {
+        return None;
+    }
+    let heading = rest.trim().trim_end_matches('#').trim();
+    (!heading.is_empty()).then_some((level, heading))
+}
+
+// ── PDF ─────────────────────────────────────────────────────────
+
+/// Extracts page text from PDFs, using bookmarks as sections
+pub struct PdfExtractor;
+
+impl Extractor for PdfExtractor {
+    fn name(&self) -> &'static str {
+        "pdf"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["pdf"]
+    }
+
+    fn extract(&self, bytes: &[u8]) -> Result<ExtractedDocument> {
+        let doc = lopdf::Document::load_mem(bytes).context("Failed to parse PDF")?;
+
+        // Bookmarks give the section structure PDFs otherwise lack
+        let mut outline: BTreeMap<u32, Vec<(usize, String)>> = BTreeMap::new();
+        if let Ok(toc) = doc.get_toc() {
+            for entry in toc.toc {
+                outline
+                    .entry(entry.page as u32)
+                    .or_default()
+                    .push((entry.level, entry.title));
+            }
+        }
+
+        let pages: Vec<u32> = doc.get_pages().keys().copied().collect();
+        let mut builder = TextBuilder::default();
+        for &number in &pages {
+            builder.set_page(number);
+            for (level, title) in outline.get(&number).into_iter().flatten() {
+                builder.set_section(*level, title);
+            }
+            match doc.extract_text(&[number]) {
+                Ok(text) => builder.paragraph(&clean_pdf_text(&text)),
+                Err(e) => warn!("Could not extract text from PDF page {}: {}", number, e),
+            }
+        }
+
+        let title = pdf_info(&doc, b"Title");
+        let mut extracted = builder.finish(title, "application/pdf");
+        extracted
+            .metadata
+            .insert("pages".to_string(), serde_json::json!(pages.len()));
+        if let Some(author) = pdf_info(&doc, b"Author") {
+            extracted
+                .metadata
+                .insert("author".to_string(), serde_json::json!(author));
+        }
+        Ok(extracted)
+    }
+}
+
+/// Look up a string in the PDF's document information dictionary
+fn pdf_info(doc: &lopdf::Document, key: &[u8]) -> Option<String> {
+    let info = match doc.trailer.get(b"Info").ok()? {
+        lopdf::Object::Reference(id) => doc.get_dictionary(*id).ok()?,
+        lopdf::Object::Dictionary(dict) => dict,
+        _ => return None,
+    };
+    let value = lopdf::decode_text_string(info.get(key).ok()?).ok()?;
+    let value = value.trim().to_string();
+    (!value.is_empty()).then_some(value)
+}
+
+/// Trim page text lines, re-join hyphenated words and drop blank runs
+fn clean_pdf_text(text: &str) -> String {
+    let mut out = String::new();
+    let mut blank = false;
+    for line in text.lines().map(str::trim) {
+        if line.is_empty() {
+            blank = !out.is_empty();
+            continue;
+        }
+        if blank {
+            out.push_str("\n\n");
+            blank = false;
+        } else if out.ends_with('-') && line.starts_with(char::is_lowercase) {
+            out.pop();
+        } else if !out.is_empty() {
+            out.push('\n');
+        }
+        out.push_str(line);
+    }
+    out
+}
+
+// ── DOCX ────────────────────────────────────────────────────────
+
+/// Extracts paragraphs, headings and tables from Word documents
+pub struct DocxExtractor;
+
+impl Extractor for DocxExtractor {
+    fn name(&self) -> &'static str {
+        "docx"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["docx"]
+    }
+
+    fn extract(&self, bytes: &[u8]) -> Result<ExtractedDocument> {
+        let mut archive =
+            zip::ZipArchive::new(Cursor::new(bytes)).context("DOCX is not a zip archive")?;
+        let xml = read_zip_entry(&mut archive, "word/document.xml")?;
+        let doc = roxmltree::Document::parse(&xml).context("Failed to parse word/document.xml")?;
+        let Some(body) = doc.descendants().find(|n| n.tag_name().name() == "body") else {
+            bail!("word/document.xml has no body");
+        };
+
+        let mut builder = TextBuilder::default();
+        // Word only records where it last rendered page breaks, so pages are approximate
+        builder.set_page(1);
+        docx_blocks(body, &mut builder);
+
+        let core = read_zip_entry(&mut archive, "docProps/core.xml").ok();
+        let core = core
+            .as_deref()
+            .and_then(|c| roxmltree::Document::parse(c).ok());
+        let core_value = |name: &str| {
+            core.as_ref().and_then(|c| {
+                c.descendants()
+                    .find(|n| n.tag_name().name() == name)
+                    .and_then(|n| n.text())
+                    .map(|t| t.trim().to_string())
+                    .filter(|t| !t.is_empty())
+            })
+        };
+
+        let mut extracted = builder.finish(
+            core_value("title"),
+            "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+        );
+        if let Some(author) = core_value("creator") {
+            extracted
+                .metadata
+                .insert("author".to_string(), serde_json::json!(author));
+        }
+        Ok(extracted)
+    }
+}
+
+/// Local-name attribute lookup, ignoring the `w:` namespace
+fn xml_attr<'a>(node: roxmltree::Node<'a, '_>, name: &str) -> Option<&'a str> {
+    node.attributes()
+        .find(|a| a.name() == name)
+        .map(|a| a.value())
+}
+
+fn xml_child<'a, 'input>(
+    node: roxmltree::Node<'a, 'input>,
+    name: &str,
+) -> Option<roxmltree::Node<'a, 'input>> {
+    node.children().find(|c| c.tag_name().name() == name)
+}
+
+fn docx_blocks(node: roxmltree::Node, builder: &mut TextBuilder) {
+    for child in node.children().filter(|c| c.is_element()) {
+        match child.tag_name().name() {
+            "p" => docx_paragraph(child, builder),
+            "tbl" => {
+                let rows: Vec<Vec<String>> = child
+                    .children()
+                    .filter(|r| r.tag_name().name() == "tr")
+                    .map(|row| {
+                        row.children()
+                            .filter(|c| c.tag_name().name() == "tc")
+                            .map(|cell| {
+                                cell.descendants()
+                                    .filter(|p| p.tag_name().name() == "p")
+                                    .map(|p| docx_text(p).join(" "))
+                                    .collect::<Vec<_>>()
+                                    .join(" ")
+                            })
+                            .collect()
+                    })
+                    .collect();
+                builder.table(&rows);
+            }
+            "sectPr" => {}
+            _ => docx_blocks(child, builder),
+        }
+    }
+}
+
+/// Heading level from a paragraph style such as `Heading2` or `Title`
+fn docx_heading_level(style: &str) -> Option<usize> {
+    if style.eq_ignore_ascii_case("title") {
+        return Some(1);
+    }
+    let lower = style.to_lowercase();
+    let rest = lower.strip_prefix("heading")?.trim();
+    rest.parse::<usize>().ok().filter(|l| (1..=9).contains(l))
+}
+
+fn docx_paragraph(p: roxmltree::Node, builder: &mut TextBuilder) {
+    let properties = xml_child(p, "pPr");
+    let style = properties
+        .and_then(|pr| xml_child(pr, "pStyle"))
+        .and_then(|s| xml_attr(s, "val"))
+        .unwrap_or_default();
+    let is_list = properties.and_then(|pr| xml_child(pr, "numPr")).is_some();
+
+    for (i, segment) in docx_text(p).into_iter().enumerate() {
+        if i > 0 {
+            let next = builder.page.unwrap_or(1) + 1;
+            builder.set_page(next);
+        }
+        match docx_heading_level(style) {
+            Some(level) => builder.heading(level, &segment),
+            None if is_list && !segment.trim().is_empty() => {
+                builder.paragraph(&format!("- {}", segment.trim()))
+            }
+            None => builder.paragraph(&segment),
+        }
+    }
+}
+
+/// Paragraph text, split at page breaks
+fn docx_text(p: roxmltree::Node) -> Vec<String> {
+    let mut segments = vec![String::new()];
+    for node in p.descendants().filter(|n| n.is_element()) {
+        let current = segments.last_mut().expect("segments is never empty");
+        match node.tag_name().name() {
+            "t" => current.push_str(node.text().unwrap_or_default()),
+            "tab" => current.push('\t'),
+            "br" if xml_attr(node, "type") == Some("page") => segments.push(String::new()),
+            "br" | "cr" => current.push('\n'),
+            "lastRenderedPageBreak" => segments.push(String::new()),
+            _ => {}
+        }
+    }
+    // A break at the very start of a paragraph still moves it to the next page
+    if segments.len() > 1 && segments[0].trim().is_empty() {
+        segments[0] = segments.remove(1);
+        segments.insert(0, String::new());
+    }
+    segments
+}
+
+// ── EPUB ────────────────────────────────────────────────────────
+
+/// Extracts the chapters of an EPUB book in reading order
+pub struct EpubExtractor;
+
+impl Extractor for EpubExtractor {
+    fn name(&self) -> &'static str {
+        "epub"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["epub"]
+    }
+
+    fn extract(&self, bytes: &[u8]) -> Result<ExtractedDocument> {
+        let mut archive =
+            zip::ZipArchive::new(Cursor::new(bytes)).context("EPUB is not a zip archive")?;
+        let container = read_zip_entry(&mut archive, "META-INF/container.xml")?;
+        let container =
+            roxmltree::Document::parse(&container).context("Failed to parse container.xml")?;
+        let opf_path = container
+            .descendants()
+            .find(|n| n.tag_name().name() == "rootfile")
+            .and_then(|n| xml_attr(n, "full-path"))
+            .context("container.xml has no rootfile")?
+            .to_string();
+
+        let opf = read_zip_entry(&mut archive, &opf_path)?;
+        let opf = roxmltree::Document::parse(&opf).context("Failed to parse package document")?;
+        let metadata_value = |name: &str| {
+            opf.descendants()
+                .find(|n| n.tag_name().name() == name)
+                .and_then(|n| n.text())
+                .map(|t| t.trim().to_string())
+                .filter(|t| !t.is_empty())
+        };
+
+        let manifest: HashMap<&str, (&str, &str)> = opf
+            .descendants()
+            .filter(|n| n.tag_name().name() == "item")
+            .filter_map(|n| {
+                Some((
+                    xml_attr(n, "id")?,
+                    (
+                        xml_attr(n, "href")?,
+                        xml_attr(n, "media-type").unwrap_or_default(),
+                    ),
+                ))
+            })
+            .collect();
+        let base = opf_path.rsplit_once('/').map(|(dir, _)| dir).unwrap_or("");
+
+        let mut builder = TextBuilder::default();
+        let mut chapters = 0;
+        for idref in opf
+            .descendants()
+            .filter(|n| n.tag_name().name() == "itemref")
+            .filter_map(|n| xml_attr(n, "idref"))
+        {
+            let Some((href, media_type)) = manifest.get(idref) else {
+                continue;
+            };
+            if !media_type.contains("html") {
+                continue;
+            }
+            let path = resolve_zip_path(base, href);
+            match read_zip_entry(&mut archive, &path) {
+                Ok(xhtml) => {
+                    let html = Html::parse_document(&xhtml);
+                    HtmlWriter::new(&mut builder).write(*html.root_element());
+                    chapters += 1;
+                }
+                Err(e) => warn!("Skipping EPUB chapter {}: {}", path, e),
+            }
+        }
+
+        let mut extracted = builder.finish(metadata_value("title"), "application/epub+zip");
+        extracted
+            .metadata
+            .insert("chapters".to_string(), serde_json::json!(chapters));
+        if let Some(author) = metadata_value("creator") {
+            extracted
+                .metadata
+                .insert("author".to_string(), serde_json::json!(author));
+        }
+        Ok(extracted)
+    }
+}
+
+/// Resolve a manifest href relative to the package document's directory
+fn resolve_zip_path(base: &str, href: &str) -> String {
+    let href = href.split('#').next().unwrap_or(href).replace("%20", " ");
+    let mut parts: Vec<&str> = base.split('/').filter(|p| !p.is_empty()).collect();
+    for part in href.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop();
+            }
+            p => parts.push(p),
+        }
+    }
+    parts.join("/")
+}
+
+// ── HTML ────────────────────────────────────────────────────────
+
+/// Extracts the main content of web pages, dropping navigation and boilerplate
+pub struct HtmlExtractor;
+
+impl Extractor for HtmlExtractor {
+    fn name(&self) -> &'static str {
+        "html"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["html", "htm", "xhtml"]
+    }
+
+    fn extract(&self, bytes: &[u8]) -> Result<ExtractedDocument> {
+        let html = Html::parse_document(&String::from_utf8_lossy(bytes));
+        let title = select_text(&html, "title").or_else(|| select_text(&html, "h1"));
+
+        let mut builder = TextBuilder::default();
+        HtmlWriter::new(&mut builder).write(*main_content(&html));
+        Ok(builder.finish(title, "text/html"))
+    }
+}
+
+fn select_text(html: &Html, selector: &str) -> Option<String> {
+    let selector = Selector::parse(selector).ok()?;
+    html.select(&selector)
+        .map(|e| normalize_ws(&e.text().collect::<String>()))
+        .find(|t| !t.is_empty())
+}
+
+/// Pick the element holding the page's main content, readability style:
+/// an explicit `<article>`/`<main>` if present, otherwise the element whose
+/// paragraphs carry the most text with the fewest links.
+fn main_content(html: &Html) -> ElementRef<'_> {
+    for selector in ["article", "main", "[role=main]"] {
+        let selector = Selector::parse(selector).expect("static selector");
+        if let Some(best) = html
+            .select(&selector)
+            .max_by_key(|e| e.text().map(str::len).sum::<usize>())
+        {
+            return best;
+        }
+    }
+
+    let paragraphs = Selector::parse("p, pre").expect("static selector");
+    let mut scores: HashMap<ego_tree::NodeId, f64> = HashMap::new();
+    for p in html.select(&paragraphs) {
+        let text = normalize_ws(&p.text().collect::<String>());
+        if text.len() < 25 {
+            continue;
+        }
+        let score = 1.0 + text.matches(',').count() as f64 + (text.len() as f64 / 100.0).min(3.0);
+        if let Some(parent) = p.parent() {
+            *scores.entry(parent.id()).or_default() += score;
+            if let Some(grandparent) = parent.parent() {
+                *scores.entry(grandparent.id()).or_default() += score / 2.0;
+            }
+        }
+    }
+
+    scores
+        .into_iter()
+        .filter_map(|(id, score)| {
+            let element = ElementRef::wrap(html.tree.get(id)?)?;
+            Some((element, score * (1.0 - link_density(element))))
+        })
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(element, _)| element)
+        .or_else(|| {
+            let body = Selector::parse("body").expect("static selector");
+            html.select(&body).next()
+        })
+        .unwrap_or_else(|| html.root_element())
+}
+
+/// Share of an element's text that sits inside links
+fn link_density(element: ElementRef) -> f64 {
+    let total: usize = element.text().map(str::len).sum();
+    if total == 0 {
+        return 1.0;
+    }
+    let links = Selector::parse("a").expect("static selector");
+    let linked: usize = element
+        .select(&links)
+        .flat_map(|a| a.text())
+        .map(str::len)
+        .sum();
+    linked as f64 / total as f64
+}
+
+/// Text of a node, skipping scripts and styles
+fn node_text(node: NodeRef<Node>) -> String {
+    let mut out = String::new();
+    for child in node.children() {
+        match child.value() {
+            Node::Text(t) => out.push_str(t),
+            Node::Element(e) if HTML_SKIP.contains(&e.name()) => {}
+            Node::Element(e) if e.name() == "br" => out.push(' '),
+            Node::Element(_) => out.push_str(&node_text(child)),
+            _ => {}
+        }
+    }
+    out
+}
+
+/// Walks an HTML tree writing Markdown-ish blocks into a [`TextBuilder`]
+struct HtmlWriter<'b> {
+    builder: &'b mut TextBuilder,
+    inline: String,
+    prefix: &'static str,
+}
+
+impl<'b> HtmlWriter<'b> {
+    fn new(builder: &'b mut TextBuilder) -> Self {
+        Self {
+            builder,
+            inline: String::new(),
+            prefix: "",
+        }
+    }
+
+    fn write(mut self, node: NodeRef<Node>) {
+        self.walk(node);
+        self.flush();
+    }
+
+    fn flush(&mut self) {
+        let text = self
+            .inline
+            .split('\n')
+            .map(normalize_ws)
+            .filter(|l| !l.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n");
+        if !text.is_empty() {
+            self.builder.paragraph(&format!("{}{}", self.prefix, text));
+        }
+        self.inline.clear();
+        self.prefix = "";
+    }
+
+    fn walk(&mut self, node: NodeRef<Node>) {
+        for child in node.children() {
+            match child.value() {
+                Node::Text(t) => self.inline.push_str(t),
+                Node::Element(e) => {
+                    let name = e.name();
+                    if HTML_SKIP.contains(&name)
+                        || e.attr("hidden").is_some()
+                        || e.attr("aria-hidden") == Some("true")
+                    {
+                        continue;
+                    }
+                    match name {
+                        "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                            self.flush();
+                            let level = name[1..].parse().unwrap_or(1);
+                            self.builder.heading(level, &node_text(child));
+                        }
+                        "table" => {
+                            self.flush();
+                            match html_table(child) {
+                                Some(rows) => self.builder.table(&rows),
+                                // Layout tables are walked like any other container
+                                None => {
+                                    self.walk(child);
+                                    self.flush();
+                                }
+                            }
+                        }
+                        "pre" => {
+                            self.flush();
+                            let code = node_text(child);
+                            let code = code.trim_matches('\n');
+                            if !code.trim().is_empty() {
+                                self.builder.paragraph(&format!("```\n{}\n```", code));
+                            }
+                        }
+                        "li" => {
+                            self.flush();
+                            self.prefix = "- ";
+                            self.walk(child);
+                            self.flush();
+                        }
+                        "br" => self.inline.push('\n'),
+                        "img" => {}
+                        _ if HTML_BLOCKS.contains(&name) => {
+                            self.flush();
+                            self.walk(child);
+                            self.flush();
+                        }
+                        _ => self.walk(child),
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Rows of a data table, or `None` for tables that only do page layout
+fn html_table(table: NodeRef<Node>) -> Option<Vec<Vec<String>>> {
+    let element = ElementRef::wrap(table)?;
+    let row_selector = Selector::parse("tr").expect("static selector");
+    let mut rows = Vec::new();
+    for row in element.select(&row_selector) {
+        // Rows of nested tables belong to the inner table
+        let owner = row
+            .ancestors()
+            .find(|a| a.value().as_element().is_some_and(|e| e.name() == "table"));
+        if owner.map(|o| o.id()) != Some(table.id()) {
+            continue;
+        }
+        let cells: Vec<String> = row
+            .children()
+            .filter_map(ElementRef::wrap)
+            .filter(|c| matches!(c.value().name(), "td" | "th"))
+            .map(|c| normalize_ws(&node_text(*c)))
+            .collect();
+        if cells.iter().any(|c| c.len() > 300) {
+            return None;
+        }
+        rows.push(cells);
+    }
+    let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+    (columns > 1).then_some(rows)
+}
+
+// ── Email ───────────────────────────────────────────────────────
+
+/// Extracts headers and body from a single `.eml` message
+pub struct EmlExtractor;
+
+impl Extractor for EmlExtractor {
+    fn name(&self) -> &'static str {
+        "eml"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["eml"]
+    }
+
+    fn extract(&self, bytes: &[u8]) -> Result<ExtractedDocument> {
+        let message = mail_parser::MessageParser::default()
+            .parse(bytes)
+            .context("Failed to parse email message")?;
+        let mut builder = TextBuilder::default();
+        let headers = write_message(&message, &mut builder);
+        let mut extracted = builder.finish(message.subject().map(str::to_string), "message/rfc822");
+        extracted.metadata = headers;
+        Ok(extracted)
+    }
+}
+
+/// Extracts every message of an mbox archive, one section per message
+pub struct MboxExtractor;
+
+impl Extractor for MboxExtractor {
+    fn name(&self) -> &'static str {
+        "mbox"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["mbox"]
+    }
+
+    fn extract(&self, bytes: &[u8]) -> Result<ExtractedDocument> {
+        let parser = mail_parser::MessageParser::default();
+        let mut builder = TextBuilder::default();
+        let mut count = 0;
+        for raw in split_mbox(bytes) {
+            let Some(message) = parser.parse(&raw) else {
+                warn!("Skipping unparseable message in mbox");
+                continue;
+            };
+            builder.heading(1, message.subject().unwrap_or("(no subject)"));
+            write_message(&message, &mut builder);
+            count += 1;
+        }
+
+        let mut extracted = builder.finish(None, "application/mbox");
+        extracted
+            .metadata
+            .insert("messages".to_string(), serde_json::json!(count));
+        Ok(extracted)
+    }
+}
+
+/// Split an mbox file on `From ` separator lines, undoing `>From ` quoting
+fn split_mbox(bytes: &[u8]) -> Vec<Vec<u8>> {
+    let mut messages = Vec::new();
+    let mut current: Option<Vec<u8>> = None;
+    let mut previous_blank = true;
+    for line in bytes.split_inclusive(|b| *b == b'\n') {
+        if previous_blank && line.starts_with(b"From ") {
+            if let Some(message) = current.take() {
+                messages.push(message);
+            }
+            current = Some(Vec::new());
+            previous_blank = false;
+            continue;
+        }
+        previous_blank = line.iter().all(|b| b.is_ascii_whitespace());
+        if let Some(message) = current.as_mut() {
+            let quoted = line.iter().position(|b| *b != b'>').unwrap_or(0);
+            if quoted > 0 && line[quoted..].starts_with(b"From ") {
+                message.extend_from_slice(&line[1..]);
+            } else {
+                message.extend_from_slice(line);
+            }
+        }
+    }
+    messages.extend(current);
+    messages
+}
+
+fn format_addresses(address: Option<&mail_parser::Address>) -> Option<String> {
+    let list: Vec<String> = address?
+        .iter()
+        .filter_map(|a| match (a.name(), a.address()) {
+            (Some(name), Some(addr)) => Some(format!("{} <{}>", name, addr)),
+            (None, Some(addr)) => Some(addr.to_string()),
+            (Some(name), None) => Some(name.to_string()),
+            (None, None) => None,
+        })
+        .collect();
+    (!list.is_empty()).then(|| list.join(", "))
+}
+
+/// Write a message's headers and body, returning the headers as metadata
+fn write_message(
+    message: &mail_parser::Message,
+    builder: &mut TextBuilder,
+) -> serde_json::Map<String, serde_json::Value> {
+    use mail_parser::MimeHeaders;
+
+    let mut headers = serde_json::Map::new();
+    let fields = [
+        ("from", format_addresses(message.from())),
+        ("to", format_addresses(message.to())),
+        ("date", message.date().map(|d| d.to_rfc3339())),
+        ("subject", message.subject().map(str::to_string)),
+    ];
+    let mut lines = Vec::new();
+    for (key, value) in fields {
+        if let Some(value) = value {
+            let mut label = key.to_string();
+            label[..1].make_ascii_uppercase();
+            lines.push(format!("{}: {}", label, value));
+            headers.insert(key.to_string(), serde_json::json!(value));
+        }
+    }
+    builder.paragraph(&lines.join("\n"));
+
+    match message.text_part(0) {
+        // HTML-only mail goes through the same cleanup as web pages
+        Some(part) if part.is_text_html() => {
+            let html = Html::parse_document(part.text_contents().unwrap_or_default());
+            HtmlWriter::new(builder).write(*html.root_element());
+        }
+        _ => {
+            if let Some(body) = message.body_text(0) {
+                builder.paragraph(&body);
+            }
+        }
+    }
+
+    let attachments: Vec<String> = message
+        .attachments()
+        .filter_map(|a| a.attachment_name().map(str::to_string))
+        .collect();
+    if !attachments.is_empty() {
+        builder.paragraph(&format!("Attachments: {}", attachments.join(", ")));
+        headers.insert("attachments".to_string(), serde_json::json!(attachments));
+    }
+    headers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn zip_archive(files: &[(&str, &str)]) -> Vec<u8> {
+        let mut buffer = Cursor::new(Vec::new());
+        let mut writer = zip::ZipWriter::new(&mut buffer);
+        for (name, content) in files {
+            writer
+                .start_file(*name, zip::write::SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+        writer.finish().unwrap();
+        buffer.into_inner()
+    }
+
+    fn location_at<'a>(doc: &'a ExtractedDocument, needle: &str) -> &'a Location {
+        let offset = doc.text.find(needle).expect("needle in text");
+        let idx = doc.locations.partition_point(|l| l.offset <= offset);
+        &doc.locations[idx - 1]
+    }
+
+    #[test]
+    fn test_html_main_content_and_tables() {
+        let html = r#"<html><head><title>Release notes</title><style>p{}</style></head>
+            <body>
+              <nav><a href="/">Home</a> <a href="/blog">Blog</a></nav>
+              <div class="sidebar"><p><a href="/x">A very long link list entry for the sidebar</a></p></div>
+              <div class="content">
+                <h1>Version 2.0</h1>
+                <p>This release rewrites the storage layer, adds encryption, and speeds up search.</p>
+                <h2>Benchmarks</h2>
+                <table><tr><th>Query</th><th>Time</th></tr><tr><td>fuzzy | exact</td><td>3 ms</td></tr></table>
+                <ul><li>Faster startup</li><li>Smaller index</li></ul>
+                <script>track()</script>
+              </div>
+              <footer>Copyright</footer>
+            </body></html>"#;
+        let doc = HtmlExtractor.extract(html.as_bytes()).unwrap();
+
+        assert_eq!(doc.title.as_deref(), Some("Release notes"));
+        assert!(doc.text.starts_with("# Version 2.0"));
+        assert!(
+            doc.text
+                .contains("| Query | Time |\n| --- | --- |\n| fuzzy \\| exact | 3 ms |")
+        );
+        assert!(doc.text.contains("- Faster startup\n\n- Smaller index"));
+        for boilerplate in ["Home", "sidebar", "Copyright", "track()"] {
+            assert!(!doc.text.contains(boilerplate), "kept {}", boilerplate);
+        }
+        assert_eq!(
+            location_at(&doc, "3 ms").section.as_deref(),
+            Some("Version 2.0 > Benchmarks")
+        );
+    }
+
+    #[test]
+    fn test_docx_headings_tables_and_pages() {
+        let document = r#"<?xml version="1.0"?>
+            <w:document xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main">
+              <w:body>
+                <w:p><w:pPr><w:pStyle w:val="Heading1"/></w:pPr><w:r><w:t>Introduction</w:t></w:r></w:p>
+                <w:p><w:r><w:t xml:space="preserve">Hello </w:t></w:r><w:r><w:t>world.</w:t></w:r></w:p>
+                <w:tbl>
+                  <w:tr><w:tc><w:p><w:r><w:t>Name</w:t></w:r></w:p></w:tc><w:tc><w:p><w:r><w:t>Role</w:t></w:r></w:p></w:tc></w:tr>
+                  <w:tr><w:tc><w:p><w:r><w:t>Alice</w:t></w:r></w:p></w:tc><w:tc><w:p><w:r><w:t>Lead</w:t></w:r></w:p></w:tc></w:tr>
+                </w:tbl>
+                <w:p><w:r><w:br w:type="page"/><w:t>Second page.</w:t></w:r></w:p>
+                <w:p><w:pPr><w:pStyle w:val="Heading2"/></w:pPr><w:r><w:t>Details</w:t></w:r></w:p>
+                <w:p><w:r><w:t>More text.</w:t></w:r></w:p>
+              </w:body>
+            </w:document>"#;
+        let core = r#"<cp:coreProperties xmlns:cp="http://schemas.openxmlformats.org/package/2006/metadata/core-properties" xmlns:dc="http://purl.org/dc/elements/1.1/"><dc:title>Handbook</dc:title><dc:creator>Alice</dc:creator></cp:coreProperties>"#;
+        let bytes = zip_archive(&[("word/document.xml", document), ("docProps/core.xml", core)]);
+        let doc = DocxExtractor.extract(&bytes).unwrap();
+
+        assert_eq!(doc.title.as_deref(), Some("Handbook"));
+        assert_eq!(doc.metadata["author"], "Alice");
+        assert!(doc.text.starts_with("# Introduction\n\nHello world."));
+        assert!(
+            doc.text
+                .contains("| Name | Role |\n| --- | --- |\n| Alice | Lead |")
+        );
+        assert!(doc.text.contains("## Details\n\nMore text."));
+
+        let first = location_at(&doc, "Hello");
+        assert_eq!(first.page, Some(1));
+        assert_eq!(first.section.as_deref(), Some("Introduction"));
+        let second = location_at(&doc, "More text");
+        assert_eq!(second.page, Some(2));
+        assert_eq!(second.section.as_deref(), Some("Introduction > Details"));
+    }
+
+    #[test]
+    fn test_epub_spine_order() {
+        let container = r#"<container xmlns="urn:oasis:names:tc:opendocument:xmlns:container"><rootfiles><rootfile full-path="OEBPS/content.opf" media-type="application/oebps-package+xml"/></rootfiles></container>"#;
+        let opf = r#"<package xmlns="http://www.idpf.org/2007/opf" xmlns:dc="http://purl.org/dc/elements/1.1/">
+            <metadata><dc:title>Field Guide</dc:title><dc:creator>Bob</dc:creator></metadata>
+            <manifest>
+              <item id="c1" href="text/one.xhtml" media-type="application/xhtml+xml"/>
+              <item id="c2" href="text/two.xhtml" media-type="application/xhtml+xml"/>
+              <item id="css" href="style.css" media-type="text/css"/>
+            </manifest>
+            <spine><itemref idref="c2"/><itemref idref="css"/><itemref idref="c1"/></spine>
+          </package>"#;
+        let bytes = zip_archive(&[
+            ("META-INF/container.xml", container),
+            ("OEBPS/content.opf", opf),
+            (
+                "OEBPS/text/one.xhtml",
+                "<html><body><h1>Birds</h1><p>Robins sing.</p></body></html>",
+            ),
+            (
+                "OEBPS/text/two.xhtml",
+                "<html><body><h1>Trees</h1><p>Oaks grow.</p></body></html>",
+            ),
+        ]);
+        let doc = EpubExtractor.extract(&bytes).unwrap();
+
+        assert_eq!(doc.title.as_deref(), Some("Field Guide"));
+        assert_eq!(doc.metadata["chapters"], 2);
+        assert_eq!(doc.text, "# Trees\n\nOaks grow.\n\n# Birds\n\nRobins sing.");
+        assert_eq!(
+            location_at(&doc, "Robins").section.as_deref(),
+            Some("Birds")
+        );
+        assert_eq!(
+            resolve_zip_path("OEBPS/text", "../img/a%20b.png#x"),
+            "OEBPS/img/a b.png"
+        );
+    }
+
+    #[test]
+    fn test_email_and_mbox() {
+        let eml = "From: Alice <alice@example.com>\r\nTo: bob@example.com\r\n\
+                   Subject: Lunch plans\r\nDate: Mon, 2 Mar 2026 10:00:00 +0000\r\n\r\n\
+                   Shall we meet at noon?\r\n";
+        let doc = EmlExtractor.extract(eml.as_bytes()).unwrap();
+        assert_eq!(doc.title.as_deref(), Some("Lunch plans"));
+        assert_eq!(doc.metadata["from"], "Alice <alice@example.com>");
+        assert!(
+            doc.text
+                .contains("From: Alice <alice@example.com>\nTo: bob@example.com")
+        );
+        assert!(doc.text.contains("Shall we meet at noon?"));
+
+        let html_mail = "From: news@example.com\r\nSubject: Digest\r\n\
+                         Content-Type: text/html\r\n\r\n\
+                         <html><body><h2>Top story</h2><p>Rust ships.</p><script>x()</script></body></html>";
+        let doc = EmlExtractor.extract(html_mail.as_bytes()).unwrap();
+        assert!(doc.text.contains("## Top story\n\nRust ships."));
+        assert!(!doc.text.contains("x()"));
+
+        let mbox = "From alice@example.com Mon Mar  2 10:00:00 2026\n\
+                    From: alice@example.com\nSubject: First\n\n>From the top.\n\n\
+                    From bob@example.com Mon Mar  2 11:00:00 2026\n\
+                    From: bob@example.com\nSubject: Second\n\nReply body.\n";
+        assert_eq!(split_mbox(mbox.as_bytes()).len(), 2);
+        let doc = MboxExtractor.extract(mbox.as_bytes()).unwrap();
+        assert_eq!(doc.metadata["messages"], 2);
+        assert!(doc.text.contains("From the top."));
+        assert_eq!(
+            location_at(&doc, "Reply body").section.as_deref(),
+            Some("Second")
+        );
+    }
+
+    #[test]
+    fn test_pdf_pages() {
+        use lopdf::content::{Content, Operation};
+        use lopdf::{Object, Stream, dictionary};
+
+        let mut doc = lopdf::Document::with_version("1.5");
+        let pages_id = doc.new_object_id();
+        let font_id = doc.add_object(dictionary! {
+            "Type" => "Font",
+            "Subtype" => "Type1",
+            "BaseFont" => "Courier",
+        });
+        let resources_id = doc.add_object(dictionary! {
+            "Font" => dictionary! { "F1" => font_id },
+        });
+        let mut kids = Vec::new();
+        for text in ["First page text", "Second page text"] {
+            let content = Content {
+                operations: vec![
+                    Operation::new("BT", vec![]),
+                    Operation::new("Tf", vec!["F1".into(), 12.into()]),
+                    Operation::new("Td", vec![72.into(), 700.into()]),
+                    Operation::new("Tj", vec![Object::string_literal(text)]),
+                    Operation::new("ET", vec![]),
+                ],
+            };
+            let content_id = doc.add_object(Stream::new(dictionary! {}, content.encode().unwrap()));
+            kids.push(
+                doc.add_object(dictionary! {
+                    "Type" => "Page",
+                    "Parent" => pages_id,
+                    "Contents" => content_id,
+                })
+                .into(),
+            );
+        }
+        doc.objects.insert(
+            pages_id,
+            Object::Dictionary(dictionary! {
+                "Type" => "Pages",
+                "Kids" => kids,
+                "Count" => 2,
+                "Resources" => resources_id,
+                "MediaBox" => vec![0.into(), 0.into(), 595.into(), 842.into()],
+            }),
+        );
+        let catalog_id = doc.add_object(dictionary! { "Type" => "Catalog", "Pages" => pages_id });
+        let info_id =
+            doc.add_object(dictionary! { "Title" => Object::string_literal("Quarterly report") });
+        doc.trailer.set("Root", catalog_id);
+        doc.trailer.set("Info", info_id);
+        let mut bytes = Vec::new();
+        doc.save_to(&mut bytes).unwrap();
+
+        let extracted = PdfExtractor.extract(&bytes).unwrap();
+        assert_eq!(extracted.title.as_deref(), Some("Quarterly report"));
+        assert_eq!(extracted.metadata["pages"], 2);
+        assert_eq!(location_at(&extracted, "First page").page, Some(1));
+        assert_eq!(location_at(&extracted, "Second page").page, Some(2));
+    }
+
+    #[test]
+    fn test_registry_dispatch_and_markdown_fallback() {
+        let registry = ExtractorRegistry::default();
+        assert_eq!(registry.find(Path::new("a/Report.PDF")).name(), "pdf");
+        assert_eq!(registry.find(Path::new("notes.md")).name(), "text");
+
+        let markdown = "# Guide\n\nIntro.\n\n```\n# not a heading\n```\n\n## Install\n\nRun it.\n";
+        let doc = registry
+            .extract(Path::new("guide.md"), markdown.as_bytes())
+            .unwrap();
+        assert_eq!(doc.text, markdown);
+        assert_eq!(doc.title.as_deref(), Some("Guide"));
+        assert_eq!(doc.content_type, "text/markdown");
+        assert_eq!(
+            location_at(&doc, "Run it").section.as_deref(),
+            Some("Guide > Install")
+        );
+        assert_eq!(
+            location_at(&doc, "not a heading").section.as_deref(),
+            Some("Guide")
+        );
+
+        struct Upper;
+        impl Extractor for Upper {
+            fn name(&self) -> &'static str {
+                "upper"
+            }
+            fn extensions(&self) -> &'static [&'static str] {
+                &["md"]
+            }
+            fn extract(&self, bytes: &[u8]) -> Result<ExtractedDocument> {
+                Ok(ExtractedDocument {
+                    text: String::from_utf8_lossy(bytes).to_uppercase(),
+                    ..Default::default()
+                })
+            }
+        }
+        let mut registry = ExtractorRegistry::empty();
+        registry.register(Arc::new(Upper));
+        let doc = registry.extract(Path::new("x.md"), b"hi").unwrap();
+        assert_eq!(doc.text, "HI");
+        assert_eq!(doc.content_type, "text/markdown");
+    }
+}
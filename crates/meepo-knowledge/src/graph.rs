@@ -1,14 +1,46 @@
 //! Knowledge graph operations combining SQLite and Tantivy
 
 use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
+use std::collections::HashMap;
 use std::path::Path;
 use std::sync::Arc;
-use tracing::{debug, info};
-
+use tracing::{debug, info, warn};
+
+use crate::chunking::{ChunkStrategy, DocumentMetadata, annotate_chunks, chunk_with_strategy};
+use crate::communities::{
+    self, COMMUNITY_TYPE, CommunityConfig, CommunityReport, CommunitySummarizer, RefreshAction,
+    StoredCommunity,
+};
+use crate::corpus::{self, Corpus, CorpusFile, CorpusSyncReport, MAX_FILE_BYTES};
+use crate::embeddings::{EmbeddingProvider, VectorIndex};
+use crate::episodic::{
+    self, CandidateStatus, Episode, ExtractionConfig, ExtractionReport, MemoryCandidate,
+    MemoryExtractor, MemoryProposal,
+};
+use crate::graph_query::{
+    self, EdgeFilter, GraphPath, Neighborhood, Pattern, PatternResults, QueryLimits,
+};
+use crate::importance::{
+    self, ArchivedMemory, ImportanceConfig, RetentionAction, RetentionPolicy, RetentionReport,
+};
+use crate::ingest::{CHUNK_TYPE, DOCUMENT_TYPE, IngestOptions, IngestPipeline, IngestedDocument};
+use crate::memory_sync::{
+    self, ConflictPolicy, FileChange, MemoryConflict, MemoryDocument, MemoryEntry,
+    MemorySyncConfig, MemorySyncReport,
+};
+use crate::outbox::{self, ConsistencyReport};
+use crate::resolution::{
+    self, FailedMerge, MatchCandidate, MatchReason, MergeConfirmer, MergeRecord, ResolutionConfig,
+    ResolutionReport,
+};
 use crate::sqlite::{Entity, KnowledgeDb, Relationship};
-use crate::tantivy::{SearchResult, TantivyIndex};
+use crate::tantivy::{SearchLanguage, SearchOptions, SearchResult, TantivyIndex};
+use crate::temporal::{
+    FactOptions, FactType, Provenance, ProvenanceRecord, RecordedFact, SourceType,
+};
 
 /// Context for an entity including relationships and conversations
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,17 +49,37 @@ pub struct EntityContext {
     pub related_entities: Vec<Entity>,
     pub relationships: Vec<Relationship>,
     pub recent_conversations: Vec<crate::sqlite::Conversation>,
+    /// Sources for the entity and its relationships
+    #[serde(default)]
+    pub provenance: Vec<ProvenanceRecord>,
 }
 
 /// Knowledge graph combining SQLite and Tantivy
 pub struct KnowledgeGraph {
     db: Arc<KnowledgeDb>,
     index: TantivyIndex,
+    resolution: ResolutionConfig,
+    importance: ImportanceConfig,
+    communities: CommunityConfig,
+    vectors: Option<(Arc<VectorIndex>, Arc<dyn EmbeddingProvider>)>,
 }
 
 impl KnowledgeGraph {
-    /// Create a new knowledge graph
+    /// Create a new knowledge graph with an English search index
     pub fn new<P: AsRef<Path>, Q: AsRef<Path>>(db_path: P, index_path: Q) -> Result<Self> {
+        Self::open(db_path, index_path, SearchLanguage::English)
+    }
+
+    /// Create a new knowledge graph whose search index stems for `language`.
+    ///
+    /// If the on-disk index was missing or written with an older schema it
+    /// is rebuilt from the database; otherwise any entity changes that never
+    /// reached it (e.g. after a crash) are applied from the outbox.
+    pub fn open<P: AsRef<Path>, Q: AsRef<Path>>(
+        db_path: P,
+        index_path: Q,
+        language: SearchLanguage,
+    ) -> Result<Self> {
         info!(
             "Initializing knowledge graph with db at {:?} and index at {:?}",
             db_path.as_ref(),
@@ -35,12 +87,102 @@ impl KnowledgeGraph {
         );
 
         let db = Arc::new(KnowledgeDb::new(db_path)?);
-        let index = TantivyIndex::new(index_path)?;
+        let index = TantivyIndex::open(index_path, language)?;
+        let graph = Self {
+            db,
+            index,
+            resolution: ResolutionConfig::default(),
+            importance: ImportanceConfig::default(),
+            communities: CommunityConfig::default(),
+            vectors: None,
+        };
+
+        if graph.index.needs_reindex() {
+            graph.rebuild_index()?;
+        } else {
+            let recovered = graph.sync_index()?;
+            if recovered > 0 {
+                info!("Recovered {} unindexed entity change(s)", recovered);
+            }
+        }
+        Ok(graph)
+    }
+
+    /// Open a knowledge graph backed by an encrypted database.
+    ///
+    /// The search index is kept in memory and rebuilt from the database, so
+    /// no plaintext copy of entity text is written to disk.
+    pub async fn new_encrypted<P: AsRef<Path>>(db_path: P, key: &str) -> Result<Self> {
+        Self::open_encrypted(db_path, key, SearchLanguage::English).await
+    }
+
+    /// Open a knowledge graph backed by an encrypted database, with a search
+    /// index that stems for `language`
+    pub async fn open_encrypted<P: AsRef<Path>>(
+        db_path: P,
+        key: &str,
+        language: SearchLanguage,
+    ) -> Result<Self> {
+        info!(
+            "Initializing encrypted knowledge graph with db at {:?}",
+            db_path.as_ref()
+        );
+
+        let db = Arc::new(KnowledgeDb::new_encrypted(db_path, key)?);
+        let index = TantivyIndex::in_memory(language)?;
+        let graph = Self {
+            db,
+            index,
+            resolution: ResolutionConfig::default(),
+            importance: ImportanceConfig::default(),
+            communities: CommunityConfig::default(),
+            vectors: None,
+        };
+        graph.rebuild_index()?;
+        Ok(graph)
+    }
+
+    /// Set the entity resolution configuration
+    pub fn with_resolution(mut self, config: ResolutionConfig) -> Self {
+        self.resolution = config;
+        self
+    }
 
-        Ok(Self { db, index })
+    /// Set the importance scoring configuration
+    pub fn with_importance(mut self, config: ImportanceConfig) -> Self {
+        self.importance = config;
+        self
     }
 
-    /// Add an entity to the knowledge graph
+    /// Set the community detection configuration
+    pub fn with_communities(mut self, config: CommunityConfig) -> Self {
+        self.communities = config;
+        self
+    }
+
+    /// Attach a vector index and the provider that fills it, used for
+    /// embedding-based duplicate detection and rebuilt on import
+    pub fn with_vectors(
+        mut self,
+        index: Arc<VectorIndex>,
+        provider: Arc<dyn EmbeddingProvider>,
+    ) -> Self {
+        self.vectors = Some((index, provider));
+        self
+    }
+
+    /// The attached vector index and embedding provider, if any
+    pub fn vectors(&self) -> Option<(&VectorIndex, &dyn EmbeddingProvider)> {
+        self.vectors
+            .as_ref()
+            .map(|(index, provider)| (index.as_ref(), provider.as_ref()))
+    }
+
+    /// Add an entity to the knowledge graph.
+    ///
+    /// When insert-time resolution is enabled and an existing entity of the
+    /// same type matches above the auto-merge threshold, the new name and
+    /// metadata are folded into that entity and its ID is returned instead.
     pub async fn add_entity(
         &self,
         name: &str,
@@ -49,66 +191,1154 @@ impl KnowledgeGraph {
     ) -> Result<String> {
         debug!("Adding entity: {} ({})", name, entity_type);
 
-        // Insert into SQLite
-        let id = self
+        if self.resolution.resolve_on_insert
+            && self.resolution.applies_to(entity_type)
+            && let Some(best) = self.find_duplicates(name, entity_type).await?.first()
+            && best.score >= self.resolution.auto_merge_threshold
+        {
+            return self.fold_into_existing(&best.entity, name, metadata).await;
+        }
+
+        // Insert into SQLite (the outbox trigger records the change)
+        let id = self.db.insert_entity(name, entity_type, metadata).await?;
+
+        // Index in Tantivy
+        self.index_pending();
+
+        info!("Added entity: {} with ID {}", name, id);
+        Ok(id)
+    }
+
+    /// Fold a newly-mentioned name and metadata into an existing entity
+    async fn fold_into_existing(
+        &self,
+        existing: &Entity,
+        name: &str,
+        metadata: Option<JsonValue>,
+    ) -> Result<String> {
+        let mut merged = match existing.metadata.clone() {
+            Some(JsonValue::Object(map)) => map,
+            _ => serde_json::Map::new(),
+        };
+        if let Some(JsonValue::Object(incoming)) = metadata {
+            for (key, value) in incoming {
+                if key != "aliases" && key != "merged_from" {
+                    merged.insert(key, value);
+                }
+            }
+        }
+        if name != existing.name
+            && !resolution::aliases_of(existing)
+                .iter()
+                .any(|a| a.eq_ignore_ascii_case(name))
+        {
+            let aliases = merged
+                .entry("aliases")
+                .or_insert_with(|| JsonValue::Array(Vec::new()));
+            if let JsonValue::Array(arr) = aliases {
+                arr.push(JsonValue::String(name.to_string()));
+            }
+        }
+
+        self.update_entity(&existing.id, None, None, Some(JsonValue::Object(merged)))
+            .await?;
+        info!(
+            "Resolved '{}' to existing entity '{}' ({})",
+            name, existing.name, existing.id
+        );
+        Ok(existing.id.clone())
+    }
+
+    /// Find existing entities that are likely duplicates of `name`, best first
+    pub async fn find_duplicates(
+        &self,
+        name: &str,
+        entity_type: &str,
+    ) -> Result<Vec<MatchCandidate>> {
+        let normalized = resolution::normalize_name(name);
+        let tokens: Vec<String> = normalized
+            .split(' ')
+            .filter(|t| t.chars().count() >= 2)
+            .take(8)
+            .map(|t| t.to_string())
+            .collect();
+        let mut existing = self
             .db
-            .insert_entity(name, entity_type, metadata.clone())
+            .find_entities_by_tokens(&tokens, entity_type, 200)
             .await?;
 
-        // Index in Tantivy
-        let content = format!(
-            "{} {} {}",
+        // Embedding neighbours may share no name tokens at all
+        if let Some((index, provider)) = self.vectors() {
+            match provider.embed(name) {
+                Ok(query) => {
+                    for hit in index.search(&query, 10) {
+                        if hit.similarity < self.resolution.embedding_threshold
+                            || existing.iter().any(|e| e.id == hit.entity_id)
+                        {
+                            continue;
+                        }
+                        if let Some(entity) = self.db.get_entity(&hit.entity_id).await?
+                            && entity.entity_type == entity_type
+                        {
+                            existing.push(entity);
+                        }
+                    }
+                }
+                Err(e) => warn!("Embedding lookup failed during entity resolution: {}", e),
+            }
+        }
+
+        Ok(resolution::rank_candidates(
             name,
-            entity_type,
-            metadata.as_ref().map(|m| m.to_string()).unwrap_or_default()
+            &existing,
+            &self.resolution,
+            self.vectors(),
+        ))
+    }
+
+    /// Merge entity `merge_id` into `keep_id`, re-pointing relationships and
+    /// recording provenance, then refresh the search index.
+    pub async fn merge_entities(
+        &self,
+        keep_id: &str,
+        merge_id: &str,
+        reason: MatchReason,
+        score: f32,
+    ) -> Result<Entity> {
+        let provenance = serde_json::json!({
+            "reason": reason,
+            "score": score,
+        });
+        let kept = self
+            .db
+            .merge_entities(keep_id, merge_id, provenance)
+            .await?;
+        self.index_pending();
+        Ok(kept)
+    }
+
+    /// Run a resolution maintenance pass over the whole graph.
+    ///
+    /// Exact matches are merged automatically; fuzzier candidates are merged
+    /// only when `confirmer` agrees, and are otherwise reported as pending.
+    /// With `dry_run` nothing is merged and every candidate is reported.
+    pub async fn run_entity_resolution(
+        &self,
+        confirmer: Option<&dyn MergeConfirmer>,
+        dry_run: bool,
+    ) -> Result<ResolutionReport> {
+        let mut report = ResolutionReport::default();
+        if !self.resolution.enabled {
+            return Ok(report);
+        }
+
+        let entities = self.db.get_all_entities().await?;
+        let pairs = resolution::find_duplicate_pairs(&entities, &self.resolution);
+        report.pairs_examined = pairs.len();
+
+        if dry_run {
+            report.pending = pairs;
+            return Ok(report);
+        }
+
+        let (to_merge, pending) =
+            resolution::triage_pairs(pairs, &self.resolution, confirmer).await;
+        report.pending = pending;
+
+        for (pair, confirmed_by_llm) in to_merge {
+            match self
+                .merge_entities(&pair.keep.id, &pair.merge.id, pair.reason, pair.score)
+                .await
+            {
+                Ok(_) => report.merged.push(MergeRecord {
+                    kept_id: pair.keep.id,
+                    merged_id: pair.merge.id,
+                    merged_name: pair.merge.name,
+                    score: pair.score,
+                    reason: pair.reason,
+                    confirmed_by_llm,
+                }),
+                Err(e) => {
+                    warn!(
+                        "Failed to merge {} into {}: {:#}",
+                        pair.merge.id, pair.keep.id, e
+                    );
+                    report.failed.push(FailedMerge {
+                        kept_id: pair.keep.id,
+                        merged_id: pair.merge.id,
+                        merged_name: pair.merge.name,
+                        error: format!("{:#}", e),
+                    });
+                }
+            }
+        }
+
+        info!(
+            "Entity resolution pass: {} merged, {} pending, {} failed",
+            report.merged.len(),
+            report.pending.len(),
+            report.failed.len()
         );
+        Ok(report)
+    }
 
-        self.index
-            .index_document(&id, &content, entity_type, &chrono::Utc::now().to_rfc3339())?;
+    /// Detect communities and bring their stored summaries up to date.
+    ///
+    /// Only communities whose members or internal relationships changed since
+    /// the last pass are re-summarized; summaries of communities that no longer
+    /// exist are deleted. Without a `summarizer` (or when it fails) a plain
+    /// listing of the members is stored instead.
+    pub async fn refresh_communities(
+        &self,
+        summarizer: Option<&dyn CommunitySummarizer>,
+    ) -> Result<CommunityReport> {
+        let mut report = CommunityReport::default();
+        if !self.communities.enabled {
+            return Ok(report);
+        }
 
-        info!("Added entity: {} with ID {}", name, id);
+        let entities = self.db.get_all_entities().await?;
+        let relationships = self.db.get_all_relationships().await?;
+        let detected =
+            communities::detect_communities(&entities, &relationships, &self.communities);
+        report.detected = detected.len();
+
+        let existing: Vec<StoredCommunity> = entities
+            .iter()
+            .filter_map(StoredCommunity::from_entity)
+            .collect();
+        let actions = communities::plan_refresh(
+            &detected,
+            &existing,
+            &self.communities,
+            summarizer.is_some(),
+        );
+        let by_id: std::collections::HashMap<String, Entity> =
+            entities.into_iter().map(|e| (e.id.clone(), e)).collect();
+
+        for action in actions {
+            let (existing_id, community) = match action {
+                RefreshAction::Keep { .. } => {
+                    report.unchanged += 1;
+                    continue;
+                }
+                RefreshAction::Remove { id } => {
+                    if self.db.delete_entity(&id).await? {
+                        report.removed += 1;
+                    }
+                    continue;
+                }
+                RefreshAction::Create(community) => (None, community),
+                RefreshAction::Update { id, community } => (Some(id), community),
+            };
+
+            let context = communities::community_context(
+                &community,
+                &by_id,
+                &relationships,
+                &self.communities,
+            );
+            let (summary, summarized) = match summarizer {
+                Some(s) => match s.summarize(&context).await {
+                    Ok(summary) => (summary, true),
+                    Err(e) => {
+                        warn!("Failed to summarize community: {}", e);
+                        report.failed += 1;
+                        (communities::fallback_summary(&context), false)
+                    }
+                },
+                None => (communities::fallback_summary(&context), false),
+            };
+            let metadata =
+                communities::community_metadata(&community, &context, &summary, summarized);
+
+            match existing_id {
+                Some(id) => {
+                    self.db
+                        .update_entity(&id, Some(&summary.title), None, Some(metadata))
+                        .await?;
+                    report.updated += 1;
+                }
+                None => {
+                    self.db
+                        .insert_entity(&summary.title, COMMUNITY_TYPE, Some(metadata))
+                        .await?;
+                    report.created += 1;
+                }
+            }
+        }
+        self.index_pending();
+
+        info!(
+            "Community refresh: {} detected, {} created, {} updated, {} unchanged, {} removed",
+            report.detected, report.created, report.updated, report.unchanged, report.removed
+        );
+        Ok(report)
+    }
+
+    /// All stored community summaries, largest first
+    pub async fn list_communities(&self) -> Result<Vec<StoredCommunity>> {
+        let entities = self
+            .db
+            .get_entities_by_type(&[COMMUNITY_TYPE.to_string()], 10_000)
+            .await?;
+        let mut stored: Vec<StoredCommunity> = entities
+            .iter()
+            .filter_map(StoredCommunity::from_entity)
+            .collect();
+        stored.sort_by_key(|c| std::cmp::Reverse(c.members.len()));
+        Ok(stored)
+    }
+
+    /// Community summaries most relevant to a corpus-wide question, with
+    /// their ranking scores. See [`communities::rank_communities`].
+    pub async fn global_search(
+        &self,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<(StoredCommunity, f32)>> {
+        let stored = self.list_communities().await?;
+        if stored.is_empty() {
+            return Ok(Vec::new());
+        }
+        let options = SearchOptions {
+            entity_types: vec![COMMUNITY_TYPE.to_string()],
+            ..Default::default()
+        };
+        let relevance = self
+            .search_with(query, &options, stored.len())?
+            .into_iter()
+            .map(|r| (r.id, r.score))
+            .collect();
+        Ok(communities::rank_communities(stored, &relevance, limit))
+    }
+
+    /// Extract memories from conversation threads that have gone idle.
+    ///
+    /// Each thread's messages since its last extraction are handed to
+    /// `extractor`. Proposals below `config.min_confidence` or already known
+    /// to the graph are dropped; the rest are committed with provenance
+    /// pointing at the thread, or queued for review when
+    /// `config.require_review` is set. A thread whose extraction fails is
+    /// retried on the next pass.
+    pub async fn extract_episodic_memories(
+        &self,
+        extractor: &dyn MemoryExtractor,
+        config: &ExtractionConfig,
+    ) -> Result<ExtractionReport> {
+        let mut report = ExtractionReport::default();
+        if !config.enabled {
+            return Ok(report);
+        }
+
+        let idle_before = Utc::now() - chrono::Duration::minutes(config.idle_minutes as i64);
+        let threads = self
+            .db
+            .threads_pending_extraction(idle_before, config.max_threads_per_pass)
+            .await?;
+
+        for thread in threads {
+            let messages = self
+                .db
+                .get_thread_messages_since(&thread.id, thread.extracted_at, config.max_messages)
+                .await?;
+            let Some(last) = messages.last().map(|m| m.created_at) else {
+                self.db
+                    .set_thread_extracted(&thread.id, thread.last_message_at)
+                    .await?;
+                continue;
+            };
+            if messages.len() < config.min_messages {
+                self.db.set_thread_extracted(&thread.id, last).await?;
+                continue;
+            }
+
+            let episode = Episode {
+                known_entities: self.entities_mentioned(&messages).await?,
+                thread_id: thread.id.clone(),
+                channel: thread.channel.clone(),
+                title: thread.title.clone(),
+                messages,
+            };
+            let extracted = match extractor.extract(&episode).await {
+                Ok(extracted) => extracted,
+                Err(e) => {
+                    warn!("Memory extraction failed for thread {}: {}", thread.id, e);
+                    report.failed += 1;
+                    continue;
+                }
+            };
+            report.threads += 1;
+
+            let (proposals, low_confidence) =
+                episodic::prepare_proposals(extracted, config.min_confidence);
+            report.low_confidence += low_confidence;
+            report.proposed += proposals.len();
+            for proposal in proposals {
+                if self.is_known(&proposal).await? {
+                    report.duplicates += 1;
+                    continue;
+                }
+                if config.require_review {
+                    match self
+                        .db
+                        .insert_memory_candidate(&thread.id, &proposal)
+                        .await?
+                    {
+                        Some(_) => report.queued += 1,
+                        None => report.duplicates += 1,
+                    }
+                    continue;
+                }
+                match self.commit_proposal(&thread.id, &proposal).await {
+                    Ok(_) => report.committed += 1,
+                    Err(e) => debug!("Skipping extracted '{}': {}", proposal.describe(), e),
+                }
+            }
+            self.db.set_thread_extracted(&thread.id, last).await?;
+        }
+
+        if report.threads > 0 || report.failed > 0 {
+            info!(
+                "Memory extraction: {} threads, {} committed, {} queued, {} duplicates, {} failed",
+                report.threads, report.committed, report.queued, report.duplicates, report.failed
+            );
+        }
+        Ok(report)
+    }
+
+    /// Names of existing entities matching the words of a conversation
+    async fn entities_mentioned(
+        &self,
+        messages: &[crate::sqlite::Conversation],
+    ) -> Result<Vec<String>> {
+        let mut words: Vec<String> = Vec::new();
+        for message in messages {
+            for word in message.content.split(|c: char| !c.is_alphanumeric()) {
+                let word = word.to_lowercase();
+                if word.chars().count() >= 4 && !words.contains(&word) {
+                    words.push(word);
+                }
+            }
+        }
+        words.truncate(40);
+        if words.is_empty() {
+            return Ok(Vec::new());
+        }
+        let options = SearchOptions {
+            fuzzy: false,
+            ..Default::default()
+        };
+        let results = match self.search_with(&words.join(" "), &options, 20) {
+            Ok(results) => results,
+            Err(e) => {
+                debug!("Could not look up known entities: {}", e);
+                return Ok(Vec::new());
+            }
+        };
+        let mut names = Vec::new();
+        for result in results {
+            if result.entity_type == CHUNK_TYPE || result.entity_type == COMMUNITY_TYPE {
+                continue;
+            }
+            if let Some(entity) = self.db.get_entity(&result.id).await? {
+                names.push(entity.name);
+            }
+        }
+        Ok(names)
+    }
+
+    /// Whether the graph already holds the proposed fact
+    async fn is_known(&self, proposal: &MemoryProposal) -> Result<bool> {
+        match proposal {
+            MemoryProposal::Entity(e) => {
+                if !self
+                    .db
+                    .find_entities_by_name(&e.name, std::slice::from_ref(&e.entity_type), 1)
+                    .await?
+                    .is_empty()
+                {
+                    return Ok(true);
+                }
+                if !self.resolution.applies_to(&e.entity_type) {
+                    return Ok(false);
+                }
+                Ok(self
+                    .find_duplicates(&e.name, &e.entity_type)
+                    .await?
+                    .first()
+                    .is_some_and(|best| best.score >= self.resolution.auto_merge_threshold))
+            }
+            MemoryProposal::Relationship(r) => {
+                let (Some(source), Some(target)) = (
+                    self.find_endpoint(&r.source, r.source_type.as_deref())
+                        .await?,
+                    self.find_endpoint(&r.target, r.target_type.as_deref())
+                        .await?,
+                ) else {
+                    return Ok(false);
+                };
+                Ok(self
+                    .db
+                    .get_current_relationships_for(&source.id)
+                    .await?
+                    .iter()
+                    .any(|rel| {
+                        rel.source_id == source.id
+                            && rel.target_id == target.id
+                            && rel.relation_type == r.relation_type
+                    }))
+            }
+            MemoryProposal::Preference(p) => Ok(self
+                .db
+                .get_preferences(Some(&p.category))
+                .await?
+                .iter()
+                .any(|existing| existing.key == p.key && existing.value == p.value)),
+        }
+    }
+
+    /// Existing entity a relationship endpoint refers to, by name or alias
+    async fn find_endpoint(&self, name: &str, entity_type: Option<&str>) -> Result<Option<Entity>> {
+        if let Some(t) = entity_type {
+            let typed = self
+                .db
+                .find_entities_by_name(name, &[t.to_string()], 1)
+                .await?;
+            if let Some(entity) = typed.into_iter().next() {
+                return Ok(Some(entity));
+            }
+        }
+        Ok(self
+            .db
+            .find_entities_by_name(name, &[], 1)
+            .await?
+            .into_iter()
+            .next())
+    }
+
+    /// Write an extracted proposal to the graph with provenance pointing at
+    /// its conversation thread. Returns the ID of the stored fact.
+    async fn commit_proposal(&self, thread_id: &str, proposal: &MemoryProposal) -> Result<String> {
+        let mut source = Provenance::new(SourceType::Conversation, thread_id);
+        if let Some(evidence) = proposal.evidence() {
+            source = source.with_excerpt(evidence);
+        }
+
+        match proposal {
+            MemoryProposal::Entity(e) => {
+                let mut metadata = serde_json::json!({
+                    "confidence": e.confidence,
+                    "extracted_from": thread_id,
+                });
+                if let Some(description) = &e.description {
+                    metadata["description"] = JsonValue::String(description.clone());
+                }
+                let id = self
+                    .add_entity(&e.name, &e.entity_type, Some(metadata))
+                    .await?;
+                self.db
+                    .add_provenance(FactType::Entity, &id, &source)
+                    .await?;
+                Ok(id)
+            }
+            MemoryProposal::Relationship(r) => {
+                let source_id = self
+                    .resolve_endpoint(&r.source, r.source_type.as_deref(), &source)
+                    .await?;
+                let target_id = self
+                    .resolve_endpoint(&r.target, r.target_type.as_deref(), &source)
+                    .await?;
+                let fact = self
+                    .record_fact(
+                        &source_id,
+                        &target_id,
+                        &r.relation_type,
+                        Some(serde_json::json!({"confidence": r.confidence})),
+                        FactOptions::for_relation(&r.relation_type).with_provenance(source),
+                    )
+                    .await?;
+                Ok(fact.id)
+            }
+            MemoryProposal::Preference(p) => {
+                self.db
+                    .upsert_preference(
+                        &p.category,
+                        &p.key,
+                        p.value.clone(),
+                        p.confidence as f64,
+                        Some(&format!("conversation:{}", thread_id)),
+                    )
+                    .await
+            }
+        }
+    }
+
+    /// ID of the entity a relationship endpoint refers to, creating it when
+    /// its type is known
+    async fn resolve_endpoint(
+        &self,
+        name: &str,
+        entity_type: Option<&str>,
+        source: &Provenance,
+    ) -> Result<String> {
+        if let Some(entity) = self.find_endpoint(name, entity_type).await? {
+            return Ok(entity.id);
+        }
+        let entity_type =
+            entity_type.with_context(|| format!("Unknown entity '{}' of unknown type", name))?;
+        let id = self.add_entity(name, entity_type, None).await?;
+        self.db
+            .add_provenance(FactType::Entity, &id, source)
+            .await?;
         Ok(id)
     }
 
-    /// Link two entities with a relationship
-    pub async fn link_entities(
-        &self,
-        source_id: &str,
-        target_id: &str,
-        relation_type: &str,
-        metadata: Option<JsonValue>,
-    ) -> Result<String> {
-        debug!(
-            "Linking entities: {} -> {} ({})",
-            source_id, target_id, relation_type
-        );
+    /// Memory candidates awaiting review (or all candidates), oldest first
+    pub async fn list_memory_candidates(
+        &self,
+        status: Option<CandidateStatus>,
+        limit: usize,
+    ) -> Result<Vec<MemoryCandidate>> {
+        self.db.list_memory_candidates(status, limit).await
+    }
+
+    /// Commit a pending memory candidate to the graph. Returns the ID of the
+    /// stored fact.
+    pub async fn accept_memory_candidate(&self, id: &str) -> Result<String> {
+        let candidate = self
+            .db
+            .get_memory_candidate(id)
+            .await?
+            .with_context(|| format!("No memory candidate with ID {}", id))?;
+        if candidate.status != CandidateStatus::Pending {
+            anyhow::bail!(
+                "Memory candidate {} is already {}",
+                id,
+                candidate.status.as_str()
+            );
+        }
+        let fact_id = self
+            .commit_proposal(&candidate.thread_id, &candidate.proposal)
+            .await?;
+        self.db
+            .decide_memory_candidate(id, CandidateStatus::Committed)
+            .await?;
+        info!(
+            "Committed memory candidate {}: {}",
+            id,
+            candidate.proposal.describe()
+        );
+        Ok(fact_id)
+    }
+
+    /// Reject a pending memory candidate. Returns false if there is no such
+    /// pending candidate.
+    pub async fn reject_memory_candidate(&self, id: &str) -> Result<bool> {
+        self.db
+            .decide_memory_candidate(id, CandidateStatus::Rejected)
+            .await
+    }
+
+    /// Synchronize a MEMORY.md file with the graph in both directions.
+    ///
+    /// Bullets added, edited or deleted in the file since the last sync are
+    /// applied to the graph (new and edited facts are pinned, deleted ones
+    /// archived). A fact edited in the file whose entity also changed since
+    /// the last sync is a conflict, resolved by `config.conflict_policy`.
+    /// The managed block is then regenerated from pinned and high-importance
+    /// entities; the file is only rewritten when its content changes.
+    pub async fn sync_memory_file(
+        &self,
+        path: &Path,
+        config: &MemorySyncConfig,
+    ) -> Result<MemorySyncReport> {
+        let mut report = MemorySyncReport::default();
+        let key = path.to_string_lossy().to_string();
+        let current_text = memory_sync::load_memory(path)?;
+        let state = self.db.get_memory_sync_state(&key).await?;
+        let current = MemoryDocument::parse(&current_text);
+        let base = state
+            .as_ref()
+            .map(|(content, _)| MemoryDocument::parse(content));
+        let synced_at = state.as_ref().map(|(_, at)| *at);
+
+        if state.as_ref().map(|(content, _)| content.as_str()) != Some(current_text.as_str()) {
+            for change in memory_sync::file_changes(&current, base.as_ref()) {
+                self.apply_memory_change(change, synced_at, config, &mut report)
+                    .await?;
+            }
+            self.index_pending();
+        }
+
+        // Regenerate the managed block from the graph
+        let now = Utc::now();
+        let mut selected: Vec<(f32, String)> = self
+            .db
+            .get_all_entity_stats()
+            .await?
+            .iter()
+            .filter(|s| !config.exclude_types.iter().any(|t| t == &s.entity_type))
+            .map(|s| (importance::importance_score(s, now, &self.importance), s))
+            .filter(|(score, s)| s.pinned || *score >= config.importance_threshold)
+            .map(|(score, s)| (score, s.entity_id.clone()))
+            .collect();
+        selected.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+        selected.truncate(config.max_entries);
+
+        let mut entries = Vec::with_capacity(selected.len());
+        for (_, id) in &selected {
+            if let Some(entity) = self.db.get_entity(id).await? {
+                entries.push(MemoryEntry::from_entity(&entity));
+            }
+        }
+        report.entries = entries.len();
+        let rendered = MemoryDocument {
+            before: current.before,
+            after: current.after,
+            entries,
+        }
+        .render();
+
+        if rendered != current_text {
+            memory_sync::save_memory(path, &rendered)?;
+            report.file_written = true;
+        }
+        self.db
+            .set_memory_sync_state(&key, &rendered, Utc::now())
+            .await?;
+
+        for conflict in &report.conflicts {
+            warn!(
+                "MEMORY.md conflict on {}: file '{}' vs graph '{}', kept {:?}",
+                conflict.entity_id,
+                conflict.file_version,
+                conflict.graph_version,
+                conflict.resolution
+            );
+        }
+        info!(
+            "Synced {}: {} created, {} updated, {} archived, {} conflicts, {} facts",
+            path.display(),
+            report.created.len(),
+            report.updated.len(),
+            report.archived.len(),
+            report.conflicts.len(),
+            report.entries
+        );
+        Ok(report)
+    }
+
+    /// Apply one edit from MEMORY.md to the graph
+    async fn apply_memory_change(
+        &self,
+        change: FileChange,
+        synced_at: Option<DateTime<Utc>>,
+        config: &MemorySyncConfig,
+        report: &mut MemorySyncReport,
+    ) -> Result<()> {
+        match change {
+            FileChange::Added(entry) => {
+                let metadata = entry
+                    .note
+                    .as_deref()
+                    .map(|note| memory_sync::apply_note(None, Some(note)));
+                let id = self
+                    .add_entity(&entry.name, &entry.entity_type, metadata)
+                    .await?;
+                self.db.set_pinned(&id, true).await?;
+                report.created.push(id);
+            }
+            FileChange::Edited(entry) => {
+                let Some(id) = entry.id.clone() else {
+                    return Ok(());
+                };
+                let Some(entity) = self.db.get_entity(&id).await? else {
+                    debug!("MEMORY.md entry {} no longer exists in the graph", id);
+                    return Ok(());
+                };
+                let graph_entry = MemoryEntry::from_entity(&entity);
+                if graph_entry.same_content(&entry) {
+                    return Ok(());
+                }
+                if synced_at.is_some_and(|at| entity.updated_at > at) {
+                    report.conflicts.push(MemoryConflict {
+                        entity_id: id.clone(),
+                        file_version: entry.describe(),
+                        graph_version: graph_entry.describe(),
+                        resolution: config.conflict_policy,
+                    });
+                    if config.conflict_policy == ConflictPolicy::Graph {
+                        return Ok(());
+                    }
+                }
+                let metadata = (graph_entry.note != entry.note).then(|| {
+                    memory_sync::apply_note(entity.metadata.as_ref(), entry.note.as_deref())
+                });
+                self.db
+                    .update_entity(&id, Some(&entry.name), Some(&entry.entity_type), metadata)
+                    .await?;
+                self.db.set_pinned(&id, true).await?;
+                report.updated.push(id);
+            }
+            FileChange::Removed(id) => {
+                let importance = self.importance_of(&id).await?.unwrap_or(0.0);
+                self.db.set_pinned(&id, false).await?;
+                if self.db.archive_entity(&id, importance).await? {
+                    report.archived.push(id);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Apply pending entity changes from the outbox to the search index.
+    /// Returns the number of entities re-indexed or removed.
+    pub fn sync_index(&self) -> Result<usize> {
+        self.db
+            .drain_index_outbox(outbox::TANTIVY_CONSUMER, |changes| {
+                self.index.apply_changes(changes)
+            })
+    }
+
+    /// Bring the search index up to date after a write. A failure leaves the
+    /// changes in the outbox, to be applied by the next sync or on startup.
+    fn index_pending(&self) {
+        if let Err(e) = self.sync_index() {
+            warn!("Search index update deferred: {:#}", e);
+        }
+    }
+
+    /// Rebuild the search index from every entity in the database
+    fn rebuild_index(&self) -> Result<()> {
+        let count = self
+            .db
+            .rebuild_index_from_snapshot(outbox::TANTIVY_CONSUMER, |entities| {
+                self.index.reindex_all_from_entities(entities)
+            })?;
+        debug!("Rebuilt search index with {} entities", count);
+        Ok(())
+    }
+
+    /// Apply pending entity changes from the outbox to a vector index,
+    /// embedding changed entities with `provider`
+    pub fn sync_vectors(
+        &self,
+        vectors: &VectorIndex,
+        provider: &dyn EmbeddingProvider,
+    ) -> Result<usize> {
+        self.db
+            .drain_index_outbox(outbox::VECTOR_CONSUMER, |changes| {
+                vectors.apply_changes(changes, provider)
+            })
+    }
+
+    /// Re-embed every entity into a vector index and start tracking the
+    /// outbox from there, so later [`sync_vectors`](Self::sync_vectors)
+    /// calls only see new changes
+    pub fn rebuild_vectors(
+        &self,
+        vectors: &VectorIndex,
+        provider: &dyn EmbeddingProvider,
+    ) -> Result<usize> {
+        self.db
+            .rebuild_index_from_snapshot(outbox::VECTOR_CONSUMER, |entities| {
+                vectors
+                    .rebuild_from_entities(entities, provider)
+                    .map(|_| ())
+            })
+    }
+
+    /// Compare the search index with the entities table.
+    ///
+    /// With `repair`, pending outbox changes are applied, missing entities
+    /// are indexed and orphaned documents removed.
+    pub async fn check_consistency(&self, repair: bool) -> Result<ConsistencyReport> {
+        let pending_changes = self
+            .db
+            .pending_index_count(outbox::TANTIVY_CONSUMER)
+            .await?;
+        if repair && pending_changes > 0 {
+            self.sync_index()?;
+        }
+
+        let entity_ids: std::collections::HashSet<String> =
+            self.db.get_all_entity_ids().await?.into_iter().collect();
+        let indexed = self.index.all_ids()?;
+        let mut missing_from_index: Vec<String> =
+            entity_ids.difference(&indexed).cloned().collect();
+        let mut orphaned_in_index: Vec<String> = indexed.difference(&entity_ids).cloned().collect();
+        missing_from_index.sort();
+        orphaned_in_index.sort();
+
+        let mut report = ConsistencyReport {
+            pending_changes,
+            missing_from_index,
+            orphaned_in_index,
+            repaired: false,
+        };
+        if repair && !report.is_consistent() {
+            let mut changes = Vec::new();
+            for id in &report.missing_from_index {
+                changes.push(outbox::PendingChange {
+                    entity_id: id.clone(),
+                    entity: self.db.get_entity(id).await?,
+                });
+            }
+            changes.extend(
+                report
+                    .orphaned_in_index
+                    .iter()
+                    .map(|id| outbox::PendingChange {
+                        entity_id: id.clone(),
+                        entity: None,
+                    }),
+            );
+            self.index.apply_changes(&changes)?;
+            report.repaired = true;
+            info!(
+                "Repaired search index: {} pending, {} missing, {} orphaned",
+                report.pending_changes,
+                report.missing_from_index.len(),
+                report.orphaned_in_index.len()
+            );
+        }
+        Ok(report)
+    }
+
+    /// Update an entity and refresh its search index entry.
+    /// Returns false if the entity does not exist.
+    pub async fn update_entity(
+        &self,
+        id: &str,
+        name: Option<&str>,
+        entity_type: Option<&str>,
+        metadata: Option<JsonValue>,
+    ) -> Result<bool> {
+        debug!("Updating entity: {}", id);
+
+        if !self
+            .db
+            .update_entity(id, name, entity_type, metadata)
+            .await?
+        {
+            return Ok(false);
+        }
+
+        self.index_pending();
+
+        info!("Updated entity {}", id);
+        Ok(true)
+    }
+
+    /// Delete an entity, its relationships and its search index entry.
+    /// Returns false if the entity does not exist.
+    pub async fn delete_entity(&self, id: &str) -> Result<bool> {
+        debug!("Deleting entity: {}", id);
+
+        let deleted = self.db.delete_entity(id).await?;
+        if deleted {
+            self.index_pending();
+            info!("Deleted entity {}", id);
+        }
+        Ok(deleted)
+    }
+
+    /// Link two entities with a relationship
+    pub async fn link_entities(
+        &self,
+        source_id: &str,
+        target_id: &str,
+        relation_type: &str,
+        metadata: Option<JsonValue>,
+    ) -> Result<String> {
+        let fact = self
+            .record_fact(
+                source_id,
+                target_id,
+                relation_type,
+                metadata,
+                FactOptions::for_relation(relation_type),
+            )
+            .await?;
+        Ok(fact.id)
+    }
+
+    /// Record a relationship as a temporal fact, superseding contradicting
+    /// facts and attaching provenance as configured in `opts`
+    pub async fn record_fact(
+        &self,
+        source_id: &str,
+        target_id: &str,
+        relation_type: &str,
+        metadata: Option<JsonValue>,
+        opts: FactOptions,
+    ) -> Result<RecordedFact> {
+        debug!(
+            "Linking entities: {} -> {} ({})",
+            source_id, target_id, relation_type
+        );
+
+        // Verify both entities exist
+        self.db
+            .get_entity(source_id)
+            .await?
+            .context("Source entity not found")?;
+        self.db
+            .get_entity(target_id)
+            .await?
+            .context("Target entity not found")?;
+
+        let fact = self
+            .db
+            .record_relationship(source_id, target_id, relation_type, metadata, opts)
+            .await?;
+
+        if fact.superseded.is_empty() {
+            info!("Linked entities with relationship ID {}", fact.id);
+        } else {
+            info!(
+                "Linked entities with relationship ID {} (superseded {})",
+                fact.id,
+                fact.superseded.join(", ")
+            );
+        }
+        Ok(fact)
+    }
+
+    /// Attach a source to an existing entity or relationship
+    pub async fn add_provenance(
+        &self,
+        fact_type: FactType,
+        fact_id: &str,
+        source: &Provenance,
+    ) -> Result<String> {
+        self.db.add_provenance(fact_type, fact_id, source).await
+    }
+
+    /// Search the knowledge graph
+    pub fn search(&self, query: &str, limit: usize) -> Result<Vec<SearchResult>> {
+        debug!("Searching knowledge graph for: {}", query);
+        self.index.search(query, limit)
+    }
+
+    /// Search with entity type, tag and date filters
+    pub fn search_with(
+        &self,
+        query: &str,
+        options: &SearchOptions,
+        limit: usize,
+    ) -> Result<Vec<SearchResult>> {
+        debug!("Searching knowledge graph for: {} ({:?})", query, options);
+        self.index.search_with(query, options, limit)
+    }
+
+    /// Search and rank results by relevance blended with importance.
+    ///
+    /// Returned entities count as accessed, which raises their importance.
+    pub async fn search_ranked(&self, query: &str, limit: usize) -> Result<Vec<SearchResult>> {
+        self.search_ranked_with(query, &SearchOptions::default(), limit)
+            .await
+    }
+
+    /// [`search_ranked`](Self::search_ranked) with search filters
+    pub async fn search_ranked_with(
+        &self,
+        query: &str,
+        options: &SearchOptions,
+        limit: usize,
+    ) -> Result<Vec<SearchResult>> {
+        let mut results =
+            self.index
+                .search_with(query, options, limit.saturating_mul(3).max(limit))?;
+        if results.is_empty() {
+            return Ok(results);
+        }
+
+        if self.importance.recall_weight > 0.0 {
+            let ids: Vec<String> = results.iter().map(|r| r.id.clone()).collect();
+            let now = Utc::now();
+            let scores: std::collections::HashMap<String, f32> = self
+                .db
+                .get_entity_stats(&ids)
+                .await?
+                .iter()
+                .map(|s| {
+                    (
+                        s.entity_id.clone(),
+                        importance::importance_score(s, now, &self.importance),
+                    )
+                })
+                .collect();
+
+            let max_relevance = results
+                .iter()
+                .map(|r| r.score)
+                .fold(f32::MIN_POSITIVE, f32::max);
+            for result in &mut results {
+                let importance = scores.get(&result.id).copied().unwrap_or(0.0);
+                result.score = importance::blend_rank(
+                    result.score / max_relevance,
+                    importance,
+                    self.importance.recall_weight,
+                );
+            }
+            results.sort_by(|a, b| b.score.total_cmp(&a.score));
+        }
+        results.truncate(limit);
+
+        let ids: Vec<String> = results.iter().map(|r| r.id.clone()).collect();
+        self.db.record_access(&ids).await?;
+        Ok(results)
+    }
 
-        // Verify both entities exist
-        self.db
-            .get_entity(source_id)
-            .await?
-            .context("Source entity not found")?;
-        self.db
-            .get_entity(target_id)
+    /// Find an entity by ID, or else by exact name or alias
+    pub async fn resolve_entity(&self, id_or_name: &str) -> Result<Option<Entity>> {
+        if let Some(entity) = self.db.get_entity(id_or_name).await? {
+            return Ok(Some(entity));
+        }
+        Ok(self
+            .db
+            .find_entities_by_name(id_or_name, &[], 1)
             .await?
-            .context("Target entity not found")?;
+            .into_iter()
+            .next())
+    }
 
-        let id = self
-            .db
-            .insert_relationship(source_id, target_id, relation_type, metadata)
-            .await?;
+    /// Shortest path between two entities over current relationships
+    pub async fn shortest_path(
+        &self,
+        from_id: &str,
+        to_id: &str,
+        filter: &EdgeFilter,
+        limits: &QueryLimits,
+    ) -> Result<Option<GraphPath>> {
+        graph_query::shortest_path(&self.db, from_id, to_id, filter, limits).await
+    }
 
-        info!("Linked entities with relationship ID {}", id);
-        Ok(id)
+    /// Entities within `hops` relationships of an entity, nearest first
+    pub async fn neighborhood(
+        &self,
+        entity_id: &str,
+        hops: usize,
+        filter: &EdgeFilter,
+        limits: &QueryLimits,
+    ) -> Result<Option<Neighborhood>> {
+        graph_query::neighborhood(&self.db, entity_id, hops, filter, limits).await
     }
 
-    /// Search the knowledge graph
-    pub fn search(&self, query: &str, limit: usize) -> Result<Vec<SearchResult>> {
-        debug!("Searching knowledge graph for: {}", query);
-        self.index.search(query, limit)
+    /// Match a graph pattern such as
+    /// `(p:person)-[works_at]->(:organization {name: "Acme"})`
+    pub async fn match_pattern(
+        &self,
+        pattern: &str,
+        limits: &QueryLimits,
+    ) -> Result<PatternResults> {
+        let pattern: Pattern = pattern.parse()?;
+        graph_query::match_pattern(&self.db, &pattern, limits).await
     }
 
-    /// Get full context for an entity
+    /// Get full context for an entity, with only relationships still in effect
     pub async fn get_context_for(&self, entity_id: &str) -> Result<EntityContext> {
         debug!("Getting context for entity: {}", entity_id);
 
@@ -120,12 +1350,41 @@ impl KnowledgeGraph {
             .context("Entity not found")?;
 
         // Get relationships
-        let relationships = self.db.get_relationships_for(entity_id).await?;
+        let relationships = self.db.get_current_relationships_for(entity_id).await?;
+
+        self.build_context(entity, relationships, None).await
+    }
+
+    /// Get context for an entity as it was known at a point in time
+    pub async fn get_context_as_of(
+        &self,
+        entity_id: &str,
+        at: DateTime<Utc>,
+    ) -> Result<EntityContext> {
+        debug!("Getting context for entity {} as of {}", entity_id, at);
+
+        let entity = self
+            .db
+            .get_entity(entity_id)
+            .await?
+            .filter(|e| e.created_at <= at)
+            .context("Entity not known at that time")?;
 
+        let relationships = self.db.get_relationships_as_of(entity_id, at).await?;
+
+        self.build_context(entity, relationships, Some(at)).await
+    }
+
+    async fn build_context(
+        &self,
+        entity: Entity,
+        relationships: Vec<Relationship>,
+        as_of: Option<DateTime<Utc>>,
+    ) -> Result<EntityContext> {
         // Get related entities
         let mut related_entities = Vec::new();
         for rel in &relationships {
-            let related_id = if rel.source_id == entity_id {
+            let related_id = if rel.source_id == entity.id {
                 &rel.target_id
             } else {
                 &rel.source_id
@@ -137,13 +1396,24 @@ impl KnowledgeGraph {
         }
 
         // Get recent conversations (limit to 20)
-        let recent_conversations = self.db.get_recent_conversations(None, 20).await?;
+        let mut recent_conversations = self.db.get_recent_conversations(None, 20).await?;
+
+        // Sources for the entity and each relationship
+        let mut fact_ids = vec![entity.id.clone()];
+        fact_ids.extend(relationships.iter().map(|r| r.id.clone()));
+        let mut provenance = self.db.get_provenance(&fact_ids).await?;
+
+        if let Some(at) = as_of {
+            recent_conversations.retain(|c| c.created_at <= at);
+            provenance.retain(|p| p.recorded_at <= at);
+        }
 
         Ok(EntityContext {
             entity,
             related_entities,
             relationships,
             recent_conversations,
+            provenance,
         })
     }
 
@@ -173,7 +1443,8 @@ impl KnowledgeGraph {
 
         // Also store as conversation if channel provided
         if let Some(ch) = channel {
-            self.db
+            let conversation_id = self
+                .db
                 .insert_conversation(
                     ch,
                     "system",
@@ -181,6 +1452,11 @@ impl KnowledgeGraph {
                     Some(serde_json::json!({"entity_id": entity_id})),
                 )
                 .await?;
+            let source =
+                Provenance::new(SourceType::Conversation, conversation_id).with_excerpt(content);
+            self.db
+                .add_provenance(FactType::Entity, &entity_id, &source)
+                .await?;
         }
 
         info!("Remembered content as entity {}", entity_id);
@@ -191,8 +1467,8 @@ impl KnowledgeGraph {
     pub async fn recall(&self, query: &str, limit: usize) -> Result<Vec<EntityContext>> {
         debug!("Recalling: {}", query);
 
-        // Search using Tantivy
-        let results = self.search(query, limit)?;
+        // Search using Tantivy, ranked by relevance and importance
+        let results = self.search_ranked(query, limit).await?;
 
         // Get full context for each result
         let mut contexts = Vec::new();
@@ -206,6 +1482,33 @@ impl KnowledgeGraph {
         Ok(contexts)
     }
 
+    /// Recall information as it was known at a point in time
+    pub async fn recall_as_of(
+        &self,
+        query: &str,
+        limit: usize,
+        at: DateTime<Utc>,
+    ) -> Result<Vec<EntityContext>> {
+        debug!("Recalling as of {}: {}", at, query);
+
+        let results = self.search_ranked(query, limit).await?;
+
+        let mut contexts = Vec::new();
+        for result in results {
+            if let Ok(context) = self.get_context_as_of(&result.id, at).await {
+                contexts.push(context);
+            }
+        }
+
+        info!(
+            "Recalled {} contexts as of {} for query: {}",
+            contexts.len(),
+            at,
+            query
+        );
+        Ok(contexts)
+    }
+
     /// Get entity by ID
     pub async fn get_entity(&self, id: &str) -> Result<Option<Entity>> {
         self.db.get_entity(id).await
@@ -225,6 +1528,23 @@ impl KnowledgeGraph {
         self.db.get_relationships_for(entity_id).await
     }
 
+    /// Update a relationship's type and/or metadata
+    pub async fn update_relationship(
+        &self,
+        id: &str,
+        relation_type: Option<&str>,
+        metadata: Option<JsonValue>,
+    ) -> Result<bool> {
+        self.db
+            .update_relationship(id, relation_type, metadata)
+            .await
+    }
+
+    /// Delete a relationship
+    pub async fn delete_relationship(&self, id: &str) -> Result<bool> {
+        self.db.delete_relationship(id).await
+    }
+
     /// Store a conversation
     pub async fn store_conversation(
         &self,
@@ -247,39 +1567,28 @@ impl KnowledgeGraph {
         self.db.get_recent_conversations(channel, limit).await
     }
 
-    /// Create a watcher
-    pub async fn create_watcher(
+    /// Most recent messages of a conversation thread, newest first
+    pub async fn get_thread_conversations(
         &self,
-        kind: &str,
-        config: JsonValue,
-        action: &str,
-        reply_channel: &str,
-    ) -> Result<String> {
-        self.db
-            .insert_watcher(kind, config, action, reply_channel)
-            .await
-    }
-
-    /// Get active watchers
-    pub async fn get_active_watchers(&self) -> Result<Vec<crate::sqlite::Watcher>> {
-        self.db.get_active_watchers().await
-    }
-
-    /// Update watcher status
-    pub async fn update_watcher(&self, id: &str, active: bool) -> Result<()> {
-        self.db.update_watcher_active(id, active).await
+        thread_id: &str,
+        limit: usize,
+    ) -> Result<Vec<crate::sqlite::Conversation>> {
+        self.db.get_thread_conversations(thread_id, limit).await
     }
 
-    /// Delete a watcher
-    pub async fn delete_watcher(&self, id: &str) -> Result<()> {
-        self.db.delete_watcher(id).await
+    /// Full-text search over conversation history
+    pub async fn search_conversations(
+        &self,
+        query: &str,
+        options: &crate::sqlite::ConversationSearch,
+    ) -> Result<Vec<crate::sqlite::ConversationMatch>> {
+        self.db.search_conversations(query, options).await
     }
 
     /// Reindex all entities in Tantivy
     pub async fn reindex(&self) -> Result<()> {
         info!("Reindexing all entities");
-        let entities = self.db.get_all_entities().await?;
-        self.index.reindex_all_from_entities(&entities)
+        self.rebuild_index()
     }
 
     /// Get all entities
@@ -299,6 +1608,546 @@ impl KnowledgeGraph {
     pub async fn cleanup_old_conversations(&self, retain_days: u32) -> Result<usize> {
         self.db.cleanup_old_conversations(retain_days).await
     }
+
+    /// Importance of an entity right now, or None if it does not exist
+    pub async fn importance_of(&self, entity_id: &str) -> Result<Option<f32>> {
+        let stats = self.db.get_entity_stats(&[entity_id.to_string()]).await?;
+        Ok(stats
+            .first()
+            .map(|s| importance::importance_score(s, Utc::now(), &self.importance)))
+    }
+
+    /// Pin or unpin an entity. Returns false if the entity does not exist.
+    pub async fn pin(&self, entity_id: &str, pinned: bool) -> Result<bool> {
+        self.db.set_pinned(entity_id, pinned).await
+    }
+
+    /// Forget memories that score below the policy threshold.
+    ///
+    /// With `dry_run`, nothing is changed and the report lists what would be
+    /// forgotten. Pinned entities and protected types are never forgotten.
+    pub async fn apply_retention(
+        &self,
+        policy: &RetentionPolicy,
+        dry_run: bool,
+    ) -> Result<RetentionReport> {
+        // Corpus documents are kept in step with their files by corpus sync
+        let managed = self.db.corpus_entity_ids().await?;
+        let mut stats = self.db.get_all_entity_stats().await?;
+        stats.retain(|s| !managed.contains(&s.entity_id));
+        let (selected, pinned_skipped) =
+            importance::select_for_forgetting(&stats, policy, &self.importance, Utc::now());
+
+        let mut report = RetentionReport {
+            dry_run,
+            action: policy.action,
+            examined: stats.len(),
+            pinned_skipped,
+            ..Default::default()
+        };
+
+        if dry_run {
+            report.forgotten = selected;
+            return Ok(report);
+        }
+
+        for memory in selected {
+            let forgotten = match policy.action {
+                RetentionAction::Archive => {
+                    self.db
+                        .archive_entity(&memory.entity_id, memory.score)
+                        .await?
+                }
+                RetentionAction::Delete => self.db.delete_entity(&memory.entity_id).await?,
+            };
+            if forgotten {
+                report.forgotten.push(memory);
+            }
+        }
+        self.index_pending();
+
+        if policy.action_log_retain_days > 0 {
+            report.action_logs_deleted = self
+                .db
+                .cleanup_old_action_logs(policy.action_log_retain_days)
+                .await?;
+        }
+        if policy.conversation_retain_days > 0 {
+            report.conversations_deleted = self
+                .db
+                .cleanup_old_conversations(policy.conversation_retain_days)
+                .await?;
+        }
+
+        info!(
+            "Retention pass: examined {}, forgot {} ({:?}), skipped {} pinned",
+            report.examined,
+            report.forgotten.len(),
+            report.action,
+            report.pinned_skipped
+        );
+        Ok(report)
+    }
+
+    /// List archived memories, most recent first
+    pub async fn list_archived(&self, limit: usize) -> Result<Vec<ArchivedMemory>> {
+        self.db.list_archived(limit).await
+    }
+
+    /// Restore an archived memory and re-index it.
+    /// Returns None if the entity is not in the archive.
+    pub async fn restore(&self, entity_id: &str) -> Result<Option<Entity>> {
+        let restored = self.db.restore_archived(entity_id).await?;
+        if restored.is_some() {
+            self.index_pending();
+        }
+        Ok(restored)
+    }
+
+    /// Read, extract, chunk and store a file as a document.
+    ///
+    /// Any document previously ingested from the same path is replaced.
+    /// Returns None if the file has no text.
+    pub async fn ingest_document(
+        &self,
+        path: &Path,
+        pipeline: &IngestPipeline,
+        options: &IngestOptions,
+    ) -> Result<Option<IngestedDocument>> {
+        let bytes = tokio::fs::read(path)
+            .await
+            .with_context(|| format!("Failed to read file: {}", path.display()))?;
+        self.ingest_bytes(path, bytes, pipeline, options).await
+    }
+
+    /// Like [`Self::ingest_document`] for a file that has already been read
+    pub async fn ingest_bytes(
+        &self,
+        path: &Path,
+        bytes: Vec<u8>,
+        pipeline: &IngestPipeline,
+        options: &IngestOptions,
+    ) -> Result<Option<IngestedDocument>> {
+        let extractors = pipeline.extractors.clone();
+        let extract_path = path.to_path_buf();
+        let extracted =
+            tokio::task::spawn_blocking(move || extractors.extract(&extract_path, &bytes))
+                .await
+                .context("spawn_blocking task panicked")??;
+
+        let source = Provenance::new(SourceType::Document, path.display().to_string());
+        let mut replaced = 0;
+        for previous in self.documents_from_source(&source).await? {
+            self.remove_document(&previous).await?;
+            replaced += 1;
+        }
+
+        let content = &extracted.text;
+        if content.trim().is_empty() {
+            return Ok(None);
+        }
+
+        let filename = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| "unknown".to_string());
+        let doc_title = options
+            .title
+            .as_deref()
+            .or(extracted.title.as_deref())
+            .unwrap_or(&filename)
+            .to_string();
+        let content_type = extracted.content_type.as_str();
+
+        info!(
+            "Ingesting document: {} ({} chars, {})",
+            doc_title,
+            content.len(),
+            content_type
+        );
+
+        // Chunk the document along its structure and note where each chunk
+        // comes from. `#` lines in source files are comments, not headings.
+        let strategy = ChunkStrategy::for_content_type(content_type);
+        let mut chunks = chunk_with_strategy(content, strategy, &pipeline.chunking);
+        if !matches!(strategy, ChunkStrategy::Code(_)) {
+            annotate_chunks(&mut chunks, &extracted.locations);
+        }
+
+        let mut doc_metadata = serde_json::json!({
+            "source_path": path.display().to_string(),
+            "content_type": content_type,
+            "total_chars": content.len(),
+            "chunk_count": chunks.len(),
+            "tags": options.tags,
+        });
+        if let Some(corpus) = &options.corpus {
+            doc_metadata["corpus"] = serde_json::json!(corpus);
+        }
+        if let Some(fields) = doc_metadata.as_object_mut() {
+            for (key, value) in &extracted.metadata {
+                fields.entry(key.clone()).or_insert_with(|| value.clone());
+            }
+        }
+
+        let doc_id = self
+            .add_entity(&doc_title, DOCUMENT_TYPE, Some(doc_metadata))
+            .await
+            .context("Failed to create document entity")?;
+        self.add_provenance(FactType::Entity, &doc_id, &source)
+            .await
+            .context("Failed to record document source")?;
+
+        // Index each chunk as a child entity linked to the document
+        let mut chunk_ids = Vec::new();
+        for chunk in &chunks {
+            let citation = chunk.citation();
+            let chunk_name = match &citation {
+                Some(c) => format!(
+                    "{} [chunk {}/{}, {}]",
+                    doc_title,
+                    chunk.chunk_index + 1,
+                    chunk.total_chunks,
+                    c
+                ),
+                None => format!(
+                    "{} [chunk {}/{}]",
+                    doc_title,
+                    chunk.chunk_index + 1,
+                    chunk.total_chunks
+                ),
+            };
+
+            let mut chunk_metadata = serde_json::json!({
+                "full_content": chunk.content,
+                "chunk_index": chunk.chunk_index,
+                "start_offset": chunk.start_offset,
+                "end_offset": chunk.end_offset,
+                "total_chunks": chunk.total_chunks,
+                "parent_document": doc_id,
+            });
+            if let Some(citation) = citation {
+                chunk_metadata["citation"] = serde_json::json!(citation);
+                chunk_metadata["page"] = serde_json::json!(chunk.page);
+                chunk_metadata["section"] = serde_json::json!(chunk.section);
+            }
+
+            let chunk_id = self
+                .add_entity(&chunk_name, CHUNK_TYPE, Some(chunk_metadata))
+                .await
+                .context("Failed to create chunk entity")?;
+            self.link_entities(&doc_id, &chunk_id, "contains_chunk", None)
+                .await
+                .context("Failed to link chunk to document")?;
+            chunk_ids.push(chunk_id);
+        }
+
+        // Link consecutive chunks
+        for window in chunk_ids.windows(2) {
+            let _ = self
+                .link_entities(&window[0], &window[1], "next_chunk", None)
+                .await;
+        }
+
+        Ok(Some(IngestedDocument {
+            document_id: doc_id,
+            metadata: DocumentMetadata {
+                source_path: Some(path.display().to_string()),
+                title: Some(doc_title),
+                content_type: content_type.to_string(),
+                total_chars: content.len(),
+                chunk_count: chunks.len(),
+            },
+            replaced,
+        }))
+    }
+
+    /// IDs of the documents ingested from a source
+    async fn documents_from_source(&self, source: &Provenance) -> Result<Vec<String>> {
+        let mut ids = Vec::new();
+        for record in self.db.get_facts_from_source(source).await? {
+            if record.fact_type != FactType::Entity {
+                continue;
+            }
+            if let Some(entity) = self.db.get_entity(&record.fact_id).await?
+                && entity.entity_type == DOCUMENT_TYPE
+            {
+                ids.push(entity.id);
+            }
+        }
+        Ok(ids)
+    }
+
+    /// Delete a document and its chunks. Returns the number of entities deleted.
+    pub async fn remove_document(&self, document_id: &str) -> Result<usize> {
+        let chunk_ids: Vec<String> = self
+            .db
+            .get_relationships_for(document_id)
+            .await?
+            .into_iter()
+            .filter(|r| r.relation_type == "contains_chunk" && r.source_id == document_id)
+            .map(|r| r.target_id)
+            .collect();
+
+        let mut deleted = 0;
+        for id in chunk_ids.iter().map(String::as_str).chain([document_id]) {
+            if self.db.delete_entity(id).await? {
+                deleted += 1;
+            }
+        }
+        if deleted > 0 {
+            self.index_pending();
+            debug!("Removed document {} ({} entities)", document_id, deleted);
+        }
+        Ok(deleted)
+    }
+
+    /// Register a directory as a corpus. It is not ingested until synced.
+    pub async fn add_corpus(
+        &self,
+        name: &str,
+        root: &Path,
+        include: Vec<String>,
+        exclude: Vec<String>,
+    ) -> Result<Corpus> {
+        if self.db.get_corpus(name).await?.is_some() {
+            anyhow::bail!("A corpus named '{}' already exists", name.trim());
+        }
+        let corpus = Corpus::new(name, root, include, exclude)?;
+        self.db.insert_corpus(&corpus).await?;
+        info!(
+            "Registered corpus {} at {}",
+            corpus.name,
+            corpus.root.display()
+        );
+        Ok(corpus)
+    }
+
+    /// Look up a corpus by name or ID
+    pub async fn get_corpus(&self, name_or_id: &str) -> Result<Option<Corpus>> {
+        self.db.get_corpus(name_or_id).await
+    }
+
+    /// All registered corpora
+    pub async fn list_corpora(&self) -> Result<Vec<Corpus>> {
+        self.db.list_corpora().await
+    }
+
+    /// Files ingested from a corpus
+    pub async fn list_corpus_files(&self, corpus: &Corpus) -> Result<Vec<CorpusFile>> {
+        self.db.list_corpus_files(&corpus.id).await
+    }
+
+    /// Unregister a corpus, deleting its documents unless `keep_documents`.
+    /// Returns the number of documents deleted.
+    pub async fn remove_corpus(&self, corpus: &Corpus, keep_documents: bool) -> Result<usize> {
+        let mut removed = 0;
+        if !keep_documents {
+            for file in self.db.list_corpus_files(&corpus.id).await? {
+                // The file may have been re-ingested directly since the last sync
+                let source = Provenance::new(SourceType::Document, file.path.clone());
+                let mut documents = self.documents_from_source(&source).await?;
+                if !file.document_id.is_empty() && !documents.contains(&file.document_id) {
+                    documents.push(file.document_id.clone());
+                }
+                for document in documents {
+                    if self.remove_document(&document).await? > 0 {
+                        removed += 1;
+                    }
+                }
+            }
+        }
+        self.db.delete_corpus(&corpus.id).await?;
+        info!(
+            "Removed corpus {} ({} documents deleted)",
+            corpus.name, removed
+        );
+        Ok(removed)
+    }
+
+    /// Bring a corpus fully in line with its directory.
+    ///
+    /// New files are ingested, files whose content hash changed are
+    /// re-ingested (replacing their old chunks), unchanged files are skipped
+    /// and files that no longer exist or match are removed.
+    pub async fn sync_corpus(
+        &self,
+        corpus: &Corpus,
+        pipeline: &IngestPipeline,
+    ) -> Result<CorpusSyncReport> {
+        let scan_corpus = corpus.clone();
+        let files = tokio::task::spawn_blocking(move || scan_corpus.scan())
+            .await
+            .context("spawn_blocking task panicked")??;
+
+        let mut report = CorpusSyncReport::default();
+        let mut known: HashMap<String, CorpusFile> = self
+            .db
+            .list_corpus_files(&corpus.id)
+            .await?
+            .into_iter()
+            .map(|f| (f.path.clone(), f))
+            .collect();
+
+        for path in &files {
+            let previous = known.remove(&path.display().to_string());
+            self.sync_corpus_file(corpus, path, previous, pipeline, &mut report)
+                .await?;
+        }
+        for (_, stale) in known {
+            self.forget_corpus_file(corpus, stale, &mut report).await?;
+        }
+
+        self.db.set_corpus_synced(&corpus.id, Utc::now()).await?;
+        info!("Synced corpus {}: {}", corpus.name, report);
+        Ok(report)
+    }
+
+    /// Sync only the given paths of a corpus, e.g. after file events.
+    ///
+    /// Paths may be files or directories; anything that no longer exists
+    /// (or no longer matches the corpus patterns) is removed.
+    pub async fn sync_corpus_paths(
+        &self,
+        corpus: &Corpus,
+        paths: &[std::path::PathBuf],
+        pipeline: &IngestPipeline,
+    ) -> Result<CorpusSyncReport> {
+        let mut report = CorpusSyncReport::default();
+        let mut known: HashMap<String, CorpusFile> = self
+            .db
+            .list_corpus_files(&corpus.id)
+            .await?
+            .into_iter()
+            .map(|f| (f.path.clone(), f))
+            .collect();
+
+        for path in paths {
+            if path.is_file() && corpus.matches(path) {
+                let previous = known.remove(&path.display().to_string());
+                self.sync_corpus_file(corpus, path, previous, pipeline, &mut report)
+                    .await?;
+                continue;
+            }
+            // Deleted or renamed files and directories
+            let stale: Vec<String> = known
+                .keys()
+                .filter(|k| {
+                    let known_path = Path::new(k.as_str());
+                    known_path.starts_with(path)
+                        && !(known_path.is_file() && corpus.matches(known_path))
+                })
+                .cloned()
+                .collect();
+            for key in stale {
+                if let Some(file) = known.remove(&key) {
+                    self.forget_corpus_file(corpus, file, &mut report).await?;
+                }
+            }
+        }
+
+        if report.changed() {
+            info!("Synced corpus {}: {}", corpus.name, report);
+        }
+        Ok(report)
+    }
+
+    async fn sync_corpus_file(
+        &self,
+        corpus: &Corpus,
+        path: &Path,
+        previous: Option<CorpusFile>,
+        pipeline: &IngestPipeline,
+        report: &mut CorpusSyncReport,
+    ) -> Result<()> {
+        let key = path.display().to_string();
+        let bytes = match tokio::fs::metadata(path).await {
+            Ok(meta) if meta.len() > MAX_FILE_BYTES => {
+                report
+                    .failed
+                    .push((key, format!("larger than {} bytes", MAX_FILE_BYTES)));
+                return Ok(());
+            }
+            Ok(_) => tokio::fs::read(path).await,
+            Err(e) => Err(e),
+        };
+        let bytes = match bytes {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                report.failed.push((key, e.to_string()));
+                return Ok(());
+            }
+        };
+
+        // Unchanged only if the content matches and everything ingested from
+        // it is still there (entities can be deleted or archived meanwhile)
+        let hash = corpus::content_hash(&bytes);
+        if let Some(previous) = &previous
+            && previous.content_hash == hash
+            && (previous.document_id.is_empty()
+                || (self.db.get_entity(&previous.document_id).await?.is_some()
+                    && self.db.count_document_chunks(&previous.document_id).await?
+                        == previous.chunk_count))
+        {
+            report.unchanged += 1;
+            return Ok(());
+        }
+
+        let options = IngestOptions {
+            corpus: Some(corpus.name.clone()),
+            ..Default::default()
+        };
+        let ingested = match self.ingest_bytes(path, bytes, pipeline, &options).await {
+            Ok(ingested) => ingested,
+            Err(e) => {
+                warn!("Failed to ingest {}: {:#}", key, e);
+                report.failed.push((key, format!("{:#}", e)));
+                return Ok(());
+            }
+        };
+        if let Some(previous) = &previous
+            && !previous.document_id.is_empty()
+        {
+            self.remove_document(&previous.document_id).await?;
+        }
+
+        self.db
+            .upsert_corpus_file(&CorpusFile {
+                corpus_id: corpus.id.clone(),
+                path: key.clone(),
+                content_hash: hash,
+                document_id: ingested
+                    .as_ref()
+                    .map(|d| d.document_id.clone())
+                    .unwrap_or_default(),
+                chunk_count: ingested
+                    .as_ref()
+                    .map(|d| d.metadata.chunk_count)
+                    .unwrap_or(0),
+                ingested_at: Utc::now(),
+            })
+            .await?;
+        if previous.is_some() {
+            report.updated.push(key);
+        } else {
+            report.added.push(key);
+        }
+        Ok(())
+    }
+
+    async fn forget_corpus_file(
+        &self,
+        corpus: &Corpus,
+        file: CorpusFile,
+        report: &mut CorpusSyncReport,
+    ) -> Result<()> {
+        if !file.document_id.is_empty() {
+            self.remove_document(&file.document_id).await?;
+        }
+        self.db.delete_corpus_file(&corpus.id, &file.path).await?;
+        report.removed.push(file.path);
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -384,51 +2233,16 @@ mod tests {
         // Remember something
         let id = graph
             .remember(
-                "Rust is a systems programming language focused on safety and performance",
-                "fact",
-                Some("test_channel"),
-            )
-            .await?;
-        assert!(!id.is_empty());
-
-        // Recall it
-        let contexts = graph.recall("Rust systems programming", 10).await?;
-        assert!(!contexts.is_empty());
-
-        let _ = std::fs::remove_file(&db_path);
-        let _ = std::fs::remove_dir_all(&index_path);
-        Ok(())
-    }
-
-    #[tokio::test]
-    async fn test_watcher_operations() -> Result<()> {
-        let temp_dir = env::temp_dir();
-        let db_path = temp_dir.join("test_graph_watcher.db");
-        let index_path = temp_dir.join("test_graph_watcher_index");
-
-        let _ = std::fs::remove_file(&db_path);
-        let _ = std::fs::remove_dir_all(&index_path);
-
-        let graph = KnowledgeGraph::new(&db_path, &index_path)?;
-
-        // Create watcher
-        let config = serde_json::json!({"path": "/test/path", "pattern": "*.rs"});
-        let watcher_id = graph
-            .create_watcher("file", config, "notify", "test_channel")
+                "Rust is a systems programming language focused on safety and performance",
+                "fact",
+                Some("test_channel"),
+            )
             .await?;
-        assert!(!watcher_id.is_empty());
-
-        // Get active watchers
-        let watchers = graph.get_active_watchers().await?;
-        assert_eq!(watchers.len(), 1);
-
-        // Deactivate watcher
-        graph.update_watcher(&watcher_id, false).await?;
-        let watchers = graph.get_active_watchers().await?;
-        assert_eq!(watchers.len(), 0);
+        assert!(!id.is_empty());
 
-        // Delete watcher
-        graph.delete_watcher(&watcher_id).await?;
+        // Recall it
+        let contexts = graph.recall("Rust systems programming", 10).await?;
+        assert!(!contexts.is_empty());
 
         let _ = std::fs::remove_file(&db_path);
         let _ = std::fs::remove_dir_all(&index_path);
@@ -636,6 +2450,7 @@ mod tests {
             related_entities: vec![],
             relationships: vec![],
             recent_conversations: vec![],
+            provenance: vec![],
         };
         let debug = format!("{:?}", ctx);
         assert!(debug.contains("Test"));
@@ -655,40 +2470,930 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_update_and_delete_watcher() -> Result<()> {
+    async fn test_search_full_text() -> Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        let graph = KnowledgeGraph::new(temp.path().join("t.db"), temp.path().join("idx"))?;
+
+        graph
+            .add_entity("Rust Programming", "language", None)
+            .await?;
+        graph
+            .add_entity("Python Programming", "language", None)
+            .await?;
+
+        let results = graph.search("Rust", 10)?;
+        assert!(!results.is_empty());
+        assert!(results.iter().any(|r| r.content.contains("Rust")));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_add_entity_resolves_exact_duplicate() -> Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        let graph = KnowledgeGraph::new(temp.path().join("t.db"), temp.path().join("idx"))?;
+
+        let first = graph
+            .add_entity("Alice", "person", Some(serde_json::json!({"role": "eng"})))
+            .await?;
+        let second = graph
+            .add_entity(
+                "alice (coworker)",
+                "person",
+                Some(serde_json::json!({"team": "infra"})),
+            )
+            .await?;
+        assert_eq!(first, second);
+
+        let entity = graph.get_entity(&first).await?.unwrap();
+        let meta = entity.metadata.unwrap();
+        assert_eq!(meta["role"], "eng");
+        assert_eq!(meta["team"], "infra");
+        assert_eq!(meta["aliases"][0], "alice (coworker)");
+
+        // Different type is not resolved
+        let other = graph.add_entity("Alice", "project", None).await?;
+        assert_ne!(first, other);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_find_duplicates_uses_attached_vectors() -> Result<()> {
+        struct FixedProvider;
+        impl EmbeddingProvider for FixedProvider {
+            fn embed(&self, _text: &str) -> Result<Vec<f32>> {
+                Ok(vec![1.0, 0.0, 0.0])
+            }
+            fn dimensions(&self) -> usize {
+                3
+            }
+        }
+
+        let temp = tempfile::TempDir::new()?;
+        let index = Arc::new(VectorIndex::new(3));
+        let graph = KnowledgeGraph::new(temp.path().join("t.db"), temp.path().join("idx"))?
+            .with_vectors(index.clone(), Arc::new(FixedProvider));
+
+        let acme = graph
+            .add_entity("Acme Corporation", "company", None)
+            .await?;
+        index.insert(&acme, vec![1.0, 0.0, 0.0])?;
+
+        // No shared name tokens, so only the embedding can find it
+        let candidates = graph
+            .find_duplicates("The widget company", "company")
+            .await?;
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(candidates[0].entity.id, acme);
+        assert_eq!(candidates[0].reason, MatchReason::Embedding);
+        assert!(
+            graph
+                .find_duplicates("The widget company", "person")
+                .await?
+                .is_empty()
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_resolution_disabled_keeps_duplicates() -> Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        let graph = KnowledgeGraph::new(temp.path().join("t.db"), temp.path().join("idx"))?
+            .with_resolution(ResolutionConfig {
+                enabled: false,
+                ..Default::default()
+            });
+
+        let a = graph.add_entity("Alice", "person", None).await?;
+        let b = graph.add_entity("alice", "person", None).await?;
+        assert_ne!(a, b);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_merge_entities_repoints_relationships() -> Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        let graph = KnowledgeGraph::new(temp.path().join("t.db"), temp.path().join("idx"))?
+            .with_resolution(ResolutionConfig {
+                resolve_on_insert: false,
+                ..Default::default()
+            });
+
+        let alice = graph.add_entity("Alice Smith", "person", None).await?;
+        let dup = graph.add_entity("A. Smith", "person", None).await?;
+        let acme = graph.add_entity("Acme", "company", None).await?;
+        graph.link_entities(&alice, &acme, "works_at", None).await?;
+        graph.link_entities(&dup, &acme, "works_at", None).await?;
+        graph.link_entities(&dup, &alice, "same_as", None).await?;
+
+        let kept = graph
+            .merge_entities(&alice, &dup, MatchReason::Manual, 1.0)
+            .await?;
+        assert_eq!(kept.id, alice);
+        assert!(graph.get_entity(&dup).await?.is_none());
+
+        let rels = graph.get_relationships(&alice).await?;
+        assert_eq!(rels.len(), 1);
+        assert_eq!(rels[0].target_id, acme);
+
+        let meta = kept.metadata.unwrap();
+        assert_eq!(meta["merged_from"][0]["id"], dup.as_str());
+        assert_eq!(meta["merged_from"][0]["reason"], "manual");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_run_entity_resolution() -> Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        let graph = KnowledgeGraph::new(temp.path().join("t.db"), temp.path().join("idx"))?
+            .with_resolution(ResolutionConfig {
+                resolve_on_insert: false,
+                ..Default::default()
+            });
+
+        graph.add_entity("Bob", "person", None).await?;
+        graph.add_entity("bob", "person", None).await?;
+        graph.add_entity("Bob Jones", "person", None).await?;
+        graph.add_entity("Carol", "person", None).await?;
+
+        let preview = graph.run_entity_resolution(None, true).await?;
+        assert!(preview.merged.is_empty());
+        assert!(!preview.pending.is_empty());
+
+        let report = graph.run_entity_resolution(None, false).await?;
+        assert_eq!(report.merged.len(), 1);
+        assert_eq!(report.merged[0].reason, MatchReason::ExactName);
+        assert!(report.failed.is_empty());
+        assert_eq!(graph.get_all_entities().await?.len(), 3);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_run_entity_resolution_reports_failed_merges() -> Result<()> {
+        /// Agrees to every merge, but deletes any "Erin" duplicate first
+        struct DeletingConfirmer(Arc<KnowledgeDb>);
+
+        #[async_trait::async_trait]
+        impl MergeConfirmer for DeletingConfirmer {
+            async fn confirm(&self, _a: &Entity, b: &Entity) -> Result<bool> {
+                if b.name.starts_with("Erin") {
+                    self.0.delete_entity(&b.id).await?;
+                }
+                Ok(true)
+            }
+        }
+
+        let temp = tempfile::TempDir::new()?;
+        let graph = KnowledgeGraph::new(temp.path().join("t.db"), temp.path().join("idx"))?
+            .with_resolution(ResolutionConfig {
+                resolve_on_insert: false,
+                // Every pair goes through the confirmer
+                auto_merge_threshold: 1.1,
+                ..Default::default()
+            });
+
+        graph.add_entity("Dana", "person", None).await?;
+        graph.add_entity("dana", "person", None).await?;
+        // A fuzzy pair the confirmer sabotages
+        graph.add_entity("Erin Walsh", "person", None).await?;
+        graph
+            .add_entity("Erin Walsh (neighbour)", "person", None)
+            .await?;
+
+        let confirmer = DeletingConfirmer(graph.db());
+        let report = graph.run_entity_resolution(Some(&confirmer), false).await?;
+        assert_eq!(report.merged.len(), 1);
+        assert_eq!(report.failed.len(), 1);
+        assert_eq!(report.failed[0].merged_name, "Erin Walsh (neighbour)");
+        assert!(report.failed[0].error.contains("not found"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_link_entities_supersedes_and_recalls_as_of() -> Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        let graph = KnowledgeGraph::new(temp.path().join("t.db"), temp.path().join("idx"))?;
+
+        let bob = graph.add_entity("Bob", "person", None).await?;
+        let acme = graph.add_entity("Acme", "company", None).await?;
+        let globex = graph.add_entity("Globex", "company", None).await?;
+
+        let first = graph.link_entities(&bob, &acme, "works_at", None).await?;
+        let between = Utc::now();
+        tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+        let second = graph
+            .record_fact(
+                &bob,
+                &globex,
+                "works_at",
+                None,
+                FactOptions::for_relation("works_at").with_provenance(
+                    Provenance::new(SourceType::Email, "<msg-7@example.com>")
+                        .with_excerpt("Starting at Globex on Monday"),
+                ),
+            )
+            .await?;
+        assert_eq!(second.superseded, vec![first.clone()]);
+
+        // Current context only shows the new job, with its source
+        let now = graph.get_context_for(&bob).await?;
+        assert_eq!(now.relationships.len(), 1);
+        assert_eq!(now.related_entities[0].name, "Globex");
+        assert_eq!(now.provenance.len(), 1);
+        assert_eq!(now.provenance[0].source.source_id, "<msg-7@example.com>");
+
+        // Before the change, Bob worked at Acme and the email wasn't known yet
+        let then = graph.get_context_as_of(&bob, between).await?;
+        assert_eq!(then.relationships.len(), 1);
+        assert_eq!(then.relationships[0].id, first);
+        assert!(then.provenance.is_empty());
+
+        let recalled = graph.recall_as_of("Bob", 5, between).await?;
+        assert!(recalled.iter().any(|c| c.entity.id == bob));
+        Ok(())
+    }
+
+    /// Graph whose memories decay instantly, so retention can be tested
+    /// without waiting: unused, unlinked entities score zero.
+    fn decaying_graph(temp: &tempfile::TempDir) -> Result<KnowledgeGraph> {
+        Ok(
+            KnowledgeGraph::new(temp.path().join("t.db"), temp.path().join("idx"))?
+                .with_importance(ImportanceConfig {
+                    half_life_days: 0.0,
+                    ..Default::default()
+                }),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_recall_prefers_important_entities() -> Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        let graph = KnowledgeGraph::new(temp.path().join("t.db"), temp.path().join("idx"))?
+            .with_importance(ImportanceConfig {
+                recall_weight: 0.9,
+                ..Default::default()
+            });
+
+        let stale = graph.add_entity("Tokyo trip notes", "fact", None).await?;
+        let used = graph.add_entity("Tokyo trip budget", "fact", None).await?;
+        graph.pin(&used, true).await?;
+
+        let results = graph.search_ranked("Tokyo trip", 2).await?;
+        assert_eq!(results[0].id, used);
+        assert_eq!(results[1].id, stale);
+
+        // Recall counts as access
+        let stats = graph
+            .db()
+            .get_entity_stats(std::slice::from_ref(&used))
+            .await?;
+        assert_eq!(stats[0].access_count, 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_apply_retention_never_forgets_pinned() -> Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        let graph = decaying_graph(&temp)?;
+
+        let trivia = graph
+            .add_entity("Lunch was a sandwich", "fact", None)
+            .await?;
+        let pinned = graph.add_entity("Passport number", "fact", None).await?;
+        let linked = graph.add_entity("Alice", "person", None).await?;
+        let friend = graph.add_entity("Bob", "person", None).await?;
+        graph.link_entities(&linked, &friend, "knows", None).await?;
+        graph.pin(&pinned, true).await?;
+
+        let policy = RetentionPolicy {
+            min_age_days: 0,
+            threshold: 0.05,
+            ..Default::default()
+        };
+        let report = graph.apply_retention(&policy, true).await?;
+        assert!(report.dry_run);
+        assert_eq!(report.examined, 4);
+        assert_eq!(report.pinned_skipped, 1);
+        assert_eq!(report.forgotten.len(), 1);
+        assert_eq!(report.forgotten[0].entity_id, trivia);
+        assert!(graph.get_entity(&trivia).await?.is_some());
+
+        let report = graph.apply_retention(&policy, false).await?;
+        assert_eq!(report.forgotten.len(), 1);
+        assert!(graph.get_entity(&trivia).await?.is_none());
+        assert!(graph.get_entity(&pinned).await?.is_some());
+        assert!(graph.get_entity(&linked).await?.is_some());
+        assert!(graph.search("sandwich", 5)?.is_empty());
+
+        let archived = graph.list_archived(10).await?;
+        assert_eq!(archived.len(), 1);
+        graph.restore(&trivia).await?.unwrap();
+        assert_eq!(graph.search("sandwich", 5)?[0].id, trivia);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_apply_retention_delete() -> Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        let graph = decaying_graph(&temp)?;
+
+        let trivia = graph.add_entity("Old weather", "fact", None).await?;
+
+        let policy = RetentionPolicy {
+            action: RetentionAction::Delete,
+            min_age_days: 0,
+            ..Default::default()
+        };
+        let report = graph.apply_retention(&policy, false).await?;
+        assert_eq!(report.forgotten.len(), 1);
+        assert!(graph.get_entity(&trivia).await?.is_none());
+        assert!(graph.list_archived(10).await?.is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_encrypted_graph_rebuilds_index_in_memory() -> Result<()> {
+        if !crate::encryption::sqlcipher_available() {
+            return Ok(());
+        }
+        let temp = tempfile::TempDir::new()?;
+        let db_path = temp.path().join("t.db");
+        {
+            let graph = KnowledgeGraph::new(&db_path, temp.path().join("idx"))?;
+            graph.add_entity("Safe deposit box", "fact", None).await?;
+        }
+
+        crate::encryption::encrypt_database(&db_path, "k1")?;
+        assert!(KnowledgeDb::new(&db_path).is_err());
+        assert!(
+            KnowledgeGraph::new_encrypted(&db_path, "wrong")
+                .await
+                .is_err()
+        );
+
+        let graph = KnowledgeGraph::new_encrypted(&db_path, "k1").await?;
+        assert_eq!(graph.search("deposit", 5)?.len(), 1);
+        graph.db().rekey("k2").await?;
+        drop(graph);
+
+        let graph = KnowledgeGraph::new_encrypted(&db_path, "k2").await?;
+        assert_eq!(graph.get_all_entities().await?.len(), 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_remember_records_conversation_provenance() -> Result<()> {
         let temp = tempfile::TempDir::new()?;
         let graph = KnowledgeGraph::new(temp.path().join("t.db"), temp.path().join("idx"))?;
 
         let id = graph
-            .create_watcher("email", serde_json::json!({}), "alert", "slack")
+            .remember("Dentist appointment on Friday", "fact", Some("imessage"))
             .await?;
+        let context = graph.get_context_for(&id).await?;
+        assert_eq!(context.provenance.len(), 1);
+        assert_eq!(
+            context.provenance[0].source.source_type,
+            SourceType::Conversation
+        );
+        assert_eq!(
+            context.provenance[0].source.source_id,
+            context.recent_conversations[0].id
+        );
+        Ok(())
+    }
 
-        let watchers = graph.get_active_watchers().await?;
-        assert_eq!(watchers.len(), 1);
+    #[tokio::test]
+    async fn test_open_rebuilds_index_after_schema_change() -> Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        let db_path = temp.path().join("t.db");
+        let index_path = temp.path().join("idx");
+        {
+            let graph = KnowledgeGraph::new(&db_path, &index_path)?;
+            graph.add_entity("Espresso machine", "thing", None).await?;
+        }
 
-        graph.update_watcher(&id, false).await?;
-        let watchers = graph.get_active_watchers().await?;
-        assert!(watchers.is_empty());
+        // Switching language recreates the index; entities are reindexed on open
+        let graph = KnowledgeGraph::open(&db_path, &index_path, SearchLanguage::Italian)?;
+        assert_eq!(graph.search("espresso", 5)?.len(), 1);
 
-        graph.delete_watcher(&id).await?;
+        // Losing the index entirely is recovered the same way
+        drop(graph);
+        std::fs::remove_dir_all(&index_path)?;
+        let graph = KnowledgeGraph::new(&db_path, &index_path)?;
+        assert_eq!(graph.search("espresso", 5)?.len(), 1);
         Ok(())
     }
 
     #[tokio::test]
-    async fn test_search_full_text() -> Result<()> {
+    async fn test_search_with_filters() -> Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        let graph = KnowledgeGraph::new(temp.path().join("t.db"), temp.path().join("idx"))?;
+        graph
+            .add_entity(
+                "Rust meetup",
+                "event",
+                Some(serde_json::json!({"tags": ["community"]})),
+            )
+            .await?;
+        graph.add_entity("Rust", "language", None).await?;
+
+        let events = SearchOptions {
+            entity_types: vec!["event".to_string()],
+            ..Default::default()
+        };
+        let results = graph.search_ranked_with("rust", &events, 5).await?;
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].entity_type, "event");
+
+        let community = SearchOptions {
+            tags: vec!["community".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(graph.search_with("rust", &community, 5)?.len(), 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_unindexed_changes_recovered_on_open() -> Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        let db_path = temp.path().join("t.db");
+        let index_path = temp.path().join("idx");
+        {
+            let graph = KnowledgeGraph::new(&db_path, &index_path)?;
+            graph.add_entity("Lighthouse", "place", None).await?;
+            // Written to SQLite only, as if the process died before indexing
+            graph.db().insert_entity("Harbour", "place", None).await?;
+            assert!(graph.search("harbour", 5)?.is_empty());
+        }
+
+        let graph = KnowledgeGraph::new(&db_path, &index_path)?;
+        assert_eq!(graph.search("harbour", 5)?.len(), 1);
+        assert_eq!(graph.search("lighthouse", 5)?.len(), 1);
+        assert_eq!(
+            graph
+                .db()
+                .pending_index_count(outbox::TANTIVY_CONSUMER)
+                .await?,
+            0
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_check_consistency_reports_and_repairs_drift() -> Result<()> {
         let temp = tempfile::TempDir::new()?;
         let graph = KnowledgeGraph::new(temp.path().join("t.db"), temp.path().join("idx"))?;
+        graph.add_entity("Lighthouse", "place", None).await?;
+        assert!(graph.check_consistency(false).await?.is_consistent());
 
+        let unindexed = graph.db().insert_entity("Harbour", "place", None).await?;
         graph
-            .add_entity("Rust Programming", "language", None)
+            .index
+            .index_document("ghost", "Ghost ship", "place", "")?;
+
+        let report = graph.check_consistency(false).await?;
+        assert_eq!(report.pending_changes, 1);
+        assert_eq!(report.missing_from_index, vec![unindexed]);
+        assert_eq!(report.orphaned_in_index, vec!["ghost".to_string()]);
+        assert!(!report.repaired);
+
+        let report = graph.check_consistency(true).await?;
+        assert!(report.repaired);
+        assert!(graph.check_consistency(false).await?.is_consistent());
+        assert_eq!(graph.search("harbour", 5)?.len(), 1);
+        assert!(graph.search("ghost", 5)?.is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sync_vectors_follows_outbox() -> Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        let graph = KnowledgeGraph::new(temp.path().join("t.db"), temp.path().join("idx"))?;
+        let provider = crate::embeddings::NoOpEmbeddingProvider::new(4);
+        let vectors = VectorIndex::new(4);
+
+        graph.add_entity("Lighthouse", "place", None).await?;
+        assert_eq!(graph.rebuild_vectors(&vectors, &provider)?, 1);
+
+        let harbour = graph.add_entity("Harbour", "place", None).await?;
+        assert_eq!(graph.sync_vectors(&vectors, &provider)?, 1);
+        assert_eq!(vectors.len(), 2);
+
+        graph.delete_entity(&harbour).await?;
+        graph.sync_vectors(&vectors, &provider)?;
+        assert_eq!(vectors.len(), 1);
+        assert_eq!(graph.sync_vectors(&vectors, &provider)?, 0);
+        Ok(())
+    }
+
+    struct TripExtractor;
+
+    #[async_trait::async_trait]
+    impl MemoryExtractor for TripExtractor {
+        async fn extract(&self, episode: &Episode) -> Result<episodic::ExtractedMemories> {
+            assert!(episode.transcript().contains("alice: Book Casa Alfama"));
+            Ok(serde_json::from_value(serde_json::json!({
+                "entities": [
+                    {"name": "Casa Alfama", "entity_type": "hotel", "confidence": 0.9,
+                     "description": "Hotel in Lisbon", "evidence": "Book Casa Alfama"},
+                    {"name": "Alice", "entity_type": "person", "confidence": 0.9},
+                    {"name": "Maybe Porto", "entity_type": "place", "confidence": 0.2}
+                ],
+                "relationships": [
+                    {"source": "Alice", "relation_type": "stays at", "target": "Casa Alfama",
+                     "confidence": 0.8}
+                ],
+                "preferences": [
+                    {"category": "travel", "key": "seat", "value": "aisle", "confidence": 0.9}
+                ]
+            }))?)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_extract_episodic_memories() -> Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        let graph = KnowledgeGraph::new(temp.path().join("t.db"), temp.path().join("idx"))?;
+        graph.add_entity("alice", "person", None).await?;
+        for (sender, content) in [
+            (
+                "alice",
+                "Book Casa Alfama for Lisbon, and I always want an aisle seat",
+            ),
+            ("meepo", "Done, booked Casa Alfama and an aisle seat."),
+        ] {
+            graph
+                .db()
+                .insert_conversation_in_thread("slack", Some("T1"), sender, content, None)
+                .await?;
+        }
+        let config = ExtractionConfig {
+            idle_minutes: 0,
+            require_review: true,
+            ..Default::default()
+        };
+
+        // Review mode: known facts are dropped, the rest queued
+        let report = graph
+            .extract_episodic_memories(&TripExtractor, &config)
             .await?;
+        assert_eq!(report.threads, 1);
+        assert_eq!(report.low_confidence, 1);
+        assert_eq!((report.queued, report.duplicates), (3, 1));
+        assert_eq!(report.committed, 0);
+        let pending = graph
+            .list_memory_candidates(Some(CandidateStatus::Pending), 10)
+            .await?;
+        assert_eq!(pending.len(), 3);
+        assert!(pending.iter().all(|c| c.thread_id == "slack:T1"));
+
+        // Nothing new in the thread: nothing to do
+        let report = graph
+            .extract_episodic_memories(&TripExtractor, &config)
+            .await?;
+        assert_eq!(report.threads, 0);
+
+        // Accepting the relationship creates its missing endpoint with provenance
+        let rel = pending
+            .iter()
+            .find(|c| matches!(c.proposal, MemoryProposal::Relationship(_)))
+            .unwrap();
+        let rel_id = graph.accept_memory_candidate(&rel.id).await?;
+        assert!(graph.accept_memory_candidate(&rel.id).await.is_err());
+        let stored = graph.db().get_relationship(&rel_id).await?.unwrap();
+        assert_eq!(stored.relation_type, "stays_at");
+        let hotel = graph
+            .db()
+            .find_entity_by_name("Casa Alfama", "hotel")
+            .await?
+            .unwrap();
+        assert_eq!(stored.target_id, hotel.id);
+        let sources = graph
+            .db()
+            .get_provenance(std::slice::from_ref(&rel_id))
+            .await?;
+        assert_eq!(sources[0].source.source_type, SourceType::Conversation);
+        assert_eq!(sources[0].source.source_id, "slack:T1");
+
+        let pref = pending
+            .iter()
+            .find(|c| matches!(c.proposal, MemoryProposal::Preference(_)))
+            .unwrap();
+        assert!(graph.reject_memory_candidate(&pref.id).await?);
+        assert!(graph.db().get_preferences(Some("travel")).await?.is_empty());
+
+        // A new message makes the thread due again; without review the
+        // remaining new facts are committed directly
         graph
-            .add_entity("Python Programming", "language", None)
+            .db()
+            .insert_conversation_in_thread(
+                "slack",
+                Some("T1"),
+                "alice",
+                "Book Casa Alfama again",
+                None,
+            )
+            .await?;
+        graph
+            .db()
+            .insert_conversation_in_thread("slack", Some("T1"), "meepo", "Booked.", None)
+            .await?;
+        let config = ExtractionConfig {
+            idle_minutes: 0,
+            ..Default::default()
+        };
+        let report = graph
+            .extract_episodic_memories(&TripExtractor, &config)
             .await?;
+        assert_eq!(report.threads, 1);
+        assert_eq!((report.committed, report.duplicates), (1, 3));
+        let prefs = graph.db().get_preferences(Some("travel")).await?;
+        assert_eq!(prefs[0].value, serde_json::json!("aisle"));
+        assert_eq!(
+            prefs[0].learned_from.as_deref(),
+            Some("conversation:slack:T1")
+        );
+        Ok(())
+    }
 
-        let results = graph.search("Rust", 10)?;
-        assert!(!results.is_empty());
-        assert!(results.iter().any(|r| r.content.contains("Rust")));
+    struct CountingSummarizer(std::sync::atomic::AtomicUsize);
+
+    #[async_trait::async_trait]
+    impl CommunitySummarizer for CountingSummarizer {
+        async fn summarize(
+            &self,
+            community: &communities::CommunityContext,
+        ) -> Result<communities::CommunitySummary> {
+            self.0.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            let lead = &community.members[0].name;
+            Ok(communities::CommunitySummary {
+                title: format!("{} circle", lead),
+                summary: format!("Work around {} and the billing migration", lead),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_refresh_communities_is_incremental() -> Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        let graph = KnowledgeGraph::new(temp.path().join("t.db"), temp.path().join("idx"))?;
+        let mut ids = Vec::new();
+        for name in ["Alice", "Bob", "Carol", "Dave", "Erin", "Frank"] {
+            ids.push(graph.add_entity(name, "person", None).await?);
+        }
+        for (a, b) in [(0, 1), (1, 2), (0, 2), (3, 4), (4, 5), (3, 5), (2, 3)] {
+            graph
+                .link_entities(&ids[a], &ids[b], "works_with", None)
+                .await?;
+        }
+
+        let summarizer = CountingSummarizer(Default::default());
+        let report = graph.refresh_communities(Some(&summarizer)).await?;
+        assert_eq!((report.detected, report.created), (2, 2));
+        assert_eq!(summarizer.0.load(std::sync::atomic::Ordering::SeqCst), 2);
+
+        // Nothing changed: no summarizer calls
+        let report = graph.refresh_communities(Some(&summarizer)).await?;
+        assert_eq!(
+            (report.unchanged, report.created, report.updated),
+            (2, 0, 0)
+        );
+        assert_eq!(summarizer.0.load(std::sync::atomic::Ordering::SeqCst), 2);
+
+        // A new member joins one community: only that one is re-summarized
+        let grace = graph.add_entity("Grace", "person", None).await?;
+        graph
+            .link_entities(&grace, &ids[4], "works_with", None)
+            .await?;
+        graph
+            .link_entities(&grace, &ids[5], "works_with", None)
+            .await?;
+        let report = graph.refresh_communities(Some(&summarizer)).await?;
+        assert_eq!((report.unchanged, report.updated), (1, 1));
+        assert_eq!(summarizer.0.load(std::sync::atomic::Ordering::SeqCst), 3);
+
+        let stored = graph.list_communities().await?;
+        assert_eq!(stored.len(), 2);
+        assert_eq!(stored[0].members.len(), 4);
+        assert!(stored[0].members.contains(&grace));
+
+        // Summaries are searchable and feed the global view
+        let ranked = graph.global_search("billing migration", 5).await?;
+        assert_eq!(ranked.len(), 2);
+        assert!(ranked.iter().all(|(c, _)| c.summarized));
+        assert!(
+            graph
+                .run_entity_resolution(None, true)
+                .await?
+                .pending
+                .is_empty()
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sync_memory_file_both_directions() -> Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        let graph = KnowledgeGraph::new(temp.path().join("t.db"), temp.path().join("idx"))?;
+        let path = temp.path().join("MEMORY.md");
+        std::fs::write(&path, "# Memory\n\nWritten by hand.\n")?;
+        let config = MemorySyncConfig::default();
+
+        let coffee = graph
+            .add_entity(
+                "Dark roast coffee",
+                "preference",
+                Some(serde_json::json!({"note": "before noon"})),
+            )
+            .await?;
+        let desk = graph
+            .add_entity("Standing desk", "preference", None)
+            .await?;
+        graph.add_entity("Unimportant", "note", None).await?;
+        graph.pin(&coffee, true).await?;
+        graph.pin(&desk, true).await?;
+
+        // Graph -> file: pinned facts are written, free text is kept
+        let report = graph.sync_memory_file(&path, &config).await?;
+        assert!(report.file_written);
+        assert_eq!(report.entries, 2);
+        let text = std::fs::read_to_string(&path)?;
+        assert!(text.starts_with("# Memory\n\nWritten by hand.\n"));
+        assert!(text.contains(&format!(
+            "- Dark roast coffee — before noon <!-- id:{} -->",
+            coffee
+        )));
+        assert!(!text.contains("Unimportant"));
+
+        // Nothing changed: nothing written
+        assert!(!graph.sync_memory_file(&path, &config).await?.file_written);
+
+        // File -> graph: edit, add and delete bullets
+        let edited = text
+            .replace("before noon", "any time")
+            .replace(&format!("- Standing desk <!-- id:{} -->\n", desk), "")
+            .replace(
+                "<!-- meepo:memory:end -->",
+                "## Person\n\n- Alice — leads billing\n\n<!-- meepo:memory:end -->",
+            );
+        std::fs::write(&path, &edited)?;
+        let report = graph.sync_memory_file(&path, &config).await?;
+        assert_eq!(report.updated, vec![coffee.clone()]);
+        assert_eq!(report.created.len(), 1);
+        assert_eq!(report.archived, vec![desk.clone()]);
+        assert!(report.conflicts.is_empty());
+
+        let entity = graph.get_entity(&coffee).await?.unwrap();
+        assert_eq!(entity.metadata.unwrap()["note"], "any time");
+        let alice = graph.get_entity(&report.created[0]).await?.unwrap();
+        assert_eq!(
+            (alice.name.as_str(), alice.entity_type.as_str()),
+            ("Alice", "person")
+        );
+        assert!(graph.get_entity(&desk).await?.is_none());
+        let text = std::fs::read_to_string(&path)?;
+        assert!(text.contains(&format!("- Alice — leads billing <!-- id:{} -->", alice.id)));
+
+        // Both sides change the same fact: a conflict, resolved for the file
+        tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+        graph
+            .update_entity(&coffee, Some("Espresso"), None, None)
+            .await?;
+        std::fs::write(&path, text.replace("any time", "after lunch"))?;
+        let report = graph.sync_memory_file(&path, &config).await?;
+        assert_eq!(report.conflicts.len(), 1);
+        assert_eq!(report.conflicts[0].graph_version, "Espresso — any time");
+        assert_eq!(
+            report.conflicts[0].file_version,
+            "Dark roast coffee — after lunch"
+        );
+        let entity = graph.get_entity(&coffee).await?.unwrap();
+        assert_eq!(entity.name, "Dark roast coffee");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_corpus_survives_retention_and_restores_lost_chunks() -> Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        let graph = decaying_graph(&temp)?;
+        let docs = temp.path().join("docs");
+        std::fs::create_dir_all(&docs)?;
+        std::fs::write(docs.join("a.md"), "# Alpha\n\nFirst note.")?;
+        let pipeline = IngestPipeline::default();
+        let chunk_type = [CHUNK_TYPE.to_string()];
+
+        let corpus = graph.add_corpus("notes", &docs, vec![], vec![]).await?;
+        graph.sync_corpus(&corpus, &pipeline).await?;
+        let trivia = graph.add_entity("Old weather", "fact", None).await?;
+
+        // Never-read corpus entities score as low as trivia but are kept
+        let policy = RetentionPolicy {
+            min_age_days: 0,
+            ..Default::default()
+        };
+        let report = graph.apply_retention(&policy, false).await?;
+        assert_eq!(report.forgotten.len(), 1);
+        assert_eq!(report.forgotten[0].entity_id, trivia);
+        let chunks = graph.db.get_entities_by_type(&chunk_type, 10).await?;
+        assert_eq!(chunks.len(), 1);
+
+        // A chunk lost some other way is re-ingested even though the file
+        // itself did not change
+        graph.db.archive_entity(&chunks[0].id, 0.0).await?;
+        let report = graph.sync_corpus(&corpus, &pipeline).await?;
+        assert_eq!((report.updated.len(), report.unchanged), (1, 0));
+        assert_eq!(
+            graph.db.get_entities_by_type(&chunk_type, 10).await?.len(),
+            1
+        );
+        let report = graph.sync_corpus(&corpus, &pipeline).await?;
+        assert_eq!(report.unchanged, 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sync_corpus_tracks_changes() -> Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        let graph = KnowledgeGraph::new(temp.path().join("t.db"), temp.path().join("idx"))?;
+        let docs = temp.path().join("docs");
+        std::fs::create_dir_all(docs.join("drafts"))?;
+        std::fs::write(docs.join("a.md"), "# Alpha\n\nFirst note.")?;
+        std::fs::write(docs.join("b.md"), "# Beta\n\nSecond note.")?;
+        std::fs::write(docs.join("drafts/c.md"), "Draft.")?;
+        let pipeline = IngestPipeline::default();
+        let chunk_type = [CHUNK_TYPE.to_string()];
+        let doc_type = [DOCUMENT_TYPE.to_string()];
+
+        let corpus = graph
+            .add_corpus(
+                "notes",
+                &docs,
+                vec!["*.md".into()],
+                vec!["drafts/**".into()],
+            )
+            .await?;
+        assert!(
+            graph
+                .add_corpus("notes", &docs, vec![], vec![])
+                .await
+                .is_err()
+        );
+
+        let report = graph.sync_corpus(&corpus, &pipeline).await?;
+        assert_eq!((report.added.len(), report.unchanged), (2, 0));
+        assert_eq!(graph.db.get_entities_by_type(&doc_type, 10).await?.len(), 2);
+
+        // Nothing changed: nothing is re-ingested
+        let report = graph.sync_corpus(&corpus, &pipeline).await?;
+        assert!(!report.changed());
+        assert_eq!(report.unchanged, 2);
+
+        // Edit one, delete one, add one
+        std::fs::write(docs.join("a.md"), "# Alpha\n\nRewritten note.")?;
+        std::fs::remove_file(docs.join("b.md"))?;
+        std::fs::write(docs.join("d.md"), "Delta.")?;
+        let report = graph.sync_corpus(&corpus, &pipeline).await?;
+        assert_eq!(report.updated.len(), 1);
+        assert_eq!(report.removed.len(), 1);
+        assert_eq!(report.added.len(), 1);
+        let documents = graph.db.get_entities_by_type(&doc_type, 10).await?;
+        assert_eq!(documents.len(), 2);
+        let chunks = graph.db.get_entities_by_type(&chunk_type, 10).await?;
+        assert_eq!(chunks.len(), 2);
+        assert!(chunks.iter().any(|c| c.name == "Alpha [chunk 1/1, §Alpha]"));
+        assert!(chunks.iter().all(|c| {
+            !c.metadata.as_ref().unwrap()["full_content"]
+                .as_str()
+                .unwrap()
+                .contains("First note")
+        }));
+        assert_eq!(graph.list_corpus_files(&corpus).await?.len(), 2);
+
+        // Incremental sync of a deleted path
+        std::fs::remove_file(docs.join("d.md"))?;
+        let report = graph
+            .sync_corpus_paths(&corpus, &[corpus.root.join("d.md")], &pipeline)
+            .await?;
+        assert_eq!(report.removed.len(), 1);
+        assert_eq!(graph.db.get_entities_by_type(&doc_type, 10).await?.len(), 1);
+
+        // Ingesting the same file directly replaces its document
+        let path = corpus.root.join("a.md");
+        let again = graph
+            .ingest_document(&path, &pipeline, &IngestOptions::default())
+            .await?
+            .unwrap();
+        assert_eq!(again.replaced, 1);
+        assert_eq!(graph.db.get_entities_by_type(&doc_type, 10).await?.len(), 1);
+
+        assert_eq!(graph.remove_corpus(&corpus, false).await?, 1);
+        assert!(graph.get_corpus("notes").await?.is_none());
+        assert!(
+            graph
+                .db
+                .get_entities_by_type(&chunk_type, 10)
+                .await?
+                .is_empty()
+        );
         Ok(())
     }
 }
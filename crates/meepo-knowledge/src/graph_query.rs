@@ -0,0 +1,1060 @@
+//! Graph queries: paths, neighborhoods and pattern matching
+//!
+//! Queries walk the relationships currently in effect (ended relationships
+//! are ignored, see [`temporal`](crate::temporal)). Every query is bounded
+//! by [`QueryLimits`] so that results stay small enough for a prompt.
+//!
+//! Patterns are a chain of nodes joined by edges, in a Cypher-like syntax:
+//!
+//! ```text
+//! (p:person)-[works_at]->(:organization {name: "Acme"})
+//! (proj:project)<-[involved_in]-(p:person)-[works_at]->(o {name: "Acme"})
+//! (a {name: "Alice"})-[knows|works_with]-(b)
+//! ```
+//!
+//! A node is `(var:type|type {name: "..."})`, with every part optional.
+//! Names match case-insensitively, including aliases. An edge is
+//! `-[types]->`, `<-[types]-` or `-[types]-` (either direction); `-->`,
+//! `<--` and `--` match any relation type. Repeating a variable requires
+//! both positions to be the same entity.
+
+use anyhow::{Result, bail};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::str::FromStr;
+use tracing::debug;
+
+use crate::resolution;
+use crate::sqlite::{Entity, KnowledgeDb, Relationship};
+
+/// Bounds applied to every graph query
+#[derive(Debug, Clone)]
+pub struct QueryLimits {
+    /// Maximum number of results (paths, neighbors or matches) returned
+    pub max_results: usize,
+    /// Maximum number of hops walked from a starting entity
+    pub max_depth: usize,
+    /// Maximum number of entities expanded or partial matches kept
+    pub max_visited: usize,
+}
+
+impl Default for QueryLimits {
+    fn default() -> Self {
+        Self {
+            max_results: 20,
+            max_depth: 4,
+            max_visited: 2000,
+        }
+    }
+}
+
+/// Direction in which a relationship may be followed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Direction {
+    /// From source to target
+    Outgoing,
+    /// From target to source
+    Incoming,
+    /// Either way
+    #[default]
+    Both,
+}
+
+impl FromStr for Direction {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "out" | "outgoing" => Ok(Direction::Outgoing),
+            "in" | "incoming" => Ok(Direction::Incoming),
+            "both" | "any" => Ok(Direction::Both),
+            other => bail!(
+                "Unknown direction '{}' (use outgoing, incoming or both)",
+                other
+            ),
+        }
+    }
+}
+
+/// Which relationships a traversal may follow
+#[derive(Debug, Clone, Default)]
+pub struct EdgeFilter {
+    /// Allowed relation types (empty = any), compared case-insensitively
+    pub relation_types: Vec<String>,
+    pub direction: Direction,
+}
+
+impl EdgeFilter {
+    /// The entity at the other end of `rel` from `from_id`, if the filter
+    /// allows following it, and whether it is followed source to target
+    fn follow<'a>(&self, rel: &'a Relationship, from_id: &str) -> Option<(&'a str, bool)> {
+        if !type_allowed(&self.relation_types, &rel.relation_type) {
+            return None;
+        }
+        let forward = rel.source_id == from_id;
+        let backward = rel.target_id == from_id;
+        match self.direction {
+            Direction::Outgoing if forward => Some((&rel.target_id, true)),
+            Direction::Incoming if backward => Some((&rel.source_id, false)),
+            Direction::Both if forward => Some((&rel.target_id, true)),
+            Direction::Both if backward => Some((&rel.source_id, false)),
+            _ => None,
+        }
+    }
+}
+
+fn type_allowed(allowed: &[String], actual: &str) -> bool {
+    allowed.is_empty() || allowed.iter().any(|t| t.eq_ignore_ascii_case(actual))
+}
+
+/// One step along a path
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Hop {
+    pub relationship: Relationship,
+    /// Entity reached by this step
+    pub entity: Entity,
+    /// Whether the relationship was followed from source to target
+    pub forward: bool,
+}
+
+/// A path between two entities
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphPath {
+    pub start: Entity,
+    pub hops: Vec<Hop>,
+}
+
+impl GraphPath {
+    /// Number of relationships on the path
+    pub fn len(&self) -> usize {
+        self.hops.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.hops.is_empty()
+    }
+
+    /// The last entity on the path
+    pub fn end(&self) -> &Entity {
+        self.hops.last().map_or(&self.start, |h| &h.entity)
+    }
+}
+
+impl fmt::Display for GraphPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.start.name)?;
+        for hop in &self.hops {
+            if hop.forward {
+                write!(
+                    f,
+                    " -[{}]-> {}",
+                    hop.relationship.relation_type, hop.entity.name
+                )?;
+            } else {
+                write!(
+                    f,
+                    " <-[{}]- {}",
+                    hop.relationship.relation_type, hop.entity.name
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// An entity near the center of a neighborhood
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Neighbor {
+    pub entity: Entity,
+    /// Hops from the center
+    pub distance: usize,
+    /// Relationship through which the entity was first reached
+    pub via: Relationship,
+    /// Entity on the other end of `via`
+    pub via_entity_id: String,
+}
+
+/// Entities within a number of hops of a center entity, nearest first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Neighborhood {
+    pub center: Entity,
+    pub neighbors: Vec<Neighbor>,
+    /// Whether the result was cut short by the query limits
+    pub truncated: bool,
+}
+
+/// Constraints on one node of a pattern
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NodePattern {
+    pub var: Option<String>,
+    /// Allowed entity types (empty = any)
+    pub types: Vec<String>,
+    /// Required name or alias
+    pub name: Option<String>,
+}
+
+impl NodePattern {
+    fn matches(&self, entity: &Entity) -> bool {
+        type_allowed(&self.types, &entity.entity_type)
+            && self.name.as_ref().is_none_or(|name| {
+                entity.name.eq_ignore_ascii_case(name)
+                    || resolution::aliases_of(entity)
+                        .iter()
+                        .any(|a| a.eq_ignore_ascii_case(name))
+            })
+    }
+
+    /// How much this node narrows down the starting candidates
+    fn selectivity(&self) -> u8 {
+        match (&self.name, self.types.is_empty()) {
+            (Some(_), _) => 2,
+            (None, false) => 1,
+            (None, true) => 0,
+        }
+    }
+}
+
+/// Constraints on one edge of a pattern, from the node before it to the
+/// node after it
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EdgePattern {
+    pub relation_types: Vec<String>,
+    pub direction: Direction,
+}
+
+impl EdgePattern {
+    fn filter(&self, reversed: bool) -> EdgeFilter {
+        let direction = match (self.direction, reversed) {
+            (Direction::Outgoing, true) => Direction::Incoming,
+            (Direction::Incoming, true) => Direction::Outgoing,
+            (direction, _) => direction,
+        };
+        EdgeFilter {
+            relation_types: self.relation_types.clone(),
+            direction,
+        }
+    }
+}
+
+/// A chain of node patterns joined by edge patterns
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pattern {
+    pub nodes: Vec<NodePattern>,
+    /// `edges[i]` joins `nodes[i]` and `nodes[i + 1]`
+    pub edges: Vec<EdgePattern>,
+}
+
+impl FromStr for Pattern {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        PatternParser::new(s).parse()
+    }
+}
+
+/// One way the pattern matched the graph
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PatternMatch {
+    /// Entity matched by each node, in pattern order
+    pub nodes: Vec<Entity>,
+    /// Relationship matched by each edge, in pattern order
+    pub relationships: Vec<Relationship>,
+    /// Named variables and the entity ID bound to each
+    pub bindings: BTreeMap<String, String>,
+}
+
+impl fmt::Display for PatternMatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, entity) in self.nodes.iter().enumerate() {
+            if let Some(rel) = self.relationships.get(i.wrapping_sub(1)) {
+                if rel.source_id == entity.id {
+                    write!(f, " <-[{}]- ", rel.relation_type)?;
+                } else {
+                    write!(f, " -[{}]-> ", rel.relation_type)?;
+                }
+            }
+            write!(f, "{} ({})", entity.name, entity.entity_type)?;
+        }
+        Ok(())
+    }
+}
+
+/// Matches of a pattern query
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PatternResults {
+    pub matches: Vec<PatternMatch>,
+    /// Whether matching was cut short by the query limits
+    pub truncated: bool,
+}
+
+/// Entities and current relationships loaded during one query
+struct GraphCache<'a> {
+    db: &'a KnowledgeDb,
+    entities: HashMap<String, Option<Entity>>,
+    relationships: HashMap<String, Vec<Relationship>>,
+}
+
+impl<'a> GraphCache<'a> {
+    fn new(db: &'a KnowledgeDb) -> Self {
+        Self {
+            db,
+            entities: HashMap::new(),
+            relationships: HashMap::new(),
+        }
+    }
+
+    async fn entity(&mut self, id: &str) -> Result<Option<Entity>> {
+        if let Some(entity) = self.entities.get(id) {
+            return Ok(entity.clone());
+        }
+        let entity = self.db.get_entity(id).await?;
+        self.entities.insert(id.to_string(), entity.clone());
+        Ok(entity)
+    }
+
+    async fn relationships(&mut self, id: &str) -> Result<Vec<Relationship>> {
+        if let Some(rels) = self.relationships.get(id) {
+            return Ok(rels.clone());
+        }
+        let rels = self.db.get_current_relationships_for(id).await?;
+        self.relationships.insert(id.to_string(), rels.clone());
+        Ok(rels)
+    }
+}
+
+/// Shortest path from one entity to another, following relationships
+/// allowed by `filter`, or None if they are not connected within
+/// `limits.max_depth` hops
+pub async fn shortest_path(
+    db: &KnowledgeDb,
+    from_id: &str,
+    to_id: &str,
+    filter: &EdgeFilter,
+    limits: &QueryLimits,
+) -> Result<Option<GraphPath>> {
+    let mut cache = GraphCache::new(db);
+    let Some(start) = cache.entity(from_id).await? else {
+        return Ok(None);
+    };
+    if from_id == to_id {
+        return Ok(Some(GraphPath {
+            start,
+            hops: Vec::new(),
+        }));
+    }
+
+    // Breadth-first search, remembering how each entity was reached
+    let mut reached: HashMap<String, (String, Relationship, bool)> = HashMap::new();
+    let mut queue = VecDeque::from([(from_id.to_string(), 0usize)]);
+    let mut visited = HashSet::from([from_id.to_string()]);
+    while let Some((id, depth)) = queue.pop_front() {
+        if depth >= limits.max_depth || visited.len() > limits.max_visited {
+            continue;
+        }
+        for rel in cache.relationships(&id).await? {
+            let Some((next, forward)) = filter.follow(&rel, &id) else {
+                continue;
+            };
+            if !visited.insert(next.to_string()) {
+                continue;
+            }
+            reached.insert(next.to_string(), (id.clone(), rel.clone(), forward));
+            if next == to_id {
+                return Ok(Some(build_path(&mut cache, start, &reached, to_id).await?));
+            }
+            queue.push_back((next.to_string(), depth + 1));
+        }
+    }
+
+    debug!(
+        "No path from {} to {} within {} hops",
+        from_id, to_id, limits.max_depth
+    );
+    Ok(None)
+}
+
+async fn build_path(
+    cache: &mut GraphCache<'_>,
+    start: Entity,
+    reached: &HashMap<String, (String, Relationship, bool)>,
+    to_id: &str,
+) -> Result<GraphPath> {
+    let mut hops = Vec::new();
+    let mut current = to_id.to_string();
+    while let Some((previous, rel, forward)) = reached.get(&current) {
+        let Some(entity) = cache.entity(&current).await? else {
+            bail!("Entity {} disappeared during path search", current);
+        };
+        hops.push(Hop {
+            relationship: rel.clone(),
+            entity,
+            forward: *forward,
+        });
+        current = previous.clone();
+    }
+    hops.reverse();
+    Ok(GraphPath { start, hops })
+}
+
+/// Entities within `hops` relationships of `center_id` (capped at
+/// `limits.max_depth`), nearest first, or None if the center does not exist
+pub async fn neighborhood(
+    db: &KnowledgeDb,
+    center_id: &str,
+    hops: usize,
+    filter: &EdgeFilter,
+    limits: &QueryLimits,
+) -> Result<Option<Neighborhood>> {
+    let mut cache = GraphCache::new(db);
+    let Some(center) = cache.entity(center_id).await? else {
+        return Ok(None);
+    };
+
+    let hops = hops.min(limits.max_depth);
+    let mut neighbors = Vec::new();
+    let mut truncated = false;
+    let mut visited = HashSet::from([center_id.to_string()]);
+    let mut frontier = vec![center_id.to_string()];
+
+    'outer: for distance in 1..=hops {
+        let mut next_frontier = Vec::new();
+        for id in &frontier {
+            for rel in cache.relationships(id).await? {
+                let Some((next, _)) = filter.follow(&rel, id) else {
+                    continue;
+                };
+                if visited.contains(next) {
+                    continue;
+                }
+                if neighbors.len() >= limits.max_results || visited.len() >= limits.max_visited {
+                    truncated = true;
+                    break 'outer;
+                }
+                visited.insert(next.to_string());
+                if let Some(entity) = cache.entity(next).await? {
+                    neighbors.push(Neighbor {
+                        entity,
+                        distance,
+                        via: rel.clone(),
+                        via_entity_id: id.clone(),
+                    });
+                    next_frontier.push(next.to_string());
+                }
+            }
+        }
+        frontier = next_frontier;
+    }
+
+    Ok(Some(Neighborhood {
+        center,
+        neighbors,
+        truncated,
+    }))
+}
+
+/// A partially matched pattern
+#[derive(Clone)]
+struct Partial {
+    nodes: Vec<Option<Entity>>,
+    edges: Vec<Option<Relationship>>,
+}
+
+impl Partial {
+    /// Whether binding `entity` at node `index` agrees with other nodes
+    /// that share its variable
+    fn consistent(&self, pattern: &Pattern, index: usize, entity: &Entity) -> bool {
+        let Some(var) = &pattern.nodes[index].var else {
+            return true;
+        };
+        pattern.nodes.iter().enumerate().all(|(i, node)| {
+            node.var.as_ref() != Some(var)
+                || self.nodes[i]
+                    .as_ref()
+                    .is_none_or(|bound| bound.id == entity.id)
+        })
+    }
+
+    fn uses(&self, rel: &Relationship) -> bool {
+        self.edges.iter().flatten().any(|r| r.id == rel.id)
+    }
+}
+
+/// Find every way `pattern` matches the graph, up to `limits.max_results`
+pub async fn match_pattern(
+    db: &KnowledgeDb,
+    pattern: &Pattern,
+    limits: &QueryLimits,
+) -> Result<PatternResults> {
+    let mut cache = GraphCache::new(db);
+    let mut truncated = false;
+
+    // Start from the most constrained node and grow the chain both ways
+    let anchor = (0..pattern.nodes.len())
+        .max_by_key(|&i| (pattern.nodes[i].selectivity(), std::cmp::Reverse(i)))
+        .unwrap_or(0);
+    let anchor_node = &pattern.nodes[anchor];
+    let candidates = match &anchor_node.name {
+        Some(name) => {
+            db.find_entities_by_name(name, &anchor_node.types, limits.max_visited)
+                .await?
+        }
+        None if !anchor_node.types.is_empty() => {
+            db.get_entities_by_type(&anchor_node.types, limits.max_visited)
+                .await?
+        }
+        None => {
+            let mut all = db.get_all_entities().await?;
+            all.truncate(limits.max_visited);
+            all
+        }
+    };
+
+    let mut partials: Vec<Partial> = candidates
+        .into_iter()
+        .filter(|e| anchor_node.matches(e))
+        .map(|entity| {
+            let mut nodes = vec![None; pattern.nodes.len()];
+            nodes[anchor] = Some(entity);
+            Partial {
+                nodes,
+                edges: vec![None; pattern.edges.len()],
+            }
+        })
+        .collect();
+
+    // Steps as (known node, edge, new node, edge walked backwards)
+    let steps = (anchor..pattern.edges.len())
+        .map(|i| (i, i, i + 1, false))
+        .chain((0..anchor).rev().map(|i| (i + 1, i, i, true)));
+    for (from, edge, to, reversed) in steps {
+        let filter = pattern.edges[edge].filter(reversed);
+        let mut extended = Vec::new();
+        for partial in &partials {
+            let Some(from_entity) = partial.nodes[from].clone() else {
+                continue;
+            };
+            for rel in cache.relationships(&from_entity.id).await? {
+                let Some((next, _)) = filter.follow(&rel, &from_entity.id) else {
+                    continue;
+                };
+                if partial.uses(&rel) {
+                    continue;
+                }
+                let Some(entity) = cache.entity(next).await? else {
+                    continue;
+                };
+                if !pattern.nodes[to].matches(&entity) || !partial.consistent(pattern, to, &entity)
+                {
+                    continue;
+                }
+                if extended.len() >= limits.max_visited {
+                    truncated = true;
+                    break;
+                }
+                let mut grown = partial.clone();
+                grown.nodes[to] = Some(entity);
+                grown.edges[edge] = Some(rel);
+                extended.push(grown);
+            }
+        }
+        partials = extended;
+    }
+
+    let mut seen = HashSet::new();
+    let mut matches = Vec::new();
+    for partial in partials {
+        let nodes: Vec<Entity> = partial.nodes.into_iter().flatten().collect();
+        let relationships: Vec<Relationship> = partial.edges.into_iter().flatten().collect();
+        let key: Vec<String> = nodes
+            .iter()
+            .map(|e| e.id.clone())
+            .chain(relationships.iter().map(|r| r.id.clone()))
+            .collect();
+        if !seen.insert(key) {
+            continue;
+        }
+        if matches.len() >= limits.max_results {
+            truncated = true;
+            break;
+        }
+        let bindings = pattern
+            .nodes
+            .iter()
+            .zip(&nodes)
+            .filter_map(|(node, entity)| node.var.clone().map(|var| (var, entity.id.clone())))
+            .collect();
+        matches.push(PatternMatch {
+            nodes,
+            relationships,
+            bindings,
+        });
+    }
+
+    debug!(
+        "Pattern matched {} time(s){}",
+        matches.len(),
+        if truncated { " (truncated)" } else { "" }
+    );
+    Ok(PatternResults { matches, truncated })
+}
+
+/// Recursive-descent parser for the pattern syntax
+struct PatternParser<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl<'a> PatternParser<'a> {
+    fn new(input: &'a str) -> Self {
+        Self { input, pos: 0 }
+    }
+
+    fn parse(mut self) -> Result<Pattern> {
+        let mut nodes = vec![self.node()?];
+        let mut edges = Vec::new();
+        loop {
+            self.skip_ws();
+            if self.rest().is_empty() {
+                break;
+            }
+            edges.push(self.edge()?);
+            nodes.push(self.node()?);
+        }
+        Ok(Pattern { nodes, edges })
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.input[self.pos..]
+    }
+
+    fn skip_ws(&mut self) {
+        let trimmed = self.rest().trim_start();
+        self.pos = self.input.len() - trimmed.len();
+    }
+
+    fn eat(&mut self, token: &str) -> bool {
+        self.skip_ws();
+        if self.rest().starts_with(token) {
+            self.pos += token.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, token: &str) -> Result<()> {
+        if !self.eat(token) {
+            self.fail(&format!("expected '{}'", token))?;
+        }
+        Ok(())
+    }
+
+    fn fail<T>(&self, message: &str) -> Result<T> {
+        bail!(
+            "Invalid graph pattern at position {}: {} (near '{}')",
+            self.pos,
+            message,
+            self.rest().chars().take(12).collect::<String>()
+        )
+    }
+
+    fn ident(&mut self) -> Option<String> {
+        self.skip_ws();
+        let len = self
+            .rest()
+            .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .unwrap_or(self.rest().len());
+        if len == 0 {
+            return None;
+        }
+        let ident = self.rest()[..len].to_string();
+        self.pos += len;
+        Some(ident)
+    }
+
+    fn types(&mut self) -> Result<Vec<String>> {
+        let mut types = Vec::new();
+        loop {
+            match self.ident() {
+                Some(t) => types.push(t),
+                None => return self.fail("expected a type name"),
+            }
+            if !self.eat("|") {
+                return Ok(types);
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<String> {
+        self.skip_ws();
+        let Some(quote) = self
+            .rest()
+            .chars()
+            .next()
+            .filter(|c| *c == '"' || *c == '\'')
+        else {
+            return self.fail("expected a quoted string");
+        };
+        let body = &self.rest()[1..];
+        let Some(end) = body.find(quote) else {
+            return self.fail("unterminated string");
+        };
+        let value = body[..end].to_string();
+        self.pos += end + 2;
+        Ok(value)
+    }
+
+    fn node(&mut self) -> Result<NodePattern> {
+        self.expect("(")?;
+        let mut node = NodePattern {
+            var: self.ident(),
+            ..Default::default()
+        };
+        if self.eat(":") {
+            node.types = self.types()?;
+        }
+        if self.eat("{") {
+            match self.ident().as_deref() {
+                Some("name") => {}
+                _ => return self.fail("only the 'name' property can be matched"),
+            }
+            self.expect(":")?;
+            node.name = Some(self.string()?);
+            self.expect("}")?;
+        }
+        self.expect(")")?;
+        Ok(node)
+    }
+
+    fn edge(&mut self) -> Result<EdgePattern> {
+        let incoming = self.eat("<");
+        self.expect("-")?;
+        let mut relation_types = Vec::new();
+        if self.eat("[") {
+            // An optional variable name before ':' is accepted and ignored
+            let first = self.ident();
+            if self.eat(":") {
+                relation_types = self.types()?;
+            } else if let Some(first) = first {
+                relation_types.push(first);
+                while self.eat("|") {
+                    relation_types.extend(self.types()?);
+                }
+            }
+            self.expect("]")?;
+        }
+        self.expect("-")?;
+        let outgoing = self.eat(">");
+        let direction = match (incoming, outgoing) {
+            (true, false) => Direction::Incoming,
+            (false, true) => Direction::Outgoing,
+            (false, false) => Direction::Both,
+            (true, true) => return self.fail("an edge cannot point both ways"),
+        };
+        Ok(EdgePattern {
+            relation_types,
+            direction,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pattern() {
+        let pattern: Pattern =
+            r#"(p:person)-[works_at]->(o:organization|company {name: "Acme Corp"})"#
+                .parse()
+                .unwrap();
+        assert_eq!(pattern.nodes.len(), 2);
+        assert_eq!(pattern.nodes[0].var.as_deref(), Some("p"));
+        assert_eq!(pattern.nodes[0].types, vec!["person"]);
+        assert_eq!(pattern.nodes[1].types, vec!["organization", "company"]);
+        assert_eq!(pattern.nodes[1].name.as_deref(), Some("Acme Corp"));
+        assert_eq!(pattern.edges[0].relation_types, vec!["works_at"]);
+        assert_eq!(pattern.edges[0].direction, Direction::Outgoing);
+    }
+
+    #[test]
+    fn test_parse_edge_forms() {
+        let pattern: Pattern = "(a)<-[:knows|likes]-( ) -- (c) <-- (d)-[r:x]-(e)"
+            .parse()
+            .unwrap();
+        assert_eq!(pattern.nodes.len(), 5);
+        assert_eq!(pattern.nodes[1], NodePattern::default());
+        let edges: Vec<(Vec<String>, Direction)> = pattern
+            .edges
+            .into_iter()
+            .map(|e| (e.relation_types, e.direction))
+            .collect();
+        assert_eq!(
+            edges,
+            vec![
+                (
+                    vec!["knows".to_string(), "likes".to_string()],
+                    Direction::Incoming
+                ),
+                (vec![], Direction::Both),
+                (vec![], Direction::Incoming),
+                (vec!["x".to_string()], Direction::Both),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        for bad in [
+            "",
+            "(a",
+            "(a)-[x]->",
+            "(a)<-[x]->(b)",
+            "(a {age: \"3\"})",
+            "(a {name: \"x)",
+        ] {
+            assert!(bad.parse::<Pattern>().is_err(), "accepted {:?}", bad);
+        }
+    }
+
+    #[test]
+    fn test_direction_from_str() {
+        assert_eq!("out".parse::<Direction>().unwrap(), Direction::Outgoing);
+        assert_eq!(
+            "Incoming".parse::<Direction>().unwrap(),
+            Direction::Incoming
+        );
+        assert!("sideways".parse::<Direction>().is_err());
+    }
+
+    /// Alice -knows-> Bob -knows-> Carol, Bob -works_at-> Acme,
+    /// Dave -works_at-> Acme, Dave -involved_in-> Apollo
+    async fn sample_graph(db: &KnowledgeDb) -> Result<HashMap<&'static str, String>> {
+        let mut ids = HashMap::new();
+        for (name, entity_type) in [
+            ("Alice", "person"),
+            ("Bob", "person"),
+            ("Carol", "person"),
+            ("Dave", "person"),
+            ("Acme", "organization"),
+            ("Apollo", "project"),
+        ] {
+            ids.insert(name, db.insert_entity(name, entity_type, None).await?);
+        }
+        for (source, relation, target) in [
+            ("Alice", "knows", "Bob"),
+            ("Bob", "knows", "Carol"),
+            ("Bob", "works_at", "Acme"),
+            ("Dave", "works_at", "Acme"),
+            ("Dave", "involved_in", "Apollo"),
+        ] {
+            db.insert_relationship(&ids[source], &ids[target], relation, None)
+                .await?;
+        }
+        Ok(ids)
+    }
+
+    #[tokio::test]
+    async fn test_shortest_path() -> Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        let db = KnowledgeDb::new(temp.path().join("t.db"))?;
+        let ids = sample_graph(&db).await?;
+        let limits = QueryLimits::default();
+
+        let path = shortest_path(
+            &db,
+            &ids["Alice"],
+            &ids["Carol"],
+            &EdgeFilter::default(),
+            &limits,
+        )
+        .await?
+        .unwrap();
+        assert_eq!(path.len(), 2);
+        assert_eq!(path.end().name, "Carol");
+        assert_eq!(path.to_string(), "Alice -[knows]-> Bob -[knows]-> Carol");
+
+        // Following relationships backwards is shown as such
+        let path = shortest_path(
+            &db,
+            &ids["Carol"],
+            &ids["Dave"],
+            &EdgeFilter::default(),
+            &limits,
+        )
+        .await?
+        .unwrap();
+        assert_eq!(
+            path.to_string(),
+            "Carol <-[knows]- Bob -[works_at]-> Acme <-[works_at]- Dave"
+        );
+
+        // Filters and depth limits can disconnect entities
+        let knows_only = EdgeFilter {
+            relation_types: vec!["knows".to_string()],
+            direction: Direction::Both,
+        };
+        assert!(
+            shortest_path(&db, &ids["Alice"], &ids["Dave"], &knows_only, &limits)
+                .await?
+                .is_none()
+        );
+        let shallow = QueryLimits {
+            max_depth: 1,
+            ..Default::default()
+        };
+        assert!(
+            shortest_path(
+                &db,
+                &ids["Alice"],
+                &ids["Carol"],
+                &EdgeFilter::default(),
+                &shallow
+            )
+            .await?
+            .is_none()
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_neighborhood() -> Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        let db = KnowledgeDb::new(temp.path().join("t.db"))?;
+        let ids = sample_graph(&db).await?;
+        let limits = QueryLimits::default();
+
+        let hood = neighborhood(&db, &ids["Bob"], 1, &EdgeFilter::default(), &limits)
+            .await?
+            .unwrap();
+        let mut names: Vec<&str> = hood
+            .neighbors
+            .iter()
+            .map(|n| n.entity.name.as_str())
+            .collect();
+        names.sort();
+        assert_eq!(names, vec!["Acme", "Alice", "Carol"]);
+        assert!(!hood.truncated);
+
+        let outgoing = EdgeFilter {
+            relation_types: vec![],
+            direction: Direction::Outgoing,
+        };
+        let hood = neighborhood(&db, &ids["Bob"], 2, &outgoing, &limits)
+            .await?
+            .unwrap();
+        assert_eq!(hood.neighbors.len(), 2);
+
+        let hood = neighborhood(&db, &ids["Bob"], 2, &EdgeFilter::default(), &limits)
+            .await?
+            .unwrap();
+        let dave = hood
+            .neighbors
+            .iter()
+            .find(|n| n.entity.name == "Dave")
+            .unwrap();
+        assert_eq!(dave.distance, 2);
+        assert_eq!(dave.via_entity_id, ids["Acme"]);
+
+        let capped = QueryLimits {
+            max_results: 2,
+            ..Default::default()
+        };
+        let hood = neighborhood(&db, &ids["Bob"], 3, &EdgeFilter::default(), &capped)
+            .await?
+            .unwrap();
+        assert_eq!(hood.neighbors.len(), 2);
+        assert!(hood.truncated);
+        assert!(
+            neighborhood(&db, "missing", 1, &EdgeFilter::default(), &limits)
+                .await?
+                .is_none()
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_match_pattern() -> Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        let db = KnowledgeDb::new(temp.path().join("t.db"))?;
+        let ids = sample_graph(&db).await?;
+        let limits = QueryLimits::default();
+
+        // Which projects involve people from Acme?
+        let pattern: Pattern =
+            r#"(proj:project)<-[involved_in]-(p:person)-[works_at]->(:organization {name: "acme"})"#
+                .parse()?;
+        let results = match_pattern(&db, &pattern, &limits).await?;
+        assert_eq!(results.matches.len(), 1);
+        let m = &results.matches[0];
+        assert_eq!(m.bindings["proj"], ids["Apollo"]);
+        assert_eq!(m.bindings["p"], ids["Dave"]);
+        assert_eq!(
+            m.to_string(),
+            "Apollo (project) <-[involved_in]- Dave (person) -[works_at]-> Acme (organization)"
+        );
+
+        // Who works at Acme?
+        let pattern: Pattern = "(p:person)-[works_at]->(:organization)".parse()?;
+        let results = match_pattern(&db, &pattern, &limits).await?;
+        let mut people: Vec<&str> = results
+            .matches
+            .iter()
+            .map(|m| m.nodes[0].name.as_str())
+            .collect();
+        people.sort();
+        assert_eq!(people, vec!["Bob", "Dave"]);
+
+        // Coworkers: a repeated relationship is not reused and results are capped
+        let pattern: Pattern = "(a)-[works_at]->(o)<-[works_at]-(b)".parse()?;
+        let results = match_pattern(&db, &pattern, &limits).await?;
+        assert_eq!(results.matches.len(), 2);
+        assert!(
+            results
+                .matches
+                .iter()
+                .all(|m| m.nodes[0].id != m.nodes[2].id)
+        );
+        let capped = QueryLimits {
+            max_results: 1,
+            ..Default::default()
+        };
+        let results = match_pattern(&db, &pattern, &capped).await?;
+        assert_eq!(results.matches.len(), 1);
+        assert!(results.truncated);
+
+        // A repeated variable must bind the same entity
+        let pattern: Pattern = "(a)-[knows]-(b)-[knows]-(a)".parse()?;
+        assert!(
+            match_pattern(&db, &pattern, &limits)
+                .await?
+                .matches
+                .is_empty()
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_match_pattern_uses_aliases() -> Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        let db = KnowledgeDb::new(temp.path().join("t.db"))?;
+        let bob = db
+            .insert_entity(
+                "Robert",
+                "person",
+                Some(serde_json::json!({"aliases": ["Bob"]})),
+            )
+            .await?;
+        let acme = db.insert_entity("Acme", "organization", None).await?;
+        db.insert_relationship(&bob, &acme, "works_at", None)
+            .await?;
+
+        let pattern: Pattern = r#"({name: "bob"})-->(o)"#.parse()?;
+        let results = match_pattern(&db, &pattern, &QueryLimits::default()).await?;
+        assert_eq!(results.matches.len(), 1);
+        assert_eq!(results.matches[0].bindings["o"], acme);
+        Ok(())
+    }
+}
@@ -429,6 +429,9 @@ mod tests {
                     relation_type: "enables".to_string(),
                     metadata: None,
                     created_at: chrono::Utc::now(),
+                    valid_from: None,
+                    valid_to: None,
+                    superseded_by: None,
                 }],
             },
         ];
@@ -468,6 +471,9 @@ mod tests {
                 relation_type: "links_to".to_string(),
                 metadata: None,
                 created_at: chrono::Utc::now(),
+                valid_from: None,
+                valid_to: None,
+                superseded_by: None,
             }],
         }];
 
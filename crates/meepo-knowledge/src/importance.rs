@@ -0,0 +1,320 @@
+//! Memory importance scoring, decay and retention
+//!
+//! Each entity gets an importance score in `[0, 1]` built from how often it is
+//! recalled, how recently it was touched, and how connected it is in the
+//! graph. Scores decay with a configurable half-life, so trivia that is never
+//! looked at again fades. Recall blends importance into its ranking, and a
+//! [`RetentionPolicy`] archives or deletes memories that fall below a
+//! threshold. Pinned memories always score 1.0 and are never forgotten.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// Weights and decay for importance scoring
+#[derive(Debug, Clone)]
+pub struct ImportanceConfig {
+    /// Weight of access frequency
+    pub access_weight: f32,
+    /// Weight of recency (last access or update)
+    pub recency_weight: f32,
+    /// Weight of link degree
+    pub degree_weight: f32,
+    /// Days for the recency component to halve
+    pub half_life_days: f32,
+    /// How much importance counts in recall ranking (0 = relevance only)
+    pub recall_weight: f32,
+}
+
+impl Default for ImportanceConfig {
+    fn default() -> Self {
+        Self {
+            access_weight: 0.4,
+            recency_weight: 0.4,
+            degree_weight: 0.2,
+            half_life_days: 30.0,
+            recall_weight: 0.3,
+        }
+    }
+}
+
+/// Usage statistics for one entity
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntityStats {
+    pub entity_id: String,
+    pub name: String,
+    pub entity_type: String,
+    pub access_count: u32,
+    pub last_accessed_at: Option<DateTime<Utc>>,
+    pub pinned: bool,
+    /// Number of relationships touching the entity
+    pub degree: u32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl EntityStats {
+    /// Most recent time the entity was read or written
+    pub fn last_touched(&self) -> DateTime<Utc> {
+        self.last_accessed_at
+            .map_or(self.updated_at, |a| a.max(self.updated_at))
+    }
+}
+
+/// Saturating curve mapping a count onto `[0, 1)`
+fn saturate(count: u32, scale: f32) -> f32 {
+    1.0 - (-(count as f32) / scale).exp()
+}
+
+/// Compute an entity's importance at `now`
+pub fn importance_score(stats: &EntityStats, now: DateTime<Utc>, config: &ImportanceConfig) -> f32 {
+    if stats.pinned {
+        return 1.0;
+    }
+
+    let access = saturate(stats.access_count, 5.0);
+    let degree = saturate(stats.degree, 3.0);
+    let age_days = (now - stats.last_touched()).num_seconds().max(0) as f32 / 86_400.0;
+    let recency = if config.half_life_days > 0.0 {
+        (-std::f32::consts::LN_2 * age_days / config.half_life_days).exp()
+    } else {
+        0.0
+    };
+
+    let total = config.access_weight + config.recency_weight + config.degree_weight;
+    if total <= 0.0 {
+        return 0.0;
+    }
+    let score = (config.access_weight * access
+        + config.recency_weight * recency
+        + config.degree_weight * degree)
+        / total;
+    score.clamp(0.0, 1.0)
+}
+
+/// Blend a normalized relevance score with importance for recall ranking
+pub fn blend_rank(relevance: f32, importance: f32, recall_weight: f32) -> f32 {
+    let w = recall_weight.clamp(0.0, 1.0);
+    relevance * (1.0 - w) + importance * w
+}
+
+/// What to do with memories that fall below the threshold
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RetentionAction {
+    /// Move to the archive table, restorable with `meepo memory restore`
+    #[default]
+    Archive,
+    /// Delete permanently
+    Delete,
+}
+
+impl FromStr for RetentionAction {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.to_lowercase().as_str() {
+            "archive" => Ok(Self::Archive),
+            "delete" => Ok(Self::Delete),
+            other => anyhow::bail!(
+                "Unknown retention action '{}' (expected 'archive' or 'delete')",
+                other
+            ),
+        }
+    }
+}
+
+/// Policy for forgetting low-value memories
+#[derive(Debug, Clone)]
+pub struct RetentionPolicy {
+    /// Archive or delete
+    pub action: RetentionAction,
+    /// Entities scoring below this are forgotten
+    pub threshold: f32,
+    /// Never forget entities younger than this
+    pub min_age_days: u32,
+    /// Entity types that are never forgotten (community summaries never are,
+    /// whether listed or not)
+    pub protected_types: Vec<String>,
+    /// Maximum entities forgotten per pass
+    pub max_per_pass: usize,
+    /// Delete action log entries older than this (0 = keep forever)
+    pub action_log_retain_days: u32,
+    /// Delete conversations older than this (0 = keep forever)
+    pub conversation_retain_days: u32,
+}
+
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        Self {
+            action: RetentionAction::Archive,
+            threshold: 0.15,
+            min_age_days: 30,
+            protected_types: vec!["preference".to_string(), "goal".to_string()],
+            max_per_pass: 500,
+            action_log_retain_days: 90,
+            conversation_retain_days: 0,
+        }
+    }
+}
+
+/// A memory selected (or, in a dry run, proposed) for forgetting
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ForgottenMemory {
+    pub entity_id: String,
+    pub name: String,
+    pub entity_type: String,
+    pub score: f32,
+}
+
+/// Outcome of a retention pass
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RetentionReport {
+    pub dry_run: bool,
+    pub action: RetentionAction,
+    pub examined: usize,
+    pub forgotten: Vec<ForgottenMemory>,
+    pub pinned_skipped: usize,
+    pub action_logs_deleted: usize,
+    pub conversations_deleted: usize,
+}
+
+/// An archived memory, restorable with `meepo memory restore`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedMemory {
+    pub entity_id: String,
+    pub name: String,
+    pub entity_type: String,
+    pub importance: f32,
+    pub archived_at: DateTime<Utc>,
+}
+
+/// Pick the entities a policy would forget, lowest score first
+pub fn select_for_forgetting(
+    stats: &[EntityStats],
+    policy: &RetentionPolicy,
+    config: &ImportanceConfig,
+    now: DateTime<Utc>,
+) -> (Vec<ForgottenMemory>, usize) {
+    let min_age = chrono::Duration::days(policy.min_age_days as i64);
+    let mut pinned_skipped = 0;
+    let mut selected: Vec<ForgottenMemory> = stats
+        .iter()
+        .filter(|s| {
+            if s.pinned {
+                pinned_skipped += 1;
+                return false;
+            }
+            // Community summaries have no links of their own and are kept
+            // current by the community refresh, not by use
+            s.entity_type != crate::communities::COMMUNITY_TYPE
+                && !policy.protected_types.iter().any(|t| t == &s.entity_type)
+                && now - s.created_at >= min_age
+        })
+        .filter_map(|s| {
+            let score = importance_score(s, now, config);
+            (score < policy.threshold).then(|| ForgottenMemory {
+                entity_id: s.entity_id.clone(),
+                name: s.name.clone(),
+                entity_type: s.entity_type.clone(),
+                score,
+            })
+        })
+        .collect();
+    selected.sort_by(|a, b| a.score.total_cmp(&b.score));
+    selected.truncate(policy.max_per_pass);
+    (selected, pinned_skipped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(access: u32, days_ago: i64, degree: u32, pinned: bool) -> EntityStats {
+        let now = Utc::now();
+        let touched = now - chrono::Duration::days(days_ago);
+        EntityStats {
+            entity_id: format!("e-{}-{}-{}", access, days_ago, degree),
+            name: "x".to_string(),
+            entity_type: "fact".to_string(),
+            access_count: access,
+            last_accessed_at: (access > 0).then_some(touched),
+            pinned,
+            degree,
+            created_at: touched - chrono::Duration::days(1),
+            updated_at: touched,
+        }
+    }
+
+    #[test]
+    fn test_pinned_scores_max() {
+        let config = ImportanceConfig::default();
+        assert_eq!(
+            importance_score(&stats(0, 1000, 0, true), Utc::now(), &config),
+            1.0
+        );
+    }
+
+    #[test]
+    fn test_score_decays_with_age() {
+        let config = ImportanceConfig::default();
+        let now = Utc::now();
+        let fresh = importance_score(&stats(0, 0, 0, false), now, &config);
+        let month = importance_score(&stats(0, 30, 0, false), now, &config);
+        let year = importance_score(&stats(0, 365, 0, false), now, &config);
+        assert!(fresh > month && month > year);
+        // One half-life halves the recency component
+        assert!((month - fresh / 2.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_access_and_degree_raise_score() {
+        let config = ImportanceConfig::default();
+        let now = Utc::now();
+        let base = importance_score(&stats(0, 90, 0, false), now, &config);
+        assert!(importance_score(&stats(10, 90, 0, false), now, &config) > base);
+        assert!(importance_score(&stats(0, 90, 5, false), now, &config) > base);
+    }
+
+    #[test]
+    fn test_blend_rank() {
+        assert_eq!(blend_rank(1.0, 0.0, 0.0), 1.0);
+        assert_eq!(blend_rank(0.5, 1.0, 1.0), 1.0);
+        assert!((blend_rank(1.0, 0.0, 0.3) - 0.7).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_select_for_forgetting() {
+        let config = ImportanceConfig::default();
+        let policy = RetentionPolicy::default();
+        let mut goal = stats(0, 400, 0, false);
+        goal.entity_type = "goal".to_string();
+        let mut community = stats(0, 400, 0, false);
+        community.entity_type = crate::communities::COMMUNITY_TYPE.to_string();
+        let all = vec![
+            stats(0, 400, 0, false), // stale trivia
+            stats(0, 400, 0, true),  // pinned
+            stats(20, 2, 4, false),  // well used
+            stats(0, 5, 0, false),   // too young
+            goal,                    // protected type
+            community,               // derived summary
+        ];
+        let (selected, pinned) = select_for_forgetting(&all, &policy, &config, Utc::now());
+        assert_eq!(pinned, 1);
+        assert_eq!(selected.len(), 1);
+        assert_eq!(selected[0].entity_id, all[0].entity_id);
+    }
+
+    #[test]
+    fn test_retention_action_parse() {
+        assert_eq!(
+            "archive".parse::<RetentionAction>().unwrap(),
+            RetentionAction::Archive
+        );
+        assert_eq!(
+            "DELETE".parse::<RetentionAction>().unwrap(),
+            RetentionAction::Delete
+        );
+        assert!("shred".parse::<RetentionAction>().is_err());
+    }
+}
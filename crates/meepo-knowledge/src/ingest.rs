@@ -0,0 +1,75 @@
+//! Document ingestion into the knowledge graph
+//!
+//! Shared by the `ingest_document` tool and corpus syncing: a file is run
+//! through an [`ExtractorRegistry`], chunked, and stored as a `document`
+//! entity with linked `document_chunk` entities. Documents are keyed by
+//! their source path, so ingesting a path again replaces the previous
+//! document and its chunks instead of duplicating them.
+//!
+//! Chunks are sized in estimated tokens and split with the strategy for the
+//! document's content type (see [`crate::chunking::ChunkStrategy`]).
+
+use std::sync::Arc;
+
+use crate::chunking::{ChunkingConfig, DocumentMetadata};
+use crate::extract::ExtractorRegistry;
+
+/// Entity type of ingested documents
+pub const DOCUMENT_TYPE: &str = "document";
+
+/// Entity type of document chunks
+pub const CHUNK_TYPE: &str = "document_chunk";
+
+/// Default chunk size of the pipeline, in estimated tokens
+pub const DEFAULT_CHUNK_TOKENS: usize = 256;
+
+/// Default overlap between chunks split inside one section, in estimated tokens
+pub const DEFAULT_CHUNK_OVERLAP_TOKENS: usize = 32;
+
+/// How files are turned into documents and chunks
+#[derive(Clone)]
+pub struct IngestPipeline {
+    pub extractors: Arc<ExtractorRegistry>,
+    pub chunking: ChunkingConfig,
+}
+
+impl Default for IngestPipeline {
+    fn default() -> Self {
+        Self {
+            extractors: Arc::default(),
+            chunking: ChunkingConfig::tokens(DEFAULT_CHUNK_TOKENS, DEFAULT_CHUNK_OVERLAP_TOKENS),
+        }
+    }
+}
+
+impl IngestPipeline {
+    pub fn with_extractors(mut self, extractors: Arc<ExtractorRegistry>) -> Self {
+        self.extractors = extractors;
+        self
+    }
+
+    pub fn with_chunking_config(mut self, chunking: ChunkingConfig) -> Self {
+        self.chunking = chunking;
+        self
+    }
+}
+
+/// Per-document ingestion options
+#[derive(Debug, Clone, Default)]
+pub struct IngestOptions {
+    /// Title to use instead of the extracted title or file name
+    pub title: Option<String>,
+    pub tags: Vec<String>,
+    /// Name of the corpus the file belongs to, if any
+    pub corpus: Option<String>,
+}
+
+/// Result of ingesting one file
+#[derive(Debug, Clone)]
+pub struct IngestedDocument {
+    /// ID of the new `document` entity
+    pub document_id: String,
+    pub metadata: DocumentMetadata,
+    /// Earlier documents from the same path that were replaced
+    pub replaced: usize,
+}
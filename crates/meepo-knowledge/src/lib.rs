@@ -1,37 +1,94 @@
 //! Knowledge graph and persistence layer for meepo
 //!
 //! This crate provides:
-//! - SQLite storage for entities, relationships, conversations, and watchers
-//! - Tantivy full-text search index
+//! - SQLite storage for entities, relationships and conversations
+//! - Conversation threads with an FTS5 index over message content
+//! - Tantivy full-text search index with fuzzy matching, facets and snippets
 //! - Knowledge graph operations combining both
-//! - MEMORY.md synchronization
+//! - Graph queries: shortest paths, neighborhoods and pattern matching
+//! - Text extraction from PDF, DOCX, EPUB, HTML and email files
+//! - Heading- and syntax-aware chunking sized in estimated tokens
+//! - Managed document corpora kept in sync with their directories
+//! - Two-way MEMORY.md synchronization with the knowledge graph
+//! - Episodic memory extraction from idle conversation threads
+//! - Versioned export/import of the whole knowledge graph
+//! - Entity resolution and duplicate merging
+//! - Temporal validity and provenance for facts
+//! - Importance scoring, decay and retention of memories
+//! - Optional SQLCipher encryption at rest
+//! - A change outbox that keeps the search indexes consistent with SQLite
+//! - GraphRAG community detection with stored community summaries
 
 pub mod chunking;
+pub mod communities;
+pub mod corpus;
 pub mod embeddings;
+pub mod encryption;
+pub mod episodic;
+pub mod export;
+pub mod extract;
 pub mod graph;
+pub mod graph_query;
 pub mod graph_rag;
+pub mod importance;
+pub mod ingest;
 pub mod memory_sync;
+pub mod outbox;
+pub mod resolution;
 pub mod sqlite;
 pub mod tantivy;
+pub mod temporal;
 
 // Re-export main types
 pub use chunking::{
-    ChunkingConfig, DocumentChunk, DocumentMetadata, chunk_text, detect_content_type,
+    ChunkStrategy, ChunkUnit, ChunkingConfig, CodeLanguage, DocumentChunk, DocumentMetadata,
+    annotate_chunks, chunk_document, chunk_text, chunk_with_strategy, count_tokens,
+    detect_content_type, format_citation,
 };
+pub use communities::{
+    COMMUNITY_TYPE, CommunityConfig, CommunityContext, CommunityReport, CommunitySummarizer,
+    CommunitySummary, StoredCommunity,
+};
+pub use corpus::{Corpus, CorpusFile, CorpusSyncReport};
 pub use embeddings::{
     EmbeddingConfig, EmbeddingProvider, HybridSearchResult, NoOpEmbeddingProvider, VectorIndex,
     VectorSearchResult, hybrid_search_rrf,
 };
+pub use episodic::{
+    CandidateStatus, Episode, ExtractedMemories, ExtractionConfig, ExtractionReport,
+    MemoryCandidate, MemoryExtractor, MemoryProposal, ProposedEntity, ProposedPreference,
+    ProposedRelationship,
+};
+pub use export::{
+    ConflictStrategy, ExportFormat, ImportReport, export_knowledge, import_knowledge,
+};
+pub use extract::{ExtractedDocument, Extractor, ExtractorRegistry, Location};
 pub use graph::KnowledgeGraph;
+pub use graph_query::{
+    Direction, EdgeFilter, GraphPath, Neighborhood, Pattern, PatternResults, QueryLimits,
+};
 pub use graph_rag::{
     EntitySource, GraphRagConfig, ScoredEntity, format_graph_context, graph_expand,
 };
-pub use memory_sync::{load_memory, load_soul, save_memory};
+pub use importance::{
+    ImportanceConfig, RetentionAction, RetentionPolicy, RetentionReport, importance_score,
+};
+pub use ingest::{IngestOptions, IngestPipeline, IngestedDocument};
+pub use memory_sync::{
+    ConflictPolicy, MemoryConflict, MemoryDocument, MemorySyncConfig, MemorySyncReport,
+    load_memory, load_soul, save_memory,
+};
+pub use outbox::{ConsistencyReport, ConsumerLag};
+pub use resolution::{
+    FailedMerge, MatchReason, MergeConfirmer, ResolutionConfig, ResolutionReport,
+};
 pub use sqlite::{
-    ActionLogEntry, BackgroundTask, Conversation, Entity, Goal, KnowledgeDb, ModelUsage,
-    Relationship, SourceUsage, UsageSummary, UserPreference, Watcher,
+    ActionLogEntry, BackgroundTask, Conversation, ConversationMatch, ConversationSearch,
+    ConversationThread, Entity, Goal, KnowledgeDb, ModelUsage, Relationship, SourceUsage,
+    UsageSummary, UserPreference, conversation_thread_id,
 };
-pub use tantivy::{SearchResult, TantivyIndex};
+pub use tantivy::{SearchLanguage, SearchOptions, SearchResult, TantivyIndex, parse_language};
+pub use temporal::{FactOptions, FactType, Provenance, ProvenanceRecord, RecordedFact, SourceType};
 
 #[cfg(test)]
 mod tests {
@@ -1,9 +1,50 @@
 //! MEMORY.md and SOUL.md synchronization
+//!
+//! MEMORY.md holds a managed block of facts that is kept in sync with the
+//! knowledge graph in both directions:
+//!
+//! ```text
+//! <!-- meepo:memory:start -->
+//! ## Preference
+//!
+//! - Dark roast coffee — only before noon <!-- id:3f2a... -->
+//!
+//! <!-- meepo:memory:end -->
+//! ```
+//!
+//! Each `## heading` is an entity type and each bullet an entity, optionally
+//! followed by ` — note`. The id comment ties a bullet to its entity; bullets
+//! added without one become new entities. Text outside the managed block is
+//! left untouched. Syncing is a three-way merge against the content written
+//! by the previous sync, so edits on both sides of the same fact are
+//! reported as conflicts (see [`crate::KnowledgeGraph::sync_memory_file`]).
 
 use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 use std::path::Path;
 use tracing::{debug, info, warn};
 
+use crate::sqlite::Entity;
+
+/// Marks the start of the managed block
+pub const BLOCK_START: &str = "<!-- meepo:memory:start -->";
+
+/// Marks the end of the managed block
+pub const BLOCK_END: &str = "<!-- meepo:memory:end -->";
+
+const BLOCK_HINT: &str = "<!-- Facts in this block sync with meepo's knowledge graph. Edit, delete or \
+                          add bullets under a section; keep the id comments. -->";
+
+/// Separates an entry's name from its note
+const NOTE_SEPARATOR: &str = " — ";
+
+/// Longest note rendered for a graph entity
+const MAX_NOTE_CHARS: usize = 200;
+
+/// Metadata keys shown as an entity's note, in order of preference
+const NOTE_KEYS: [&str; 4] = ["note", "description", "content", "value"];
+
 /// Load MEMORY.md contents
 pub fn load_memory<P: AsRef<Path>>(path: P) -> Result<String> {
     let path = path.as_ref();
@@ -61,6 +102,360 @@ pub fn load_soul<P: AsRef<Path>>(path: P) -> Result<String> {
     Ok(content)
 }
 
+/// Which side wins when a fact was edited both in the file and in the graph
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConflictPolicy {
+    /// Apply the file edit to the graph
+    File,
+    /// Keep the graph version and overwrite the file edit
+    Graph,
+}
+
+impl std::str::FromStr for ConflictPolicy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "file" => Ok(ConflictPolicy::File),
+            "graph" => Ok(ConflictPolicy::Graph),
+            other => anyhow::bail!(
+                "Unknown conflict policy '{}' (expected file or graph)",
+                other
+            ),
+        }
+    }
+}
+
+/// Configuration for MEMORY.md synchronization
+#[derive(Debug, Clone)]
+pub struct MemorySyncConfig {
+    /// Unpinned entities at or above this importance are written to the file
+    pub importance_threshold: f32,
+    /// Maximum number of facts in the file
+    pub max_entries: usize,
+    /// Entity types never written to the file
+    pub exclude_types: Vec<String>,
+    /// Who wins when both sides edited the same fact
+    pub conflict_policy: ConflictPolicy,
+}
+
+impl Default for MemorySyncConfig {
+    fn default() -> Self {
+        Self {
+            importance_threshold: 0.6,
+            max_entries: 100,
+            exclude_types: vec![
+                "document".to_string(),
+                "document_chunk".to_string(),
+                crate::communities::COMMUNITY_TYPE.to_string(),
+            ],
+            conflict_policy: ConflictPolicy::File,
+        }
+    }
+}
+
+/// One fact in the managed block
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemoryEntry {
+    /// Entity ID, or None for a bullet the user just added
+    pub id: Option<String>,
+    pub entity_type: String,
+    pub name: String,
+    pub note: Option<String>,
+}
+
+impl MemoryEntry {
+    /// Build the entry shown for a graph entity
+    pub fn from_entity(entity: &Entity) -> Self {
+        let note = entity.metadata.as_ref().and_then(|m| {
+            NOTE_KEYS
+                .iter()
+                .find_map(|k| m.get(*k).and_then(|v| v.as_str()))
+                .map(|n| {
+                    let flat = n.split_whitespace().collect::<Vec<_>>().join(" ");
+                    flat.chars().take(MAX_NOTE_CHARS).collect::<String>()
+                })
+                .filter(|n| !n.is_empty())
+        });
+        Self {
+            id: Some(entity.id.clone()),
+            entity_type: entity.entity_type.clone(),
+            name: single_line(&entity.name),
+            note,
+        }
+    }
+
+    /// Whether the entry shows the same fact as `other`, ignoring the ID
+    pub fn same_content(&self, other: &MemoryEntry) -> bool {
+        self.entity_type == other.entity_type && self.name == other.name && self.note == other.note
+    }
+
+    /// One-line description, used in conflict reports
+    pub fn describe(&self) -> String {
+        match &self.note {
+            Some(note) => format!("{}{}{}", self.name, NOTE_SEPARATOR, note),
+            None => self.name.clone(),
+        }
+    }
+
+    fn render(&self) -> String {
+        let mut line = format!("- {}", self.describe());
+        if let Some(id) = &self.id {
+            line.push_str(&format!(" <!-- id:{} -->", id));
+        }
+        line
+    }
+
+    fn parse(line: &str, entity_type: &str) -> Option<Self> {
+        let body = line
+            .strip_prefix("- ")
+            .or_else(|| line.strip_prefix("* "))?
+            .trim();
+        let (body, id) = match body.rfind("<!-- id:") {
+            Some(start) if body.ends_with("-->") => {
+                let id = body[start + "<!-- id:".len()..body.len() - "-->".len()].trim();
+                (body[..start].trim_end(), Some(id.to_string()))
+            }
+            _ => (body, None),
+        };
+        let (name, note) = match body
+            .split_once(NOTE_SEPARATOR)
+            .or_else(|| body.split_once(" -- "))
+        {
+            Some((name, note)) => (name.trim(), Some(note.trim().to_string())),
+            None => (body, None),
+        };
+        if name.is_empty() {
+            return None;
+        }
+        Some(Self {
+            id: id.filter(|id| !id.is_empty()),
+            entity_type: entity_type.to_string(),
+            name: name.to_string(),
+            note: note.filter(|n| !n.is_empty()),
+        })
+    }
+}
+
+fn single_line(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Section heading for an entity type ("work_project" -> "Work project")
+pub fn section_title(entity_type: &str) -> String {
+    let spaced = entity_type.replace('_', " ");
+    let mut chars = spaced.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Entity type for a section heading ("Work project" -> "work_project")
+pub fn section_type(heading: &str) -> String {
+    heading
+        .trim()
+        .to_lowercase()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+/// A parsed MEMORY.md: free text around the managed block, and its facts
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MemoryDocument {
+    /// Text before the managed block, kept verbatim
+    pub before: String,
+    /// Text after the managed block, kept verbatim
+    pub after: String,
+    pub entries: Vec<MemoryEntry>,
+}
+
+impl MemoryDocument {
+    /// Parse a memory file. Files without a managed block are all free text.
+    pub fn parse(text: &str) -> Self {
+        let Some(start) = text.find(BLOCK_START) else {
+            return Self {
+                before: text.to_string(),
+                ..Default::default()
+            };
+        };
+        let inner_start = start + BLOCK_START.len();
+        let (inner, after) = match text[inner_start..].find(BLOCK_END) {
+            Some(end) => (
+                &text[inner_start..inner_start + end],
+                &text[inner_start + end + BLOCK_END.len()..],
+            ),
+            None => (&text[inner_start..], ""),
+        };
+
+        let mut entries = Vec::new();
+        let mut section: Option<String> = None;
+        for line in inner.lines() {
+            let line = line.trim();
+            if let Some(heading) = line.strip_prefix("## ") {
+                section = Some(section_type(heading));
+            } else if let Some(entity_type) = &section
+                && let Some(entry) = MemoryEntry::parse(line, entity_type)
+            {
+                entries.push(entry);
+            }
+        }
+
+        Self {
+            before: text[..start].to_string(),
+            after: after.to_string(),
+            entries,
+        }
+    }
+
+    /// Render the document, grouping entries into sections by type
+    pub fn render(&self) -> String {
+        let mut sections: BTreeMap<&str, Vec<&MemoryEntry>> = BTreeMap::new();
+        for entry in &self.entries {
+            sections.entry(&entry.entity_type).or_default().push(entry);
+        }
+
+        let mut out = String::new();
+        let before = self.before.trim_end();
+        if !before.is_empty() {
+            out.push_str(before);
+            out.push_str("\n\n");
+        }
+        out.push_str(BLOCK_START);
+        out.push('\n');
+        out.push_str(BLOCK_HINT);
+        out.push('\n');
+        for (entity_type, mut entries) in sections {
+            entries.sort_by_key(|e| e.name.to_lowercase());
+            out.push_str(&format!("\n## {}\n\n", section_title(entity_type)));
+            for entry in entries {
+                out.push_str(&entry.render());
+                out.push('\n');
+            }
+        }
+        out.push('\n');
+        out.push_str(BLOCK_END);
+        out.push('\n');
+        let after = self.after.trim();
+        if !after.is_empty() {
+            out.push('\n');
+            out.push_str(after);
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Entries that carry an entity ID, by ID
+    pub fn by_id(&self) -> HashMap<&str, &MemoryEntry> {
+        self.entries
+            .iter()
+            .filter_map(|e| e.id.as_deref().map(|id| (id, e)))
+            .collect()
+    }
+}
+
+/// A change the user made to the file since the last sync
+#[derive(Debug, Clone, PartialEq)]
+pub enum FileChange {
+    /// A bullet without an ID: a new fact
+    Added(MemoryEntry),
+    /// A known fact whose text changed
+    Edited(MemoryEntry),
+    /// A known fact whose bullet was deleted
+    Removed(String),
+}
+
+/// Compare the file with the content of the previous sync.
+///
+/// Without a previous sync every known fact counts as edited; applying an
+/// edit that matches the graph is a no-op.
+pub fn file_changes(current: &MemoryDocument, base: Option<&MemoryDocument>) -> Vec<FileChange> {
+    let base_ids = base.map(|b| b.by_id()).unwrap_or_default();
+    let mut changes = Vec::new();
+    for entry in &current.entries {
+        match &entry.id {
+            None => changes.push(FileChange::Added(entry.clone())),
+            Some(id) => match base_ids.get(id.as_str()) {
+                Some(previous) if previous.same_content(entry) => {}
+                _ => changes.push(FileChange::Edited(entry.clone())),
+            },
+        }
+    }
+    if base.is_some() {
+        let current_ids = current.by_id();
+        let mut removed: Vec<&str> = base_ids
+            .keys()
+            .copied()
+            .filter(|id| !current_ids.contains_key(id))
+            .collect();
+        removed.sort_unstable();
+        changes.extend(
+            removed
+                .into_iter()
+                .map(|id| FileChange::Removed(id.to_string())),
+        );
+    }
+    changes
+}
+
+/// Metadata for an entity after a file edit: the note replaces the shown
+/// note key, other metadata is kept
+pub fn apply_note(metadata: Option<&serde_json::Value>, note: Option<&str>) -> serde_json::Value {
+    let mut map = match metadata {
+        Some(serde_json::Value::Object(map)) => map.clone(),
+        _ => serde_json::Map::new(),
+    };
+    let key = NOTE_KEYS
+        .iter()
+        .find(|k| map.get(**k).is_some_and(|v| v.is_string()))
+        .copied()
+        .unwrap_or("note");
+    match note {
+        Some(note) => {
+            map.insert(key.to_string(), serde_json::Value::String(note.to_string()));
+        }
+        None => {
+            map.remove(key);
+        }
+    }
+    serde_json::Value::Object(map)
+}
+
+/// A fact edited both in the file and in the graph since the last sync
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryConflict {
+    pub entity_id: String,
+    pub file_version: String,
+    pub graph_version: String,
+    /// The side that was kept
+    pub resolution: ConflictPolicy,
+}
+
+/// What a MEMORY.md sync did
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MemorySyncReport {
+    /// Entities created from new bullets
+    pub created: Vec<String>,
+    /// Entities updated from edited bullets
+    pub updated: Vec<String>,
+    /// Entities archived because their bullet was deleted
+    pub archived: Vec<String>,
+    pub conflicts: Vec<MemoryConflict>,
+    /// Facts in the regenerated file
+    pub entries: usize,
+    /// Whether the file was rewritten
+    pub file_written: bool,
+}
+
+impl MemorySyncReport {
+    /// Whether the graph was changed by file edits
+    pub fn graph_changed(&self) -> bool {
+        !self.created.is_empty() || !self.updated.is_empty() || !self.archived.is_empty()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -162,4 +557,97 @@ mod tests {
         assert_eq!(loaded, content);
         Ok(())
     }
+
+    fn sample_document() -> &'static str {
+        "# Memory\n\nFree text stays.\n\n<!-- meepo:memory:start -->\n\
+         <!-- hint -->\n\n## Preference\n\n\
+         - Dark roast coffee — only before noon <!-- id:p1 -->\n\
+         - Standing desk <!-- id:p2 -->\n\n\
+         ## Work project\n\n- Billing migration -- due in May\n\n\
+         <!-- meepo:memory:end -->\n\nFooter.\n"
+    }
+
+    #[test]
+    fn test_parse_memory_document() {
+        let doc = MemoryDocument::parse(sample_document());
+        assert_eq!(doc.before, "# Memory\n\nFree text stays.\n\n");
+        assert_eq!(doc.after.trim(), "Footer.");
+        assert_eq!(doc.entries.len(), 3);
+        assert_eq!(
+            doc.entries[0],
+            MemoryEntry {
+                id: Some("p1".to_string()),
+                entity_type: "preference".to_string(),
+                name: "Dark roast coffee".to_string(),
+                note: Some("only before noon".to_string()),
+            }
+        );
+        assert_eq!(doc.entries[1].note, None);
+        assert_eq!(doc.entries[2].entity_type, "work_project");
+        assert_eq!(doc.entries[2].id, None);
+        assert_eq!(doc.entries[2].note.as_deref(), Some("due in May"));
+
+        // Unstructured files are kept as free text
+        let legacy = MemoryDocument::parse("# Notes\n\n- a bullet\n");
+        assert!(legacy.entries.is_empty());
+        assert_eq!(legacy.before, "# Notes\n\n- a bullet\n");
+    }
+
+    #[test]
+    fn test_render_roundtrip() {
+        let doc = MemoryDocument::parse(sample_document());
+        let rendered = doc.render();
+        assert!(
+            rendered.starts_with("# Memory\n\nFree text stays.\n\n<!-- meepo:memory:start -->")
+        );
+        assert!(rendered.contains("\n## Work project\n\n- Billing migration — due in May\n"));
+        assert!(rendered.ends_with("<!-- meepo:memory:end -->\n\nFooter.\n"));
+        assert_eq!(MemoryDocument::parse(&rendered), doc);
+        assert_eq!(MemoryDocument::parse(&rendered).render(), rendered);
+        assert_eq!(section_type(&section_title("work_project")), "work_project");
+    }
+
+    #[test]
+    fn test_file_changes_against_base() {
+        let base = MemoryDocument::parse(sample_document());
+        let edited = sample_document()
+            .replace("only before noon", "any time")
+            .replace("- Standing desk <!-- id:p2 -->\n", "");
+        let current = MemoryDocument::parse(&edited);
+
+        let changes = file_changes(&current, Some(&base));
+        assert_eq!(changes.len(), 3);
+        assert!(
+            matches!(&changes[0], FileChange::Edited(e) if e.note.as_deref() == Some("any time"))
+        );
+        assert!(matches!(&changes[1], FileChange::Added(e) if e.name == "Billing migration"));
+        assert_eq!(changes[2], FileChange::Removed("p2".to_string()));
+
+        // First sync: nothing is removed, known facts are checked against the graph
+        let first = file_changes(&base, None);
+        assert_eq!(first.len(), 3);
+        assert!(!first.iter().any(|c| matches!(c, FileChange::Removed(_))));
+    }
+
+    #[test]
+    fn test_entry_from_entity_and_apply_note() {
+        let entity = Entity {
+            id: "e1".to_string(),
+            name: "Alice\nSmith".to_string(),
+            entity_type: "person".to_string(),
+            metadata: Some(serde_json::json!({"description": "Leads   billing", "team": "pay"})),
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        };
+        let entry = MemoryEntry::from_entity(&entity);
+        assert_eq!(entry.name, "Alice Smith");
+        assert_eq!(entry.note.as_deref(), Some("Leads billing"));
+
+        let metadata = apply_note(entity.metadata.as_ref(), Some("Runs payments"));
+        assert_eq!(metadata["description"], "Runs payments");
+        assert_eq!(metadata["team"], "pay");
+        assert_eq!(apply_note(None, Some("x"))["note"], "x");
+        assert!("graph".parse::<ConflictPolicy>().is_ok());
+        assert!("both".parse::<ConflictPolicy>().is_err());
+    }
 }
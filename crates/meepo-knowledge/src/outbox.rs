@@ -0,0 +1,150 @@
+//! Change outbox keeping the search indexes consistent with SQLite
+//!
+//! Every insert, update and delete on `entities` appends a row to the
+//! `index_outbox` table from an SQLite trigger, in the same transaction as
+//! the change itself. Each index (Tantivy, the vector index) is a consumer
+//! with its own cursor in `index_cursors` and applies the changes after its
+//! cursor. Applying a change re-reads the entity's current state, so
+//! replaying a change twice is harmless and a crash between the SQLite write
+//! and the index write is repaired by the next sync (at the latest on startup).
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::sqlite::Entity;
+
+/// Outbox consumer name of the Tantivy full-text index
+pub const TANTIVY_CONSUMER: &str = "tantivy";
+
+/// Outbox consumer name of the vector index
+pub const VECTOR_CONSUMER: &str = "vectors";
+
+/// Number of outbox rows applied per index commit
+pub(crate) const OUTBOX_BATCH: usize = 500;
+
+/// Kind of entity mutation recorded in the outbox
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IndexOp {
+    Upsert,
+    Delete,
+}
+
+impl IndexOp {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            IndexOp::Upsert => "upsert",
+            IndexOp::Delete => "delete",
+        }
+    }
+}
+
+impl std::str::FromStr for IndexOp {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "upsert" => Ok(IndexOp::Upsert),
+            "delete" => Ok(IndexOp::Delete),
+            other => anyhow::bail!("Unknown outbox operation '{}'", other),
+        }
+    }
+}
+
+/// One row of the outbox
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndexChange {
+    pub seq: i64,
+    pub entity_id: String,
+    pub op: IndexOp,
+}
+
+/// A change ready to apply: the entity's current state, or None if it no
+/// longer exists and should be removed from the index
+#[derive(Debug, Clone)]
+pub struct PendingChange {
+    pub entity_id: String,
+    pub entity: Option<Entity>,
+}
+
+/// Collapse changes to the last one per entity, keeping outbox order
+pub fn coalesce(changes: &[IndexChange]) -> Vec<&IndexChange> {
+    let last: HashMap<&str, i64> = changes
+        .iter()
+        .map(|c| (c.entity_id.as_str(), c.seq))
+        .collect();
+    changes
+        .iter()
+        .filter(|c| last.get(c.entity_id.as_str()) == Some(&c.seq))
+        .collect()
+}
+
+/// How far a consumer is behind the outbox
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConsumerLag {
+    pub consumer: String,
+    pub applied_seq: i64,
+    pub pending: usize,
+}
+
+/// Differences between the entities table and the search index
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConsistencyReport {
+    /// Outbox changes not yet applied to the search index
+    pub pending_changes: usize,
+    /// Entities that are missing from the search index
+    pub missing_from_index: Vec<String>,
+    /// Index documents whose entity no longer exists
+    pub orphaned_in_index: Vec<String>,
+    /// Whether the differences were repaired
+    pub repaired: bool,
+}
+
+impl ConsistencyReport {
+    pub fn is_consistent(&self) -> bool {
+        self.pending_changes == 0
+            && self.missing_from_index.is_empty()
+            && self.orphaned_in_index.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn change(seq: i64, id: &str, op: IndexOp) -> IndexChange {
+        IndexChange {
+            seq,
+            entity_id: id.to_string(),
+            op,
+        }
+    }
+
+    #[test]
+    fn test_coalesce_keeps_last_change_per_entity() {
+        let changes = vec![
+            change(1, "a", IndexOp::Upsert),
+            change(2, "b", IndexOp::Upsert),
+            change(3, "a", IndexOp::Upsert),
+            change(4, "a", IndexOp::Delete),
+        ];
+        let kept: Vec<(i64, IndexOp)> = coalesce(&changes).iter().map(|c| (c.seq, c.op)).collect();
+        assert_eq!(kept, vec![(2, IndexOp::Upsert), (4, IndexOp::Delete)]);
+    }
+
+    #[test]
+    fn test_index_op_roundtrip() {
+        for op in [IndexOp::Upsert, IndexOp::Delete] {
+            assert_eq!(op.as_str().parse::<IndexOp>().unwrap(), op);
+        }
+        assert!("rename".parse::<IndexOp>().is_err());
+    }
+
+    #[test]
+    fn test_consistency_report() {
+        let mut report = ConsistencyReport::default();
+        assert!(report.is_consistent());
+        report.orphaned_in_index.push("gone".to_string());
+        assert!(!report.is_consistent());
+    }
+}
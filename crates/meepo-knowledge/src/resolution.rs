@@ -0,0 +1,574 @@
+//! Entity resolution — detecting and merging duplicate entities
+//!
+//! Over time the same real-world thing gets remembered under slightly different
+//! names ("Alice", "alice smith", "Alice (coworker)"). This module scores
+//! candidate duplicates by normalized name, aliases and (optionally) embedding
+//! similarity, and drives merges either at insert time or as a periodic
+//! maintenance pass. Ambiguous pairs can be confirmed by an LLM via
+//! [`MergeConfirmer`].
+
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use tracing::{debug, info, warn};
+
+use crate::embeddings::{EmbeddingProvider, VectorIndex};
+use crate::sqlite::Entity;
+
+/// Configuration for entity resolution
+#[derive(Debug, Clone)]
+pub struct ResolutionConfig {
+    /// Whether entity resolution is enabled at all
+    pub enabled: bool,
+    /// Resolve against existing entities when adding a new one
+    pub resolve_on_insert: bool,
+    /// Pairs scoring at or above this are merged without confirmation
+    pub auto_merge_threshold: f32,
+    /// Pairs scoring at or above this (but below auto-merge) are candidates
+    /// that need confirmation before merging
+    pub candidate_threshold: f32,
+    /// Minimum cosine similarity for an embedding-based match
+    pub embedding_threshold: f32,
+    /// Entity types that are never resolved (e.g. document chunks)
+    pub exclude_types: Vec<String>,
+    /// Maximum pairs examined per maintenance pass
+    pub max_pairs_per_pass: usize,
+}
+
+impl Default for ResolutionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            resolve_on_insert: true,
+            auto_merge_threshold: 1.0, // only exact normalized-name / alias matches
+            candidate_threshold: 0.6,
+            embedding_threshold: 0.92,
+            exclude_types: vec!["document".to_string(), "document_chunk".to_string()],
+            max_pairs_per_pass: 200,
+        }
+    }
+}
+
+impl ResolutionConfig {
+    /// Whether entities of this type participate in resolution. Community
+    /// summaries are derived from the graph and never merged.
+    pub fn applies_to(&self, entity_type: &str) -> bool {
+        self.enabled
+            && entity_type != crate::communities::COMMUNITY_TYPE
+            && !self.exclude_types.iter().any(|t| t == entity_type)
+    }
+}
+
+/// Why two entities were considered the same
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchReason {
+    /// Names are identical after normalization
+    ExactName,
+    /// Name matches a recorded alias
+    Alias,
+    /// One name's tokens contain the other's, or they overlap heavily
+    TokenOverlap,
+    /// Embedding vectors are nearly identical
+    Embedding,
+    /// Merged explicitly by the user
+    Manual,
+}
+
+/// A scored duplicate candidate for an entity
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MatchCandidate {
+    pub entity: Entity,
+    pub score: f32,
+    pub reason: MatchReason,
+}
+
+/// A pair of entities that may be duplicates
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CandidatePair {
+    /// The entity that would be kept (the older one)
+    pub keep: Entity,
+    /// The entity that would be merged into `keep`
+    pub merge: Entity,
+    pub score: f32,
+    pub reason: MatchReason,
+}
+
+/// Record of a completed merge
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MergeRecord {
+    pub kept_id: String,
+    pub merged_id: String,
+    pub merged_name: String,
+    pub score: f32,
+    pub reason: MatchReason,
+    pub confirmed_by_llm: bool,
+}
+
+/// A merge that was attempted and failed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FailedMerge {
+    pub kept_id: String,
+    pub merged_id: String,
+    pub merged_name: String,
+    pub error: String,
+}
+
+/// Outcome of a resolution maintenance pass
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ResolutionReport {
+    pub pairs_examined: usize,
+    pub merged: Vec<MergeRecord>,
+    /// Candidates that were not merged (no confirmer, or confirmer declined)
+    pub pending: Vec<CandidatePair>,
+    /// Merges that were decided on but failed
+    pub failed: Vec<FailedMerge>,
+}
+
+/// Confirms whether two entities refer to the same thing (typically via an LLM)
+#[async_trait]
+pub trait MergeConfirmer: Send + Sync {
+    async fn confirm(&self, a: &Entity, b: &Entity) -> Result<bool>;
+}
+
+/// Normalize an entity name for comparison.
+///
+/// Lowercases, drops parenthesized qualifiers ("Alice (coworker)"), replaces
+/// punctuation with spaces and collapses whitespace.
+pub fn normalize_name(name: &str) -> String {
+    let mut out = String::with_capacity(name.len());
+    let mut depth = 0usize;
+    for c in name.chars() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            _ if depth > 0 => {}
+            c if c.is_alphanumeric() => out.extend(c.to_lowercase()),
+            _ => out.push(' '),
+        }
+    }
+    out.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn tokens(normalized: &str) -> HashSet<&str> {
+    normalized.split(' ').filter(|t| !t.is_empty()).collect()
+}
+
+/// Score how likely two names refer to the same entity (0.0 to 1.0)
+pub fn name_similarity(a: &str, b: &str) -> f32 {
+    let (na, nb) = (normalize_name(a), normalize_name(b));
+    if na.is_empty() || nb.is_empty() {
+        return 0.0;
+    }
+    if na == nb {
+        return 1.0;
+    }
+
+    let (ta, tb) = (tokens(&na), tokens(&nb));
+    let shared = ta.intersection(&tb).count();
+    if shared == 0 {
+        return 0.0;
+    }
+
+    // "alice" vs "alice smith": all tokens of the shorter name are present
+    if shared == ta.len().min(tb.len()) {
+        return 0.8;
+    }
+
+    let union = ta.union(&tb).count();
+    shared as f32 / union as f32
+}
+
+/// Aliases recorded in an entity's metadata
+pub fn aliases_of(entity: &Entity) -> Vec<String> {
+    entity
+        .metadata
+        .as_ref()
+        .and_then(|m| m.get("aliases"))
+        .and_then(|a| a.as_array())
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Score a name against an existing entity, considering its aliases
+pub fn score_against(name: &str, entity: &Entity) -> (f32, MatchReason) {
+    let direct = name_similarity(name, &entity.name);
+    let mut best = (
+        direct,
+        if direct >= 1.0 {
+            MatchReason::ExactName
+        } else {
+            MatchReason::TokenOverlap
+        },
+    );
+
+    let normalized = normalize_name(name);
+    for alias in aliases_of(entity) {
+        if normalize_name(&alias) == normalized {
+            return (1.0, MatchReason::Alias);
+        }
+        let score = name_similarity(name, &alias);
+        if score > best.0 {
+            best = (score, MatchReason::TokenOverlap);
+        }
+    }
+    best
+}
+
+/// Rank existing entities as duplicates of `name`, best first.
+///
+/// `existing` should already be restricted to the same entity type. When a
+/// vector index and provider are given, near-identical embeddings count as
+/// matches even if the names share no tokens.
+pub fn rank_candidates(
+    name: &str,
+    existing: &[Entity],
+    config: &ResolutionConfig,
+    vectors: Option<(&VectorIndex, &dyn EmbeddingProvider)>,
+) -> Vec<MatchCandidate> {
+    let mut scored: HashMap<String, MatchCandidate> = HashMap::new();
+
+    for entity in existing {
+        let (score, reason) = score_against(name, entity);
+        if score >= config.candidate_threshold {
+            scored.insert(
+                entity.id.clone(),
+                MatchCandidate {
+                    entity: entity.clone(),
+                    score,
+                    reason,
+                },
+            );
+        }
+    }
+
+    if let Some((index, provider)) = vectors {
+        match provider.embed(name) {
+            Ok(query) => {
+                let by_id: HashMap<&str, &Entity> =
+                    existing.iter().map(|e| (e.id.as_str(), e)).collect();
+                for hit in index.search(&query, 10) {
+                    if hit.similarity < config.embedding_threshold {
+                        continue;
+                    }
+                    let Some(entity) = by_id.get(hit.entity_id.as_str()) else {
+                        continue;
+                    };
+                    // Embedding matches are never auto-merged on their own
+                    let score = hit.similarity.min(0.99);
+                    let entry = scored.entry(entity.id.clone()).or_insert(MatchCandidate {
+                        entity: (*entity).clone(),
+                        score,
+                        reason: MatchReason::Embedding,
+                    });
+                    if score > entry.score {
+                        entry.score = score;
+                        entry.reason = MatchReason::Embedding;
+                    }
+                }
+            }
+            Err(e) => warn!("Embedding lookup failed during entity resolution: {}", e),
+        }
+    }
+
+    let mut candidates: Vec<MatchCandidate> = scored.into_values().collect();
+    candidates.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then(a.entity.created_at.cmp(&b.entity.created_at))
+    });
+    candidates
+}
+
+/// Find likely duplicate pairs within a set of entities.
+///
+/// Entities are blocked by type and shared name tokens so the comparison stays
+/// far below quadratic on real graphs. The older entity of each pair is the one
+/// kept. Each entity appears in at most one pair per pass.
+pub fn find_duplicate_pairs(entities: &[Entity], config: &ResolutionConfig) -> Vec<CandidatePair> {
+    let mut blocks: HashMap<(String, String), Vec<usize>> = HashMap::new();
+    let normalized: Vec<Vec<String>> = entities
+        .iter()
+        .map(|e| {
+            let mut names = vec![normalize_name(&e.name)];
+            names.extend(aliases_of(e).iter().map(|a| normalize_name(a)));
+            names
+        })
+        .collect();
+
+    for (i, entity) in entities.iter().enumerate() {
+        if !config.applies_to(&entity.entity_type) {
+            continue;
+        }
+        let mut seen = HashSet::new();
+        for name in &normalized[i] {
+            for token in name.split(' ').filter(|t| !t.is_empty()) {
+                if seen.insert(token.to_string()) {
+                    blocks
+                        .entry((entity.entity_type.clone(), token.to_string()))
+                        .or_default()
+                        .push(i);
+                }
+            }
+        }
+    }
+
+    let mut compared = HashSet::new();
+    let mut pairs = Vec::new();
+    for members in blocks.values() {
+        // Very common tokens ("the", "project") make useless blocks
+        if members.len() > 200 {
+            continue;
+        }
+        for (x, &i) in members.iter().enumerate() {
+            for &j in &members[x + 1..] {
+                let key = (i.min(j), i.max(j));
+                if !compared.insert(key) {
+                    continue;
+                }
+                let (a, b) = (&entities[i], &entities[j]);
+                let (s1, r1) = score_against(&a.name, b);
+                let (s2, r2) = score_against(&b.name, a);
+                let (score, reason) = if s1 >= s2 { (s1, r1) } else { (s2, r2) };
+                if score < config.candidate_threshold {
+                    continue;
+                }
+                let (keep, merge) = if a.created_at <= b.created_at {
+                    (a, b)
+                } else {
+                    (b, a)
+                };
+                pairs.push(CandidatePair {
+                    keep: keep.clone(),
+                    merge: merge.clone(),
+                    score,
+                    reason,
+                });
+            }
+        }
+    }
+
+    pairs.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    // Each entity takes part in at most one merge per pass
+    let mut used = HashSet::new();
+    pairs.retain(|p| {
+        if used.contains(&p.keep.id) || used.contains(&p.merge.id) {
+            return false;
+        }
+        used.insert(p.keep.id.clone());
+        used.insert(p.merge.id.clone());
+        true
+    });
+    pairs.truncate(config.max_pairs_per_pass);
+
+    debug!(
+        "Entity resolution found {} candidate pairs among {} entities",
+        pairs.len(),
+        entities.len()
+    );
+    pairs
+}
+
+/// Decide which candidate pairs to merge, consulting the confirmer for
+/// pairs below the auto-merge threshold.
+///
+/// Returns `(to_merge, pending)` where each merge carries whether the LLM
+/// confirmed it.
+pub async fn triage_pairs(
+    pairs: Vec<CandidatePair>,
+    config: &ResolutionConfig,
+    confirmer: Option<&dyn MergeConfirmer>,
+) -> (Vec<(CandidatePair, bool)>, Vec<CandidatePair>) {
+    let mut to_merge = Vec::new();
+    let mut pending = Vec::new();
+
+    for pair in pairs {
+        if pair.score >= config.auto_merge_threshold {
+            to_merge.push((pair, false));
+            continue;
+        }
+        match confirmer {
+            Some(c) => match c.confirm(&pair.keep, &pair.merge).await {
+                Ok(true) => to_merge.push((pair, true)),
+                Ok(false) => {
+                    debug!(
+                        "Merge of '{}' into '{}' declined by confirmer",
+                        pair.merge.name, pair.keep.name
+                    );
+                }
+                Err(e) => {
+                    warn!("Merge confirmation failed: {}", e);
+                    pending.push(pair);
+                }
+            },
+            None => pending.push(pair),
+        }
+    }
+
+    info!(
+        "Entity resolution triage: {} to merge, {} pending",
+        to_merge.len(),
+        pending.len()
+    );
+    (to_merge, pending)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, Utc};
+
+    fn entity(id: &str, name: &str, entity_type: &str, age_days: i64) -> Entity {
+        let created = Utc::now() - Duration::days(age_days);
+        Entity {
+            id: id.to_string(),
+            name: name.to_string(),
+            entity_type: entity_type.to_string(),
+            metadata: None,
+            created_at: created,
+            updated_at: created,
+        }
+    }
+
+    #[test]
+    fn test_normalize_name() {
+        assert_eq!(normalize_name("Alice (coworker)"), "alice");
+        assert_eq!(normalize_name("  Alice   SMITH "), "alice smith");
+        assert_eq!(normalize_name("O'Brien, Pat"), "o brien pat");
+        assert_eq!(normalize_name("(only qualifier)"), "");
+    }
+
+    #[test]
+    fn test_name_similarity() {
+        assert_eq!(name_similarity("Alice", "alice"), 1.0);
+        assert_eq!(name_similarity("Alice", "Alice (coworker)"), 1.0);
+        assert_eq!(name_similarity("Alice", "alice smith"), 0.8);
+        assert_eq!(name_similarity("Alice", "Bob"), 0.0);
+        let partial = name_similarity("Alice Smith", "Alice Jones");
+        assert!(partial > 0.0 && partial < 0.6);
+    }
+
+    #[test]
+    fn test_score_against_alias() {
+        let mut e = entity("1", "Robert Tables", "person", 1);
+        e.metadata = Some(serde_json::json!({"aliases": ["Bobby"]}));
+        assert_eq!(score_against("bobby", &e), (1.0, MatchReason::Alias));
+        assert_eq!(score_against("Robert Tables", &e).1, MatchReason::ExactName);
+    }
+
+    #[test]
+    fn test_rank_candidates() {
+        let existing = vec![
+            entity("1", "Alice Smith", "person", 3),
+            entity("2", "Alice", "person", 2),
+            entity("3", "Bob", "person", 1),
+        ];
+        let config = ResolutionConfig::default();
+        let ranked = rank_candidates("alice (coworker)", &existing, &config, None);
+        assert_eq!(ranked.len(), 2);
+        assert_eq!(ranked[0].entity.id, "2");
+        assert_eq!(ranked[0].reason, MatchReason::ExactName);
+        assert_eq!(ranked[1].entity.id, "1");
+    }
+
+    #[test]
+    fn test_rank_candidates_with_embeddings() {
+        let existing = vec![entity("1", "Acme Corporation", "company", 1)];
+        let index = VectorIndex::new(3);
+        index.insert("1", vec![1.0, 0.0, 0.0]).unwrap();
+
+        struct FixedProvider;
+        impl EmbeddingProvider for FixedProvider {
+            fn embed(&self, _text: &str) -> Result<Vec<f32>> {
+                Ok(vec![1.0, 0.0, 0.0])
+            }
+            fn dimensions(&self) -> usize {
+                3
+            }
+        }
+
+        let config = ResolutionConfig::default();
+        let ranked = rank_candidates("ACME Inc", &existing, &config, None);
+        assert!(ranked.is_empty());
+
+        let ranked = rank_candidates(
+            "The widget company",
+            &existing,
+            &config,
+            Some((&index, &FixedProvider)),
+        );
+        assert_eq!(ranked.len(), 1);
+        assert_eq!(ranked[0].reason, MatchReason::Embedding);
+        assert!(ranked[0].score < config.auto_merge_threshold);
+    }
+
+    #[test]
+    fn test_find_duplicate_pairs() {
+        let entities = vec![
+            entity("a", "Alice", "person", 10),
+            entity("b", "alice smith", "person", 5),
+            entity("c", "Alice (coworker)", "person", 1),
+            entity("d", "Alice", "company", 1),
+            entity("e", "Alice [chunk 1/2]", "document_chunk", 1),
+            entity("f", "Alice [chunk 1/2]", "document_chunk", 1),
+        ];
+        let pairs = find_duplicate_pairs(&entities, &ResolutionConfig::default());
+
+        // Only one pair involving "a" per pass; the exact match wins
+        assert_eq!(pairs.len(), 1);
+        assert_eq!(pairs[0].keep.id, "a");
+        assert_eq!(pairs[0].merge.id, "c");
+        assert_eq!(pairs[0].score, 1.0);
+    }
+
+    struct YesConfirmer;
+
+    #[async_trait]
+    impl MergeConfirmer for YesConfirmer {
+        async fn confirm(&self, _a: &Entity, _b: &Entity) -> Result<bool> {
+            Ok(true)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_triage_pairs() {
+        let config = ResolutionConfig::default();
+        let pairs = || {
+            vec![
+                CandidatePair {
+                    keep: entity("a", "Alice", "person", 2),
+                    merge: entity("b", "alice", "person", 1),
+                    score: 1.0,
+                    reason: MatchReason::ExactName,
+                },
+                CandidatePair {
+                    keep: entity("c", "Carol", "person", 2),
+                    merge: entity("d", "Carol Diaz", "person", 1),
+                    score: 0.8,
+                    reason: MatchReason::TokenOverlap,
+                },
+            ]
+        };
+
+        let (merge, pending) = triage_pairs(pairs(), &config, None).await;
+        assert_eq!(merge.len(), 1);
+        assert!(!merge[0].1);
+        assert_eq!(pending.len(), 1);
+
+        let (merge, pending) = triage_pairs(pairs(), &config, Some(&YesConfirmer)).await;
+        assert_eq!(merge.len(), 2);
+        assert!(merge[1].1);
+        assert!(pending.is_empty());
+    }
+}
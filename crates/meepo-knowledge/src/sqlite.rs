@@ -5,11 +5,25 @@ use chrono::{DateTime, Utc};
 use rusqlite::{Connection, OptionalExtension, params};
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
+use std::collections::HashMap;
 use std::path::Path;
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, MutexGuard};
 use tracing::{debug, info, warn};
 use uuid::Uuid;
 
+use crate::corpus::{Corpus, CorpusFile};
+use crate::episodic::{CandidateStatus, MemoryCandidate, MemoryProposal};
+use crate::importance::{ArchivedMemory, EntityStats};
+use crate::outbox::{self, ConsumerLag, IndexChange, IndexOp, OUTBOX_BATCH, PendingChange};
+use crate::temporal::{self, FactOptions, FactType, Provenance, ProvenanceRecord, RecordedFact};
+
+/// Entity usage statistics joined with link degree
+const ENTITY_STATS_QUERY: &str = "SELECT e.id, e.name, e.entity_type, e.created_at, e.updated_at,
+        COALESCE(s.access_count, 0), s.last_accessed_at, COALESCE(s.pinned, 0),
+        (SELECT COUNT(*) FROM relationships r WHERE r.source_id = e.id)
+          + (SELECT COUNT(*) FROM relationships r WHERE r.target_id = e.id)
+     FROM entities e LEFT JOIN entity_stats s ON s.entity_id = e.id";
+
 /// Entity in the knowledge graph
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Entity {
@@ -32,6 +46,15 @@ pub struct Relationship {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub metadata: Option<JsonValue>,
     pub created_at: DateTime<Utc>,
+    /// When the fact became true (None = since it was recorded)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub valid_from: Option<DateTime<Utc>>,
+    /// When the fact stopped being true (None = still current)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub valid_to: Option<DateTime<Utc>>,
+    /// ID of the relationship that replaced this one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub superseded_by: Option<String>,
 }
 
 /// Conversation record
@@ -44,18 +67,124 @@ pub struct Conversation {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub metadata: Option<JsonValue>,
     pub created_at: DateTime<Utc>,
+    /// Thread the message belongs to, see [`conversation_thread_id`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thread_id: Option<String>,
+}
+
+/// ID of the thread a message belongs to.
+///
+/// Messages with a channel-native thread or reply-chain ID (a Slack
+/// `thread_ts`, a Discord channel, an iMessage chat) are grouped under
+/// `channel:native`; everything else falls into the channel's default thread,
+/// whose ID is the channel name.
+pub fn conversation_thread_id(channel: &str, native: Option<&str>) -> String {
+    match native.map(str::trim) {
+        Some(native) if !native.is_empty() => format!("{}:{}", channel, native),
+        _ => channel.to_string(),
+    }
 }
 
-/// Watcher configuration
+/// A conversation thread
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Watcher {
+pub struct ConversationThread {
     pub id: String,
-    pub kind: String,
-    pub config: JsonValue,
-    pub action: String,
-    pub reply_channel: String,
-    pub active: bool,
+    pub channel: String,
+    /// Start of the first message in the thread
+    pub title: String,
     pub created_at: DateTime<Utc>,
+    pub last_message_at: DateTime<Utc>,
+    pub message_count: usize,
+    /// Time of the last message handed to episodic memory extraction
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extracted_at: Option<DateTime<Utc>>,
+}
+
+/// Filters for [`KnowledgeDb::search_conversations`]
+#[derive(Debug, Clone)]
+pub struct ConversationSearch {
+    pub channel: Option<String>,
+    pub thread_id: Option<String>,
+    pub limit: usize,
+    /// Messages of surrounding context to return on each side of a match
+    pub context: usize,
+}
+
+impl Default for ConversationSearch {
+    fn default() -> Self {
+        Self {
+            channel: None,
+            thread_id: None,
+            limit: 10,
+            context: 2,
+        }
+    }
+}
+
+/// A message matching a conversation search, with the messages around it
+#[derive(Debug, Clone, Serialize)]
+pub struct ConversationMatch {
+    pub message: Conversation,
+    /// Excerpt around the matched terms, which are wrapped in `**`
+    pub excerpt: String,
+    /// BM25 relevance, higher is better
+    pub score: f64,
+    /// Earlier messages of the same thread, oldest first
+    pub before: Vec<Conversation>,
+    /// Later messages of the same thread, oldest first
+    pub after: Vec<Conversation>,
+}
+
+/// Longest thread title, in characters
+const THREAD_TITLE_CHARS: usize = 80;
+
+/// Columns read by [`KnowledgeDb::row_to_conversation`]
+const CONVERSATION_COLUMNS: &str = "id, channel, sender, content, metadata, created_at, thread_id";
+
+/// Words too common to narrow a conversation search
+const SEARCH_STOP_WORDS: &[&str] = &[
+    "about", "all", "an", "and", "any", "are", "as", "at", "be", "but", "by", "can", "did", "do",
+    "for", "from", "had", "has", "have", "he", "her", "his", "how", "in", "is", "it", "its", "me",
+    "my", "of", "on", "or", "our", "she", "so", "that", "the", "their", "them", "there", "they",
+    "this", "to", "us", "was", "we", "were", "what", "when", "where", "which", "who", "why",
+    "will", "with", "you", "your",
+];
+
+/// Turn free text into an FTS5 query matching any of its words
+fn fts_query(text: &str) -> Option<String> {
+    let terms: Vec<String> = text
+        .split(|c: char| !c.is_alphanumeric())
+        .map(str::to_lowercase)
+        .filter(|t| t.chars().count() > 1 && !SEARCH_STOP_WORDS.contains(&t.as_str()))
+        .map(|t| format!("\"{}\"", t))
+        .collect();
+    (!terms.is_empty()).then(|| terms.join(" OR "))
+}
+
+/// Create a thread on its first message and bump its last activity
+fn touch_thread(
+    conn: &Connection,
+    thread_id: &str,
+    channel: &str,
+    first_message: &str,
+    at: &DateTime<Utc>,
+) -> rusqlite::Result<()> {
+    let title: String = first_message
+        .lines()
+        .find(|l| !l.trim().is_empty())
+        .unwrap_or("")
+        .trim()
+        .chars()
+        .take(THREAD_TITLE_CHARS)
+        .collect();
+    conn.execute(
+        "INSERT INTO conversation_threads (id, channel, title, created_at, last_message_at)
+         VALUES (?1, ?2, ?3, ?4, ?4)
+         ON CONFLICT(id) DO UPDATE SET
+            last_message_at = MAX(last_message_at, excluded.last_message_at)",
+        params![thread_id, channel, title, at.to_rfc3339()],
+    )?;
+    Ok(())
 }
 
 /// Autonomous goal tracked by the agent
@@ -162,23 +291,78 @@ pub struct BackgroundTask {
 /// SQLite database wrapper (thread-safe via Arc<Mutex>)
 pub struct KnowledgeDb {
     conn: Arc<Mutex<Connection>>,
+    /// One lock per outbox consumer, held for a whole drain so concurrent
+    /// drains of the same index don't claim the same changes while the
+    /// connection itself is released
+    drains: Mutex<HashMap<String, Arc<Mutex<()>>>>,
 }
 
 impl KnowledgeDb {
     /// Initialize database with schema
     pub fn new<P: AsRef<Path>>(path: P) -> Result<Self> {
+        if crate::encryption::is_encrypted(path.as_ref())? {
+            anyhow::bail!(
+                "Knowledge database at {:?} is encrypted; enable [knowledge.encryption] to open it",
+                path.as_ref()
+            );
+        }
         let conn = Connection::open(path.as_ref()).context("Failed to open SQLite database")?;
 
         info!("Initializing knowledge database at {:?}", path.as_ref());
 
         // Security note: The knowledge database stores conversation history, entities,
-        // goals, and action logs in plaintext. Consider using SQLCipher for encryption
-        // if the host machine is shared or the data is sensitive.
+        // goals, and action logs in plaintext unless opened with `new_encrypted`.
         warn!(
             "Knowledge database is NOT encrypted. Conversation history and agent data are stored in plaintext at {:?}",
             path.as_ref()
         );
 
+        Self::init(conn)
+    }
+
+    /// Open (or create) a SQLCipher-encrypted database with `key`.
+    /// Fails if the key is wrong or the database is still plaintext.
+    pub fn new_encrypted<P: AsRef<Path>>(path: P, key: &str) -> Result<Self> {
+        if path.as_ref().exists() && !crate::encryption::is_encrypted(path.as_ref())? {
+            anyhow::bail!(
+                "Knowledge database at {:?} is not encrypted; run `meepo memory encrypt` first",
+                path.as_ref()
+            );
+        }
+        let conn = crate::encryption::open_connection(path.as_ref(), Some(key))?;
+
+        info!(
+            "Initializing encrypted knowledge database at {:?}",
+            path.as_ref()
+        );
+
+        Self::init(conn)
+    }
+
+    /// Rotate the encryption key of an open encrypted database
+    pub async fn rekey(&self, new_key: &str) -> Result<()> {
+        if new_key.is_empty() {
+            anyhow::bail!("New encryption key is empty");
+        }
+        let conn = Arc::clone(&self.conn);
+        let new_key = new_key.to_owned();
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            conn.pragma_update(None, "rekey", &new_key)
+                .context("Failed to rotate knowledge database key")?;
+            info!("Rotated knowledge database encryption key");
+            Ok(())
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// Create the schema and run migrations on a freshly opened connection
+    fn init(conn: Connection) -> Result<Self> {
         // Enable foreign keys
         conn.execute("PRAGMA foreign_keys = ON", [])?;
 
@@ -210,6 +394,139 @@ impl KnowledgeDb {
             [],
         )?;
 
+        // Migration: temporal validity on relationships
+        let _ = conn.execute("ALTER TABLE relationships ADD COLUMN valid_from TEXT", []);
+        let _ = conn.execute("ALTER TABLE relationships ADD COLUMN valid_to TEXT", []);
+        let _ = conn.execute(
+            "ALTER TABLE relationships ADD COLUMN superseded_by TEXT",
+            [],
+        );
+
+        // Create provenance table (links facts to the source that produced them)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS provenance (
+                id TEXT PRIMARY KEY,
+                fact_type TEXT NOT NULL,
+                fact_id TEXT NOT NULL,
+                source_type TEXT NOT NULL,
+                source_id TEXT NOT NULL,
+                excerpt TEXT,
+                recorded_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_provenance_fact ON provenance(fact_type, fact_id)",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_provenance_source ON provenance(source_type, source_id)",
+            [],
+        )?;
+
+        // Create entity_stats table (access tracking and pinning for importance scoring)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS entity_stats (
+                entity_id TEXT PRIMARY KEY,
+                access_count INTEGER NOT NULL DEFAULT 0,
+                last_accessed_at TEXT,
+                pinned INTEGER NOT NULL DEFAULT 0,
+                FOREIGN KEY(entity_id) REFERENCES entities(id) ON DELETE CASCADE
+            )",
+            [],
+        )?;
+
+        // Create archived_memories table (forgotten entities, restorable)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS archived_memories (
+                entity_id TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                entity_type TEXT NOT NULL,
+                importance REAL NOT NULL,
+                payload TEXT NOT NULL,
+                archived_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        // Create index_outbox table (entity changes not yet applied to every
+        // search index) and the per-index cursors into it
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS index_outbox (
+                seq INTEGER PRIMARY KEY AUTOINCREMENT,
+                entity_id TEXT NOT NULL,
+                op TEXT NOT NULL,
+                created_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS index_cursors (
+                consumer TEXT PRIMARY KEY,
+                applied_seq INTEGER NOT NULL,
+                updated_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        // Record every entity mutation in the outbox within the same transaction
+        for (event, row, op) in [
+            ("INSERT", "NEW", "upsert"),
+            ("UPDATE", "NEW", "upsert"),
+            ("DELETE", "OLD", "delete"),
+        ] {
+            conn.execute(
+                &format!(
+                    "CREATE TRIGGER IF NOT EXISTS entities_outbox_{} AFTER {} ON entities
+                     BEGIN
+                         INSERT INTO index_outbox (entity_id, op, created_at)
+                         VALUES ({}.id, '{}', strftime('%Y-%m-%dT%H:%M:%SZ', 'now'));
+                     END",
+                    event.to_lowercase(),
+                    event,
+                    row,
+                    op
+                ),
+                [],
+            )?;
+        }
+
+        // Last content written to (or accepted from) each synced MEMORY.md
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS memory_sync_state (
+                path TEXT PRIMARY KEY,
+                content TEXT NOT NULL,
+                synced_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        // Registered document directories and the files ingested from them
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS corpora (
+                id TEXT PRIMARY KEY,
+                name TEXT NOT NULL UNIQUE,
+                root TEXT NOT NULL,
+                include_patterns TEXT NOT NULL,
+                exclude_patterns TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                last_synced_at TEXT
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS corpus_files (
+                corpus_id TEXT NOT NULL REFERENCES corpora(id) ON DELETE CASCADE,
+                path TEXT NOT NULL,
+                content_hash TEXT NOT NULL,
+                document_id TEXT NOT NULL,
+                chunk_count INTEGER NOT NULL,
+                ingested_at TEXT NOT NULL,
+                PRIMARY KEY (corpus_id, path)
+            )",
+            [],
+        )?;
+
         // Create conversations table
         conn.execute(
             "CREATE TABLE IF NOT EXISTS conversations (
@@ -223,19 +540,105 @@ impl KnowledgeDb {
             [],
         )?;
 
-        // Create watchers table
+        // Create conversation threads table
         conn.execute(
-            "CREATE TABLE IF NOT EXISTS watchers (
+            "CREATE TABLE IF NOT EXISTS conversation_threads (
                 id TEXT PRIMARY KEY,
-                kind TEXT NOT NULL,
-                config TEXT NOT NULL,
-                action TEXT NOT NULL,
-                reply_channel TEXT NOT NULL,
-                active INTEGER NOT NULL DEFAULT 1,
-                created_at TEXT NOT NULL
+                channel TEXT NOT NULL,
+                title TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                last_message_at TEXT NOT NULL
+            )",
+            [],
+        )?;
+
+        // Migration: conversation threads. Existing messages go into the
+        // default thread of their channel.
+        if conn
+            .execute("ALTER TABLE conversations ADD COLUMN thread_id TEXT", [])
+            .is_ok()
+        {
+            conn.execute(
+                "UPDATE conversations SET thread_id = channel WHERE thread_id IS NULL",
+                [],
+            )?;
+            conn.execute(
+                "INSERT OR IGNORE INTO conversation_threads
+                    (id, channel, title, created_at, last_message_at)
+                 SELECT channel, channel, channel, MIN(created_at), MAX(created_at)
+                 FROM conversations GROUP BY channel",
+                [],
+            )?;
+        }
+
+        // Migration: episodic extraction progress. History from before the
+        // migration counts as already extracted.
+        if conn
+            .execute(
+                "ALTER TABLE conversation_threads ADD COLUMN extracted_at TEXT",
+                [],
+            )
+            .is_ok()
+        {
+            conn.execute(
+                "UPDATE conversation_threads SET extracted_at = last_message_at",
+                [],
+            )?;
+        }
+
+        // Create memory candidates table (extracted facts awaiting review)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS memory_candidates (
+                id TEXT PRIMARY KEY,
+                thread_id TEXT NOT NULL,
+                fact_key TEXT NOT NULL,
+                proposal TEXT NOT NULL,
+                status TEXT NOT NULL DEFAULT 'pending',
+                created_at TEXT NOT NULL,
+                decided_at TEXT
             )",
             [],
         )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_memory_candidates_status ON memory_candidates(status, created_at)",
+            [],
+        )?;
+
+        // Full-text index over conversation content, kept in sync by triggers
+        let fts_exists: bool = conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE name = 'conversations_fts')",
+            [],
+            |row| row.get(0),
+        )?;
+        conn.execute_batch(
+            "CREATE VIRTUAL TABLE IF NOT EXISTS conversations_fts USING fts5(
+                content,
+                content = 'conversations',
+                content_rowid = 'rowid',
+                tokenize = 'porter unicode61'
+            );
+            CREATE TRIGGER IF NOT EXISTS conversations_fts_insert
+            AFTER INSERT ON conversations BEGIN
+                INSERT INTO conversations_fts (rowid, content) VALUES (new.rowid, new.content);
+            END;
+            CREATE TRIGGER IF NOT EXISTS conversations_fts_delete
+            AFTER DELETE ON conversations BEGIN
+                INSERT INTO conversations_fts (conversations_fts, rowid, content)
+                VALUES ('delete', old.rowid, old.content);
+            END;
+            CREATE TRIGGER IF NOT EXISTS conversations_fts_update
+            AFTER UPDATE OF content ON conversations BEGIN
+                INSERT INTO conversations_fts (conversations_fts, rowid, content)
+                VALUES ('delete', old.rowid, old.content);
+                INSERT INTO conversations_fts (rowid, content) VALUES (new.rowid, new.content);
+            END;",
+        )?;
+        if !fts_exists {
+            conn.execute(
+                "INSERT INTO conversations_fts (conversations_fts) VALUES ('rebuild')",
+                [],
+            )?;
+        }
 
         // Create indices for better query performance
         conn.execute(
@@ -263,7 +666,11 @@ impl KnowledgeDb {
             [],
         )?;
         conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_watchers_active ON watchers(active)",
+            "CREATE INDEX IF NOT EXISTS idx_conversations_thread ON conversations(thread_id, created_at)",
+            [],
+        )?;
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_conversation_threads_last ON conversation_threads(last_message_at)",
             [],
         )?;
 
@@ -410,6 +817,7 @@ impl KnowledgeDb {
 
         Ok(Self {
             conn: Arc::new(Mutex::new(conn)),
+            drains: Mutex::new(HashMap::new()),
         })
     }
 
@@ -550,9 +958,17 @@ impl KnowledgeDb {
         .context("spawn_blocking task panicked")?
     }
 
-    /// Get all entities (capped to prevent OOM on large databases)
-    pub async fn get_all_entities(&self) -> Result<Vec<Entity>> {
+    /// Get entities named `name` or carrying it as an alias (case-insensitive),
+    /// optionally restricted to some entity types
+    pub async fn find_entities_by_name(
+        &self,
+        name: &str,
+        entity_types: &[String],
+        limit: usize,
+    ) -> Result<Vec<Entity>> {
         let conn = Arc::clone(&self.conn);
+        let name = name.to_owned();
+        let entity_types: Vec<String> = entity_types.iter().map(|t| t.to_lowercase()).collect();
 
         tokio::task::spawn_blocking(move || {
             let conn = conn.lock().unwrap_or_else(|poisoned| {
@@ -562,152 +978,1733 @@ impl KnowledgeDb {
             let mut stmt = conn.prepare(
                 "SELECT id, name, entity_type, metadata, created_at, updated_at
                  FROM entities
+                 WHERE lower(name) = lower(?1)
+                    OR (json_valid(metadata) AND EXISTS (
+                        SELECT 1 FROM json_each(metadata, '$.aliases')
+                        WHERE lower(value) = lower(?1)))
                  ORDER BY updated_at DESC
-                 LIMIT 50000",
+                 LIMIT ?2",
             )?;
-
             let entities = stmt
-                .query_map([], Self::row_to_entity)?
+                .query_map(params![&name, limit as i64], Self::row_to_entity)?
+                .filter(|e| {
+                    e.as_ref().map_or(true, |e| {
+                        entity_types.is_empty()
+                            || entity_types.contains(&e.entity_type.to_lowercase())
+                    })
+                })
                 .collect::<Result<Vec<_>, _>>()?;
-
             Ok(entities)
         })
         .await
         .context("spawn_blocking task panicked")?
     }
 
-    /// Helper to convert row to Entity
-    fn row_to_entity(row: &rusqlite::Row) -> rusqlite::Result<Entity> {
-        let metadata_str: Option<String> = row.get(3)?;
-        let metadata = metadata_str
-            .map(|s| serde_json::from_str(&s))
-            .transpose()
-            .map_err(|e| {
-                rusqlite::Error::FromSqlConversionFailure(
-                    3,
-                    rusqlite::types::Type::Text,
-                    Box::new(e),
-                )
-            })?;
+    /// Get entities of any of the given types (case-insensitive), most recently updated first
+    pub async fn get_entities_by_type(
+        &self,
+        entity_types: &[String],
+        limit: usize,
+    ) -> Result<Vec<Entity>> {
+        if entity_types.is_empty() {
+            return Ok(Vec::new());
+        }
+        let conn = Arc::clone(&self.conn);
+        let entity_types: Vec<String> = entity_types.iter().map(|t| t.to_lowercase()).collect();
 
-        Ok(Entity {
-            id: row.get(0)?,
-            name: row.get(1)?,
-            entity_type: row.get(2)?,
-            metadata,
-            created_at: row
-                .get::<_, String>(4)?
-                .parse()
-                .unwrap_or_else(|_| Utc::now()),
-            updated_at: row
-                .get::<_, String>(5)?
-                .parse()
-                .unwrap_or_else(|_| Utc::now()),
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            let placeholders: Vec<String> = (0..entity_types.len())
+                .map(|i| format!("?{}", i + 2))
+                .collect();
+            let sql = format!(
+                "SELECT id, name, entity_type, metadata, created_at, updated_at
+                 FROM entities
+                 WHERE lower(entity_type) IN ({})
+                 ORDER BY updated_at DESC
+                 LIMIT ?1",
+                placeholders.join(", ")
+            );
+            let limit = limit as i64;
+            let mut values: Vec<&dyn rusqlite::ToSql> = vec![&limit];
+            for t in &entity_types {
+                values.push(t);
+            }
+            let mut stmt = conn.prepare(&sql)?;
+            let entities = stmt
+                .query_map(values.as_slice(), Self::row_to_entity)?
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(entities)
         })
+        .await
+        .context("spawn_blocking task panicked")?
     }
 
-    /// Insert a relationship
-    pub async fn insert_relationship(
+    /// Get entities of a type whose name or metadata mentions any of the given tokens
+    pub async fn find_entities_by_tokens(
         &self,
-        source_id: &str,
-        target_id: &str,
-        relation_type: &str,
-        metadata: Option<JsonValue>,
-    ) -> Result<String> {
+        tokens: &[String],
+        entity_type: &str,
+        limit: usize,
+    ) -> Result<Vec<Entity>> {
+        if tokens.is_empty() {
+            return Ok(Vec::new());
+        }
         let conn = Arc::clone(&self.conn);
-        let source_id = source_id.to_owned();
-        let target_id = target_id.to_owned();
-        let relation_type = relation_type.to_owned();
+        let entity_type = entity_type.to_owned();
+        let patterns: Vec<String> = tokens.iter().map(|t| format!("%{}%", t)).collect();
 
         tokio::task::spawn_blocking(move || {
-            let id = Uuid::new_v4().to_string();
-            let now = Utc::now();
-            let metadata_json = metadata.map(|m| serde_json::to_string(&m)).transpose()?;
+            let clauses: Vec<String> = (0..patterns.len())
+                .map(|i| format!("name LIKE ?{0} OR metadata LIKE ?{0}", i + 3))
+                .collect();
+            let sql = format!(
+                "SELECT id, name, entity_type, metadata, created_at, updated_at
+                 FROM entities
+                 WHERE entity_type = ?1 AND ({})
+                 ORDER BY created_at ASC
+                 LIMIT ?2",
+                clauses.join(" OR ")
+            );
+            let mut values: Vec<&dyn rusqlite::ToSql> = vec![&entity_type];
+            let limit = limit as i64;
+            values.push(&limit);
+            for p in &patterns {
+                values.push(p);
+            }
+
             let conn = conn.lock().unwrap_or_else(|poisoned| {
                 warn!("Database mutex was poisoned, recovering");
                 poisoned.into_inner()
             });
-
-            conn.execute(
-                "INSERT INTO relationships (id, source_id, target_id, relation_type, metadata, created_at)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
-                params![
-                    &id,
-                    &source_id,
-                    &target_id,
-                    &relation_type,
-                    metadata_json,
-                    now.to_rfc3339(),
-                ],
-            )?;
-
-            debug!("Inserted relationship: {} -> {} ({})", source_id, target_id, relation_type);
-            Ok(id)
+            let mut stmt = conn.prepare(&sql)?;
+            let entities = stmt
+                .query_map(values.as_slice(), Self::row_to_entity)?
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(entities)
         })
         .await
         .context("spawn_blocking task panicked")?
     }
 
-    /// Get relationships for an entity
-    pub async fn get_relationships_for(&self, entity_id: &str) -> Result<Vec<Relationship>> {
+    /// Get all entities (capped to prevent OOM on large databases)
+    pub async fn get_all_entities(&self) -> Result<Vec<Entity>> {
         let conn = Arc::clone(&self.conn);
-        let entity_id = entity_id.to_owned();
 
         tokio::task::spawn_blocking(move || {
             let conn = conn.lock().unwrap_or_else(|poisoned| {
                 warn!("Database mutex was poisoned, recovering");
                 poisoned.into_inner()
             });
-            let mut stmt = conn.prepare(
-                "SELECT id, source_id, target_id, relation_type, metadata, created_at
-                 FROM relationships
-                 WHERE source_id = ?1 OR target_id = ?1
-                 ORDER BY created_at DESC",
-            )?;
-
-            let relationships = stmt
-                .query_map(params![&entity_id], |row| {
-                    let metadata_str: Option<String> = row.get(4)?;
-                    let metadata = metadata_str
-                        .map(|s| serde_json::from_str(&s))
-                        .transpose()
-                        .map_err(|e| {
-                            rusqlite::Error::FromSqlConversionFailure(
-                                4,
-                                rusqlite::types::Type::Text,
-                                Box::new(e),
-                            )
-                        })?;
-
-                    Ok(Relationship {
-                        id: row.get(0)?,
-                        source_id: row.get(1)?,
-                        target_id: row.get(2)?,
-                        relation_type: row.get(3)?,
-                        metadata,
-                        created_at: row
-                            .get::<_, String>(5)?
-                            .parse()
-                            .unwrap_or_else(|_| Utc::now()),
-                    })
-                })?
-                .collect::<Result<Vec<_>, _>>()?;
-
-            Ok(relationships)
+            Self::query_all_entities(&conn)
         })
         .await
         .context("spawn_blocking task panicked")?
     }
 
-    /// Insert a conversation
-    pub async fn insert_conversation(
-        &self,
+    /// IDs of every entity
+    pub async fn get_all_entity_ids(&self) -> Result<Vec<String>> {
+        let conn = Arc::clone(&self.conn);
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            let mut stmt = conn.prepare("SELECT id FROM entities")?;
+            let ids = stmt
+                .query_map([], |row| row.get(0))?
+                .collect::<Result<Vec<String>, _>>()?;
+            Ok(ids)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    fn query_all_entities(conn: &Connection) -> Result<Vec<Entity>> {
+        let mut stmt = conn.prepare(
+            "SELECT id, name, entity_type, metadata, created_at, updated_at
+             FROM entities
+             ORDER BY updated_at DESC
+             LIMIT 50000",
+        )?;
+
+        let entities = stmt
+            .query_map([], Self::row_to_entity)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(entities)
+    }
+
+    /// Apply every outbox change after `consumer`'s cursor with `apply`,
+    /// advancing the cursor after each successful batch.
+    ///
+    /// Each batch is claimed with the connection held, then applied with it
+    /// released — `apply` may embed text over the network — and the cursor is
+    /// advanced under the lock again. Drains for the same consumer are
+    /// serialized. Returns the number of entities applied.
+    pub(crate) fn drain_index_outbox(
+        &self,
+        consumer: &str,
+        mut apply: impl FnMut(&[PendingChange]) -> Result<()>,
+    ) -> Result<usize> {
+        let drain = self.drain_lock(consumer);
+        let _draining = drain
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        let mut applied = 0;
+        loop {
+            let (last_seq, pending) = {
+                let conn = self.lock_conn();
+                let cursor = Self::index_cursor(&conn, consumer)?;
+                let changes = Self::query_index_changes(&conn, cursor, OUTBOX_BATCH)?;
+                let Some(last_seq) = changes.last().map(|c| c.seq) else {
+                    break;
+                };
+
+                let mut stmt = conn.prepare_cached(
+                    "SELECT id, name, entity_type, metadata, created_at, updated_at
+                     FROM entities WHERE id = ?1",
+                )?;
+                let pending = outbox::coalesce(&changes)
+                    .into_iter()
+                    .map(|change| {
+                        let entity = stmt
+                            .query_row(params![change.entity_id], Self::row_to_entity)
+                            .optional()?;
+                        Ok(PendingChange {
+                            entity_id: change.entity_id.clone(),
+                            entity,
+                        })
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                (last_seq, pending)
+            };
+
+            // Writes made meanwhile land after `last_seq` and are picked up
+            // by the next batch
+            apply(&pending)?;
+            Self::set_index_cursor(&self.lock_conn(), consumer, last_seq)?;
+            applied += pending.len();
+        }
+
+        if applied > 0 {
+            Self::compact_index_outbox(&self.lock_conn())?;
+            debug!("Applied {} outbox change(s) to {}", applied, consumer);
+        }
+        Ok(applied)
+    }
+
+    /// Rebuild `consumer` from a snapshot of every entity and move its
+    /// cursor past all changes the snapshot already contains. Like
+    /// [`drain_index_outbox`](Self::drain_index_outbox), `apply` runs with
+    /// the connection released.
+    pub(crate) fn rebuild_index_from_snapshot(
+        &self,
+        consumer: &str,
+        apply: impl FnOnce(&[Entity]) -> Result<()>,
+    ) -> Result<usize> {
+        let drain = self.drain_lock(consumer);
+        let _draining = drain
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        let (latest, entities) = {
+            let conn = self.lock_conn();
+            let latest: i64 = conn.query_row(
+                "SELECT COALESCE(MAX(seq), 0) FROM index_outbox",
+                [],
+                |row| row.get(0),
+            )?;
+            (latest, Self::query_all_entities(&conn)?)
+        };
+        apply(&entities)?;
+
+        let conn = self.lock_conn();
+        Self::set_index_cursor(&conn, consumer, latest)?;
+        Self::compact_index_outbox(&conn)?;
+        Ok(entities.len())
+    }
+
+    /// The lock serializing drains of `consumer`
+    fn drain_lock(&self, consumer: &str) -> Arc<Mutex<()>> {
+        let mut drains = self
+            .drains
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        Arc::clone(drains.entry(consumer.to_string()).or_default())
+    }
+
+    fn lock_conn(&self) -> MutexGuard<'_, Connection> {
+        self.conn.lock().unwrap_or_else(|poisoned| {
+            warn!("Database mutex was poisoned, recovering");
+            poisoned.into_inner()
+        })
+    }
+
+    /// Outbox changes not yet applied by `consumer`, oldest first
+    pub async fn pending_index_changes(
+        &self,
+        consumer: &str,
+        limit: usize,
+    ) -> Result<Vec<IndexChange>> {
+        let conn = Arc::clone(&self.conn);
+        let consumer = consumer.to_owned();
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            let cursor = Self::index_cursor(&conn, &consumer)?;
+            Self::query_index_changes(&conn, cursor, limit)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// Number of outbox changes not yet applied by `consumer`
+    pub async fn pending_index_count(&self, consumer: &str) -> Result<usize> {
+        let conn = Arc::clone(&self.conn);
+        let consumer = consumer.to_owned();
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            let cursor = Self::index_cursor(&conn, &consumer)?;
+            let count: i64 = conn.query_row(
+                "SELECT COUNT(*) FROM index_outbox WHERE seq > ?1",
+                params![cursor],
+                |row| row.get(0),
+            )?;
+            Ok(count as usize)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// Content of a memory file as of its last sync, and when that was
+    pub async fn get_memory_sync_state(
+        &self,
+        path: &str,
+    ) -> Result<Option<(String, DateTime<Utc>)>> {
+        let conn = Arc::clone(&self.conn);
+        let path = path.to_owned();
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            let row: Option<(String, String)> = conn
+                .query_row(
+                    "SELECT content, synced_at FROM memory_sync_state WHERE path = ?1",
+                    params![&path],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )
+                .optional()?;
+            row.map(|(content, synced_at)| {
+                let synced_at = DateTime::parse_from_rfc3339(&synced_at)?.with_timezone(&Utc);
+                Ok((content, synced_at))
+            })
+            .transpose()
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// Record the content of a memory file after a sync
+    pub async fn set_memory_sync_state(
+        &self,
+        path: &str,
+        content: &str,
+        synced_at: DateTime<Utc>,
+    ) -> Result<()> {
+        let conn = Arc::clone(&self.conn);
+        let path = path.to_owned();
+        let content = content.to_owned();
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            conn.execute(
+                "INSERT INTO memory_sync_state (path, content, synced_at) VALUES (?1, ?2, ?3)
+                 ON CONFLICT(path) DO UPDATE SET content = excluded.content,
+                                                 synced_at = excluded.synced_at",
+                params![&path, &content, synced_at.to_rfc3339()],
+            )?;
+            Ok(())
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// Register a corpus. Fails if the name is already taken.
+    pub async fn insert_corpus(&self, corpus: &Corpus) -> Result<()> {
+        let conn = Arc::clone(&self.conn);
+        let corpus = corpus.clone();
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            conn.execute(
+                "INSERT INTO corpora (id, name, root, include_patterns, exclude_patterns,
+                                      created_at, last_synced_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    &corpus.id,
+                    &corpus.name,
+                    corpus.root.to_string_lossy(),
+                    serde_json::to_string(&corpus.include)?,
+                    serde_json::to_string(&corpus.exclude)?,
+                    corpus.created_at.to_rfc3339(),
+                    corpus.last_synced_at.map(|t| t.to_rfc3339()),
+                ],
+            )
+            .with_context(|| format!("Failed to register corpus '{}'", corpus.name))?;
+            Ok(())
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// Helper to convert row to Corpus
+    fn row_to_corpus(row: &rusqlite::Row) -> rusqlite::Result<Corpus> {
+        let root: String = row.get(2)?;
+        let include: String = row.get(3)?;
+        let exclude: String = row.get(4)?;
+        Ok(Corpus {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            root: root.into(),
+            include: serde_json::from_str(&include).unwrap_or_default(),
+            exclude: serde_json::from_str(&exclude).unwrap_or_default(),
+            created_at: row
+                .get::<_, String>(5)?
+                .parse()
+                .unwrap_or_else(|_| Utc::now()),
+            last_synced_at: row
+                .get::<_, Option<String>>(6)?
+                .and_then(|t| t.parse().ok()),
+        })
+    }
+
+    /// Look up a corpus by name or ID
+    pub async fn get_corpus(&self, name_or_id: &str) -> Result<Option<Corpus>> {
+        let conn = Arc::clone(&self.conn);
+        let key = name_or_id.to_owned();
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            conn.query_row(
+                "SELECT id, name, root, include_patterns, exclude_patterns, created_at,
+                        last_synced_at
+                 FROM corpora WHERE name = ?1 OR id = ?1",
+                params![&key],
+                Self::row_to_corpus,
+            )
+            .optional()
+            .map_err(Into::into)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// All registered corpora, by name
+    pub async fn list_corpora(&self) -> Result<Vec<Corpus>> {
+        let conn = Arc::clone(&self.conn);
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            let mut stmt = conn.prepare(
+                "SELECT id, name, root, include_patterns, exclude_patterns, created_at,
+                        last_synced_at
+                 FROM corpora ORDER BY name",
+            )?;
+            let corpora = stmt
+                .query_map([], Self::row_to_corpus)?
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(corpora)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// Remove a corpus and its file records. Returns false if no such corpus.
+    pub async fn delete_corpus(&self, id: &str) -> Result<bool> {
+        let conn = Arc::clone(&self.conn);
+        let id = id.to_owned();
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            conn.execute(
+                "DELETE FROM corpus_files WHERE corpus_id = ?1",
+                params![&id],
+            )?;
+            let deleted = conn.execute("DELETE FROM corpora WHERE id = ?1", params![&id])?;
+            Ok(deleted > 0)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// Record when a corpus was last fully synced
+    pub async fn set_corpus_synced(&self, id: &str, synced_at: DateTime<Utc>) -> Result<()> {
+        let conn = Arc::clone(&self.conn);
+        let id = id.to_owned();
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            conn.execute(
+                "UPDATE corpora SET last_synced_at = ?2 WHERE id = ?1",
+                params![&id, synced_at.to_rfc3339()],
+            )?;
+            Ok(())
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// Files ingested from a corpus, by path
+    pub async fn list_corpus_files(&self, corpus_id: &str) -> Result<Vec<CorpusFile>> {
+        let conn = Arc::clone(&self.conn);
+        let corpus_id = corpus_id.to_owned();
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            let mut stmt = conn.prepare(
+                "SELECT corpus_id, path, content_hash, document_id, chunk_count, ingested_at
+                 FROM corpus_files WHERE corpus_id = ?1 ORDER BY path",
+            )?;
+            let files = stmt
+                .query_map(params![&corpus_id], |row| {
+                    Ok(CorpusFile {
+                        corpus_id: row.get(0)?,
+                        path: row.get(1)?,
+                        content_hash: row.get(2)?,
+                        document_id: row.get(3)?,
+                        chunk_count: row.get::<_, i64>(4)?.max(0) as usize,
+                        ingested_at: row
+                            .get::<_, String>(5)?
+                            .parse()
+                            .unwrap_or_else(|_| Utc::now()),
+                    })
+                })?
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(files)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// IDs of every document and chunk entity ingested from a corpus
+    pub async fn corpus_entity_ids(&self) -> Result<std::collections::HashSet<String>> {
+        let conn = Arc::clone(&self.conn);
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            let mut stmt = conn.prepare(
+                "SELECT document_id FROM corpus_files WHERE document_id != ''
+                 UNION
+                 SELECT r.target_id FROM relationships r
+                 JOIN corpus_files f ON r.source_id = f.document_id
+                 WHERE r.relation_type = 'contains_chunk'",
+            )?;
+            let ids = stmt
+                .query_map([], |row| row.get(0))?
+                .collect::<Result<_, _>>()?;
+            Ok(ids)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// Number of chunks a document entity still links to
+    pub async fn count_document_chunks(&self, document_id: &str) -> Result<usize> {
+        let conn = Arc::clone(&self.conn);
+        let document_id = document_id.to_owned();
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            let count: i64 = conn.query_row(
+                "SELECT COUNT(*) FROM relationships r
+                 JOIN entities e ON e.id = r.target_id
+                 WHERE r.source_id = ?1 AND r.relation_type = 'contains_chunk'",
+                params![&document_id],
+                |row| row.get(0),
+            )?;
+            Ok(count as usize)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// Insert or replace the record of an ingested corpus file
+    pub async fn upsert_corpus_file(&self, file: &CorpusFile) -> Result<()> {
+        let conn = Arc::clone(&self.conn);
+        let file = file.clone();
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            conn.execute(
+                "INSERT INTO corpus_files (corpus_id, path, content_hash, document_id,
+                                           chunk_count, ingested_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+                 ON CONFLICT(corpus_id, path) DO UPDATE SET
+                     content_hash = excluded.content_hash,
+                     document_id = excluded.document_id,
+                     chunk_count = excluded.chunk_count,
+                     ingested_at = excluded.ingested_at",
+                params![
+                    &file.corpus_id,
+                    &file.path,
+                    &file.content_hash,
+                    &file.document_id,
+                    file.chunk_count as i64,
+                    file.ingested_at.to_rfc3339(),
+                ],
+            )?;
+            Ok(())
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// Forget a corpus file record
+    pub async fn delete_corpus_file(&self, corpus_id: &str, path: &str) -> Result<()> {
+        let conn = Arc::clone(&self.conn);
+        let corpus_id = corpus_id.to_owned();
+        let path = path.to_owned();
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            conn.execute(
+                "DELETE FROM corpus_files WHERE corpus_id = ?1 AND path = ?2",
+                params![&corpus_id, &path],
+            )?;
+            Ok(())
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// How far behind the outbox each known consumer is
+    pub async fn index_outbox_lag(&self) -> Result<Vec<ConsumerLag>> {
+        let conn = Arc::clone(&self.conn);
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            let mut stmt = conn.prepare(
+                "SELECT c.consumer, c.applied_seq,
+                        (SELECT COUNT(*) FROM index_outbox o WHERE o.seq > c.applied_seq)
+                 FROM index_cursors c
+                 ORDER BY c.consumer",
+            )?;
+            let lag = stmt
+                .query_map([], |row| {
+                    Ok(ConsumerLag {
+                        consumer: row.get(0)?,
+                        applied_seq: row.get(1)?,
+                        pending: row.get::<_, i64>(2)? as usize,
+                    })
+                })?
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(lag)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    fn index_cursor(conn: &Connection, consumer: &str) -> Result<i64> {
+        Ok(conn
+            .query_row(
+                "SELECT applied_seq FROM index_cursors WHERE consumer = ?1",
+                params![consumer],
+                |row| row.get(0),
+            )
+            .optional()?
+            .unwrap_or(0))
+    }
+
+    fn set_index_cursor(conn: &Connection, consumer: &str, seq: i64) -> Result<()> {
+        conn.execute(
+            "INSERT INTO index_cursors (consumer, applied_seq, updated_at)
+             VALUES (?1, ?2, ?3)
+             ON CONFLICT(consumer) DO UPDATE SET
+                 applied_seq = MAX(applied_seq, excluded.applied_seq),
+                 updated_at = excluded.updated_at",
+            params![consumer, seq, Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    fn query_index_changes(
+        conn: &Connection,
+        after: i64,
+        limit: usize,
+    ) -> Result<Vec<IndexChange>> {
+        let mut stmt = conn.prepare(
+            "SELECT seq, entity_id, op FROM index_outbox
+             WHERE seq > ?1 ORDER BY seq LIMIT ?2",
+        )?;
+        let rows = stmt
+            .query_map(params![after, limit as i64], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get::<_, String>(2)?))
+            })?
+            .collect::<Result<Vec<(i64, String, String)>, _>>()?;
+        rows.into_iter()
+            .map(|(seq, entity_id, op)| {
+                Ok(IndexChange {
+                    seq,
+                    entity_id,
+                    op: op.parse::<IndexOp>()?,
+                })
+            })
+            .collect()
+    }
+
+    /// Drop outbox rows every consumer has applied
+    fn compact_index_outbox(conn: &Connection) -> Result<()> {
+        conn.execute(
+            "DELETE FROM index_outbox
+             WHERE seq <= (SELECT COALESCE(MIN(applied_seq), 0) FROM index_cursors)",
+            [],
+        )?;
+        Ok(())
+    }
+
+    /// Update an entity's name, type and/or metadata. Returns false if no such entity.
+    pub async fn update_entity(
+        &self,
+        id: &str,
+        name: Option<&str>,
+        entity_type: Option<&str>,
+        metadata: Option<JsonValue>,
+    ) -> Result<bool> {
+        let conn = Arc::clone(&self.conn);
+        let id = id.to_owned();
+        let name = name.map(|s| s.to_owned());
+        let entity_type = entity_type.map(|s| s.to_owned());
+
+        tokio::task::spawn_blocking(move || {
+            let now = Utc::now();
+            let metadata_json = metadata.map(|m| serde_json::to_string(&m)).transpose()?;
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            let updated = conn.execute(
+                "UPDATE entities SET name = COALESCE(?1, name), entity_type = COALESCE(?2, entity_type),
+                        metadata = COALESCE(?3, metadata), updated_at = ?4
+                 WHERE id = ?5",
+                params![name, entity_type, metadata_json, now.to_rfc3339(), &id],
+            )?;
+            debug!("Updated entity {}", id);
+            Ok(updated > 0)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// Delete an entity and (via cascade) its relationships. Returns false if no such entity.
+    pub async fn delete_entity(&self, id: &str) -> Result<bool> {
+        let conn = Arc::clone(&self.conn);
+        let id = id.to_owned();
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            conn.execute(
+                "DELETE FROM provenance
+                 WHERE (fact_type = 'entity' AND fact_id = ?1)
+                    OR (fact_type = 'relationship' AND fact_id IN (
+                        SELECT id FROM relationships WHERE source_id = ?1 OR target_id = ?1))",
+                params![&id],
+            )?;
+            let deleted = conn.execute("DELETE FROM entities WHERE id = ?1", params![&id])?;
+            debug!("Deleted entity {}", id);
+            Ok(deleted > 0)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// Merge entity `merge_id` into `keep_id` in a single transaction.
+    ///
+    /// Relationships are re-pointed at the kept entity (dropping links between
+    /// the two and exact duplicates), metadata is unioned with existing keys
+    /// winning, the merged name is recorded as an alias, and a `merged_from`
+    /// provenance entry is appended. The merged entity is then deleted.
+    /// Returns the updated kept entity.
+    pub async fn merge_entities(
+        &self,
+        keep_id: &str,
+        merge_id: &str,
+        provenance: JsonValue,
+    ) -> Result<Entity> {
+        let conn = Arc::clone(&self.conn);
+        let keep_id = keep_id.to_owned();
+        let merge_id = merge_id.to_owned();
+
+        tokio::task::spawn_blocking(move || {
+            if keep_id == merge_id {
+                anyhow::bail!("Cannot merge an entity into itself");
+            }
+            let mut conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            let tx = conn.transaction()?;
+
+            let load = |id: &str| {
+                tx.query_row(
+                    "SELECT id, name, entity_type, metadata, created_at, updated_at
+                     FROM entities WHERE id = ?1",
+                    params![id],
+                    Self::row_to_entity,
+                )
+                .optional()
+            };
+            let keep = load(&keep_id)?.context("Entity to keep not found")?;
+            let merge = load(&merge_id)?.context("Entity to merge not found")?;
+
+            // Links between the two would become self-loops
+            tx.execute(
+                "DELETE FROM relationships
+                 WHERE (source_id = ?1 AND target_id = ?2) OR (source_id = ?2 AND target_id = ?1)",
+                params![&keep_id, &merge_id],
+            )?;
+            let moved_out = tx.execute(
+                "UPDATE relationships SET source_id = ?1 WHERE source_id = ?2",
+                params![&keep_id, &merge_id],
+            )?;
+            let moved_in = tx.execute(
+                "UPDATE relationships SET target_id = ?1 WHERE target_id = ?2",
+                params![&keep_id, &merge_id],
+            )?;
+            tx.execute(
+                "DELETE FROM relationships
+                 WHERE (source_id = ?1 OR target_id = ?1)
+                   AND rowid NOT IN (
+                       SELECT MIN(rowid) FROM relationships
+                       WHERE source_id = ?1 OR target_id = ?1
+                       GROUP BY source_id, target_id, relation_type, COALESCE(valid_to, '')
+                   )",
+                params![&keep_id],
+            )?;
+            tx.execute(
+                "DELETE FROM provenance
+                 WHERE fact_type = 'relationship'
+                   AND fact_id NOT IN (SELECT id FROM relationships)",
+                [],
+            )?;
+            tx.execute(
+                "UPDATE provenance SET fact_id = ?1 WHERE fact_type = 'entity' AND fact_id = ?2",
+                params![&keep_id, &merge_id],
+            )?;
+
+            let mut metadata = match keep.metadata.clone() {
+                Some(JsonValue::Object(map)) => map,
+                Some(other) => {
+                    let mut map = serde_json::Map::new();
+                    map.insert("value".to_string(), other);
+                    map
+                }
+                None => serde_json::Map::new(),
+            };
+            let merged_meta = match merge.metadata.clone() {
+                Some(JsonValue::Object(map)) => map,
+                _ => serde_json::Map::new(),
+            };
+
+            let mut aliases: Vec<JsonValue> = Vec::new();
+            for source in [metadata.get("aliases"), merged_meta.get("aliases")] {
+                if let Some(JsonValue::Array(arr)) = source {
+                    aliases.extend(arr.iter().cloned());
+                }
+            }
+            if merge.name != keep.name {
+                aliases.push(JsonValue::String(merge.name.clone()));
+            }
+            let mut seen = std::collections::HashSet::new();
+            aliases.retain(|a| {
+                a.as_str()
+                    .map(|s| s != keep.name && seen.insert(s.to_lowercase()))
+                    .unwrap_or(false)
+            });
+
+            let mut merged_from: Vec<JsonValue> = Vec::new();
+            for source in [metadata.get("merged_from"), merged_meta.get("merged_from")] {
+                if let Some(JsonValue::Array(arr)) = source {
+                    merged_from.extend(arr.iter().cloned());
+                }
+            }
+            let mut entry = serde_json::json!({
+                "id": merge.id,
+                "name": merge.name,
+                "entity_type": merge.entity_type,
+                "created_at": merge.created_at.to_rfc3339(),
+                "merged_at": Utc::now().to_rfc3339(),
+            });
+            if let (Some(entry_map), JsonValue::Object(extra)) = (entry.as_object_mut(), provenance)
+            {
+                entry_map.extend(extra);
+            }
+            merged_from.push(entry);
+
+            for (key, value) in merged_meta {
+                if key != "aliases" && key != "merged_from" {
+                    metadata.entry(key).or_insert(value);
+                }
+            }
+            if !aliases.is_empty() {
+                metadata.insert("aliases".to_string(), JsonValue::Array(aliases));
+            }
+            metadata.insert("merged_from".to_string(), JsonValue::Array(merged_from));
+
+            let now = Utc::now();
+            let metadata = JsonValue::Object(metadata);
+            tx.execute(
+                "UPDATE entities SET metadata = ?1, updated_at = ?2 WHERE id = ?3",
+                params![
+                    serde_json::to_string(&metadata)?,
+                    now.to_rfc3339(),
+                    &keep_id
+                ],
+            )?;
+            tx.execute("DELETE FROM entities WHERE id = ?1", params![&merge_id])?;
+            tx.commit()?;
+
+            info!(
+                "Merged entity '{}' ({}) into '{}' ({}), re-pointed {} relationships",
+                merge.name,
+                merge_id,
+                keep.name,
+                keep_id,
+                moved_out + moved_in
+            );
+            Ok(Entity {
+                metadata: Some(metadata),
+                updated_at: now,
+                ..keep
+            })
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// Insert a fully-specified entity record, preserving its ID and timestamps (used by import)
+    pub async fn insert_entity_record(&self, entity: &Entity) -> Result<()> {
+        let conn = Arc::clone(&self.conn);
+        let entity = entity.clone();
+
+        tokio::task::spawn_blocking(move || {
+            let metadata_json = entity
+                .metadata
+                .as_ref()
+                .map(serde_json::to_string)
+                .transpose()?;
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            conn.execute(
+                "INSERT INTO entities (id, name, entity_type, metadata, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    &entity.id,
+                    &entity.name,
+                    &entity.entity_type,
+                    metadata_json,
+                    entity.created_at.to_rfc3339(),
+                    entity.updated_at.to_rfc3339(),
+                ],
+            )?;
+            Ok(())
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// Find an entity by exact (case-insensitive) name and type
+    pub async fn find_entity_by_name(
+        &self,
+        name: &str,
+        entity_type: &str,
+    ) -> Result<Option<Entity>> {
+        let conn = Arc::clone(&self.conn);
+        let name = name.to_owned();
+        let entity_type = entity_type.to_owned();
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            let result = conn
+                .query_row(
+                    "SELECT id, name, entity_type, metadata, created_at, updated_at
+                     FROM entities
+                     WHERE name = ?1 COLLATE NOCASE AND entity_type = ?2
+                     ORDER BY created_at ASC
+                     LIMIT 1",
+                    params![&name, &entity_type],
+                    Self::row_to_entity,
+                )
+                .optional()?;
+            Ok(result)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// Helper to convert row to Entity
+    fn row_to_entity(row: &rusqlite::Row) -> rusqlite::Result<Entity> {
+        let metadata_str: Option<String> = row.get(3)?;
+        let metadata = metadata_str
+            .map(|s| serde_json::from_str(&s))
+            .transpose()
+            .map_err(|e| {
+                rusqlite::Error::FromSqlConversionFailure(
+                    3,
+                    rusqlite::types::Type::Text,
+                    Box::new(e),
+                )
+            })?;
+
+        Ok(Entity {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            entity_type: row.get(2)?,
+            metadata,
+            created_at: row
+                .get::<_, String>(4)?
+                .parse()
+                .unwrap_or_else(|_| Utc::now()),
+            updated_at: row
+                .get::<_, String>(5)?
+                .parse()
+                .unwrap_or_else(|_| Utc::now()),
+        })
+    }
+
+    /// Insert a relationship
+    pub async fn insert_relationship(
+        &self,
+        source_id: &str,
+        target_id: &str,
+        relation_type: &str,
+        metadata: Option<JsonValue>,
+    ) -> Result<String> {
+        let conn = Arc::clone(&self.conn);
+        let source_id = source_id.to_owned();
+        let target_id = target_id.to_owned();
+        let relation_type = relation_type.to_owned();
+
+        tokio::task::spawn_blocking(move || {
+            let id = Uuid::new_v4().to_string();
+            let now = Utc::now();
+            let metadata_json = metadata.map(|m| serde_json::to_string(&m)).transpose()?;
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+
+            conn.execute(
+                "INSERT INTO relationships (id, source_id, target_id, relation_type, metadata, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    &id,
+                    &source_id,
+                    &target_id,
+                    &relation_type,
+                    metadata_json,
+                    now.to_rfc3339(),
+                ],
+            )?;
+
+            debug!("Inserted relationship: {} -> {} ({})", source_id, target_id, relation_type);
+            Ok(id)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// Get relationships for an entity
+    pub async fn get_relationships_for(&self, entity_id: &str) -> Result<Vec<Relationship>> {
+        let conn = Arc::clone(&self.conn);
+        let entity_id = entity_id.to_owned();
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            let mut stmt = conn.prepare(
+                "SELECT id, source_id, target_id, relation_type, metadata, created_at,
+                        valid_from, valid_to, superseded_by
+                 FROM relationships
+                 WHERE source_id = ?1 OR target_id = ?1
+                 ORDER BY created_at DESC",
+            )?;
+
+            let relationships = stmt
+                .query_map(params![&entity_id], Self::row_to_relationship)?
+                .collect::<Result<Vec<_>, _>>()?;
+
+            Ok(relationships)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// Get a single relationship by ID
+    pub async fn get_relationship(&self, id: &str) -> Result<Option<Relationship>> {
+        let conn = Arc::clone(&self.conn);
+        let id = id.to_owned();
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            let result = conn
+                .query_row(
+                    "SELECT id, source_id, target_id, relation_type, metadata, created_at,
+                            valid_from, valid_to, superseded_by
+                     FROM relationships WHERE id = ?1",
+                    params![&id],
+                    Self::row_to_relationship,
+                )
+                .optional()?;
+            Ok(result)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// Get all relationships (capped to prevent OOM on large databases)
+    pub async fn get_all_relationships(&self) -> Result<Vec<Relationship>> {
+        let conn = Arc::clone(&self.conn);
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            let mut stmt = conn.prepare(
+                "SELECT id, source_id, target_id, relation_type, metadata, created_at,
+                        valid_from, valid_to, superseded_by
+                 FROM relationships
+                 ORDER BY created_at ASC
+                 LIMIT 200000",
+            )?;
+            let relationships = stmt
+                .query_map([], Self::row_to_relationship)?
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(relationships)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// Update a relationship's type and/or metadata. Returns false if no such relationship.
+    pub async fn update_relationship(
+        &self,
+        id: &str,
+        relation_type: Option<&str>,
+        metadata: Option<JsonValue>,
+    ) -> Result<bool> {
+        let conn = Arc::clone(&self.conn);
+        let id = id.to_owned();
+        let relation_type = relation_type.map(|s| s.to_owned());
+
+        tokio::task::spawn_blocking(move || {
+            let metadata_json = metadata.map(|m| serde_json::to_string(&m)).transpose()?;
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            let updated = conn.execute(
+                "UPDATE relationships SET relation_type = COALESCE(?1, relation_type),
+                        metadata = COALESCE(?2, metadata)
+                 WHERE id = ?3",
+                params![relation_type, metadata_json, &id],
+            )?;
+            debug!("Updated relationship {}", id);
+            Ok(updated > 0)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// Delete a relationship. Returns false if no such relationship.
+    pub async fn delete_relationship(&self, id: &str) -> Result<bool> {
+        let conn = Arc::clone(&self.conn);
+        let id = id.to_owned();
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            conn.execute(
+                "DELETE FROM provenance WHERE fact_type = 'relationship' AND fact_id = ?1",
+                params![&id],
+            )?;
+            let deleted = conn.execute("DELETE FROM relationships WHERE id = ?1", params![&id])?;
+            debug!("Deleted relationship {}", id);
+            Ok(deleted > 0)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// Insert a fully-specified relationship record, preserving its ID and timestamp (used by import)
+    pub async fn insert_relationship_record(&self, rel: &Relationship) -> Result<()> {
+        let conn = Arc::clone(&self.conn);
+        let rel = rel.clone();
+
+        tokio::task::spawn_blocking(move || {
+            let metadata_json = rel
+                .metadata
+                .as_ref()
+                .map(serde_json::to_string)
+                .transpose()?;
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            conn.execute(
+                "INSERT INTO relationships (id, source_id, target_id, relation_type, metadata, created_at,
+                                            valid_from, valid_to, superseded_by)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                params![
+                    &rel.id,
+                    &rel.source_id,
+                    &rel.target_id,
+                    &rel.relation_type,
+                    metadata_json,
+                    rel.created_at.to_rfc3339(),
+                    rel.valid_from.map(|t| t.to_rfc3339()),
+                    rel.valid_to.map(|t| t.to_rfc3339()),
+                    &rel.superseded_by,
+                ],
+            )?;
+            Ok(())
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// Record a relationship as a temporal fact.
+    ///
+    /// If an identical current relationship already exists it is reused.
+    /// Relation types are compared in normalized form ("Works At" is
+    /// "works_at"). With `opts.supersede`, current relationships of the same
+    /// type from the same source to a different target that started earlier
+    /// are closed out at the new fact's `valid_from` and linked to it via
+    /// `superseded_by`. A backfilled fact that starts before such a
+    /// relationship is instead closed at that relationship's start. Any
+    /// provenance is attached to the resulting relationship. Runs in a single
+    /// transaction.
+    pub async fn record_relationship(
+        &self,
+        source_id: &str,
+        target_id: &str,
+        relation_type: &str,
+        metadata: Option<JsonValue>,
+        opts: FactOptions,
+    ) -> Result<RecordedFact> {
+        let conn = Arc::clone(&self.conn);
+        let source_id = source_id.to_owned();
+        let target_id = target_id.to_owned();
+        let relation_type = relation_type.to_owned();
+
+        tokio::task::spawn_blocking(move || {
+            let now = Utc::now();
+            let valid_from = opts.valid_from.unwrap_or(now);
+            let metadata_json = metadata.map(|m| serde_json::to_string(&m)).transpose()?;
+            let mut conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            let tx = conn.transaction()?;
+
+            // Current facts from this source with the same normalized type:
+            // (id, target_id, start)
+            let relation = temporal::normalize_relation(&relation_type);
+            let mut stmt = tx.prepare(
+                "SELECT id, target_id, relation_type, valid_from, created_at FROM relationships
+                 WHERE source_id = ?1 AND valid_to IS NULL",
+            )?;
+            let current = stmt
+                .query_map(params![&source_id], |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, String>(2)?,
+                        row.get::<_, Option<String>>(3)?,
+                        row.get::<_, String>(4)?,
+                    ))
+                })?
+                .collect::<Result<Vec<_>, _>>()?
+                .into_iter()
+                .filter(|(_, _, rel_type, _, _)| temporal::normalize_relation(rel_type) == relation)
+                .map(|(id, target, _, valid_from, created_at)| {
+                    // Facts recorded before temporal tracking start at creation
+                    let start = valid_from
+                        .unwrap_or(created_at)
+                        .parse::<DateTime<Utc>>()
+                        .unwrap_or(DateTime::<Utc>::MIN_UTC);
+                    (id, target, start)
+                })
+                .collect::<Vec<_>>();
+            drop(stmt);
+
+            let existing = current
+                .iter()
+                .find(|(_, target, _)| *target == target_id)
+                .map(|(id, _, _)| id.clone());
+
+            let mut fact = match existing {
+                Some(id) => RecordedFact {
+                    id,
+                    existing: true,
+                    superseded: Vec::new(),
+                    superseded_by: None,
+                },
+                None => {
+                    let id = Uuid::new_v4().to_string();
+                    tx.execute(
+                        "INSERT INTO relationships (id, source_id, target_id, relation_type, metadata,
+                                                    created_at, valid_from)
+                         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                        params![
+                            &id,
+                            &source_id,
+                            &target_id,
+                            &relation_type,
+                            metadata_json,
+                            now.to_rfc3339(),
+                            valid_from.to_rfc3339(),
+                        ],
+                    )?;
+                    RecordedFact {
+                        id,
+                        existing: false,
+                        superseded: Vec::new(),
+                        superseded_by: None,
+                    }
+                }
+            };
+
+            if opts.supersede && !fact.existing {
+                let others = current.iter().filter(|(_, target, _)| *target != target_id);
+                let (earlier, later): (Vec<_>, Vec<_>) =
+                    others.partition(|(_, _, start)| *start < valid_from);
+                for (old_id, _, _) in &earlier {
+                    tx.execute(
+                        "UPDATE relationships SET valid_to = ?1, superseded_by = ?2 WHERE id = ?3",
+                        params![valid_from.to_rfc3339(), &fact.id, old_id],
+                    )?;
+                    fact.superseded.push(old_id.clone());
+                }
+                // A backfilled fact ends where the next known one begins
+                if let Some((next_id, _, next_start)) =
+                    later.into_iter().min_by_key(|(_, _, start)| *start)
+                {
+                    tx.execute(
+                        "UPDATE relationships SET valid_to = ?1, superseded_by = ?2 WHERE id = ?3",
+                        params![next_start.to_rfc3339(), next_id, &fact.id],
+                    )?;
+                    fact.superseded_by = Some(next_id.clone());
+                }
+            }
+
+            if let Some(source) = &opts.provenance {
+                Self::insert_provenance(&tx, FactType::Relationship, &fact.id, source)?;
+            }
+            tx.commit()?;
+
+            debug!(
+                "Recorded relationship {}: {} -> {} ({}), superseded {}",
+                fact.id,
+                source_id,
+                target_id,
+                relation_type,
+                fact.superseded.len()
+            );
+            Ok(fact)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// Mark a relationship as no longer true from `at`. Returns false if no
+    /// such relationship or it had already ended.
+    pub async fn end_relationship(&self, id: &str, at: DateTime<Utc>) -> Result<bool> {
+        let conn = Arc::clone(&self.conn);
+        let id = id.to_owned();
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            let updated = conn.execute(
+                "UPDATE relationships SET valid_to = ?1 WHERE id = ?2 AND valid_to IS NULL",
+                params![at.to_rfc3339(), &id],
+            )?;
+            debug!("Ended relationship {} at {}", id, at);
+            Ok(updated > 0)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// Get relationships for an entity that are still in effect
+    pub async fn get_current_relationships_for(
+        &self,
+        entity_id: &str,
+    ) -> Result<Vec<Relationship>> {
+        let conn = Arc::clone(&self.conn);
+        let entity_id = entity_id.to_owned();
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            let mut stmt = conn.prepare(
+                "SELECT id, source_id, target_id, relation_type, metadata, created_at,
+                        valid_from, valid_to, superseded_by
+                 FROM relationships
+                 WHERE (source_id = ?1 OR target_id = ?1) AND valid_to IS NULL
+                 ORDER BY created_at DESC",
+            )?;
+            let relationships = stmt
+                .query_map(params![&entity_id], Self::row_to_relationship)?
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(relationships)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// Get relationships for an entity as they were known at a point in time:
+    /// recorded by `at`, in effect at `at`, and not yet ended.
+    pub async fn get_relationships_as_of(
+        &self,
+        entity_id: &str,
+        at: DateTime<Utc>,
+    ) -> Result<Vec<Relationship>> {
+        let all = self.get_relationships_for(entity_id).await?;
+        Ok(all.into_iter().filter(|r| r.is_valid_at(at)).collect())
+    }
+
+    /// Attach a provenance link to an entity or relationship
+    pub async fn add_provenance(
+        &self,
+        fact_type: FactType,
+        fact_id: &str,
+        source: &Provenance,
+    ) -> Result<String> {
+        let conn = Arc::clone(&self.conn);
+        let fact_id = fact_id.to_owned();
+        let source = source.clone();
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            Self::insert_provenance(&conn, fact_type, &fact_id, &source)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// Insert a fully-specified provenance record, preserving its ID (used by import).
+    /// Returns false if a record with that ID already exists.
+    pub async fn insert_provenance_record(&self, record: &ProvenanceRecord) -> Result<bool> {
+        let conn = Arc::clone(&self.conn);
+        let record = record.clone();
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            let inserted = conn.execute(
+                "INSERT OR IGNORE INTO provenance
+                    (id, fact_type, fact_id, source_type, source_id, excerpt, recorded_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    &record.id,
+                    record.fact_type.as_str(),
+                    &record.fact_id,
+                    record.source.source_type.as_str(),
+                    &record.source.source_id,
+                    &record.source.excerpt,
+                    record.recorded_at.to_rfc3339(),
+                ],
+            )?;
+            Ok(inserted > 0)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// Get provenance records for a set of facts (entities and/or relationships)
+    pub async fn get_provenance(&self, fact_ids: &[String]) -> Result<Vec<ProvenanceRecord>> {
+        if fact_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let conn = Arc::clone(&self.conn);
+        let fact_ids = fact_ids.to_vec();
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            let mut records = Vec::new();
+            for chunk in fact_ids.chunks(500) {
+                let placeholders = vec!["?"; chunk.len()].join(", ");
+                let sql = format!(
+                    "SELECT id, fact_type, fact_id, source_type, source_id, excerpt, recorded_at
+                     FROM provenance WHERE fact_id IN ({})
+                     ORDER BY recorded_at ASC",
+                    placeholders
+                );
+                let mut stmt = conn.prepare(&sql)?;
+                let rows = stmt
+                    .query_map(
+                        rusqlite::params_from_iter(chunk.iter()),
+                        Self::row_to_provenance,
+                    )?
+                    .collect::<Result<Vec<_>, _>>()?;
+                records.extend(rows.into_iter().flatten());
+            }
+            Ok(records)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// Get every fact recorded from a given source (e.g. one email)
+    pub async fn get_facts_from_source(
+        &self,
+        source: &Provenance,
+    ) -> Result<Vec<ProvenanceRecord>> {
+        let conn = Arc::clone(&self.conn);
+        let source_type = source.source_type.as_str();
+        let source_id = source.source_id.clone();
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            let mut stmt = conn.prepare(
+                "SELECT id, fact_type, fact_id, source_type, source_id, excerpt, recorded_at
+                 FROM provenance WHERE source_type = ?1 AND source_id = ?2
+                 ORDER BY recorded_at ASC",
+            )?;
+            let records = stmt
+                .query_map(params![source_type, &source_id], Self::row_to_provenance)?
+                .collect::<Result<Vec<_>, _>>()?
+                .into_iter()
+                .flatten()
+                .collect();
+            Ok(records)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// Get all provenance records (capped to prevent OOM on large databases)
+    pub async fn get_all_provenance(&self) -> Result<Vec<ProvenanceRecord>> {
+        let conn = Arc::clone(&self.conn);
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            let mut stmt = conn.prepare(
+                "SELECT id, fact_type, fact_id, source_type, source_id, excerpt, recorded_at
+                 FROM provenance
+                 ORDER BY recorded_at ASC
+                 LIMIT 500000",
+            )?;
+            let records = stmt
+                .query_map([], Self::row_to_provenance)?
+                .collect::<Result<Vec<_>, _>>()?
+                .into_iter()
+                .flatten()
+                .collect();
+            Ok(records)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    fn insert_provenance(
+        conn: &Connection,
+        fact_type: FactType,
+        fact_id: &str,
+        source: &Provenance,
+    ) -> Result<String> {
+        let id = Uuid::new_v4().to_string();
+        conn.execute(
+            "INSERT INTO provenance (id, fact_type, fact_id, source_type, source_id, excerpt, recorded_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                &id,
+                fact_type.as_str(),
+                fact_id,
+                source.source_type.as_str(),
+                &source.source_id,
+                &source.excerpt,
+                Utc::now().to_rfc3339(),
+            ],
+        )?;
+        debug!(
+            "Recorded provenance for {} {}: {} {}",
+            fact_type.as_str(),
+            fact_id,
+            source.source_type.as_str(),
+            source.source_id
+        );
+        Ok(id)
+    }
+
+    /// Helper to convert row to ProvenanceRecord. Rows with unknown fact or
+    /// source types (from a newer version) are skipped.
+    fn row_to_provenance(row: &rusqlite::Row) -> rusqlite::Result<Option<ProvenanceRecord>> {
+        let fact_type: String = row.get(1)?;
+        let source_type: String = row.get(3)?;
+        let (Ok(fact_type), Ok(source_type)) = (fact_type.parse(), source_type.parse()) else {
+            return Ok(None);
+        };
+        Ok(Some(ProvenanceRecord {
+            id: row.get(0)?,
+            fact_type,
+            fact_id: row.get(2)?,
+            source: Provenance {
+                source_type,
+                source_id: row.get(4)?,
+                excerpt: row.get(5)?,
+            },
+            recorded_at: row
+                .get::<_, String>(6)?
+                .parse()
+                .unwrap_or_else(|_| Utc::now()),
+        }))
+    }
+
+    /// Helper to convert row to Relationship
+    fn row_to_relationship(row: &rusqlite::Row) -> rusqlite::Result<Relationship> {
+        let metadata_str: Option<String> = row.get(4)?;
+        let metadata = metadata_str
+            .map(|s| serde_json::from_str(&s))
+            .transpose()
+            .map_err(|e| {
+                rusqlite::Error::FromSqlConversionFailure(
+                    4,
+                    rusqlite::types::Type::Text,
+                    Box::new(e),
+                )
+            })?;
+
+        Ok(Relationship {
+            id: row.get(0)?,
+            source_id: row.get(1)?,
+            target_id: row.get(2)?,
+            relation_type: row.get(3)?,
+            metadata,
+            created_at: row
+                .get::<_, String>(5)?
+                .parse()
+                .unwrap_or_else(|_| Utc::now()),
+            valid_from: row
+                .get::<_, Option<String>>(6)?
+                .and_then(|s| s.parse().ok()),
+            valid_to: row
+                .get::<_, Option<String>>(7)?
+                .and_then(|s| s.parse().ok()),
+            superseded_by: row.get(8)?,
+        })
+    }
+
+    /// Insert a conversation into its channel's default thread
+    pub async fn insert_conversation(
+        &self,
+        channel: &str,
+        sender: &str,
+        content: &str,
+        metadata: Option<JsonValue>,
+    ) -> Result<String> {
+        self.insert_conversation_in_thread(channel, None, sender, content, metadata)
+            .await
+    }
+
+    /// Insert a conversation into the thread identified by a channel-native
+    /// thread or reply-chain ID (see [`conversation_thread_id`])
+    pub async fn insert_conversation_in_thread(
+        &self,
         channel: &str,
+        native_thread: Option<&str>,
         sender: &str,
         content: &str,
         metadata: Option<JsonValue>,
     ) -> Result<String> {
         let conn = Arc::clone(&self.conn);
+        let thread_id = conversation_thread_id(channel, native_thread);
         let channel = channel.to_owned();
         let sender = sender.to_owned();
         let content = content.to_owned();
@@ -716,14 +2713,16 @@ impl KnowledgeDb {
             let id = Uuid::new_v4().to_string();
             let now = Utc::now();
             let metadata_json = metadata.map(|m| serde_json::to_string(&m)).transpose()?;
-            let conn = conn.lock().unwrap_or_else(|poisoned| {
+            let mut conn = conn.lock().unwrap_or_else(|poisoned| {
                 warn!("Database mutex was poisoned, recovering");
                 poisoned.into_inner()
             });
 
-            conn.execute(
-                "INSERT INTO conversations (id, channel, sender, content, metadata, created_at)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            let tx = conn.transaction()?;
+            touch_thread(&tx, &thread_id, &channel, &content, &now)?;
+            tx.execute(
+                "INSERT INTO conversations (id, channel, sender, content, metadata, created_at, thread_id)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
                 params![
                     &id,
                     &channel,
@@ -731,10 +2730,12 @@ impl KnowledgeDb {
                     &content,
                     metadata_json,
                     now.to_rfc3339(),
+                    &thread_id,
                 ],
             )?;
+            tx.commit()?;
 
-            debug!("Inserted conversation in channel {}", channel);
+            debug!("Inserted conversation in thread {}", thread_id);
             Ok(id)
         })
         .await
@@ -756,21 +2757,23 @@ impl KnowledgeDb {
             });
             let (sql, params_vec): (String, Vec<String>) = if let Some(ref ch) = channel {
                 (
-                    "SELECT id, channel, sender, content, metadata, created_at
-                     FROM conversations
-                     WHERE channel = ?1
-                     ORDER BY created_at DESC
-                     LIMIT ?2"
-                        .to_string(),
+                    format!(
+                        "SELECT {} FROM conversations
+                         WHERE channel = ?1
+                         ORDER BY created_at DESC
+                         LIMIT ?2",
+                        CONVERSATION_COLUMNS
+                    ),
                     vec![ch.to_string(), limit.to_string()],
                 )
             } else {
                 (
-                    "SELECT id, channel, sender, content, metadata, created_at
-                     FROM conversations
-                     ORDER BY created_at DESC
-                     LIMIT ?1"
-                        .to_string(),
+                    format!(
+                        "SELECT {} FROM conversations
+                         ORDER BY created_at DESC
+                         LIMIT ?1",
+                        CONVERSATION_COLUMNS
+                    ),
                     vec![limit.to_string()],
                 )
             };
@@ -793,6 +2796,80 @@ impl KnowledgeDb {
         .context("spawn_blocking task panicked")?
     }
 
+    /// Get all conversations in chronological order (capped to prevent OOM)
+    pub async fn get_all_conversations(&self) -> Result<Vec<Conversation>> {
+        let conn = Arc::clone(&self.conn);
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {} FROM conversations
+                 ORDER BY created_at ASC
+                 LIMIT 200000",
+                CONVERSATION_COLUMNS
+            ))?;
+            let conversations = stmt
+                .query_map([], Self::row_to_conversation)?
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(conversations)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// Insert a fully-specified conversation record unless its ID already exists.
+    /// Returns true if a row was inserted.
+    pub async fn insert_conversation_record(&self, conversation: &Conversation) -> Result<bool> {
+        let conn = Arc::clone(&self.conn);
+        let conversation = conversation.clone();
+
+        tokio::task::spawn_blocking(move || {
+            let metadata_json = conversation
+                .metadata
+                .as_ref()
+                .map(serde_json::to_string)
+                .transpose()?;
+            let thread_id = conversation
+                .thread_id
+                .clone()
+                .unwrap_or_else(|| conversation.channel.clone());
+            let mut conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            let tx = conn.transaction()?;
+            let inserted = tx.execute(
+                "INSERT OR IGNORE INTO conversations (id, channel, sender, content, metadata, created_at, thread_id)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    &conversation.id,
+                    &conversation.channel,
+                    &conversation.sender,
+                    &conversation.content,
+                    metadata_json,
+                    conversation.created_at.to_rfc3339(),
+                    &thread_id,
+                ],
+            )?;
+            if inserted > 0 {
+                touch_thread(
+                    &tx,
+                    &thread_id,
+                    &conversation.channel,
+                    &conversation.content,
+                    &conversation.created_at,
+                )?;
+            }
+            tx.commit()?;
+            Ok(inserted > 0)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
     /// Helper to convert row to Conversation
     fn row_to_conversation(row: &rusqlite::Row) -> rusqlite::Result<Conversation> {
         let metadata_str: Option<String> = row.get(4)?;
@@ -817,160 +2894,443 @@ impl KnowledgeDb {
                 .get::<_, String>(5)?
                 .parse()
                 .unwrap_or_else(|_| Utc::now()),
+            thread_id: row.get(6)?,
+        })
+    }
+
+    /// Most recent messages of a thread, newest first
+    pub async fn get_thread_conversations(
+        &self,
+        thread_id: &str,
+        limit: usize,
+    ) -> Result<Vec<Conversation>> {
+        let conn = Arc::clone(&self.conn);
+        let thread_id = thread_id.to_owned();
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {} FROM conversations
+                 WHERE thread_id = ?1
+                 ORDER BY created_at DESC, rowid DESC
+                 LIMIT ?2",
+                CONVERSATION_COLUMNS
+            ))?;
+            let conversations = stmt
+                .query_map(params![thread_id, limit as i64], Self::row_to_conversation)?
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(conversations)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// Get a conversation thread by ID
+    pub async fn get_conversation_thread(&self, id: &str) -> Result<Option<ConversationThread>> {
+        let conn = Arc::clone(&self.conn);
+        let id = id.to_owned();
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            let thread = conn
+                .query_row(
+                    "SELECT t.id, t.channel, t.title, t.created_at, t.last_message_at,
+                        (SELECT COUNT(*) FROM conversations c WHERE c.thread_id = t.id),
+                        t.extracted_at
+                     FROM conversation_threads t WHERE t.id = ?1",
+                    params![id],
+                    Self::row_to_thread,
+                )
+                .optional()?;
+            Ok(thread)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// Threads ordered by most recent activity
+    pub async fn list_conversation_threads(
+        &self,
+        channel: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<ConversationThread>> {
+        let conn = Arc::clone(&self.conn);
+        let channel = channel.map(str::to_owned);
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            let mut stmt = conn.prepare(
+                "SELECT t.id, t.channel, t.title, t.created_at, t.last_message_at,
+                    (SELECT COUNT(*) FROM conversations c WHERE c.thread_id = t.id),
+                    t.extracted_at
+                 FROM conversation_threads t
+                 WHERE ?1 IS NULL OR t.channel = ?1
+                 ORDER BY t.last_message_at DESC
+                 LIMIT ?2",
+            )?;
+            let threads = stmt
+                .query_map(params![channel, limit as i64], Self::row_to_thread)?
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(threads)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    fn row_to_thread(row: &rusqlite::Row) -> rusqlite::Result<ConversationThread> {
+        let timestamp = |idx: usize| -> rusqlite::Result<DateTime<Utc>> {
+            Ok(row
+                .get::<_, String>(idx)?
+                .parse()
+                .unwrap_or_else(|_| Utc::now()))
+        };
+        Ok(ConversationThread {
+            id: row.get(0)?,
+            channel: row.get(1)?,
+            title: row.get(2)?,
+            created_at: timestamp(3)?,
+            last_message_at: timestamp(4)?,
+            message_count: row.get::<_, i64>(5)? as usize,
+            extracted_at: row
+                .get::<_, Option<String>>(6)?
+                .and_then(|t| t.parse().ok()),
+        })
+    }
+
+    /// Threads idle since `idle_before` with messages not yet handed to
+    /// episodic extraction, least recently active first
+    pub async fn threads_pending_extraction(
+        &self,
+        idle_before: DateTime<Utc>,
+        limit: usize,
+    ) -> Result<Vec<ConversationThread>> {
+        let conn = Arc::clone(&self.conn);
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            let mut stmt = conn.prepare(
+                "SELECT t.id, t.channel, t.title, t.created_at, t.last_message_at,
+                    (SELECT COUNT(*) FROM conversations c WHERE c.thread_id = t.id),
+                    t.extracted_at
+                 FROM conversation_threads t
+                 WHERE t.last_message_at <= ?1
+                   AND (t.extracted_at IS NULL OR t.extracted_at < t.last_message_at)
+                 ORDER BY t.last_message_at ASC
+                 LIMIT ?2",
+            )?;
+            let threads = stmt
+                .query_map(
+                    params![idle_before.to_rfc3339(), limit as i64],
+                    Self::row_to_thread,
+                )?
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(threads)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// The newest `limit` messages of a thread written after `since`, oldest
+    /// first
+    pub async fn get_thread_messages_since(
+        &self,
+        thread_id: &str,
+        since: Option<DateTime<Utc>>,
+        limit: usize,
+    ) -> Result<Vec<Conversation>> {
+        let conn = Arc::clone(&self.conn);
+        let thread_id = thread_id.to_owned();
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {} FROM conversations
+                 WHERE thread_id = ?1 AND (?2 IS NULL OR created_at > ?2)
+                 ORDER BY created_at DESC, rowid DESC
+                 LIMIT ?3",
+                CONVERSATION_COLUMNS
+            ))?;
+            let mut messages = stmt
+                .query_map(
+                    params![thread_id, since.map(|t| t.to_rfc3339()), limit as i64],
+                    Self::row_to_conversation,
+                )?
+                .collect::<Result<Vec<_>, _>>()?;
+            messages.reverse();
+            Ok(messages)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// Record that a thread's messages up to `at` have been extracted
+    pub async fn set_thread_extracted(&self, thread_id: &str, at: DateTime<Utc>) -> Result<()> {
+        let conn = Arc::clone(&self.conn);
+        let thread_id = thread_id.to_owned();
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            conn.execute(
+                "UPDATE conversation_threads SET extracted_at = ?2 WHERE id = ?1",
+                params![&thread_id, at.to_rfc3339()],
+            )?;
+            Ok(())
         })
+        .await
+        .context("spawn_blocking task panicked")?
     }
 
-    /// Insert a watcher
-    pub async fn insert_watcher(
+    /// Queue an extracted proposal for review. Returns None when the same
+    /// fact is already pending or was rejected before.
+    pub async fn insert_memory_candidate(
         &self,
-        kind: &str,
-        config: JsonValue,
-        action: &str,
-        reply_channel: &str,
-    ) -> Result<String> {
+        thread_id: &str,
+        proposal: &MemoryProposal,
+    ) -> Result<Option<String>> {
         let conn = Arc::clone(&self.conn);
-        let kind = kind.to_owned();
-        let action = action.to_owned();
-        let reply_channel = reply_channel.to_owned();
+        let thread_id = thread_id.to_owned();
+        let fact_key = proposal.key();
+        let proposal_json = serde_json::to_string(proposal)?;
 
         tokio::task::spawn_blocking(move || {
-            let id = format!("w-{}", Uuid::new_v4());
-            let now = Utc::now();
-            let config_json = serde_json::to_string(&config)?;
             let conn = conn.lock().unwrap_or_else(|poisoned| {
                 warn!("Database mutex was poisoned, recovering");
                 poisoned.into_inner()
             });
-
+            let pending: bool = conn.query_row(
+                "SELECT EXISTS(SELECT 1 FROM memory_candidates
+                               WHERE fact_key = ?1 AND status IN ('pending', 'rejected'))",
+                params![&fact_key],
+                |row| row.get(0),
+            )?;
+            if pending {
+                return Ok(None);
+            }
+            let id = Uuid::new_v4().to_string();
             conn.execute(
-                "INSERT INTO watchers (id, kind, config, action, reply_channel, active, created_at)
-                 VALUES (?1, ?2, ?3, ?4, ?5, 1, ?6)",
+                "INSERT INTO memory_candidates (id, thread_id, fact_key, proposal, status, created_at)
+                 VALUES (?1, ?2, ?3, ?4, 'pending', ?5)",
                 params![
                     &id,
-                    &kind,
-                    config_json,
-                    &action,
-                    &reply_channel,
-                    now.to_rfc3339(),
+                    &thread_id,
+                    &fact_key,
+                    &proposal_json,
+                    Utc::now().to_rfc3339()
                 ],
             )?;
-
-            debug!("Inserted watcher: {} ({})", kind, id);
-            Ok(id)
+            debug!("Queued memory candidate {} ({})", id, fact_key);
+            Ok(Some(id))
         })
         .await
         .context("spawn_blocking task panicked")?
     }
 
-    /// Get active watchers
-    pub async fn get_active_watchers(&self) -> Result<Vec<Watcher>> {
+    /// Memory candidates, oldest first, optionally filtered by status
+    pub async fn list_memory_candidates(
+        &self,
+        status: Option<CandidateStatus>,
+        limit: usize,
+    ) -> Result<Vec<MemoryCandidate>> {
         let conn = Arc::clone(&self.conn);
-
         tokio::task::spawn_blocking(move || {
             let conn = conn.lock().unwrap_or_else(|poisoned| {
                 warn!("Database mutex was poisoned, recovering");
                 poisoned.into_inner()
             });
             let mut stmt = conn.prepare(
-                "SELECT id, kind, config, action, reply_channel, active, created_at
-                 FROM watchers
-                 WHERE active = 1
-                 ORDER BY created_at DESC",
+                "SELECT id, thread_id, proposal, status, created_at, decided_at
+                 FROM memory_candidates
+                 WHERE ?1 IS NULL OR status = ?1
+                 ORDER BY created_at ASC, rowid ASC
+                 LIMIT ?2",
             )?;
-
-            let watchers = stmt
-                .query_map([], Self::row_to_watcher)?
+            let candidates = stmt
+                .query_map(
+                    params![status.map(|s| s.as_str()), limit as i64],
+                    Self::row_to_candidate,
+                )?
                 .collect::<Result<Vec<_>, _>>()?;
-
-            Ok(watchers)
+            Ok(candidates)
         })
         .await
         .context("spawn_blocking task panicked")?
     }
 
-    /// Helper to convert row to Watcher
-    fn row_to_watcher(row: &rusqlite::Row) -> rusqlite::Result<Watcher> {
-        let config_str: String = row.get(2)?;
-        let config = serde_json::from_str(&config_str).map_err(|e| {
-            rusqlite::Error::FromSqlConversionFailure(2, rusqlite::types::Type::Text, Box::new(e))
-        })?;
-
-        Ok(Watcher {
-            id: row.get(0)?,
-            kind: row.get(1)?,
-            config,
-            action: row.get(3)?,
-            reply_channel: row.get(4)?,
-            active: row.get::<_, i64>(5)? != 0,
-            created_at: row
-                .get::<_, String>(6)?
-                .parse()
-                .unwrap_or_else(|_| Utc::now()),
-        })
-    }
-
-    /// Get a single watcher by ID
-    pub async fn get_watcher(&self, id: &str) -> Result<Option<Watcher>> {
+    /// Get a memory candidate by ID
+    pub async fn get_memory_candidate(&self, id: &str) -> Result<Option<MemoryCandidate>> {
         let conn = Arc::clone(&self.conn);
         let id = id.to_owned();
-
         tokio::task::spawn_blocking(move || {
             let conn = conn.lock().unwrap_or_else(|poisoned| {
                 warn!("Database mutex was poisoned, recovering");
                 poisoned.into_inner()
             });
-            let mut stmt = conn.prepare(
-                "SELECT id, kind, config, action, reply_channel, active, created_at
-                 FROM watchers
-                 WHERE id = ?1",
-            )?;
-
-            let mut rows = stmt.query_map(params![&id], Self::row_to_watcher)?;
-            match rows.next() {
-                Some(Ok(w)) => Ok(Some(w)),
-                Some(Err(e)) => Err(e.into()),
-                None => Ok(None),
-            }
+            conn.query_row(
+                "SELECT id, thread_id, proposal, status, created_at, decided_at
+                 FROM memory_candidates WHERE id = ?1",
+                params![&id],
+                Self::row_to_candidate,
+            )
+            .optional()
+            .map_err(Into::into)
         })
         .await
         .context("spawn_blocking task panicked")?
     }
 
-    /// Update watcher active status
-    pub async fn update_watcher_active(&self, id: &str, active: bool) -> Result<()> {
+    /// Mark a pending candidate as committed or rejected. Returns false if no
+    /// such pending candidate exists.
+    pub async fn decide_memory_candidate(&self, id: &str, status: CandidateStatus) -> Result<bool> {
         let conn = Arc::clone(&self.conn);
         let id = id.to_owned();
-
         tokio::task::spawn_blocking(move || {
             let conn = conn.lock().unwrap_or_else(|poisoned| {
                 warn!("Database mutex was poisoned, recovering");
                 poisoned.into_inner()
             });
-            conn.execute(
-                "UPDATE watchers SET active = ?1 WHERE id = ?2",
-                params![active as i64, &id],
+            let updated = conn.execute(
+                "UPDATE memory_candidates SET status = ?2, decided_at = ?3
+                 WHERE id = ?1 AND status = 'pending'",
+                params![&id, status.as_str(), Utc::now().to_rfc3339()],
             )?;
-
-            debug!("Updated watcher {} active status to {}", id, active);
-            Ok(())
+            Ok(updated > 0)
         })
         .await
         .context("spawn_blocking task panicked")?
     }
 
-    /// Delete a watcher
-    pub async fn delete_watcher(&self, id: &str) -> Result<()> {
+    /// Helper to convert row to MemoryCandidate
+    fn row_to_candidate(row: &rusqlite::Row) -> rusqlite::Result<MemoryCandidate> {
+        let proposal: String = row.get(2)?;
+        let proposal = serde_json::from_str(&proposal).map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(2, rusqlite::types::Type::Text, Box::new(e))
+        })?;
+        Ok(MemoryCandidate {
+            id: row.get(0)?,
+            thread_id: row.get(1)?,
+            proposal,
+            status: row
+                .get::<_, String>(3)?
+                .parse()
+                .unwrap_or(CandidateStatus::Pending),
+            created_at: row
+                .get::<_, String>(4)?
+                .parse()
+                .unwrap_or_else(|_| Utc::now()),
+            decided_at: row
+                .get::<_, Option<String>>(5)?
+                .and_then(|t| t.parse().ok()),
+        })
+    }
+
+    /// Full-text search over conversation content.
+    ///
+    /// Matches messages containing any of the query's words (stemmed), ranked
+    /// by BM25, and returns each with an excerpt and up to
+    /// `options.context` messages before and after it in the same thread.
+    pub async fn search_conversations(
+        &self,
+        query: &str,
+        options: &ConversationSearch,
+    ) -> Result<Vec<ConversationMatch>> {
+        let Some(fts) = fts_query(query) else {
+            return Ok(Vec::new());
+        };
         let conn = Arc::clone(&self.conn);
-        let id = id.to_owned();
+        let options = options.clone();
 
         tokio::task::spawn_blocking(move || {
             let conn = conn.lock().unwrap_or_else(|poisoned| {
                 warn!("Database mutex was poisoned, recovering");
                 poisoned.into_inner()
             });
-            conn.execute("DELETE FROM watchers WHERE id = ?1", params![&id])?;
-            debug!("Deleted watcher {}", id);
-            Ok(())
+            let mut stmt = conn.prepare(
+                "SELECT c.id, c.channel, c.sender, c.content, c.metadata, c.created_at, c.thread_id,
+                    snippet(conversations_fts, 0, '**', '**', '…', 24),
+                    bm25(conversations_fts), c.rowid
+                 FROM conversations_fts
+                 JOIN conversations c ON c.rowid = conversations_fts.rowid
+                 WHERE conversations_fts MATCH ?1
+                   AND (?2 IS NULL OR c.channel = ?2)
+                   AND (?3 IS NULL OR c.thread_id = ?3)
+                 ORDER BY bm25(conversations_fts)
+                 LIMIT ?4",
+            )?;
+            let hits = stmt
+                .query_map(
+                    params![
+                        fts,
+                        options.channel,
+                        options.thread_id,
+                        options.limit as i64
+                    ],
+                    |row| {
+                        Ok((
+                            Self::row_to_conversation(row)?,
+                            row.get::<_, String>(7)?,
+                            -row.get::<_, f64>(8)?,
+                            row.get::<_, i64>(9)?,
+                        ))
+                    },
+                )?
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let mut before_stmt = conn.prepare(&format!(
+                "SELECT {} FROM conversations
+                 WHERE thread_id = ?1
+                   AND (created_at < ?2 OR (created_at = ?2 AND rowid < ?3))
+                 ORDER BY created_at DESC, rowid DESC
+                 LIMIT ?4",
+                CONVERSATION_COLUMNS
+            ))?;
+            let mut after_stmt = conn.prepare(&format!(
+                "SELECT {} FROM conversations
+                 WHERE thread_id = ?1
+                   AND (created_at > ?2 OR (created_at = ?2 AND rowid > ?3))
+                 ORDER BY created_at ASC, rowid ASC
+                 LIMIT ?4",
+                CONVERSATION_COLUMNS
+            ))?;
+
+            let mut matches = Vec::with_capacity(hits.len());
+            for (message, excerpt, score, rowid) in hits {
+                let (mut before, after) = match (&message.thread_id, options.context) {
+                    (Some(thread_id), context) if context > 0 => {
+                        let at = message.created_at.to_rfc3339();
+                        let args = params![thread_id, at, rowid, context as i64];
+                        (
+                            before_stmt
+                                .query_map(args, Self::row_to_conversation)?
+                                .collect::<Result<Vec<_>, _>>()?,
+                            after_stmt
+                                .query_map(args, Self::row_to_conversation)?
+                                .collect::<Result<Vec<_>, _>>()?,
+                        )
+                    }
+                    _ => (Vec::new(), Vec::new()),
+                };
+                before.reverse();
+                matches.push(ConversationMatch {
+                    message,
+                    excerpt,
+                    score,
+                    before,
+                    after,
+                });
+            }
+            Ok(matches)
         })
         .await
         .context("spawn_blocking task panicked")?
@@ -1063,6 +3423,78 @@ impl KnowledgeDb {
         .context("spawn_blocking task panicked")?
     }
 
+    /// Get all goals regardless of status
+    pub async fn get_all_goals(&self) -> Result<Vec<Goal>> {
+        let conn = Arc::clone(&self.conn);
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            let mut stmt = conn.prepare(
+                "SELECT id, description, status, priority, success_criteria, strategy,
+                        check_interval_secs, last_checked_at, source_channel, source, created_at, updated_at
+                 FROM goals ORDER BY created_at ASC",
+            )?;
+            let goals = stmt
+                .query_map([], Self::row_to_goal)?
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(goals)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// Insert or replace a fully-specified goal record (used by import).
+    /// When `replace` is false an existing goal with the same ID is left untouched.
+    /// Returns true if a row was written.
+    pub async fn insert_goal_record(&self, goal: &Goal, replace: bool) -> Result<bool> {
+        let conn = Arc::clone(&self.conn);
+        let goal = goal.clone();
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            let on_conflict = if replace {
+                "DO UPDATE SET description = excluded.description, status = excluded.status,
+                    priority = excluded.priority, success_criteria = excluded.success_criteria,
+                    strategy = excluded.strategy, check_interval_secs = excluded.check_interval_secs,
+                    last_checked_at = excluded.last_checked_at, source_channel = excluded.source_channel,
+                    source = excluded.source, updated_at = excluded.updated_at"
+            } else {
+                "DO NOTHING"
+            };
+            let written = conn.execute(
+                &format!(
+                    "INSERT INTO goals (id, description, status, priority, success_criteria, strategy, check_interval_secs, last_checked_at, source_channel, source, created_at, updated_at)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)
+                     ON CONFLICT(id) {}",
+                    on_conflict
+                ),
+                params![
+                    &goal.id,
+                    &goal.description,
+                    &goal.status,
+                    goal.priority,
+                    goal.success_criteria,
+                    goal.strategy,
+                    goal.check_interval_secs,
+                    goal.last_checked_at.map(|t| t.to_rfc3339()),
+                    goal.source_channel,
+                    &goal.source,
+                    goal.created_at.to_rfc3339(),
+                    goal.updated_at.to_rfc3339(),
+                ],
+            )?;
+            Ok(written > 0)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
     /// Update goal status
     pub async fn update_goal_status(&self, id: &str, status: &str) -> Result<()> {
         let conn = Arc::clone(&self.conn);
@@ -1404,51 +3836,440 @@ impl KnowledgeDb {
         .context("spawn_blocking task panicked")?
     }
 
-    /// Approve or reject a queued action
-    pub async fn decide_approval(&self, id: &str, approved: bool) -> Result<()> {
+    /// Approve or reject a queued action
+    pub async fn decide_approval(&self, id: &str, approved: bool) -> Result<()> {
+        let conn = Arc::clone(&self.conn);
+        let id = id.to_owned();
+        let status = if approved { "approved" } else { "rejected" };
+
+        tokio::task::spawn_blocking(move || {
+            let now = Utc::now();
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            conn.execute(
+                "UPDATE approval_queue SET status = ?1, decided_at = ?2 WHERE id = ?3",
+                params![status, now.to_rfc3339(), &id],
+            )?;
+            debug!("Approval {} decided: {}", id, status);
+            Ok(())
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// Clean up old conversations (keep only last N days)
+    pub async fn cleanup_old_conversations(&self, retain_days: u32) -> Result<usize> {
+        let conn = Arc::clone(&self.conn);
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            let deleted = conn.execute(
+                "DELETE FROM conversations WHERE created_at < datetime('now', ?)",
+                params![format!("-{} days", retain_days)],
+            )?;
+            if deleted > 0 {
+                conn.execute(
+                    "DELETE FROM conversation_threads
+                     WHERE id NOT IN (SELECT thread_id FROM conversations WHERE thread_id IS NOT NULL)",
+                    [],
+                )?;
+                info!("Cleaned up {} old conversations", deleted);
+            }
+            Ok(deleted)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// Delete action log entries older than `retain_days`
+    pub async fn cleanup_old_action_logs(&self, retain_days: u32) -> Result<usize> {
+        let conn = Arc::clone(&self.conn);
+        let cutoff = Utc::now() - chrono::Duration::days(retain_days as i64);
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            let deleted = conn.execute(
+                "DELETE FROM action_log WHERE created_at < ?1",
+                params![cutoff.to_rfc3339()],
+            )?;
+            if deleted > 0 {
+                info!("Cleaned up {} old action log entries", deleted);
+            }
+            Ok(deleted)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// Record that entities were read (e.g. returned by recall)
+    pub async fn record_access(&self, entity_ids: &[String]) -> Result<()> {
+        if entity_ids.is_empty() {
+            return Ok(());
+        }
+        let conn = Arc::clone(&self.conn);
+        let entity_ids = entity_ids.to_vec();
+
+        tokio::task::spawn_blocking(move || {
+            let now = Utc::now().to_rfc3339();
+            let mut conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            let tx = conn.transaction()?;
+            for id in &entity_ids {
+                tx.execute(
+                    "INSERT INTO entity_stats (entity_id, access_count, last_accessed_at)
+                     SELECT id, 1, ?2 FROM entities WHERE id = ?1
+                     ON CONFLICT(entity_id) DO UPDATE SET
+                        access_count = access_count + 1,
+                        last_accessed_at = excluded.last_accessed_at",
+                    params![id, &now],
+                )?;
+            }
+            tx.commit()?;
+            Ok(())
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// Pin or unpin an entity. Pinned entities are never forgotten.
+    /// Returns false if no such entity.
+    pub async fn set_pinned(&self, entity_id: &str, pinned: bool) -> Result<bool> {
+        let conn = Arc::clone(&self.conn);
+        let entity_id = entity_id.to_owned();
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            let updated = conn.execute(
+                "INSERT INTO entity_stats (entity_id, pinned)
+                 SELECT id, ?2 FROM entities WHERE id = ?1
+                 ON CONFLICT(entity_id) DO UPDATE SET pinned = excluded.pinned",
+                params![&entity_id, pinned],
+            )?;
+            debug!("Set pinned={} on entity {}", pinned, entity_id);
+            Ok(updated > 0)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// Get usage statistics for the given entities
+    pub async fn get_entity_stats(&self, entity_ids: &[String]) -> Result<Vec<EntityStats>> {
+        if entity_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let conn = Arc::clone(&self.conn);
+        let entity_ids = entity_ids.to_vec();
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            let mut stats = Vec::new();
+            for chunk in entity_ids.chunks(500) {
+                let placeholders = vec!["?"; chunk.len()].join(", ");
+                let sql = format!("{} WHERE e.id IN ({})", ENTITY_STATS_QUERY, placeholders);
+                let mut stmt = conn.prepare(&sql)?;
+                let rows = stmt
+                    .query_map(
+                        rusqlite::params_from_iter(chunk.iter()),
+                        Self::row_to_entity_stats,
+                    )?
+                    .collect::<Result<Vec<_>, _>>()?;
+                stats.extend(rows);
+            }
+            Ok(stats)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// Get usage statistics for every entity (capped to prevent OOM on large databases)
+    pub async fn get_all_entity_stats(&self) -> Result<Vec<EntityStats>> {
+        let conn = Arc::clone(&self.conn);
+
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            let sql = format!("{} LIMIT 200000", ENTITY_STATS_QUERY);
+            let mut stmt = conn.prepare(&sql)?;
+            let stats = stmt
+                .query_map([], Self::row_to_entity_stats)?
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(stats)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// Move an entity, its relationships and provenance into the archive.
+    /// Returns false if no such entity.
+    pub async fn archive_entity(&self, entity_id: &str, importance: f32) -> Result<bool> {
+        let conn = Arc::clone(&self.conn);
+        let entity_id = entity_id.to_owned();
+
+        tokio::task::spawn_blocking(move || {
+            let mut conn = conn.lock().unwrap_or_else(|poisoned| {
+                warn!("Database mutex was poisoned, recovering");
+                poisoned.into_inner()
+            });
+            let tx = conn.transaction()?;
+
+            let Some(entity) = tx
+                .query_row(
+                    "SELECT id, name, entity_type, metadata, created_at, updated_at
+                     FROM entities WHERE id = ?1",
+                    params![&entity_id],
+                    Self::row_to_entity,
+                )
+                .optional()?
+            else {
+                return Ok(false);
+            };
+
+            let relationships = {
+                let mut stmt = tx.prepare(
+                    "SELECT id, source_id, target_id, relation_type, metadata, created_at,
+                            valid_from, valid_to, superseded_by
+                     FROM relationships WHERE source_id = ?1 OR target_id = ?1",
+                )?;
+                stmt.query_map(params![&entity_id], Self::row_to_relationship)?
+                    .collect::<Result<Vec<_>, _>>()?
+            };
+            let mut fact_ids = vec![entity_id.clone()];
+            fact_ids.extend(relationships.iter().map(|r| r.id.clone()));
+            let provenance = {
+                let placeholders = vec!["?"; fact_ids.len()].join(", ");
+                let sql = format!(
+                    "SELECT id, fact_type, fact_id, source_type, source_id, excerpt, recorded_at
+                     FROM provenance WHERE fact_id IN ({})",
+                    placeholders
+                );
+                let mut stmt = tx.prepare(&sql)?;
+                stmt.query_map(
+                    rusqlite::params_from_iter(fact_ids.iter()),
+                    Self::row_to_provenance,
+                )?
+                .collect::<Result<Vec<_>, _>>()?
+                .into_iter()
+                .flatten()
+                .collect::<Vec<_>>()
+            };
+
+            let payload = serde_json::json!({
+                "entity": entity,
+                "relationships": relationships,
+                "provenance": provenance,
+            });
+            tx.execute(
+                "INSERT OR REPLACE INTO archived_memories
+                    (entity_id, name, entity_type, importance, payload, archived_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    &entity.id,
+                    &entity.name,
+                    &entity.entity_type,
+                    importance,
+                    serde_json::to_string(&payload)?,
+                    Utc::now().to_rfc3339(),
+                ],
+            )?;
+            for fact_id in &fact_ids {
+                tx.execute(
+                    "DELETE FROM provenance WHERE fact_id = ?1",
+                    params![fact_id],
+                )?;
+            }
+            tx.execute("DELETE FROM entities WHERE id = ?1", params![&entity_id])?;
+            tx.commit()?;
+
+            debug!("Archived entity {} ({})", entity.name, entity_id);
+            Ok(true)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+
+    /// List archived memories, most recently archived first
+    pub async fn list_archived(&self, limit: usize) -> Result<Vec<ArchivedMemory>> {
         let conn = Arc::clone(&self.conn);
-        let id = id.to_owned();
-        let status = if approved { "approved" } else { "rejected" };
 
         tokio::task::spawn_blocking(move || {
-            let now = Utc::now();
             let conn = conn.lock().unwrap_or_else(|poisoned| {
                 warn!("Database mutex was poisoned, recovering");
                 poisoned.into_inner()
             });
-            conn.execute(
-                "UPDATE approval_queue SET status = ?1, decided_at = ?2 WHERE id = ?3",
-                params![status, now.to_rfc3339(), &id],
+            let mut stmt = conn.prepare(
+                "SELECT entity_id, name, entity_type, importance, archived_at
+                 FROM archived_memories
+                 ORDER BY archived_at DESC
+                 LIMIT ?1",
             )?;
-            debug!("Approval {} decided: {}", id, status);
-            Ok(())
+            let archived = stmt
+                .query_map(params![limit as i64], |row| {
+                    Ok(ArchivedMemory {
+                        entity_id: row.get(0)?,
+                        name: row.get(1)?,
+                        entity_type: row.get(2)?,
+                        importance: row.get::<_, f64>(3)? as f32,
+                        archived_at: row
+                            .get::<_, String>(4)?
+                            .parse()
+                            .unwrap_or_else(|_| Utc::now()),
+                    })
+                })?
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(archived)
         })
         .await
         .context("spawn_blocking task panicked")?
     }
 
-    /// Clean up old conversations (keep only last N days)
-    pub async fn cleanup_old_conversations(&self, retain_days: u32) -> Result<usize> {
+    /// Restore an archived entity with its relationships (where the other
+    /// endpoint still exists) and provenance. Returns None if not archived.
+    pub async fn restore_archived(&self, entity_id: &str) -> Result<Option<Entity>> {
         let conn = Arc::clone(&self.conn);
+        let entity_id = entity_id.to_owned();
 
         tokio::task::spawn_blocking(move || {
-            let conn = conn.lock().unwrap_or_else(|poisoned| {
+            let mut conn = conn.lock().unwrap_or_else(|poisoned| {
                 warn!("Database mutex was poisoned, recovering");
                 poisoned.into_inner()
             });
-            let deleted = conn.execute(
-                "DELETE FROM conversations WHERE created_at < datetime('now', ?)",
-                params![format!("-{} days", retain_days)],
+            let tx = conn.transaction()?;
+
+            let Some(payload) = tx
+                .query_row(
+                    "SELECT payload FROM archived_memories WHERE entity_id = ?1",
+                    params![&entity_id],
+                    |row| row.get::<_, String>(0),
+                )
+                .optional()?
+            else {
+                return Ok(None);
+            };
+            let payload: JsonValue = serde_json::from_str(&payload)?;
+            let entity: Entity = serde_json::from_value(payload["entity"].clone())
+                .context("Archived payload has no entity")?;
+            let relationships: Vec<Relationship> =
+                serde_json::from_value(payload["relationships"].clone()).unwrap_or_default();
+            let provenance: Vec<ProvenanceRecord> =
+                serde_json::from_value(payload["provenance"].clone()).unwrap_or_default();
+
+            tx.execute(
+                "INSERT INTO entities (id, name, entity_type, metadata, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    &entity.id,
+                    &entity.name,
+                    &entity.entity_type,
+                    entity
+                        .metadata
+                        .as_ref()
+                        .map(serde_json::to_string)
+                        .transpose()?,
+                    entity.created_at.to_rfc3339(),
+                    entity.updated_at.to_rfc3339(),
+                ],
             )?;
-            if deleted > 0 {
-                info!("Cleaned up {} old conversations", deleted);
+            // Provenance is only restored for facts that exist again
+            let mut restored_facts = std::collections::HashSet::from([entity.id.clone()]);
+            for rel in &relationships {
+                let inserted = tx.execute(
+                    "INSERT OR IGNORE INTO relationships
+                        (id, source_id, target_id, relation_type, metadata, created_at,
+                         valid_from, valid_to, superseded_by)
+                     SELECT ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9
+                     WHERE EXISTS (SELECT 1 FROM entities WHERE id = ?2)
+                       AND EXISTS (SELECT 1 FROM entities WHERE id = ?3)",
+                    params![
+                        &rel.id,
+                        &rel.source_id,
+                        &rel.target_id,
+                        &rel.relation_type,
+                        rel.metadata
+                            .as_ref()
+                            .map(serde_json::to_string)
+                            .transpose()?,
+                        rel.created_at.to_rfc3339(),
+                        rel.valid_from.map(|t| t.to_rfc3339()),
+                        rel.valid_to.map(|t| t.to_rfc3339()),
+                        &rel.superseded_by,
+                    ],
+                )?;
+                if inserted > 0 {
+                    restored_facts.insert(rel.id.clone());
+                }
             }
-            Ok(deleted)
+            for record in provenance
+                .iter()
+                .filter(|r| restored_facts.contains(&r.fact_id))
+            {
+                tx.execute(
+                    "INSERT OR IGNORE INTO provenance
+                        (id, fact_type, fact_id, source_type, source_id, excerpt, recorded_at)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                    params![
+                        &record.id,
+                        record.fact_type.as_str(),
+                        &record.fact_id,
+                        record.source.source_type.as_str(),
+                        &record.source.source_id,
+                        &record.source.excerpt,
+                        record.recorded_at.to_rfc3339(),
+                    ],
+                )?;
+            }
+            tx.execute(
+                "DELETE FROM archived_memories WHERE entity_id = ?1",
+                params![&entity_id],
+            )?;
+            tx.commit()?;
+
+            info!("Restored archived entity {} ({})", entity.name, entity.id);
+            Ok(Some(entity))
         })
         .await
         .context("spawn_blocking task panicked")?
     }
 
+    fn row_to_entity_stats(row: &rusqlite::Row) -> rusqlite::Result<EntityStats> {
+        Ok(EntityStats {
+            entity_id: row.get(0)?,
+            name: row.get(1)?,
+            entity_type: row.get(2)?,
+            created_at: row
+                .get::<_, String>(3)?
+                .parse()
+                .unwrap_or_else(|_| Utc::now()),
+            updated_at: row
+                .get::<_, String>(4)?
+                .parse()
+                .unwrap_or_else(|_| Utc::now()),
+            access_count: row.get::<_, i64>(5)?.max(0) as u32,
+            last_accessed_at: row
+                .get::<_, Option<String>>(6)?
+                .and_then(|s| s.parse().ok()),
+            pinned: row.get(7)?,
+            degree: row.get::<_, i64>(8)?.max(0) as u32,
+        })
+    }
+
     /// Insert a new background task
     pub async fn insert_background_task(
         &self,
@@ -2093,61 +4914,6 @@ mod tests {
         Ok(())
     }
 
-    #[tokio::test]
-    async fn test_watcher_operations() -> Result<()> {
-        let temp_path = env::temp_dir().join(format!("test_watchers_{}.db", std::process::id()));
-        let _ = std::fs::remove_file(&temp_path);
-        let db = KnowledgeDb::new(&temp_path)?;
-
-        // Insert watcher
-        let id = db
-            .insert_watcher(
-                "cron",
-                serde_json::json!({"schedule": "0 9 * * *"}),
-                "Send morning summary",
-                "discord",
-            )
-            .await?;
-        assert!(!id.is_empty());
-
-        // Get active watchers
-        let active = db.get_active_watchers().await?;
-        assert_eq!(active.len(), 1);
-        assert_eq!(active[0].kind, "cron");
-        assert!(active[0].active);
-
-        // Get watcher by ID
-        let w = db.get_watcher(&id).await?;
-        assert!(w.is_some());
-        let w = w.unwrap();
-        assert_eq!(w.action, "Send morning summary");
-        assert_eq!(w.reply_channel, "discord");
-
-        // Get nonexistent watcher
-        let missing = db.get_watcher("nonexistent-id").await?;
-        assert!(missing.is_none());
-
-        // Deactivate watcher
-        db.update_watcher_active(&id, false).await?;
-        let active = db.get_active_watchers().await?;
-        assert!(active.is_empty());
-
-        // Reactivate
-        db.update_watcher_active(&id, true).await?;
-        let active = db.get_active_watchers().await?;
-        assert_eq!(active.len(), 1);
-
-        // Delete watcher
-        db.delete_watcher(&id).await?;
-        let active = db.get_active_watchers().await?;
-        assert!(active.is_empty());
-        let deleted = db.get_watcher(&id).await?;
-        assert!(deleted.is_none());
-
-        let _ = std::fs::remove_file(&temp_path);
-        Ok(())
-    }
-
     #[tokio::test]
     async fn test_approval_operations() -> Result<()> {
         let temp_path = env::temp_dir().join(format!("test_approvals_{}.db", std::process::id()));
@@ -2271,6 +5037,145 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_conversation_threads_and_search() -> Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        let db = KnowledgeDb::new(temp.path().join("threads.db"))?;
+
+        let thread = Some("C42/1700000000.000100");
+        for (sender, content) in [
+            ("alice", "Can you help plan the Lisbon trip in May?"),
+            (
+                "meepo",
+                "Sure. Flights from Berlin to Lisbon start at 80 EUR.",
+            ),
+            ("alice", "Book the cheapest one and a hotel near Alfama."),
+            (
+                "meepo",
+                "Booked the 8:05 flight and Casa Alfama for four nights.",
+            ),
+        ] {
+            db.insert_conversation_in_thread("slack", thread, sender, content, None)
+                .await?;
+        }
+        db.insert_conversation("slack", "bob", "Standup moved to 10am", None)
+            .await?;
+        db.insert_conversation("discord", "carol", "Any tips for Lisbon restaurants?", None)
+            .await?;
+
+        let thread_id = conversation_thread_id("slack", thread);
+        assert_eq!(thread_id, "slack:C42/1700000000.000100");
+        assert_eq!(conversation_thread_id("slack", None), "slack");
+        assert_eq!(conversation_thread_id("slack", Some(" ")), "slack");
+
+        let history = db.get_thread_conversations(&thread_id, 10).await?;
+        assert_eq!(history.len(), 4);
+        assert!(history[0].content.starts_with("Booked"));
+        assert!(
+            history
+                .iter()
+                .all(|c| c.thread_id.as_deref() == Some(thread_id.as_str()))
+        );
+
+        let info = db.get_conversation_thread(&thread_id).await?.unwrap();
+        assert_eq!(info.channel, "slack");
+        assert_eq!(info.title, "Can you help plan the Lisbon trip in May?");
+        assert_eq!(info.message_count, 4);
+        let slack_threads = db.list_conversation_threads(Some("slack"), 10).await?;
+        assert_eq!(slack_threads.len(), 2);
+        assert_eq!(slack_threads[0].id, "slack");
+
+        // Stemmed search across channels, best match first, with context
+        let matches = db
+            .search_conversations(
+                "what did we discuss about the Lisbon trips",
+                &ConversationSearch::default(),
+            )
+            .await?;
+        assert_eq!(matches.len(), 3);
+        assert!(matches[0].message.content.contains("Lisbon trip"));
+        assert!(matches[0].excerpt.contains("**Lisbon**"));
+        assert!(matches[0].before.is_empty());
+        assert_eq!(matches[0].after.len(), 2);
+        assert!(matches[0].after[0].content.starts_with("Sure."));
+
+        let flights = db
+            .search_conversations(
+                "flight",
+                &ConversationSearch {
+                    thread_id: Some(thread_id.clone()),
+                    context: 1,
+                    ..Default::default()
+                },
+            )
+            .await?;
+        assert_eq!(flights.len(), 2);
+        for m in &flights {
+            assert_eq!(m.before.len(), 1);
+            assert!(m.after.len() <= 1);
+        }
+
+        let discord = db
+            .search_conversations(
+                "Lisbon",
+                &ConversationSearch {
+                    channel: Some("discord".to_string()),
+                    ..Default::default()
+                },
+            )
+            .await?;
+        assert_eq!(discord.len(), 1);
+        assert_eq!(discord[0].message.sender, "carol");
+        assert!(
+            db.search_conversations("?!", &ConversationSearch::default())
+                .await?
+                .is_empty()
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_conversation_threads_migration() -> Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        let path = temp.path().join("legacy.db");
+        {
+            let conn = Connection::open(&path)?;
+            conn.execute(
+                "CREATE TABLE conversations (
+                    id TEXT PRIMARY KEY,
+                    channel TEXT NOT NULL,
+                    sender TEXT NOT NULL,
+                    content TEXT NOT NULL,
+                    metadata TEXT,
+                    created_at TEXT NOT NULL
+                )",
+                [],
+            )?;
+            conn.execute(
+                "INSERT INTO conversations VALUES ('c1', 'imessage', 'dan', 'Pick up the passports', NULL, ?1)",
+                params![Utc::now().to_rfc3339()],
+            )?;
+        }
+
+        let db = KnowledgeDb::new(&path)?;
+        let history = db.get_thread_conversations("imessage", 10).await?;
+        assert_eq!(history.len(), 1);
+        let thread = db.get_conversation_thread("imessage").await?.unwrap();
+        assert_eq!(thread.message_count, 1);
+        // Legacy history is not handed to episodic extraction
+        assert_eq!(thread.extracted_at, Some(thread.last_message_at));
+        assert!(
+            db.threads_pending_extraction(Utc::now(), 10)
+                .await?
+                .is_empty()
+        );
+        let matches = db
+            .search_conversations("passport", &ConversationSearch::default())
+            .await?;
+        assert_eq!(matches.len(), 1);
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_cleanup_old_conversations() -> Result<()> {
         let temp_path = env::temp_dir().join(format!("test_cleanup_{}.db", std::process::id()));
@@ -2299,6 +5204,92 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_entity_stats_access_and_pinning() -> Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        let db = KnowledgeDb::new(temp.path().join("t.db"))?;
+
+        let a = db.insert_entity("Alice", "person", None).await?;
+        let b = db.insert_entity("Bob", "person", None).await?;
+        db.insert_relationship(&a, &b, "knows", None).await?;
+
+        db.record_access(&[a.clone(), a.clone(), "missing".to_string()])
+            .await?;
+        assert!(db.set_pinned(&b, true).await?);
+        assert!(!db.set_pinned("missing", true).await?);
+
+        let stats = db.get_entity_stats(std::slice::from_ref(&a)).await?;
+        assert_eq!(stats.len(), 1);
+        assert_eq!(stats[0].access_count, 2);
+        assert!(stats[0].last_accessed_at.is_some());
+        assert!(!stats[0].pinned);
+        assert_eq!(stats[0].degree, 1);
+
+        let all = db.get_all_entity_stats().await?;
+        assert_eq!(all.len(), 2);
+        let bob = all.iter().find(|s| s.entity_id == b).unwrap();
+        assert!(bob.pinned);
+        assert_eq!(bob.access_count, 0);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_archive_and_restore_entity() -> Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        let db = KnowledgeDb::new(temp.path().join("t.db"))?;
+
+        let a = db
+            .insert_entity("Alice", "person", Some(serde_json::json!({"k": "v"})))
+            .await?;
+        let b = db.insert_entity("Bob", "person", None).await?;
+        let c = db.insert_entity("Carol", "person", None).await?;
+        db.insert_relationship(&a, &b, "knows", None).await?;
+        let to_carol = db.insert_relationship(&a, &c, "knows", None).await?;
+        let source = Provenance::new(crate::temporal::SourceType::User, "cli");
+        db.add_provenance(FactType::Entity, &a, &source).await?;
+        db.add_provenance(FactType::Relationship, &to_carol, &source)
+            .await?;
+
+        assert!(db.archive_entity(&a, 0.05).await?);
+        assert!(!db.archive_entity(&a, 0.05).await?);
+        assert!(db.get_entity(&a).await?.is_none());
+        assert!(db.get_relationships_for(&b).await?.is_empty());
+        assert!(
+            db.get_provenance(std::slice::from_ref(&a))
+                .await?
+                .is_empty()
+        );
+
+        let archived = db.list_archived(10).await?;
+        assert_eq!(archived.len(), 1);
+        assert_eq!(archived[0].name, "Alice");
+
+        // Carol is gone by the time Alice comes back
+        db.delete_entity(&c).await?;
+
+        let restored = db.restore_archived(&a).await?.unwrap();
+        assert_eq!(restored.metadata, Some(serde_json::json!({"k": "v"})));
+        assert_eq!(db.get_relationships_for(&b).await?.len(), 1);
+        assert_eq!(db.get_relationships_for(&a).await?.len(), 1);
+        assert_eq!(db.get_provenance(std::slice::from_ref(&a)).await?.len(), 1);
+        assert!(db.get_provenance(&[to_carol]).await?.is_empty());
+        assert!(db.list_archived(10).await?.is_empty());
+        assert!(db.restore_archived(&a).await?.is_none());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_cleanup_old_action_logs() -> Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        let db = KnowledgeDb::new(temp.path().join("t.db"))?;
+
+        db.insert_action_log(None, "sent_email", "Sent summary", "success")
+            .await?;
+        assert_eq!(db.cleanup_old_action_logs(30).await?, 0);
+        assert_eq!(db.get_recent_actions(10).await?.len(), 1);
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_entity_with_metadata() -> Result<()> {
         let temp_path = env::temp_dir().join(format!("test_entity_meta_{}.db", std::process::id()));
@@ -2432,41 +5423,6 @@ mod tests {
         Ok(())
     }
 
-    #[tokio::test]
-    async fn test_multiple_watchers() -> Result<()> {
-        let temp_path = env::temp_dir().join(format!("test_multi_watch_{}.db", std::process::id()));
-        let _ = std::fs::remove_file(&temp_path);
-        let db = KnowledgeDb::new(&temp_path)?;
-
-        let id1 = db
-            .insert_watcher("cron", serde_json::json!({}), "task1", "discord")
-            .await?;
-        let id2 = db
-            .insert_watcher("webhook", serde_json::json!({}), "task2", "slack")
-            .await?;
-        let _id3 = db
-            .insert_watcher("poll", serde_json::json!({}), "task3", "email")
-            .await?;
-
-        // All active
-        let active = db.get_active_watchers().await?;
-        assert_eq!(active.len(), 3);
-
-        // Deactivate one
-        db.update_watcher_active(&id1, false).await?;
-        let active = db.get_active_watchers().await?;
-        assert_eq!(active.len(), 2);
-
-        // Delete another
-        db.delete_watcher(&id2).await?;
-        let active = db.get_active_watchers().await?;
-        assert_eq!(active.len(), 1);
-        assert_eq!(active[0].action, "task3");
-
-        let _ = std::fs::remove_file(&temp_path);
-        Ok(())
-    }
-
     #[tokio::test]
     async fn test_export_usage_csv_empty() -> Result<()> {
         let temp_path = env::temp_dir().join(format!("test_csv_empty_{}.db", std::process::id()));
@@ -2599,35 +5555,6 @@ mod tests {
         Ok(())
     }
 
-    #[tokio::test]
-    async fn test_get_watcher_by_id() -> Result<()> {
-        let temp_path = env::temp_dir().join(format!("test_get_watcher_{}.db", std::process::id()));
-        let _ = std::fs::remove_file(&temp_path);
-        let db = KnowledgeDb::new(&temp_path)?;
-
-        let id = db
-            .insert_watcher(
-                "email",
-                serde_json::json!({"from": "boss"}),
-                "alert",
-                "slack",
-            )
-            .await?;
-
-        let watcher = db.get_watcher(&id).await?;
-        assert!(watcher.is_some());
-        let w = watcher.unwrap();
-        assert_eq!(w.kind, "email");
-        assert_eq!(w.action, "alert");
-        assert!(w.active);
-
-        let none = db.get_watcher("nonexistent").await?;
-        assert!(none.is_none());
-
-        let _ = std::fs::remove_file(&temp_path);
-        Ok(())
-    }
-
     #[tokio::test]
     async fn test_upsert_preference() -> Result<()> {
         let temp_path = env::temp_dir().join(format!("test_upsert_pref_{}.db", std::process::id()));
@@ -2718,6 +5645,9 @@ mod tests {
             relation_type: "knows".to_string(),
             metadata: None,
             created_at: Utc::now(),
+            valid_from: None,
+            valid_to: None,
+            superseded_by: None,
         };
         let json = serde_json::to_string(&rel).unwrap();
         let parsed: Relationship = serde_json::from_str(&json).unwrap();
@@ -2735,9 +5665,314 @@ mod tests {
             content: "hello".to_string(),
             metadata: None,
             created_at: Utc::now(),
+            thread_id: None,
         };
         let json = serde_json::to_string(&conv).unwrap();
         let parsed: Conversation = serde_json::from_str(&json).unwrap();
         assert_eq!(parsed.sender, "alice");
     }
+
+    #[tokio::test]
+    async fn test_record_relationship_supersedes_exclusive() -> Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        let db = KnowledgeDb::new(temp.path().join("t.db"))?;
+        let bob = db.insert_entity("Bob", "person", None).await?;
+        let acme = db.insert_entity("Acme", "company", None).await?;
+        let globex = db.insert_entity("Globex", "company", None).await?;
+
+        let march: DateTime<Utc> = "2025-03-01T00:00:00Z".parse()?;
+        let first = db
+            .record_relationship(
+                &bob,
+                &acme,
+                "works_at",
+                None,
+                FactOptions::for_relation("works_at"),
+            )
+            .await?;
+        assert!(first.superseded.is_empty());
+
+        // Same fact again is reused, not duplicated
+        let again = db
+            .record_relationship(
+                &bob,
+                &acme,
+                "works_at",
+                None,
+                FactOptions::for_relation("works_at"),
+            )
+            .await?;
+        assert!(again.existing);
+        assert_eq!(again.id, first.id);
+
+        let second = db
+            .record_relationship(
+                &bob,
+                &globex,
+                "works_at",
+                None,
+                FactOptions::for_relation("works_at").valid_from(Utc::now()),
+            )
+            .await?;
+        assert_eq!(second.superseded, vec![first.id.clone()]);
+
+        let old = db.get_relationship(&first.id).await?.unwrap();
+        assert!(old.valid_to.is_some());
+        assert_eq!(old.superseded_by.as_deref(), Some(second.id.as_str()));
+
+        let current = db.get_current_relationships_for(&bob).await?;
+        assert_eq!(current.len(), 1);
+        assert_eq!(current[0].target_id, globex);
+
+        // Nothing was known yet in March 2025
+        assert!(db.get_relationships_as_of(&bob, march).await?.is_empty());
+        assert_eq!(db.get_relationships_for(&bob).await?.len(), 2);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_record_relationship_backfills_older_fact() -> Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        let db = KnowledgeDb::new(temp.path().join("t.db"))?;
+        let bob = db.insert_entity("Bob", "person", None).await?;
+        let acme = db.insert_entity("Acme", "company", None).await?;
+        let globex = db.insert_entity("Globex", "company", None).await?;
+
+        let y2020: DateTime<Utc> = "2020-01-01T00:00:00Z".parse()?;
+        let y2023: DateTime<Utc> = "2023-01-01T00:00:00Z".parse()?;
+        let current = db
+            .record_relationship(
+                &bob,
+                &globex,
+                "works_at",
+                None,
+                FactOptions::for_relation("works_at").valid_from(y2023),
+            )
+            .await?;
+
+        // Learning later that Bob used to work at Acme must not end the
+        // current job; the old fact ends where the current one starts
+        let backfill = db
+            .record_relationship(
+                &bob,
+                &acme,
+                "Works At",
+                None,
+                FactOptions::for_relation("works_at").valid_from(y2020),
+            )
+            .await?;
+        assert!(backfill.superseded.is_empty());
+        assert_eq!(backfill.superseded_by.as_deref(), Some(current.id.as_str()));
+
+        let old = db.get_relationship(&backfill.id).await?.unwrap();
+        assert_eq!(old.valid_from, Some(y2020));
+        assert_eq!(old.valid_to, Some(y2023));
+        assert_eq!(old.superseded_by.as_deref(), Some(current.id.as_str()));
+        assert!(
+            db.get_relationship(&current.id)
+                .await?
+                .unwrap()
+                .is_current()
+        );
+
+        let current_rels = db.get_current_relationships_for(&bob).await?;
+        assert_eq!(current_rels.len(), 1);
+        assert_eq!(current_rels[0].target_id, globex);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_record_relationship_non_exclusive_keeps_both() -> Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        let db = KnowledgeDb::new(temp.path().join("t.db"))?;
+        let a = db.insert_entity("Alice", "person", None).await?;
+        let b = db.insert_entity("Bob", "person", None).await?;
+        let c = db.insert_entity("Carol", "person", None).await?;
+
+        db.record_relationship(&a, &b, "knows", None, FactOptions::for_relation("knows"))
+            .await?;
+        let second = db
+            .record_relationship(&a, &c, "knows", None, FactOptions::for_relation("knows"))
+            .await?;
+        assert!(second.superseded.is_empty());
+        assert_eq!(db.get_current_relationships_for(&a).await?.len(), 2);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_end_relationship() -> Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        let db = KnowledgeDb::new(temp.path().join("t.db"))?;
+        let a = db.insert_entity("Alice", "person", None).await?;
+        let b = db.insert_entity("Paris", "place", None).await?;
+        let rel = db.insert_relationship(&a, &b, "lives_in", None).await?;
+
+        let ended_at = Utc::now() + chrono::Duration::hours(1);
+        assert!(db.end_relationship(&rel, ended_at).await?);
+        assert!(!db.end_relationship(&rel, ended_at).await?);
+        assert!(db.get_current_relationships_for(&a).await?.is_empty());
+
+        let before = ended_at - chrono::Duration::minutes(30);
+        let as_of = db.get_relationships_as_of(&a, before).await?;
+        assert_eq!(as_of.len(), 1);
+        assert!(db.get_relationships_as_of(&a, ended_at).await?.is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_provenance_roundtrip() -> Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        let db = KnowledgeDb::new(temp.path().join("t.db"))?;
+        let bob = db.insert_entity("Bob", "person", None).await?;
+        let acme = db.insert_entity("Acme", "company", None).await?;
+
+        let email = Provenance::new(crate::temporal::SourceType::Email, "<msg-1@example.com>")
+            .with_excerpt("I just started at Acme");
+        db.add_provenance(FactType::Entity, &bob, &email).await?;
+        let fact = db
+            .record_relationship(
+                &bob,
+                &acme,
+                "works_at",
+                None,
+                FactOptions::for_relation("works_at").with_provenance(email.clone()),
+            )
+            .await?;
+
+        let records = db.get_provenance(&[bob.clone(), fact.id.clone()]).await?;
+        assert_eq!(records.len(), 2);
+        assert!(records.iter().all(|r| r.source == email));
+
+        let from_email = db.get_facts_from_source(&email).await?;
+        assert_eq!(from_email.len(), 2);
+        assert_eq!(db.get_all_provenance().await?.len(), 2);
+
+        // Deleting the entity removes its provenance and that of its relationships
+        db.delete_entity(&bob).await?;
+        assert!(db.get_all_provenance().await?.is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_merge_entities_moves_provenance() -> Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        let db = KnowledgeDb::new(temp.path().join("t.db"))?;
+        let keep = db.insert_entity("Bob", "person", None).await?;
+        let dup = db.insert_entity("bob", "person", None).await?;
+        let source = Provenance::new(crate::temporal::SourceType::Conversation, "conv-1");
+        db.add_provenance(FactType::Entity, &dup, &source).await?;
+
+        db.merge_entities(&keep, &dup, serde_json::json!({}))
+            .await?;
+        let records = db.get_provenance(std::slice::from_ref(&keep)).await?;
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].source.source_id, "conv-1");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_entity_mutations_fill_index_outbox() -> Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        let db = KnowledgeDb::new(temp.path().join("t.db"))?;
+        let a = db.insert_entity("Alpha", "concept", None).await?;
+        let b = db.insert_entity("Beta", "concept", None).await?;
+        db.update_entity(&a, Some("Alpha 2"), None, None).await?;
+        db.delete_entity(&b).await?;
+
+        let ops: Vec<(String, IndexOp)> = db
+            .pending_index_changes("tantivy", 10)
+            .await?
+            .into_iter()
+            .map(|c| (c.entity_id, c.op))
+            .collect();
+        assert_eq!(
+            ops,
+            vec![
+                (a.clone(), IndexOp::Upsert),
+                (b.clone(), IndexOp::Upsert),
+                (a.clone(), IndexOp::Upsert),
+                (b.clone(), IndexOp::Delete),
+            ]
+        );
+
+        // Draining coalesces per entity and resolves the current state
+        let mut seen = Vec::new();
+        let applied = db.drain_index_outbox("tantivy", |changes| {
+            seen.extend(changes.iter().map(|c| {
+                (
+                    c.entity_id.clone(),
+                    c.entity.as_ref().map(|e| e.name.clone()),
+                )
+            }));
+            Ok(())
+        })?;
+        assert_eq!(applied, 2);
+        assert_eq!(seen, vec![(a, Some("Alpha 2".to_string())), (b, None)]);
+
+        // Applied rows are compacted away once every consumer has them
+        assert_eq!(db.pending_index_count("tantivy").await?, 0);
+        assert!(db.pending_index_changes("other", 10).await?.is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_failed_drain_keeps_changes_pending() -> Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        let db = KnowledgeDb::new(temp.path().join("t.db"))?;
+        db.insert_entity("Alpha", "concept", None).await?;
+
+        let result = db.drain_index_outbox("tantivy", |_| anyhow::bail!("index locked"));
+        assert!(result.is_err());
+        assert_eq!(db.pending_index_count("tantivy").await?, 1);
+
+        assert_eq!(db.drain_index_outbox("tantivy", |_| Ok(()))?, 1);
+        let lag = db.index_outbox_lag().await?;
+        assert_eq!(lag.len(), 1);
+        assert_eq!(lag[0].pending, 0);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_drain_releases_connection_while_applying() -> Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        let db = KnowledgeDb::new(temp.path().join("t.db"))?;
+        db.insert_entity("Alpha", "concept", None).await?;
+
+        // A write during `apply` would deadlock if the connection were held
+        let mut batches = 0;
+        let applied = db.drain_index_outbox("vectors", |_| {
+            batches += 1;
+            if batches == 1 {
+                db.lock_conn().execute(
+                    "UPDATE entities SET name = 'Alpha 2' WHERE name = 'Alpha'",
+                    [],
+                )?;
+            }
+            Ok(())
+        })?;
+        // The write made during the drain is applied by the next batch
+        assert_eq!(batches, 2);
+        assert_eq!(applied, 2);
+        assert_eq!(db.pending_index_count("vectors").await?, 0);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_rebuild_from_snapshot_skips_included_changes() -> Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        let db = KnowledgeDb::new(temp.path().join("t.db"))?;
+        db.insert_entity("Alpha", "concept", None).await?;
+        db.insert_entity("Beta", "concept", None).await?;
+
+        let count = db.rebuild_index_from_snapshot("vectors", |entities| {
+            assert_eq!(entities.len(), 2);
+            Ok(())
+        })?;
+        assert_eq!(count, 2);
+        assert_eq!(db.pending_index_count("vectors").await?, 0);
+
+        db.insert_entity("Gamma", "concept", None).await?;
+        assert_eq!(db.pending_index_count("vectors").await?, 1);
+        Ok(())
+    }
 }
@@ -1,16 +1,52 @@
 //! Tantivy full-text search index
-
-use anyhow::{Context, Result};
+//!
+//! Entities are indexed with a boosted `name` field and a `body` field built
+//! from their type and metadata, both run through a stemming tokenizer for
+//! the configured language. A `created_at` date field supports range filters
+//! and a facet field supports filtering by entity type and metadata tags.
+//! Queries match exact (stemmed) terms and, at a lower weight, terms within
+//! one edit, and results carry highlighted snippets.
+//!
+//! The on-disk schema is versioned; opening an index written with an older
+//! schema (or another language) recreates it and flags it for reindexing.
+
+use anyhow::{Context, Result, anyhow};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::ops::Bound;
 use std::path::Path;
 use tantivy::{
-    Index, IndexWriter, ReloadPolicy, TantivyDocument, collector::TopDocs, query::QueryParser,
+    Index, IndexWriter, ReloadPolicy, SnippetGenerator, TantivyDocument,
+    collector::{DocSetCollector, TopDocs},
+    query::{AllQuery, BooleanQuery, BoostQuery, Occur, Query, QueryParser, RangeQuery, TermQuery},
     schema::*,
+    tokenizer::{
+        AsciiFoldingFilter, LowerCaser, RemoveLongFilter, SimpleTokenizer, Stemmer, TextAnalyzer,
+    },
 };
 use tracing::{debug, info};
 
+use crate::outbox::PendingChange;
 use crate::sqlite::Entity;
 
+pub use tantivy::tokenizer::Language as SearchLanguage;
+
+/// Bump when the index schema or tokenization changes; older indexes are rebuilt
+pub const SCHEMA_VERSION: u32 = 2;
+
+/// Sidecar file recording the schema version and language of an on-disk index
+const SCHEMA_FILE: &str = "meepo_schema.json";
+
+/// Name under which the language-aware analyzer is registered
+const TEXT_TOKENIZER: &str = "meepo_text";
+
+/// Relative weight of a name match over a body match
+const NAME_BOOST: f32 = 2.0;
+
+/// Relative weight of fuzzy (one-edit) matches compared with exact matches
+const FUZZY_BOOST: f32 = 0.3;
+
 /// Search result with score and snippet
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SearchResult {
@@ -22,50 +58,237 @@ pub struct SearchResult {
     pub snippet: Option<String>,
 }
 
+/// Filters and matching options for a search
+#[derive(Debug, Clone)]
+pub struct SearchOptions {
+    /// Only return entities of one of these types (empty = any)
+    pub entity_types: Vec<String>,
+    /// Only return entities carrying all of these metadata tags
+    pub tags: Vec<String>,
+    /// Only return entities created at or after this time
+    pub created_after: Option<DateTime<Utc>>,
+    /// Only return entities created before this time
+    pub created_before: Option<DateTime<Utc>>,
+    /// Also match terms within one edit (typo tolerance)
+    pub fuzzy: bool,
+}
+
+impl Default for SearchOptions {
+    fn default() -> Self {
+        Self {
+            entity_types: Vec::new(),
+            tags: Vec::new(),
+            created_after: None,
+            created_before: None,
+            fuzzy: true,
+        }
+    }
+}
+
+/// Schema version and language stored next to an on-disk index
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct SchemaInfo {
+    version: u32,
+    language: SearchLanguage,
+}
+
+/// Parse a language name such as `english` or `German`
+pub fn parse_language(name: &str) -> Result<SearchLanguage> {
+    serde_json::from_value(serde_json::Value::String(capitalize(name.trim())))
+        .map_err(|_| anyhow!("Unsupported search language '{}'", name))
+}
+
+fn capitalize(s: &str) -> String {
+    let lower = s.to_lowercase();
+    let mut chars = lower.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Tags listed under `tags` in entity metadata (an array or comma-separated string)
+pub fn metadata_tags(metadata: Option<&serde_json::Value>) -> Vec<String> {
+    let tags = match metadata.and_then(|m| m.get("tags")) {
+        Some(serde_json::Value::Array(items)) => items
+            .iter()
+            .filter_map(|v| v.as_str())
+            .map(str::to_string)
+            .collect(),
+        Some(serde_json::Value::String(s)) => s.split(',').map(str::to_string).collect(),
+        _ => Vec::new(),
+    };
+    tags.into_iter()
+        .map(|t| t.trim().to_lowercase())
+        .filter(|t| !t.is_empty())
+        .collect()
+}
+
+/// Searchable text of metadata values (keys and JSON punctuation are dropped)
+fn metadata_text(value: &serde_json::Value, out: &mut Vec<String>) {
+    match value {
+        serde_json::Value::String(s) => out.push(s.clone()),
+        serde_json::Value::Number(n) => out.push(n.to_string()),
+        serde_json::Value::Bool(b) => out.push(b.to_string()),
+        serde_json::Value::Array(items) => items.iter().for_each(|v| metadata_text(v, out)),
+        serde_json::Value::Object(map) => map.values().for_each(|v| metadata_text(v, out)),
+        serde_json::Value::Null => {}
+    }
+}
+
+/// Render a snippet with `**` around highlighted terms
+fn render_snippet(snippet: &tantivy::Snippet) -> Option<String> {
+    let fragment = snippet.fragment();
+    if fragment.is_empty() || snippet.highlighted().is_empty() {
+        return None;
+    }
+    let mut out = String::with_capacity(fragment.len() + 8);
+    let mut pos = 0;
+    for range in snippet.highlighted() {
+        out.push_str(&fragment[pos..range.start]);
+        out.push_str("**");
+        out.push_str(&fragment[range.clone()]);
+        out.push_str("**");
+        pos = range.end;
+    }
+    out.push_str(&fragment[pos..]);
+    Some(out)
+}
+
+/// First 200 characters of a document, used when nothing is highlighted
+fn leading_snippet(content: &str) -> String {
+    if content.chars().count() > 200 {
+        let head: String = content.chars().take(197).collect();
+        format!("{}...", head)
+    } else {
+        content.to_string()
+    }
+}
+
 /// Tantivy search index wrapper
 pub struct TantivyIndex {
     index: Index,
     id_field: Field,
-    content_field: Field,
+    name_field: Field,
+    body_field: Field,
     entity_type_field: Field,
     created_at_field: Field,
+    facets_field: Field,
+    needs_reindex: bool,
 }
 
 impl TantivyIndex {
-    /// Create or open a Tantivy index
+    /// Create or open a Tantivy index using English stemming
     pub fn new<P: AsRef<Path>>(path: P) -> Result<Self> {
-        info!("Initializing Tantivy index at {:?}", path.as_ref());
+        Self::open(path, SearchLanguage::English)
+    }
+
+    /// Create or open a Tantivy index for `language`.
+    ///
+    /// An existing index written with another schema version or language is
+    /// recreated empty and [`needs_reindex`](Self::needs_reindex) is set.
+    pub fn open<P: AsRef<Path>>(path: P, language: SearchLanguage) -> Result<Self> {
+        let path = path.as_ref();
+        info!("Initializing Tantivy index at {:?}", path);
 
         // Create directory if it doesn't exist
-        std::fs::create_dir_all(path.as_ref())?;
+        std::fs::create_dir_all(path)?;
 
-        // Define schema
-        let mut schema_builder = Schema::builder();
-        let id_field = schema_builder.add_text_field("id", STRING | STORED);
-        let content_field = schema_builder.add_text_field("content", TEXT | STORED);
-        let entity_type_field = schema_builder.add_text_field("entity_type", STRING | STORED);
-        let created_at_field = schema_builder.add_text_field("created_at", STRING | STORED);
-        let schema = schema_builder.build();
-
-        // Open or create index
-        let index = if path.as_ref().join("meta.json").exists() {
-            Index::open_in_dir(path.as_ref())?
+        let expected = SchemaInfo {
+            version: SCHEMA_VERSION,
+            language,
+        };
+        let schema_file = path.join(SCHEMA_FILE);
+        let existing = path.join("meta.json").exists();
+        let current = existing
+            && std::fs::read_to_string(&schema_file)
+                .ok()
+                .and_then(|s| serde_json::from_str::<SchemaInfo>(&s).ok())
+                .is_some_and(|info| info == expected);
+
+        // Open, or (re)create and mark for reindexing
+        let index = if current {
+            Index::open_in_dir(path)?
         } else {
-            Index::create_in_dir(path.as_ref(), schema.clone())?
+            if existing {
+                info!(
+                    "Search index schema changed (now v{}, {:?}); rebuilding",
+                    SCHEMA_VERSION, language
+                );
+                std::fs::remove_dir_all(path)?;
+                std::fs::create_dir_all(path)?;
+            }
+            let index = Index::create_in_dir(path, Self::schema())?;
+            std::fs::write(&schema_file, serde_json::to_string(&expected)?)?;
+            index
         };
 
         debug!("Tantivy index initialized successfully");
 
+        Self::from_index(index, language, !current)
+    }
+
+    /// Create an index that lives only in memory.
+    ///
+    /// Used with an encrypted knowledge database so that entity text is never
+    /// written to disk unencrypted; the index is rebuilt from the database.
+    pub fn in_memory(language: SearchLanguage) -> Result<Self> {
+        info!("Initializing in-memory Tantivy index");
+        Self::from_index(Index::create_in_ram(Self::schema()), language, true)
+    }
+
+    /// Whether the index was just created and should be filled from the database
+    pub fn needs_reindex(&self) -> bool {
+        self.needs_reindex
+    }
+
+    fn schema() -> Schema {
+        let text = TextOptions::default()
+            .set_indexing_options(
+                TextFieldIndexing::default()
+                    .set_tokenizer(TEXT_TOKENIZER)
+                    .set_index_option(IndexRecordOption::WithFreqsAndPositions),
+            )
+            .set_stored();
+        let date = DateOptions::default()
+            .set_indexed()
+            .set_fast()
+            .set_stored()
+            .set_precision(DateTimePrecision::Seconds);
+
+        let mut schema_builder = Schema::builder();
+        schema_builder.add_text_field("id", STRING | STORED);
+        schema_builder.add_text_field("name", text.clone());
+        schema_builder.add_text_field("body", text);
+        schema_builder.add_text_field("entity_type", STRING | STORED);
+        schema_builder.add_date_field("created_at", date);
+        schema_builder.add_facet_field("facets", FacetOptions::default());
+        schema_builder.build()
+    }
+
+    fn from_index(index: Index, language: SearchLanguage, needs_reindex: bool) -> Result<Self> {
+        let analyzer = TextAnalyzer::builder(SimpleTokenizer::default())
+            .filter(RemoveLongFilter::limit(40))
+            .filter(LowerCaser)
+            .filter(AsciiFoldingFilter)
+            .filter(Stemmer::new(language))
+            .build();
+        index.tokenizers().register(TEXT_TOKENIZER, analyzer);
+
+        let schema = index.schema();
         Ok(Self {
+            id_field: schema.get_field("id")?,
+            name_field: schema.get_field("name")?,
+            body_field: schema.get_field("body")?,
+            entity_type_field: schema.get_field("entity_type")?,
+            created_at_field: schema.get_field("created_at")?,
+            facets_field: schema.get_field("facets")?,
             index,
-            id_field,
-            content_field,
-            entity_type_field,
-            created_at_field,
+            needs_reindex,
         })
     }
 
-    /// Index a document
+    /// Index a document from free text
     pub fn index_document(
         &self,
         id: &str,
@@ -73,31 +296,87 @@ impl TantivyIndex {
         entity_type: &str,
         created_at: &str,
     ) -> Result<()> {
-        let mut writer = self.get_writer()?;
+        let created_at = created_at
+            .parse::<DateTime<Utc>>()
+            .unwrap_or_else(|_| Utc::now());
+        let doc = self.build_document(id, "", content, entity_type, &[], created_at);
+        self.write_document(id, doc)?;
 
-        // Delete existing document with same ID (if any)
-        let id_query = tantivy::query::TermQuery::new(
-            tantivy::Term::from_field_text(self.id_field, id),
-            tantivy::schema::IndexRecordOption::Basic,
-        );
-        let _ = writer.delete_query(Box::new(id_query));
+        debug!("Indexed document: {} ({})", id, entity_type);
+        Ok(())
+    }
+
+    /// Index an entity with its name, metadata text and tags
+    pub fn index_entity(&self, entity: &Entity) -> Result<()> {
+        let doc = self.entity_document(entity);
+        self.write_document(&entity.id, doc)?;
+
+        debug!("Indexed entity: {} ({})", entity.id, entity.entity_type);
+        Ok(())
+    }
+
+    fn entity_document(&self, entity: &Entity) -> TantivyDocument {
+        let mut body = vec![entity.entity_type.clone()];
+        if let Some(metadata) = &entity.metadata {
+            metadata_text(metadata, &mut body);
+        }
+        self.build_document(
+            &entity.id,
+            &entity.name,
+            &body.join(" "),
+            &entity.entity_type,
+            &metadata_tags(entity.metadata.as_ref()),
+            entity.created_at,
+        )
+    }
 
-        // Create document
+    fn build_document(
+        &self,
+        id: &str,
+        name: &str,
+        body: &str,
+        entity_type: &str,
+        tags: &[String],
+        created_at: DateTime<Utc>,
+    ) -> TantivyDocument {
         let mut doc = TantivyDocument::default();
         doc.add_text(self.id_field, id);
-        doc.add_text(self.content_field, content);
+        doc.add_text(self.name_field, name);
+        doc.add_text(self.body_field, body);
         doc.add_text(self.entity_type_field, entity_type);
-        doc.add_text(self.created_at_field, created_at);
+        doc.add_date(
+            self.created_at_field,
+            tantivy::DateTime::from_timestamp_secs(created_at.timestamp()),
+        );
+        doc.add_facet(self.facets_field, Facet::from_path(["type", entity_type]));
+        for tag in tags {
+            doc.add_facet(self.facets_field, Facet::from_path(["tag", tag.as_str()]));
+        }
+        doc
+    }
 
+    /// Replace any document with the same ID and commit
+    fn write_document(&self, id: &str, doc: TantivyDocument) -> Result<()> {
+        let mut writer = self.get_writer()?;
+        let _ = writer.delete_term(Term::from_field_text(self.id_field, id));
         writer.add_document(doc)?;
         writer.commit()?;
-
-        debug!("Indexed document: {} ({})", id, entity_type);
         Ok(())
     }
 
-    /// Search the index
+    /// Search the index with default options (fuzzy matching, no filters)
     pub fn search(&self, query_str: &str, limit: usize) -> Result<Vec<SearchResult>> {
+        self.search_with(query_str, &SearchOptions::default(), limit)
+    }
+
+    /// Search the index with filters. An empty query matches every document
+    /// that passes the filters.
+    pub fn search_with(
+        &self,
+        query_str: &str,
+        options: &SearchOptions,
+        limit: usize,
+    ) -> Result<Vec<SearchResult>> {
         let reader = self
             .index
             .reader_builder()
@@ -106,50 +385,72 @@ impl TantivyIndex {
 
         let searcher = reader.searcher();
 
-        // Parse query
-        let query_parser = QueryParser::for_index(&self.index, vec![self.content_field]);
-        let query = query_parser
-            .parse_query(query_str)
-            .context("Failed to parse search query")?;
+        // Exact (stemmed) matches, with names weighted above bodies
+        let mut exact_parser =
+            QueryParser::for_index(&self.index, vec![self.name_field, self.body_field]);
+        exact_parser.set_field_boost(self.name_field, NAME_BOOST);
+        let text_query: Box<dyn Query> = if query_str.trim().is_empty() {
+            Box::new(AllQuery)
+        } else {
+            let (exact, _) = exact_parser.parse_query_lenient(query_str);
+            if options.fuzzy {
+                // Typo-tolerant matches count, but less than exact ones
+                let mut fuzzy_parser =
+                    QueryParser::for_index(&self.index, vec![self.name_field, self.body_field]);
+                fuzzy_parser.set_field_boost(self.name_field, NAME_BOOST);
+                fuzzy_parser.set_field_fuzzy(self.name_field, false, 1, true);
+                fuzzy_parser.set_field_fuzzy(self.body_field, false, 1, true);
+                let (fuzzy, _) = fuzzy_parser.parse_query_lenient(query_str);
+                Box::new(BooleanQuery::new(vec![
+                    (Occur::Should, exact),
+                    (Occur::Should, Box::new(BoostQuery::new(fuzzy, FUZZY_BOOST))),
+                ]))
+            } else {
+                exact
+            }
+        };
+
+        let query = self.apply_filters(text_query, options);
 
         // Search
-        let top_docs = searcher.search(&query, &TopDocs::with_limit(limit))?;
+        let top_docs = searcher.search(&*query, &TopDocs::with_limit(limit))?;
+
+        let mut snippets = SnippetGenerator::create(&searcher, &*query, self.body_field)?;
+        snippets.set_max_num_chars(200);
+        let name_snippets = SnippetGenerator::create(&searcher, &*query, self.name_field)?;
 
         let mut results = Vec::new();
         for (score, doc_address) in top_docs {
             let retrieved_doc: TantivyDocument = searcher.doc(doc_address)?;
+            let text = |field: Field| {
+                retrieved_doc
+                    .get_first(field)
+                    .and_then(|v: &tantivy::schema::OwnedValue| v.as_str())
+                    .unwrap_or("")
+                    .to_string()
+            };
 
-            let id = retrieved_doc
-                .get_first(self.id_field)
-                .and_then(|v: &tantivy::schema::OwnedValue| v.as_str())
-                .unwrap_or("")
-                .to_string();
-
-            let content = retrieved_doc
-                .get_first(self.content_field)
-                .and_then(|v: &tantivy::schema::OwnedValue| v.as_str())
-                .unwrap_or("")
-                .to_string();
-
-            let entity_type = retrieved_doc
-                .get_first(self.entity_type_field)
-                .and_then(|v: &tantivy::schema::OwnedValue| v.as_str())
-                .unwrap_or("")
-                .to_string();
-
-            // Generate snippet (first 200 chars)
-            let snippet = if content.len() > 200 {
-                Some(format!("{}...", &content[..197]))
+            let id = text(self.id_field);
+            let name = text(self.name_field);
+            let body = text(self.body_field);
+            let entity_type = text(self.entity_type_field);
+            let content = if name.is_empty() {
+                body
             } else {
-                Some(content.clone())
+                format!("{} {}", name, body)
             };
 
+            // Highlighted snippet from the body, else the name, else the start
+            let snippet = render_snippet(&snippets.snippet_from_doc(&retrieved_doc))
+                .or_else(|| render_snippet(&name_snippets.snippet_from_doc(&retrieved_doc)))
+                .unwrap_or_else(|| leading_snippet(&content));
+
             results.push(SearchResult {
                 id,
                 content,
                 entity_type,
                 score,
-                snippet,
+                snippet: Some(snippet),
             });
         }
 
@@ -161,22 +462,105 @@ impl TantivyIndex {
         Ok(results)
     }
 
+    /// Combine a text query with type, tag and date filters
+    fn apply_filters(&self, text_query: Box<dyn Query>, options: &SearchOptions) -> Box<dyn Query> {
+        let mut clauses: Vec<(Occur, Box<dyn Query>)> = vec![(Occur::Must, text_query)];
+
+        let facet_query = |path: [&str; 2]| -> Box<dyn Query> {
+            Box::new(TermQuery::new(
+                Term::from_facet(self.facets_field, &Facet::from_path(path)),
+                IndexRecordOption::Basic,
+            ))
+        };
+
+        if !options.entity_types.is_empty() {
+            let any_type = options
+                .entity_types
+                .iter()
+                .map(|t| (Occur::Should, facet_query(["type", t.as_str()])))
+                .collect();
+            clauses.push((Occur::Must, Box::new(BooleanQuery::new(any_type))));
+        }
+        for tag in &options.tags {
+            let tag = tag.trim().to_lowercase();
+            clauses.push((Occur::Must, facet_query(["tag", tag.as_str()])));
+        }
+
+        if options.created_after.is_some() || options.created_before.is_some() {
+            let to_date =
+                |at: DateTime<Utc>| tantivy::DateTime::from_timestamp_secs(at.timestamp());
+            let lower = options
+                .created_after
+                .map_or(Bound::Unbounded, |at| Bound::Included(to_date(at)));
+            let upper = options
+                .created_before
+                .map_or(Bound::Unbounded, |at| Bound::Excluded(to_date(at)));
+            clauses.push((
+                Occur::Must,
+                Box::new(RangeQuery::new_date_bounds(
+                    "created_at".to_string(),
+                    lower,
+                    upper,
+                )),
+            ));
+        }
+
+        if clauses.len() == 1 {
+            clauses.remove(0).1
+        } else {
+            Box::new(BooleanQuery::new(clauses))
+        }
+    }
+
     /// Delete a document by ID
     pub fn delete_document(&self, id: &str) -> Result<()> {
         let mut writer = self.get_writer()?;
 
-        let id_query = tantivy::query::TermQuery::new(
-            tantivy::Term::from_field_text(self.id_field, id),
-            tantivy::schema::IndexRecordOption::Basic,
-        );
-
-        let _ = writer.delete_query(Box::new(id_query));
+        let _ = writer.delete_term(Term::from_field_text(self.id_field, id));
         writer.commit()?;
 
         debug!("Deleted document: {}", id);
         Ok(())
     }
 
+    /// Apply outbox changes in a single commit: changed entities are
+    /// re-indexed and deleted ones removed
+    pub fn apply_changes(&self, changes: &[PendingChange]) -> Result<()> {
+        let mut writer = self.get_writer()?;
+        for change in changes {
+            let _ = writer.delete_term(Term::from_field_text(self.id_field, &change.entity_id));
+            if let Some(entity) = &change.entity {
+                writer.add_document(self.entity_document(entity))?;
+            }
+        }
+        writer.commit()?;
+
+        debug!("Applied {} change(s) to the search index", changes.len());
+        Ok(())
+    }
+
+    /// IDs of every indexed document
+    pub fn all_ids(&self) -> Result<HashSet<String>> {
+        let reader = self
+            .index
+            .reader_builder()
+            .reload_policy(ReloadPolicy::Manual)
+            .try_into()?;
+        let searcher = reader.searcher();
+
+        let mut ids = HashSet::new();
+        for address in searcher.search(&AllQuery, &DocSetCollector)? {
+            let doc: TantivyDocument = searcher.doc(address)?;
+            if let Some(id) = doc
+                .get_first(self.id_field)
+                .and_then(|v: &tantivy::schema::OwnedValue| v.as_str())
+            {
+                ids.insert(id.to_string());
+            }
+        }
+        Ok(ids)
+    }
+
     /// Reindex all entities from a pre-fetched entity list
     pub fn reindex_all_from_entities(&self, entities: &[Entity]) -> Result<()> {
         info!("Reindexing all entities");
@@ -186,32 +570,14 @@ impl TantivyIndex {
         // Delete all documents
         writer.delete_all_documents()?;
 
-        let entity_count = entities.len();
         // Index all entities
         for entity in entities {
-            let content = format!(
-                "{} {} {}",
-                entity.name,
-                entity.entity_type,
-                entity
-                    .metadata
-                    .as_ref()
-                    .map(|m: &serde_json::Value| m.to_string())
-                    .unwrap_or_default()
-            );
-
-            let mut doc = TantivyDocument::default();
-            doc.add_text(self.id_field, &entity.id);
-            doc.add_text(self.content_field, &content);
-            doc.add_text(self.entity_type_field, &entity.entity_type);
-            doc.add_text(self.created_at_field, entity.created_at.to_rfc3339());
-
-            writer.add_document(doc)?;
+            writer.add_document(self.entity_document(entity))?;
         }
 
         writer.commit()?;
 
-        info!("Reindexed {} entities", entity_count);
+        info!("Reindexed {} entities", entities.len());
         Ok(())
     }
 
@@ -561,4 +927,209 @@ mod tests {
         let _ = std::fs::remove_dir_all(&temp_path);
         Ok(())
     }
+
+    fn entity(id: &str, name: &str, entity_type: &str, metadata: serde_json::Value) -> Entity {
+        let now = chrono::Utc::now();
+        Entity {
+            id: id.to_string(),
+            name: name.to_string(),
+            entity_type: entity_type.to_string(),
+            metadata: Some(metadata),
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    #[test]
+    fn test_stemming_matches_word_forms() -> Result<()> {
+        let index = TantivyIndex::in_memory(SearchLanguage::English)?;
+        index.index_entity(&entity(
+            "e1",
+            "Morning routine",
+            "habit",
+            serde_json::json!({"note": "runs five kilometres before work"}),
+        ))?;
+
+        let results = index.search("running", 10)?;
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].id, "e1");
+        Ok(())
+    }
+
+    #[test]
+    fn test_fuzzy_search_tolerates_typos() -> Result<()> {
+        let index = TantivyIndex::in_memory(SearchLanguage::English)?;
+        index.index_entity(&entity("e1", "Kubernetes", "tool", serde_json::json!({})))?;
+
+        assert_eq!(index.search("kubrenetes", 10)?.len(), 1);
+
+        let exact_only = SearchOptions {
+            fuzzy: false,
+            ..Default::default()
+        };
+        assert!(index.search_with("kubrenetes", &exact_only, 10)?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_exact_match_outranks_fuzzy_match() -> Result<()> {
+        let index = TantivyIndex::in_memory(SearchLanguage::English)?;
+        index.index_entity(&entity("near", "cart", "concept", serde_json::json!({})))?;
+        index.index_entity(&entity("exact", "card", "concept", serde_json::json!({})))?;
+
+        let results = index.search("card", 10)?;
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].id, "exact");
+        Ok(())
+    }
+
+    #[test]
+    fn test_name_match_outranks_body_match() -> Result<()> {
+        let index = TantivyIndex::in_memory(SearchLanguage::English)?;
+        index.index_entity(&entity(
+            "body",
+            "Weekly sync",
+            "meeting",
+            serde_json::json!({"topic": "quarterly budget review"}),
+        ))?;
+        index.index_entity(&entity("name", "Budget", "concept", serde_json::json!({})))?;
+
+        let results = index.search("budget", 10)?;
+        assert_eq!(results[0].id, "name");
+        Ok(())
+    }
+
+    #[test]
+    fn test_filter_by_entity_type_and_tags() -> Result<()> {
+        let index = TantivyIndex::in_memory(SearchLanguage::English)?;
+        index.index_entity(&entity(
+            "alice",
+            "Alice",
+            "person",
+            serde_json::json!({"role": "rust reviewer", "tags": ["work", "Rust"]}),
+        ))?;
+        index.index_entity(&entity(
+            "crate",
+            "Rust crate",
+            "project",
+            serde_json::json!({"tags": "work, side"}),
+        ))?;
+
+        let people = SearchOptions {
+            entity_types: vec!["person".to_string()],
+            ..Default::default()
+        };
+        let results = index.search_with("rust", &people, 10)?;
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].id, "alice");
+
+        let tagged = SearchOptions {
+            tags: vec!["work".to_string(), "side".to_string()],
+            ..Default::default()
+        };
+        let results = index.search_with("rust", &tagged, 10)?;
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].id, "crate");
+
+        // An empty query lists everything that passes the filters
+        let results = index.search_with(
+            "",
+            &SearchOptions {
+                tags: vec!["RUST".to_string()],
+                ..Default::default()
+            },
+            10,
+        )?;
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].id, "alice");
+        Ok(())
+    }
+
+    #[test]
+    fn test_filter_by_date_range() -> Result<()> {
+        let index = TantivyIndex::in_memory(SearchLanguage::English)?;
+        index.index_document("old", "release notes", "note", "2024-01-15T00:00:00Z")?;
+        index.index_document("new", "release notes", "note", "2025-06-01T00:00:00Z")?;
+
+        let cutoff = "2025-01-01T00:00:00Z".parse::<DateTime<Utc>>()?;
+        let after = SearchOptions {
+            created_after: Some(cutoff),
+            ..Default::default()
+        };
+        let results = index.search_with("release", &after, 10)?;
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].id, "new");
+
+        let before = SearchOptions {
+            created_before: Some(cutoff),
+            ..Default::default()
+        };
+        let results = index.search_with("release", &before, 10)?;
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].id, "old");
+        Ok(())
+    }
+
+    #[test]
+    fn test_snippet_highlights_matches() -> Result<()> {
+        let index = TantivyIndex::in_memory(SearchLanguage::English)?;
+        index.index_document(
+            "doc",
+            "The deployment failed because the certificate had expired",
+            "event",
+            &chrono::Utc::now().to_rfc3339(),
+        )?;
+
+        let results = index.search("certificate", 10)?;
+        let snippet = results[0].snippet.as_deref().unwrap();
+        assert!(snippet.contains("**certificate**"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_language_specific_stemming() -> Result<()> {
+        let index = TantivyIndex::in_memory(parse_language("german")?)?;
+        index.index_document("doc", "Die Katzen schlafen", "note", "")?;
+
+        assert_eq!(index.search("Katze", 10)?.len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_language() {
+        assert_eq!(parse_language("English").unwrap(), SearchLanguage::English);
+        assert_eq!(parse_language(" french ").unwrap(), SearchLanguage::French);
+        assert!(parse_language("klingon").is_err());
+    }
+
+    #[test]
+    fn test_schema_change_triggers_reindex() -> Result<()> {
+        let temp = tempfile::TempDir::new()?;
+        let path = temp.path().join("index");
+
+        let index = TantivyIndex::new(&path)?;
+        assert!(index.needs_reindex());
+        index.index_document("a", "kept across reopen", "note", "")?;
+        drop(index);
+
+        let index = TantivyIndex::new(&path)?;
+        assert!(!index.needs_reindex());
+        assert_eq!(index.search("kept", 10)?.len(), 1);
+        drop(index);
+
+        // An index from an older schema version is rebuilt empty
+        std::fs::write(
+            path.join(SCHEMA_FILE),
+            r#"{"version":1,"language":"English"}"#,
+        )?;
+        let index = TantivyIndex::new(&path)?;
+        assert!(index.needs_reindex());
+        assert!(index.search("kept", 10)?.is_empty());
+        drop(index);
+
+        // So is one opened with a different language
+        let index = TantivyIndex::open(&path, SearchLanguage::Spanish)?;
+        assert!(index.needs_reindex());
+        Ok(())
+    }
 }
@@ -0,0 +1,386 @@
+//! Temporal facts and provenance
+//!
+//! Relationships carry a validity interval (`valid_from`/`valid_to`) so that
+//! "Bob works at Acme" can stop being true without being deleted. Recording a
+//! contradicting fact for an exclusive relation type (e.g. a new `works_at`)
+//! closes the old one and links it to its replacement via `superseded_by`.
+//!
+//! Every entity or relationship can also be linked to the conversation, email,
+//! document or other source that produced it.
+
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::sqlite::Relationship;
+
+/// Relation types where an entity can only have one current target.
+/// A new fact of one of these types supersedes the previous one.
+pub const EXCLUSIVE_RELATIONS: &[&str] = &[
+    "works_at",
+    "employed_by",
+    "lives_in",
+    "located_in",
+    "reports_to",
+    "married_to",
+    "has_role",
+    "has_title",
+    "has_status",
+];
+
+/// Canonical form of a relation type: "Works At" and "works-at" are "works_at"
+pub fn normalize_relation(relation_type: &str) -> String {
+    relation_type.trim().to_lowercase().replace([' ', '-'], "_")
+}
+
+/// Whether a new fact of this relation type replaces the current one
+pub fn is_exclusive_relation(relation_type: &str) -> bool {
+    EXCLUSIVE_RELATIONS.contains(&normalize_relation(relation_type).as_str())
+}
+
+/// What kind of fact a provenance record points at
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FactType {
+    Entity,
+    Relationship,
+}
+
+impl FactType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Entity => "entity",
+            Self::Relationship => "relationship",
+        }
+    }
+}
+
+impl std::str::FromStr for FactType {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "entity" => Ok(Self::Entity),
+            "relationship" => Ok(Self::Relationship),
+            other => Err(anyhow!("Unknown fact type: {}", other)),
+        }
+    }
+}
+
+/// Where a fact came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SourceType {
+    Conversation,
+    Email,
+    Document,
+    Web,
+    User,
+    Agent,
+}
+
+impl SourceType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Conversation => "conversation",
+            Self::Email => "email",
+            Self::Document => "document",
+            Self::Web => "web",
+            Self::User => "user",
+            Self::Agent => "agent",
+        }
+    }
+}
+
+impl std::str::FromStr for SourceType {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "conversation" | "chat" | "message" => Ok(Self::Conversation),
+            "email" | "mail" => Ok(Self::Email),
+            "document" | "file" => Ok(Self::Document),
+            "web" | "url" => Ok(Self::Web),
+            "user" => Ok(Self::User),
+            "agent" => Ok(Self::Agent),
+            other => Err(anyhow!(
+                "Unknown source type '{}' (expected conversation, email, document, web, user or agent)",
+                other
+            )),
+        }
+    }
+}
+
+/// A pointer to the source of a fact, supplied when recording it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Provenance {
+    pub source_type: SourceType,
+    /// Conversation ID, email message ID, document path, URL, ...
+    pub source_id: String,
+    /// Short excerpt of the source that supports the fact
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub excerpt: Option<String>,
+}
+
+impl Provenance {
+    pub fn new(source_type: SourceType, source_id: impl Into<String>) -> Self {
+        Self {
+            source_type,
+            source_id: source_id.into(),
+            excerpt: None,
+        }
+    }
+
+    pub fn with_excerpt(mut self, excerpt: impl Into<String>) -> Self {
+        let excerpt: String = excerpt.into();
+        self.excerpt = Some(excerpt.chars().take(500).collect());
+        self
+    }
+}
+
+/// A stored provenance link between a fact and its source
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProvenanceRecord {
+    pub id: String,
+    pub fact_type: FactType,
+    pub fact_id: String,
+    #[serde(flatten)]
+    pub source: Provenance,
+    pub recorded_at: DateTime<Utc>,
+}
+
+impl ProvenanceRecord {
+    /// One-line human-readable description, e.g. `email <abc@x> (2026-03-02)`
+    pub fn describe(&self) -> String {
+        let mut out = format!(
+            "{} {} ({})",
+            self.source.source_type.as_str(),
+            self.source.source_id,
+            self.recorded_at.format("%Y-%m-%d")
+        );
+        if let Some(excerpt) = &self.source.excerpt {
+            let short: String = excerpt.chars().take(80).collect();
+            out.push_str(&format!(": \"{}\"", short));
+        }
+        out
+    }
+}
+
+/// Options for recording a relationship as a temporal fact
+#[derive(Debug, Clone, Default)]
+pub struct FactOptions {
+    /// When the fact became true (defaults to now)
+    pub valid_from: Option<DateTime<Utc>>,
+    /// Close out current facts of the same type from the same source entity
+    pub supersede: bool,
+    /// Where the fact came from
+    pub provenance: Option<Provenance>,
+}
+
+impl FactOptions {
+    /// Default options for a relation type: exclusive types supersede
+    pub fn for_relation(relation_type: &str) -> Self {
+        Self {
+            supersede: is_exclusive_relation(relation_type),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_provenance(mut self, provenance: Provenance) -> Self {
+        self.provenance = Some(provenance);
+        self
+    }
+
+    pub fn valid_from(mut self, at: DateTime<Utc>) -> Self {
+        self.valid_from = Some(at);
+        self
+    }
+}
+
+/// Result of recording a relationship fact
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordedFact {
+    /// ID of the new (or already current, identical) relationship
+    pub id: String,
+    /// True if an identical current relationship already existed
+    pub existing: bool,
+    /// IDs of relationships this fact closed out
+    pub superseded: Vec<String>,
+    /// When a backfilled fact starts before a current one, the current fact
+    /// that ends it
+    pub superseded_by: Option<String>,
+}
+
+impl Relationship {
+    /// Whether this relationship is still in effect
+    pub fn is_current(&self) -> bool {
+        self.valid_to.is_none()
+    }
+
+    /// Whether this relationship was known and in effect at `at`
+    pub fn is_valid_at(&self, at: DateTime<Utc>) -> bool {
+        let start = self.valid_from.unwrap_or(self.created_at);
+        start <= at && self.created_at <= at && self.valid_to.is_none_or(|end| end > at)
+    }
+}
+
+/// Parse a point-in-time argument: RFC 3339, `YYYY-MM-DD` or `YYYY-MM`.
+/// Dates resolve to the end of that day/month so "as of March" includes March.
+pub fn parse_as_of(input: &str) -> Result<DateTime<Utc>> {
+    parse_date(input, true)
+}
+
+/// Parse when a fact became true: RFC 3339, `YYYY-MM-DD` or `YYYY-MM`.
+/// Dates resolve to the start of that day/month.
+pub fn parse_valid_from(input: &str) -> Result<DateTime<Utc>> {
+    parse_date(input, false)
+}
+
+fn parse_date(input: &str, end_of_period: bool) -> Result<DateTime<Utc>> {
+    let input = input.trim();
+    if let Ok(dt) = DateTime::parse_from_rfc3339(input) {
+        return Ok(dt.with_timezone(&Utc));
+    }
+    let (start, next) = if let Ok(date) = NaiveDate::parse_from_str(input, "%Y-%m-%d") {
+        (date, date.succ_opt())
+    } else if let Ok(first) = NaiveDate::parse_from_str(&format!("{}-01", input), "%Y-%m-%d") {
+        (first, first.checked_add_months(chrono::Months::new(1)))
+    } else {
+        return Err(anyhow!(
+            "Invalid date '{}' (expected RFC 3339, YYYY-MM-DD or YYYY-MM)",
+            input
+        ));
+    };
+    let day = if end_of_period {
+        next.ok_or_else(|| anyhow!("Date out of range: {}", input))?
+    } else {
+        start
+    };
+    let midnight = Utc.from_utc_datetime(
+        &day.and_hms_opt(0, 0, 0)
+            .ok_or_else(|| anyhow!("Invalid date: {}", input))?,
+    );
+    Ok(if end_of_period {
+        midnight - chrono::Duration::seconds(1)
+    } else {
+        midnight
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rel(created: &str, from: Option<&str>, to: Option<&str>) -> Relationship {
+        let p = |s: &str| s.parse::<DateTime<Utc>>().unwrap();
+        Relationship {
+            id: "r".to_string(),
+            source_id: "a".to_string(),
+            target_id: "b".to_string(),
+            relation_type: "works_at".to_string(),
+            metadata: None,
+            created_at: p(created),
+            valid_from: from.map(p),
+            valid_to: to.map(p),
+            superseded_by: None,
+        }
+    }
+
+    #[test]
+    fn test_is_exclusive_relation() {
+        assert!(is_exclusive_relation("works_at"));
+        assert!(is_exclusive_relation("Works At"));
+        assert!(is_exclusive_relation("lives-in"));
+        assert!(!is_exclusive_relation("knows"));
+        assert!(!is_exclusive_relation("contains_chunk"));
+    }
+
+    #[test]
+    fn test_fact_options_for_relation() {
+        assert!(FactOptions::for_relation("works_at").supersede);
+        assert!(!FactOptions::for_relation("related_to").supersede);
+    }
+
+    #[test]
+    fn test_source_type_parse() {
+        assert_eq!("email".parse::<SourceType>().unwrap(), SourceType::Email);
+        assert_eq!(
+            "chat".parse::<SourceType>().unwrap(),
+            SourceType::Conversation
+        );
+        assert!("carrier pigeon".parse::<SourceType>().is_err());
+        assert_eq!(
+            "relationship".parse::<FactType>().unwrap(),
+            FactType::Relationship
+        );
+    }
+
+    #[test]
+    fn test_is_valid_at() {
+        let r = rel(
+            "2025-01-10T00:00:00Z",
+            Some("2025-01-01T00:00:00Z"),
+            Some("2025-06-01T00:00:00Z"),
+        );
+        let at = |s: &str| s.parse::<DateTime<Utc>>().unwrap();
+        // Not yet recorded, even though valid_from is earlier
+        assert!(!r.is_valid_at(at("2025-01-05T00:00:00Z")));
+        assert!(r.is_valid_at(at("2025-03-01T00:00:00Z")));
+        assert!(!r.is_valid_at(at("2025-06-01T00:00:00Z")));
+        assert!(!r.is_current());
+
+        let open = rel("2025-01-10T00:00:00Z", None, None);
+        assert!(open.is_current());
+        assert!(open.is_valid_at(at("2030-01-01T00:00:00Z")));
+    }
+
+    #[test]
+    fn test_parse_as_of() {
+        assert_eq!(
+            parse_as_of("2025-03-15T12:00:00Z").unwrap().to_rfc3339(),
+            "2025-03-15T12:00:00+00:00"
+        );
+        assert_eq!(
+            parse_as_of("2025-03-15").unwrap().to_rfc3339(),
+            "2025-03-15T23:59:59+00:00"
+        );
+        assert_eq!(
+            parse_as_of("2025-03").unwrap().to_rfc3339(),
+            "2025-03-31T23:59:59+00:00"
+        );
+        assert_eq!(
+            parse_as_of("2025-12").unwrap().to_rfc3339(),
+            "2025-12-31T23:59:59+00:00"
+        );
+        assert!(parse_as_of("last march").is_err());
+    }
+
+    #[test]
+    fn test_parse_valid_from() {
+        assert_eq!(
+            parse_valid_from("2025-03-15").unwrap().to_rfc3339(),
+            "2025-03-15T00:00:00+00:00"
+        );
+        assert_eq!(
+            parse_valid_from("2025-03").unwrap().to_rfc3339(),
+            "2025-03-01T00:00:00+00:00"
+        );
+        assert!(parse_valid_from("soon").is_err());
+    }
+
+    #[test]
+    fn test_provenance_describe() {
+        let record = ProvenanceRecord {
+            id: "p".to_string(),
+            fact_type: FactType::Entity,
+            fact_id: "e".to_string(),
+            source: Provenance::new(SourceType::Email, "<msg-1@example.com>")
+                .with_excerpt("Bob said he joined Acme"),
+            recorded_at: "2026-03-02T10:00:00Z".parse().unwrap(),
+        };
+        assert_eq!(
+            record.describe(),
+            "email <msg-1@example.com> (2026-03-02): \"Bob said he joined Acme\""
+        );
+    }
+}
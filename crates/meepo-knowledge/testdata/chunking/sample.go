@@ -0,0 +1,46 @@
+package store
+
+import (
+	"errors"
+	"sync"
+)
+
+// Item is a product held in the store.
+type Item struct {
+	SKU      string
+	Name     string
+	Quantity int
+}
+
+// Store keeps items in memory.
+type Store struct {
+	mu    sync.Mutex
+	items map[string]*Item
+}
+
+// NewStore creates an empty store.
+func NewStore() *Store {
+	return &Store{items: make(map[string]*Item)}
+}
+
+// Add merges the quantity of an item into the store.
+func (s *Store) Add(item Item) {
+	s.mu.Lock()
+	defer s.mu.Unlock()
+	if existing, ok := s.items[item.SKU]; ok {
+		existing.Quantity += item.Quantity
+		return
+	}
+	s.items[item.SKU] = &item
+}
+
+// Get returns a stored item.
+func (s *Store) Get(sku string) (*Item, error) {
+	s.mu.Lock()
+	defer s.mu.Unlock()
+	item, ok := s.items[sku]
+	if !ok {
+		return nil, errors.New("unknown sku")
+	}
+	return item, nil
+}
@@ -0,0 +1,39 @@
+"""Inventory store used by the sample service."""
+
+import asyncio
+from dataclasses import dataclass
+
+
+@dataclass
+class Item:
+    sku: str
+    name: str
+    quantity: int = 0
+
+
+class Store:
+    """Keeps items in memory and loads them from a backend."""
+
+    def __init__(self, backend):
+        self.backend = backend
+        self.items = {}
+
+    def add(self, item: Item) -> None:
+        # Merge quantities of items that are already known
+        existing = self.items.get(item.sku)
+        if existing is not None:
+            existing.quantity += item.quantity
+        else:
+            self.items[item.sku] = item
+
+    async def load(self, sku: str) -> Item:
+        if sku not in self.items:
+            record = await self.backend.fetch(sku)
+            self.items[sku] = Item(sku=sku, name=record["name"], quantity=record["qty"])
+        return self.items[sku]
+
+
+def main():
+    store = Store(backend=None)
+    store.add(Item(sku="a-1", name="Anvil", quantity=3))
+    print(asyncio.run(store.load("a-1")))
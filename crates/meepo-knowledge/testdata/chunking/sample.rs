@@ -0,0 +1,59 @@
+//! Configuration loading for the sample service.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// Settings read from the config file
+#[derive(Debug, Clone, Default)]
+pub struct Config {
+    pub name: String,
+    pub root: PathBuf,
+    pub limits: HashMap<String, u64>,
+}
+
+impl Config {
+    /// Create a config rooted at `root`
+    pub fn new(name: &str, root: PathBuf) -> Self {
+        Self {
+            name: name.to_string(),
+            root,
+            limits: HashMap::new(),
+        }
+    }
+
+    /// Check that every limit is positive and the name is set
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("name must not be empty".to_string());
+        }
+        for (key, limit) in &self.limits {
+            if *limit == 0 {
+                return Err(format!("limit {} must be positive", key));
+            }
+        }
+        if !self.root.is_absolute() {
+            return Err(format!("root {} must be absolute", self.root.display()));
+        }
+        Ok(())
+    }
+}
+
+/// Parse `key = value` lines into limits
+pub fn parse_limits(text: &str) -> HashMap<String, u64> {
+    text.lines()
+        .filter_map(|line| line.split_once('='))
+        .filter_map(|(k, v)| Some((k.trim().to_string(), v.trim().parse().ok()?)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_limits() {
+        let limits = parse_limits("a = 1\nb = 2");
+        assert_eq!(limits["a"], 1);
+        assert_eq!(limits["b"], 2);
+    }
+}
@@ -0,0 +1,43 @@
+import { Backend } from "./backend";
+
+export interface Item {
+  sku: string;
+  name: string;
+  quantity: number;
+}
+
+export class Store {
+  private items = new Map<string, Item>();
+
+  constructor(private backend: Backend) {}
+
+  async load(sku: string): Promise<Item> {
+    const cached = this.items.get(sku);
+    if (cached) {
+      return cached;
+    }
+    const record = await this.backend.fetch(sku);
+    const item = { sku, name: record.name, quantity: record.qty };
+    this.items.set(sku, item);
+    return item;
+  }
+
+  /** Merge quantities of items that are already known */
+  add(item: Item): void {
+    const existing = this.items.get(item.sku);
+    if (existing) {
+      existing.quantity += item.quantity;
+    } else {
+      this.items.set(item.sku, item);
+    }
+  }
+}
+
+export const formatItem = (item: Item): string => {
+  return `${item.name} (${item.sku}) x${item.quantity}`;
+};
+
+export function main(backend: Backend): void {
+  const store = new Store(backend);
+  store.add({ sku: "a-1", name: "Anvil", quantity: 3 });
+}
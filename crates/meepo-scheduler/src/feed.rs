@@ -0,0 +1,477 @@
+//! RSS, Atom and JSON Feed fetching and parsing
+//!
+//! Used by `FeedWatch` watchers. Feeds are fetched with conditional GET
+//! (ETag / Last-Modified) so an unchanged feed costs a `304`, and every item
+//! gets a stable GUID so it is only reported once, even across restarts.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use rusqlite::Connection;
+use scraper::Html;
+use serde::{Deserialize, Serialize};
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex};
+use tracing::debug;
+
+use crate::page::PageFetcher;
+
+/// Largest feed document we are willing to download
+const MAX_FEED_BYTES: usize = 5 * 1024 * 1024;
+
+/// Longest summary kept per item (characters)
+const MAX_SUMMARY_CHARS: usize = 500;
+
+/// A single entry of a parsed feed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FeedItem {
+    /// Stable identifier: the item's guid/id, falling back to its link
+    pub guid: String,
+    pub title: String,
+    pub link: Option<String>,
+    /// Plain-text summary (HTML stripped, truncated)
+    pub summary: Option<String>,
+    pub published: Option<DateTime<Utc>>,
+}
+
+impl FeedItem {
+    /// Whether the title or summary mentions any of `keywords` (case-insensitive).
+    /// An empty keyword list matches everything.
+    pub fn matches_keywords(&self, keywords: &[String]) -> bool {
+        if keywords.is_empty() {
+            return true;
+        }
+        let haystack = format!(
+            "{} {}",
+            self.title,
+            self.summary.as_deref().unwrap_or_default()
+        )
+        .to_lowercase();
+        keywords
+            .iter()
+            .filter(|k| !k.trim().is_empty())
+            .any(|k| haystack.contains(&k.trim().to_lowercase()))
+    }
+}
+
+/// A parsed feed document
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Feed {
+    pub title: Option<String>,
+    /// Items in document order (usually newest first)
+    pub items: Vec<FeedItem>,
+}
+
+/// Cache validators from the last successful fetch
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FeedValidators {
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+}
+
+/// Outcome of a conditional feed fetch
+#[derive(Debug)]
+pub enum FeedFetch {
+    /// The server answered `304 Not Modified`
+    NotModified,
+    Fetched {
+        feed: Feed,
+        validators: FeedValidators,
+    },
+}
+
+/// Fetch `url` through `fetcher`, sending the stored validators so unchanged
+/// feeds return `304`
+pub async fn fetch_feed(
+    fetcher: &dyn PageFetcher,
+    url: &str,
+    validators: &FeedValidators,
+) -> Result<FeedFetch> {
+    let mut headers = vec![(
+        "Accept",
+        "application/rss+xml, application/atom+xml, application/feed+json, \
+         application/xml;q=0.9, text/xml;q=0.9, */*;q=0.8",
+    )];
+    if let Some(etag) = &validators.etag {
+        headers.push(("If-None-Match", etag));
+    }
+    if let Some(last_modified) = &validators.last_modified {
+        headers.push(("If-Modified-Since", last_modified));
+    }
+
+    let response = fetcher
+        .get(url, &headers)
+        .await
+        .with_context(|| format!("Failed to fetch feed {}", url))?;
+    let status = response.status();
+    if status == reqwest::StatusCode::NOT_MODIFIED {
+        debug!("Feed {} not modified", url);
+        return Ok(FeedFetch::NotModified);
+    }
+    if !status.is_success() {
+        anyhow::bail!("Feed {} returned status {}", url, status);
+    }
+
+    let header = |name: &str| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(String::from)
+    };
+    let new_validators = FeedValidators {
+        etag: header("etag"),
+        last_modified: header("last-modified"),
+    };
+
+    let body = crate::page::read_body_limited(response, MAX_FEED_BYTES)
+        .await
+        .with_context(|| format!("Failed to read feed {}", url))?;
+
+    let feed = parse_feed(&String::from_utf8_lossy(&body))
+        .with_context(|| format!("Failed to parse feed {}", url))?;
+    Ok(FeedFetch::Fetched {
+        feed,
+        validators: new_validators,
+    })
+}
+
+/// Parse an RSS 2.0 / RSS 1.0, Atom or JSON Feed document
+pub fn parse_feed(body: &str) -> Result<Feed> {
+    let trimmed = body.trim_start_matches('\u{feff}').trim_start();
+    if trimmed.starts_with('{') {
+        parse_json_feed(trimmed)
+    } else {
+        parse_xml_feed(trimmed)
+    }
+}
+
+fn parse_xml_feed(body: &str) -> Result<Feed> {
+    let options = roxmltree::ParsingOptions {
+        allow_dtd: true,
+        ..Default::default()
+    };
+    let doc = roxmltree::Document::parse_with_options(body, options)
+        .context("Feed is not well-formed XML")?;
+    let root = doc.root_element();
+
+    match root.tag_name().name() {
+        "feed" => Ok(parse_atom(root)),
+        "rss" | "RDF" => Ok(parse_rss(root)),
+        other => anyhow::bail!("Unrecognized feed root element <{}>", other),
+    }
+}
+
+fn parse_rss(root: roxmltree::Node) -> Feed {
+    let channel = child(root, "channel");
+    let title = channel.and_then(|c| child_text(c, "title"));
+
+    // RSS 2.0 nests items in <channel>; RSS 1.0 (RDF) places them beside it
+    let items = root
+        .descendants()
+        .filter(|n| n.is_element() && n.tag_name().name() == "item")
+        .map(|item| {
+            let link = child_text(item, "link");
+            let title = child_text(item, "title").unwrap_or_default();
+            let published = child_text(item, "pubDate")
+                .or_else(|| child_text(item, "date"))
+                .as_deref()
+                .and_then(parse_date);
+            let guid = child_text(item, "guid").or_else(|| {
+                item.attribute(("http://www.w3.org/1999/02/22-rdf-syntax-ns#", "about"))
+                    .map(String::from)
+            });
+            let summary = child_text(item, "description")
+                .or_else(|| child_text(item, "encoded"))
+                .map(|s| html_to_text(&s));
+            FeedItem {
+                guid: stable_guid(guid, link.as_deref(), &title, published),
+                title,
+                link,
+                summary: summary.filter(|s| !s.is_empty()),
+                published,
+            }
+        })
+        .collect();
+
+    Feed { title, items }
+}
+
+fn parse_atom(root: roxmltree::Node) -> Feed {
+    let title = child_text(root, "title");
+    let items = root
+        .children()
+        .filter(|n| n.is_element() && n.tag_name().name() == "entry")
+        .map(|entry| {
+            let link = entry
+                .children()
+                .filter(|n| n.is_element() && n.tag_name().name() == "link")
+                .find(|n| matches!(n.attribute("rel"), None | Some("alternate")))
+                .and_then(|n| n.attribute("href"))
+                .map(String::from);
+            let title = child_text(entry, "title")
+                .map(|t| html_to_text(&t))
+                .unwrap_or_default();
+            let published = child_text(entry, "published")
+                .or_else(|| child_text(entry, "updated"))
+                .as_deref()
+                .and_then(parse_date);
+            let summary = child_text(entry, "summary")
+                .or_else(|| child_text(entry, "content"))
+                .map(|s| html_to_text(&s));
+            FeedItem {
+                guid: stable_guid(child_text(entry, "id"), link.as_deref(), &title, published),
+                title,
+                link,
+                summary: summary.filter(|s| !s.is_empty()),
+                published,
+            }
+        })
+        .collect();
+
+    Feed { title, items }
+}
+
+#[derive(Deserialize)]
+struct JsonFeed {
+    title: Option<String>,
+    #[serde(default)]
+    items: Vec<JsonFeedItem>,
+}
+
+#[derive(Deserialize)]
+struct JsonFeedItem {
+    id: Option<serde_json::Value>,
+    url: Option<String>,
+    title: Option<String>,
+    summary: Option<String>,
+    content_text: Option<String>,
+    content_html: Option<String>,
+    date_published: Option<String>,
+    date_modified: Option<String>,
+}
+
+fn parse_json_feed(body: &str) -> Result<Feed> {
+    let feed: JsonFeed = serde_json::from_str(body).context("Feed is not a valid JSON Feed")?;
+    let items = feed
+        .items
+        .into_iter()
+        .map(|item| {
+            // JSON Feed 1.0 allowed numeric ids
+            let id = item.id.and_then(|v| match v {
+                serde_json::Value::String(s) => Some(s),
+                serde_json::Value::Number(n) => Some(n.to_string()),
+                _ => None,
+            });
+            let published = item
+                .date_published
+                .or(item.date_modified)
+                .as_deref()
+                .and_then(parse_date);
+            let title = item.title.unwrap_or_default();
+            let summary = item
+                .summary
+                .or(item.content_text)
+                .or(item.content_html)
+                .map(|s| html_to_text(&s));
+            FeedItem {
+                guid: stable_guid(id, item.url.as_deref(), &title, published),
+                title,
+                link: item.url,
+                summary: summary.filter(|s| !s.is_empty()),
+                published,
+            }
+        })
+        .collect();
+
+    Ok(Feed {
+        title: feed.title,
+        items,
+    })
+}
+
+fn child<'a, 'i>(node: roxmltree::Node<'a, 'i>, name: &str) -> Option<roxmltree::Node<'a, 'i>> {
+    node.children()
+        .find(|n| n.is_element() && n.tag_name().name() == name)
+}
+
+fn child_text(node: roxmltree::Node, name: &str) -> Option<String> {
+    let text: String = child(node, name)?
+        .descendants()
+        .filter(|n| n.is_text())
+        .filter_map(|n| n.text())
+        .collect();
+    let text = text.trim();
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+/// Feed dates are RFC 2822 in RSS and RFC 3339 in Atom / JSON Feed
+fn parse_date(s: &str) -> Option<DateTime<Utc>> {
+    let s = s.trim();
+    DateTime::parse_from_rfc2822(s)
+        .or_else(|_| DateTime::parse_from_rfc3339(s))
+        .ok()
+        .map(|d| d.with_timezone(&Utc))
+}
+
+/// Use the feed's own identifier when present, otherwise the link, otherwise
+/// a hash of title and date
+fn stable_guid(
+    id: Option<String>,
+    link: Option<&str>,
+    title: &str,
+    published: Option<DateTime<Utc>>,
+) -> String {
+    if let Some(id) = id.filter(|s| !s.trim().is_empty()) {
+        return id.trim().to_string();
+    }
+    if let Some(link) = link.filter(|s| !s.trim().is_empty()) {
+        return link.trim().to_string();
+    }
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    title.hash(&mut hasher);
+    published.map(|d| d.timestamp()).hash(&mut hasher);
+    format!("hash:{:016x}", hasher.finish())
+}
+
+/// Strip markup from an HTML snippet and truncate it for event payloads
+fn html_to_text(html: &str) -> String {
+    let fragment = Html::parse_fragment(html);
+    let text = fragment.root_element().text().collect::<Vec<_>>().join(" ");
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.chars().count() > MAX_SUMMARY_CHARS {
+        let truncated: String = text.chars().take(MAX_SUMMARY_CHARS - 3).collect();
+        format!("{}...", truncated)
+    } else {
+        text
+    }
+}
+
+/// Read access to feed items collected by `FeedWatch` watchers
+#[derive(Clone)]
+pub struct FeedStore {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl FeedStore {
+    pub fn new(conn: Arc<Mutex<Connection>>) -> Self {
+        Self { conn }
+    }
+
+    /// Most recent items collected from `feed_url`, newest first
+    pub async fn recent_items(&self, feed_url: &str, limit: usize) -> Result<Vec<FeedItem>> {
+        let conn = Arc::clone(&self.conn);
+        let feed_url = feed_url.to_string();
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+            crate::persistence::recent_feed_items(&conn, &feed_url, limit)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RSS: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0" xmlns:content="http://purl.org/rss/1.0/modules/content/">
+  <channel>
+    <title>Rust Blog</title>
+    <item>
+      <title>Announcing Rust 1.90</title>
+      <link>https://blog.rust-lang.org/2025/09/18/Rust-1.90.0/</link>
+      <guid isPermaLink="false">rust-1.90</guid>
+      <pubDate>Thu, 18 Sep 2025 00:00:00 +0000</pubDate>
+      <description>&lt;p&gt;The Rust team is happy to announce a &lt;b&gt;new&lt;/b&gt; version.&lt;/p&gt;</description>
+    </item>
+    <item>
+      <title>No guid here</title>
+      <link>https://example.com/no-guid</link>
+    </item>
+  </channel>
+</rss>"#;
+
+    const ATOM: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+  <title>Example Atom</title>
+  <entry>
+    <title>Atom entry</title>
+    <link rel="self" href="https://example.com/self"/>
+    <link href="https://example.com/atom/1"/>
+    <id>urn:uuid:1225c695-cfb8-4ebb-aaaa-80da344efa6a</id>
+    <updated>2025-10-01T12:00:00Z</updated>
+    <summary type="html">Some &lt;em&gt;markup&lt;/em&gt;</summary>
+  </entry>
+</feed>"#;
+
+    #[test]
+    fn test_parse_rss() {
+        let feed = parse_feed(RSS).unwrap();
+        assert_eq!(feed.title.as_deref(), Some("Rust Blog"));
+        assert_eq!(feed.items.len(), 2);
+
+        let first = &feed.items[0];
+        assert_eq!(first.guid, "rust-1.90");
+        assert_eq!(
+            first.summary.as_deref(),
+            Some("The Rust team is happy to announce a new version.")
+        );
+        assert_eq!(
+            first.published.unwrap().to_rfc3339(),
+            "2025-09-18T00:00:00+00:00"
+        );
+        // Falls back to the link when there is no guid
+        assert_eq!(feed.items[1].guid, "https://example.com/no-guid");
+        assert!(feed.items[1].summary.is_none());
+    }
+
+    #[test]
+    fn test_parse_atom() {
+        let feed = parse_feed(ATOM).unwrap();
+        assert_eq!(feed.title.as_deref(), Some("Example Atom"));
+        let entry = &feed.items[0];
+        assert_eq!(entry.guid, "urn:uuid:1225c695-cfb8-4ebb-aaaa-80da344efa6a");
+        assert_eq!(entry.link.as_deref(), Some("https://example.com/atom/1"));
+        assert_eq!(entry.summary.as_deref(), Some("Some markup"));
+        assert!(entry.published.is_some());
+    }
+
+    #[test]
+    fn test_parse_json_feed() {
+        let feed = parse_feed(
+            r#"{"version": "https://jsonfeed.org/version/1.1", "title": "JSON",
+                "items": [{"id": 42, "url": "https://example.com/42", "title": "Answer",
+                           "content_text": "Forty two", "date_published": "2025-10-02T08:00:00+02:00"}]}"#,
+        )
+        .unwrap();
+        assert_eq!(feed.title.as_deref(), Some("JSON"));
+        assert_eq!(feed.items[0].guid, "42");
+        assert_eq!(feed.items[0].summary.as_deref(), Some("Forty two"));
+        assert_eq!(
+            feed.items[0].published.unwrap().to_rfc3339(),
+            "2025-10-02T06:00:00+00:00"
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_non_feeds() {
+        assert!(parse_feed("<html><body>nope</body></html>").is_err());
+        assert!(parse_feed("not a feed").is_err());
+    }
+
+    #[test]
+    fn test_matches_keywords() {
+        let item = FeedItem {
+            guid: "1".to_string(),
+            title: "Announcing Rust 1.90".to_string(),
+            link: None,
+            summary: Some("Faster linking on Linux".to_string()),
+            published: None,
+        };
+        assert!(item.matches_keywords(&[]));
+        assert!(item.matches_keywords(&["rust".to_string()]));
+        assert!(item.matches_keywords(&["python".to_string(), "LINKING".to_string()]));
+        assert!(!item.matches_keywords(&["python".to_string()]));
+    }
+}
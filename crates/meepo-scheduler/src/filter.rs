@@ -0,0 +1,626 @@
+//! Filter expressions over watcher event payloads
+//!
+//! A small boolean language evaluated against `WatcherEvent.payload` before an
+//! event reaches the agent, so noisy sources can be narrowed without an LLM:
+//!
+//! ```text
+//! from == "boss@example.com" and not (subject ~ "(?i)newsletter|digest")
+//! action in ["opened", "reopened"] && issue.comments >= 3
+//! labels contains "urgent" || title contains "outage"
+//! ```
+//!
+//! Paths are dotted keys into the payload (`issue.user.login`, `labels.0`);
+//! a missing path is `null`. `==`/`!=`/`<`/`<=`/`>`/`>=` compare numbers
+//! numerically (numeric strings included) and other strings lexicographically.
+//! `~` and `!~` match a regex, `contains` tests substrings (case-insensitive),
+//! array elements or object keys, and `in` tests membership of a list. A bare
+//! path is true when its value is not null, false, 0, or empty.
+
+use anyhow::{Result, anyhow, bail};
+use regex::Regex;
+use serde_json::Value;
+
+/// Longest filter accepted, in bytes
+const MAX_FILTER_LEN: usize = 4096;
+
+/// Deepest nesting of parentheses and `not` accepted
+const MAX_DEPTH: usize = 64;
+
+/// Size limit for compiled regexes, so a hostile pattern can't exhaust memory
+const MAX_REGEX_SIZE: usize = 1 << 20;
+
+/// A parsed filter expression
+#[derive(Debug, Clone)]
+pub struct Filter {
+    source: String,
+    expr: Expr,
+}
+
+impl Filter {
+    /// Parse a filter expression
+    pub fn parse(source: &str) -> Result<Self> {
+        if source.len() > MAX_FILTER_LEN {
+            bail!("Filter is longer than {} bytes", MAX_FILTER_LEN);
+        }
+        let tokens = tokenize(source)?;
+        if tokens.is_empty() {
+            bail!("Filter is empty");
+        }
+        let mut parser = Parser {
+            tokens,
+            pos: 0,
+            depth: 0,
+        };
+        let expr = parser.or()?;
+        if let Some((token, at)) = parser.tokens.get(parser.pos) {
+            bail!("Unexpected {} at position {} in filter", token, at);
+        }
+        Ok(Self {
+            source: source.to_string(),
+            expr,
+        })
+    }
+
+    /// Whether an event payload passes the filter
+    pub fn matches(&self, payload: &Value) -> bool {
+        self.expr.eval(payload)
+    }
+
+    /// The expression as written
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// Dotted paths the expression reads, in order of appearance
+    pub fn paths(&self) -> Vec<String> {
+        let mut paths = Vec::new();
+        self.expr.collect_paths(&mut paths);
+        paths
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Expr {
+    Or(Box<Expr>, Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Compare(Operand, CompareOp, Operand),
+    Matches(Operand, Regex, bool),
+    Truthy(Operand),
+}
+
+#[derive(Debug, Clone, Copy)]
+enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Contains,
+    In,
+}
+
+#[derive(Debug, Clone)]
+enum Operand {
+    Path(Vec<String>),
+    Literal(Value),
+}
+
+impl Operand {
+    fn resolve<'a>(&'a self, payload: &'a Value) -> &'a Value {
+        match self {
+            Self::Literal(value) => value,
+            Self::Path(path) => path
+                .iter()
+                .try_fold(payload, |value, key| match value {
+                    Value::Object(map) => map.get(key),
+                    Value::Array(items) => key.parse::<usize>().ok().and_then(|i| items.get(i)),
+                    _ => None,
+                })
+                .unwrap_or(&Value::Null),
+        }
+    }
+}
+
+impl Expr {
+    fn collect_paths(&self, paths: &mut Vec<String>) {
+        let mut push = |operand: &Operand| {
+            if let Operand::Path(path) = operand {
+                paths.push(path.join("."));
+            }
+        };
+        match self {
+            Self::Or(a, b) | Self::And(a, b) => {
+                a.collect_paths(paths);
+                b.collect_paths(paths);
+            }
+            Self::Not(a) => a.collect_paths(paths),
+            Self::Truthy(operand) | Self::Matches(operand, _, _) => push(operand),
+            Self::Compare(left, _, right) => {
+                push(left);
+                push(right);
+            }
+        }
+    }
+
+    fn eval(&self, payload: &Value) -> bool {
+        match self {
+            Self::Or(a, b) => a.eval(payload) || b.eval(payload),
+            Self::And(a, b) => a.eval(payload) && b.eval(payload),
+            Self::Not(a) => !a.eval(payload),
+            Self::Truthy(operand) => truthy(operand.resolve(payload)),
+            Self::Matches(operand, regex, negate) => {
+                let matched = match operand.resolve(payload) {
+                    Value::Null => false,
+                    Value::String(s) => regex.is_match(s),
+                    other => regex.is_match(&other.to_string()),
+                };
+                matched != *negate
+            }
+            Self::Compare(left, op, right) => {
+                let (left, right) = (left.resolve(payload), right.resolve(payload));
+                match op {
+                    CompareOp::Eq => equal(left, right),
+                    CompareOp::Ne => !equal(left, right),
+                    CompareOp::Lt => order(left, right).is_some_and(|o| o.is_lt()),
+                    CompareOp::Le => order(left, right).is_some_and(|o| o.is_le()),
+                    CompareOp::Gt => order(left, right).is_some_and(|o| o.is_gt()),
+                    CompareOp::Ge => order(left, right).is_some_and(|o| o.is_ge()),
+                    CompareOp::Contains => contains(left, right),
+                    CompareOp::In => match right {
+                        Value::Array(items) => items.iter().any(|item| equal(left, item)),
+                        _ => contains(right, left),
+                    },
+                }
+            }
+        }
+    }
+}
+
+fn truthy(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Bool(b) => *b,
+        Value::Number(n) => n.as_f64().is_some_and(|n| n != 0.0),
+        Value::String(s) => !s.is_empty(),
+        Value::Array(items) => !items.is_empty(),
+        Value::Object(map) => !map.is_empty(),
+    }
+}
+
+/// A value as a number, accepting numeric strings
+fn number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+fn equal(a: &Value, b: &Value) -> bool {
+    if matches!(a, Value::Number(_)) || matches!(b, Value::Number(_)) {
+        return match (number(a), number(b)) {
+            (Some(x), Some(y)) => x == y,
+            _ => false,
+        };
+    }
+    a == b
+}
+
+fn order(a: &Value, b: &Value) -> Option<std::cmp::Ordering> {
+    match (number(a), number(b)) {
+        (Some(x), Some(y)) => x.partial_cmp(&y),
+        _ => match (a, b) {
+            (Value::String(x), Value::String(y)) => Some(x.cmp(y)),
+            _ => None,
+        },
+    }
+}
+
+fn contains(haystack: &Value, needle: &Value) -> bool {
+    match (haystack, needle) {
+        (Value::String(h), Value::String(n)) => h.to_lowercase().contains(&n.to_lowercase()),
+        (Value::Array(items), _) => items.iter().any(|item| equal(item, needle)),
+        (Value::Object(map), Value::String(key)) => map.contains_key(key),
+        _ => false,
+    }
+}
+
+// ── Tokenizer ──
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Str(String),
+    Num(f64),
+    Op(&'static str),
+    LParen,
+    RParen,
+    LBracket,
+    RBracket,
+    Comma,
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Ident(s) => write!(f, "'{}'", s),
+            Self::Str(s) => write!(f, "string \"{}\"", s),
+            Self::Num(n) => write!(f, "number {}", n),
+            Self::Op(op) => write!(f, "'{}'", op),
+            Self::LParen => write!(f, "'('"),
+            Self::RParen => write!(f, "')'"),
+            Self::LBracket => write!(f, "'['"),
+            Self::RBracket => write!(f, "']'"),
+            Self::Comma => write!(f, "','"),
+        }
+    }
+}
+
+const OPERATORS: [&str; 11] = ["==", "!=", ">=", "<=", "!~", "&&", "||", ">", "<", "~", "!"];
+
+fn tokenize(source: &str) -> Result<Vec<(Token, usize)>> {
+    let chars: Vec<(usize, char)> = source.char_indices().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let (at, c) = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        let single = match c {
+            '(' => Some(Token::LParen),
+            ')' => Some(Token::RParen),
+            '[' => Some(Token::LBracket),
+            ']' => Some(Token::RBracket),
+            ',' => Some(Token::Comma),
+            _ => None,
+        };
+        if let Some(token) = single {
+            tokens.push((token, at));
+            i += 1;
+        } else if c == '"' || c == '\'' {
+            // Only the quote and backslash are escapes, so regexes like '\d+' read naturally
+            let mut s = String::new();
+            i += 1;
+            loop {
+                match chars.get(i) {
+                    None => bail!("Unterminated string at position {} in filter", at),
+                    Some(&(_, ch)) if ch == c => break,
+                    Some(&(_, '\\')) => match chars.get(i + 1) {
+                        Some(&(_, next)) if next == c || next == '\\' => {
+                            s.push(next);
+                            i += 1;
+                        }
+                        _ => s.push('\\'),
+                    },
+                    Some(&(_, ch)) => s.push(ch),
+                }
+                i += 1;
+            }
+            tokens.push((Token::Str(s), at));
+            i += 1;
+        } else if c.is_ascii_digit()
+            || (c == '-' && chars.get(i + 1).is_some_and(|(_, d)| d.is_ascii_digit()))
+        {
+            let start = i;
+            i += 1;
+            while chars
+                .get(i)
+                .is_some_and(|(_, d)| d.is_ascii_digit() || *d == '.')
+            {
+                i += 1;
+            }
+            let text: String = chars[start..i].iter().map(|(_, ch)| ch).collect();
+            let n = text
+                .parse()
+                .map_err(|_| anyhow!("Invalid number '{}' at position {} in filter", text, at))?;
+            tokens.push((Token::Num(n), at));
+        } else if c.is_alphabetic() || c == '_' || c == '$' {
+            let start = i;
+            while chars
+                .get(i)
+                .is_some_and(|(_, ch)| ch.is_alphanumeric() || matches!(ch, '_' | '$' | '.' | '-'))
+            {
+                i += 1;
+            }
+            let text: String = chars[start..i].iter().map(|(_, ch)| ch).collect();
+            tokens.push((Token::Ident(text), at));
+        } else if let Some(op) = OPERATORS.iter().find(|op| source[at..].starts_with(**op)) {
+            tokens.push((Token::Op(op), at));
+            i += op.chars().count();
+        } else if c == '=' {
+            bail!("Use '==' for equality (position {} in filter)", at);
+        } else {
+            bail!("Unexpected character '{}' at position {} in filter", c, at);
+        }
+    }
+    Ok(tokens)
+}
+
+// ── Parser ──
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(token, _)| token)
+    }
+
+    fn next(&mut self) -> Result<Token> {
+        let token = self
+            .tokens
+            .get(self.pos)
+            .map(|(token, _)| token.clone())
+            .ok_or_else(|| anyhow!("Filter ends unexpectedly"))?;
+        self.pos += 1;
+        Ok(token)
+    }
+
+    fn error<T>(&self, expected: &str) -> Result<T> {
+        match self.tokens.get(self.pos) {
+            Some((token, at)) => bail!(
+                "Expected {} but found {} at position {} in filter",
+                expected,
+                token,
+                at
+            ),
+            None => bail!("Expected {} at the end of the filter", expected),
+        }
+    }
+
+    /// Consume a keyword (case-insensitive) or operator alias
+    fn eat(&mut self, keyword: &str, op: &str) -> bool {
+        let found = match self.peek() {
+            Some(Token::Ident(word)) => word.eq_ignore_ascii_case(keyword),
+            Some(Token::Op(o)) => *o == op,
+            _ => false,
+        };
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn or(&mut self) -> Result<Expr> {
+        let mut expr = self.and()?;
+        while self.eat("or", "||") {
+            expr = Expr::Or(Box::new(expr), Box::new(self.and()?));
+        }
+        Ok(expr)
+    }
+
+    fn and(&mut self) -> Result<Expr> {
+        let mut expr = self.unary()?;
+        while self.eat("and", "&&") {
+            expr = Expr::And(Box::new(expr), Box::new(self.unary()?));
+        }
+        Ok(expr)
+    }
+
+    fn unary(&mut self) -> Result<Expr> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            bail!("Filter is nested more than {} levels deep", MAX_DEPTH);
+        }
+        let expr = if self.eat("not", "!") {
+            Expr::Not(Box::new(self.unary()?))
+        } else if self.peek() == Some(&Token::LParen) {
+            self.pos += 1;
+            let expr = self.or()?;
+            if self.peek() != Some(&Token::RParen) {
+                return self.error("')'");
+            }
+            self.pos += 1;
+            expr
+        } else {
+            self.comparison()?
+        };
+        self.depth -= 1;
+        Ok(expr)
+    }
+
+    fn comparison(&mut self) -> Result<Expr> {
+        let left = self.operand()?;
+        let op = match self.peek() {
+            Some(Token::Op("==")) => CompareOp::Eq,
+            Some(Token::Op("!=")) => CompareOp::Ne,
+            Some(Token::Op("<")) => CompareOp::Lt,
+            Some(Token::Op("<=")) => CompareOp::Le,
+            Some(Token::Op(">")) => CompareOp::Gt,
+            Some(Token::Op(">=")) => CompareOp::Ge,
+            Some(Token::Op(op @ ("~" | "!~"))) => {
+                let negate = *op == "!~";
+                self.pos += 1;
+                let Token::Str(pattern) = self.next()? else {
+                    self.pos -= 1;
+                    return self.error("a quoted regex");
+                };
+                let regex = regex::RegexBuilder::new(&pattern)
+                    .size_limit(MAX_REGEX_SIZE)
+                    .build()
+                    .map_err(|e| anyhow!("Invalid regex '{}' in filter: {}", pattern, e))?;
+                return Ok(Expr::Matches(left, regex, negate));
+            }
+            Some(Token::Ident(word)) if word.eq_ignore_ascii_case("contains") => {
+                CompareOp::Contains
+            }
+            Some(Token::Ident(word)) if word.eq_ignore_ascii_case("in") => CompareOp::In,
+            _ => return Ok(Expr::Truthy(left)),
+        };
+        self.pos += 1;
+        let right = self.operand()?;
+        Ok(Expr::Compare(left, op, right))
+    }
+
+    fn operand(&mut self) -> Result<Operand> {
+        let Some(token) = self.peek().cloned() else {
+            return self.error("a value or field");
+        };
+        let operand = match token {
+            Token::Str(s) => Operand::Literal(Value::String(s)),
+            Token::Num(n) => Operand::Literal(number_value(n)),
+            Token::LBracket => {
+                self.pos += 1;
+                return Ok(Operand::Literal(Value::Array(self.list()?)));
+            }
+            Token::Ident(word) => match word.to_ascii_lowercase().as_str() {
+                "true" => Operand::Literal(Value::Bool(true)),
+                "false" => Operand::Literal(Value::Bool(false)),
+                "null" => Operand::Literal(Value::Null),
+                "and" | "or" | "not" | "contains" | "in" => return self.error("a value or field"),
+                _ => {
+                    let path: Vec<String> = word.split('.').map(str::to_string).collect();
+                    if path.iter().any(String::is_empty) {
+                        return self.error("a field path");
+                    }
+                    Operand::Path(path)
+                }
+            },
+            _ => return self.error("a value or field"),
+        };
+        self.pos += 1;
+        Ok(operand)
+    }
+
+    /// Literal list items after '['
+    fn list(&mut self) -> Result<Vec<Value>> {
+        let mut items = Vec::new();
+        if self.peek() == Some(&Token::RBracket) {
+            self.pos += 1;
+            return Ok(items);
+        }
+        loop {
+            match self.operand()? {
+                Operand::Literal(value) => items.push(value),
+                Operand::Path(_) => {
+                    self.pos -= 1;
+                    return self.error("a literal list item");
+                }
+            }
+            match self.next()? {
+                Token::Comma => continue,
+                Token::RBracket => return Ok(items),
+                _ => {
+                    self.pos -= 1;
+                    return self.error("',' or ']'");
+                }
+            }
+        }
+    }
+}
+
+fn number_value(n: f64) -> Value {
+    if n.fract() == 0.0 && n.abs() < i64::MAX as f64 {
+        Value::from(n as i64)
+    } else {
+        serde_json::Number::from_f64(n)
+            .map(Value::Number)
+            .unwrap_or(Value::Null)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn email() -> Value {
+        json!({
+            "from": "boss@example.com",
+            "subject": "Q3 Invoice overdue",
+            "size": 2048,
+            "labels": ["urgent", "finance"],
+            "thread": {"messages": "4", "participants": []},
+            "read": false
+        })
+    }
+
+    fn check(filter: &str) -> bool {
+        Filter::parse(filter).unwrap().matches(&email())
+    }
+
+    #[test]
+    fn test_comparisons() {
+        assert!(check(r#"from == "boss@example.com""#));
+        assert!(check("from != 'someone@example.com'"));
+        assert!(check("size > 1000 and size <= 2048"));
+        assert!(!check("size < 1000"));
+        // Numeric strings compare as numbers
+        assert!(check("thread.messages >= 3"));
+        assert!(check("thread.messages == 4"));
+        assert!(check("labels.0 == 'urgent'"));
+        // Missing fields are null
+        assert!(check("missing == null"));
+        assert!(!check("missing > 0"));
+        assert!(check("read == false"));
+    }
+
+    #[test]
+    fn test_regex_and_contains() {
+        assert!(check(r"subject ~ '(?i)^q\d invoice'"));
+        assert!(check("subject !~ 'newsletter'"));
+        assert!(!check("missing ~ '.*'"));
+        assert!(check("subject contains 'INVOICE'"));
+        assert!(check("labels contains 'finance'"));
+        assert!(!check("labels contains 'spam'"));
+        assert!(check("thread contains 'participants'"));
+        assert!(check("from in ['boss@example.com', 'cto@example.com']"));
+        assert!(check("'finance' in labels"));
+        assert!(!check("size in [1, 2, 3]"));
+    }
+
+    #[test]
+    fn test_boolean_logic() {
+        assert!(check("not read"));
+        assert!(check("labels and not thread.participants"));
+        assert!(check("size > 5000 or from contains 'boss'"));
+        assert!(!check("size > 5000 || from contains 'intern'"));
+        // and binds tighter than or
+        assert!(check("size > 5000 and missing or read == false"));
+        assert!(!check("size > 5000 and (missing or read == false)"));
+        assert!(check(
+            "!(size > 5000) && (subject contains 'overdue' || read)"
+        ));
+        assert!(check("NOT read AND labels"));
+    }
+
+    #[test]
+    fn test_paths() {
+        let filter = Filter::parse(r#"a.b > 1 and (not c || d ~ "x") and 2 in e.0"#).unwrap();
+        assert_eq!(filter.paths(), vec!["a.b", "c", "d", "e.0"]);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        for (filter, expected) in [
+            ("", "empty"),
+            ("from = 'a'", "'=='"),
+            ("from == 'a", "Unterminated"),
+            ("(size > 1", "')'"),
+            ("size > ", "end of the filter"),
+            ("subject ~ other", "quoted regex"),
+            ("subject ~ '('", "Invalid regex"),
+            ("size > 1 size", "Unexpected"),
+            ("from in [a, 'b']", "literal list item"),
+            ("from # 1", "Unexpected character"),
+        ] {
+            let err = Filter::parse(filter).unwrap_err().to_string();
+            assert!(err.contains(expected), "{}: {}", filter, err);
+        }
+        assert!(Filter::parse(&"a or ".repeat(1000)).is_err());
+        let nested = format!("{}a{}", "(".repeat(100), ")".repeat(100));
+        assert!(
+            Filter::parse(&nested)
+                .unwrap_err()
+                .to_string()
+                .contains("nested")
+        );
+    }
+}
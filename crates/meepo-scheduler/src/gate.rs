@@ -0,0 +1,561 @@
+//! Event gate: per-watcher filtering and throttling of watcher events
+//!
+//! Every watcher event passes through the gate before it reaches the agent.
+//! A watcher's `EventRules` can drop events that don't match a filter
+//! expression, hold bursts back until the source goes quiet (debounce) or a
+//! window closes (batching), and cap how many events get through per period.
+//! A burst closed by a batching window is coalesced into one `event_batch`
+//! event summarizing its members; a debounced burst without batching keeps
+//! only its latest event.
+//!
+//! `Throttle` holds the state and takes the current time as an argument, so
+//! it can be tested without timers; `EventGate` shares it between the runner,
+//! which installs each watcher's rules, and the task forwarding events.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+use tracing::{debug, info};
+
+use crate::filter::Filter;
+use crate::watcher::WatcherEvent;
+
+/// Most member events kept in an `event_batch`; the rest are only counted
+pub const MAX_BATCH_EVENTS: usize = 20;
+
+/// Which events of a watcher reach the agent, and how often
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EventRules {
+    /// Filter expression over the event payload (see `filter`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filter: Option<String>,
+
+    /// Hold events until none has arrived for this many seconds
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub debounce_secs: u64,
+
+    /// Coalesce events arriving within this many seconds of the first into one
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub batch_window_secs: u64,
+
+    /// Cap on events passed to the agent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rate_limit: Option<RateLimit>,
+}
+
+/// At most `max_events` events per `per_secs` seconds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RateLimit {
+    pub max_events: u32,
+    pub per_secs: u64,
+}
+
+fn is_zero(n: &u64) -> bool {
+    *n == 0
+}
+
+impl EventRules {
+    /// Whether every event passes straight through
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Check the filter parses and the limits make sense
+    pub fn validate(&self) -> Result<()> {
+        self.compile().map(|_| ())
+    }
+
+    fn compile(&self) -> Result<CompiledRules> {
+        let filter = self
+            .filter
+            .as_deref()
+            .filter(|f| !f.trim().is_empty())
+            .map(Filter::parse)
+            .transpose()
+            .context("Invalid event filter")?;
+        if let Some(limit) = self.rate_limit
+            && (limit.max_events == 0 || limit.per_secs == 0)
+        {
+            bail!("rate_limit needs max_events and per_secs above zero");
+        }
+        Ok(CompiledRules {
+            filter,
+            debounce: Duration::from_secs(self.debounce_secs),
+            batch_window: Duration::from_secs(self.batch_window_secs),
+            rate_limit: self.rate_limit.map(|limit| {
+                (
+                    limit.max_events as usize,
+                    Duration::from_secs(limit.per_secs),
+                )
+            }),
+        })
+    }
+
+    /// Short human-readable summary, e.g. "filter `a == 1`, batched per 60s"
+    pub fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(ref filter) = self.filter {
+            parts.push(format!("filter `{}`", filter));
+        }
+        if self.debounce_secs > 0 {
+            parts.push(format!("debounced {}s", self.debounce_secs));
+        }
+        if self.batch_window_secs > 0 {
+            parts.push(format!("batched per {}s", self.batch_window_secs));
+        }
+        if let Some(limit) = self.rate_limit {
+            parts.push(format!(
+                "at most {} per {}s",
+                limit.max_events, limit.per_secs
+            ));
+        }
+        parts.join(", ")
+    }
+}
+
+struct CompiledRules {
+    filter: Option<Filter>,
+    debounce: Duration,
+    batch_window: Duration,
+    rate_limit: Option<(usize, Duration)>,
+}
+
+impl CompiledRules {
+    fn groups(&self) -> bool {
+        !self.debounce.is_zero() || !self.batch_window.is_zero()
+    }
+}
+
+/// Events held back while a burst is in progress
+struct Burst {
+    first: Instant,
+    last: Instant,
+    events: Vec<WatcherEvent>,
+    total: usize,
+}
+
+struct WatcherGate {
+    rules: CompiledRules,
+    burst: Option<Burst>,
+    /// When recently passed events went out, for the rate limit
+    sent: VecDeque<Instant>,
+    /// Events dropped by the rate limit since the last one passed
+    suppressed: usize,
+}
+
+impl WatcherGate {
+    fn deadline(&self) -> Option<Instant> {
+        let burst = self.burst.as_ref()?;
+        let debounce = (!self.rules.debounce.is_zero()).then(|| burst.last + self.rules.debounce);
+        let window =
+            (!self.rules.batch_window.is_zero()).then(|| burst.first + self.rules.batch_window);
+        debounce.into_iter().chain(window).min()
+    }
+
+    /// Turn the held burst into the one event that represents it
+    fn close_burst(&mut self, watcher_id: &str) -> Option<WatcherEvent> {
+        let mut burst = self.burst.take()?;
+        if burst.total == 1 || self.rules.batch_window.is_zero() {
+            return burst.events.pop();
+        }
+        Some(WatcherEvent::batch(
+            watcher_id.to_string(),
+            &burst.events,
+            burst.total,
+        ))
+    }
+
+    /// Apply the rate limit to an event about to go out
+    fn admit(&mut self, mut event: WatcherEvent, now: Instant) -> Option<WatcherEvent> {
+        if let Some((max_events, period)) = self.rules.rate_limit {
+            while self
+                .sent
+                .front()
+                .is_some_and(|&at| now.duration_since(at) >= period)
+            {
+                self.sent.pop_front();
+            }
+            if self.sent.len() >= max_events {
+                self.suppressed += 1;
+                info!(
+                    "Rate limit reached for watcher {}, dropping {} event",
+                    event.watcher_id, event.kind
+                );
+                return None;
+            }
+            self.sent.push_back(now);
+        }
+        if self.suppressed > 0 {
+            if let Some(payload) = event.payload.as_object_mut() {
+                payload.insert("suppressed_events".to_string(), self.suppressed.into());
+            }
+            self.suppressed = 0;
+        }
+        Some(event)
+    }
+}
+
+/// Filter and throttle state for all watchers
+#[derive(Default)]
+pub struct Throttle {
+    watchers: HashMap<String, WatcherGate>,
+}
+
+impl Throttle {
+    /// Install a watcher's rules, replacing earlier ones. Empty rules remove
+    /// the watcher, so its events pass straight through.
+    pub fn set_rules(&mut self, watcher_id: &str, rules: &EventRules) -> Result<()> {
+        if rules.is_empty() {
+            self.watchers.remove(watcher_id);
+            return Ok(());
+        }
+        let rules = rules.compile()?;
+        self.watchers.insert(
+            watcher_id.to_string(),
+            WatcherGate {
+                rules,
+                burst: None,
+                sent: VecDeque::new(),
+                suppressed: 0,
+            },
+        );
+        Ok(())
+    }
+
+    /// Forget a watcher, dropping any events it has held back
+    pub fn remove(&mut self, watcher_id: &str) {
+        self.watchers.remove(watcher_id);
+    }
+
+    /// Take an event, returning it if it should go out right away
+    pub fn push(&mut self, event: WatcherEvent, now: Instant) -> Option<WatcherEvent> {
+        // Reports about the watcher itself are not subject to its rules
+        if event.kind == "watcher_disabled" {
+            return Some(event);
+        }
+        let Some(gate) = self.watchers.get_mut(&event.watcher_id) else {
+            return Some(event);
+        };
+        if let Some(ref filter) = gate.rules.filter
+            && !filter.matches(&event.payload)
+        {
+            debug!(
+                "Watcher {} {} event filtered out by `{}`",
+                event.watcher_id,
+                event.kind,
+                filter.as_str()
+            );
+            return None;
+        }
+        if !gate.rules.groups() {
+            return gate.admit(event, now);
+        }
+        let burst = gate.burst.get_or_insert_with(|| Burst {
+            first: now,
+            last: now,
+            events: Vec::new(),
+            total: 0,
+        });
+        burst.last = now;
+        burst.total += 1;
+        if burst.events.len() < MAX_BATCH_EVENTS {
+            burst.events.push(event);
+        } else if gate.rules.batch_window.is_zero() {
+            // Debouncing only keeps the latest event
+            *burst.events.last_mut().expect("burst has events") = event;
+        }
+        None
+    }
+
+    /// Close every burst whose debounce or batching deadline has passed
+    pub fn flush(&mut self, now: Instant) -> Vec<WatcherEvent> {
+        self.close(|deadline| deadline <= now, now)
+    }
+
+    /// Close every burst, e.g. when shutting down
+    pub fn flush_all(&mut self, now: Instant) -> Vec<WatcherEvent> {
+        self.close(|_| true, now)
+    }
+
+    fn close(&mut self, due: impl Fn(Instant) -> bool, now: Instant) -> Vec<WatcherEvent> {
+        let mut out = Vec::new();
+        for (watcher_id, gate) in &mut self.watchers {
+            if gate.deadline().is_some_and(&due)
+                && let Some(event) = gate.close_burst(watcher_id)
+                && let Some(event) = gate.admit(event, now)
+            {
+                out.push(event);
+            }
+        }
+        out.sort_by_key(|event| event.timestamp);
+        out
+    }
+
+    /// When the next burst closes
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.watchers
+            .values()
+            .filter_map(WatcherGate::deadline)
+            .min()
+    }
+}
+
+/// Shared handle on the `Throttle` between watcher events and the agent
+#[derive(Clone, Default)]
+pub struct EventGate {
+    throttle: Arc<Mutex<Throttle>>,
+}
+
+impl EventGate {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Install a watcher's rules (see `Throttle::set_rules`)
+    pub fn set_rules(&self, watcher_id: &str, rules: &EventRules) -> Result<()> {
+        self.lock().set_rules(watcher_id, rules)
+    }
+
+    /// Forget a watcher
+    pub fn remove(&self, watcher_id: &str) {
+        self.lock().remove(watcher_id);
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Throttle> {
+        self.throttle
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Forward events from `input` to `output` through the gate until
+    /// either side closes, flushing held-back events on the way out
+    pub fn spawn(
+        &self,
+        mut input: mpsc::UnboundedReceiver<WatcherEvent>,
+        output: mpsc::UnboundedSender<WatcherEvent>,
+    ) -> tokio::task::JoinHandle<()> {
+        let gate = self.clone();
+        tokio::spawn(async move {
+            loop {
+                let deadline = gate.lock().next_deadline();
+                let ready = tokio::select! {
+                    event = input.recv() => match event {
+                        Some(event) => gate.lock().push(event, Instant::now()).into_iter().collect(),
+                        None => {
+                            for event in gate.lock().flush_all(Instant::now()) {
+                                let _ = output.send(event);
+                            }
+                            break;
+                        }
+                    },
+                    _ = sleep_until(deadline) => gate.lock().flush(Instant::now()),
+                };
+                for event in ready {
+                    if output.send(event).is_err() {
+                        return;
+                    }
+                }
+            }
+        })
+    }
+}
+
+async fn sleep_until(deadline: Option<Instant>) {
+    match deadline {
+        Some(deadline) => tokio::time::sleep_until(deadline).await,
+        None => std::future::pending().await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const SEC: Duration = Duration::from_secs(1);
+
+    fn event(n: u64) -> WatcherEvent {
+        WatcherEvent::new(
+            "w1".to_string(),
+            "email_received".to_string(),
+            json!({"n": n, "subject": if n.is_multiple_of(2) { "Invoice" } else { "Newsletter" }}),
+        )
+    }
+
+    fn throttle(rules: EventRules) -> Throttle {
+        let mut throttle = Throttle::default();
+        throttle.set_rules("w1", &rules).unwrap();
+        throttle
+    }
+
+    #[test]
+    fn test_filter() {
+        let mut t = throttle(EventRules {
+            filter: Some("subject contains 'invoice'".to_string()),
+            ..Default::default()
+        });
+        let now = Instant::now();
+        assert!(t.push(event(1), now).is_none());
+        assert_eq!(t.push(event(2), now).unwrap().payload["n"], 2);
+        // Other watchers and disabled reports pass through untouched
+        let mut other = event(1);
+        other.watcher_id = "w2".to_string();
+        assert!(t.push(other, now).is_some());
+        let disabled = WatcherEvent::disabled("w1".into(), "d".into(), "e".into(), 3);
+        assert!(t.push(disabled, now).is_some());
+    }
+
+    #[test]
+    fn test_debounce_keeps_latest_event() {
+        let mut t = throttle(EventRules {
+            debounce_secs: 10,
+            ..Default::default()
+        });
+        let start = Instant::now();
+        for i in 0..5 {
+            assert!(t.push(event(i), start + i as u32 * 5 * SEC).is_none());
+        }
+        // The quiet period restarts with every event
+        assert_eq!(t.next_deadline(), Some(start + 30 * SEC));
+        assert!(t.flush(start + 29 * SEC).is_empty());
+        let out = t.flush(start + 30 * SEC);
+        assert_eq!(out.len(), 1);
+        assert_eq!(out[0].kind, "email_received");
+        assert_eq!(out[0].payload["n"], 4);
+        assert_eq!(t.next_deadline(), None);
+    }
+
+    #[test]
+    fn test_batch_window_coalesces_burst() {
+        let mut t = throttle(EventRules {
+            batch_window_secs: 60,
+            ..Default::default()
+        });
+        let start = Instant::now();
+        for i in 0..(MAX_BATCH_EVENTS as u64 + 5) {
+            assert!(t.push(event(i), start + SEC).is_none());
+        }
+        assert_eq!(t.next_deadline(), Some(start + 61 * SEC));
+        let out = t.flush(start + 61 * SEC);
+        assert_eq!(out.len(), 1);
+        let batch = &out[0];
+        assert_eq!(batch.kind, "event_batch");
+        assert_eq!(batch.payload["count"], MAX_BATCH_EVENTS + 5);
+        assert_eq!(batch.payload["omitted"], 5);
+        assert_eq!(batch.payload["kinds"]["email_received"], MAX_BATCH_EVENTS);
+        assert_eq!(batch.payload["events"][0]["payload"]["n"], 0);
+
+        // A lone event in a window goes out as itself
+        t.push(event(7), start + 100 * SEC);
+        let out = t.flush(start + 160 * SEC);
+        assert_eq!(out[0].kind, "email_received");
+    }
+
+    #[test]
+    fn test_debounce_is_capped_by_batch_window() {
+        let mut t = throttle(EventRules {
+            debounce_secs: 10,
+            batch_window_secs: 30,
+            ..Default::default()
+        });
+        let start = Instant::now();
+        for i in 0..10 {
+            t.push(event(i), start + i as u32 * 5 * SEC);
+            if i == 6 {
+                // Events never go quiet, so the window closes the burst
+                let out = t.flush(start + 30 * SEC);
+                assert_eq!(out.len(), 1);
+                assert_eq!(out[0].payload["count"], 7);
+            }
+        }
+        assert_eq!(t.next_deadline(), Some(start + 55 * SEC));
+    }
+
+    #[test]
+    fn test_rate_limit() {
+        let mut t = throttle(EventRules {
+            rate_limit: Some(RateLimit {
+                max_events: 2,
+                per_secs: 60,
+            }),
+            ..Default::default()
+        });
+        let start = Instant::now();
+        assert!(t.push(event(0), start).is_some());
+        assert!(t.push(event(1), start + SEC).is_some());
+        assert!(t.push(event(2), start + 2 * SEC).is_none());
+        assert!(t.push(event(3), start + 59 * SEC).is_none());
+        // The window slides; the next event reports what was dropped
+        let out = t.push(event(4), start + 60 * SEC).unwrap();
+        assert_eq!(out.payload["suppressed_events"], 2);
+        assert!(t.push(event(5), start + 60 * SEC).is_none());
+        assert!(t.push(event(6), start + 61 * SEC).is_some());
+        assert!(t.push(event(7), start + 61 * SEC).is_none());
+    }
+
+    #[test]
+    fn test_rules_validation() {
+        assert!(EventRules::default().is_empty());
+        assert!(
+            EventRules {
+                filter: Some("a ==".to_string()),
+                ..Default::default()
+            }
+            .validate()
+            .is_err()
+        );
+        assert!(
+            EventRules {
+                rate_limit: Some(RateLimit {
+                    max_events: 0,
+                    per_secs: 60
+                }),
+                ..Default::default()
+            }
+            .validate()
+            .is_err()
+        );
+        let parsed: Result<EventRules, _> =
+            serde_json::from_value(json!({"filter": "a", "debounce": 5}));
+        assert!(parsed.is_err());
+        let rules: EventRules =
+            serde_json::from_value(json!({"filter": "a > 1", "batch_window_secs": 60})).unwrap();
+        assert_eq!(rules.describe(), "filter `a > 1`, batched per 60s");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_gate_task_flushes_on_deadline() {
+        let gate = EventGate::new();
+        gate.set_rules(
+            "w1",
+            &EventRules {
+                batch_window_secs: 30,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let (in_tx, in_rx) = mpsc::unbounded_channel();
+        let (out_tx, mut out_rx) = mpsc::unbounded_channel();
+        let handle = gate.spawn(in_rx, out_tx);
+
+        in_tx.send(event(1)).unwrap();
+        in_tx.send(event(2)).unwrap();
+        tokio::time::sleep(29 * SEC).await;
+        assert!(out_rx.try_recv().is_err());
+        tokio::time::sleep(2 * SEC).await;
+        assert_eq!(out_rx.try_recv().unwrap().payload["count"], 2);
+
+        // Held-back events are flushed when the input closes
+        in_tx.send(event(3)).unwrap();
+        drop(in_tx);
+        handle.await.unwrap();
+        assert_eq!(out_rx.try_recv().unwrap().payload["n"], 3);
+    }
+}
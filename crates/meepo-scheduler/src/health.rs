@@ -0,0 +1,166 @@
+//! Watcher health: failure backoff, dead letters and status reporting
+//!
+//! A polling watcher whose poll fails retries after an exponentially growing,
+//! jittered delay instead of its normal interval, so a revoked token or a dead
+//! server is not hammered. Every failure is kept in `watcher_dead_letters`
+//! (with the undelivered event, when there was one) and counted in
+//! `watcher_health`; after too many consecutive failures the watcher is
+//! disabled and a `watcher_disabled` event tells the user.
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+
+use crate::watcher::WatcherEvent;
+
+/// Delay before retrying a watcher after `failures` consecutive failures.
+///
+/// Doubles from `interval` per failure up to `max`, then keeps a random part
+/// of the upper half ("equal jitter") so watchers failing together spread out.
+/// `jitter` is in `[0, 1)`. Never shorter than the normal interval.
+pub fn backoff_delay(interval: Duration, failures: u32, max: Duration, jitter: f64) -> Duration {
+    let factor = 2u32.saturating_pow(failures.min(31));
+    let ceiling = interval.saturating_mul(factor).min(max.max(interval));
+    let half = ceiling / 2;
+    (half + half.mul_f64(jitter.clamp(0.0, 1.0))).max(interval)
+}
+
+/// A random jitter fraction in `[0, 1)`
+pub fn jitter() -> f64 {
+    let (bits, _) = uuid::Uuid::new_v4().as_u64_pair();
+    (bits >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// Overall state of a watcher
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthStatus {
+    /// Has not run yet
+    Pending,
+    /// Last run succeeded
+    Ok,
+    /// Recent runs failed; retrying with backoff
+    Failing,
+    /// Turned off after too many consecutive failures
+    Disabled,
+}
+
+impl std::fmt::Display for HealthStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Pending => write!(f, "pending"),
+            Self::Ok => write!(f, "ok"),
+            Self::Failing => write!(f, "failing"),
+            Self::Disabled => write!(f, "disabled"),
+        }
+    }
+}
+
+/// Health of one watcher
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WatcherHealth {
+    pub watcher_id: String,
+    pub status: HealthStatus,
+    pub last_success: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+    pub last_error_at: Option<DateTime<Utc>>,
+    pub consecutive_errors: u32,
+    pub next_run: Option<DateTime<Utc>>,
+}
+
+/// A watcher failure, with the event that could not be delivered if any
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeadLetter {
+    pub id: i64,
+    pub watcher_id: String,
+    pub event: Option<WatcherEvent>,
+    pub error: String,
+    pub failed_at: DateTime<Utc>,
+}
+
+/// Read access to watcher health and dead letters
+#[derive(Clone)]
+pub struct HealthStore {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl HealthStore {
+    pub fn new(conn: Arc<Mutex<Connection>>) -> Self {
+        Self { conn }
+    }
+
+    /// Health of active and auto-disabled watchers, oldest watcher first
+    pub async fn watchers(&self) -> Result<Vec<WatcherHealth>> {
+        self.with_conn(crate::persistence::list_watcher_health)
+            .await
+    }
+
+    /// Health of one watcher
+    pub async fn watcher(&self, watcher_id: &str) -> Result<Option<WatcherHealth>> {
+        let watcher_id = watcher_id.to_string();
+        self.with_conn(move |conn| crate::persistence::get_watcher_health(conn, &watcher_id))
+            .await
+    }
+
+    /// Most recent dead letters, newest first, optionally for one watcher
+    pub async fn dead_letters(
+        &self,
+        watcher_id: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<DeadLetter>> {
+        let watcher_id = watcher_id.map(str::to_string);
+        self.with_conn(move |conn| {
+            crate::persistence::get_dead_letters(conn, watcher_id.as_deref(), limit)
+        })
+        .await
+    }
+
+    async fn with_conn<T: Send + 'static>(
+        &self,
+        f: impl FnOnce(&Connection) -> Result<T> + Send + 'static,
+    ) -> Result<T> {
+        let conn = Arc::clone(&self.conn);
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+            f(&conn)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MINUTE: Duration = Duration::from_secs(60);
+    const HOUR: Duration = Duration::from_secs(3600);
+
+    #[test]
+    fn test_backoff_grows_and_caps() {
+        // Without jitter the delay is half the ceiling, but never below the interval
+        assert_eq!(backoff_delay(MINUTE, 1, HOUR, 0.0), MINUTE);
+        assert_eq!(backoff_delay(MINUTE, 2, HOUR, 0.0), 2 * MINUTE);
+        assert_eq!(backoff_delay(MINUTE, 3, HOUR, 0.0), 4 * MINUTE);
+        assert_eq!(
+            backoff_delay(MINUTE, 3, HOUR, 0.999),
+            8 * MINUTE - Duration::from_millis(240)
+        );
+        assert_eq!(backoff_delay(MINUTE, 20, HOUR, 0.0), HOUR / 2);
+        assert!(backoff_delay(MINUTE, u32::MAX, HOUR, 1.0) <= HOUR);
+        // A cap below the interval never makes polling faster
+        assert_eq!(backoff_delay(HOUR, 5, MINUTE, 0.5), HOUR);
+    }
+
+    #[test]
+    fn test_jitter_range() {
+        for _ in 0..1000 {
+            let j = jitter();
+            assert!((0.0..1.0).contains(&j));
+        }
+    }
+}
@@ -1,21 +1,44 @@
 //! meepo-scheduler - Reactive watchers and task scheduling
 //!
 //! This crate provides functionality for:
-//! - Defining various types of watchers (email, calendar, GitHub, file, etc.)
-//! - Persisting watchers to SQLite
+//! - Defining various types of watchers (email, calendar, GitHub, feeds, web pages, webhooks, files, etc.)
+//! - Persisting watchers to SQLite behind a single watcher repository
 //! - Running watchers as tokio tasks with event emission
+//! - Backing off failing watchers and reporting their health
+//! - Filtering, debouncing, batching and rate-limiting watcher events
+//! - Keeping the run history of watcher-triggered workflows
 //! - Scheduling one-shot and recurring tasks
 
+pub mod feed;
+pub mod filter;
+pub mod gate;
+pub mod health;
+pub mod misfire;
+pub mod page;
 pub mod persistence;
 pub mod runner;
+pub mod schedule;
+pub mod store;
 pub mod watcher;
+pub mod webhook;
+pub mod workflow_runs;
 
+pub use feed::{FeedItem, FeedStore};
+pub use filter::Filter;
+pub use gate::{EventGate, EventRules, RateLimit};
+pub use health::{DeadLetter, HealthStatus, HealthStore, WatcherHealth};
+pub use misfire::{Clock, MisfireAction, MisfirePolicy};
+pub use page::PageFetcher;
 pub use persistence::{
     deactivate_watcher, delete_watcher, get_active_watchers, get_watcher_by_id,
-    init_watcher_tables, save_watcher,
+    init_watcher_tables, migrate_legacy_watchers, save_watcher,
 };
 pub use runner::{WatcherConfig, WatcherRunner};
-pub use watcher::{Watcher, WatcherEvent, WatcherKind};
+pub use schedule::{CronSchedule, ParsedSchedule, Tz, parse_schedule};
+pub use store::WatcherStore;
+pub use watcher::{Watcher, WatcherEvent, WatcherKind, WebhookScheme};
+pub use webhook::{WebhookDeliveries, WebhookRoutes};
+pub use workflow_runs::{RunStatus, StepRun, StepStatus, WorkflowRun, WorkflowRunStore};
 
 #[cfg(test)]
 mod tests {
@@ -36,6 +59,7 @@ mod tests {
             reply_channel: "slack-finance".to_string(),
             active: true,
             created_at: Utc::now(),
+            rules: EventRules::default(),
         };
 
         let json = serde_json::to_string(&watcher).unwrap();
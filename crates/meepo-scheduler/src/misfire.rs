@@ -0,0 +1,272 @@
+//! Missed-run handling for time-based watchers
+//!
+//! A `Scheduled` or `OneShot` run is *missed* when the daemon was down or the
+//! machine asleep at its fire time. Runs found less than the grace window late
+//! fire as normal; later ones are handled by the watcher's [`MisfirePolicy`].
+//! The last run of each watcher is kept in `watcher_last_run`, so misses across
+//! restarts are caught as well as those across system sleep.
+//!
+//! Wall-clock time comes from a [`Clock`] so tests can move it freely.
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::schedule::CronSchedule;
+
+/// Longest single sleep, so wall-clock jumps are noticed promptly
+pub const MAX_SLEEP_STEP: Duration = Duration::from_secs(60);
+
+/// Wall-clock drift past which a sleep is reported as a clock jump
+pub const CLOCK_JUMP_THRESHOLD: Duration = Duration::from_secs(30);
+
+/// Source of wall-clock time for the scheduler
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// The system clock
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// A clock for tests: follows tokio's (pausable) clock from a chosen start,
+/// and can jump ahead to simulate the machine sleeping.
+#[derive(Debug)]
+pub struct ManualClock {
+    /// Wall time at a tokio instant
+    base: Mutex<(DateTime<Utc>, tokio::time::Instant)>,
+}
+
+impl ManualClock {
+    pub fn new(now: DateTime<Utc>) -> Self {
+        Self {
+            base: Mutex::new((now, tokio::time::Instant::now())),
+        }
+    }
+
+    /// Move wall time forward without tokio time passing
+    pub fn jump(&self, by: chrono::Duration) {
+        let mut base = self.base.lock().unwrap_or_else(|e| e.into_inner());
+        base.0 += by;
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> DateTime<Utc> {
+        let (at, anchor) = *self.base.lock().unwrap_or_else(|e| e.into_inner());
+        at + chrono::Duration::from_std(anchor.elapsed()).unwrap_or_default()
+    }
+}
+
+/// What to do about missed runs
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MisfireAction {
+    /// Drop missed runs
+    Skip,
+    /// Run once for any number of missed runs
+    #[default]
+    RunOnce,
+    /// Run every missed occurrence, up to `max_runs`
+    RunAll,
+}
+
+/// How a time-based watcher handles runs missed while the daemon was down or
+/// the machine asleep
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MisfirePolicy {
+    pub action: MisfireAction,
+    /// Most missed runs replayed by [`MisfireAction::RunAll`] (the latest ones)
+    pub max_runs: u32,
+    /// Runs at most this many seconds late still count as on time
+    pub grace_secs: u64,
+}
+
+impl Default for MisfirePolicy {
+    fn default() -> Self {
+        Self {
+            action: MisfireAction::RunOnce,
+            max_runs: 5,
+            grace_secs: 300,
+        }
+    }
+}
+
+impl MisfirePolicy {
+    fn grace(&self) -> chrono::Duration {
+        chrono::Duration::seconds(self.grace_secs.min(i64::MAX as u64) as i64)
+    }
+
+    /// Whether a run due at `at` should still fire at `now`
+    pub fn fires_late(&self, at: DateTime<Utc>, now: DateTime<Utc>) -> bool {
+        self.action != MisfireAction::Skip || now - at <= self.grace()
+    }
+}
+
+impl std::fmt::Display for MisfireAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Skip => write!(f, "skip"),
+            Self::RunOnce => write!(f, "run once"),
+            Self::RunAll => write!(f, "run all"),
+        }
+    }
+}
+
+/// Occurrences of `schedule` to fire now, oldest first.
+///
+/// Considers occurrences after `last_run` up to and including `now`. The
+/// latest one fires if it is within the grace window; otherwise all of them
+/// are missed and the policy decides what runs.
+pub fn due_runs(
+    schedule: &CronSchedule,
+    last_run: DateTime<Utc>,
+    now: DateTime<Utc>,
+    policy: &MisfirePolicy,
+) -> Vec<DateTime<Utc>> {
+    let keep = match policy.action {
+        MisfireAction::RunAll => policy.max_runs.max(1) as usize,
+        _ => 1,
+    };
+    // Walk back from now
+    let mut runs: Vec<DateTime<Utc>> = schedule
+        .until(now)
+        .take_while(|at| *at > last_run)
+        .take(keep)
+        .collect();
+
+    match runs.first() {
+        Some(latest) if policy.fires_late(*latest, now) => {
+            runs.reverse();
+            runs
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Whether occurrences of `schedule` were due between `last_run` and `now`
+pub fn any_due(schedule: &CronSchedule, last_run: DateTime<Utc>, now: DateTime<Utc>) -> bool {
+    schedule.after(last_run).next().is_some_and(|at| at <= now)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schedule::Tz;
+    use chrono::TimeZone;
+
+    fn at(day: u32, hour: u32, min: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, day, hour, min, 0).unwrap()
+    }
+
+    fn daily_nine() -> CronSchedule {
+        CronSchedule::parse("0 0 9 * * *", Tz::UTC).unwrap()
+    }
+
+    fn policy(action: MisfireAction) -> MisfirePolicy {
+        MisfirePolicy {
+            action,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_on_time_run_fires_for_every_policy() {
+        for action in [
+            MisfireAction::Skip,
+            MisfireAction::RunOnce,
+            MisfireAction::RunAll,
+        ] {
+            // Exactly on time, and a little late (within grace)
+            assert_eq!(
+                due_runs(&daily_nine(), at(1, 9, 0), at(2, 9, 0), &policy(action)),
+                vec![at(2, 9, 0)]
+            );
+            assert_eq!(
+                due_runs(&daily_nine(), at(1, 9, 0), at(2, 9, 4), &policy(action)),
+                vec![at(2, 9, 0)]
+            );
+        }
+        // Nothing due yet
+        assert!(
+            due_runs(
+                &daily_nine(),
+                at(2, 9, 0),
+                at(2, 12, 0),
+                &MisfirePolicy::default()
+            )
+            .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_missed_runs_follow_policy() {
+        // Down from the 1st at 10:00 until the 4th at 12:00: missed 2nd, 3rd, 4th
+        let (last_run, now) = (at(1, 10, 0), at(4, 12, 0));
+        assert!(due_runs(&daily_nine(), last_run, now, &policy(MisfireAction::Skip)).is_empty());
+        assert_eq!(
+            due_runs(
+                &daily_nine(),
+                last_run,
+                now,
+                &policy(MisfireAction::RunOnce)
+            ),
+            vec![at(4, 9, 0)]
+        );
+
+        let all = MisfirePolicy {
+            action: MisfireAction::RunAll,
+            max_runs: 2,
+            ..Default::default()
+        };
+        assert_eq!(
+            due_runs(&daily_nine(), last_run, now, &all),
+            vec![at(3, 9, 0), at(4, 9, 0)]
+        );
+        let uncapped = MisfirePolicy {
+            max_runs: 10,
+            ..all
+        };
+        assert_eq!(due_runs(&daily_nine(), last_run, now, &uncapped).len(), 3);
+    }
+
+    #[test]
+    fn test_grace_window() {
+        let strict = MisfirePolicy {
+            action: MisfireAction::Skip,
+            grace_secs: 60,
+            ..Default::default()
+        };
+        assert!(due_runs(&daily_nine(), at(1, 10, 0), at(2, 9, 2), &strict).is_empty());
+        assert!(strict.fires_late(at(2, 9, 0), at(2, 9, 1)));
+        assert!(!strict.fires_late(at(2, 9, 0), at(2, 9, 2)));
+        assert!(policy(MisfireAction::RunOnce).fires_late(at(1, 9, 0), at(9, 9, 0)));
+    }
+
+    #[test]
+    fn test_policy_serde_defaults() {
+        let parsed: MisfirePolicy = serde_json::from_str(r#"{"action": "run_all"}"#).unwrap();
+        assert_eq!(parsed.action, MisfireAction::RunAll);
+        assert_eq!(parsed.max_runs, 5);
+        assert_eq!(parsed.grace_secs, 300);
+        assert!(serde_json::from_str::<MisfirePolicy>(r#"{"action": "later"}"#).is_err());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_manual_clock() {
+        let clock = ManualClock::new(at(1, 9, 0));
+        tokio::time::sleep(Duration::from_secs(90)).await;
+        assert_eq!(clock.now(), at(1, 9, 1) + chrono::Duration::seconds(30));
+        clock.jump(chrono::Duration::hours(2));
+        assert_eq!(clock.now(), at(1, 11, 1) + chrono::Duration::seconds(30));
+    }
+}
@@ -0,0 +1,353 @@
+//! Web page change detection
+//!
+//! Used by `PageWatch` watchers. A page is reduced to a normalized text
+//! snapshot (optionally just a CSS selector's matches or the text between two
+//! markers) so markup, script and whitespace churn don't count as changes, and
+//! successive snapshots are compared line by line into a readable diff.
+//!
+//! Fetching (of pages and of feeds) is delegated to a [`PageFetcher`] so the
+//! agent can supply one that enforces its SSRF policy.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use scraper::{ElementRef, Html, Selector};
+
+/// Longest snapshot kept, in lines
+const MAX_SNAPSHOT_LINES: usize = 2_000;
+
+/// Longest diff included in an event, in lines
+const MAX_DIFF_LINES: usize = 60;
+
+/// Elements whose text is never page content
+const SKIPPED_ELEMENTS: &[&str] = &["script", "style", "noscript", "template", "svg"];
+
+/// Largest page body read, in bytes
+const MAX_PAGE_BYTES: usize = 5 * 1024 * 1024;
+
+/// Fetches watched pages and feeds. Implementations decide which URLs are
+/// safe to fetch, including every hop of a redirect chain.
+#[async_trait]
+pub trait PageFetcher: Send + Sync {
+    /// GET `url` with extra request headers, following redirects, and return
+    /// the final response whatever its status (feeds need `304 Not Modified`)
+    async fn get(&self, url: &str, headers: &[(&str, &str)]) -> Result<reqwest::Response>;
+
+    /// The HTML of a page; fails on error statuses and oversized pages
+    async fn fetch(&self, url: &str) -> Result<String> {
+        let response = self.get(url, &[]).await?;
+        let status = response.status();
+        if !status.is_success() {
+            anyhow::bail!("Fetching {} failed with status: {}", url, status);
+        }
+        let body = read_body_limited(response, MAX_PAGE_BYTES)
+            .await
+            .with_context(|| format!("Failed to read page {}", url))?;
+        Ok(String::from_utf8_lossy(&body).into_owned())
+    }
+}
+
+/// Read a response body, failing as soon as it is known to exceed `max_bytes`
+/// rather than after buffering all of it
+pub(crate) async fn read_body_limited(
+    mut response: reqwest::Response,
+    max_bytes: usize,
+) -> Result<Vec<u8>> {
+    if let Some(length) = response.content_length()
+        && length > max_bytes as u64
+    {
+        anyhow::bail!("Body is too large ({} bytes, max {})", length, max_bytes);
+    }
+
+    let mut body = Vec::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .context("Failed to read response body")?
+    {
+        if body.len() + chunk.len() > max_bytes {
+            anyhow::bail!("Body is too large (over {} bytes)", max_bytes);
+        }
+        body.extend_from_slice(&chunk);
+    }
+    Ok(body)
+}
+
+/// Which part of a page to watch
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PageRegion<'a> {
+    /// CSS selector; every match contributes its text
+    pub selector: Option<&'a str>,
+    /// Start after the first line containing this text
+    pub region_start: Option<&'a str>,
+    /// Stop before the first following line containing this text
+    pub region_end: Option<&'a str>,
+}
+
+/// Reduce `html` to the watched text, one visible text run per line
+pub fn normalize_page(html: &str, region: PageRegion) -> Result<String> {
+    let document = Html::parse_document(html);
+    let mut lines = Vec::new();
+
+    match region.selector {
+        Some(selector) => {
+            let parsed = Selector::parse(selector)
+                .map_err(|e| anyhow::anyhow!("Invalid CSS selector '{}': {}", selector, e))?;
+            for element in document.select(&parsed) {
+                collect_lines(element, &mut lines);
+            }
+            if lines.is_empty() {
+                anyhow::bail!("Selector '{}' matched no text", selector);
+            }
+        }
+        None => {
+            let body = Selector::parse("body").expect("valid selector");
+            let root = document
+                .select(&body)
+                .next()
+                .unwrap_or_else(|| document.root_element());
+            collect_lines(root, &mut lines);
+        }
+    }
+
+    let lines = select_region(lines, region.region_start, region.region_end)?;
+    Ok(lines
+        .into_iter()
+        .take(MAX_SNAPSHOT_LINES)
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
+
+fn collect_lines(element: ElementRef, lines: &mut Vec<String>) {
+    for node in element.descendants() {
+        let Some(text) = node.value().as_text() else {
+            continue;
+        };
+        let hidden = node.ancestors().any(|a| {
+            a.value()
+                .as_element()
+                .is_some_and(|e| SKIPPED_ELEMENTS.contains(&e.name()))
+        });
+        if hidden {
+            continue;
+        }
+        let line = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        if !line.is_empty() {
+            lines.push(line);
+        }
+    }
+}
+
+fn select_region(
+    lines: Vec<String>,
+    start: Option<&str>,
+    end: Option<&str>,
+) -> Result<Vec<String>> {
+    let from = match start {
+        Some(marker) => {
+            lines
+                .iter()
+                .position(|l| l.contains(marker))
+                .with_context(|| format!("Region start '{}' not found on page", marker))?
+                + 1
+        }
+        None => 0,
+    };
+    let rest = &lines[from..];
+    let to = match end {
+        Some(marker) => rest
+            .iter()
+            .position(|l| l.contains(marker))
+            .unwrap_or(rest.len()),
+        None => rest.len(),
+    };
+    Ok(rest[..to].to_vec())
+}
+
+/// Line-level difference between two snapshots
+#[derive(Debug, Clone, PartialEq)]
+pub struct PageDiff {
+    pub added: usize,
+    pub removed: usize,
+    /// Changed lines relative to the size of both snapshots (0.0–1.0)
+    pub change_ratio: f64,
+    /// `- old` / `+ new` lines, truncated
+    pub text: String,
+}
+
+/// Compare two snapshots; `None` if they are identical
+pub fn diff_snapshots(old: &str, new: &str) -> Option<PageDiff> {
+    if old == new {
+        return None;
+    }
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+
+    // Longest common subsequence table, filled from the end
+    let (n, m) = (old.len(), new.len());
+    let mut lcs = vec![vec![0u32; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut lines = Vec::new();
+    let (mut added, mut removed) = (0, 0);
+    let (mut i, mut j) = (0, 0);
+    while i < n || j < m {
+        if i < n && j < m && old[i] == new[j] {
+            i += 1;
+            j += 1;
+        } else if i < n && (j == m || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            lines.push(format!("- {}", old[i]));
+            removed += 1;
+            i += 1;
+        } else {
+            lines.push(format!("+ {}", new[j]));
+            added += 1;
+            j += 1;
+        }
+    }
+
+    if lines.len() > MAX_DIFF_LINES {
+        let omitted = lines.len() - MAX_DIFF_LINES;
+        lines.truncate(MAX_DIFF_LINES);
+        lines.push(format!("... ({} more changed lines)", omitted));
+    }
+
+    Some(PageDiff {
+        added,
+        removed,
+        change_ratio: (added + removed) as f64 / (n + m).max(1) as f64,
+        text: lines.join("\n"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Fetches over plain HTTP, for local fixtures
+    struct DirectFetcher;
+
+    #[async_trait]
+    impl PageFetcher for DirectFetcher {
+        async fn get(&self, url: &str, _headers: &[(&str, &str)]) -> Result<reqwest::Response> {
+            Ok(reqwest::get(url).await?)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fetch_rejects_oversized_body_without_content_length() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        // Streams 6 MB and closes the connection, announcing no length
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 1024];
+            let _ = socket.read(&mut buf).await;
+            let _ = socket
+                .write_all(
+                    b"HTTP/1.1 200 OK\r\nContent-Type: text/html\r\nConnection: close\r\n\r\n",
+                )
+                .await;
+            let chunk = vec![b'x'; 64 * 1024];
+            for _ in 0..96 {
+                if socket.write_all(&chunk).await.is_err() {
+                    break;
+                }
+            }
+            let _ = socket.shutdown().await;
+        });
+
+        let err = DirectFetcher.fetch(&url).await.unwrap_err();
+        assert!(format!("{:#}", err).contains("too large"), "{:#}", err);
+    }
+
+    const PAGE: &str = r#"<html><head><title>Shop</title><style>.x { color: red }</style></head>
+<body>
+  <nav>Home | Products</nav>
+  <div class="product">
+    <h1>Espresso Machine</h1>
+    <span class="price">  $499.00 </span>
+    <script>trackView("abc123")</script>
+  </div>
+  <footer>Rendered at 10:42:01</footer>
+</body></html>"#;
+
+    #[test]
+    fn test_normalize_whole_page_skips_scripts() {
+        let text = normalize_page(PAGE, PageRegion::default()).unwrap();
+        assert_eq!(
+            text,
+            "Home | Products\nEspresso Machine\n$499.00\nRendered at 10:42:01"
+        );
+    }
+
+    #[test]
+    fn test_normalize_with_selector() {
+        let region = PageRegion {
+            selector: Some(".product"),
+            ..Default::default()
+        };
+        assert_eq!(
+            normalize_page(PAGE, region).unwrap(),
+            "Espresso Machine\n$499.00"
+        );
+
+        let missing = PageRegion {
+            selector: Some(".nope"),
+            ..Default::default()
+        };
+        assert!(normalize_page(PAGE, missing).is_err());
+        let invalid = PageRegion {
+            selector: Some("[[["),
+            ..Default::default()
+        };
+        assert!(normalize_page(PAGE, invalid).is_err());
+    }
+
+    #[test]
+    fn test_normalize_with_text_region() {
+        let region = PageRegion {
+            region_start: Some("Espresso"),
+            region_end: Some("Rendered at"),
+            ..Default::default()
+        };
+        assert_eq!(normalize_page(PAGE, region).unwrap(), "$499.00");
+
+        let missing = PageRegion {
+            region_start: Some("Grinder"),
+            ..Default::default()
+        };
+        assert!(normalize_page(PAGE, missing).is_err());
+    }
+
+    #[test]
+    fn test_diff_snapshots() {
+        assert!(diff_snapshots("a\nb", "a\nb").is_none());
+
+        let diff = diff_snapshots("Title\n$499.00\nIn stock", "Title\n$449.00\nIn stock").unwrap();
+        assert_eq!(diff.text, "- $499.00\n+ $449.00");
+        assert_eq!((diff.added, diff.removed), (1, 1));
+        assert!((diff.change_ratio - 2.0 / 6.0).abs() < 1e-9);
+
+        let appended = diff_snapshots("v1.0", "v1.1\nv1.0").unwrap();
+        assert_eq!(appended.text, "+ v1.1");
+        assert_eq!(appended.removed, 0);
+    }
+
+    #[test]
+    fn test_diff_is_truncated() {
+        let new: Vec<String> = (0..100).map(|i| format!("line {}", i)).collect();
+        let diff = diff_snapshots("", &new.join("\n")).unwrap();
+        assert_eq!(diff.added, 100);
+        assert!(diff.text.ends_with("... (40 more changed lines)"));
+    }
+}
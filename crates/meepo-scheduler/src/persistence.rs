@@ -3,7 +3,11 @@
 //! This module handles saving and loading watchers from SQLite,
 //! reusing the same database connection as the knowledge graph.
 
-use crate::watcher::Watcher;
+use crate::feed::{FeedItem, FeedValidators};
+use crate::gate::EventRules;
+use crate::health::{DeadLetter, HealthStatus, WatcherHealth};
+use crate::watcher::{Watcher, WatcherEvent, WatcherKind};
+use crate::workflow_runs::WorkflowRun;
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
 use rusqlite::{Connection, params};
@@ -16,8 +20,8 @@ use tracing::{debug, info, warn};
 pub fn init_watcher_tables(conn: &Connection) -> Result<()> {
     debug!("Initializing watcher tables");
 
-    // Use scheduler_watchers to avoid collision with meepo-knowledge's watchers table
-    // (both crates share the same SQLite file)
+    // Named scheduler_watchers because older versions kept a second `watchers`
+    // table for the knowledge graph in the same SQLite file; see migrate_legacy_watchers
     conn.execute(
         "CREATE TABLE IF NOT EXISTS scheduler_watchers (
             id TEXT PRIMARY KEY,
@@ -31,6 +35,12 @@ pub fn init_watcher_tables(conn: &Connection) -> Result<()> {
     )
     .context("Failed to create scheduler_watchers table")?;
 
+    // Migration: per-watcher event filter and throttle rules
+    let _ = conn.execute(
+        "ALTER TABLE scheduler_watchers ADD COLUMN rules_json TEXT",
+        [],
+    );
+
     // Index for querying active watchers
     conn.execute(
         "CREATE INDEX IF NOT EXISTS idx_sched_watchers_active ON scheduler_watchers(active)",
@@ -64,10 +74,285 @@ pub fn init_watcher_tables(conn: &Connection) -> Result<()> {
     )
     .context("Failed to create watcher_events timestamp index")?;
 
+    // Feed watchers: cache validators for conditional GET, and every item seen
+    // so new entries are reported once and content digests can read them back
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS feed_state (
+            watcher_id TEXT PRIMARY KEY,
+            feed_url TEXT NOT NULL,
+            etag TEXT,
+            last_modified TEXT,
+            checked_at TEXT NOT NULL
+        )",
+        [],
+    )
+    .context("Failed to create feed_state table")?;
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS feed_items (
+            watcher_id TEXT NOT NULL,
+            guid TEXT NOT NULL,
+            feed_url TEXT NOT NULL,
+            title TEXT NOT NULL,
+            link TEXT,
+            summary TEXT,
+            published_at TEXT,
+            collected_at TEXT NOT NULL,
+            PRIMARY KEY (watcher_id, guid)
+        )",
+        [],
+    )
+    .context("Failed to create feed_items table")?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_feed_items_url ON feed_items(feed_url, collected_at)",
+        [],
+    )
+    .context("Failed to create feed_items index")?;
+
+    // Page watchers: last normalized snapshot of the watched content
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS page_snapshots (
+            watcher_id TEXT PRIMARY KEY,
+            url TEXT NOT NULL,
+            content TEXT NOT NULL,
+            checked_at TEXT NOT NULL,
+            changed_at TEXT NOT NULL
+        )",
+        [],
+    )
+    .context("Failed to create page_snapshots table")?;
+
+    // Polling watchers: dedup keys of items already reported, and the last
+    // position reached in sources that have one (GitHub event IDs), so
+    // restarts don't re-report anything
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS watcher_seen_items (
+            watcher_id TEXT NOT NULL,
+            item_key TEXT NOT NULL,
+            first_seen_at TEXT NOT NULL,
+            last_seen_at TEXT NOT NULL,
+            PRIMARY KEY (watcher_id, item_key)
+        )",
+        [],
+    )
+    .context("Failed to create watcher_seen_items table")?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_watcher_seen_items_last_seen
+         ON watcher_seen_items(last_seen_at)",
+        [],
+    )
+    .context("Failed to create watcher_seen_items index")?;
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS watcher_cursors (
+            watcher_id TEXT PRIMARY KEY,
+            cursor TEXT NOT NULL,
+            updated_at TEXT NOT NULL
+        )",
+        [],
+    )
+    .context("Failed to create watcher_cursors table")?;
+
+    // Webhook watchers: delivery IDs already accepted, so a captured request
+    // can't be replayed after a restart
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS webhook_deliveries (
+            watcher_id TEXT NOT NULL,
+            delivery_id TEXT NOT NULL,
+            received_at TEXT NOT NULL,
+            PRIMARY KEY (watcher_id, delivery_id)
+        )",
+        [],
+    )
+    .context("Failed to create webhook_deliveries table")?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_received
+         ON webhook_deliveries(received_at)",
+        [],
+    )
+    .context("Failed to create webhook_deliveries index")?;
+
+    // Scheduled and one-shot watchers: last run, for catching up missed runs
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS watcher_last_run (
+            watcher_id TEXT PRIMARY KEY,
+            last_run_at TEXT NOT NULL,
+            consecutive_errors INTEGER NOT NULL DEFAULT 0
+        )",
+        [],
+    )
+    .context("Failed to create watcher_last_run table")?;
+
+    // Watcher health: success/error streaks and next run, for status reporting
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS watcher_health (
+            watcher_id TEXT PRIMARY KEY,
+            last_success_at TEXT,
+            last_error TEXT,
+            last_error_at TEXT,
+            consecutive_errors INTEGER NOT NULL DEFAULT 0,
+            next_run_at TEXT,
+            disabled_at TEXT
+        )",
+        [],
+    )
+    .context("Failed to create watcher_health table")?;
+
+    // Failed polls and undelivered events
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS watcher_dead_letters (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            watcher_id TEXT NOT NULL,
+            event_json TEXT,
+            error TEXT NOT NULL,
+            failed_at TEXT NOT NULL
+        )",
+        [],
+    )
+    .context("Failed to create watcher_dead_letters table")?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_watcher_dead_letters_watcher
+         ON watcher_dead_letters(watcher_id)",
+        [],
+    )
+    .context("Failed to create watcher_dead_letters index")?;
+
+    // Workflow run history
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS workflow_runs (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            workflow TEXT NOT NULL,
+            status TEXT NOT NULL,
+            event_json TEXT,
+            steps_json TEXT NOT NULL,
+            error TEXT,
+            started_at TEXT NOT NULL,
+            finished_at TEXT NOT NULL
+        )",
+        [],
+    )
+    .context("Failed to create workflow_runs table")?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_workflow_runs_workflow
+         ON workflow_runs(workflow)",
+        [],
+    )
+    .context("Failed to create workflow_runs index")?;
+
+    migrate_legacy_watchers(conn)?;
+
     info!("Watcher tables initialized successfully");
     Ok(())
 }
 
+/// Merge the knowledge graph's old `watchers` table into `scheduler_watchers`
+///
+/// Older versions stored every watcher twice: the agent's tools wrote a kind
+/// name and config JSON to `watchers`, the daemon a `WatcherKind` to
+/// `scheduler_watchers`. Rows only in the old table are converted and added;
+/// a watcher in both stays active only if both copies were. The old table is
+/// then renamed to `legacy_watchers`, so this runs once. Returns the number of
+/// rows merged.
+pub fn migrate_legacy_watchers(conn: &Connection) -> Result<usize> {
+    let table_exists = |name: &str| -> Result<bool> {
+        Ok(conn.query_row(
+            "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = ?1",
+            params![name],
+            |row| row.get::<_, i64>(0),
+        )? > 0)
+    };
+    if !table_exists("watchers")? {
+        return Ok(0);
+    }
+
+    let tx = conn
+        .unchecked_transaction()
+        .context("Failed to start watcher migration")?;
+
+    let rows = {
+        let mut stmt = tx
+            .prepare(
+                "SELECT id, kind, config, action, reply_channel, active, created_at FROM watchers",
+            )
+            .context("Failed to read legacy watchers")?;
+        stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, String>(4)?,
+                row.get::<_, i32>(5)? != 0,
+                row.get::<_, String>(6)?,
+            ))
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .context("Failed to read legacy watchers")?
+    };
+
+    let mut merged = 0;
+    for (id, kind, config, action, reply_channel, active, created_at) in rows {
+        if let Some(existing) = get_watcher_by_id(&tx, &id)? {
+            if existing.active && !active {
+                deactivate_watcher(&tx, &id)?;
+            }
+            merged += 1;
+            continue;
+        }
+
+        let watcher = serde_json::from_str(&config)
+            .map_err(anyhow::Error::from)
+            .and_then(|config| legacy_watcher(&kind, config));
+        let (kind, rules) = match watcher {
+            Ok(watcher) => watcher,
+            Err(e) => {
+                warn!("Not migrating legacy watcher {} ({}): {:#}", id, kind, e);
+                continue;
+            }
+        };
+        let created_at = DateTime::parse_from_rfc3339(&created_at)
+            .map(|dt| dt.with_timezone(&Utc))
+            .unwrap_or_else(|_| Utc::now());
+        save_watcher(
+            &tx,
+            &Watcher {
+                id,
+                kind,
+                action,
+                reply_channel,
+                active,
+                created_at,
+                rules,
+            },
+        )?;
+        merged += 1;
+    }
+
+    if table_exists("legacy_watchers")? {
+        tx.execute("DROP TABLE watchers", [])?;
+    } else {
+        tx.execute("ALTER TABLE watchers RENAME TO legacy_watchers", [])?;
+    }
+    tx.commit().context("Failed to commit watcher migration")?;
+
+    info!("Migrated {} legacy watchers", merged);
+    Ok(merged)
+}
+
+/// A legacy watcher's kind and the event rules that travelled in its config
+fn legacy_watcher(kind: &str, mut config: serde_json::Value) -> Result<(WatcherKind, EventRules)> {
+    let rules = match config.as_object_mut().and_then(|map| map.remove("rules")) {
+        Some(rules) => serde_json::from_value(rules).context("Invalid event rules")?,
+        None => EventRules::default(),
+    };
+    Ok((WatcherKind::from_config(kind, config)?, rules))
+}
+
 /// Save a watcher to the database
 ///
 /// If a watcher with the same ID exists, it will be updated.
@@ -76,16 +361,23 @@ pub fn save_watcher(conn: &Connection, watcher: &Watcher) -> Result<()> {
     let kind_json =
         serde_json::to_string(&watcher.kind).context("Failed to serialize watcher kind")?;
 
+    let rules_json = if watcher.rules.is_empty() {
+        None
+    } else {
+        Some(serde_json::to_string(&watcher.rules).context("Failed to serialize watcher rules")?)
+    };
+
     let created_at = watcher.created_at.to_rfc3339();
 
     conn.execute(
-        "INSERT INTO scheduler_watchers (id, kind_json, action, reply_channel, active, created_at)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+        "INSERT INTO scheduler_watchers (id, kind_json, action, reply_channel, active, created_at, rules_json)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
          ON CONFLICT(id) DO UPDATE SET
             kind_json = excluded.kind_json,
             action = excluded.action,
             reply_channel = excluded.reply_channel,
-            active = excluded.active",
+            active = excluded.active,
+            rules_json = excluded.rules_json",
         params![
             &watcher.id,
             &kind_json,
@@ -93,6 +385,7 @@ pub fn save_watcher(conn: &Connection, watcher: &Watcher) -> Result<()> {
             &watcher.reply_channel,
             watcher.active as i32,
             &created_at,
+            &rules_json,
         ],
     )
     .context("Failed to save watcher")?;
@@ -104,7 +397,7 @@ pub fn save_watcher(conn: &Connection, watcher: &Watcher) -> Result<()> {
 /// Get all active watchers from the database
 pub fn get_active_watchers(conn: &Connection) -> Result<Vec<Watcher>> {
     let mut stmt = conn
-        .prepare("SELECT id, kind_json, action, reply_channel, active, created_at FROM scheduler_watchers WHERE active = 1")
+        .prepare("SELECT id, kind_json, action, reply_channel, active, created_at, rules_json FROM scheduler_watchers WHERE active = 1 ORDER BY created_at")
         .context("Failed to prepare query for active watchers")?;
 
     let watchers: Vec<Watcher> = stmt
@@ -115,12 +408,21 @@ pub fn get_active_watchers(conn: &Connection) -> Result<Vec<Watcher>> {
             let reply_channel: String = row.get(3)?;
             let active: i32 = row.get(4)?;
             let created_at_str: String = row.get(5)?;
+            let rules_json: Option<String> = row.get(6)?;
 
-            Ok((id, kind_json, action, reply_channel, active, created_at_str))
+            Ok((
+                id,
+                kind_json,
+                action,
+                reply_channel,
+                active,
+                created_at_str,
+                rules_json,
+            ))
         })
         .context("Failed to query active watchers")?
         .filter_map(|result| match result {
-            Ok((id, kind_json, action, reply_channel, active, created_at_str)) => {
+            Ok((id, kind_json, action, reply_channel, active, created_at_str, rules_json)) => {
                 let kind = match serde_json::from_str(&kind_json) {
                     Ok(k) => k,
                     Err(e) => {
@@ -129,6 +431,14 @@ pub fn get_active_watchers(conn: &Connection) -> Result<Vec<Watcher>> {
                     }
                 };
 
+                let rules = match rules_json.as_deref().map(serde_json::from_str).transpose() {
+                    Ok(rules) => rules.unwrap_or_default(),
+                    Err(e) => {
+                        warn!("Failed to deserialize watcher rules for {}: {}", id, e);
+                        return None;
+                    }
+                };
+
                 let created_at = match DateTime::parse_from_rfc3339(&created_at_str) {
                     Ok(dt) => dt.with_timezone(&Utc),
                     Err(e) => {
@@ -144,6 +454,7 @@ pub fn get_active_watchers(conn: &Connection) -> Result<Vec<Watcher>> {
                     reply_channel,
                     active: active != 0,
                     created_at,
+                    rules,
                 })
             }
             Err(e) => {
@@ -160,7 +471,7 @@ pub fn get_active_watchers(conn: &Connection) -> Result<Vec<Watcher>> {
 /// Get a specific watcher by ID
 pub fn get_watcher_by_id(conn: &Connection, id: &str) -> Result<Option<Watcher>> {
     let mut stmt = conn
-        .prepare("SELECT id, kind_json, action, reply_channel, active, created_at FROM scheduler_watchers WHERE id = ?1")
+        .prepare("SELECT id, kind_json, action, reply_channel, active, created_at, rules_json FROM scheduler_watchers WHERE id = ?1")
         .context("Failed to prepare query for watcher by ID")?;
 
     let result = stmt.query_row(params![id], |row| {
@@ -170,14 +481,29 @@ pub fn get_watcher_by_id(conn: &Connection, id: &str) -> Result<Option<Watcher>>
         let reply_channel: String = row.get(3)?;
         let active: i32 = row.get(4)?;
         let created_at_str: String = row.get(5)?;
-
-        Ok((id, kind_json, action, reply_channel, active, created_at_str))
+        let rules_json: Option<String> = row.get(6)?;
+
+        Ok((
+            id,
+            kind_json,
+            action,
+            reply_channel,
+            active,
+            created_at_str,
+            rules_json,
+        ))
     });
 
     match result {
-        Ok((id, kind_json, action, reply_channel, active, created_at_str)) => {
+        Ok((id, kind_json, action, reply_channel, active, created_at_str, rules_json)) => {
             let kind =
                 serde_json::from_str(&kind_json).context("Failed to deserialize watcher kind")?;
+            let rules = rules_json
+                .as_deref()
+                .map(serde_json::from_str)
+                .transpose()
+                .context("Failed to deserialize watcher rules")?
+                .unwrap_or_default();
 
             let created_at = DateTime::parse_from_rfc3339(&created_at_str)
                 .context("Failed to parse created_at")?
@@ -190,6 +516,7 @@ pub fn get_watcher_by_id(conn: &Connection, id: &str) -> Result<Option<Watcher>>
                 reply_channel,
                 active: active != 0,
                 created_at,
+                rules,
             }))
         }
         Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
@@ -199,8 +526,8 @@ pub fn get_watcher_by_id(conn: &Connection, id: &str) -> Result<Option<Watcher>>
 
 /// Deactivate a watcher (set active = false)
 ///
-/// This doesn't delete the watcher, just marks it as inactive.
-/// The watcher runner should stop running it.
+/// This doesn't delete the watcher, just marks it as inactive and drops the
+/// feed items it collected. The watcher runner should stop running it.
 pub fn deactivate_watcher(conn: &Connection, id: &str) -> Result<bool> {
     let rows_affected = conn
         .execute(
@@ -210,6 +537,7 @@ pub fn deactivate_watcher(conn: &Connection, id: &str) -> Result<bool> {
         .context("Failed to deactivate watcher")?;
 
     if rows_affected > 0 {
+        delete_feed_items(conn, id)?;
         info!("Deactivated watcher: {}", id);
         Ok(true)
     } else {
@@ -220,13 +548,15 @@ pub fn deactivate_watcher(conn: &Connection, id: &str) -> Result<bool> {
 
 /// Permanently delete a watcher from the database
 ///
-/// This also deletes all associated events due to the CASCADE constraint.
+/// This also deletes all associated events due to the CASCADE constraint,
+/// and the feed items the watcher collected.
 pub fn delete_watcher(conn: &Connection, id: &str) -> Result<bool> {
     let rows_affected = conn
         .execute("DELETE FROM scheduler_watchers WHERE id = ?1", params![id])
         .context("Failed to delete watcher")?;
 
     if rows_affected > 0 {
+        delete_feed_items(conn, id)?;
         info!("Deleted watcher: {}", id);
         Ok(true)
     } else {
@@ -316,7 +646,13 @@ pub fn get_watcher_events(
 /// Used for catch-up mechanism (OpenClaw #10403) — when the daemon restarts,
 /// it can check if any cron jobs were missed and run them.
 pub fn record_last_run(conn: &Connection, watcher_id: &str) -> Result<()> {
-    let now = Utc::now().to_rfc3339();
+    record_run_at(conn, watcher_id, Utc::now())
+}
+
+/// Record a cron or one-shot watcher as having run (or passed over its due
+/// runs) up to `at`
+pub fn record_run_at(conn: &Connection, watcher_id: &str, at: DateTime<Utc>) -> Result<()> {
+    let now = at.to_rfc3339();
 
     // Create the table if it doesn't exist (idempotent)
     conn.execute(
@@ -343,55 +679,287 @@ pub fn record_last_run(conn: &Connection, watcher_id: &str) -> Result<()> {
     Ok(())
 }
 
-/// Record an error for a cron watcher (increments consecutive error count).
-/// After `max_errors` consecutive errors, the watcher is automatically deactivated.
-pub fn record_run_error(conn: &Connection, watcher_id: &str, max_errors: u32) -> Result<bool> {
-    // Create the table if it doesn't exist (idempotent)
+/// Record a successful run, clearing the watcher's error streak
+pub fn record_watcher_success(
+    conn: &Connection,
+    watcher_id: &str,
+    at: DateTime<Utc>,
+) -> Result<()> {
     conn.execute(
-        "CREATE TABLE IF NOT EXISTS watcher_last_run (
-            watcher_id TEXT PRIMARY KEY,
-            last_run_at TEXT NOT NULL,
-            consecutive_errors INTEGER NOT NULL DEFAULT 0
-        )",
-        [],
+        "INSERT INTO watcher_health (watcher_id, last_success_at, consecutive_errors)
+         VALUES (?1, ?2, 0)
+         ON CONFLICT(watcher_id) DO UPDATE SET
+            last_success_at = excluded.last_success_at,
+            consecutive_errors = 0,
+            disabled_at = NULL",
+        params![watcher_id, at.to_rfc3339()],
     )
-    .context("Failed to create watcher_last_run table")?;
+    .context("Failed to record watcher success")?;
+    Ok(())
+}
 
-    let now = Utc::now().to_rfc3339();
+/// Record a failed run and return the number of consecutive failures
+pub fn record_watcher_failure(
+    conn: &Connection,
+    watcher_id: &str,
+    error: &str,
+    at: DateTime<Utc>,
+) -> Result<u32> {
+    conn.query_row(
+        "INSERT INTO watcher_health (watcher_id, last_error, last_error_at, consecutive_errors)
+         VALUES (?1, ?2, ?3, 1)
+         ON CONFLICT(watcher_id) DO UPDATE SET
+            last_error = excluded.last_error,
+            last_error_at = excluded.last_error_at,
+            consecutive_errors = consecutive_errors + 1
+         RETURNING consecutive_errors",
+        params![watcher_id, error, at.to_rfc3339()],
+        |row| row.get(0),
+    )
+    .context("Failed to record watcher failure")
+}
+
+/// Record when a watcher will next run (`None` when it will not)
+pub fn set_watcher_next_run(
+    conn: &Connection,
+    watcher_id: &str,
+    next_run: Option<DateTime<Utc>>,
+) -> Result<()> {
+    conn.execute(
+        "INSERT INTO watcher_health (watcher_id, next_run_at) VALUES (?1, ?2)
+         ON CONFLICT(watcher_id) DO UPDATE SET next_run_at = excluded.next_run_at",
+        params![watcher_id, next_run.map(|at| at.to_rfc3339())],
+    )
+    .context("Failed to record watcher next run")?;
+    Ok(())
+}
 
+/// Deactivate a watcher that keeps failing, marking it as disabled rather
+/// than cancelled
+pub fn disable_watcher(conn: &Connection, watcher_id: &str, at: DateTime<Utc>) -> Result<()> {
+    deactivate_watcher(conn, watcher_id)?;
     conn.execute(
-        "INSERT INTO watcher_last_run (watcher_id, last_run_at, consecutive_errors)
-         VALUES (?1, ?2, 1)
+        "INSERT INTO watcher_health (watcher_id, disabled_at, next_run_at) VALUES (?1, ?2, NULL)
          ON CONFLICT(watcher_id) DO UPDATE SET
-            last_run_at = excluded.last_run_at,
-            consecutive_errors = consecutive_errors + 1",
-        params![watcher_id, &now],
+            disabled_at = excluded.disabled_at,
+            next_run_at = NULL",
+        params![watcher_id, at.to_rfc3339()],
     )
-    .context("Failed to record run error")?;
+    .context("Failed to mark watcher disabled")?;
+    warn!("Watcher {} disabled after repeated failures", watcher_id);
+    Ok(())
+}
 
-    // Check if we've exceeded max errors
-    let errors: u32 = conn
-        .query_row(
-            "SELECT consecutive_errors FROM watcher_last_run WHERE watcher_id = ?1",
-            params![watcher_id],
-            |row| row.get(0),
-        )
-        .context("Failed to query consecutive errors")?;
+const HEALTH_COLUMNS: &str = "w.id, w.active, h.last_success_at, h.last_error, h.last_error_at,
+    COALESCE(h.consecutive_errors, 0), h.next_run_at, h.disabled_at";
+
+fn health_from_row(row: &rusqlite::Row) -> rusqlite::Result<WatcherHealth> {
+    let active: bool = row.get(1)?;
+    let consecutive_errors: u32 = row.get(5)?;
+    let disabled: Option<String> = row.get(7)?;
+    let last_success = parse_optional_time(row.get(2)?);
+    let status = if !active && disabled.is_some() {
+        HealthStatus::Disabled
+    } else if consecutive_errors > 0 {
+        HealthStatus::Failing
+    } else if last_success.is_some() {
+        HealthStatus::Ok
+    } else {
+        HealthStatus::Pending
+    };
+    Ok(WatcherHealth {
+        watcher_id: row.get(0)?,
+        status,
+        last_success,
+        last_error: row.get(3)?,
+        last_error_at: parse_optional_time(row.get(4)?),
+        consecutive_errors,
+        next_run: parse_optional_time(row.get(6)?),
+    })
+}
 
-    if errors >= max_errors {
-        warn!(
-            "Watcher {} has {} consecutive errors (max {}), deactivating",
-            watcher_id, errors, max_errors
-        );
-        deactivate_watcher(conn, watcher_id)?;
-        return Ok(true); // deactivated
-    }
+fn parse_optional_time(value: Option<String>) -> Option<DateTime<Utc>> {
+    value
+        .and_then(|ts| DateTime::parse_from_rfc3339(&ts).ok())
+        .map(|dt| dt.with_timezone(&Utc))
+}
 
-    debug!(
-        "Watcher {} error count: {}/{}",
-        watcher_id, errors, max_errors
+/// Health of one watcher
+pub fn get_watcher_health(conn: &Connection, watcher_id: &str) -> Result<Option<WatcherHealth>> {
+    let result = conn.query_row(
+        &format!(
+            "SELECT {HEALTH_COLUMNS} FROM scheduler_watchers w
+             LEFT JOIN watcher_health h ON h.watcher_id = w.id
+             WHERE w.id = ?1"
+        ),
+        params![watcher_id],
+        health_from_row,
     );
-    Ok(false) // still active
+    match result {
+        Ok(health) => Ok(Some(health)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e).context("Failed to query watcher health"),
+    }
+}
+
+/// Health of active and auto-disabled watchers, oldest watcher first
+pub fn list_watcher_health(conn: &Connection) -> Result<Vec<WatcherHealth>> {
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {HEALTH_COLUMNS} FROM scheduler_watchers w
+             LEFT JOIN watcher_health h ON h.watcher_id = w.id
+             WHERE w.active = 1 OR h.disabled_at IS NOT NULL
+             ORDER BY w.created_at"
+        ))
+        .context("Failed to prepare watcher health query")?;
+    let health = stmt
+        .query_map([], health_from_row)
+        .context("Failed to query watcher health")?
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .context("Failed to read watcher health")?;
+    Ok(health)
+}
+
+/// Keep a failure, and the event that could not be delivered if any
+pub fn record_dead_letter(
+    conn: &Connection,
+    watcher_id: &str,
+    event: Option<&WatcherEvent>,
+    error: &str,
+    at: DateTime<Utc>,
+) -> Result<()> {
+    let event_json = event
+        .map(serde_json::to_string)
+        .transpose()
+        .context("Failed to serialize dead-lettered event")?;
+    conn.execute(
+        "INSERT INTO watcher_dead_letters (watcher_id, event_json, error, failed_at)
+         VALUES (?1, ?2, ?3, ?4)",
+        params![watcher_id, event_json, error, at.to_rfc3339()],
+    )
+    .context("Failed to record dead letter")?;
+    Ok(())
+}
+
+/// Most recent dead letters, newest first, optionally for one watcher
+pub fn get_dead_letters(
+    conn: &Connection,
+    watcher_id: Option<&str>,
+    limit: usize,
+) -> Result<Vec<DeadLetter>> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, watcher_id, event_json, error, failed_at FROM watcher_dead_letters
+             WHERE ?1 IS NULL OR watcher_id = ?1
+             ORDER BY id DESC LIMIT ?2",
+        )
+        .context("Failed to prepare dead letter query")?;
+    let rows = stmt
+        .query_map(params![watcher_id, limit as i64], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, Option<String>>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, String>(4)?,
+            ))
+        })
+        .context("Failed to query dead letters")?;
+
+    let mut letters = Vec::new();
+    for row in rows {
+        let (id, watcher_id, event_json, error, failed_at) =
+            row.context("Failed to read dead letter")?;
+        letters.push(DeadLetter {
+            id,
+            watcher_id,
+            event: event_json.and_then(|json| serde_json::from_str(&json).ok()),
+            error,
+            failed_at: DateTime::parse_from_rfc3339(&failed_at)
+                .context("Failed to parse failed_at")?
+                .with_timezone(&Utc),
+        });
+    }
+    Ok(letters)
+}
+
+/// Keep a finished workflow run, returning its id
+pub fn record_workflow_run(conn: &Connection, run: &WorkflowRun) -> Result<i64> {
+    let event_json = run
+        .event
+        .as_ref()
+        .map(serde_json::to_string)
+        .transpose()
+        .context("Failed to serialize workflow event")?;
+    let steps_json =
+        serde_json::to_string(&run.steps).context("Failed to serialize workflow steps")?;
+    conn.execute(
+        "INSERT INTO workflow_runs
+         (workflow, status, event_json, steps_json, error, started_at, finished_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![
+            run.workflow,
+            run.status.to_string(),
+            event_json,
+            steps_json,
+            run.error,
+            run.started_at.to_rfc3339(),
+            run.finished_at.to_rfc3339(),
+        ],
+    )
+    .context("Failed to record workflow run")?;
+    Ok(conn.last_insert_rowid())
+}
+
+/// Most recent workflow runs, newest first, optionally for one workflow
+pub fn get_workflow_runs(
+    conn: &Connection,
+    workflow: Option<&str>,
+    limit: usize,
+) -> Result<Vec<WorkflowRun>> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, workflow, status, event_json, steps_json, error, started_at, finished_at
+             FROM workflow_runs
+             WHERE ?1 IS NULL OR workflow = ?1
+             ORDER BY id DESC LIMIT ?2",
+        )
+        .context("Failed to prepare workflow run query")?;
+    let rows = stmt
+        .query_map(params![workflow, limit as i64], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, Option<String>>(3)?,
+                row.get::<_, String>(4)?,
+                row.get::<_, Option<String>>(5)?,
+                row.get::<_, String>(6)?,
+                row.get::<_, String>(7)?,
+            ))
+        })
+        .context("Failed to query workflow runs")?;
+
+    let mut runs = Vec::new();
+    for row in rows {
+        let (id, workflow, status, event_json, steps_json, error, started_at, finished_at) =
+            row.context("Failed to read workflow run")?;
+        runs.push(WorkflowRun {
+            id,
+            workflow,
+            status: status.parse()?,
+            event: event_json.and_then(|json| serde_json::from_str(&json).ok()),
+            steps: serde_json::from_str(&steps_json).context("Failed to parse workflow steps")?,
+            error,
+            started_at: DateTime::parse_from_rfc3339(&started_at)
+                .context("Failed to parse started_at")?
+                .with_timezone(&Utc),
+            finished_at: DateTime::parse_from_rfc3339(&finished_at)
+                .context("Failed to parse finished_at")?
+                .with_timezone(&Utc),
+        });
+    }
+    Ok(runs)
 }
 
 /// Get the last run time for a watcher (for catch-up scheduling)
@@ -430,6 +998,318 @@ pub fn get_last_run(conn: &Connection, watcher_id: &str) -> Result<Option<DateTi
     }
 }
 
+/// Load the cache validators stored by the last fetch of a feed watcher
+pub fn get_feed_validators(conn: &Connection, watcher_id: &str) -> Result<FeedValidators> {
+    let result = conn.query_row(
+        "SELECT etag, last_modified FROM feed_state WHERE watcher_id = ?1",
+        params![watcher_id],
+        |row| {
+            Ok(FeedValidators {
+                etag: row.get(0)?,
+                last_modified: row.get(1)?,
+            })
+        },
+    );
+
+    match result {
+        Ok(validators) => Ok(validators),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(FeedValidators::default()),
+        Err(e) => Err(e).context("Failed to query feed validators"),
+    }
+}
+
+/// Store the cache validators returned by a feed fetch
+pub fn save_feed_validators(
+    conn: &Connection,
+    watcher_id: &str,
+    feed_url: &str,
+    validators: &FeedValidators,
+) -> Result<()> {
+    conn.execute(
+        "INSERT INTO feed_state (watcher_id, feed_url, etag, last_modified, checked_at)
+         VALUES (?1, ?2, ?3, ?4, ?5)
+         ON CONFLICT(watcher_id) DO UPDATE SET
+            feed_url = excluded.feed_url,
+            etag = excluded.etag,
+            last_modified = excluded.last_modified,
+            checked_at = excluded.checked_at",
+        params![
+            watcher_id,
+            feed_url,
+            &validators.etag,
+            &validators.last_modified,
+            Utc::now().to_rfc3339(),
+        ],
+    )
+    .context("Failed to save feed validators")?;
+    Ok(())
+}
+
+/// Whether a feed watcher has collected any items yet
+pub fn has_feed_items(conn: &Connection, watcher_id: &str) -> Result<bool> {
+    conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM feed_items WHERE watcher_id = ?1)",
+        params![watcher_id],
+        |row| row.get(0),
+    )
+    .context("Failed to query feed items")
+}
+
+/// Record a feed item for a watcher. Returns `false` if its GUID was already seen.
+pub fn record_feed_item(
+    conn: &Connection,
+    watcher_id: &str,
+    feed_url: &str,
+    item: &FeedItem,
+) -> Result<bool> {
+    let inserted = conn
+        .execute(
+            "INSERT OR IGNORE INTO feed_items
+                (watcher_id, guid, feed_url, title, link, summary, published_at, collected_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                watcher_id,
+                &item.guid,
+                feed_url,
+                &item.title,
+                &item.link,
+                &item.summary,
+                item.published.map(|d| d.to_rfc3339()),
+                Utc::now().to_rfc3339(),
+            ],
+        )
+        .context("Failed to record feed item")?;
+    Ok(inserted > 0)
+}
+
+/// Drop every feed item a watcher has collected. Returns the number removed.
+pub fn delete_feed_items(conn: &Connection, watcher_id: &str) -> Result<usize> {
+    conn.execute(
+        "DELETE FROM feed_items WHERE watcher_id = ?1",
+        params![watcher_id],
+    )
+    .context("Failed to delete feed items")
+}
+
+/// Most recent items collected from a feed URL (across watchers), newest first
+pub fn recent_feed_items(conn: &Connection, feed_url: &str, limit: usize) -> Result<Vec<FeedItem>> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT guid, title, link, summary, published_at FROM feed_items
+             WHERE feed_url = ?1
+             GROUP BY guid
+             ORDER BY COALESCE(MAX(published_at), MAX(collected_at)) DESC
+             LIMIT ?2",
+        )
+        .context("Failed to prepare feed items query")?;
+
+    let items = stmt
+        .query_map(params![feed_url, limit as i64], |row| {
+            let published: Option<String> = row.get(4)?;
+            Ok(FeedItem {
+                guid: row.get(0)?,
+                title: row.get(1)?,
+                link: row.get(2)?,
+                summary: row.get(3)?,
+                published: published
+                    .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
+                    .map(|d| d.with_timezone(&Utc)),
+            })
+        })
+        .context("Failed to query feed items")?
+        .collect::<Result<Vec<_>, _>>()
+        .context("Failed to read feed items")?;
+
+    Ok(items)
+}
+
+/// Load the last snapshot stored for a page watcher
+pub fn get_page_snapshot(conn: &Connection, watcher_id: &str) -> Result<Option<String>> {
+    let result = conn.query_row(
+        "SELECT content FROM page_snapshots WHERE watcher_id = ?1",
+        params![watcher_id],
+        |row| row.get(0),
+    );
+
+    match result {
+        Ok(content) => Ok(Some(content)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e).context("Failed to query page snapshot"),
+    }
+}
+
+/// Store the current snapshot of a page watcher
+pub fn save_page_snapshot(
+    conn: &Connection,
+    watcher_id: &str,
+    url: &str,
+    content: &str,
+) -> Result<()> {
+    let now = Utc::now().to_rfc3339();
+    conn.execute(
+        "INSERT INTO page_snapshots (watcher_id, url, content, checked_at, changed_at)
+         VALUES (?1, ?2, ?3, ?4, ?4)
+         ON CONFLICT(watcher_id) DO UPDATE SET
+            url = excluded.url,
+            changed_at = CASE WHEN content = excluded.content
+                THEN changed_at ELSE excluded.changed_at END,
+            content = excluded.content,
+            checked_at = excluded.checked_at",
+        params![watcher_id, url, content, &now],
+    )
+    .context("Failed to save page snapshot")?;
+    Ok(())
+}
+
+/// Record an item a watcher has seen. Returns `false` if it was seen before.
+///
+/// Seeing an item again refreshes it, so items still present at the source
+/// are never garbage-collected.
+pub fn record_seen_item(conn: &Connection, watcher_id: &str, item_key: &str) -> Result<bool> {
+    let now = Utc::now().to_rfc3339();
+    let refreshed = conn
+        .execute(
+            "UPDATE watcher_seen_items SET last_seen_at = ?3
+             WHERE watcher_id = ?1 AND item_key = ?2",
+            params![watcher_id, item_key, &now],
+        )
+        .context("Failed to refresh seen item")?;
+    if refreshed > 0 {
+        return Ok(false);
+    }
+
+    conn.execute(
+        "INSERT INTO watcher_seen_items (watcher_id, item_key, first_seen_at, last_seen_at)
+         VALUES (?1, ?2, ?3, ?3)",
+        params![watcher_id, item_key, &now],
+    )
+    .context("Failed to record seen item")?;
+    Ok(true)
+}
+
+/// Record a webhook delivery. Returns `false` if the same delivery was
+/// accepted within `ttl`.
+///
+/// Deliveries older than `ttl` are forgotten first, so the table stays small.
+pub fn record_webhook_delivery(
+    conn: &Connection,
+    watcher_id: &str,
+    delivery_id: &str,
+    ttl: chrono::Duration,
+) -> Result<bool> {
+    let now = Utc::now();
+    conn.execute(
+        "DELETE FROM webhook_deliveries WHERE received_at < ?1",
+        params![(now - ttl).to_rfc3339()],
+    )
+    .context("Failed to expire webhook deliveries")?;
+
+    let inserted = conn
+        .execute(
+            "INSERT OR IGNORE INTO webhook_deliveries (watcher_id, delivery_id, received_at)
+             VALUES (?1, ?2, ?3)",
+            params![watcher_id, delivery_id, now.to_rfc3339()],
+        )
+        .context("Failed to record webhook delivery")?;
+    Ok(inserted > 0)
+}
+
+/// Load the position a watcher has reached in its source
+pub fn get_watcher_cursor(conn: &Connection, watcher_id: &str) -> Result<Option<String>> {
+    let result = conn.query_row(
+        "SELECT cursor FROM watcher_cursors WHERE watcher_id = ?1",
+        params![watcher_id],
+        |row| row.get(0),
+    );
+
+    match result {
+        Ok(cursor) => Ok(Some(cursor)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e).context("Failed to query watcher cursor"),
+    }
+}
+
+/// Store the position a watcher has reached in its source
+pub fn save_watcher_cursor(conn: &Connection, watcher_id: &str, cursor: &str) -> Result<()> {
+    conn.execute(
+        "INSERT INTO watcher_cursors (watcher_id, cursor, updated_at) VALUES (?1, ?2, ?3)
+         ON CONFLICT(watcher_id) DO UPDATE SET
+            cursor = excluded.cursor,
+            updated_at = excluded.updated_at",
+        params![watcher_id, cursor, Utc::now().to_rfc3339()],
+    )
+    .context("Failed to save watcher cursor")?;
+    Ok(())
+}
+
+/// Garbage-collect poll state
+///
+/// Drops seen items, feed items and dead letters older than `days_to_keep`
+/// days, and all cursors, seen items, webhook deliveries, feed validators,
+/// feed items, page snapshots, last runs and health of watchers that were
+/// deleted or cancelled. Returns the number of rows
+/// removed.
+pub fn cleanup_poll_state(conn: &Connection, days_to_keep: u32) -> Result<usize> {
+    let cutoff = (Utc::now() - chrono::Duration::days(days_to_keep as i64)).to_rfc3339();
+
+    let mut rows_deleted = conn
+        .execute(
+            "DELETE FROM watcher_seen_items WHERE last_seen_at < ?1",
+            params![&cutoff],
+        )
+        .context("Failed to clean up seen items")?;
+    rows_deleted += conn
+        .execute(
+            "DELETE FROM feed_items WHERE collected_at < ?1",
+            params![&cutoff],
+        )
+        .context("Failed to clean up feed items")?;
+
+    for table in [
+        "watcher_seen_items",
+        "watcher_cursors",
+        "webhook_deliveries",
+        "feed_state",
+        "feed_items",
+        "page_snapshots",
+        "watcher_last_run",
+    ] {
+        rows_deleted += conn
+            .execute(
+                &format!(
+                    "DELETE FROM {table} WHERE watcher_id NOT IN
+                        (SELECT id FROM scheduler_watchers WHERE active = 1)"
+                ),
+                [],
+            )
+            .with_context(|| format!("Failed to clean up {}", table))?;
+    }
+
+    // Health of auto-disabled watchers stays visible until they are deleted
+    rows_deleted += conn
+        .execute(
+            "DELETE FROM watcher_health WHERE watcher_id NOT IN
+                (SELECT id FROM scheduler_watchers WHERE active = 1)
+             AND (disabled_at IS NULL
+                  OR watcher_id NOT IN (SELECT id FROM scheduler_watchers))",
+            [],
+        )
+        .context("Failed to clean up watcher_health")?;
+    rows_deleted += conn
+        .execute(
+            "DELETE FROM watcher_dead_letters WHERE failed_at < ?1
+             OR watcher_id NOT IN (SELECT id FROM scheduler_watchers)",
+            params![&cutoff],
+        )
+        .context("Failed to clean up watcher_dead_letters")?;
+
+    if rows_deleted > 0 {
+        info!("Cleaned up {} stale watcher poll state rows", rows_deleted);
+    }
+
+    Ok(rows_deleted)
+}
+
 /// Clean up old watcher events (keep only last N days)
 pub fn cleanup_old_events(conn: &Connection, days_to_keep: u32) -> Result<usize> {
     let cutoff = Utc::now() - chrono::Duration::days(days_to_keep as i64);
@@ -482,6 +1362,144 @@ mod tests {
         assert_eq!(loaded.reply_channel, watcher.reply_channel);
     }
 
+    #[test]
+    fn test_watcher_rules_roundtrip() {
+        // A database created before rules existed gains the column
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute(
+            "CREATE TABLE scheduler_watchers (
+                id TEXT PRIMARY KEY,
+                kind_json TEXT NOT NULL,
+                action TEXT NOT NULL,
+                reply_channel TEXT NOT NULL,
+                active INTEGER NOT NULL DEFAULT 1,
+                created_at TEXT NOT NULL
+            )",
+            [],
+        )
+        .unwrap();
+        init_watcher_tables(&conn).unwrap();
+
+        let rules = crate::gate::EventRules {
+            filter: Some("subject ~ '(?i)invoice'".to_string()),
+            batch_window_secs: 300,
+            ..Default::default()
+        };
+        let watcher = Watcher::new(
+            WatcherKind::FileWatch {
+                path: "/tmp/test".to_string(),
+            },
+            "Watch test file".to_string(),
+            "alerts".to_string(),
+        )
+        .with_rules(rules.clone());
+        save_watcher(&conn, &watcher).unwrap();
+
+        let loaded = get_watcher_by_id(&conn, &watcher.id).unwrap().unwrap();
+        assert_eq!(loaded.rules, rules);
+        assert_eq!(get_active_watchers(&conn).unwrap()[0].rules, rules);
+
+        // Clearing the rules clears the column
+        save_watcher(&conn, &watcher.clone().with_rules(Default::default())).unwrap();
+        let loaded = get_watcher_by_id(&conn, &watcher.id).unwrap().unwrap();
+        assert!(loaded.rules.is_empty());
+    }
+
+    #[test]
+    fn test_migrate_legacy_watchers() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute(
+            "CREATE TABLE watchers (
+                id TEXT PRIMARY KEY,
+                kind TEXT NOT NULL,
+                config TEXT NOT NULL,
+                action TEXT NOT NULL,
+                reply_channel TEXT NOT NULL,
+                active INTEGER NOT NULL DEFAULT 1,
+                created_at TEXT NOT NULL
+            )",
+            [],
+        )
+        .unwrap();
+        let legacy = [
+            // Only in the old table, with rules in its config
+            (
+                "w-feed",
+                "feed",
+                r#"{"url": "https://example.com/feed.xml", "rules": {"debounce_secs": 60}}"#,
+                1,
+            ),
+            // Only in the old table, already cancelled
+            ("w-file", "file", r#"{"path": "/tmp/inbox"}"#, 0),
+            // In both tables, cancelled only in the old one
+            ("w-both", "message", r#"{"keyword": "urgent"}"#, 0),
+            // Not convertible
+            ("w-bad", "pager", "{}", 1),
+        ];
+        for (id, kind, config, active) in legacy {
+            conn.execute(
+                "INSERT INTO watchers (id, kind, config, action, reply_channel, active, created_at)
+                 VALUES (?1, ?2, ?3, 'act', 'internal', ?4, '2025-01-02T03:04:05+00:00')",
+                params![id, kind, config, active],
+            )
+            .unwrap();
+        }
+
+        // The daemon's copy of w-both is still active
+        conn.execute(
+            "CREATE TABLE scheduler_watchers (
+                id TEXT PRIMARY KEY,
+                kind_json TEXT NOT NULL,
+                action TEXT NOT NULL,
+                reply_channel TEXT NOT NULL,
+                active INTEGER NOT NULL DEFAULT 1,
+                created_at TEXT NOT NULL
+            )",
+            [],
+        )
+        .unwrap();
+        let mut both = Watcher::new(
+            WatcherKind::MessageWatch {
+                keyword: "urgent".to_string(),
+            },
+            "act".to_string(),
+            "internal".to_string(),
+        );
+        both.id = "w-both".to_string();
+        conn.execute(
+            "INSERT INTO scheduler_watchers (id, kind_json, action, reply_channel, active, created_at)
+             VALUES (?1, ?2, 'act', 'internal', 1, ?3)",
+            params![
+                both.id,
+                serde_json::to_string(&both.kind).unwrap(),
+                both.created_at.to_rfc3339()
+            ],
+        )
+        .unwrap();
+
+        init_watcher_tables(&conn).unwrap();
+
+        let active = get_active_watchers(&conn).unwrap();
+        assert_eq!(active.len(), 1);
+        assert_eq!(active[0].id, "w-feed");
+        assert!(matches!(active[0].kind, WatcherKind::FeedWatch { .. }));
+        assert_eq!(active[0].rules.debounce_secs, 60);
+        assert_eq!(
+            active[0].created_at.to_rfc3339(),
+            "2025-01-02T03:04:05+00:00"
+        );
+        assert!(!get_watcher_by_id(&conn, "w-file").unwrap().unwrap().active);
+        assert!(!get_watcher_by_id(&conn, "w-both").unwrap().unwrap().active);
+        assert!(get_watcher_by_id(&conn, "w-bad").unwrap().is_none());
+
+        // The old rows are kept aside and the migration does not run again
+        let kept: i64 = conn
+            .query_row("SELECT COUNT(*) FROM legacy_watchers", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(kept, 4);
+        assert_eq!(migrate_legacy_watchers(&conn).unwrap(), 0);
+    }
+
     #[test]
     fn test_get_active_watchers() {
         let conn = setup_test_db();
@@ -572,7 +1590,7 @@ mod tests {
     }
 
     #[test]
-    fn test_record_run_error_deactivates() {
+    fn test_watcher_health_streaks() {
         let conn = setup_test_db();
         let watcher = Watcher::new(
             WatcherKind::FileWatch {
@@ -582,40 +1600,98 @@ mod tests {
             "test".to_string(),
         );
         save_watcher(&conn, &watcher).unwrap();
+        let health = get_watcher_health(&conn, &watcher.id).unwrap().unwrap();
+        assert_eq!(health.status, HealthStatus::Pending);
 
-        // 3 errors with max_errors=3 should deactivate
-        assert!(!record_run_error(&conn, &watcher.id, 3).unwrap());
-        assert!(!record_run_error(&conn, &watcher.id, 3).unwrap());
-        assert!(record_run_error(&conn, &watcher.id, 3).unwrap()); // deactivated
-
-        let loaded = get_watcher_by_id(&conn, &watcher.id).unwrap().unwrap();
-        assert!(!loaded.active);
+        let now = Utc::now();
+        assert_eq!(
+            record_watcher_failure(&conn, &watcher.id, "401", now).unwrap(),
+            1
+        );
+        assert_eq!(
+            record_watcher_failure(&conn, &watcher.id, "403", now).unwrap(),
+            2
+        );
+        set_watcher_next_run(&conn, &watcher.id, Some(now)).unwrap();
+        let health = get_watcher_health(&conn, &watcher.id).unwrap().unwrap();
+        assert_eq!(health.status, HealthStatus::Failing);
+        assert_eq!(health.last_error.as_deref(), Some("403"));
+        assert_eq!(health.consecutive_errors, 2);
+        assert!(health.next_run.is_some());
+
+        // A success resets the streak but keeps the last error for reference
+        record_watcher_success(&conn, &watcher.id, now).unwrap();
+        let health = get_watcher_health(&conn, &watcher.id).unwrap().unwrap();
+        assert_eq!(health.status, HealthStatus::Ok);
+        assert_eq!(health.consecutive_errors, 0);
+        assert_eq!(health.last_error.as_deref(), Some("403"));
+        assert_eq!(
+            record_watcher_failure(&conn, &watcher.id, "500", now).unwrap(),
+            1
+        );
     }
 
     #[test]
-    fn test_record_last_run_resets_errors() {
+    fn test_disabled_watchers_stay_listed() {
         let conn = setup_test_db();
-        let watcher = Watcher::new(
-            WatcherKind::FileWatch {
-                path: "/tmp".to_string(),
-            },
-            "Test".to_string(),
-            "test".to_string(),
+        let make = |path: &str| {
+            let watcher = Watcher::new(
+                WatcherKind::FileWatch {
+                    path: path.to_string(),
+                },
+                "Test".to_string(),
+                "test".to_string(),
+            );
+            save_watcher(&conn, &watcher).unwrap();
+            watcher
+        };
+        let (broken, cancelled, fine) = (make("/a"), make("/b"), make("/c"));
+
+        record_watcher_failure(&conn, &broken.id, "boom", Utc::now()).unwrap();
+        disable_watcher(&conn, &broken.id, Utc::now()).unwrap();
+        deactivate_watcher(&conn, &cancelled.id).unwrap();
+        assert!(
+            !get_watcher_by_id(&conn, &broken.id)
+                .unwrap()
+                .unwrap()
+                .active
         );
-        save_watcher(&conn, &watcher).unwrap();
 
-        // Record 2 errors
-        record_run_error(&conn, &watcher.id, 5).unwrap();
-        record_run_error(&conn, &watcher.id, 5).unwrap();
+        let listed = list_watcher_health(&conn).unwrap();
+        let ids: Vec<_> = listed.iter().map(|h| h.watcher_id.as_str()).collect();
+        assert_eq!(ids, [broken.id.as_str(), fine.id.as_str()]);
+        assert_eq!(listed[0].status, HealthStatus::Disabled);
+
+        // Cleanup keeps the disabled watcher's health until it is deleted
+        cleanup_poll_state(&conn, 30).unwrap();
+        assert_eq!(list_watcher_health(&conn).unwrap().len(), 2);
+        delete_watcher(&conn, &broken.id).unwrap();
+        cleanup_poll_state(&conn, 30).unwrap();
+        assert_eq!(list_watcher_health(&conn).unwrap().len(), 1);
+    }
 
-        // Successful run resets error count
-        record_last_run(&conn, &watcher.id).unwrap();
+    #[test]
+    fn test_dead_letters() {
+        let conn = setup_test_db();
+        let event = WatcherEvent::task("w1".to_string(), "Morning briefing".to_string());
+        record_dead_letter(&conn, "w1", None, "HTTP 401", Utc::now()).unwrap();
+        record_dead_letter(&conn, "w1", Some(&event), "channel closed", Utc::now()).unwrap();
+        record_dead_letter(&conn, "w2", None, "timeout", Utc::now()).unwrap();
+
+        let letters = get_dead_letters(&conn, Some("w1"), 10).unwrap();
+        assert_eq!(letters.len(), 2);
+        assert_eq!(letters[0].error, "channel closed");
+        assert_eq!(letters[0].event.as_ref().unwrap().kind, event.kind);
+        assert!(letters[1].event.is_none());
+        assert_eq!(get_dead_letters(&conn, None, 2).unwrap().len(), 2);
+        assert_eq!(
+            get_dead_letters(&conn, None, 10).unwrap()[0].watcher_id,
+            "w2"
+        );
 
-        // Should need 5 more errors to deactivate
-        for _ in 0..4 {
-            assert!(!record_run_error(&conn, &watcher.id, 5).unwrap());
-        }
-        assert!(record_run_error(&conn, &watcher.id, 5).unwrap());
+        // Letters of watchers that no longer exist are cleaned up
+        cleanup_poll_state(&conn, 30).unwrap();
+        assert!(get_dead_letters(&conn, None, 10).unwrap().is_empty());
     }
 
     #[test]
@@ -658,6 +1734,146 @@ mod tests {
         assert!(!result);
     }
 
+    #[test]
+    fn test_seen_items_and_cursors() {
+        let conn = setup_test_db();
+
+        assert!(record_seen_item(&conn, "w1", "a|b").unwrap());
+        assert!(!record_seen_item(&conn, "w1", "a|b").unwrap());
+        // Keys are per watcher
+        assert!(record_seen_item(&conn, "w2", "a|b").unwrap());
+
+        assert_eq!(get_watcher_cursor(&conn, "w1").unwrap(), None);
+        save_watcher_cursor(&conn, "w1", "100").unwrap();
+        save_watcher_cursor(&conn, "w1", "105").unwrap();
+        assert_eq!(
+            get_watcher_cursor(&conn, "w1").unwrap().as_deref(),
+            Some("105")
+        );
+    }
+
+    #[test]
+    fn test_webhook_deliveries_expire() {
+        let conn = setup_test_db();
+        let ttl = chrono::Duration::hours(24);
+
+        assert!(record_webhook_delivery(&conn, "w1", "d-1", ttl).unwrap());
+        assert!(!record_webhook_delivery(&conn, "w1", "d-1", ttl).unwrap());
+        // Delivery IDs are per watcher
+        assert!(record_webhook_delivery(&conn, "w2", "d-1", ttl).unwrap());
+
+        conn.execute(
+            "UPDATE webhook_deliveries SET received_at = ?1 WHERE watcher_id = 'w1'",
+            params![(Utc::now() - chrono::Duration::hours(25)).to_rfc3339()],
+        )
+        .unwrap();
+        assert!(record_webhook_delivery(&conn, "w1", "d-1", ttl).unwrap());
+    }
+
+    #[test]
+    fn test_cleanup_poll_state() {
+        let conn = setup_test_db();
+        let watcher = Watcher::new(
+            WatcherKind::GitHubWatch {
+                repo: "owner/repo".to_string(),
+                events: vec![],
+                github_token: None,
+                interval_secs: 300,
+            },
+            "Summarize".to_string(),
+            "ch".to_string(),
+        );
+        save_watcher(&conn, &watcher).unwrap();
+
+        record_seen_item(&conn, &watcher.id, "fresh").unwrap();
+        record_seen_item(&conn, &watcher.id, "stale").unwrap();
+        conn.execute(
+            "UPDATE watcher_seen_items SET last_seen_at = ?1 WHERE item_key = 'stale'",
+            params![(Utc::now() - chrono::Duration::days(90)).to_rfc3339()],
+        )
+        .unwrap();
+        save_watcher_cursor(&conn, &watcher.id, "42").unwrap();
+        // State left behind by a watcher that no longer exists
+        record_seen_item(&conn, "deleted", "x").unwrap();
+        save_watcher_cursor(&conn, "deleted", "7").unwrap();
+
+        assert_eq!(cleanup_poll_state(&conn, 30).unwrap(), 3);
+        assert!(!record_seen_item(&conn, &watcher.id, "fresh").unwrap());
+        assert!(record_seen_item(&conn, &watcher.id, "stale").unwrap());
+        assert!(get_watcher_cursor(&conn, &watcher.id).unwrap().is_some());
+        assert!(get_watcher_cursor(&conn, "deleted").unwrap().is_none());
+
+        deactivate_watcher(&conn, &watcher.id).unwrap();
+        cleanup_poll_state(&conn, 30).unwrap();
+        assert!(get_watcher_cursor(&conn, &watcher.id).unwrap().is_none());
+    }
+
+    fn feed_watcher(conn: &Connection, url: &str) -> Watcher {
+        let watcher = Watcher::new(
+            WatcherKind::FeedWatch {
+                url: url.to_string(),
+                keywords: vec![],
+                interval_secs: 300,
+            },
+            "Summarize".to_string(),
+            "ch".to_string(),
+        );
+        save_watcher(conn, &watcher).unwrap();
+        watcher
+    }
+
+    fn feed_item(guid: &str) -> FeedItem {
+        FeedItem {
+            guid: guid.to_string(),
+            title: guid.to_string(),
+            link: None,
+            summary: None,
+            published: None,
+        }
+    }
+
+    #[test]
+    fn test_cleanup_poll_state_prunes_feed_items() {
+        let conn = setup_test_db();
+        let url = "https://example.com/feed.xml";
+        let watcher = feed_watcher(&conn, url);
+
+        record_feed_item(&conn, &watcher.id, url, &feed_item("fresh")).unwrap();
+        record_feed_item(&conn, &watcher.id, url, &feed_item("stale")).unwrap();
+        conn.execute(
+            "UPDATE feed_items SET collected_at = ?1 WHERE guid = 'stale'",
+            params![(Utc::now() - chrono::Duration::days(90)).to_rfc3339()],
+        )
+        .unwrap();
+        // Items left behind by a watcher that no longer exists
+        record_feed_item(&conn, "deleted", url, &feed_item("x")).unwrap();
+
+        assert_eq!(cleanup_poll_state(&conn, 30).unwrap(), 2);
+        let recent = recent_feed_items(&conn, url, 10).unwrap();
+        assert_eq!(recent.len(), 1);
+        assert_eq!(recent[0].guid, "fresh");
+        assert!(!has_feed_items(&conn, "deleted").unwrap());
+    }
+
+    #[test]
+    fn test_removing_watcher_drops_its_feed_items() {
+        let conn = setup_test_db();
+        let url = "https://example.com/feed.xml";
+        let cancelled = feed_watcher(&conn, url);
+        let deleted = feed_watcher(&conn, url);
+        let kept = feed_watcher(&conn, url);
+        for watcher in [&cancelled, &deleted, &kept] {
+            record_feed_item(&conn, &watcher.id, url, &feed_item("a")).unwrap();
+        }
+
+        deactivate_watcher(&conn, &cancelled.id).unwrap();
+        delete_watcher(&conn, &deleted.id).unwrap();
+
+        assert!(!has_feed_items(&conn, &cancelled.id).unwrap());
+        assert!(!has_feed_items(&conn, &deleted.id).unwrap());
+        assert!(has_feed_items(&conn, &kept.id).unwrap());
+    }
+
     #[test]
     fn test_cleanup_old_events_empty() {
         let conn = setup_test_db();
@@ -701,6 +1917,59 @@ mod tests {
         assert!(last.is_none());
     }
 
+    #[test]
+    fn test_feed_items_and_validators() {
+        let conn = setup_test_db();
+        let url = "https://example.com/feed.xml";
+
+        assert_eq!(
+            get_feed_validators(&conn, "feed-1").unwrap(),
+            FeedValidators::default()
+        );
+        let validators = FeedValidators {
+            etag: Some("\"v1\"".to_string()),
+            last_modified: None,
+        };
+        save_feed_validators(&conn, "feed-1", url, &validators).unwrap();
+        assert_eq!(get_feed_validators(&conn, "feed-1").unwrap(), validators);
+
+        assert!(!has_feed_items(&conn, "feed-1").unwrap());
+        let item = FeedItem {
+            guid: "a".to_string(),
+            title: "First".to_string(),
+            link: Some("https://example.com/a".to_string()),
+            summary: None,
+            published: Some(Utc::now()),
+        };
+        assert!(record_feed_item(&conn, "feed-1", url, &item).unwrap());
+        assert!(!record_feed_item(&conn, "feed-1", url, &item).unwrap());
+        // A second watcher on the same feed tracks its own dedup state
+        assert!(record_feed_item(&conn, "feed-2", url, &item).unwrap());
+        assert!(has_feed_items(&conn, "feed-1").unwrap());
+
+        let recent = recent_feed_items(&conn, url, 10).unwrap();
+        assert_eq!(recent.len(), 1);
+        assert_eq!(recent[0].title, "First");
+        assert!(
+            recent_feed_items(&conn, "https://other.example/", 10)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_page_snapshots() {
+        let conn = setup_test_db();
+        assert!(get_page_snapshot(&conn, "page-1").unwrap().is_none());
+
+        save_page_snapshot(&conn, "page-1", "https://example.com", "v1").unwrap();
+        save_page_snapshot(&conn, "page-1", "https://example.com", "v2").unwrap();
+        assert_eq!(
+            get_page_snapshot(&conn, "page-1").unwrap().as_deref(),
+            Some("v2")
+        );
+    }
+
     #[test]
     fn test_init_tables_idempotent() {
         let conn = Connection::open_in_memory().unwrap();
@@ -737,6 +2006,8 @@ mod tests {
             WatcherKind::Scheduled {
                 cron_expr: "0 * * * *".to_string(),
                 task: "hourly check".to_string(),
+                timezone: None,
+                misfire: crate::misfire::MisfirePolicy::default(),
             },
         ];
 
@@ -3,25 +3,30 @@
 //! This module manages the lifecycle of watcher tasks, spawning them as
 //! tokio tasks and coordinating their execution.
 
+use crate::feed::{self, FeedFetch, FeedValidators};
+use crate::gate::EventGate;
+use crate::health;
+use crate::misfire::{self, Clock, SystemClock};
+use crate::page::{self, PageFetcher, PageRegion};
+use crate::persistence;
+use crate::schedule::{self, CronSchedule, Tz};
 use crate::watcher::{Watcher, WatcherEvent, WatcherKind};
+use crate::webhook::WebhookRoutes;
 use anyhow::{Context, Result};
-use chrono::{NaiveTime, Utc};
+use chrono::{DateTime, NaiveTime, Utc};
 #[cfg(target_os = "macos")]
 use lru::LruCache;
 use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher as NotifyWatcher};
-use std::collections::HashMap;
-#[cfg(target_os = "macos")]
-use std::hash::{Hash, Hasher};
+use rusqlite::Connection;
+use std::collections::{HashMap, HashSet};
 #[cfg(target_os = "macos")]
 use std::num::NonZeroUsize;
 use std::path::Path;
-use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Duration;
 #[cfg(target_os = "macos")]
 use tokio::process::Command;
 use tokio::sync::{RwLock, mpsc};
-use tokio::time::{Instant, sleep_until};
 use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info, warn};
 
@@ -39,6 +44,15 @@ pub struct WatcherConfig {
 
     /// Whether to enforce active hours check
     pub enforce_active_hours: bool,
+
+    /// Timezone for scheduled watchers that do not name one
+    pub timezone: Tz,
+
+    /// Consecutive failed polls after which a watcher is disabled (0 = never)
+    pub max_consecutive_errors: u32,
+
+    /// Longest delay between retries of a failing polling watcher
+    pub max_backoff_secs: u64,
 }
 
 impl Default for WatcherConfig {
@@ -48,6 +62,9 @@ impl Default for WatcherConfig {
             min_poll_interval_secs: 10,
             active_hours: None,
             enforce_active_hours: false,
+            timezone: Tz::UTC,
+            max_consecutive_errors: 10,
+            max_backoff_secs: 3600,
         }
     }
 }
@@ -65,6 +82,32 @@ pub struct WatcherRunner {
 
     /// Global shutdown token
     shutdown_token: CancellationToken,
+
+    /// Database and fetchers shared with polling watchers
+    resources: PollResources,
+
+    /// HTTP server routes for webhook watchers
+    webhook_routes: Option<Arc<dyn WebhookRoutes>>,
+
+    /// Wall clock for scheduled and one-shot watchers
+    clock: Arc<dyn Clock>,
+
+    /// Filter and throttle rules applied to emitted events
+    event_gate: Option<EventGate>,
+}
+
+/// Default base URL of the GitHub REST API
+const GITHUB_API_URL: &str = "https://api.github.com";
+
+/// Shared handles polling watchers may need
+#[derive(Clone, Default)]
+struct PollResources {
+    /// Scheduler database for state that must survive restarts (e.g. seen feed items)
+    db: Option<Arc<std::sync::Mutex<Connection>>>,
+    /// Fetches pages and feeds for `PageWatch` and `FeedWatch` watchers
+    page_fetcher: Option<Arc<dyn PageFetcher>>,
+    /// GitHub REST API base URL, when not [`GITHUB_API_URL`]
+    github_api_url: Option<String>,
 }
 
 impl WatcherRunner {
@@ -83,9 +126,57 @@ impl WatcherRunner {
             event_tx,
             active_tasks: Arc::new(RwLock::new(HashMap::new())),
             shutdown_token: CancellationToken::new(),
+            resources: PollResources::default(),
+            webhook_routes: None,
+            clock: Arc::new(SystemClock),
+            event_gate: None,
         }
     }
 
+    /// Persist poll state in the scheduler database (tables created by
+    /// [`persistence::init_watcher_tables`]). Without it, dedup state is
+    /// kept in memory and lost on restart.
+    pub fn with_database(mut self, db: Arc<std::sync::Mutex<Connection>>) -> Self {
+        self.resources.db = Some(db);
+        self
+    }
+
+    /// Fetcher used by `PageWatch` and `FeedWatch` watchers. It is
+    /// responsible for refusing unsafe URLs; page and feed watchers fail to
+    /// poll until one is set.
+    pub fn with_page_fetcher(mut self, fetcher: Arc<dyn PageFetcher>) -> Self {
+        self.resources.page_fetcher = Some(fetcher);
+        self
+    }
+
+    /// Base URL of the GitHub REST API polled by `GitHubWatch` watchers, for
+    /// GitHub Enterprise Server (`https://github.example.com/api/v3`)
+    pub fn with_github_api_url(mut self, url: impl Into<String>) -> Self {
+        self.resources.github_api_url = Some(url.into());
+        self
+    }
+
+    /// Clock used to schedule `Scheduled` and `OneShot` watchers
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Server that receives requests for `Webhook` watchers. Without it,
+    /// webhook watchers fail to start.
+    pub fn with_webhook_routes(mut self, routes: Arc<dyn WebhookRoutes>) -> Self {
+        self.webhook_routes = Some(routes);
+        self
+    }
+
+    /// Gate the runner's events pass through on the way to the agent; each
+    /// watcher's `rules` are installed in it while the watcher runs. Without
+    /// it, rules are ignored.
+    pub fn with_event_gate(mut self, gate: EventGate) -> Self {
+        self.event_gate = Some(gate);
+        self
+    }
+
     /// Start a watcher
     pub async fn start_watcher(&self, watcher: Watcher) -> Result<()> {
         // Check if we've reached max concurrent watchers
@@ -109,6 +200,16 @@ impl WatcherRunner {
             watcher.description()
         );
 
+        match &self.event_gate {
+            Some(gate) => gate
+                .set_rules(&watcher.id, &watcher.rules)
+                .with_context(|| format!("Invalid event rules for watcher {}", watcher.id))?,
+            None if !watcher.rules.is_empty() => {
+                warn!("Watcher {} has event rules but no event gate", watcher.id)
+            }
+            None => {}
+        }
+
         // Create cancellation token for this watcher
         let token = CancellationToken::new();
 
@@ -122,7 +223,9 @@ impl WatcherRunner {
         match &watcher.kind {
             WatcherKind::EmailWatch { .. }
             | WatcherKind::CalendarWatch { .. }
-            | WatcherKind::GitHubWatch { .. } => {
+            | WatcherKind::GitHubWatch { .. }
+            | WatcherKind::FeedWatch { .. }
+            | WatcherKind::PageWatch { .. } => {
                 self.spawn_polling_watcher(watcher, token).await?;
             }
             WatcherKind::FileWatch { .. } => {
@@ -136,6 +239,23 @@ impl WatcherRunner {
                     watcher.id
                 );
             }
+            WatcherKind::Webhook { .. } => {
+                let registered = match &self.webhook_routes {
+                    Some(routes) => routes.register(&watcher),
+                    None => Err(anyhow::anyhow!(
+                        "Webhook watchers need the gateway to be enabled"
+                    )),
+                };
+                if let Err(e) = registered {
+                    self.active_tasks.write().await.remove(&watcher.id);
+                    return Err(e);
+                }
+                info!(
+                    "Webhook watcher {} registered on /hooks/{}",
+                    watcher.id,
+                    watcher.webhook_path().unwrap_or_default()
+                );
+            }
             WatcherKind::Scheduled { .. } => {
                 self.spawn_scheduled_watcher(watcher, token).await?;
             }
@@ -154,6 +274,12 @@ impl WatcherRunner {
         if let Some(token) = tasks.remove(id) {
             info!("Stopping watcher: {}", id);
             token.cancel();
+            if let Some(routes) = &self.webhook_routes {
+                routes.unregister(id);
+            }
+            if let Some(gate) = &self.event_gate {
+                gate.remove(id);
+            }
             Ok(true)
         } else {
             warn!("Attempted to stop non-running watcher: {}", id);
@@ -173,6 +299,9 @@ impl WatcherRunner {
         for (id, token) in tasks.drain() {
             debug!("Cancelling watcher: {}", id);
             token.cancel();
+            if let Some(routes) = &self.webhook_routes {
+                routes.unregister(&id);
+            }
         }
 
         info!("All watchers stopped");
@@ -198,19 +327,22 @@ impl WatcherRunner {
         let config = self.config.clone();
         let global_shutdown = self.shutdown_token.clone();
         let active_tasks = self.active_tasks.clone();
+        let resources = self.resources.clone();
 
         tokio::spawn(async move {
             let interval_secs = match &watcher.kind {
                 WatcherKind::EmailWatch { interval_secs, .. } => *interval_secs,
                 WatcherKind::CalendarWatch { interval_secs, .. } => *interval_secs,
                 WatcherKind::GitHubWatch { interval_secs, .. } => *interval_secs,
+                WatcherKind::FeedWatch { interval_secs, .. } => *interval_secs,
+                WatcherKind::PageWatch { interval_secs, .. } => *interval_secs,
                 _ => unreachable!(),
             };
 
             // Enforce minimum interval
             let interval_secs = interval_secs.max(config.min_poll_interval_secs);
-            let mut interval = tokio::time::interval(Duration::from_secs(interval_secs));
-            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+            let interval = Duration::from_secs(interval_secs);
+            let max_backoff = Duration::from_secs(config.max_backoff_secs);
 
             debug!(
                 "Polling watcher {} started with interval {}s",
@@ -218,8 +350,26 @@ impl WatcherRunner {
             );
 
             let mut poll_state = PollState::new();
+            let db = resources.db.clone();
+
+            // A watcher that was failing before a restart keeps backing off
+            let mut failures = db
+                .as_ref()
+                .and_then(|db| persistence::get_watcher_health(&lock_db(db), &watcher.id).ok())
+                .flatten()
+                .map_or(0, |health| health.consecutive_errors);
+            let mut delay = if failures > 0 {
+                health::backoff_delay(interval, failures, max_backoff, health::jitter())
+            } else {
+                Duration::ZERO
+            };
 
             loop {
+                let next_run = Utc::now() + chrono::Duration::from_std(delay).unwrap_or_default();
+                update_health(&db, &watcher.id, |conn| {
+                    persistence::set_watcher_next_run(conn, &watcher.id, Some(next_run))
+                });
+
                 tokio::select! {
                     _ = cancel_token.cancelled() => {
                         info!("Watcher {} cancelled", watcher.id);
@@ -229,28 +379,84 @@ impl WatcherRunner {
                         info!("Watcher {} stopped due to global shutdown", watcher.id);
                         break;
                     }
-                    _ = interval.tick() => {
-                        // Check active hours
-                        if config.enforce_active_hours
-                            && let Some((start, end)) = config.active_hours
+                    _ = tokio::time::sleep(delay) => {}
+                }
+                delay = interval;
+
+                // Check active hours
+                if config.enforce_active_hours
+                    && let Some((start, end)) = config.active_hours
+                {
+                    let now = Utc::now().time();
+                    let is_active = if start < end {
+                        now >= start && now <= end
+                    } else {
+                        now >= start || now <= end
+                    };
+
+                    if !is_active {
+                        debug!("Watcher {} paused outside active hours", watcher.id);
+                        continue;
+                    }
+                }
+
+                // Execute the poll
+                match poll_watcher(&watcher, &event_tx, &mut poll_state, &resources).await {
+                    Ok(()) => {
+                        if failures > 0 {
+                            info!(
+                                "Watcher {} recovered after {} failed polls",
+                                watcher.id, failures
+                            );
+                        }
+                        failures = 0;
+                        update_health(&db, &watcher.id, |conn| {
+                            persistence::record_watcher_success(conn, &watcher.id, Utc::now())
+                        });
+                    }
+                    Err(e) => {
+                        failures += 1;
+                        let error = format!("{:#}", e);
+                        error!(
+                            "Error polling watcher {} ({} in a row): {}",
+                            watcher.id, failures, error
+                        );
+                        let now = Utc::now();
+                        update_health(&db, &watcher.id, |conn| {
+                            persistence::record_dead_letter(conn, &watcher.id, None, &error, now)?;
+                            persistence::record_watcher_failure(conn, &watcher.id, &error, now)
+                                .map(drop)
+                        });
+
+                        if config.max_consecutive_errors > 0
+                            && failures >= config.max_consecutive_errors
                         {
-                            let now = Utc::now().time();
-                            let is_active = if start < end {
-                                now >= start && now <= end
-                            } else {
-                                now >= start || now <= end
-                            };
-
-                            if !is_active {
-                                debug!("Watcher {} paused outside active hours", watcher.id);
-                                continue;
+                            update_health(&db, &watcher.id, |conn| {
+                                persistence::disable_watcher(conn, &watcher.id, now)
+                            });
+                            let disabled = WatcherEvent::disabled(
+                                watcher.id.clone(),
+                                watcher.description(),
+                                error,
+                                failures,
+                            );
+                            if event_tx.send(disabled).is_err() {
+                                warn!("Failed to report watcher {} as disabled", watcher.id);
                             }
+                            warn!(
+                                "Watcher {} disabled after {} consecutive failed polls",
+                                watcher.id, failures
+                            );
+                            break;
                         }
 
-                        // Execute the poll
-                        if let Err(e) = poll_watcher(&watcher, &event_tx, &mut poll_state).await {
-                            error!("Error polling watcher {}: {}", watcher.id, e);
-                        }
+                        delay = health::backoff_delay(
+                            interval,
+                            failures,
+                            max_backoff,
+                            health::jitter(),
+                        );
+                        warn!("Retrying watcher {} in {}s", watcher.id, delay.as_secs());
                     }
                 }
             }
@@ -368,65 +574,105 @@ impl WatcherRunner {
         watcher: Watcher,
         cancel_token: CancellationToken,
     ) -> Result<()> {
-        let (cron_expr, task) = match &watcher.kind {
-            WatcherKind::Scheduled { cron_expr, task } => (cron_expr.clone(), task.clone()),
+        let (cron_expr, task, timezone, misfire) = match &watcher.kind {
+            WatcherKind::Scheduled {
+                cron_expr,
+                task,
+                timezone,
+                misfire,
+            } => (
+                cron_expr.clone(),
+                task.clone(),
+                timezone.as_deref(),
+                *misfire,
+            ),
             _ => unreachable!(),
         };
 
-        // Parse cron expression
-        let schedule = cron::Schedule::from_str(&cron_expr)
-            .with_context(|| format!("Invalid cron expression: {}", cron_expr))?;
+        let tz = match timezone {
+            Some(name) => schedule::resolve_timezone(name)?,
+            None => self.config.timezone,
+        };
+        let schedule = CronSchedule::parse(&cron_expr, tz)?;
 
         let event_tx = self.event_tx.clone();
         let watcher_id = watcher.id.clone();
         let task_name = task.clone();
         let global_shutdown = self.shutdown_token.clone();
         let active_tasks = self.active_tasks.clone();
+        let clock = self.clock.clone();
+        let db = self.resources.db.clone();
+
+        // Catch up from the last recorded run; a watcher that never ran can
+        // only have missed runs since it was created
+        let mut last_run = match &db {
+            Some(db) => persistence::get_last_run(&lock_db(db), &watcher_id)?,
+            None => None,
+        }
+        .unwrap_or(watcher.created_at);
 
         tokio::spawn(async move {
-            info!("Scheduled watcher {} started: {}", watcher_id, cron_expr);
+            info!(
+                "Scheduled watcher {} started: {} ({})",
+                watcher_id, cron_expr, tz
+            );
 
             loop {
-                // Get next occurrence
-                let now = Utc::now();
-                let next = match schedule.after(&now).next() {
-                    Some(n) => n,
-                    None => {
-                        error!("No next occurrence for cron expression");
-                        break;
-                    }
-                };
-
-                let duration: Duration = (next - now).to_std().unwrap_or(Duration::from_secs(60));
-                let wake_time = Instant::now() + duration;
-
-                debug!(
-                    "Scheduled watcher {} next run at {} (in {:?})",
-                    watcher_id, next, duration
-                );
-
-                tokio::select! {
-                    _ = cancel_token.cancelled() => {
-                        info!("Scheduled watcher {} cancelled", watcher_id);
-                        break;
-                    }
-                    _ = global_shutdown.cancelled() => {
-                        info!("Scheduled watcher {} stopped due to global shutdown", watcher_id);
-                        break;
-                    }
-                    _ = sleep_until(wake_time) => {
-                        // Execute the task
-                        let watcher_event = WatcherEvent::task(
-                            watcher_id.clone(),
-                            task_name.clone(),
+                let now = clock.now();
+                if misfire::any_due(&schedule, last_run, now) {
+                    let runs = misfire::due_runs(&schedule, last_run, now, &misfire);
+                    if runs.is_empty() {
+                        warn!(
+                            "Scheduled watcher {} skipped runs missed since {} (misfire policy: {})",
+                            watcher_id, last_run, misfire.action
                         );
-
-                        if let Err(e) = event_tx.send(watcher_event) {
-                            error!("Failed to send scheduled task event: {}", e);
+                    }
+                    for at in runs {
+                        let late = now - at > chrono::Duration::seconds(misfire.grace_secs as i64);
+                        let watcher_event = if late {
+                            info!(
+                                "Scheduled task '{}' catching up missed run from {}",
+                                task_name, at
+                            );
+                            WatcherEvent::missed_task(watcher_id.clone(), task_name.clone(), at)
                         } else {
+                            WatcherEvent::task(watcher_id.clone(), task_name.clone())
+                        };
+
+                        if deliver_task(&event_tx, &db, watcher_event) {
                             info!("Scheduled task '{}' triggered", task_name);
                         }
                     }
+
+                    last_run = now;
+                    if let Some(db) = &db
+                        && let Err(e) = persistence::record_run_at(&lock_db(db), &watcher_id, now)
+                    {
+                        warn!("Failed to record run of watcher {}: {}", watcher_id, e);
+                    }
+                }
+
+                // Get next occurrence
+                let Some(next) = schedule.after(now).next() else {
+                    error!("No next occurrence for cron expression");
+                    break;
+                };
+                debug!("Scheduled watcher {} next run at {}", watcher_id, next);
+                update_health(&db, &watcher_id, |conn| {
+                    persistence::set_watcher_next_run(conn, &watcher_id, Some(next))
+                });
+
+                let woke = sleep_until_wall(
+                    clock.as_ref(),
+                    next,
+                    &watcher_id,
+                    &cancel_token,
+                    &global_shutdown,
+                )
+                .await;
+                if !woke {
+                    info!("Scheduled watcher {} stopped", watcher_id);
+                    break;
                 }
             }
 
@@ -451,71 +697,93 @@ impl WatcherRunner {
         watcher: Watcher,
         cancel_token: CancellationToken,
     ) -> Result<()> {
-        let (target_time, task_name) = match &watcher.kind {
-            WatcherKind::OneShot { at, task } => (*at, task.clone()),
+        let (target_time, task_name, misfire) = match &watcher.kind {
+            WatcherKind::OneShot { at, task, misfire } => (*at, task.clone(), *misfire),
             _ => unreachable!(),
         };
         let event_tx = self.event_tx.clone();
         let watcher_id = watcher.id.clone();
         let global_shutdown = self.shutdown_token.clone();
         let active_tasks = self.active_tasks.clone();
+        let clock = self.clock.clone();
+        let db = self.resources.db.clone();
 
-        tokio::spawn(async move {
-            let now = Utc::now();
-
-            if target_time <= now {
-                warn!(
-                    "One-shot watcher {} target time {} is in the past",
-                    watcher_id, target_time
-                );
-                // Execute immediately
-                let watcher_event = WatcherEvent::task(watcher_id.clone(), task_name.clone());
-
-                if let Err(e) = event_tx.send(watcher_event) {
-                    error!("Failed to send one-shot task event: {}", e);
-                }
+        // Already fired before a restart
+        let last_run = match &db {
+            Some(db) => persistence::get_last_run(&lock_db(db), &watcher_id)?,
+            None => None,
+        };
 
-                // Clean up - idempotent, entry may already be removed by stop_watcher()
-                let mut tasks = active_tasks.write().await;
-                if tasks.remove(&watcher_id).is_some() {
-                    debug!(
-                        "One-shot watcher {} cleaned up from active tasks (immediate execution)",
-                        watcher_id
+        tokio::spawn(async move {
+            let already_ran = last_run.is_some_and(|last| last >= target_time);
+            let fire = if already_ran {
+                debug!("One-shot watcher {} already ran", watcher_id);
+                false
+            } else {
+                if target_time > clock.now() {
+                    info!(
+                        "One-shot watcher {} scheduled for {}",
+                        watcher_id, target_time
                     );
                 }
-                return;
-            }
-
-            let duration = (target_time - now)
-                .to_std()
-                .unwrap_or(Duration::from_secs(0));
-            let wake_time = Instant::now() + duration;
-
-            info!(
-                "One-shot watcher {} scheduled for {} (in {:?})",
-                watcher_id, target_time, duration
-            );
+                update_health(&db, &watcher_id, |conn| {
+                    persistence::set_watcher_next_run(conn, &watcher_id, Some(target_time))
+                });
+                sleep_until_wall(
+                    clock.as_ref(),
+                    target_time,
+                    &watcher_id,
+                    &cancel_token,
+                    &global_shutdown,
+                )
+                .await
+            };
 
-            tokio::select! {
-                _ = cancel_token.cancelled() => {
-                    info!("One-shot watcher {} cancelled", watcher_id);
-                }
-                _ = global_shutdown.cancelled() => {
-                    info!("One-shot watcher {} stopped due to global shutdown", watcher_id);
-                }
-                _ = sleep_until(wake_time) => {
-                    // Execute the task
-                    let watcher_event = WatcherEvent::task(
-                        watcher_id.clone(),
-                        task_name.clone(),
+            if fire {
+                let now = clock.now();
+                if !misfire.fires_late(target_time, now) {
+                    warn!(
+                        "One-shot watcher {} missed its time {} and its misfire policy is skip",
+                        watcher_id, target_time
                     );
-
-                    if let Err(e) = event_tx.send(watcher_event) {
-                        error!("Failed to send one-shot task event: {}", e);
+                } else {
+                    let late =
+                        now - target_time > chrono::Duration::seconds(misfire.grace_secs as i64);
+                    let watcher_event = if late {
+                        warn!(
+                            "One-shot watcher {} target time {} is in the past, running now",
+                            watcher_id, target_time
+                        );
+                        WatcherEvent::missed_task(
+                            watcher_id.clone(),
+                            task_name.clone(),
+                            target_time,
+                        )
                     } else {
+                        WatcherEvent::task(watcher_id.clone(), task_name.clone())
+                    };
+
+                    if deliver_task(&event_tx, &db, watcher_event) {
                         info!("One-shot task '{}' triggered", task_name);
                     }
                 }
+
+                if let Some(db) = &db
+                    && let Err(e) = persistence::record_run_at(&lock_db(db), &watcher_id, now)
+                {
+                    warn!("Failed to record run of watcher {}: {}", watcher_id, e);
+                }
+                update_health(&db, &watcher_id, |conn| {
+                    persistence::set_watcher_next_run(conn, &watcher_id, None)
+                });
+            }
+
+            // A one-shot watcher is done once its time has passed
+            if (fire || already_ran)
+                && let Some(db) = &db
+                && let Err(e) = persistence::deactivate_watcher(&lock_db(db), &watcher_id)
+            {
+                warn!("Failed to deactivate watcher {}: {}", watcher_id, e);
             }
 
             // Clean up - idempotent, entry may already be removed by stop_watcher()
@@ -534,29 +802,176 @@ impl WatcherRunner {
     }
 }
 
-/// State maintained across poll cycles for dedup
+/// Sleep until `target` on the wall clock.
+///
+/// Tokio timers follow a monotonic clock that may stop while the machine is
+/// asleep, so the sleep is split into steps of at most
+/// [`misfire::MAX_SLEEP_STEP`] and the wall clock re-checked after each one.
+/// Returns `false` if the watcher was cancelled first.
+async fn sleep_until_wall(
+    clock: &dyn Clock,
+    target: DateTime<Utc>,
+    watcher_id: &str,
+    cancel_token: &CancellationToken,
+    global_shutdown: &CancellationToken,
+) -> bool {
+    loop {
+        let now = clock.now();
+        let Ok(remaining) = (target - now).to_std() else {
+            return true;
+        };
+        if remaining.is_zero() {
+            return true;
+        }
+        let step = remaining.min(misfire::MAX_SLEEP_STEP);
+
+        tokio::select! {
+            _ = cancel_token.cancelled() => return false,
+            _ = global_shutdown.cancelled() => return false,
+            _ = tokio::time::sleep(step) => {}
+        }
+
+        let expected = now + chrono::Duration::from_std(step).unwrap_or_default();
+        if let Ok(jump) = (clock.now() - expected).to_std()
+            && jump > misfire::CLOCK_JUMP_THRESHOLD
+        {
+            warn!(
+                "Watcher {}: wall clock jumped ahead {}s (system sleep?)",
+                watcher_id,
+                jump.as_secs()
+            );
+        }
+    }
+}
+
+/// State maintained across poll cycles for dedup.
+///
+/// Only used as-is when no database is configured; with one, seen items and
+/// cursors live in the scheduler tables so they survive restarts.
 struct PollState {
-    /// Hashes of previously seen items (emails, calendar events) - bounded LRU cache
+    /// Keys of previously seen items (emails, calendar events) - bounded LRU cache
     #[cfg(target_os = "macos")]
-    seen_hashes: LruCache<u64, ()>,
+    seen_items: LruCache<String, ()>,
     /// Last GitHub event ID seen
     last_github_event_id: Option<String>,
+    /// Conditional GET validators for feed watchers (`None` until loaded)
+    feed_validators: Option<FeedValidators>,
+    /// Feed item GUIDs seen, used when no database is configured
+    feed_seen: HashSet<String>,
+    /// Last page snapshot, used when no database is configured
+    page_snapshot: Option<String>,
 }
 
 impl PollState {
     fn new() -> Self {
         Self {
             #[cfg(target_os = "macos")]
-            seen_hashes: LruCache::new(NonZeroUsize::new(10_000).unwrap()),
+            seen_items: LruCache::new(NonZeroUsize::new(10_000).unwrap()),
             last_github_event_id: None,
+            feed_validators: None,
+            feed_seen: HashSet::new(),
+            page_snapshot: None,
         }
     }
+}
 
-    #[cfg(target_os = "macos")]
-    fn hash_item(s: &str) -> u64 {
-        let mut hasher = std::collections::hash_map::DefaultHasher::new();
-        s.hash(&mut hasher);
-        hasher.finish()
+/// Send an event together with the state change that marks its item handled.
+///
+/// `record` returns `false` if the item was already handled, in which case
+/// nothing is sent. With a database, `record` runs in a transaction committed
+/// only after the event reached the channel, so a failed send leaves the item
+/// to be retried rather than marked as seen.
+fn emit_recorded(
+    conn: Option<&Connection>,
+    event_tx: &mpsc::UnboundedSender<WatcherEvent>,
+    event: WatcherEvent,
+    record: impl FnOnce(Option<&Connection>) -> Result<bool>,
+) -> Result<bool> {
+    let send = |event| {
+        event_tx
+            .send(event)
+            .map_err(|_| anyhow::anyhow!("Watcher event channel closed"))
+    };
+
+    let Some(conn) = conn else {
+        let is_new = record(None)?;
+        if is_new {
+            send(event)?;
+        }
+        return Ok(is_new);
+    };
+
+    let tx = conn
+        .unchecked_transaction()
+        .context("Failed to start poll state transaction")?;
+    let is_new = record(Some(&tx))?;
+    if is_new && let Err(mpsc::error::SendError(event)) = event_tx.send(event) {
+        // Roll back so the item is retried, and keep the event for inspection
+        drop(tx);
+        let error = "Watcher event channel closed";
+        persistence::record_dead_letter(conn, &event.watcher_id, Some(&event), error, Utc::now())?;
+        anyhow::bail!(error);
+    }
+    tx.commit().context("Failed to commit poll state")?;
+    Ok(is_new)
+}
+
+/// Send a scheduled or one-shot task event, recording the outcome in the
+/// watcher's health (and the event as a dead letter if it could not be sent)
+fn deliver_task(
+    event_tx: &mpsc::UnboundedSender<WatcherEvent>,
+    db: &Option<Arc<std::sync::Mutex<Connection>>>,
+    event: WatcherEvent,
+) -> bool {
+    let watcher_id = event.watcher_id.clone();
+    let now = Utc::now();
+    match event_tx.send(event) {
+        Ok(()) => {
+            update_health(db, &watcher_id, |conn| {
+                persistence::record_watcher_success(conn, &watcher_id, now)
+            });
+            true
+        }
+        Err(mpsc::error::SendError(event)) => {
+            let error = "Watcher event channel closed";
+            error!(
+                "Failed to send task event of watcher {}: {}",
+                watcher_id, error
+            );
+            update_health(db, &watcher_id, |conn| {
+                persistence::record_dead_letter(conn, &watcher_id, Some(&event), error, now)?;
+                persistence::record_watcher_failure(conn, &watcher_id, error, now).map(drop)
+            });
+            false
+        }
+    }
+}
+
+/// Update a watcher's health, if there is a database. Failures are logged
+/// rather than interrupting the watcher.
+fn update_health(
+    db: &Option<Arc<std::sync::Mutex<Connection>>>,
+    watcher_id: &str,
+    update: impl FnOnce(&Connection) -> Result<()>,
+) {
+    if let Some(db) = db
+        && let Err(e) = update(&lock_db(db))
+    {
+        warn!("Failed to update health of watcher {}: {}", watcher_id, e);
+    }
+}
+
+/// Record an email or calendar item key, in memory when there is no database
+#[cfg(target_os = "macos")]
+fn record_seen(
+    conn: Option<&Connection>,
+    watcher_id: &str,
+    item_key: &str,
+    state: &mut PollState,
+) -> Result<bool> {
+    match conn {
+        Some(conn) => persistence::record_seen_item(conn, watcher_id, item_key),
+        None => Ok(state.seen_items.put(item_key.to_string(), ()).is_none()),
     }
 }
 
@@ -565,6 +980,7 @@ async fn poll_watcher(
     watcher: &Watcher,
     event_tx: &mpsc::UnboundedSender<WatcherEvent>,
     state: &mut PollState,
+    resources: &PollResources,
 ) -> Result<()> {
     match &watcher.kind {
         WatcherKind::EmailWatch {
@@ -619,14 +1035,12 @@ end tell
 
                 if !output.status.success() {
                     let stderr = String::from_utf8_lossy(&output.stderr);
-                    warn!("Email polling failed: {}", stderr);
-                    return Ok(());
+                    anyhow::bail!("Email polling failed: {}", stderr.trim());
                 }
 
                 let stdout = String::from_utf8_lossy(&output.stdout);
                 if stdout.starts_with("Error:") {
-                    warn!("Email polling returned error: {}", stdout);
-                    return Ok(());
+                    anyhow::bail!("Email polling returned error: {}", stdout.trim());
                 }
 
                 for entry in stdout.split("---\n").filter(|e| !e.trim().is_empty()) {
@@ -663,13 +1077,7 @@ end tell
                         continue;
                     }
 
-                    // Dedup - check if we've seen this before
-                    let hash_key = format!("{}|{}|{}", email_from, email_subject, email_date);
-                    let hash = PollState::hash_item(&hash_key);
-                    if state.seen_hashes.get(&hash).is_some() {
-                        continue;
-                    }
-                    state.seen_hashes.put(hash, ());
+                    let item_key = format!("{}|{}|{}", email_from, email_subject, email_date);
 
                     // Truncate body for the event (char-safe to avoid slicing mid-UTF-8)
                     let body_preview = if email_body.chars().count() > 500 {
@@ -686,9 +1094,11 @@ end tell
                         body_preview,
                     );
 
-                    if let Err(e) = event_tx.send(event) {
-                        error!("Failed to send email event: {}", e);
-                    }
+                    // Dedup - only emit emails not seen before, even across restarts
+                    let conn = resources.db.as_ref().map(|db| lock_db(db));
+                    emit_recorded(conn.as_deref(), event_tx, event, |conn| {
+                        record_seen(conn, &watcher.id, &item_key, state)
+                    })?;
                 }
             }
         }
@@ -750,14 +1160,12 @@ end tell
 
                 if !output.status.success() {
                     let stderr = String::from_utf8_lossy(&output.stderr);
-                    warn!("Calendar polling failed: {}", stderr);
-                    return Ok(());
+                    anyhow::bail!("Calendar polling failed: {}", stderr.trim());
                 }
 
                 let stdout = String::from_utf8_lossy(&output.stdout);
                 if stdout.starts_with("Error:") {
-                    warn!("Calendar polling returned error: {}", stdout);
-                    return Ok(());
+                    anyhow::bail!("Calendar polling returned error: {}", stdout.trim());
                 }
 
                 for entry in stdout.split("---\n").filter(|e| !e.trim().is_empty()) {
@@ -772,23 +1180,18 @@ end tell
                         }
                     }
 
-                    // Dedup - check if we've seen this before
-                    let hash_key = format!("{}|{}", event_title, event_start);
-                    let hash = PollState::hash_item(&hash_key);
-                    if state.seen_hashes.get(&hash).is_some() {
-                        continue;
-                    }
-                    state.seen_hashes.put(hash, ());
-
+                    let item_key = format!("{}|{}", event_title, event_start);
                     let event = WatcherEvent::calendar(
                         watcher.id.clone(),
                         event_title,
                         Utc::now(), // Use current time as proxy since AppleScript date parsing is unreliable
                     );
 
-                    if let Err(e) = event_tx.send(event) {
-                        error!("Failed to send calendar event: {}", e);
-                    }
+                    // Dedup - only emit events not seen before, even across restarts
+                    let conn = resources.db.as_ref().map(|db| lock_db(db));
+                    emit_recorded(conn.as_deref(), event_tx, event, |conn| {
+                        record_seen(conn, &watcher.id, &item_key, state)
+                    })?;
                 }
             }
         }
@@ -803,7 +1206,11 @@ end tell
                 watcher.id, repo, events
             );
 
-            let url = format!("https://api.github.com/repos/{}/events", repo);
+            let api_url = resources
+                .github_api_url
+                .as_deref()
+                .unwrap_or(GITHUB_API_URL);
+            let url = format!("{}/repos/{}/events", api_url.trim_end_matches('/'), repo);
             let client = reqwest::Client::builder()
                 .user_agent("meepo-agent/1.0")
                 .timeout(Duration::from_secs(30))
@@ -815,69 +1222,263 @@ end tell
             }
             let response = request.send().await?;
 
-            if !response.status().is_success() {
-                warn!(
-                    "GitHub API returned status {} for {}",
-                    response.status(),
-                    repo
-                );
-                return Ok(());
+            let status = response.status();
+            if !status.is_success() {
+                anyhow::bail!("GitHub API returned status {} for {}", status, repo);
             }
 
             let body: serde_json::Value = response.json().await?;
-            let events_array = body.as_array().unwrap_or(&Vec::new()).clone();
-
-            for gh_event in &events_array {
-                let event_id = gh_event
-                    .get("id")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("")
-                    .to_string();
-
-                let event_type = gh_event
-                    .get("type")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("")
-                    .to_string();
-
-                // Skip if we've already seen this event (compare as u64 since GitHub IDs are numeric strings)
-                if let Some(last_id) = &state.last_github_event_id {
-                    let current: u64 = event_id.parse().unwrap_or(0);
-                    let last: u64 = last_id.parse().unwrap_or(0);
-                    if current <= last {
-                        continue;
-                    }
-                }
+            let events_array = body.as_array().cloned().unwrap_or_default();
+
+            let conn = resources.db.as_ref().map(|db| lock_db(db));
+            emit_github_events(
+                watcher,
+                events,
+                &events_array,
+                event_tx,
+                state,
+                conn.as_deref(),
+            )?;
+        }
+        WatcherKind::FeedWatch { url, keywords, .. } => {
+            poll_feed(watcher, url, keywords, event_tx, state, resources).await?;
+        }
+        WatcherKind::PageWatch {
+            url,
+            selector,
+            region_start,
+            region_end,
+            change_threshold,
+            ..
+        } => {
+            let region = PageRegion {
+                selector: selector.as_deref(),
+                region_start: region_start.as_deref(),
+                region_end: region_end.as_deref(),
+            };
+            poll_page(
+                watcher,
+                url,
+                region,
+                *change_threshold,
+                event_tx,
+                state,
+                resources,
+            )
+            .await?;
+        }
+        _ => {
+            warn!("poll_watcher called on non-polling watcher: {}", watcher.id);
+        }
+    }
 
-                // Filter by requested event types (if specified)
-                if !events.is_empty() {
-                    let type_lower = event_type.to_lowercase();
-                    let matches = events
-                        .iter()
-                        .any(|e| type_lower.contains(&e.to_lowercase()));
-                    if !matches {
-                        continue;
-                    }
-                }
+    Ok(())
+}
 
-                let watcher_event =
-                    WatcherEvent::github(watcher.id.clone(), event_type, gh_event.clone());
+/// Emit GitHub events newer than the watcher's cursor, oldest first.
+///
+/// The cursor advances with each event (in the same transaction as its
+/// emission), including events skipped by the type filter.
+fn emit_github_events(
+    watcher: &Watcher,
+    wanted: &[String],
+    gh_events: &[serde_json::Value],
+    event_tx: &mpsc::UnboundedSender<WatcherEvent>,
+    state: &mut PollState,
+    conn: Option<&Connection>,
+) -> Result<()> {
+    let cursor = match conn {
+        Some(conn) => persistence::get_watcher_cursor(conn, &watcher.id)?,
+        None => state.last_github_event_id.clone(),
+    };
+    // Compare as u64 since GitHub IDs are numeric strings
+    let mut last: u64 = cursor.and_then(|id| id.parse().ok()).unwrap_or(0);
+
+    // The API lists newest first
+    for gh_event in gh_events.iter().rev() {
+        let Some(event_id) = gh_event.get("id").and_then(|v| v.as_str()) else {
+            continue;
+        };
+        let Ok(id) = event_id.parse::<u64>() else {
+            continue;
+        };
+        if id <= last {
+            continue;
+        }
+        last = id;
 
-                if let Err(e) = event_tx.send(watcher_event) {
-                    error!("Failed to send GitHub event: {}", e);
-                }
+        let mut advance = |conn: Option<&Connection>| -> Result<bool> {
+            match conn {
+                Some(conn) => persistence::save_watcher_cursor(conn, &watcher.id, event_id)?,
+                None => state.last_github_event_id = Some(event_id.to_string()),
             }
+            Ok(true)
+        };
 
-            // Update last seen event ID (first event in the array is the newest)
-            if let Some(first) = events_array.first()
-                && let Some(id) = first.get("id").and_then(|v| v.as_str())
-            {
-                state.last_github_event_id = Some(id.to_string());
+        let event_type = gh_event
+            .get("type")
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string();
+
+        // Filter by requested event types (if specified)
+        if !wanted.is_empty() {
+            let type_lower = event_type.to_lowercase();
+            let matches = wanted
+                .iter()
+                .any(|e| type_lower.contains(&e.to_lowercase()));
+            if !matches {
+                advance(conn)?;
+                continue;
             }
         }
-        _ => {
-            warn!("poll_watcher called on non-polling watcher: {}", watcher.id);
+
+        let event = WatcherEvent::github(watcher.id.clone(), event_type, gh_event.clone());
+        emit_recorded(conn, event_tx, event, advance)?;
+    }
+
+    Ok(())
+}
+
+fn lock_db(db: &std::sync::Mutex<Connection>) -> std::sync::MutexGuard<'_, Connection> {
+    db.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Fetch a feed and emit an event for each item not seen before.
+///
+/// The first successful poll of a watcher only records the items already in
+/// the feed, so creating a watcher doesn't replay the publisher's backlog.
+async fn poll_feed(
+    watcher: &Watcher,
+    url: &str,
+    keywords: &[String],
+    event_tx: &mpsc::UnboundedSender<WatcherEvent>,
+    state: &mut PollState,
+    resources: &PollResources,
+) -> Result<()> {
+    debug!("Polling feed watcher {} ({})", watcher.id, url);
+    let db = resources.db.as_ref();
+
+    if state.feed_validators.is_none() {
+        state.feed_validators = Some(match db {
+            Some(db) => persistence::get_feed_validators(&lock_db(db), &watcher.id)?,
+            None => FeedValidators::default(),
+        });
+    }
+    let validators = state.feed_validators.clone().unwrap_or_default();
+
+    let fetcher = resources
+        .page_fetcher
+        .as_ref()
+        .context("No page fetcher configured for feed watchers")?;
+    let (parsed, new_validators) =
+        match feed::fetch_feed(fetcher.as_ref(), url, &validators).await? {
+            FeedFetch::NotModified => return Ok(()),
+            FeedFetch::Fetched { feed, validators } => (feed, validators),
+        };
+
+    {
+        let conn = db.map(|db| lock_db(db));
+        let conn = conn.as_deref();
+        let baseline = match conn {
+            Some(conn) => !persistence::has_feed_items(conn, &watcher.id)?,
+            None => state.feed_seen.is_empty(),
+        };
+
+        // Feeds list newest first; report in publication order
+        for item in parsed.items.iter().rev() {
+            let mut record = |conn: Option<&Connection>| match conn {
+                Some(conn) => persistence::record_feed_item(conn, &watcher.id, url, item),
+                None => Ok(state.feed_seen.insert(item.guid.clone())),
+            };
+            if baseline || !item.matches_keywords(keywords) {
+                record(conn)?;
+                continue;
+            }
+            let event = WatcherEvent::feed_item(
+                watcher.id.clone(),
+                url.to_string(),
+                parsed.title.clone(),
+                item,
+            );
+            emit_recorded(conn, event_tx, event, record)?;
+        }
+
+        if let Some(conn) = conn {
+            persistence::save_feed_validators(conn, &watcher.id, url, &new_validators)?;
+        }
+        if baseline {
+            debug!(
+                "Feed watcher {} recorded {} existing items",
+                watcher.id,
+                parsed.items.len()
+            );
+        }
+    }
+    state.feed_validators = Some(new_validators);
+
+    Ok(())
+}
+
+/// Fetch a page and emit a diff when its watched content changed enough.
+///
+/// Changes below the threshold are not reported and don't replace the stored
+/// snapshot, so slow drift still adds up to a reported change.
+async fn poll_page(
+    watcher: &Watcher,
+    url: &str,
+    region: PageRegion<'_>,
+    change_threshold: f64,
+    event_tx: &mpsc::UnboundedSender<WatcherEvent>,
+    state: &mut PollState,
+    resources: &PollResources,
+) -> Result<()> {
+    debug!("Polling page watcher {} ({})", watcher.id, url);
+
+    let fetcher = resources
+        .page_fetcher
+        .as_ref()
+        .context("No page fetcher configured for page watchers")?;
+    let html = fetcher.fetch(url).await?;
+    let snapshot = page::normalize_page(&html, region)?;
+
+    let conn = resources.db.as_ref().map(|db| lock_db(db));
+    let conn = conn.as_deref();
+    let previous = match conn {
+        Some(conn) => persistence::get_page_snapshot(conn, &watcher.id)?,
+        None => state.page_snapshot.clone(),
+    };
+
+    let mut save = |conn: Option<&Connection>| -> Result<bool> {
+        match conn {
+            Some(conn) => persistence::save_page_snapshot(conn, &watcher.id, url, &snapshot)?,
+            None => state.page_snapshot = Some(snapshot.clone()),
+        }
+        Ok(true)
+    };
+
+    match previous {
+        // First poll: the current content becomes the baseline
+        None => {
+            save(conn)?;
         }
+        Some(previous) => match page::diff_snapshots(&previous, &snapshot) {
+            Some(diff) if diff.change_ratio >= change_threshold => {
+                info!(
+                    "Page watcher {} detected a change (+{} -{})",
+                    watcher.id, diff.added, diff.removed
+                );
+                let event = WatcherEvent::page_changed(watcher.id.clone(), url.to_string(), &diff);
+                emit_recorded(conn, event_tx, event, save)?;
+            }
+            Some(diff) => {
+                debug!(
+                    "Page watcher {} change ratio {:.3} below threshold {}",
+                    watcher.id, diff.change_ratio, change_threshold
+                );
+            }
+            None => {}
+        },
     }
 
     Ok(())
@@ -960,6 +1561,7 @@ mod tests {
             WatcherKind::OneShot {
                 at: past_time,
                 task: "Immediate task".to_string(),
+                misfire: crate::misfire::MisfirePolicy::default(),
             },
             "Test immediate".to_string(),
             "test".to_string(),
@@ -1024,6 +1626,7 @@ mod tests {
         assert_eq!(config.min_poll_interval_secs, 10);
         assert!(config.active_hours.is_none());
         assert!(!config.enforce_active_hours);
+        assert_eq!(config.timezone, Tz::UTC);
     }
 
     #[tokio::test]
@@ -1077,6 +1680,1043 @@ mod tests {
         assert_eq!(runner.active_count().await, 1);
     }
 
+    #[tokio::test]
+    async fn test_watcher_rules_installed_in_gate() {
+        let (raw_tx, raw_rx) = mpsc::unbounded_channel();
+        let (out_tx, mut out_rx) = mpsc::unbounded_channel();
+        let gate = EventGate::new();
+        gate.spawn(raw_rx, out_tx);
+        let runner = WatcherRunner::new(raw_tx.clone()).with_event_gate(gate);
+
+        let watcher = |filter: &str| {
+            Watcher::new(
+                WatcherKind::MessageWatch {
+                    keyword: "deploy".to_string(),
+                },
+                "Notify on deploy".to_string(),
+                "slack".to_string(),
+            )
+            .with_rules(crate::gate::EventRules {
+                filter: Some(filter.to_string()),
+                ..Default::default()
+            })
+        };
+        let invalid = watcher("env ==");
+        let err = runner.start_watcher(invalid.clone()).await.unwrap_err();
+        assert!(format!("{:#}", err).contains("Invalid event filter"));
+        assert!(!runner.is_running(&invalid.id).await);
+
+        let valid = watcher("env == 'prod'");
+        runner.start_watcher(valid.clone()).await.unwrap();
+        let send = |env: &str| {
+            let event = WatcherEvent::new(
+                valid.id.clone(),
+                "message_matched".to_string(),
+                serde_json::json!({"env": env}),
+            );
+            raw_tx.send(event).unwrap();
+        };
+        send("staging");
+        send("prod");
+        let event = out_rx.recv().await.unwrap();
+        assert_eq!(event.payload["env"], "prod");
+
+        // A stopped watcher's rules are dropped
+        runner.stop_watcher(&valid.id).await.unwrap();
+        send("staging");
+        assert_eq!(out_rx.recv().await.unwrap().payload["env"], "staging");
+    }
+
+    /// Minimal HTTP stand-in serving a feed document with ETag support
+    struct FeedServer {
+        url: String,
+        /// (etag, body) currently served
+        feed: Arc<std::sync::Mutex<(String, String)>>,
+        /// If-None-Match header of the most recent request
+        last_if_none_match: Arc<std::sync::Mutex<Option<String>>>,
+    }
+
+    impl FeedServer {
+        async fn start(etag: &str, body: &str) -> Self {
+            use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let url = format!("http://{}/feed.xml", listener.local_addr().unwrap());
+            let feed = Arc::new(std::sync::Mutex::new((etag.to_string(), body.to_string())));
+            let last_if_none_match = Arc::new(std::sync::Mutex::new(None));
+
+            let (feed_c, inm_c) = (feed.clone(), last_if_none_match.clone());
+            tokio::spawn(async move {
+                while let Ok((mut socket, _)) = listener.accept().await {
+                    let mut buf = Vec::new();
+                    let mut chunk = [0u8; 1024];
+                    while !buf.windows(4).any(|w| w == b"\r\n\r\n") {
+                        match socket.read(&mut chunk).await {
+                            Ok(0) | Err(_) => break,
+                            Ok(n) => buf.extend_from_slice(&chunk[..n]),
+                        }
+                    }
+                    let request = String::from_utf8_lossy(&buf).to_string();
+                    let if_none_match = request.lines().find_map(|l| {
+                        l.split_once(':')
+                            .filter(|(k, _)| k.eq_ignore_ascii_case("if-none-match"))
+                            .map(|(_, v)| v.trim().to_string())
+                    });
+                    *inm_c.lock().unwrap() = if_none_match.clone();
+
+                    let (etag, body) = feed_c.lock().unwrap().clone();
+                    let response = if if_none_match.as_deref() == Some(etag.as_str()) {
+                        format!(
+                            "HTTP/1.1 304 Not Modified\r\nETag: {}\r\nConnection: close\r\n\r\n",
+                            etag
+                        )
+                    } else {
+                        format!(
+                            "HTTP/1.1 200 OK\r\nContent-Type: application/rss+xml\r\nETag: {}\r\n\
+                             Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+                            etag,
+                            body.len(),
+                            body
+                        )
+                    };
+                    let _ = socket.write_all(response.as_bytes()).await;
+                    let _ = socket.shutdown().await;
+                }
+            });
+
+            Self {
+                url,
+                feed,
+                last_if_none_match,
+            }
+        }
+
+        fn publish(&self, etag: &str, body: &str) {
+            *self.feed.lock().unwrap() = (etag.to_string(), body.to_string());
+        }
+
+        fn last_if_none_match(&self) -> Option<String> {
+            self.last_if_none_match.lock().unwrap().clone()
+        }
+    }
+
+    fn rss_fixture(items: &[(&str, &str)]) -> String {
+        let items: String = items
+            .iter()
+            .map(|(guid, title)| {
+                format!(
+                    "<item><guid>{}</guid><title>{}</title><link>https://example.com/{}</link></item>",
+                    guid, title, guid
+                )
+            })
+            .collect();
+        format!(
+            "<?xml version=\"1.0\"?><rss version=\"2.0\"><channel><title>Fixture</title>{}</channel></rss>",
+            items
+        )
+    }
+
+    fn feed_db() -> Arc<std::sync::Mutex<Connection>> {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::persistence::init_watcher_tables(&conn).unwrap();
+        Arc::new(std::sync::Mutex::new(conn))
+    }
+
+    /// Fetches over plain HTTP without any URL policy, for local fixtures
+    struct DirectFetcher;
+
+    #[async_trait::async_trait]
+    impl PageFetcher for DirectFetcher {
+        async fn get(&self, url: &str, headers: &[(&str, &str)]) -> Result<reqwest::Response> {
+            let client = reqwest::Client::new();
+            let mut request = client.get(url);
+            for (name, value) in headers {
+                request = request.header(*name, *value);
+            }
+            Ok(request.send().await?)
+        }
+    }
+
+    fn feed_resources(db: Option<Arc<std::sync::Mutex<Connection>>>) -> PollResources {
+        PollResources {
+            db,
+            page_fetcher: Some(Arc::new(DirectFetcher)),
+            ..Default::default()
+        }
+    }
+
+    fn drain(rx: &mut mpsc::UnboundedReceiver<WatcherEvent>) -> Vec<WatcherEvent> {
+        std::iter::from_fn(|| rx.try_recv().ok()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_feed_watcher_conditional_get_and_keywords() {
+        let server = FeedServer::start("\"v1\"", &rss_fixture(&[("1", "Old news")])).await;
+        let watcher = Watcher::new(
+            WatcherKind::FeedWatch {
+                url: server.url.clone(),
+                keywords: vec!["rust".to_string()],
+                interval_secs: 300,
+            },
+            "Summarize".to_string(),
+            "ch".to_string(),
+        );
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let db = feed_db();
+        let resources = feed_resources(Some(db.clone()));
+        let mut state = PollState::new();
+
+        // First poll only records the existing backlog
+        poll_watcher(&watcher, &tx, &mut state, &resources)
+            .await
+            .unwrap();
+        assert!(drain(&mut rx).is_empty());
+
+        // Unchanged feed: the stored ETag is sent back and the server answers 304
+        poll_watcher(&watcher, &tx, &mut state, &resources)
+            .await
+            .unwrap();
+        assert_eq!(server.last_if_none_match().as_deref(), Some("\"v1\""));
+        assert!(drain(&mut rx).is_empty());
+
+        server.publish(
+            "\"v2\"",
+            &rss_fixture(&[
+                ("3", "Cooking tips"),
+                ("2", "Rust 1.90 released"),
+                ("1", "Old news"),
+            ]),
+        );
+        poll_watcher(&watcher, &tx, &mut state, &resources)
+            .await
+            .unwrap();
+        let events = drain(&mut rx);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].kind, "feed_item");
+        assert_eq!(events[0].payload["title"], "Rust 1.90 released");
+        assert_eq!(events[0].payload["feed_title"], "Fixture");
+
+        // Filtered-out items are still collected for digests
+        let collected =
+            crate::persistence::recent_feed_items(&db.lock().unwrap(), &server.url, 10).unwrap();
+        assert_eq!(collected.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_feed_watcher_dedup_survives_restart() {
+        let server = FeedServer::start("\"v1\"", &rss_fixture(&[("a", "First")])).await;
+        let watcher = Watcher::new(
+            WatcherKind::FeedWatch {
+                url: server.url.clone(),
+                keywords: vec![],
+                interval_secs: 300,
+            },
+            "Summarize".to_string(),
+            "ch".to_string(),
+        );
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let db = feed_db();
+        let resources = feed_resources(Some(db.clone()));
+
+        let mut state = PollState::new();
+        poll_watcher(&watcher, &tx, &mut state, &resources)
+            .await
+            .unwrap();
+        server.publish("\"v2\"", &rss_fixture(&[("b", "Second"), ("a", "First")]));
+        poll_watcher(&watcher, &tx, &mut state, &resources)
+            .await
+            .unwrap();
+        assert_eq!(drain(&mut rx).len(), 1);
+
+        // Simulate a daemon restart: fresh in-memory state, same database
+        let mut restarted = PollState::new();
+        poll_watcher(&watcher, &tx, &mut restarted, &resources)
+            .await
+            .unwrap();
+        assert_eq!(server.last_if_none_match().as_deref(), Some("\"v2\""));
+
+        // Even a full refetch doesn't re-emit items seen before the restart
+        server.publish("\"v3\"", &rss_fixture(&[("b", "Second"), ("a", "First")]));
+        poll_watcher(&watcher, &tx, &mut restarted, &resources)
+            .await
+            .unwrap();
+        assert!(drain(&mut rx).is_empty());
+    }
+
+    fn github_watcher(events: Vec<String>) -> Watcher {
+        Watcher::new(
+            WatcherKind::GitHubWatch {
+                repo: "owner/repo".to_string(),
+                events,
+                interval_secs: 300,
+                github_token: None,
+            },
+            "Summarize".to_string(),
+            "ch".to_string(),
+        )
+    }
+
+    /// GitHub events API page (newest first) for the given (id, type) pairs
+    fn github_page(events: &[(u64, &str)]) -> Vec<serde_json::Value> {
+        events
+            .iter()
+            .rev()
+            .map(|(id, kind)| serde_json::json!({"id": id.to_string(), "type": kind}))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_github_dedup_survives_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("scheduler.db");
+        let open = || {
+            let conn = Connection::open(&path).unwrap();
+            crate::persistence::init_watcher_tables(&conn).unwrap();
+            Arc::new(std::sync::Mutex::new(conn))
+        };
+        let github_json =
+            |events: &[(u64, &str)]| serde_json::Value::Array(github_page(events)).to_string();
+
+        let server = FeedServer::start(
+            "\"v1\"",
+            &github_json(&[(10, "PushEvent"), (11, "IssuesEvent"), (12, "PushEvent")]),
+        )
+        .await;
+        let api_url = server.url.trim_end_matches("/feed.xml").to_string();
+        let watcher = github_watcher(vec!["push".to_string()]);
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let runner = WatcherRunner::new(tx)
+            .with_database(open())
+            .with_github_api_url(api_url.clone());
+        runner.start_watcher(watcher.clone()).await.unwrap();
+        let mut ids = Vec::new();
+        while ids.len() < 2 {
+            let event = tokio::time::timeout(Duration::from_secs(5), rx.recv())
+                .await
+                .expect("GitHub events were not emitted")
+                .unwrap();
+            ids.push(event.payload["id"].as_str().unwrap().to_string());
+        }
+        assert_eq!(ids, vec!["10", "12"]);
+        runner.stop_all().await;
+        drop(runner);
+
+        // Restart: a new runner on the same database file loads the cursor
+        server.publish(
+            "\"v2\"",
+            &github_json(&[(11, "IssuesEvent"), (12, "PushEvent"), (13, "PushEvent")]),
+        );
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let runner = WatcherRunner::new(tx)
+            .with_database(open())
+            .with_github_api_url(api_url);
+        runner.start_watcher(watcher).await.unwrap();
+        let event = tokio::time::timeout(Duration::from_secs(5), rx.recv())
+            .await
+            .expect("new GitHub event was not emitted")
+            .unwrap();
+        assert_eq!(event.payload["id"], "13");
+        assert!(
+            tokio::time::timeout(Duration::from_millis(500), rx.recv())
+                .await
+                .is_err()
+        );
+        runner.stop_all().await;
+    }
+
+    #[test]
+    fn test_github_cursor_not_advanced_when_send_fails() {
+        let watcher = github_watcher(vec![]);
+        let db = feed_db();
+        let page = github_page(&[(20, "PushEvent")]);
+
+        let (closed_tx, closed_rx) = mpsc::unbounded_channel();
+        drop(closed_rx);
+        let mut state = PollState::new();
+        let conn = lock_db(&db);
+        assert!(
+            emit_github_events(&watcher, &[], &page, &closed_tx, &mut state, Some(&conn)).is_err()
+        );
+        assert_eq!(
+            persistence::get_watcher_cursor(&conn, &watcher.id).unwrap(),
+            None
+        );
+
+        // The event is delivered on the next poll instead of being lost
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        emit_github_events(&watcher, &[], &page, &tx, &mut state, Some(&conn)).unwrap();
+        assert_eq!(drain(&mut rx).len(), 1);
+        assert_eq!(
+            persistence::get_watcher_cursor(&conn, &watcher.id)
+                .unwrap()
+                .as_deref(),
+            Some("20")
+        );
+    }
+
+    #[test]
+    fn test_github_dedup_without_database() {
+        let watcher = github_watcher(vec![]);
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut state = PollState::new();
+
+        let page = github_page(&[(1, "PushEvent"), (2, "PushEvent")]);
+        emit_github_events(&watcher, &[], &page, &tx, &mut state, None).unwrap();
+        emit_github_events(&watcher, &[], &page, &tx, &mut state, None).unwrap();
+        assert_eq!(drain(&mut rx).len(), 2);
+        assert_eq!(state.last_github_event_id.as_deref(), Some("2"));
+    }
+
+    #[tokio::test]
+    async fn test_feed_watcher_without_database() {
+        let server = FeedServer::start("\"v1\"", &rss_fixture(&[("a", "First")])).await;
+        let watcher = Watcher::new(
+            WatcherKind::FeedWatch {
+                url: server.url.clone(),
+                keywords: vec![],
+                interval_secs: 300,
+            },
+            "Summarize".to_string(),
+            "ch".to_string(),
+        );
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut state = PollState::new();
+        let resources = feed_resources(None);
+
+        poll_watcher(&watcher, &tx, &mut state, &resources)
+            .await
+            .unwrap();
+        server.publish("\"v2\"", &rss_fixture(&[("b", "Second"), ("a", "First")]));
+        poll_watcher(&watcher, &tx, &mut state, &resources)
+            .await
+            .unwrap();
+        let events = drain(&mut rx);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].payload["guid"], "b");
+    }
+
+    /// Serves whatever HTML the test last set
+    struct StubPageFetcher(std::sync::Mutex<String>);
+
+    impl StubPageFetcher {
+        fn set(&self, html: &str) {
+            *self.0.lock().unwrap() = html.to_string();
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl PageFetcher for StubPageFetcher {
+        async fn get(&self, _url: &str, _headers: &[(&str, &str)]) -> Result<reqwest::Response> {
+            anyhow::bail!("Not used by page tests")
+        }
+
+        async fn fetch(&self, _url: &str) -> Result<String> {
+            Ok(self.0.lock().unwrap().clone())
+        }
+    }
+
+    fn price_page(price: &str, rendered_at: &str) -> String {
+        format!(
+            "<html><body><div id=\"product\"><h1>Espresso Machine</h1>\
+             <span>  {} </span><p>In stock</p></div>\
+             <footer>Rendered at {}</footer></body></html>",
+            price, rendered_at
+        )
+    }
+
+    fn page_watcher(change_threshold: f64) -> Watcher {
+        Watcher::new(
+            WatcherKind::PageWatch {
+                url: "https://shop.example.com/espresso".to_string(),
+                selector: Some("#product".to_string()),
+                region_start: None,
+                region_end: None,
+                interval_secs: 3600,
+                change_threshold,
+            },
+            "Tell me if the price changes".to_string(),
+            "ch".to_string(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_page_watcher_reports_selected_changes() {
+        let fetcher = Arc::new(StubPageFetcher(std::sync::Mutex::new(price_page(
+            "$499.00", "10:00",
+        ))));
+        let resources = PollResources {
+            db: Some(feed_db()),
+            page_fetcher: Some(fetcher.clone()),
+            ..Default::default()
+        };
+        let watcher = page_watcher(0.0);
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut state = PollState::new();
+
+        // Baseline, then churn outside the selector and in whitespace only
+        poll_watcher(&watcher, &tx, &mut state, &resources)
+            .await
+            .unwrap();
+        fetcher.set(&price_page("$499.00   ", "10:05"));
+        poll_watcher(&watcher, &tx, &mut state, &resources)
+            .await
+            .unwrap();
+        assert!(drain(&mut rx).is_empty());
+
+        fetcher.set(&price_page("$449.00", "10:10"));
+        poll_watcher(&watcher, &tx, &mut state, &resources)
+            .await
+            .unwrap();
+        let events = drain(&mut rx);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].kind, "page_changed");
+        assert_eq!(events[0].payload["diff"], "- $499.00\n+ $449.00");
+
+        // After a restart the stored snapshot is the baseline
+        let mut restarted = PollState::new();
+        poll_watcher(&watcher, &tx, &mut restarted, &resources)
+            .await
+            .unwrap();
+        assert!(drain(&mut rx).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_page_watcher_change_threshold() {
+        let fetcher = Arc::new(StubPageFetcher(std::sync::Mutex::new(price_page(
+            "$499.00", "10:00",
+        ))));
+        let resources = PollResources {
+            db: None,
+            page_fetcher: Some(fetcher.clone()),
+            ..Default::default()
+        };
+        // One changed line out of three is a ratio of 2/6
+        let watcher = page_watcher(0.5);
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut state = PollState::new();
+
+        poll_watcher(&watcher, &tx, &mut state, &resources)
+            .await
+            .unwrap();
+        fetcher.set(&price_page("$449.00", "10:00"));
+        poll_watcher(&watcher, &tx, &mut state, &resources)
+            .await
+            .unwrap();
+        assert!(drain(&mut rx).is_empty());
+
+        // Small changes accumulate against the last reported snapshot
+        fetcher.set(
+            "<html><body><div id=\"product\"><h1>Espresso Machine II</h1>\
+             <span>$449.00</span><p>Sold out</p></div></body></html>",
+        );
+        poll_watcher(&watcher, &tx, &mut state, &resources)
+            .await
+            .unwrap();
+        assert_eq!(drain(&mut rx).len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_page_watcher_requires_fetcher() {
+        let (tx, _rx) = mpsc::unbounded_channel();
+        let mut state = PollState::new();
+        let result = poll_watcher(
+            &page_watcher(0.0),
+            &tx,
+            &mut state,
+            &PollResources::default(),
+        )
+        .await;
+        assert!(result.is_err());
+    }
+
+    /// Fails the first `fail_first` fetches
+    struct FlakyPageFetcher {
+        attempts: std::sync::atomic::AtomicU32,
+        fail_first: u32,
+    }
+
+    #[async_trait::async_trait]
+    impl PageFetcher for FlakyPageFetcher {
+        async fn get(&self, _url: &str, _headers: &[(&str, &str)]) -> Result<reqwest::Response> {
+            anyhow::bail!("Not used by page tests")
+        }
+
+        async fn fetch(&self, _url: &str) -> Result<String> {
+            let attempt = self
+                .attempts
+                .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            if attempt < self.fail_first {
+                anyhow::bail!("HTTP 401 Unauthorized");
+            }
+            Ok(price_page("$499.00", "10:00"))
+        }
+    }
+
+    fn flaky_runner(
+        fail_first: u32,
+        db: Arc<std::sync::Mutex<Connection>>,
+    ) -> (
+        WatcherRunner,
+        Arc<FlakyPageFetcher>,
+        Watcher,
+        mpsc::UnboundedReceiver<WatcherEvent>,
+    ) {
+        let fetcher = Arc::new(FlakyPageFetcher {
+            attempts: Default::default(),
+            fail_first,
+        });
+        let config = WatcherConfig {
+            max_consecutive_errors: 4,
+            max_backoff_secs: 600,
+            ..Default::default()
+        };
+        let (tx, rx) = mpsc::unbounded_channel();
+        let runner = WatcherRunner::with_config(tx, config)
+            .with_database(db.clone())
+            .with_page_fetcher(fetcher.clone());
+        let mut watcher = page_watcher(0.0);
+        if let WatcherKind::PageWatch { interval_secs, .. } = &mut watcher.kind {
+            *interval_secs = 60;
+        }
+        persistence::save_watcher(&lock_db(&db), &watcher).unwrap();
+        (runner, fetcher, watcher, rx)
+    }
+
+    fn attempts(fetcher: &FlakyPageFetcher) -> u32 {
+        fetcher.attempts.load(std::sync::atomic::Ordering::SeqCst)
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_failing_watcher_backs_off_and_is_disabled() {
+        let db = feed_db();
+        let (runner, fetcher, watcher, mut rx) = flaky_runner(u32::MAX, db.clone());
+        runner.start_watcher(watcher.clone()).await.unwrap();
+        settle().await;
+        assert_eq!(attempts(&fetcher), 1);
+
+        // Retries wait 60-120s, then 120-240s, then 240-480s instead of every 60s
+        tokio::time::sleep(Duration::from_secs(59)).await;
+        settle().await;
+        assert_eq!(attempts(&fetcher), 1);
+        tokio::time::sleep(Duration::from_secs(61)).await;
+        settle().await;
+        assert_eq!(attempts(&fetcher), 2);
+
+        let health = persistence::get_watcher_health(&lock_db(&db), &watcher.id)
+            .unwrap()
+            .unwrap();
+        assert_eq!(health.status, health::HealthStatus::Failing);
+        assert_eq!(health.consecutive_errors, 2);
+        assert_eq!(health.last_error.as_deref(), Some("HTTP 401 Unauthorized"));
+        assert!(health.next_run.is_some());
+
+        tokio::time::sleep(Duration::from_secs(240 + 480)).await;
+        settle().await;
+        assert_eq!(attempts(&fetcher), 4);
+        assert!(!runner.is_running(&watcher.id).await);
+
+        let events = drain(&mut rx);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].kind, "watcher_disabled");
+        assert_eq!(events[0].payload["consecutive_errors"], 4);
+
+        let conn = lock_db(&db);
+        let health = persistence::get_watcher_health(&conn, &watcher.id)
+            .unwrap()
+            .unwrap();
+        assert_eq!(health.status, health::HealthStatus::Disabled);
+        assert!(health.next_run.is_none());
+        assert!(
+            !persistence::get_watcher_by_id(&conn, &watcher.id)
+                .unwrap()
+                .unwrap()
+                .active
+        );
+        assert_eq!(
+            persistence::get_dead_letters(&conn, Some(&watcher.id), 10)
+                .unwrap()
+                .len(),
+            4
+        );
+    }
+
+    #[tokio::test]
+    async fn test_github_watcher_with_revoked_token_backs_off_and_is_disabled() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        // A GitHub API that rejects every request, recording when each arrived
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let api_url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let requests_c = requests.clone();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                requests_c.lock().unwrap().push(std::time::Instant::now());
+                let mut buf = [0u8; 4096];
+                let _ = socket.read(&mut buf).await;
+                let body = r#"{"message":"Bad credentials"}"#;
+                let response = format!(
+                    "HTTP/1.1 401 Unauthorized\r\nContent-Type: application/json\r\n\
+                     Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                let _ = socket.write_all(response.as_bytes()).await;
+                let _ = socket.shutdown().await;
+            }
+        });
+
+        let db = feed_db();
+        let config = WatcherConfig {
+            min_poll_interval_secs: 0,
+            max_consecutive_errors: 3,
+            ..Default::default()
+        };
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let runner = WatcherRunner::with_config(tx, config)
+            .with_database(db.clone())
+            .with_github_api_url(api_url);
+        let mut watcher = github_watcher(vec![]);
+        if let WatcherKind::GitHubWatch { interval_secs, .. } = &mut watcher.kind {
+            *interval_secs = 1;
+        }
+        persistence::save_watcher(&lock_db(&db), &watcher).unwrap();
+        runner.start_watcher(watcher.clone()).await.unwrap();
+
+        let event = tokio::time::timeout(Duration::from_secs(15), rx.recv())
+            .await
+            .expect("watcher was not disabled")
+            .unwrap();
+        assert_eq!(event.kind, "watcher_disabled");
+        assert_eq!(event.payload["consecutive_errors"], 3);
+
+        // Retries waited 1-2s, then 2-4s, instead of the 1s interval
+        let requests = requests.lock().unwrap().clone();
+        assert_eq!(requests.len(), 3);
+        let slack = Duration::from_millis(100);
+        assert!(requests[1] - requests[0] + slack >= Duration::from_secs(1));
+        assert!(requests[2] - requests[1] + slack >= Duration::from_secs(2));
+        for _ in 0..50 {
+            if !runner.is_running(&watcher.id).await {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert!(!runner.is_running(&watcher.id).await);
+
+        let conn = lock_db(&db);
+        let health = persistence::get_watcher_health(&conn, &watcher.id)
+            .unwrap()
+            .unwrap();
+        assert_eq!(health.status, health::HealthStatus::Disabled);
+        assert!(health.last_error.unwrap().contains("401"));
+        assert_eq!(
+            persistence::get_dead_letters(&conn, Some(&watcher.id), 10)
+                .unwrap()
+                .len(),
+            3
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_watcher_recovers_from_failures() {
+        let db = feed_db();
+        let (runner, fetcher, watcher, _rx) = flaky_runner(2, db.clone());
+        runner.start_watcher(watcher.clone()).await.unwrap();
+        tokio::time::sleep(Duration::from_secs(120 + 240)).await;
+        settle().await;
+        let polls = attempts(&fetcher);
+        assert!(polls >= 3);
+
+        let health = persistence::get_watcher_health(&lock_db(&db), &watcher.id)
+            .unwrap()
+            .unwrap();
+        assert_eq!(health.status, health::HealthStatus::Ok);
+        assert!(health.last_success.is_some());
+
+        // Back to the normal interval
+        tokio::time::sleep(Duration::from_secs(60)).await;
+        settle().await;
+        assert_eq!(attempts(&fetcher), polls + 1);
+        assert!(runner.is_running(&watcher.id).await);
+    }
+
+    #[tokio::test]
+    async fn test_undelivered_events_are_dead_lettered() {
+        let db = feed_db();
+        let (tx, rx) = mpsc::unbounded_channel();
+        drop(rx);
+        let event = WatcherEvent::task("w1".to_string(), "Backup".to_string());
+        assert!(!deliver_task(&tx, &Some(db.clone()), event));
+
+        let conn = lock_db(&db);
+        let letters = persistence::get_dead_letters(&conn, Some("w1"), 10).unwrap();
+        assert_eq!(letters.len(), 1);
+        assert_eq!(letters[0].event.as_ref().unwrap().payload["task"], "Backup");
+    }
+
+    /// Records registered webhook paths
+    #[derive(Default)]
+    struct RecordingRoutes(std::sync::Mutex<HashMap<String, String>>);
+
+    impl WebhookRoutes for RecordingRoutes {
+        fn register(&self, watcher: &Watcher) -> Result<()> {
+            let path = watcher.webhook_path().unwrap_or_default().to_string();
+            self.0.lock().unwrap().insert(watcher.id.clone(), path);
+            Ok(())
+        }
+
+        fn unregister(&self, watcher_id: &str) {
+            self.0.lock().unwrap().remove(watcher_id);
+        }
+    }
+
+    fn webhook_watcher() -> Watcher {
+        Watcher::new(
+            WatcherKind::Webhook {
+                path: "ci".to_string(),
+                secret: "s3cret".to_string(),
+                scheme: crate::watcher::WebhookScheme::Generic,
+                max_body_bytes: 1024,
+            },
+            "Triage the build failure".to_string(),
+            "ch".to_string(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_webhook_watcher_registers_route() {
+        let (tx, _rx) = mpsc::unbounded_channel();
+        let routes = Arc::new(RecordingRoutes::default());
+        let runner = WatcherRunner::new(tx).with_webhook_routes(routes.clone());
+
+        let watcher = webhook_watcher();
+        let id = watcher.id.clone();
+        runner.start_watcher(watcher).await.unwrap();
+        assert!(runner.is_running(&id).await);
+        assert_eq!(
+            routes.0.lock().unwrap().get(&id).map(String::as_str),
+            Some("ci")
+        );
+
+        runner.stop_watcher(&id).await.unwrap();
+        assert!(routes.0.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_webhook_watcher_requires_routes() {
+        let (tx, _rx) = mpsc::unbounded_channel();
+        let runner = WatcherRunner::new(tx);
+        assert!(runner.start_watcher(webhook_watcher()).await.is_err());
+        assert_eq!(runner.active_count().await, 0);
+    }
+
+    fn scheduled_watcher(
+        cron_expr: &str,
+        misfire: crate::misfire::MisfirePolicy,
+        created_at: DateTime<Utc>,
+    ) -> Watcher {
+        let mut watcher = Watcher::new(
+            WatcherKind::Scheduled {
+                cron_expr: cron_expr.to_string(),
+                task: "Morning briefing".to_string(),
+                timezone: None,
+                misfire,
+            },
+            "Brief me".to_string(),
+            "ch".to_string(),
+        );
+        watcher.created_at = created_at;
+        watcher
+    }
+
+    fn utc(day: u32, hour: u32, min: u32, sec: u32) -> DateTime<Utc> {
+        use chrono::TimeZone;
+        Utc.with_ymd_and_hms(2026, 3, day, hour, min, sec).unwrap()
+    }
+
+    /// Let spawned watcher tasks run without advancing time
+    async fn settle() {
+        for _ in 0..10 {
+            tokio::task::yield_now().await;
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_scheduled_watcher_catches_up_after_restart() {
+        use crate::misfire::{ManualClock, MisfireAction, MisfirePolicy};
+
+        let db = feed_db();
+        // Last ran on the 1st; the daemon comes back on the 4th at noon
+        let watcher = scheduled_watcher(
+            "0 0 9 * * *",
+            MisfirePolicy {
+                action: MisfireAction::RunAll,
+                max_runs: 2,
+                ..Default::default()
+            },
+            utc(1, 8, 0, 0),
+        );
+        persistence::record_run_at(&lock_db(&db), &watcher.id, utc(1, 9, 0, 5)).unwrap();
+
+        let clock = Arc::new(ManualClock::new(utc(4, 12, 0, 0)));
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let runner = WatcherRunner::new(tx)
+            .with_database(db.clone())
+            .with_clock(clock.clone());
+        runner.start_watcher(watcher.clone()).await.unwrap();
+        settle().await;
+
+        let events = drain(&mut rx);
+        let missed: Vec<_> = events
+            .iter()
+            .map(|e| e.payload["scheduled_for"].as_str().unwrap().to_string())
+            .collect();
+        assert_eq!(
+            missed,
+            vec![utc(3, 9, 0, 0).to_rfc3339(), utc(4, 9, 0, 0).to_rfc3339()]
+        );
+        assert!(events.iter().all(|e| e.payload["catch_up"] == true));
+        assert_eq!(
+            persistence::get_last_run(&lock_db(&db), &watcher.id).unwrap(),
+            Some(utc(4, 12, 0, 0))
+        );
+
+        // Restarting again doesn't replay them
+        runner.stop_all().await;
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let runner = WatcherRunner::new(tx).with_database(db).with_clock(clock);
+        runner.start_watcher(watcher).await.unwrap();
+        settle().await;
+        assert!(drain(&mut rx).is_empty());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_scheduled_watcher_uses_local_time() {
+        use crate::misfire::{ManualClock, MisfirePolicy};
+
+        // 08:59:30 in New York on the day after clocks went forward
+        let clock = Arc::new(ManualClock::new(utc(9, 12, 59, 30)));
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let config = WatcherConfig {
+            timezone: chrono_tz::America::New_York,
+            ..Default::default()
+        };
+        let runner = WatcherRunner::with_config(tx, config).with_clock(clock.clone());
+        runner
+            .start_watcher(scheduled_watcher(
+                "0 9 * * *",
+                MisfirePolicy::default(),
+                utc(9, 12, 0, 0),
+            ))
+            .await
+            .unwrap();
+
+        // A watcher's own timezone wins over the runner's
+        let mut tokyo = scheduled_watcher("0 9 * * *", MisfirePolicy::default(), utc(9, 12, 0, 0));
+        if let WatcherKind::Scheduled { timezone, .. } = &mut tokyo.kind {
+            *timezone = Some("Asia/Tokyo".to_string());
+        }
+        runner.start_watcher(tokyo.clone()).await.unwrap();
+        settle().await;
+        assert!(drain(&mut rx).is_empty());
+
+        tokio::time::sleep(Duration::from_secs(60)).await;
+        settle().await;
+        let events = drain(&mut rx);
+        assert_eq!(events.len(), 1);
+        assert_ne!(events[0].watcher_id, tokyo.id);
+        assert!(events[0].payload.get("catch_up").is_none());
+
+        let mut bad = tokyo;
+        bad.id = "bad-tz".to_string();
+        if let WatcherKind::Scheduled { timezone, .. } = &mut bad.kind {
+            *timezone = Some("Atlantis/Capital".to_string());
+        }
+        assert!(runner.start_watcher(bad).await.is_err());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_scheduled_watcher_detects_clock_jump() {
+        use crate::misfire::{ManualClock, MisfireAction, MisfirePolicy};
+
+        let clock = Arc::new(ManualClock::new(utc(1, 8, 59, 30)));
+        let skip = MisfirePolicy {
+            action: MisfireAction::Skip,
+            grace_secs: 60,
+            ..Default::default()
+        };
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let runner = WatcherRunner::new(tx).with_clock(clock.clone());
+        runner
+            .start_watcher(scheduled_watcher("0 0 9 * * *", skip, clock.now()))
+            .await
+            .unwrap();
+
+        // On time
+        tokio::time::sleep(Duration::from_secs(31)).await;
+        let events = drain(&mut rx);
+        assert_eq!(events.len(), 1);
+        assert!(events[0].payload.get("catch_up").is_none());
+
+        // The machine sleeps through the next two mornings: wall time jumps
+        // while tokio time barely moves. Skip drops the stale runs...
+        clock.jump(chrono::Duration::days(2) + chrono::Duration::hours(1));
+        tokio::time::sleep(Duration::from_secs(61)).await;
+        assert!(drain(&mut rx).is_empty());
+
+        // ...and the schedule carries on normally afterwards
+        clock.jump(chrono::Duration::hours(22) + chrono::Duration::minutes(57));
+        tokio::time::sleep(Duration::from_secs(150)).await;
+        assert_eq!(drain(&mut rx).len(), 1);
+        runner.stop_all().await;
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_oneshot_misfire_policies() {
+        use crate::misfire::{ManualClock, MisfireAction, MisfirePolicy};
+
+        let clock = Arc::new(ManualClock::new(utc(2, 12, 0, 0)));
+        let db = feed_db();
+        let oneshot = |misfire| {
+            Watcher::new(
+                WatcherKind::OneShot {
+                    at: utc(2, 9, 0, 0),
+                    task: "Call the bank".to_string(),
+                    misfire,
+                },
+                "Remind me".to_string(),
+                "ch".to_string(),
+            )
+        };
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let runner = WatcherRunner::new(tx)
+            .with_database(db.clone())
+            .with_clock(clock.clone());
+
+        // Missed by three hours: skipped, or run late by default
+        let skipped = oneshot(MisfirePolicy {
+            action: MisfireAction::Skip,
+            ..Default::default()
+        });
+        runner.start_watcher(skipped).await.unwrap();
+        settle().await;
+        assert!(drain(&mut rx).is_empty());
+
+        let late = oneshot(MisfirePolicy::default());
+        runner.start_watcher(late.clone()).await.unwrap();
+        settle().await;
+        let events = drain(&mut rx);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].payload["catch_up"], true);
+
+        // Already fired before the restart
+        runner.start_watcher(late).await.unwrap();
+        settle().await;
+        assert!(drain(&mut rx).is_empty());
+    }
+
     #[tokio::test]
     async fn test_with_config() {
         let (tx, _rx) = mpsc::unbounded_channel();
@@ -1085,6 +2725,9 @@ mod tests {
             min_poll_interval_secs: 30,
             active_hours: None,
             enforce_active_hours: false,
+            timezone: Tz::UTC,
+            max_consecutive_errors: 3,
+            max_backoff_secs: 600,
         };
         let runner = WatcherRunner::with_config(tx, config);
         assert_eq!(runner.active_count().await, 0);
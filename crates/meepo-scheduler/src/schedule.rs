@@ -0,0 +1,651 @@
+//! Timezone-aware cron schedules and natural-language schedule parsing
+//!
+//! Cron expressions are evaluated on the local wall clock of an IANA timezone,
+//! so "0 9 * * MON" fires at 9:00 local time all year round. Across daylight
+//! saving changes:
+//!
+//! - a time skipped by the spring-forward gap fires once, when the clock jumps
+//!   (a 02:30 job runs at 03:00 on the night the clocks go forward)
+//! - a time repeated by the fall-back overlap fires once, on its first pass
+//!
+//! [`parse_schedule`] turns common phrases ("every weekday at 8:30", "first
+//! Monday of the month", "in 2 hours") into a cron expression or a single time.
+//! It is deterministic: anything it does not recognise is an error rather than
+//! a guess.
+
+use std::str::FromStr;
+
+use anyhow::{Context, Result, anyhow, bail};
+use chrono::{
+    DateTime, Datelike, Duration, LocalResult, NaiveDateTime, NaiveTime, TimeZone, Timelike, Utc,
+};
+pub use chrono_tz::Tz;
+
+/// Widest UTC offset change at a DST transition, used as slack when mapping
+/// between local and absolute times
+const DST_SLACK: Duration = Duration::hours(3);
+
+/// Parse an IANA timezone name; an empty name means the system timezone
+pub fn resolve_timezone(name: &str) -> Result<Tz> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Ok(local_timezone());
+    }
+    name.parse::<Tz>().map_err(|_| {
+        anyhow!(
+            "Unknown timezone '{}' (use an IANA name like 'Europe/Paris')",
+            name
+        )
+    })
+}
+
+/// The system timezone, or UTC if it cannot be determined
+pub fn local_timezone() -> Tz {
+    iana_time_zone::get_timezone()
+        .ok()
+        .and_then(|name| name.parse().ok())
+        .unwrap_or(Tz::UTC)
+}
+
+/// The instant a local wall-clock time refers to in `tz`.
+///
+/// Times repeated when clocks go back resolve to their first occurrence; times
+/// skipped when clocks go forward resolve to the moment the clock jumps.
+pub fn local_to_utc(tz: Tz, local: NaiveDateTime) -> DateTime<Utc> {
+    match tz.from_local_datetime(&local) {
+        LocalResult::Single(at) | LocalResult::Ambiguous(at, _) => at.with_timezone(&Utc),
+        LocalResult::None => {
+            // Find the end of the gap a minute at a time; gaps are at most a few hours
+            let start = local.with_second(0).unwrap_or(local);
+            (1..=DST_SLACK.num_minutes())
+                .map(|m| start + Duration::minutes(m))
+                .find_map(|t| tz.from_local_datetime(&t).earliest())
+                .map(|at| at.with_timezone(&Utc))
+                .unwrap_or_else(|| Utc.from_utc_datetime(&local))
+        }
+    }
+}
+
+/// A cron schedule evaluated on the wall clock of a timezone
+#[derive(Debug, Clone)]
+pub struct CronSchedule {
+    schedule: cron::Schedule,
+    tz: Tz,
+}
+
+impl CronSchedule {
+    /// Parse a cron expression. Five-field expressions (minute hour day month
+    /// weekday) are accepted as well as the six- and seven-field forms with
+    /// seconds (and year).
+    pub fn parse(expr: &str, tz: Tz) -> Result<Self> {
+        let schedule = cron::Schedule::from_str(&with_seconds(expr))
+            .with_context(|| format!("Invalid cron expression: {}", expr.trim()))?;
+        Ok(Self { schedule, tz })
+    }
+
+    pub fn timezone(&self) -> Tz {
+        self.tz
+    }
+
+    /// Fire times strictly after `t`, in order
+    pub fn after(&self, t: DateTime<Utc>) -> impl Iterator<Item = DateTime<Utc>> + '_ {
+        let mut prev = t;
+        self.local_times(t - DST_SLACK, false).filter(move |at| {
+            let next = *at > prev;
+            if next {
+                prev = *at;
+            }
+            next
+        })
+    }
+
+    /// Fire times at or before `t`, latest first
+    pub fn until(&self, t: DateTime<Utc>) -> impl Iterator<Item = DateTime<Utc>> + '_ {
+        let mut prev = t + Duration::milliseconds(1);
+        self.local_times(t + DST_SLACK, true).filter(move |at| {
+            let next = *at < prev;
+            if next {
+                prev = *at;
+            }
+            next
+        })
+    }
+
+    /// The next `n` fire times after `t`
+    pub fn upcoming(&self, t: DateTime<Utc>, n: usize) -> Vec<DateTime<Utc>> {
+        self.after(t).take(n).collect()
+    }
+
+    /// Occurrences near `t` on the local wall clock, mapped to instants.
+    /// Reversed iteration includes `t`'s own local time.
+    fn local_times(
+        &self,
+        t: DateTime<Utc>,
+        rev: bool,
+    ) -> Box<dyn Iterator<Item = DateTime<Utc>> + '_> {
+        // The cron crate walks UTC; feed it local wall time dressed as UTC
+        let local = Utc.from_utc_datetime(&t.with_timezone(&self.tz).naive_local());
+        let tz = self.tz;
+        let to_instant = move |at: DateTime<Utc>| local_to_utc(tz, at.naive_utc());
+        if rev {
+            Box::new(
+                self.schedule
+                    .after(&(local + Duration::milliseconds(1)))
+                    .rev()
+                    .map(to_instant),
+            )
+        } else {
+            Box::new(self.schedule.after(&local).map(to_instant))
+        }
+    }
+}
+
+/// A cron expression with a seconds field, added to five-field expressions
+fn with_seconds(expr: &str) -> String {
+    let expr = expr.trim();
+    if expr.split_whitespace().count() == 5 {
+        format!("0 {}", expr)
+    } else {
+        expr.to_string()
+    }
+}
+
+/// A schedule read from a natural-language phrase
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParsedSchedule {
+    /// Recurring, as a six-field cron expression (with seconds)
+    Cron(String),
+    /// Once, at this time
+    Once(DateTime<Utc>),
+}
+
+impl ParsedSchedule {
+    /// The first `n` fire times after `now`
+    pub fn preview(&self, now: DateTime<Utc>, tz: Tz, n: usize) -> Result<Vec<DateTime<Utc>>> {
+        match self {
+            Self::Cron(expr) => Ok(CronSchedule::parse(expr, tz)?.upcoming(now, n)),
+            Self::Once(at) => Ok(Some(*at)
+                .filter(|at| *at > now)
+                .into_iter()
+                .take(n)
+                .collect()),
+        }
+    }
+}
+
+const SUPPORTED: &str = "try e.g. 'every weekday at 8:30', 'every monday and thursday at 6pm', \
+     'every 15 minutes', 'first monday of the month at 9', 'every month on the 15th', \
+     'in 2 hours', 'tomorrow at noon', or a cron expression";
+
+/// Parse a schedule phrase, or a cron expression, in timezone `tz`.
+///
+/// Day-level schedules without a time run at 9:00.
+pub fn parse_schedule(text: &str, now: DateTime<Utc>, tz: Tz) -> Result<ParsedSchedule> {
+    let normalized = text.trim().to_lowercase().replace([',', '.'], " ");
+    let words: Vec<&str> = normalized.split_whitespace().collect();
+    if words.is_empty() {
+        bail!("Empty schedule ({})", SUPPORTED);
+    }
+
+    if let Some(parsed) = parse_phrase(&words, now, tz)? {
+        return Ok(parsed);
+    }
+    if CronSchedule::parse(text, tz).is_ok() {
+        return Ok(ParsedSchedule::Cron(with_seconds(text)));
+    }
+    bail!(
+        "Could not understand schedule '{}' ({})",
+        text.trim(),
+        SUPPORTED
+    )
+}
+
+/// Parse a phrase; `Ok(None)` when it is not one we recognise
+fn parse_phrase(words: &[&str], now: DateTime<Utc>, tz: Tz) -> Result<Option<ParsedSchedule>> {
+    // Split off a trailing "at <time>"
+    let (head, time) = match words.iter().position(|w| *w == "at") {
+        Some(i) => (&words[..i], Some(parse_time(&words[i + 1..].concat())?)),
+        None => (words, None),
+    };
+    let time_or_default = || time.unwrap_or_else(|| NaiveTime::from_hms_opt(9, 0, 0).unwrap());
+
+    let local_now = now.with_timezone(&tz).naive_local();
+    let once_on = |date: chrono::NaiveDate| {
+        ParsedSchedule::Once(local_to_utc(tz, date.and_time(time_or_default())))
+    };
+
+    match head {
+        // "in 2 hours", "in an hour"
+        ["in", amount, unit] => {
+            let amount = parse_count(amount)?;
+            let unit = duration_unit(unit)
+                .ok_or_else(|| anyhow!("Unknown time unit '{}' ({})", unit, SUPPORTED))?;
+            let offset = unit
+                .checked_mul(amount as i32)
+                .ok_or_else(|| anyhow!("Schedule is too far in the future"))?;
+            return Ok(Some(ParsedSchedule::Once(now + offset)));
+        }
+        // "at 17:00": the next time the clock reads 17:00
+        [] => {
+            let Some(time) = time else { return Ok(None) };
+            let today = local_now.date().and_time(time);
+            let date = if today > local_now {
+                local_now.date()
+            } else {
+                local_now.date().succ_opt().unwrap_or(local_now.date())
+            };
+            return Ok(Some(once_on(date)));
+        }
+        ["today"] => {
+            let at = local_to_utc(tz, local_now.date().and_time(time_or_default()));
+            if at <= now {
+                bail!("That time has already passed today");
+            }
+            return Ok(Some(ParsedSchedule::Once(at)));
+        }
+        ["tomorrow"] => {
+            let tomorrow = local_now.date().succ_opt().unwrap_or(local_now.date());
+            return Ok(Some(once_on(tomorrow)));
+        }
+        // "next friday": the first Friday after today
+        ["next", day] => {
+            let Some(day) = weekday(day) else {
+                return Ok(None);
+            };
+            let mut date = local_now.date();
+            loop {
+                date = date
+                    .succ_opt()
+                    .ok_or_else(|| anyhow!("Date out of range"))?;
+                if date.weekday() == day {
+                    break;
+                }
+            }
+            return Ok(Some(once_on(date)));
+        }
+        _ => {}
+    }
+
+    let at_time = |dom: &str, dow: &str| {
+        let time = time_or_default();
+        ParsedSchedule::Cron(format!(
+            "0 {} {} {} * {}",
+            time.minute(),
+            time.hour(),
+            dom,
+            dow
+        ))
+    };
+
+    // Recurring phrases may start with "every", "on the" or "the"
+    let body = match head {
+        ["every", rest @ ..]
+        | ["on", "the", rest @ ..]
+        | ["the", rest @ ..]
+        | ["on", rest @ ..] => rest,
+        _ => head,
+    };
+
+    let parsed = match body {
+        ["minute"] => ParsedSchedule::Cron("0 * * * * *".to_string()),
+        ["hour"] | ["hourly"] => ParsedSchedule::Cron("0 0 * * * *".to_string()),
+        [count, unit] if duration_unit(unit).is_some() => {
+            let count = parse_count(count)?;
+            let field = match duration_unit(unit) {
+                Some(u) if u == Duration::minutes(1) => 60,
+                Some(u) if u == Duration::hours(1) => 24,
+                _ => bail!(
+                    "Only minute and hour intervals can repeat evenly; use a day or weekday schedule instead"
+                ),
+            };
+            if count == 0 || field % count != 0 {
+                bail!(
+                    "Every {} {} does not divide evenly into {}; pick a divisor of {}",
+                    count,
+                    unit,
+                    if field == 60 { "an hour" } else { "a day" },
+                    field
+                );
+            }
+            let step = if count == 1 {
+                "*".to_string()
+            } else {
+                format!("*/{}", count)
+            };
+            if field == 60 {
+                ParsedSchedule::Cron(format!("0 {} * * * *", step))
+            } else {
+                ParsedSchedule::Cron(format!("0 0 {} * * *", step))
+            }
+        }
+        ["day"] | ["daily"] | ["morning"] => at_time("*", "*"),
+        ["weekday"] | ["weekdays"] => at_time("*", "MON-FRI"),
+        ["weekend"] | ["weekends"] => at_time("*", "SAT,SUN"),
+        ["week"] | ["weekly"] => at_time("*", "MON"),
+        ["month"] | ["monthly"] => at_time("1", "*"),
+        // "month on the 15th", "monthly on the 1st"
+        ["month" | "monthly", "on", "the", day] | ["month" | "monthly", "on", day] => {
+            let day = day_of_month(day)?;
+            at_time(&day.to_string(), "*")
+        }
+        // "first monday of the month"
+        [nth, day, "of", "the" | "every" | "each", "month"] | [nth, day, "of", "month"]
+            if weekday(day).is_some() =>
+        {
+            let first = match *nth {
+                "first" | "1st" => 1,
+                "second" | "2nd" => 8,
+                "third" | "3rd" => 15,
+                "fourth" | "4th" => 22,
+                "last" => bail!(
+                    "'last {} of the month' cannot be expressed as a cron schedule; use 'fourth' or a day of the month",
+                    day
+                ),
+                _ => return Ok(None),
+            };
+            at_time(
+                &format!("{}-{}", first, first + 6),
+                cron_weekday(day).unwrap(),
+            )
+        }
+        // "monday", "monday and thursday", "mon wed fri"
+        days if !days.is_empty()
+            && days
+                .iter()
+                .all(|w| *w == "and" || cron_weekday(w).is_some()) =>
+        {
+            let mut names: Vec<&str> = Vec::new();
+            for day in days.iter().filter(|w| **w != "and") {
+                let name = cron_weekday(day).unwrap();
+                if !names.contains(&name) {
+                    names.push(name);
+                }
+            }
+            at_time("*", &names.join(","))
+        }
+        _ => return Ok(None),
+    };
+    Ok(Some(parsed))
+}
+
+/// "8", "8:30", "8am", "8:30pm", "20:15", "noon", "midnight"
+fn parse_time(text: &str) -> Result<NaiveTime> {
+    let invalid = || anyhow!("Could not understand the time '{}'", text);
+    match text {
+        "noon" | "midday" => return Ok(NaiveTime::from_hms_opt(12, 0, 0).unwrap()),
+        "midnight" => return Ok(NaiveTime::from_hms_opt(0, 0, 0).unwrap()),
+        _ => {}
+    }
+    let (clock, meridiem) = if let Some(t) = text.strip_suffix("am") {
+        (t, Some(false))
+    } else if let Some(t) = text.strip_suffix("pm") {
+        (t, Some(true))
+    } else {
+        (text, None)
+    };
+    let (hour, minute) = match clock.split_once(':') {
+        Some((h, m)) => (h, m),
+        None => (clock, "0"),
+    };
+    let mut hour: u32 = hour.parse().map_err(|_| invalid())?;
+    let minute: u32 = minute.parse().map_err(|_| invalid())?;
+    match meridiem {
+        Some(pm) => {
+            if !(1..=12).contains(&hour) {
+                return Err(invalid());
+            }
+            hour = hour % 12 + if pm { 12 } else { 0 };
+        }
+        None if hour > 23 => return Err(invalid()),
+        None => {}
+    }
+    NaiveTime::from_hms_opt(hour, minute, 0).ok_or_else(invalid)
+}
+
+fn parse_count(word: &str) -> Result<u32> {
+    match word {
+        "a" | "an" | "one" => Ok(1),
+        "two" => Ok(2),
+        "three" => Ok(3),
+        "five" => Ok(5),
+        "ten" => Ok(10),
+        "fifteen" => Ok(15),
+        "thirty" => Ok(30),
+        _ => word
+            .parse()
+            .map_err(|_| anyhow!("Expected a number, got '{}'", word)),
+    }
+}
+
+fn duration_unit(word: &str) -> Option<Duration> {
+    match word {
+        "minute" | "minutes" | "min" | "mins" => Some(Duration::minutes(1)),
+        "hour" | "hours" | "hr" | "hrs" => Some(Duration::hours(1)),
+        "day" | "days" => Some(Duration::days(1)),
+        "week" | "weeks" => Some(Duration::weeks(1)),
+        _ => None,
+    }
+}
+
+fn day_of_month(word: &str) -> Result<u32> {
+    let digits = word.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+    match digits.parse::<u32>() {
+        Ok(day @ 1..=28) => Ok(day),
+        Ok(29..=31) => bail!(
+            "Day {} does not occur in every month; pick a day from 1 to 28",
+            digits
+        ),
+        _ => bail!("Could not understand the day of the month '{}'", word),
+    }
+}
+
+fn weekday(word: &str) -> Option<chrono::Weekday> {
+    use chrono::Weekday::*;
+    Some(match word {
+        "monday" | "mon" => Mon,
+        "tuesday" | "tue" | "tues" => Tue,
+        "wednesday" | "wed" => Wed,
+        "thursday" | "thu" | "thur" | "thurs" => Thu,
+        "friday" | "fri" => Fri,
+        "saturday" | "sat" => Sat,
+        "sunday" | "sun" => Sun,
+        _ => return None,
+    })
+}
+
+/// A weekday as a cron field, accepting plurals ("fridays")
+fn cron_weekday(word: &str) -> Option<&'static str> {
+    use chrono::Weekday::*;
+    let day = weekday(word).or_else(|| weekday(word.strip_suffix('s')?))?;
+    Some(match day {
+        Mon => "MON",
+        Tue => "TUE",
+        Wed => "WED",
+        Thu => "THU",
+        Fri => "FRI",
+        Sat => "SAT",
+        Sun => "SUN",
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NEW_YORK: Tz = chrono_tz::America::New_York;
+
+    fn utc(month: u32, day: u32, hour: u32, min: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, month, day, hour, min, 0)
+            .unwrap()
+    }
+
+    fn fmt(times: &[DateTime<Utc>]) -> Vec<String> {
+        times
+            .iter()
+            .map(|t| {
+                t.with_timezone(&NEW_YORK)
+                    .format("%m-%d %H:%M %Z")
+                    .to_string()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_cron_follows_local_wall_clock() {
+        let schedule = CronSchedule::parse("0 9 * * MON", NEW_YORK).unwrap();
+        // Mondays at 9:00 in New York, on both sides of the March 8 change
+        let runs = schedule.upcoming(utc(3, 1, 0, 0), 3);
+        assert_eq!(
+            fmt(&runs),
+            ["03-02 09:00 EST", "03-09 09:00 EDT", "03-16 09:00 EDT"]
+        );
+        assert_eq!(runs[0], utc(3, 2, 14, 0));
+        assert_eq!(runs[1], utc(3, 9, 13, 0));
+    }
+
+    #[test]
+    fn test_spring_forward_gap_fires_once_at_the_jump() {
+        // 02:30 does not exist on March 8 in New York; the run happens at 03:00
+        let schedule = CronSchedule::parse("0 30 2 * * *", NEW_YORK).unwrap();
+        let runs = schedule.upcoming(utc(3, 7, 12, 0), 3);
+        assert_eq!(
+            fmt(&runs),
+            ["03-08 03:00 EDT", "03-09 02:30 EDT", "03-10 02:30 EDT"]
+        );
+
+        // Several runs inside the gap collapse into one
+        let schedule = CronSchedule::parse("0 */15 2 * * *", NEW_YORK).unwrap();
+        let runs = schedule.upcoming(utc(3, 8, 6, 0), 2);
+        assert_eq!(fmt(&runs), ["03-08 03:00 EDT", "03-09 02:00 EDT"]);
+    }
+
+    #[test]
+    fn test_fall_back_overlap_fires_once() {
+        // 01:30 happens twice on November 1; only the first one fires
+        let schedule = CronSchedule::parse("0 30 1 * * *", NEW_YORK).unwrap();
+        let runs = schedule.upcoming(utc(10, 31, 12, 0), 2);
+        assert_eq!(fmt(&runs), ["11-01 01:30 EDT", "11-02 01:30 EST"]);
+
+        // Asking again from inside the repeated hour does not fire it a second time
+        let second_pass = utc(11, 1, 6, 10); // 01:10 EST
+        assert_eq!(fmt(&schedule.upcoming(second_pass, 1)), ["11-02 01:30 EST"]);
+        // ...and looking back from there still finds the first pass
+        assert_eq!(
+            fmt(&schedule.until(second_pass).take(2).collect::<Vec<_>>()),
+            ["11-01 01:30 EDT", "10-31 01:30 EDT"]
+        );
+    }
+
+    #[test]
+    fn test_until_includes_exact_time() {
+        let schedule = CronSchedule::parse("0 0 9 * * *", Tz::UTC).unwrap();
+        assert_eq!(
+            schedule.until(utc(3, 2, 9, 0)).take(2).collect::<Vec<_>>(),
+            vec![utc(3, 2, 9, 0), utc(3, 1, 9, 0)]
+        );
+        assert_eq!(
+            schedule.after(utc(3, 2, 9, 0)).next(),
+            Some(utc(3, 3, 9, 0))
+        );
+    }
+
+    #[test]
+    fn test_resolve_timezone() {
+        assert_eq!(
+            resolve_timezone("Europe/Paris").unwrap(),
+            chrono_tz::Europe::Paris
+        );
+        assert!(resolve_timezone("Mars/Olympus_Mons").is_err());
+        assert!(resolve_timezone("").is_ok());
+    }
+
+    fn cron(text: &str) -> String {
+        match parse_schedule(text, utc(3, 4, 15, 0), NEW_YORK).unwrap() {
+            ParsedSchedule::Cron(expr) => expr,
+            other => panic!("expected cron for '{}', got {:?}", text, other),
+        }
+    }
+
+    #[test]
+    fn test_recurring_phrases() {
+        assert_eq!(cron("every weekday at 8:30"), "0 30 8 * * MON-FRI");
+        assert_eq!(cron("Every day at 6pm"), "0 0 18 * * *");
+        assert_eq!(cron("daily"), "0 0 9 * * *");
+        assert_eq!(cron("every 15 minutes"), "0 */15 * * * *");
+        assert_eq!(cron("every 2 hours"), "0 0 */2 * * *");
+        assert_eq!(cron("every hour"), "0 0 * * * *");
+        assert_eq!(cron("every minute"), "0 * * * * *");
+        assert_eq!(
+            cron("every monday and thursday at 7:45am"),
+            "0 45 7 * * MON,THU"
+        );
+        assert_eq!(
+            cron("every mon, wed, fri at noon"),
+            "0 0 12 * * MON,WED,FRI"
+        );
+        assert_eq!(cron("on fridays at 17:00"), "0 0 17 * * FRI");
+        assert_eq!(cron("weekends at 10am"), "0 0 10 * * SAT,SUN");
+        assert_eq!(cron("first Monday of the month"), "0 0 9 1-7 * MON");
+        assert_eq!(
+            cron("every third friday of the month at 4pm"),
+            "0 0 16 15-21 * FRI"
+        );
+        assert_eq!(cron("every month on the 15th at 12am"), "0 0 0 15 * *");
+        assert_eq!(cron("0 9 * * MON"), "0 0 9 * * MON");
+        assert_eq!(cron("0 0 9 * * MON"), "0 0 9 * * MON");
+    }
+
+    #[test]
+    fn test_first_monday_preview() {
+        let parsed =
+            parse_schedule("first monday of the month at 9", utc(3, 4, 15, 0), NEW_YORK).unwrap();
+        let runs = parsed.preview(utc(3, 4, 15, 0), NEW_YORK, 3).unwrap();
+        assert_eq!(
+            fmt(&runs),
+            ["04-06 09:00 EDT", "05-04 09:00 EDT", "06-01 09:00 EDT"]
+        );
+    }
+
+    #[test]
+    fn test_one_shot_phrases() {
+        // Wednesday March 4, 10:00 in New York
+        let now = utc(3, 4, 15, 0);
+        let once = |text: &str| match parse_schedule(text, now, NEW_YORK).unwrap() {
+            ParsedSchedule::Once(at) => fmt(&[at]).remove(0),
+            other => panic!("expected a single time for '{}', got {:?}", text, other),
+        };
+        assert_eq!(once("in 2 hours"), "03-04 12:00 EST");
+        assert_eq!(once("in an hour"), "03-04 11:00 EST");
+        assert_eq!(once("in 30 minutes"), "03-04 10:30 EST");
+        assert_eq!(once("in 3 days"), "03-07 10:00 EST");
+        assert_eq!(once("at 5pm"), "03-04 17:00 EST");
+        assert_eq!(once("at 8:00"), "03-05 08:00 EST");
+        assert_eq!(once("today at 11:15"), "03-04 11:15 EST");
+        assert_eq!(once("tomorrow"), "03-05 09:00 EST");
+        assert_eq!(once("tomorrow at midnight"), "03-05 00:00 EST");
+        assert_eq!(once("next friday at 9:30am"), "03-06 09:30 EST");
+        assert_eq!(once("next wednesday"), "03-11 09:00 EDT");
+    }
+
+    #[test]
+    fn test_unrecognised_phrases_are_errors() {
+        let now = utc(3, 4, 15, 0);
+        for text in [
+            "",
+            "whenever you feel like it",
+            "every 7 minutes",
+            "every 3 days",
+            "last friday of the month",
+            "every month on the 31st",
+            "today at 9am",
+            "at 25:00",
+            "every tuesday at 13pm",
+        ] {
+            assert!(
+                parse_schedule(text, now, NEW_YORK).is_err(),
+                "'{}' should not parse",
+                text
+            );
+        }
+    }
+}
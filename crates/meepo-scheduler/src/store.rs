@@ -0,0 +1,118 @@
+//! The watcher repository
+//!
+//! `scheduler_watchers` is the one place watchers live. The agent's watcher
+//! tools write to it through a `WatcherStore`, the daemon reads it to restart
+//! watchers, and the runner marks watchers it disables there — so listing,
+//! cancelling and restarting all see the same rows.
+
+use std::sync::{Arc, Mutex};
+
+use anyhow::{Context, Result};
+use rusqlite::Connection;
+
+use crate::persistence;
+use crate::watcher::Watcher;
+
+/// Saves, loads and deactivates watchers
+#[derive(Clone)]
+pub struct WatcherStore {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl WatcherStore {
+    pub fn new(conn: Arc<Mutex<Connection>>) -> Self {
+        Self { conn }
+    }
+
+    /// Insert a watcher, or update the one with the same ID
+    pub async fn save(&self, watcher: &Watcher) -> Result<()> {
+        let watcher = watcher.clone();
+        self.with_conn(move |conn| persistence::save_watcher(conn, &watcher))
+            .await
+    }
+
+    /// A watcher by ID, active or not
+    pub async fn get(&self, id: &str) -> Result<Option<Watcher>> {
+        let id = id.to_string();
+        self.with_conn(move |conn| persistence::get_watcher_by_id(conn, &id))
+            .await
+    }
+
+    /// All active watchers, oldest first
+    pub async fn active(&self) -> Result<Vec<Watcher>> {
+        self.with_conn(persistence::get_active_watchers).await
+    }
+
+    /// Mark a watcher inactive; false when there is no such watcher
+    pub async fn deactivate(&self, id: &str) -> Result<bool> {
+        let id = id.to_string();
+        self.with_conn(move |conn| persistence::deactivate_watcher(conn, &id))
+            .await
+    }
+
+    /// Remove a watcher for good; false when there is no such watcher
+    pub async fn delete(&self, id: &str) -> Result<bool> {
+        let id = id.to_string();
+        self.with_conn(move |conn| persistence::delete_watcher(conn, &id))
+            .await
+    }
+
+    async fn with_conn<T: Send + 'static>(
+        &self,
+        f: impl FnOnce(&Connection) -> Result<T> + Send + 'static,
+    ) -> Result<T> {
+        let conn = Arc::clone(&self.conn);
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+            f(&conn)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::watcher::WatcherKind;
+
+    #[tokio::test]
+    async fn test_watcher_store() {
+        let conn = Connection::open_in_memory().unwrap();
+        persistence::init_watcher_tables(&conn).unwrap();
+        let store = WatcherStore::new(Arc::new(Mutex::new(conn)));
+
+        let mut first = Watcher::new(
+            WatcherKind::FileWatch {
+                path: "/tmp/inbox".to_string(),
+            },
+            "File new documents".to_string(),
+            "internal".to_string(),
+        );
+        first.created_at -= chrono::Duration::minutes(5);
+        let second = Watcher::new(
+            WatcherKind::MessageWatch {
+                keyword: "urgent".to_string(),
+            },
+            "Page me".to_string(),
+            "slack".to_string(),
+        );
+        store.save(&second).await.unwrap();
+        store.save(&first).await.unwrap();
+
+        let active = store.active().await.unwrap();
+        let ids: Vec<_> = active.iter().map(|w| w.id.as_str()).collect();
+        assert_eq!(ids, vec![first.id.as_str(), second.id.as_str()]);
+
+        assert!(store.deactivate(&first.id).await.unwrap());
+        assert_eq!(store.active().await.unwrap().len(), 1);
+        let stored = store.get(&first.id).await.unwrap().unwrap();
+        assert!(!stored.active);
+        assert_eq!(stored.action, "File new documents");
+
+        assert!(store.delete(&first.id).await.unwrap());
+        assert!(store.get(&first.id).await.unwrap().is_none());
+        assert!(!store.deactivate("missing").await.unwrap());
+        assert!(!store.delete("missing").await.unwrap());
+    }
+}
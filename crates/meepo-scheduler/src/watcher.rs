@@ -7,6 +7,11 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::feed::FeedItem;
+use crate::gate::EventRules;
+use crate::misfire::MisfirePolicy;
+use crate::page::PageDiff;
+
 /// A watcher monitors a specific source and triggers actions when conditions are met
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Watcher {
@@ -27,6 +32,10 @@ pub struct Watcher {
 
     /// When this watcher was created
     pub created_at: DateTime<Utc>,
+
+    /// Which of its events reach the agent, and how often
+    #[serde(default, skip_serializing_if = "EventRules::is_empty")]
+    pub rules: EventRules,
 }
 
 impl Watcher {
@@ -39,6 +48,25 @@ impl Watcher {
             reply_channel,
             active: true,
             created_at: Utc::now(),
+            rules: EventRules::default(),
+        }
+    }
+
+    /// Set which events reach the agent, and how often
+    pub fn with_rules(mut self, rules: EventRules) -> Self {
+        self.rules = rules;
+        self
+    }
+
+    /// Path segment under `/hooks/` a webhook watcher receives requests on
+    /// (its configured path, or its ID when none was given)
+    pub fn webhook_path(&self) -> Option<&str> {
+        match &self.kind {
+            WatcherKind::Webhook { path, .. } if !path.trim().is_empty() => {
+                Some(path.trim().trim_matches('/'))
+            }
+            WatcherKind::Webhook { .. } => Some(&self.id),
+            _ => None,
         }
     }
 
@@ -79,16 +107,58 @@ impl Watcher {
                     repo, events, interval_secs
                 )
             }
+            WatcherKind::FeedWatch {
+                url,
+                keywords,
+                interval_secs,
+            } => {
+                let mut desc = format!("Feed watcher for {} (every {}s", url, interval_secs);
+                if !keywords.is_empty() {
+                    desc.push_str(&format!(", keywords: {}", keywords.join(", ")));
+                }
+                desc.push(')');
+                desc
+            }
+            WatcherKind::PageWatch {
+                url,
+                selector,
+                interval_secs,
+                ..
+            } => {
+                let mut desc = format!("Page watcher for {}", url);
+                if let Some(selector) = selector {
+                    desc.push_str(&format!(" [{}]", selector));
+                }
+                desc.push_str(&format!(" (every {}s)", interval_secs));
+                desc
+            }
+            WatcherKind::Webhook { scheme, .. } => {
+                format!(
+                    "Webhook watcher on /hooks/{} ({} signatures)",
+                    self.webhook_path().unwrap_or_default(),
+                    scheme
+                )
+            }
             WatcherKind::FileWatch { path } => {
                 format!("File watcher for {}", path)
             }
             WatcherKind::MessageWatch { keyword } => {
                 format!("Message watcher for keyword: {}", keyword)
             }
-            WatcherKind::Scheduled { cron_expr, task } => {
+            WatcherKind::Scheduled {
+                cron_expr,
+                task,
+                timezone: Some(tz),
+                ..
+            } => {
+                format!("Scheduled task '{}' (cron: {}, {})", task, cron_expr, tz)
+            }
+            WatcherKind::Scheduled {
+                cron_expr, task, ..
+            } => {
                 format!("Scheduled task '{}' (cron: {})", task, cron_expr)
             }
-            WatcherKind::OneShot { at, task } => {
+            WatcherKind::OneShot { at, task, .. } => {
                 format!("One-shot task '{}' at {}", task, at)
             }
         }
@@ -136,6 +206,64 @@ pub enum WatcherKind {
         github_token: Option<String>,
     },
 
+    /// Watch an RSS, Atom or JSON Feed for new items
+    FeedWatch {
+        /// Feed URL
+        url: String,
+
+        /// Only report items whose title or summary mentions one of these (case-insensitive)
+        #[serde(default)]
+        keywords: Vec<String>,
+
+        /// How often to poll the feed (in seconds)
+        #[serde(default = "default_feed_interval_secs")]
+        interval_secs: u64,
+    },
+
+    /// Watch a web page for meaningful content changes
+    PageWatch {
+        /// Page URL
+        url: String,
+
+        /// Only watch the text of elements matching this CSS selector
+        #[serde(default)]
+        selector: Option<String>,
+
+        /// Only watch text after the first line containing this marker
+        #[serde(default)]
+        region_start: Option<String>,
+
+        /// Only watch text before the first following line containing this marker
+        #[serde(default)]
+        region_end: Option<String>,
+
+        /// How often to fetch the page (in seconds)
+        #[serde(default = "default_page_interval_secs")]
+        interval_secs: u64,
+
+        /// Minimum fraction of changed lines (0.0-1.0) before a change is reported
+        #[serde(default)]
+        change_threshold: f64,
+    },
+
+    /// Receive signed HTTP POSTs on the gateway at `/hooks/<path>`
+    Webhook {
+        /// Path segment under `/hooks/` (defaults to the watcher ID)
+        #[serde(default)]
+        path: String,
+
+        /// Shared secret used to verify request signatures
+        secret: String,
+
+        /// How requests are signed
+        #[serde(default)]
+        scheme: WebhookScheme,
+
+        /// Largest accepted payload (in bytes)
+        #[serde(default = "default_webhook_max_body_bytes")]
+        max_body_bytes: usize,
+    },
+
     /// Watch filesystem for changes
     FileWatch {
         /// Path to file or directory to watch
@@ -155,6 +283,15 @@ pub enum WatcherKind {
 
         /// Description of the task to run
         task: String,
+
+        /// IANA timezone the cron expression is read in (e.g. "Europe/Paris");
+        /// defaults to the configured user timezone
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        timezone: Option<String>,
+
+        /// What to do about runs missed while the daemon was down or asleep
+        #[serde(default)]
+        misfire: MisfirePolicy,
     },
 
     /// Run a task once at a specific time
@@ -164,16 +301,85 @@ pub enum WatcherKind {
 
         /// Description of the task to run
         task: String,
+
+        /// Whether to still run if `at` passed while the daemon was down or asleep
+        #[serde(default)]
+        misfire: MisfirePolicy,
     },
 }
 
+fn default_feed_interval_secs() -> u64 {
+    1800
+}
+
+fn default_page_interval_secs() -> u64 {
+    3600
+}
+
+fn default_webhook_max_body_bytes() -> usize {
+    256 * 1024
+}
+
+/// Signature schemes accepted by webhook watchers
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookScheme {
+    /// `X-Hub-Signature-256: sha256=<hmac of body>`, deduplicated by `X-GitHub-Delivery`
+    GitHub,
+    /// `Stripe-Signature: t=<unix>,v1=<hmac of "t.body">`
+    Stripe,
+    /// `X-Webhook-Timestamp: <unix>` and `X-Webhook-Signature: sha256=<hmac of "timestamp.body">`
+    #[default]
+    Generic,
+}
+
+impl std::fmt::Display for WebhookScheme {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::GitHub => write!(f, "github"),
+            Self::Stripe => write!(f, "stripe"),
+            Self::Generic => write!(f, "generic"),
+        }
+    }
+}
+
 impl WatcherKind {
+    /// Build a kind from a short name ("email", "feed", "time", ...) and its
+    /// config object, as the `create_watcher` tool takes them
+    pub fn from_config(kind: &str, config: serde_json::Value) -> anyhow::Result<Self> {
+        let type_tag = match kind {
+            "email" => "EmailWatch",
+            "calendar" => "CalendarWatch",
+            "github" => "GitHubWatch",
+            "feed" | "rss" => "FeedWatch",
+            "page" => "PageWatch",
+            "webhook" => "Webhook",
+            "file" => "FileWatch",
+            "message" => "MessageWatch",
+            "scheduled" | "time" => "Scheduled",
+            "oneshot" => "OneShot",
+            other => anyhow::bail!("Unknown watcher kind: {}", other),
+        };
+        let serde_json::Value::Object(mut map) = config else {
+            anyhow::bail!("Watcher config must be a JSON object");
+        };
+        map.insert(
+            "type".to_string(),
+            serde_json::Value::String(type_tag.to_string()),
+        );
+        serde_json::from_value(serde_json::Value::Object(map))
+            .map_err(|e| anyhow::anyhow!("Invalid config for {} watcher: {}", kind, e))
+    }
+
     /// Get the minimum safe polling interval for this watcher type
     pub fn min_interval_secs(&self) -> u64 {
         match self {
             Self::EmailWatch { .. } => 60,     // Email: minimum 1 minute
             Self::CalendarWatch { .. } => 300, // Calendar: minimum 5 minutes
             Self::GitHubWatch { .. } => 30,    // GitHub: minimum 30 seconds (API rate limits)
+            Self::FeedWatch { .. } => 300,     // Feed: minimum 5 minutes (be polite to publishers)
+            Self::PageWatch { .. } => 300,     // Page: minimum 5 minutes (be polite to site owners)
+            Self::Webhook { .. } => 0,         // Webhook: pushed by the sender
             Self::FileWatch { .. } => 0,       // File: event-driven, no polling
             Self::MessageWatch { .. } => 0,    // Message: event-driven
             Self::Scheduled { .. } => 0,       // Scheduled: based on cron
@@ -185,13 +391,20 @@ impl WatcherKind {
     pub fn is_polling(&self) -> bool {
         matches!(
             self,
-            Self::EmailWatch { .. } | Self::CalendarWatch { .. } | Self::GitHubWatch { .. }
+            Self::EmailWatch { .. }
+                | Self::CalendarWatch { .. }
+                | Self::GitHubWatch { .. }
+                | Self::FeedWatch { .. }
+                | Self::PageWatch { .. }
         )
     }
 
     /// Check if this is an event-driven watcher
     pub fn is_event_driven(&self) -> bool {
-        matches!(self, Self::FileWatch { .. } | Self::MessageWatch { .. })
+        matches!(
+            self,
+            Self::FileWatch { .. } | Self::MessageWatch { .. } | Self::Webhook { .. }
+        )
     }
 
     /// Check if this is a scheduled task
@@ -269,6 +482,61 @@ impl WatcherEvent {
         Self::new(watcher_id, format!("github_{}", event_type), data)
     }
 
+    /// Create a new feed item event
+    pub fn feed_item(
+        watcher_id: String,
+        feed_url: String,
+        feed_title: Option<String>,
+        item: &FeedItem,
+    ) -> Self {
+        Self::new(
+            watcher_id,
+            "feed_item".to_string(),
+            serde_json::json!({
+                "feed_url": feed_url,
+                "feed_title": feed_title,
+                "guid": item.guid,
+                "title": item.title,
+                "link": item.link,
+                "summary": item.summary,
+                "published": item.published,
+            }),
+        )
+    }
+
+    /// Create a page change event
+    pub fn page_changed(watcher_id: String, url: String, diff: &PageDiff) -> Self {
+        Self::new(
+            watcher_id,
+            "page_changed".to_string(),
+            serde_json::json!({
+                "url": url,
+                "diff": diff.text,
+                "added_lines": diff.added,
+                "removed_lines": diff.removed,
+                "change_ratio": diff.change_ratio,
+            }),
+        )
+    }
+
+    /// Create a webhook delivery event
+    pub fn webhook(
+        watcher_id: String,
+        path: String,
+        event_type: Option<String>,
+        body: serde_json::Value,
+    ) -> Self {
+        Self::new(
+            watcher_id,
+            "webhook_received".to_string(),
+            serde_json::json!({
+                "path": path,
+                "event": event_type,
+                "body": body,
+            }),
+        )
+    }
+
     /// Create a task execution event
     pub fn task(watcher_id: String, task_name: String) -> Self {
         Self::new(
@@ -279,6 +547,68 @@ impl WatcherEvent {
             }),
         )
     }
+
+    /// Create an event for a task run that was missed and is being caught up
+    pub fn missed_task(
+        watcher_id: String,
+        task_name: String,
+        scheduled_for: DateTime<Utc>,
+    ) -> Self {
+        Self::new(
+            watcher_id,
+            "task_triggered".to_string(),
+            serde_json::json!({
+                "task": task_name,
+                "scheduled_for": scheduled_for.to_rfc3339(),
+                "catch_up": true,
+            }),
+        )
+    }
+
+    /// Create an event reporting that a watcher was disabled after failing
+    /// `failures` times in a row
+    pub fn disabled(watcher_id: String, description: String, error: String, failures: u32) -> Self {
+        Self::new(
+            watcher_id,
+            "watcher_disabled".to_string(),
+            serde_json::json!({
+                "description": description,
+                "error": error,
+                "consecutive_errors": failures,
+            }),
+        )
+    }
+
+    /// Create an event summarizing a burst of `total` events, of which
+    /// `events` are the ones kept
+    pub fn batch(watcher_id: String, events: &[WatcherEvent], total: usize) -> Self {
+        let mut kinds = serde_json::Map::new();
+        for event in events {
+            let count = kinds.entry(event.kind.clone()).or_insert(0.into());
+            *count = (count.as_u64().unwrap_or_default() + 1).into();
+        }
+        let items: Vec<serde_json::Value> = events
+            .iter()
+            .map(|event| {
+                serde_json::json!({
+                    "kind": event.kind,
+                    "payload": event.payload,
+                    "timestamp": event.timestamp,
+                })
+            })
+            .collect();
+        let mut payload = serde_json::json!({
+            "count": total,
+            "kinds": kinds,
+            "first_at": events.first().map(|event| event.timestamp),
+            "last_at": events.last().map(|event| event.timestamp),
+            "events": items,
+        });
+        if total > events.len() {
+            payload["omitted"] = (total - events.len()).into();
+        }
+        Self::new(watcher_id, "event_batch".to_string(), payload)
+    }
 }
 
 #[cfg(test)]
@@ -337,6 +667,8 @@ mod tests {
         let scheduled = WatcherKind::Scheduled {
             cron_expr: "0 9 * * *".to_string(),
             task: "Daily backup".to_string(),
+            timezone: None,
+            misfire: MisfirePolicy::default(),
         };
         assert!(scheduled.is_scheduled());
     }
@@ -422,6 +754,74 @@ mod tests {
         assert!(desc.contains("user/repo"));
     }
 
+    #[test]
+    fn test_watcher_description_feed() {
+        let kind: WatcherKind = serde_json::from_value(serde_json::json!({
+            "type": "FeedWatch",
+            "url": "https://blog.rust-lang.org/feed.xml",
+            "keywords": ["release"]
+        }))
+        .unwrap();
+        assert!(kind.is_polling());
+        assert_eq!(kind.min_interval_secs(), 300);
+
+        let watcher = Watcher::new(kind, "summarize".to_string(), "ch".to_string());
+        let desc = watcher.description();
+        assert!(desc.contains("Feed watcher for https://blog.rust-lang.org/feed.xml"));
+        assert!(desc.contains("every 1800s"));
+        assert!(desc.contains("keywords: release"));
+    }
+
+    #[test]
+    fn test_watcher_description_page() {
+        let kind: WatcherKind = serde_json::from_value(serde_json::json!({
+            "type": "PageWatch",
+            "url": "https://status.example.com",
+            "selector": "#incidents"
+        }))
+        .unwrap();
+        assert!(kind.is_polling());
+        assert_eq!(kind.min_interval_secs(), 300);
+
+        let watcher = Watcher::new(kind, "tell me".to_string(), "ch".to_string());
+        assert_eq!(
+            watcher.description(),
+            "Page watcher for https://status.example.com [#incidents] (every 3600s)"
+        );
+    }
+
+    #[test]
+    fn test_webhook_watcher() {
+        let kind: WatcherKind = serde_json::from_value(serde_json::json!({
+            "type": "Webhook",
+            "secret": "s3cret",
+            "scheme": "github"
+        }))
+        .unwrap();
+        assert!(kind.is_event_driven());
+        assert!(!kind.is_polling());
+
+        let watcher = Watcher::new(kind, "triage".to_string(), "ch".to_string());
+        assert_eq!(watcher.webhook_path(), Some(watcher.id.as_str()));
+        assert!(watcher.description().ends_with("(github signatures)"));
+
+        let named = Watcher::new(
+            WatcherKind::Webhook {
+                path: "/ci/builds/".to_string(),
+                secret: "s3cret".to_string(),
+                scheme: WebhookScheme::Generic,
+                max_body_bytes: 1024,
+            },
+            "triage".to_string(),
+            "ch".to_string(),
+        );
+        assert_eq!(named.webhook_path(), Some("ci/builds"));
+        assert_eq!(
+            named.description(),
+            "Webhook watcher on /hooks/ci/builds (generic signatures)"
+        );
+    }
+
     #[test]
     fn test_watcher_description_file() {
         let watcher = Watcher::new(
@@ -452,6 +852,8 @@ mod tests {
             WatcherKind::Scheduled {
                 cron_expr: "0 9 * * MON".to_string(),
                 task: "Weekly report".to_string(),
+                timezone: Some("America/Chicago".to_string()),
+                misfire: MisfirePolicy::default(),
             },
             "run".to_string(),
             "ch".to_string(),
@@ -459,6 +861,7 @@ mod tests {
         let desc = watcher.description();
         assert!(desc.contains("Weekly report"));
         assert!(desc.contains("0 9 * * MON"));
+        assert!(desc.contains("America/Chicago"));
     }
 
     #[test]
@@ -468,6 +871,7 @@ mod tests {
             WatcherKind::OneShot {
                 at,
                 task: "Send reminder".to_string(),
+                misfire: MisfirePolicy::default(),
             },
             "run".to_string(),
             "ch".to_string(),
@@ -517,6 +921,7 @@ mod tests {
         let oneshot = WatcherKind::OneShot {
             at: Utc::now(),
             task: "test".to_string(),
+            misfire: MisfirePolicy::default(),
         };
         assert!(oneshot.is_scheduled());
         assert!(!oneshot.is_polling());
@@ -555,6 +960,26 @@ mod tests {
         assert_eq!(event.payload["ref"], "main");
     }
 
+    #[test]
+    fn test_watcher_event_feed_item() {
+        let item = FeedItem {
+            guid: "rust-1.90".to_string(),
+            title: "Announcing Rust 1.90".to_string(),
+            link: Some("https://blog.rust-lang.org/".to_string()),
+            summary: None,
+            published: None,
+        };
+        let event = WatcherEvent::feed_item(
+            "w6".to_string(),
+            "https://blog.rust-lang.org/feed.xml".to_string(),
+            Some("Rust Blog".to_string()),
+            &item,
+        );
+        assert_eq!(event.kind, "feed_item");
+        assert_eq!(event.payload["title"], "Announcing Rust 1.90");
+        assert_eq!(event.payload["feed_title"], "Rust Blog");
+    }
+
     #[test]
     fn test_watcher_event_task() {
         let event = WatcherEvent::task("w4".to_string(), "backup".to_string());
@@ -604,4 +1029,25 @@ mod tests {
         assert_eq!(parsed.watcher_id, "w1");
         assert_eq!(parsed.kind, "email_received");
     }
+
+    #[test]
+    fn test_watcher_kind_from_config() {
+        let kind = WatcherKind::from_config(
+            "rss",
+            serde_json::json!({"url": "https://example.com/feed.xml"}),
+        )
+        .unwrap();
+        assert!(matches!(kind, WatcherKind::FeedWatch { interval_secs, .. } if interval_secs > 0));
+
+        let kind = WatcherKind::from_config(
+            "time",
+            serde_json::json!({"cron_expr": "0 9 * * *", "task": "Briefing"}),
+        )
+        .unwrap();
+        assert!(matches!(kind, WatcherKind::Scheduled { .. }));
+
+        assert!(WatcherKind::from_config("pager", serde_json::json!({})).is_err());
+        assert!(WatcherKind::from_config("file", serde_json::json!("/tmp")).is_err());
+        assert!(WatcherKind::from_config("file", serde_json::json!({})).is_err());
+    }
 }
@@ -0,0 +1,51 @@
+//! Inbound webhook routing
+//!
+//! `Webhook` watchers don't run a task of their own: the HTTP server that
+//! receives the requests (the gateway) implements [`WebhookRoutes`] and the
+//! runner registers and removes routes as watchers start and stop. Accepted
+//! deliveries are kept in the scheduler database by [`WebhookDeliveries`]
+//! so replay protection survives restarts.
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use rusqlite::Connection;
+
+use crate::watcher::Watcher;
+
+/// Registers webhook watchers with the HTTP server that receives their requests
+pub trait WebhookRoutes: Send + Sync {
+    /// Start accepting requests for a `Webhook` watcher
+    fn register(&self, watcher: &Watcher) -> Result<()>;
+
+    /// Stop accepting requests for a watcher (no-op if it has no route)
+    fn unregister(&self, watcher_id: &str);
+}
+
+/// Deliveries accepted by webhook watchers, keyed by a digest of their signed
+/// content, for replay protection
+#[derive(Clone)]
+pub struct WebhookDeliveries {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl WebhookDeliveries {
+    pub fn new(conn: Arc<Mutex<Connection>>) -> Self {
+        Self { conn }
+    }
+
+    /// Record a delivery; `false` if the watcher already accepted it within `ttl`
+    pub async fn record(&self, watcher_id: &str, delivery_id: &str, ttl: Duration) -> Result<bool> {
+        let conn = Arc::clone(&self.conn);
+        let watcher_id = watcher_id.to_string();
+        let delivery_id = delivery_id.to_string();
+        let ttl = chrono::Duration::from_std(ttl)?;
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+            crate::persistence::record_webhook_delivery(&conn, &watcher_id, &delivery_id, ttl)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+}
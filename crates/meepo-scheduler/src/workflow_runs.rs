@@ -0,0 +1,197 @@
+//! Run history for watcher-triggered workflows
+//!
+//! Workflows themselves are defined and executed by `meepo-core`; this module
+//! keeps what each run did — the triggering event, every step's rendered
+//! input, output or error — in `workflow_runs`, next to the watcher tables.
+
+use std::sync::{Arc, Mutex};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::watcher::WatcherEvent;
+
+/// Outcome of a whole workflow run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunStatus {
+    /// Every step ran, was skipped, or failed with `continue_on_error`
+    Succeeded,
+    /// A step failed and stopped the run
+    Failed,
+}
+
+impl std::fmt::Display for RunStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Succeeded => write!(f, "succeeded"),
+            Self::Failed => write!(f, "failed"),
+        }
+    }
+}
+
+impl std::str::FromStr for RunStatus {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "succeeded" => Ok(Self::Succeeded),
+            "failed" => Ok(Self::Failed),
+            other => anyhow::bail!("Unknown workflow run status: {}", other),
+        }
+    }
+}
+
+/// Outcome of one step
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StepStatus {
+    Succeeded,
+    Failed,
+    /// Its `when` condition was false
+    Skipped,
+    /// Dry run: would have run
+    Planned,
+}
+
+impl std::fmt::Display for StepStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Succeeded => write!(f, "succeeded"),
+            Self::Failed => write!(f, "failed"),
+            Self::Skipped => write!(f, "skipped"),
+            Self::Planned => write!(f, "planned"),
+        }
+    }
+}
+
+/// What one step of a run did
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StepRun {
+    pub id: String,
+    /// Tool name, or `llm` for a prompt step
+    pub action: String,
+    pub status: StepStatus,
+    /// Input after template rendering
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// One recorded workflow run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkflowRun {
+    /// Row id, 0 until recorded
+    pub id: i64,
+    pub workflow: String,
+    pub status: RunStatus,
+    /// The event that triggered the run
+    pub event: Option<WatcherEvent>,
+    pub steps: Vec<StepRun>,
+    pub error: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+}
+
+/// Records and lists workflow runs
+#[derive(Clone)]
+pub struct WorkflowRunStore {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl WorkflowRunStore {
+    pub fn new(conn: Arc<Mutex<Connection>>) -> Self {
+        Self { conn }
+    }
+
+    /// Keep a finished run, returning its id
+    pub async fn record(&self, run: &WorkflowRun) -> Result<i64> {
+        let run = run.clone();
+        self.with_conn(move |conn| crate::persistence::record_workflow_run(conn, &run))
+            .await
+    }
+
+    /// Most recent runs, newest first, optionally for one workflow
+    pub async fn recent(&self, workflow: Option<&str>, limit: usize) -> Result<Vec<WorkflowRun>> {
+        let workflow = workflow.map(str::to_string);
+        self.with_conn(move |conn| {
+            crate::persistence::get_workflow_runs(conn, workflow.as_deref(), limit)
+        })
+        .await
+    }
+
+    async fn with_conn<T: Send + 'static>(
+        &self,
+        f: impl FnOnce(&Connection) -> Result<T> + Send + 'static,
+    ) -> Result<T> {
+        let conn = Arc::clone(&self.conn);
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+            f(&conn)
+        })
+        .await
+        .context("spawn_blocking task panicked")?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_record_and_list_runs() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::persistence::init_watcher_tables(&conn).unwrap();
+        let store = WorkflowRunStore::new(Arc::new(Mutex::new(conn)));
+
+        let event = WatcherEvent {
+            watcher_id: "w-1".to_string(),
+            kind: "email_received".to_string(),
+            payload: json!({"subject": "Receipt"}),
+            timestamp: Utc::now(),
+        };
+        for (workflow, status) in [
+            ("receipts", RunStatus::Succeeded),
+            ("digest", RunStatus::Succeeded),
+            ("receipts", RunStatus::Failed),
+        ] {
+            let run = WorkflowRun {
+                id: 0,
+                workflow: workflow.to_string(),
+                status,
+                event: Some(event.clone()),
+                steps: vec![StepRun {
+                    id: "parse".to_string(),
+                    action: "parse_receipt".to_string(),
+                    status: StepStatus::Succeeded,
+                    input: Some(json!({"text": "Receipt"})),
+                    output: Some(json!({"total": 12.5})),
+                    error: None,
+                }],
+                error: (status == RunStatus::Failed).then(|| "boom".to_string()),
+                started_at: Utc::now(),
+                finished_at: Utc::now(),
+            };
+            assert!(store.record(&run).await.unwrap() > 0);
+        }
+
+        let all = store.recent(None, 10).await.unwrap();
+        assert_eq!(all.len(), 3);
+
+        let receipts = store.recent(Some("receipts"), 10).await.unwrap();
+        assert_eq!(receipts.len(), 2);
+        assert_eq!(receipts[0].status, RunStatus::Failed);
+        assert_eq!(receipts[0].error.as_deref(), Some("boom"));
+        assert_eq!(receipts[1].steps[0].output, Some(json!({"total": 12.5})));
+        assert_eq!(receipts[1].event.as_ref().unwrap().kind, "email_received");
+
+        assert_eq!(store.recent(Some("receipts"), 1).await.unwrap().len(), 1);
+    }
+}